@@ -0,0 +1,141 @@
+use super::files::{is_standard_stream, read_string, DataFormat, AVRO_EXTENSION};
+use super::{timings, Error};
+use json_schema_tools::compose::{prepare, Prepared};
+use json_schema_tools::import::from_avro;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Referenced schemas that have been read and prepared for composition, keyed by a hash of their
+/// contents (and of how they're read), so that recomposing only prepares the files that changed
+///
+/// Entries are kept in memory (for `--watch`), and in a directory if one is given (for repeated
+/// runs). Nothing is ever removed from the directory, which can be deleted at any time.
+#[derive(Debug, Default)]
+pub struct ComposeCache {
+    dir: Option<PathBuf>,
+    entries: HashMap<String, Prepared>,
+}
+
+impl ComposeCache {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Read and prepare a referenced schema in the given format (which can be an Avro schema),
+    /// unless the same contents have already been prepared
+    pub fn prepare(&mut self, path: &Path, format: DataFormat) -> Result<Prepared, Error> {
+        let contents = read_string(path)?;
+        let avro_name = path
+            .extension()
+            .filter(|extension| *extension == AVRO_EXTENSION)
+            .and_then(|_| path.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string());
+
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update(format!("{:?} {:?}\0", format, avro_name));
+        hasher.update(&contents);
+        let key = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+
+        if let Some(prepared) = self.entries.get(&key) {
+            return Ok(prepared.clone());
+        }
+
+        let stored = self.stored_path(&key);
+
+        if let Some(prepared) = stored
+            .as_ref()
+            .and_then(|stored| std::fs::read(stored).ok())
+            .and_then(|bytes| serde_json::from_slice::<Prepared>(&bytes).ok())
+        {
+            log::info!("Reusing {} for {}", key, path.display());
+            self.entries.insert(key, prepared.clone());
+
+            return Ok(prepared);
+        }
+
+        let value = timings::phase("parse", || format.parse_file(path, &contents))?;
+        let value = match avro_name {
+            Some(name) => from_avro(&value, &name)?,
+            None => value,
+        };
+        let prepared = timings::phase("prepare", || prepare(None, &value))?;
+
+        // Standard input can't be read again, so there's no point in storing it.
+        if let Some(stored) = stored.filter(|_| !is_standard_stream(path)) {
+            if let Some(dir) = stored.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+
+            std::fs::write(&stored, serde_json::to_vec(&prepared)?)?;
+        }
+
+        self.entries.insert(key, prepared.clone());
+
+        Ok(prepared)
+    }
+
+    fn stored_path(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored_count(dir: &Path) -> usize {
+        std::fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn keys_change_with_contents_and_format() {
+        let dir =
+            std::env::temp_dir().join(format!("json-schema-tools-cache-{}", std::process::id()));
+        let cache_dir = dir.join("cache");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.json");
+
+        std::fs::write(
+            &path,
+            r#"{"$id": "/a", "$defs": {"A": {"type": "string"}}}"#,
+        )
+        .unwrap();
+        let mut cache = ComposeCache::new(Some(cache_dir.clone()));
+        let first = cache.prepare(&path, DataFormat::Json).unwrap();
+        assert_eq!(stored_count(&cache_dir), 1);
+
+        // The same contents are reused, whether from memory or from the directory.
+        assert_eq!(cache.prepare(&path, DataFormat::Json).unwrap(), first);
+        let mut reloaded = ComposeCache::new(Some(cache_dir.clone()));
+        assert_eq!(reloaded.prepare(&path, DataFormat::Json).unwrap(), first);
+        assert_eq!(stored_count(&cache_dir), 1);
+
+        // Reading the same contents another way is a different entry.
+        assert_eq!(cache.prepare(&path, DataFormat::Jsonc).unwrap(), first);
+        assert_eq!(stored_count(&cache_dir), 2);
+
+        std::fs::write(
+            &path,
+            r#"{"$id": "/a", "$defs": {"A": {"type": "integer"}}}"#,
+        )
+        .unwrap();
+        let changed = cache.prepare(&path, DataFormat::Json).unwrap();
+        let stored = stored_count(&cache_dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_ne!(changed, first);
+        assert_eq!(changed.defs[0].1, serde_json::json!({"type": "integer"}));
+        assert_eq!(stored, 3);
+    }
+}
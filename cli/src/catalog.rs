@@ -0,0 +1,122 @@
+use serde_json::{json, Value};
+
+/// The schema for SchemaStore-style catalogs
+const CATALOG_SCHEMA_URI: &str = "https://json.schemastore.org/schema-catalog.json";
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum CatalogStyle {
+    /// A `json.schemas` block for VS Code settings
+    Vscode,
+    /// A SchemaStore catalog
+    Schemastore,
+}
+
+/// A schema and the data files it describes
+#[derive(Debug)]
+pub struct Entry {
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub file_match: Vec<String>,
+}
+
+impl Entry {
+    /// Describe a schema using its title and description, with the given name if it has no title
+    pub fn new(schema: &Value, name: &str, url: String, file_match: Vec<String>) -> Self {
+        Self {
+            name: schema
+                .get("title")
+                .and_then(Value::as_str)
+                .unwrap_or(name)
+                .to_string(),
+            description: schema
+                .get("description")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            url,
+            file_match,
+        }
+    }
+}
+
+/// Build the catalog, where VS Code settings only include schemas that match files
+pub fn catalog(entries: &[Entry], style: CatalogStyle) -> Value {
+    match style {
+        CatalogStyle::Vscode => json!({
+            "json.schemas": entries
+                .iter()
+                .filter(|entry| !entry.file_match.is_empty())
+                .map(|entry| json!({ "fileMatch": entry.file_match, "url": entry.url }))
+                .collect::<Vec<_>>()
+        }),
+        CatalogStyle::Schemastore => json!({
+            "$schema": CATALOG_SCHEMA_URI,
+            "version": 1,
+            "schemas": entries
+                .iter()
+                .map(|entry| {
+                    let mut value = json!({ "name": entry.name });
+
+                    if let Some(description) = &entry.description {
+                        value["description"] = json!(description);
+                    }
+
+                    if !entry.file_match.is_empty() {
+                        value["fileMatch"] = json!(entry.file_match);
+                    }
+
+                    value["url"] = json!(entry.url);
+                    value
+                })
+                .collect::<Vec<_>>()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_styles() {
+        let entries = vec![
+            Entry::new(
+                &json!({ "title": "Order", "description": "An order" }),
+                "order",
+                "https://example.com/order.json".to_string(),
+                vec!["*.order.json".to_string()],
+            ),
+            Entry::new(
+                &json!({}),
+                "common",
+                "https://example.com/common.json".to_string(),
+                vec![],
+            ),
+        ];
+
+        assert_eq!(
+            catalog(&entries, CatalogStyle::Vscode),
+            json!({
+                "json.schemas": [
+                    { "fileMatch": ["*.order.json"], "url": "https://example.com/order.json" }
+                ]
+            })
+        );
+        assert_eq!(
+            catalog(&entries, CatalogStyle::Schemastore),
+            json!({
+                "$schema": CATALOG_SCHEMA_URI,
+                "version": 1,
+                "schemas": [
+                    {
+                        "name": "Order",
+                        "description": "An order",
+                        "fileMatch": ["*.order.json"],
+                        "url": "https://example.com/order.json"
+                    },
+                    { "name": "common", "url": "https://example.com/common.json" }
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use super::Context;
+use crate::files::{is_standard_stream, read_string};
+use crate::{tree, Error};
+use json_schema_tools::schema::{
+    counterexamples, diff, violations, Compatibility, CompatibilityMode, DifferentialOptions,
+    SchemaFile,
+};
+use json_schema_tools::version::{bump, changelog_entry, insert_changelog_entry, Level};
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Bump {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Previous version of the schema
+    #[clap(long)]
+    previous: Option<PathBuf>,
+    /// Version component to increment
+    #[clap(long, value_enum, required_unless_present = "previous")]
+    level: Option<BumpLevel>,
+    /// Changelog to add an entry to
+    #[clap(long, default_value = "CHANGELOG.md")]
+    changelog: PathBuf,
+}
+
+impl Bump {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            previous,
+            level,
+            changelog,
+        } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut value = inputs.read_json(&schema)?;
+        let changes = match previous {
+            Some(previous) => diff(
+                &serde_json::from_value(inputs.read_json(previous)?)?,
+                &serde_json::from_value(value.clone())?,
+            ),
+            None => vec![],
+        };
+        // Clap requires a level when there's no previous version.
+        let level = level.map_or_else(|| Level::required(&changes), Level::from);
+        let (old_version, new_version) = bump(&mut value, level)?;
+
+        if is_standard_stream(&schema) {
+            write!(out, "{}", inputs.format_of(&schema).serialize(&value))?;
+        } else {
+            std::fs::write(&schema, inputs.format_of(&schema).serialize(&value))?;
+            writeln!(out, "Bumped {} to {}", old_version, new_version)?;
+            out.report.artifact(&schema);
+        }
+
+        if !changes.is_empty() {
+            let existing = if changelog.exists() {
+                read_string(&changelog)?
+            } else {
+                String::new()
+            };
+
+            std::fs::write(
+                &changelog,
+                insert_changelog_entry(&existing, &changelog_entry(&new_version, &changes)),
+            )?;
+            log::info!("Updated {}", changelog.display());
+            out.report.artifact(&changelog);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Diff {
+    /// Old schema path
+    old: PathBuf,
+    /// New schema path, if not the configured composition
+    new: Option<PathBuf>,
+    /// Configured composition target to compare with, if no new schema is given
+    #[clap(short, long, conflicts_with = "new")]
+    target: Option<String>,
+}
+
+impl Diff {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { old, new, target } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let new = match new {
+            Some(new) => inputs.read_json(new)?,
+            None => match config.compose_targets(target.as_deref())?.as_slice() {
+                [target] => inputs.read_composed(&target.schema, &target.referenced)?,
+                _ => return Err(Error::AmbiguousComposeTarget),
+            },
+        };
+        let old: SchemaFile = serde_json::from_value(inputs.read_json(old)?)?;
+        let new: SchemaFile = serde_json::from_value(new)?;
+        let changes = diff(&old, &new);
+
+        if out.is_structured() {
+            for change in &changes {
+                out.report.finding(serde_json::to_value(change)?);
+            }
+        } else {
+            write!(out, "{}", tree::render_changes(&changes))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Compat {
+    /// Old schema path
+    old: PathBuf,
+    /// New schema path
+    new: PathBuf,
+    /// Required compatibility
+    #[clap(long, value_enum, default_value_t = Mode::Backward)]
+    mode: Mode,
+}
+
+impl Compat {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { old, new, mode } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let old: SchemaFile = serde_json::from_value(inputs.read_json(old)?)?;
+        let new: SchemaFile = serde_json::from_value(inputs.read_json(new)?)?;
+        let changes = diff(&old, &new);
+        let mode = mode.into();
+        let violations = violations(&changes, mode);
+
+        for change in &changes {
+            let compatibility = change.kind.compatibility();
+            let mut value = serde_json::to_value(change)?;
+
+            if let Some(fields) = value.as_object_mut() {
+                fields.insert(
+                    "compatibility".to_string(),
+                    serde_json::to_value(compatibility)?,
+                );
+                fields.insert(
+                    "violation".to_string(),
+                    Value::Bool(!compatibility.satisfies(mode)),
+                );
+            }
+
+            out.finding(
+                value,
+                format!(
+                    "[{}] /{}: {}",
+                    compatibility,
+                    change.new_path.join("/"),
+                    change.kind
+                ),
+            )?;
+        }
+
+        out.summary(format!(
+            "{} of {} changes violate {} compatibility",
+            violations.len(),
+            changes.len(),
+            format!("{:?}", mode).to_lowercase()
+        ))?;
+
+        if !violations.is_empty() {
+            out.report.issues();
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Differential {
+    /// Old schema path
+    old: PathBuf,
+    /// New schema path
+    new: PathBuf,
+    /// Required compatibility
+    #[clap(long, value_enum, default_value_t = Mode::Backward)]
+    mode: Mode,
+    /// Seed for the random instances
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+    /// Number of random instances of each version (in addition to examples and boundaries)
+    #[clap(long, default_value_t = 100)]
+    count: usize,
+}
+
+impl Differential {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            old,
+            new,
+            mode,
+            seed,
+            count,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let old = config.validator(inputs.read_json(old)?)?;
+        let new = config.validator(inputs.read_json(new)?)?;
+        let mode = mode.into();
+        let found = counterexamples(&old, &new, DifferentialOptions { seed, count })?;
+        let mut violations = 0;
+
+        for counterexample in &found {
+            let violation = !counterexample.compatibility.satisfies(mode);
+            // An instance of the old version can only show that the change isn't backward
+            // compatible, and vice versa.
+            let (breaks, accepted, rejected) =
+                if counterexample.compatibility == Compatibility::Forward {
+                    ("backward", "old", "new")
+                } else {
+                    ("forward", "new", "old")
+                };
+
+            if violation {
+                violations += 1;
+            }
+
+            let mut text = format!(
+                "[{}] {} is valid for the {} schema but not the {} schema",
+                breaks, counterexample.instance, accepted, rejected
+            );
+
+            for error in &counterexample.errors {
+                text.push_str(&format!("\n  {}", error));
+            }
+
+            out.finding(
+                serde_json::json!({
+                    "breaks": breaks,
+                    "violation": violation,
+                    "instance": counterexample.instance,
+                    "errors": counterexample
+                        .errors
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>(),
+                }),
+                text,
+            )?;
+        }
+
+        out.summary(format!(
+            "{} of {} counterexamples violate {} compatibility",
+            violations,
+            found.len(),
+            format!("{:?}", mode).to_lowercase()
+        ))?;
+
+        if violations > 0 {
+            out.report.issues();
+        }
+
+        Ok(())
+    }
+}
+
+impl From<BumpLevel> for Level {
+    fn from(value: BumpLevel) -> Self {
+        match value {
+            BumpLevel::Major => Self::Major,
+            BumpLevel::Minor => Self::Minor,
+            BumpLevel::Patch => Self::Patch,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl From<Mode> for CompatibilityMode {
+    fn from(value: Mode) -> Self {
+        match value {
+            Mode::Backward => Self::Backward,
+            Mode::Forward => Self::Forward,
+            Mode::Full => Self::Full,
+            Mode::None => Self::None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum Mode {
+    /// Data written with the old schema must be valid under the new schema
+    Backward,
+    /// Data written with the new schema must be valid under the old schema
+    Forward,
+    /// Both backward and forward
+    Full,
+    /// Report changes without enforcing compatibility
+    None,
+}
@@ -0,0 +1,282 @@
+use super::{Context, Output};
+use crate::cache::ComposeCache;
+use crate::config::ComposeTarget;
+use crate::config::Config;
+use crate::files::{output, schema_files, DataFormat, Inputs};
+use crate::report::Report;
+use crate::{timings, watch, Error};
+use json_schema_tools::graph::{RefGraph, Target};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, clap::Args)]
+pub struct Compose {
+    /// Main schema path (or `-` for stdin), if not configured
+    #[clap(short, long)]
+    schema: Option<PathBuf>,
+    /// Referenced schema paths
+    #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
+    referenced: Vec<PathBuf>,
+    /// Configured composition target to build
+    #[clap(short, long, conflicts_with = "schema")]
+    target: Option<String>,
+    /// Print the composed schema with indentation and canonical key order (the default)
+    #[clap(long, conflicts_with = "compact")]
+    pretty: bool,
+    /// Print the composed schema on a single line
+    #[clap(long, conflicts_with = "indent")]
+    compact: bool,
+    /// Number of spaces to indent pretty output with [default: 2]
+    #[clap(long)]
+    indent: Option<usize>,
+    /// Format to print the composed schema in, instead of JSON (or the format of a configured
+    /// output file)
+    #[clap(long, value_enum, conflicts_with = "compact")]
+    output_format: Option<DataFormat>,
+    /// Compose again whenever the main or referenced schemas change (or any schema under the
+    /// configured roots that they refer to)
+    #[clap(long)]
+    watch: bool,
+    /// Directory to keep prepared referenced schemas in, so that later runs only prepare the
+    /// ones that have changed (they're always reused in memory while watching)
+    #[clap(long)]
+    cache: Option<PathBuf>,
+}
+
+impl Compose {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            referenced,
+            target,
+            pretty,
+            compact,
+            indent,
+            output_format,
+            watch,
+            cache,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            destination,
+            mut out,
+        } = ctx;
+        let mut cache = ComposeCache::new(cache.or_else(|| config.compose.cache.clone()));
+        let targets = match schema {
+            Some(schema) => vec![ComposeTarget {
+                schema,
+                referenced,
+                output: None,
+            }],
+            None => config.compose_targets(target.as_deref())?,
+        };
+        let indent = if !pretty && (compact || config.output.compact.unwrap_or(false)) {
+            None
+        } else {
+            Some(indent.or(config.output.indent).unwrap_or(2))
+        };
+
+        if watch {
+            let paths = targets
+                .iter()
+                .flat_map(|target| std::iter::once(&target.schema).chain(&target.referenced))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            watch::watch(&paths, || {
+                run_compose_targets(
+                    &mut Output {
+                        out: &mut output(destination.clone())?,
+                        report: &mut Report::default(),
+                    },
+                    inputs,
+                    &targets,
+                    indent,
+                    output_format,
+                    &mut cache,
+                )?;
+
+                referenced_files(config, inputs, &paths)
+            })?;
+        } else {
+            run_compose_targets(
+                &mut out,
+                inputs,
+                &targets,
+                indent,
+                output_format,
+                &mut cache,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Print a composed schema, on a single line if no indentation is given (and the format is JSON)
+fn run_compose(
+    out: &mut dyn Write,
+    inputs: Inputs,
+    schema: &Path,
+    referenced: &[PathBuf],
+    indent: Option<usize>,
+    format: DataFormat,
+    cache: &mut ComposeCache,
+) -> Result<(), Error> {
+    let composed = inputs.read_composed_cached(schema, referenced, cache)?;
+
+    timings::phase("serialize", || match (indent, format) {
+        (None, DataFormat::Json | DataFormat::Jsonc) => writeln!(out, "{}", composed),
+        (indent, format) => write!(out, "{}", format.format(&composed, indent.unwrap_or(2))),
+    })?;
+
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Compose each target, writing it to its configured output file if it has one
+fn run_compose_targets(
+    out: &mut Output,
+    inputs: Inputs,
+    targets: &[ComposeTarget],
+    indent: Option<usize>,
+    format: Option<DataFormat>,
+    cache: &mut ComposeCache,
+) -> Result<(), Error> {
+    for target in targets {
+        match &target.output {
+            Some(path) => {
+                run_compose(
+                    &mut output(Some(path.clone()))?,
+                    inputs,
+                    &target.schema,
+                    &target.referenced,
+                    indent,
+                    format.unwrap_or_else(|| DataFormat::by_extension(path)),
+                    cache,
+                )?;
+                log::info!("Wrote {}", path.display());
+                out.report.artifact(path);
+            }
+            None => run_compose(
+                out,
+                inputs,
+                &target.schema,
+                &target.referenced,
+                indent,
+                format.unwrap_or(DataFormat::Json),
+                cache,
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Schema files under the configured roots that the given schemas refer to (directly or through
+/// other files), other than the given schemas themselves
+fn referenced_files(
+    config: &Config,
+    inputs: Inputs,
+    schemas: &[PathBuf],
+) -> Result<Vec<PathBuf>, Error> {
+    if config.roots().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut files = schemas.to_vec();
+
+    for path in schema_files(vec![], config)? {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+
+    let values = files
+        .iter()
+        .map(|path| Ok((path.display().to_string(), inputs.read_json(path)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+    let graph = RefGraph::new(
+        &values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+            .collect::<Vec<_>>(),
+    );
+    let mut reached = (0..files.len())
+        .map(|file| file < schemas.len())
+        .collect::<Vec<_>>();
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        for edge in &graph.edges {
+            if let Target::Node(to) = edge.to {
+                let (from, to) = (graph.nodes[edge.from].file, graph.nodes[to].file);
+
+                if reached[from] && !reached[to] {
+                    reached[to] = true;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    Ok(files
+        .into_iter()
+        .zip(reached)
+        .skip(schemas.len())
+        .filter_map(|(path, reached)| reached.then_some(path))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn referenced_files_under_roots() {
+        let dir =
+            std::env::temp_dir().join(format!("json-schema-tools-compose-{}", std::process::id()));
+        let root = dir.join("schemas");
+        std::fs::create_dir_all(&root).unwrap();
+
+        let files = [
+            (
+                dir.join("main.json"),
+                json!({"$defs": {}, "properties": {"a": {"$ref": "/a#/$defs/A"}}}),
+            ),
+            (
+                root.join("a.json"),
+                json!({"$id": "/a", "$defs": {"A": {"$ref": "/b#/$defs/B"}}}),
+            ),
+            (
+                root.join("b.json"),
+                json!({"$id": "/b", "$defs": {"B": {"type": "string"}}}),
+            ),
+            (
+                root.join("c.json"),
+                json!({"$id": "/c", "$defs": {"C": {"type": "string"}}}),
+            ),
+        ];
+
+        for (path, value) in &files {
+            std::fs::write(path, value.to_string()).unwrap();
+        }
+
+        let mut config = Config::default();
+        config.root = Some(root.clone());
+        let main = vec![dir.join("main.json")];
+        let mut referenced = referenced_files(&config, Inputs::default(), &main).unwrap();
+        referenced.sort();
+
+        let without_roots = referenced_files(&Config::default(), Inputs::default(), &main).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(referenced, vec![root.join("a.json"), root.join("b.json")]);
+        assert!(without_roots.is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+use super::Context;
+use crate::catalog::{catalog, CatalogStyle, Entry};
+use crate::files::{schema_files, DataFormat};
+use crate::Error;
+use json_schema_tools::docs::{
+    search_index, search_index_script, to_class_diagram, to_markdown_with_examples, ExampleFormat,
+    HtmlRenderer, NavPage, Page, PageLinks, SEARCH_INDEX_NAME,
+};
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Docs {
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+    /// Directory to write one page per schema to, instead of printing to stdout
+    #[clap(short, long)]
+    output_dir: Option<PathBuf>,
+    /// Markup language for the pages
+    #[clap(long, value_enum, default_value_t = DocsFormat::Markdown)]
+    markup: DocsFormat,
+    /// Template to use for HTML pages instead of the default
+    #[clap(long)]
+    template: Option<PathBuf>,
+    /// Format to write examples in
+    #[clap(long, value_enum)]
+    example_format: Option<DataFormat>,
+}
+
+impl Docs {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            paths,
+            output_dir,
+            markup,
+            template,
+            example_format,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let files = schema_files(paths, config)?;
+        let example_format = match example_format {
+            Some(DataFormat::Yaml) => ExampleFormat::Yaml,
+            Some(DataFormat::Json | DataFormat::Jsonc) | None => ExampleFormat::Json,
+        };
+
+        let extension = match markup {
+            DocsFormat::Markdown => "md",
+            DocsFormat::Html => "html",
+            DocsFormat::Mermaid => "mmd",
+        };
+        let documents = files
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                Ok((format!("{}.{}", name, extension), inputs.read_json(path)?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let pages = documents
+            .iter()
+            .map(|(name, value)| (name.clone(), Page::new(value)))
+            .collect::<Vec<_>>();
+        let links = pages
+            .iter()
+            .filter_map(|(name, page)| page.id.clone().map(|id| (id, name.clone())))
+            .collect::<PageLinks>();
+
+        let mut outputs = match markup {
+            DocsFormat::Markdown => pages
+                .iter()
+                .map(|(name, page)| {
+                    (
+                        name.clone(),
+                        to_markdown_with_examples(page, &links, example_format),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            DocsFormat::Html => {
+                let renderer = match template {
+                    Some(template) => {
+                        HtmlRenderer::with_template(std::fs::read_to_string(template)?)?
+                    }
+                    None => HtmlRenderer::default(),
+                }
+                .with_example_format(example_format);
+                let nav = pages
+                    .iter()
+                    .map(|(name, page)| NavPage {
+                        title: page.title.clone(),
+                        href: name.clone(),
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut outputs = pages
+                    .iter()
+                    .map(|(name, page)| {
+                        Ok((name.clone(), renderer.render(page, name, &links, &nav)?))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let index = search_index(
+                    &pages
+                        .iter()
+                        .map(|(name, page)| (name.as_str(), page))
+                        .collect::<Vec<_>>(),
+                );
+                outputs.push((SEARCH_INDEX_NAME.to_string(), search_index_script(&index)));
+                outputs
+            }
+            DocsFormat::Mermaid => documents
+                .iter()
+                .map(|(name, value)| (name.clone(), to_class_diagram(value)))
+                .collect(),
+        };
+
+        match &output_dir {
+            Some(output_dir) => {
+                std::fs::create_dir_all(output_dir)?;
+
+                for (name, output) in outputs {
+                    let path = output_dir.join(name);
+                    std::fs::write(&path, output)?;
+                    out.report.artifact(&path);
+                }
+            }
+            None => {
+                if matches!(markup, DocsFormat::Html) {
+                    // The search index can only be written alongside the pages.
+                    outputs.pop();
+                }
+
+                for (_, output) in outputs {
+                    write!(out, "{}", output)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Catalog {
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root or catalog
+    paths: Vec<PathBuf>,
+    #[clap(long, value_enum, default_value = "vscode")]
+    style: CatalogStyle,
+    /// Refer to schemas by path even if they have an `$id`
+    #[clap(long)]
+    local: bool,
+}
+
+impl Catalog {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            paths,
+            style,
+            local,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let paths = if paths.is_empty() && config.roots().is_empty() {
+            config.catalog.keys().cloned().collect()
+        } else {
+            schema_files(paths, config)?
+        };
+        let mut entries = vec![];
+
+        for path in paths {
+            let value = inputs.read_json(&path)?;
+            let relative = config.relative_path(&path);
+            let url = match value.get("$id").and_then(Value::as_str) {
+                Some(id) if !local => id.to_string(),
+                // VS Code resolves relative paths against the workspace folder.
+                _ => format!("./{}", relative),
+            };
+            let file_match = config.file_match(&path).to_vec();
+
+            if file_match.is_empty() {
+                log::warn!("No catalog globs for {}", relative);
+            }
+
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy())
+                .unwrap_or_default();
+
+            entries.push(Entry::new(&value, &name, url, file_match));
+        }
+
+        writeln!(out, "{:#}", catalog(&entries, style))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum DocsFormat {
+    Markdown,
+    Html,
+    /// A Mermaid class diagram of the object and enum definitions
+    Mermaid,
+}
@@ -0,0 +1,262 @@
+use super::{Context, Output};
+use crate::config::Config;
+use crate::Error;
+use json_schema_tools::compose::compose;
+use json_schema_tools::example::{
+    boundaries, example, fake, fuzz_dictionary, near_misses, seed_corpus, ExampleOptions,
+};
+use json_schema_tools::validate::{ValidationError, Validator};
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Example {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Other schemas, for resolving references to them
+    #[clap(long)]
+    with: Vec<PathBuf>,
+    /// Include every property and at least one item in each array
+    #[clap(long)]
+    full: bool,
+    /// Use the schema's own `examples` where it has them
+    #[clap(long)]
+    use_examples: bool,
+    /// Print instances that each violate a single constraint
+    #[clap(long, conflicts_with = "full")]
+    invalid: bool,
+    /// Generate random, realistic values from this seed (the same seed gives the same
+    /// instances), including each optional property half of the time
+    #[clap(long, conflicts_with = "invalid")]
+    seed: Option<u64>,
+    /// Number of random instances to print, one per line
+    #[clap(long, requires = "seed")]
+    count: Option<usize>,
+    /// Print valid instances at each boundary (numeric bounds, shortest and longest strings
+    /// and arrays, enum members, and absent optional properties)
+    #[clap(long, conflicts_with_all = ["invalid", "seed"])]
+    boundaries: bool,
+}
+
+impl Example {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            with,
+            full,
+            use_examples,
+            invalid,
+            seed,
+            count,
+            boundaries: at_boundaries,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let schemas = std::iter::once(&schema)
+            .chain(&with)
+            .map(|path| inputs.read_json(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = ExampleOptions { full, use_examples };
+        let validator = validator(config, &schemas[0], &schemas[1..])?;
+
+        if invalid {
+            let mut results = vec![];
+
+            for near_miss in near_misses(&schemas, 0, options)? {
+                let errors = validator.validate(&near_miss.instance);
+
+                // Keep only the instances that fail in exactly the intended way.
+                match errors.as_slice() {
+                    [error] if error.instance_pointer() == near_miss.pointer => {
+                        results.push(serde_json::json!({
+                            "violates": near_miss.keyword,
+                            "pointer": near_miss.pointer,
+                            "message": error.failure.to_string(),
+                            "instance": near_miss.instance,
+                        }));
+                    }
+                    _ => report_invalid(
+                        &mut out,
+                        &format!(
+                            "rejected near miss for {} at #{}",
+                            near_miss.keyword, near_miss.pointer
+                        ),
+                        &near_miss.instance,
+                        &errors,
+                    ),
+                }
+            }
+
+            writeln!(out, "{:#}", Value::Array(results))?;
+        } else if at_boundaries {
+            let mut results = vec![];
+
+            for boundary in boundaries(&schemas, 0, options)? {
+                let errors = validator.validate(&boundary.instance);
+
+                // Keep only the instances that are still valid.
+                if errors.is_empty() {
+                    results.push(serde_json::json!({
+                        "boundary": boundary.keyword,
+                        "pointer": boundary.pointer,
+                        "instance": boundary.instance,
+                    }));
+                } else {
+                    report_invalid(
+                        &mut out,
+                        &format!(
+                            "rejected boundary for {} at #{}",
+                            boundary.keyword, boundary.pointer
+                        ),
+                        &boundary.instance,
+                        &errors,
+                    );
+                }
+            }
+
+            writeln!(out, "{:#}", Value::Array(results))?;
+        } else if let Some(seed) = seed {
+            let instances = fake(&schemas, 0, options, seed, count.unwrap_or(1))?;
+
+            for instance in instances {
+                let errors = validator.validate(&instance);
+
+                // Random instances are still printed, so that the same seed gives the same lines.
+                if !errors.is_empty() {
+                    report_invalid(&mut out, "invalid random instance", &instance, &errors);
+                }
+
+                // Bulk instances are printed as NDJSON.
+                if count.is_some() {
+                    writeln!(out, "{}", instance)?;
+                } else {
+                    writeln!(out, "{:#}", instance)?;
+                }
+            }
+        } else {
+            let instance = example(&schemas, 0, options)?;
+            let errors = validator.validate(&instance);
+
+            if !errors.is_empty() {
+                report_invalid(&mut out, "invalid example", &instance, &errors);
+            }
+
+            writeln!(out, "{:#}", instance)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Fuzz {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Other schemas, for resolving references to them
+    #[clap(long)]
+    with: Vec<PathBuf>,
+    /// Directory to write seed instances to, one compact JSON file each
+    #[clap(long)]
+    corpus: Option<PathBuf>,
+    /// Seed for the random instances in the corpus
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+    /// Number of random instances to add to the corpus (in addition to examples and
+    /// boundaries)
+    #[clap(long, default_value_t = 100)]
+    count: usize,
+}
+
+impl Fuzz {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            with,
+            corpus,
+            seed,
+            count,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let schemas = std::iter::once(&schema)
+            .chain(&with)
+            .map(|path| inputs.read_json(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        write!(out, "{}", fuzz_dictionary(&schemas[0]))?;
+
+        if let Some(corpus) = corpus {
+            let validator = validator(config, &schemas[0], &schemas[1..])?;
+            let mut instances = vec![];
+
+            for instance in seed_corpus(&schemas, 0, seed, count)? {
+                let errors = validator.validate(&instance);
+
+                if errors.is_empty() {
+                    instances.push(instance);
+                } else {
+                    report_invalid(&mut out, "rejected seed instance", &instance, &errors);
+                }
+            }
+
+            std::fs::create_dir_all(&corpus)?;
+
+            for (index, instance) in instances.iter().enumerate() {
+                std::fs::write(
+                    corpus.join(format!("seed-{:04}.json", index + 1)),
+                    instance.to_string(),
+                )?;
+            }
+
+            log::info!(
+                "Wrote {} seed instances to {}",
+                instances.len(),
+                corpus.display()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A validator for the schema composed with the other schemas, for checking generated instances
+fn validator(config: &Config, schema: &Value, others: &[Value]) -> Result<Validator, Error> {
+    if others.is_empty() {
+        return config.validator(schema.clone());
+    }
+
+    let others = others
+        .iter()
+        .map(|other| (None, other.clone()))
+        .collect::<Vec<_>>();
+
+    config.validator(compose(schema, &others)?)
+}
+
+/// Report a generated instance that doesn't validate as intended
+fn report_invalid(out: &mut Output, kind: &str, instance: &Value, errors: &[ValidationError]) {
+    let messages = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let reason = if messages.is_empty() {
+        "it's valid".to_string()
+    } else {
+        messages.join("; ")
+    };
+
+    out.warning(
+        serde_json::json!({
+            "generated": kind,
+            "instance": instance,
+            "errors": messages,
+        }),
+        format!("{} {}: {}", kind, instance, reason),
+    );
+}
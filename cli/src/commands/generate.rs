@@ -0,0 +1,277 @@
+use super::Context;
+use crate::Error;
+use json_schema_tools::generate::{
+    avro, cddl, csharp, go, graphql, java, jsonld, jtd, postgres, proptest, proto, pydantic, xsd,
+    zod,
+};
+use json_schema_tools::schema::SchemaFile;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Generate {
+    #[clap(subcommand)]
+    target: GenerateTarget,
+}
+
+impl Generate {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { target } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let input = target.input();
+        let value = inputs.read_composed(&input.schema, &input.referenced)?;
+        let name = input
+            .schema
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        // The SQL, JTD, Zod, and JSON-LD generators work directly on JSON (so that they can use formats).
+        let typed = || serde_json::from_value::<SchemaFile>(value.clone());
+
+        match &target {
+            GenerateTarget::Python { flavor, .. } => {
+                let file = typed()?;
+                let code = match flavor {
+                    PythonFlavor::Pydantic => pydantic(&file, &name),
+                };
+
+                write!(out, "{}", code)?;
+            }
+            GenerateTarget::Go { package, .. } => write!(out, "{}", go(&typed()?, &name, package))?,
+            GenerateTarget::Proto { package, .. } => {
+                let proto = proto(&typed()?, &name, package.as_deref());
+
+                for constraint in &proto.unmapped {
+                    log::warn!(
+                        "No proto equivalent for {} {} on {}",
+                        constraint.keyword,
+                        constraint.value,
+                        constraint.location
+                    );
+                }
+
+                write!(out, "{}", proto.source)?;
+            }
+            GenerateTarget::Avro { namespace, .. } => {
+                writeln!(out, "{:#}", avro(&typed()?, &name, namespace.as_deref()))?
+            }
+            GenerateTarget::Sql { dialect, jsonb, .. } => {
+                let code = match dialect {
+                    SqlDialect::Postgres => postgres(&value, &name, *jsonb),
+                };
+
+                write!(out, "{}", code)?;
+            }
+            GenerateTarget::Graphql { inputs, .. } => {
+                write!(out, "{}", graphql(&typed()?, &name, *inputs))?
+            }
+            GenerateTarget::Java { package, .. } => {
+                write!(out, "{}", java(&typed()?, &name, package.as_deref()))?
+            }
+            GenerateTarget::Cddl { .. } => write!(out, "{}", cddl(&typed()?, &name))?,
+            GenerateTarget::Jtd { .. } => {
+                let jtd = jtd(&value);
+
+                for construct in &jtd.lossy {
+                    let path = format!("/{}", construct.path.join("/"));
+
+                    let text = format!(
+                        "{}: no JTD equivalent for {} {}",
+                        path, construct.keyword, construct.value
+                    );
+
+                    out.warning(
+                        serde_json::json!({
+                            "path": path,
+                            "keyword": construct.keyword,
+                            "value": construct.value,
+                        }),
+                        text,
+                    );
+                }
+
+                writeln!(out, "{:#}", jtd.schema)?
+            }
+            GenerateTarget::Xsd { namespace, .. } => {
+                write!(out, "{}", xsd(&typed()?, &name, namespace.as_deref()))?
+            }
+            GenerateTarget::Proptest { types, .. } => {
+                write!(out, "{}", proptest(&typed()?, &name, types.as_deref()))?
+            }
+            GenerateTarget::Zod { .. } => write!(out, "{}", zod(&value, &name))?,
+            GenerateTarget::Csharp { namespace, .. } => {
+                write!(out, "{}", csharp(&typed()?, &name, namespace.as_deref()))?
+            }
+            GenerateTarget::Jsonld { vocab, .. } => writeln!(out, "{:#}", jsonld(&value, vocab))?,
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum GenerateTarget {
+    /// Python models
+    Python {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Library to generate models for
+        #[clap(long, value_enum, default_value_t = PythonFlavor::Pydantic)]
+        flavor: PythonFlavor,
+    },
+    /// Go structs
+    Go {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Package name for the generated file
+        #[clap(long, default_value = "models")]
+        package: String,
+    },
+    /// A proto3 file
+    ///
+    /// Constraints with no proto equivalent (like patterns and bounds) are left in comments and
+    /// logged as warnings.
+    Proto {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Package for the generated file
+        #[clap(long)]
+        package: Option<String>,
+    },
+    /// An Avro schema
+    Avro {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Namespace for the top-level record
+        #[clap(long)]
+        namespace: Option<String>,
+    },
+    /// SQL `CREATE TABLE` statements for the top-level schema and object definitions
+    Sql {
+        #[clap(flatten)]
+        input: GenerateInput,
+        #[clap(long, value_enum, default_value_t = SqlDialect::Postgres)]
+        dialect: SqlDialect,
+        /// Store nested objects as `jsonb` instead of flattening them into columns
+        #[clap(long)]
+        jsonb: bool,
+    },
+    /// GraphQL type definitions
+    Graphql {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Also generate input types for objects
+        #[clap(long)]
+        inputs: bool,
+    },
+    /// Java records with Jackson annotations
+    Java {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Package for the generated class
+        #[clap(long)]
+        package: Option<String>,
+    },
+    /// CDDL (RFC 8610) rules, for validating CBOR representations
+    Cddl {
+        #[clap(flatten)]
+        input: GenerateInput,
+    },
+    /// A JSON Type Definition (RFC 8927) schema
+    ///
+    /// Keywords with no JTD equivalent (like patterns, bounds that aren't those of a JTD integer
+    /// type, and unions without a discriminator) are dropped and reported as warnings (or findings
+    /// in the JSON report).
+    Jtd {
+        #[clap(flatten)]
+        input: GenerateInput,
+    },
+    /// An XML Schema (experimental), for validating XML representations
+    ///
+    /// Objects become complex types and arrays become repeated elements. Additional properties,
+    /// tuples, and unions other than nullable schemas aren't represented.
+    Xsd {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Target namespace for the generated types
+        #[clap(long)]
+        namespace: Option<String>,
+    },
+    /// Rust functions returning `proptest` strategies for schema-conformant values
+    ///
+    /// Values are `serde_json::Value`s, which can be deserialized into the types generated from
+    /// the same schema (for example by Typify).
+    Proptest {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Path of the module containing the generated types, to implement `Arbitrary` for them
+        /// (for example `crate::types`)
+        #[clap(long)]
+        types: Option<String>,
+    },
+    /// A TypeScript module of Zod validators, with a type inferred from each
+    Zod {
+        #[clap(flatten)]
+        input: GenerateInput,
+    },
+    /// C# records with `System.Text.Json` attributes (for .NET 9 or later)
+    Csharp {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Namespace for the generated types
+        #[clap(long)]
+        namespace: Option<String>,
+    },
+    /// A JSON-LD context mapping properties to terms in a vocabulary
+    Jsonld {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Base vocabulary IRI for the terms (for example `https://example.com/vocab#`)
+        #[clap(long)]
+        vocab: String,
+    },
+}
+
+impl GenerateTarget {
+    fn input(&self) -> &GenerateInput {
+        match self {
+            Self::Python { input, .. }
+            | Self::Go { input, .. }
+            | Self::Proto { input, .. }
+            | Self::Avro { input, .. }
+            | Self::Sql { input, .. }
+            | Self::Graphql { input, .. }
+            | Self::Java { input, .. }
+            | Self::Cddl { input }
+            | Self::Jtd { input }
+            | Self::Xsd { input, .. }
+            | Self::Proptest { input, .. }
+            | Self::Zod { input }
+            | Self::Csharp { input, .. }
+            | Self::Jsonld { input, .. } => input,
+        }
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct GenerateInput {
+    /// Schema path (or `-` for stdin)
+    pub schema: PathBuf,
+    /// Referenced schema paths to compose with the schema
+    #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
+    pub referenced: Vec<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum PythonFlavor {
+    /// Pydantic v2
+    Pydantic,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum SqlDialect {
+    Postgres,
+}
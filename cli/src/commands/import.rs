@@ -0,0 +1,216 @@
+use super::Context;
+use crate::files::{observe_records, observe_rows, read_bytes, read_string, RecordFormat};
+use crate::{timings, Error};
+use json_schema_tools::import::{from_descriptor_set, from_jtd, from_openapi, from_typescript};
+use json_schema_tools::infer::{CsvInference, Inference};
+use json_schema_tools::key_order;
+use json_schema_tools::schema::{adapt_schemars, AdaptationKind};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Import {
+    #[clap(subcommand)]
+    source: ImportSource,
+}
+
+impl Import {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { source } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+
+        match source {
+            ImportSource::Avro { schema } => writeln!(out, "{:#}", inputs.read_avro(&schema)?)?,
+            ImportSource::Protobuf { descriptor_set } => {
+                let name = descriptor_set
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let schema = from_descriptor_set(&read_bytes(&descriptor_set)?, &name)?;
+
+                writeln!(out, "{:#}", schema)?
+            }
+            ImportSource::Openapi {
+                document,
+                output_dir,
+                base_id,
+            } => {
+                std::fs::create_dir_all(&output_dir)?;
+
+                for (name, schema) in from_openapi(&inputs.read_json(&document)?, &base_id)? {
+                    let path = output_dir.join(format!("{}.json", name));
+                    std::fs::write(&path, key_order::format_with_indent(&schema, 2))?;
+                    out.report.artifact(&path);
+                }
+            }
+            ImportSource::Schemars { schema } => {
+                let mut value = inputs.read_json(schema)?;
+
+                for adaptation in adapt_schemars(&mut value) {
+                    let path = format!("/{}", adaptation.path.join("/"));
+
+                    let finding = serde_json::json!({
+                        "path": path,
+                        "adaptation": format!("{:?}", adaptation.kind),
+                    });
+
+                    if let AdaptationKind::Removed(keyword) = &adaptation.kind {
+                        out.warning(finding, format!("{}: removed {}", path, keyword));
+                    } else if out.is_structured() {
+                        out.report.finding(finding);
+                    } else {
+                        log::info!("{}: {:?}", path, adaptation.kind);
+                    }
+                }
+
+                writeln!(out, "{:#}", value)?;
+            }
+            ImportSource::Jtd { schema } => {
+                let name = schema
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                writeln!(out, "{:#}", from_jtd(&inputs.read_json(&schema)?, &name)?)?
+            }
+            ImportSource::Typescript { source } => {
+                let name = source
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().trim_end_matches(".d").to_string())
+                    .unwrap_or_default();
+
+                writeln!(out, "{:#}", from_typescript(&read_string(&source)?, &name)?)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Infer {
+    /// Record file paths (or `-` for stdin)
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
+    /// Print how often each field appears in the objects that could contain it (or each
+    /// column is non-empty), instead of the schema (or as findings in the JSON report)
+    #[clap(long)]
+    stats: bool,
+    /// Format of the record files, instead of choosing by the extension of the first (where
+    /// `.csv` and `.tsv` files are CSV and TSV, and anything else is NDJSON)
+    #[clap(long, value_enum)]
+    records: Option<RecordFormat>,
+}
+
+impl Infer {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            paths,
+            stats,
+            records,
+        } = self;
+        let mut out = ctx.out;
+        let (schema, frequencies) =
+            match records.unwrap_or_else(|| RecordFormat::by_extension(&paths[0])) {
+                RecordFormat::Ndjson => {
+                    let mut inference = Inference::default();
+
+                    timings::phase("infer", || {
+                        paths
+                            .iter()
+                            .try_for_each(|path| observe_records(path, &mut inference))
+                    })?;
+
+                    (inference.to_schema(), inference.frequencies())
+                }
+                format => {
+                    let mut inference = None;
+
+                    timings::phase("infer", || {
+                        paths
+                            .iter()
+                            .try_for_each(|path| observe_rows(path, format, &mut inference))
+                    })?;
+
+                    let inference = inference.unwrap_or_else(|| CsvInference::new(vec![]));
+
+                    (inference.to_schema(), inference.frequencies())
+                }
+            };
+
+        if stats {
+            for frequency in frequencies {
+                let text = format!(
+                    "{} {}/{} ({:.1}%)",
+                    frequency.pointer,
+                    frequency.count,
+                    frequency.total,
+                    100.0 * frequency.proportion()
+                );
+
+                out.finding(
+                    serde_json::json!({
+                        "pointer": frequency.pointer,
+                        "count": frequency.count,
+                        "total": frequency.total,
+                    }),
+                    text,
+                )?;
+            }
+        }
+
+        if !stats || out.is_structured() {
+            writeln!(out, "{:#}", schema)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum ImportSource {
+    /// An Avro schema (`.avsc`)
+    Avro {
+        /// Avro schema path (or `-` for stdin)
+        schema: PathBuf,
+    },
+    /// A compiled protobuf `FileDescriptorSet` (from `protoc --descriptor_set_out`)
+    ///
+    /// Comments are only included if the set was compiled with `--include_source_info`.
+    Protobuf {
+        /// Descriptor set path (or `-` for stdin)
+        descriptor_set: PathBuf,
+    },
+    /// The component schemas of an OpenAPI 3.0 or 3.1 document, written to one file each
+    Openapi {
+        /// OpenAPI document path (or `-` for stdin)
+        document: PathBuf,
+        /// Directory to write the schemas to
+        #[clap(short, long)]
+        output_dir: PathBuf,
+        /// Path that the schema IDs start with
+        #[clap(long, default_value = "/schemas")]
+        base_id: String,
+    },
+    /// A schema generated by the `schemars` crate, in either its draft-07 or 2020-12 layout
+    ///
+    /// Removed keywords (like `default` and `uniqueItems`) are reported as warnings.
+    Schemars {
+        /// Schema path (or `-` for stdin)
+        schema: PathBuf,
+    },
+    /// A JSON Type Definition (RFC 8927) schema
+    Jtd {
+        /// JTD schema path (or `-` for stdin)
+        schema: PathBuf,
+    },
+    /// TypeScript interface, type alias, and enum declarations (`.ts`), as definitions
+    ///
+    /// Only a subset of TypeScript is supported, and generics, imports, and methods are errors.
+    Typescript {
+        /// Source path (or `-` for stdin)
+        source: PathBuf,
+    },
+}
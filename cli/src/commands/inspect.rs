@@ -0,0 +1,341 @@
+use super::Context;
+use crate::files::schema_files;
+use crate::Error;
+use json_schema_tools::docs::{dictionary, to_delimited};
+use json_schema_tools::explain::explain;
+use json_schema_tools::graph::{RefGraph, RenderOptions};
+use json_schema_tools::refactor::slice;
+use json_schema_tools::resolve::resolve;
+use json_schema_tools::{query, stats};
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Slice {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Definition name or JSON Pointer (for example `/properties/order`)
+    target: String,
+}
+
+impl Slice {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { schema, target } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        writeln!(out, "{:#}", slice(&inputs.read_json(schema)?, &target)?)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Explain {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Definition name or JSON Pointer (for example `/properties/order`), if not the root
+    target: Option<String>,
+    /// Other schemas, for resolving references to them
+    #[clap(long)]
+    with: Vec<PathBuf>,
+}
+
+impl Explain {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            target,
+            with,
+        } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let schemas = std::iter::once(&schema)
+            .chain(&with)
+            .map(|path| inputs.read_json(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        writeln!(
+            out,
+            "{}",
+            explain(&schemas, 0, target.as_deref().unwrap_or(""))?
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Query {
+    /// JSON Pointer (`/properties/id`) or JSONPath (`$..[?(@.format == 'date-time')]`)
+    query: String,
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+}
+
+impl Query {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { query, paths } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let query = query.parse::<query::Query>()?;
+
+        for path in schema_files(paths, config)? {
+            let value = inputs.read_json(&path)?;
+
+            for (pointer, selected) in query.select(&value) {
+                out.finding(
+                    serde_json::json!({
+                        "file": path.display().to_string(),
+                        "pointer": pointer,
+                        "value": selected,
+                    }),
+                    format!("{}#{}: {}", path.display(), pointer, selected),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Resolve {
+    /// Reference to resolve
+    reference: String,
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+    /// Schema containing the reference, for resolving relative references
+    #[clap(long)]
+    from: Option<PathBuf>,
+}
+
+impl Resolve {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            reference,
+            paths,
+            from,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let mut files = schema_files(paths, config)?;
+        let base = from.map(|from| match files.iter().position(|file| *file == from) {
+            Some(index) => index,
+            None => {
+                files.push(from);
+                files.len() - 1
+            }
+        });
+        let schemas = files
+            .iter()
+            .map(|path| inputs.read_json(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let resolved = resolve(&schemas, &reference, base)?;
+
+        writeln!(
+            out,
+            "{}#{}",
+            files[resolved.schema].display(),
+            resolved.pointer
+        )?;
+        writeln!(out, "{:#}", resolved.value)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Stats {
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+}
+
+impl Stats {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { paths } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let paths = schema_files(paths, config)?;
+        let mut total = stats::Stats::default();
+        let mut files = serde_json::Map::new();
+
+        for path in &paths {
+            let file_stats = stats::stats(&inputs.read_json(path)?);
+            total.merge(&file_stats);
+
+            if out.is_structured() {
+                files.insert(path.display().to_string(), stats_json(&file_stats)?);
+            } else {
+                print_stats(&mut out, &path.display().to_string(), &file_stats)?;
+            }
+        }
+
+        if out.is_structured() {
+            let stats = serde_json::json!({
+                "files": files,
+                "total": stats_json(&total)?,
+            });
+
+            writeln!(out, "{}", serde_json::to_string_pretty(&stats)?)?;
+        } else if paths.len() > 1 {
+            print_stats(&mut out, "total", &total)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Graph {
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+    /// Graph description language
+    #[clap(long, value_enum, default_value_t = GraphFormat::Dot)]
+    syntax: GraphFormat,
+    /// Group definitions by file
+    #[clap(long)]
+    cluster: bool,
+    /// Highlight reference cycles
+    #[clap(long)]
+    highlight_cycles: bool,
+    /// Highlight references that don't resolve
+    #[clap(long)]
+    highlight_dangling: bool,
+}
+
+impl Graph {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            paths,
+            syntax,
+            cluster,
+            highlight_cycles,
+            highlight_dangling,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let files = schema_files(paths, config)?;
+
+        let schemas = files
+            .iter()
+            .map(|path| Ok((path.display().to_string(), inputs.read_json(path)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let graph = RefGraph::new(
+            &schemas
+                .iter()
+                .map(|(name, value)| (name.as_str(), value))
+                .collect::<Vec<_>>(),
+        );
+        let options = RenderOptions {
+            cluster_by_file: cluster,
+            highlight_cycles,
+            highlight_dangling,
+        };
+
+        match syntax {
+            GraphFormat::Dot => write!(out, "{}", graph.to_dot(options))?,
+            GraphFormat::Mermaid => write!(out, "{}", graph.to_mermaid(options))?,
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Dictionary {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Separate columns with tabs instead of commas
+    #[clap(long)]
+    tsv: bool,
+}
+
+impl Dictionary {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { schema, tsv } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let delimiter = if tsv { '\t' } else { ',' };
+
+        write!(
+            out,
+            "{}",
+            to_delimited(&dictionary(&inputs.read_json(schema)?), delimiter)
+        )?;
+
+        Ok(())
+    }
+}
+
+fn print_stats(out: &mut dyn Write, name: &str, stats: &stats::Stats) -> std::io::Result<()> {
+    writeln!(out, "{}", name)?;
+    writeln!(
+        out,
+        "  definitions: {} ({} documented)",
+        stats.definitions, stats.documented_definitions
+    )?;
+    writeln!(
+        out,
+        "  properties: {} ({} documented)",
+        stats.properties, stats.documented_properties
+    )?;
+    writeln!(
+        out,
+        "  refs: {} internal, {} external",
+        stats.internal_refs, stats.external_refs
+    )?;
+    writeln!(out, "  max depth: {}", stats.max_depth)?;
+    writeln!(
+        out,
+        "  enums: {} ({} values, largest {})",
+        stats.enums, stats.enum_values, stats.max_enum_size
+    )?;
+    writeln!(
+        out,
+        "  documentation coverage: {:.1}%",
+        stats.documentation_coverage() * 100.0
+    )
+}
+
+fn stats_json(stats: &stats::Stats) -> Result<Value, serde_json::Error> {
+    let mut value = serde_json::to_value(stats)?;
+
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert(
+            "documentation_coverage".to_string(),
+            stats.documentation_coverage().into(),
+        );
+    }
+
+    Ok(value)
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum GraphFormat {
+    Dot,
+    Mermaid,
+}
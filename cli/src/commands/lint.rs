@@ -0,0 +1,246 @@
+use super::{Context, Output};
+use crate::files::{output, read_file_list, schema_files, Inputs};
+use crate::report::Report;
+use crate::{parallel, timings, watch, Error};
+use clap::builder::PossibleValuesParser;
+use json_schema_tools::key::Interner;
+use json_schema_tools::lint::{
+    check_references, lint_profiled, openapi_schemas, Issue, Severity, RULES,
+};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, clap::Args)]
+pub struct Lint {
+    /// Main schema path (or `-` for stdin)
+    #[clap(short, long, conflicts_with = "paths")]
+    schema: Option<PathBuf>,
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// entry points or root
+    paths: Vec<PathBuf>,
+    /// File listing schemas to lint (or `-` for stdin), separated by NULs or newlines
+    #[clap(long, conflicts_with_all = ["schema", "paths"])]
+    files_from: Option<PathBuf>,
+    /// OpenAPI document whose schemas (and references between components) should be linted
+    #[clap(long, conflicts_with_all = ["schema", "paths", "files_from"])]
+    openapi: Option<PathBuf>,
+    /// Rules to ignore
+    #[clap(long, value_delimiter = ',', value_parser = PossibleValuesParser::new(RULES))]
+    allow: Vec<String>,
+    /// Rules to report as warnings
+    #[clap(long, value_delimiter = ',', value_parser = PossibleValuesParser::new(RULES))]
+    warn: Vec<String>,
+    /// Rules to report as errors
+    #[clap(long, value_delimiter = ',', value_parser = PossibleValuesParser::new(RULES))]
+    deny: Vec<String>,
+    /// Lint again whenever the schema changes
+    #[clap(long)]
+    watch: bool,
+    /// Number of schemas to parse and lint at once
+    #[clap(short, long, default_value_t = 1)]
+    jobs: usize,
+}
+
+impl Lint {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            paths,
+            files_from,
+            openapi,
+            allow,
+            warn,
+            deny,
+            watch,
+            jobs,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            destination,
+            mut out,
+        } = ctx;
+        let mut severities = config.lint.rules.clone();
+
+        for (rules, severity) in [
+            (allow, Severity::Allow),
+            (warn, Severity::Warning),
+            (deny, Severity::Error),
+        ] {
+            for rule in rules {
+                severities.insert(rule, severity);
+            }
+        }
+
+        if let Some(document) = openapi {
+            if watch {
+                watch::watch(std::slice::from_ref(&document), || {
+                    run_lint_openapi(
+                        &mut Output {
+                            out: &mut output(destination.clone())?,
+                            report: &mut Report::default(),
+                        },
+                        inputs,
+                        &document,
+                        &severities,
+                    )
+                    .map(|_| vec![])
+                })?;
+            } else if run_lint_openapi(&mut out, inputs, &document, &severities)? {
+                out.report.issues();
+            }
+        } else {
+            let schemas = match (schema, files_from) {
+                (Some(schema), _) => vec![schema],
+                (None, Some(files_from)) => read_file_list(&files_from, config)?,
+                (None, None) if paths.is_empty() && !config.entry_points.is_empty() => {
+                    config.entry_points.clone()
+                }
+                (None, None) => schema_files(paths, config)?,
+            };
+
+            if watch {
+                watch::watch(&schemas, || {
+                    run_lint(
+                        &mut Output {
+                            out: &mut output(destination.clone())?,
+                            report: &mut Report::default(),
+                        },
+                        inputs,
+                        &schemas,
+                        &severities,
+                        jobs,
+                    )
+                    .map(|_| vec![])
+                })?;
+            } else if run_lint(&mut out, inputs, &schemas, &severities, jobs)? {
+                out.report.issues();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Print the issues found in schemas (after the path if there's more than one), and return whether
+/// any of them are errors
+///
+/// Schemas are parsed and linted on the given number of threads, but issues are printed in the
+/// order of the schemas, and nothing after a schema that can't be read is linted.
+fn run_lint(
+    out: &mut Output,
+    inputs: Inputs,
+    schemas: &[PathBuf],
+    severities: &BTreeMap<String, Severity>,
+    jobs: usize,
+) -> Result<bool, Error> {
+    let mut readable = vec![];
+    let mut error = None;
+
+    for value in parallel::map(schemas, jobs, |path| inputs.read_json(path)) {
+        match value {
+            Ok(value) => readable.push(value),
+            Err(value_error) => {
+                error = Some(value_error);
+                break;
+            }
+        }
+    }
+
+    let issues = parallel::map(&readable, jobs, |value| {
+        lint_profiled(value, &mut Interner::default(), &mut timings::Recorder)
+    });
+    let mut failed = false;
+
+    for (schema, issues) in schemas.iter().zip(issues) {
+        for issue in issues {
+            failed |= print_issue(out, severities, schema, schemas.len() > 1, None, &issue)?;
+        }
+    }
+
+    out.flush()?;
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(failed),
+    }
+}
+
+/// Lint the schemas in an OpenAPI document, reporting issues with their locations in it
+fn run_lint_openapi(
+    out: &mut Output,
+    inputs: Inputs,
+    document: &Path,
+    severities: &BTreeMap<String, Severity>,
+) -> Result<bool, Error> {
+    let value = inputs.read_json(document)?;
+    let schemas = openapi_schemas(&value)?;
+    let mut interner = Interner::default();
+    let mut failed = false;
+
+    for (location, schema) in &schemas {
+        for issue in lint_profiled(schema, &mut interner, &mut timings::Recorder) {
+            failed |= print_issue(out, severities, document, false, Some(location), &issue)?;
+        }
+    }
+
+    for (location, issue) in check_references(&value) {
+        failed |= print_issue(out, severities, document, false, Some(&location), &issue)?;
+    }
+
+    out.flush()?;
+
+    Ok(failed)
+}
+
+/// Print a lint issue (after the path if requested, and the location if given) unless its rule is
+/// allowed, and return whether it's an error
+fn print_issue(
+    out: &mut Output,
+    severities: &BTreeMap<String, Severity>,
+    path: &Path,
+    show_path: bool,
+    location: Option<&str>,
+    issue: &Issue,
+) -> Result<bool, Error> {
+    let severity = severities
+        .get(issue.rule())
+        .copied()
+        .unwrap_or_else(|| issue.default_severity());
+
+    if severity == Severity::Allow {
+        return Ok(false);
+    }
+
+    if out.is_structured() {
+        let mut finding = serde_json::json!({
+            "file": path.display().to_string(),
+            "rule": issue.rule(),
+            "severity": severity.to_string(),
+            "message": issue.to_string(),
+        });
+
+        if let Some(location) = location {
+            finding["location"] = serde_json::json!(location);
+        }
+
+        if let Some(pointer) = issue.pointer() {
+            finding["path"] = serde_json::json!(pointer);
+        }
+
+        out.report.finding(finding);
+    } else {
+        if show_path {
+            write!(out, "{}: ", path.display())?;
+        }
+
+        if let Some(location) = location {
+            write!(out, "{}: ", location)?;
+        }
+
+        writeln!(out, "{}: {}", severity, issue)?;
+    }
+
+    Ok(severity == Severity::Error)
+}
@@ -0,0 +1,87 @@
+//! The subcommands, with their arguments and what they do
+
+pub mod compat;
+pub mod compose;
+pub mod docs;
+pub mod example;
+pub mod generate;
+pub mod import;
+pub mod inspect;
+pub mod lint;
+pub mod project;
+pub mod refactor;
+pub mod registry;
+pub mod test;
+pub mod validate;
+
+use super::config::Config;
+use super::files::Inputs;
+use super::report::Report;
+use serde_json::Value;
+use std::fmt::Display;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// What a command runs with
+pub struct Context<'a> {
+    pub config: &'a Config,
+    pub inputs: Inputs,
+    /// The file results are written to (for commands that open it again for each run while
+    /// watching), or standard output if there isn't one
+    pub destination: Option<PathBuf>,
+    pub out: Output<'a>,
+}
+
+/// Where a command's results go: printed as text, or recorded as findings in a structured report
+///
+/// Anything written directly is printed with text output, and included in the report otherwise.
+pub struct Output<'a> {
+    pub out: &'a mut dyn Write,
+    pub report: &'a mut Report,
+}
+
+impl Output<'_> {
+    pub fn is_structured(&self) -> bool {
+        self.report.is_structured()
+    }
+
+    /// Record a finding in a structured report, or print it as a line of text
+    pub fn finding(&mut self, finding: Value, text: impl Display) -> std::io::Result<()> {
+        if self.is_structured() {
+            self.report.finding(finding);
+            Ok(())
+        } else {
+            writeln!(self.out, "{}", text)
+        }
+    }
+
+    /// Record a finding in a structured report, or print it as a warning on standard error (for
+    /// commands whose output is a schema)
+    pub fn warning(&mut self, finding: Value, text: impl Display) {
+        if self.is_structured() {
+            self.report.finding(finding);
+        } else {
+            eprintln!("warning: {}", text);
+        }
+    }
+
+    /// Print a line of text, unless the report is structured (for summaries that the findings
+    /// already cover)
+    pub fn summary(&mut self, text: impl Display) -> std::io::Result<()> {
+        if self.is_structured() {
+            Ok(())
+        } else {
+            writeln!(self.out, "{}", text)
+        }
+    }
+}
+
+impl Write for Output<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.out.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
@@ -0,0 +1,83 @@
+use super::Context;
+use crate::config::{CONFIG_FILE_NAME, CONFIG_TEMPLATE};
+use crate::{Error, Opts};
+use clap::CommandFactory;
+use clap_complete::{Generator, Shell};
+use json_schema_tools::schema::DRAFT_2020_12_URI;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Init {
+    /// Path for the new schema (or the directory for the configuration file)
+    #[clap(required_unless_present = "workspace")]
+    path: Option<PathBuf>,
+    /// Create a configuration file instead of a schema
+    #[clap(long)]
+    workspace: bool,
+}
+
+impl Init {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { path, workspace } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let (path, contents) = if workspace {
+            (
+                path.unwrap_or_default().join(CONFIG_FILE_NAME),
+                CONFIG_TEMPLATE.to_string(),
+            )
+        } else {
+            // Clap requires a path without `--workspace`.
+            let path = path.unwrap_or_default();
+            let skeleton = serde_json::json!({
+                "$schema": DRAFT_2020_12_URI,
+                "$id": config.schema_id(&path),
+                "$defs": {}
+            });
+
+            let contents = inputs
+                .format_of(&path)
+                .format(&skeleton, config.output.indent.unwrap_or(2));
+
+            (path, contents)
+        };
+
+        if path.exists() {
+            return Err(Error::FileExists(path));
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&path, contents)?;
+        writeln!(out, "Created {}", path.display())?;
+        out.report.artifact(&path);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Completions {
+    #[clap(value_enum)]
+    shell: Shell,
+}
+
+impl Completions {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { shell } = self;
+        let mut out = ctx.out;
+        let mut command = Opts::command().bin_name(env!("CARGO_BIN_NAME"));
+        command.build();
+        // Unlike `clap_complete::generate`, this doesn't panic on write errors.
+        shell.try_generate(&command, &mut out)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,520 @@
+use super::{Context, Output};
+use crate::files::{
+    expand_paths, is_standard_stream, read_file_list, read_string, schema_files, DataFormat,
+};
+use crate::{DraftVersion, Error};
+use json_schema_tools::key_order::{self, DefOrder};
+use json_schema_tools::refactor::{
+    dedupe, extract, inline, prune, rename_definition, rename_schema, retarget,
+    unreachable_definitions,
+};
+use json_schema_tools::reference::Reference;
+use json_schema_tools::schema::convert;
+use json_schema_tools::strip::{strip, StripOptions};
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, clap::Args)]
+pub struct Fmt {
+    /// Rewrite the files in place instead of printing to stdout
+    #[clap(short, long, conflicts_with = "check")]
+    write: bool,
+    /// Print a diff and exit with an error if any file is not formatted
+    #[clap(long)]
+    check: bool,
+    /// Also reorder top-level definitions
+    #[clap(long, value_enum)]
+    sort_defs: Option<DefsOrder>,
+    /// Schema paths (or `-` for stdin), where directories are searched for JSON and YAML files
+    #[clap(required_unless_present = "files_from")]
+    paths: Vec<PathBuf>,
+    /// File listing schemas to format (or `-` for stdin), separated by NULs or newlines
+    #[clap(long)]
+    files_from: Option<PathBuf>,
+    /// Format to print or write schemas in, instead of the format they're read in
+    #[clap(long, value_enum)]
+    output_format: Option<DataFormat>,
+}
+
+impl Fmt {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            write,
+            check,
+            sort_defs,
+            paths,
+            files_from,
+            output_format,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let mut paths = expand_paths(paths, config)?;
+
+        if let Some(files_from) = files_from {
+            paths.extend(read_file_list(&files_from, config)?);
+        }
+
+        for path in paths {
+            let original = read_string(&path)?;
+            let format = inputs.format_of(&path);
+            let mut value = format.parse(&original)?;
+
+            if let Some(order) = sort_defs {
+                key_order::sort_defs(&mut value, order.into());
+            }
+
+            let formatted = output_format
+                .unwrap_or(format)
+                .format(&value, config.output.indent.unwrap_or(2));
+
+            if check {
+                if original != formatted {
+                    out.report.issues();
+                    let name = path.display().to_string();
+
+                    let diff = similar::TextDiff::from_lines(&original, &formatted)
+                        .unified_diff()
+                        .header(&name, &name)
+                        .to_string();
+
+                    out.finding(serde_json::json!({ "file": name }), diff.trim_end())?;
+                }
+            } else if write && !is_standard_stream(&path) {
+                std::fs::write(&path, formatted)?;
+                out.report.artifact(&path);
+            } else {
+                write!(out, "{}", formatted)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Convert {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Target draft
+    #[clap(long, value_enum)]
+    to: DraftVersion,
+}
+
+impl Convert {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { schema, to } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut value = inputs.read_json(schema)?;
+
+        for unconverted in convert(&mut value, to.into()) {
+            let path = format!("/{}", unconverted.path.join("/"));
+
+            out.warning(
+                serde_json::json!({
+                    "path": path,
+                    "keyword": unconverted.keyword,
+                    "message": unconverted.reason,
+                }),
+                format!("{}: {}: {}", path, unconverted.keyword, unconverted.reason),
+            );
+        }
+
+        writeln!(out, "{:#}", value)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Rename {
+    /// Reference to the definition (`/schemas/foo#/$defs/bar`) or schema (`/schemas/foo`)
+    target: String,
+    /// New definition name or last segment of the schema ID
+    new_name: String,
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+}
+
+impl Rename {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            target,
+            new_name,
+            paths,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let files = schema_files(paths, config)?;
+        let mut schemas = files
+            .iter()
+            .map(|path| inputs.read_json(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let touched = match target.parse::<Reference>()? {
+            Reference::Both { fragment_name, .. } => {
+                let id = target.split('#').next().unwrap_or_default();
+
+                rename_definition(&mut schemas, id, &fragment_name, &new_name)?
+            }
+            Reference::PathOnly { .. } => rename_schema(&mut schemas, &target, &new_name)?,
+            Reference::FragmentOnly { .. } => {
+                return Err(json_schema_tools::refactor::Error::UnknownSchema(target).into())
+            }
+        };
+
+        for index in touched {
+            std::fs::write(
+                &files[index],
+                inputs.format_of(&files[index]).serialize(&schemas[index]),
+            )?;
+            writeln!(out, "{}", files[index].display())?;
+            out.report.artifact(&files[index]);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Retarget {
+    /// Old and new prefixes (for example `/schemas/=https://schemas.example.com/v2/`)
+    #[clap(short, long = "map", value_parser = parse_prefix_mapping, required = true)]
+    mappings: Vec<(String, String)>,
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+    /// Print the files that would change without changing them
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl Retarget {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            mappings,
+            paths,
+            dry_run,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let files = schema_files(paths, config)?;
+        let mut schemas = files
+            .iter()
+            .map(|path| inputs.read_json(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = retarget(&mut schemas, &mappings);
+
+        for index in result.changed {
+            if !dry_run {
+                std::fs::write(
+                    &files[index],
+                    inputs.format_of(&files[index]).serialize(&schemas[index]),
+                )?;
+                out.report.artifact(&files[index]);
+            }
+
+            writeln!(out, "{}", files[index].display())?;
+        }
+
+        for (index, uri) in result.unmatched {
+            out.finding(
+                serde_json::json!({
+                    "file": files[index].display().to_string(),
+                    "unmatched": uri,
+                }),
+                format!("{}: unmatched {}", files[index].display(), uri),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Dedupe {
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+    /// Print the definitions that would be merged without changing any files
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl Dedupe {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { paths, dry_run } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let files = schema_files(paths, config)?;
+        let original = files
+            .iter()
+            .map(|path| inputs.read_json(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut schemas = original.clone();
+
+        for duplicate in dedupe(&mut schemas) {
+            let file = files[duplicate.schema].display().to_string();
+            let canonical_file = files[duplicate.canonical_schema].display().to_string();
+
+            let text = format!(
+                "{}: {} -> {}: {}",
+                file, duplicate.name, canonical_file, duplicate.canonical_name
+            );
+
+            out.finding(
+                serde_json::json!({
+                    "file": file,
+                    "definition": duplicate.name,
+                    "canonical_file": canonical_file,
+                    "canonical_definition": duplicate.canonical_name,
+                }),
+                text,
+            )?;
+        }
+
+        if !dry_run {
+            for (index, schema) in schemas.iter().enumerate() {
+                if *schema != original[index] {
+                    std::fs::write(
+                        &files[index],
+                        inputs.format_of(&files[index]).serialize(schema),
+                    )?;
+                    out.report.artifact(&files[index]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Prune {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Definitions to keep, along with anything they refer to
+    #[clap(long, value_delimiter = ',')]
+    keep: Vec<String>,
+    /// List the definitions that would be removed without changing the schema
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl Prune {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            keep,
+            dry_run,
+        } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut value = inputs.read_json(&schema)?;
+        let keep = keep.iter().map(String::as_str).collect::<Vec<_>>();
+
+        if dry_run {
+            for name in unreachable_definitions(&value, &keep)? {
+                writeln!(out, "{}", name)?;
+            }
+        } else {
+            let removed = prune(&mut value, &keep)?;
+
+            // The pruned schema is printed instead of the names if it was read from stdin.
+            for name in removed {
+                if is_standard_stream(&schema) {
+                    eprintln!("{}", name);
+                } else {
+                    writeln!(out, "{}", name)?;
+                }
+            }
+
+            write_back(&mut out, &schema, inputs.format_of(&schema), &value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Extract {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// JSON Pointer to the subschema (for example `/properties/address`)
+    pointer: String,
+    /// Name for the new definition
+    name: String,
+}
+
+impl Extract {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            pointer,
+            name,
+        } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut value = inputs.read_json(&schema)?;
+        extract(&mut value, &pointer, &name)?;
+
+        write_back(&mut out, &schema, inputs.format_of(&schema), &value)
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Inline {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Definition name or pointer (for example `#/$defs/address`)
+    definition: String,
+    /// JSON Pointer to a single reference to replace, instead of all of them
+    #[clap(long)]
+    at: Option<String>,
+    /// Delete the definition if nothing refers to it afterwards
+    #[clap(long)]
+    remove: bool,
+}
+
+impl Inline {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            definition,
+            at,
+            remove,
+        } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut value = inputs.read_json(&schema)?;
+        inline(&mut value, &definition, at.as_deref(), remove)?;
+
+        write_back(&mut out, &schema, inputs.format_of(&schema), &value)
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct SortDefs {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Order for the definitions
+    #[clap(long, value_enum, default_value_t = DefsOrder::Alphabetical)]
+    order: DefsOrder,
+}
+
+impl SortDefs {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { schema, order } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut value = inputs.read_json(&schema)?;
+        key_order::sort_defs(&mut value, order.into());
+
+        write_back(&mut out, &schema, inputs.format_of(&schema), &value)
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Strip {
+    /// Schema path (or `-` for stdin)
+    schema: PathBuf,
+    /// Also remove `title`
+    #[clap(long)]
+    title: bool,
+    /// Also remove `default`
+    #[clap(long)]
+    default: bool,
+    /// Print without whitespace
+    #[clap(long)]
+    compact: bool,
+}
+
+impl Strip {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            title,
+            default,
+            compact,
+        } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut value = inputs.read_json(schema)?;
+        strip(&mut value, StripOptions { title, default });
+
+        if compact {
+            writeln!(out, "{}", value)?;
+        } else {
+            writeln!(out, "{:#}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write a changed schema back to its file, or print it if it was read from stdin
+pub fn write_back(
+    out: &mut Output,
+    path: &Path,
+    format: DataFormat,
+    value: &Value,
+) -> Result<(), Error> {
+    let contents = format.serialize(value);
+
+    if is_standard_stream(path) {
+        write!(out, "{}", contents)?;
+    } else {
+        std::fs::write(path, contents)?;
+        out.report.artifact(path);
+    }
+
+    Ok(())
+}
+
+/// Parse an `OLD=NEW` prefix mapping
+fn parse_prefix_mapping(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(old, new)| (old.to_string(), new.to_string()))
+        .ok_or_else(|| "expected OLD=NEW".to_string())
+}
+
+impl From<DefsOrder> for DefOrder {
+    fn from(value: DefsOrder) -> Self {
+        match value {
+            DefsOrder::Alphabetical => Self::Alphabetical,
+            DefsOrder::Dependency => Self::Dependency,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum DefsOrder {
+    Alphabetical,
+    /// Definitions before the definitions that refer to them
+    Dependency,
+}
@@ -0,0 +1,124 @@
+use super::generate::GenerateInput;
+use super::{Context, Output};
+use crate::{registry, Error};
+use serde_json::Value;
+use std::io::Write;
+
+#[derive(Debug, clap::Args)]
+pub struct Registry {
+    #[clap(subcommand)]
+    action: RegistryAction,
+}
+
+impl Registry {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { action } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let client = registry::Client::new(&config.registry)?;
+        let publish = matches!(action, RegistryAction::Publish { .. });
+
+        match action {
+            RegistryAction::Fetch { subject, version } => {
+                let registered = client.fetch(&subject, &version)?;
+                log::info!(
+                    "Fetched version {} of {} (ID {})",
+                    registered.version,
+                    subject,
+                    registered.id
+                );
+
+                if registered.schema_type != "JSON" {
+                    log::warn!("{} is a {} schema", subject, registered.schema_type);
+                }
+
+                // Avro schemas are also JSON, but Protobuf schemas aren't.
+                match serde_json::from_str::<Value>(&registered.schema) {
+                    Ok(schema) => writeln!(out, "{:#}", schema)?,
+                    Err(_) => writeln!(out, "{}", registered.schema)?,
+                }
+            }
+            RegistryAction::Check { input, subject }
+            | RegistryAction::Publish { input, subject } => {
+                let subject = subject.unwrap_or_else(|| {
+                    input
+                        .schema
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                });
+                let schema = inputs.read_composed(&input.schema, &input.referenced)?;
+
+                if !run_registry_check(&mut out, &client, &subject, &schema)? {
+                    out.report.issues();
+                } else if publish {
+                    let id = client.publish(&subject, &schema)?;
+                    writeln!(out, "Published {} with ID {}", subject, id)?;
+                } else {
+                    writeln!(out, "Compatible with {}", subject)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Print the reasons a schema failed the registry's compatibility check, returning whether it
+/// passed
+fn run_registry_check(
+    out: &mut Output,
+    client: &registry::Client,
+    subject: &str,
+    schema: &Value,
+) -> Result<bool, Error> {
+    let compatibility = client.check(subject, schema)?;
+
+    for message in &compatibility.messages {
+        out.finding(
+            serde_json::json!({ "subject": subject, "message": message }),
+            format!("{}: {}", subject, message),
+        )?;
+    }
+
+    if !compatibility.is_compatible && compatibility.messages.is_empty() {
+        out.summary(format!(
+            "{}: not compatible with the latest version",
+            subject
+        ))?;
+    }
+
+    Ok(compatibility.is_compatible)
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum RegistryAction {
+    /// Print a version of a subject's schema
+    Fetch {
+        subject: String,
+        /// Version number, or `latest`
+        #[clap(long, default_value = "latest")]
+        version: String,
+    },
+    /// Check a schema against the latest version of a subject, using the subject's compatibility
+    /// level
+    Check {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Subject name (the schema's file name without its extension by default)
+        #[clap(long)]
+        subject: Option<String>,
+    },
+    /// Register a schema as a new version of a subject, if it passes the compatibility check
+    Publish {
+        #[clap(flatten)]
+        input: GenerateInput,
+        /// Subject name (the schema's file name without its extension by default)
+        #[clap(long)]
+        subject: Option<String>,
+    },
+}
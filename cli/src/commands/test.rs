@@ -0,0 +1,315 @@
+use super::{Context, Output};
+use crate::files::{instance_files_under, schema_files};
+use crate::fixtures::{is_fixture, Fixtures};
+use crate::report::TestFailure;
+use crate::Error;
+use json_schema_tools::compliance::{self, Outcome};
+use json_schema_tools::mutation::{self, Mutant};
+use json_schema_tools::validate::ValidationError;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, clap::Args)]
+pub struct Test {
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// roots
+    paths: Vec<PathBuf>,
+    /// Referenced schema paths to compose with each schema
+    #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
+    referenced: Vec<PathBuf>,
+    /// Also check that the fixtures catch each loosening of the schema (a dropped required
+    /// property, a wider type, or a removed constraint), failing for any they don't
+    #[clap(long)]
+    mutants: bool,
+}
+
+impl Test {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            paths,
+            referenced,
+            mutants,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let mut schemas = 0;
+        let mut fixtures = 0;
+        let mut failed = 0;
+        let mut mutant_count = 0;
+        let mut survived = 0;
+
+        for schema in schema_files(paths, config)?
+            .into_iter()
+            .filter(|path| !is_fixture(path))
+        {
+            let found = match Fixtures::find(&schema)? {
+                Some(found) => found,
+                None => continue,
+            };
+            let composed = inputs.read_composed(&schema, &referenced)?;
+            let validator = config.validator(composed.clone())?;
+            let mut corpus = (vec![], vec![]);
+            schemas += 1;
+
+            for (fixture, expect_valid) in found
+                .valid
+                .iter()
+                .map(|path| (path, true))
+                .chain(found.invalid.iter().map(|path| (path, false)))
+            {
+                let instance = inputs.read_instance(fixture)?;
+                let errors = validator.validate(&instance);
+                let passed = errors.is_empty() == expect_valid;
+                fixtures += 1;
+
+                if !passed {
+                    failed += 1;
+                    out.report.issues();
+                }
+
+                print_fixture_result(&mut out, &schema, fixture, expect_valid, passed, &errors)?;
+
+                if expect_valid {
+                    corpus.0.push(instance);
+                } else {
+                    corpus.1.push(instance);
+                }
+            }
+
+            if mutants {
+                for mutant in mutation::mutants(&composed) {
+                    let validator = config.validator(mutant.schema.clone())?;
+                    let killed = mutation::is_killed(&validator, &corpus.0, &corpus.1);
+                    mutant_count += 1;
+
+                    if !killed {
+                        survived += 1;
+                        out.report.issues();
+                    }
+
+                    print_mutant_result(&mut out, &schema, &mutant, killed)?;
+                }
+            }
+        }
+
+        out.summary(format!(
+            "{} fixtures for {} schemas: {} passed, {} failed",
+            fixtures,
+            schemas,
+            fixtures - failed,
+            failed
+        ))?;
+
+        if mutants {
+            out.summary(format!(
+                "{} mutants: {} caught, {} survived",
+                mutant_count,
+                mutant_count - survived,
+                survived
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Compliance {
+    /// A directory of suite files (such as `tests/draft2020-12`), searched recursively
+    suite: PathBuf,
+    /// List the cases that failed or are unsupported
+    #[clap(long)]
+    failures: bool,
+}
+
+impl Compliance {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { suite, failures } = self;
+        let Context {
+            inputs, mut out, ..
+        } = ctx;
+        let mut results = vec![];
+
+        for path in instance_files_under(&suite)? {
+            // Files are named after the keyword they cover (`optional/format/email.json`).
+            let keyword = path
+                .strip_prefix(&suite)
+                .unwrap_or(&path)
+                .with_extension("")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let groups: Vec<_> = serde_json::from_value(inputs.read_json(&path)?)?;
+
+            results.extend(compliance::run(&keyword, &groups));
+        }
+
+        if out.is_structured() {
+            for result in &results {
+                let failures = match result.outcome {
+                    Outcome::Passed => vec![],
+                    outcome => vec![TestFailure {
+                        keyword: result.keyword.clone(),
+                        message: format!("{}: {}", outcome, result.group),
+                    }],
+                };
+
+                out.report.test_case(
+                    format!("{}: {}", result.keyword, result.description),
+                    failures,
+                );
+            }
+        }
+
+        if failures && !out.is_structured() {
+            for result in &results {
+                if result.outcome != Outcome::Passed {
+                    writeln!(
+                        out,
+                        "{} ({}): {} ({})",
+                        result.keyword, result.outcome, result.description, result.group
+                    )?;
+                }
+            }
+        }
+
+        let summary = compliance::summarize(&results);
+        let width = summary.keys().map(String::len).max().unwrap_or(0);
+        let mut total = compliance::Compliance::default();
+
+        for (keyword, compliance) in &summary {
+            total.passed += compliance.passed;
+            total.failed += compliance.failed;
+            total.unsupported += compliance.unsupported;
+
+            out.finding(
+                serde_json::json!({
+                    "keyword": keyword,
+                    "passed": compliance.passed,
+                    "failed": compliance.failed,
+                    "unsupported": compliance.unsupported,
+                }),
+                format!(
+                    "{:width$}  {:>5}/{:<5} {:>5.1}%",
+                    keyword,
+                    compliance.passed,
+                    compliance.total(),
+                    compliance.ratio() * 100.0,
+                ),
+            )?;
+        }
+
+        out.summary(format!(
+            "{} cases for {} keywords: {} passed, {} failed, {} unsupported ({:.1}%)",
+            total.total(),
+            summary.len(),
+            total.passed,
+            total.failed,
+            total.unsupported,
+            total.ratio() * 100.0
+        ))?;
+
+        Ok(())
+    }
+}
+
+/// Report a fixture that was validated, printing it only if it failed (with the errors if it
+/// was rejected)
+fn print_fixture_result(
+    out: &mut Output,
+    schema: &Path,
+    fixture: &Path,
+    expect_valid: bool,
+    passed: bool,
+    errors: &[ValidationError],
+) -> Result<(), Error> {
+    let outcome = if errors.is_empty() {
+        "accepted"
+    } else {
+        "rejected"
+    };
+
+    if out.is_structured() {
+        let failures = match (passed, expect_valid) {
+            (true, _) => vec![],
+            (false, true) => errors
+                .iter()
+                .map(|error| TestFailure::new(error, None))
+                .collect(),
+            (false, false) => vec![TestFailure {
+                keyword: outcome.to_string(),
+                message: format!("invalid fixture is accepted by {}", schema.display()),
+            }],
+        };
+
+        if !passed {
+            out.report.finding(serde_json::json!({
+                "schema": schema.display().to_string(),
+                "fixture": fixture.display().to_string(),
+                "expected": if expect_valid { "valid" } else { "invalid" },
+                "errors": errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            }));
+        }
+
+        out.report
+            .test_case(fixture.display().to_string(), failures);
+    } else if !passed {
+        writeln!(
+            out,
+            "{}: {} by {}",
+            fixture.display(),
+            outcome,
+            schema.display()
+        )?;
+
+        for error in errors {
+            writeln!(out, "  {}", error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a mutant that no fixture catches (or record a test case for any mutant)
+fn print_mutant_result(
+    out: &mut Output,
+    schema: &Path,
+    mutant: &Mutant,
+    killed: bool,
+) -> Result<(), Error> {
+    if out.is_structured() {
+        let failures = if killed {
+            vec![]
+        } else {
+            vec![TestFailure {
+                keyword: mutant.mutation.to_string(),
+                message: format!("mutant survives: {}", mutant),
+            }]
+        };
+
+        if !killed {
+            out.report.finding(serde_json::json!({
+                "schema": schema.display().to_string(),
+                "location": mutant.schema_pointer(),
+                "mutation": mutant.mutation.to_string(),
+            }));
+        }
+
+        out.report
+            .test_case(format!("{}{}", schema.display(), mutant), failures);
+    } else if !killed {
+        writeln!(
+            out,
+            "{}{} isn't caught by any fixture",
+            schema.display(),
+            mutant
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,566 @@
+use super::{Context, Output};
+use crate::files::{expand_instance_paths, is_standard_stream, schema_files};
+use crate::report::TestFailure;
+use crate::{ndjson, DraftVersion, Error};
+use json_schema_tools::schema::{meta_validate, Draft};
+use json_schema_tools::validate::{
+    self, Annotation, Redaction, Suggestion, TargetKind, TighteningOptions, ValidationError,
+    Validator,
+};
+use serde_json::Value;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// The keyword that marks values to redact if none are configured or given
+const DEFAULT_REDACTION_MARKER: &str = "writeOnly";
+
+#[derive(Debug, clap::Args)]
+pub struct Validate {
+    /// Main schema path (or `-` for stdin)
+    #[clap(short, long)]
+    schema: PathBuf,
+    /// Referenced schema paths to compose with the main schema
+    #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
+    referenced: Vec<PathBuf>,
+    /// Instance document paths (or `-` for stdin)
+    #[clap(required = true)]
+    instances: Vec<PathBuf>,
+    /// Read each instance file as newline-delimited JSON records, reporting only failures
+    #[clap(long)]
+    ndjson: bool,
+    /// Number of threads to validate records on (by default, the available parallelism)
+    #[clap(short, long, alias = "threads", requires = "ndjson")]
+    jobs: Option<usize>,
+    /// Print a standard output unit for each instance (with keyword and instance locations)
+    #[clap(long, value_enum, conflicts_with = "ndjson")]
+    output_unit: Option<OutputUnit>,
+    /// Also print the annotations (`default`, `deprecated`, `readOnly`, `format`, and `title`)
+    /// that apply to each location in each instance
+    #[clap(long, conflicts_with_all = ["ndjson", "output_unit"])]
+    annotations: bool,
+    /// Check each instance as it's read, without loading it into memory (only structural
+    /// keywords are checked for objects and arrays)
+    #[clap(long, conflicts_with_all = ["ndjson", "output_unit", "annotations"])]
+    stream: bool,
+}
+
+impl Validate {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            referenced,
+            instances,
+            ndjson,
+            jobs,
+            output_unit,
+            annotations,
+            stream,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let validator = config.validator(inputs.read_composed(&schema, &referenced)?)?;
+
+        for path in validator.ignored_keywords() {
+            let pointer = format!("/{}", path.join("/"));
+            let keyword = path.last().map_or("", String::as_str);
+            let text = format!("{}: {} isn't supported, so it's ignored", pointer, keyword);
+
+            out.warning(
+                serde_json::json!({ "path": pointer, "keyword": keyword }),
+                text,
+            );
+        }
+
+        if let Some(output_unit) = output_unit {
+            for path in instances {
+                let unit = validator.output(&inputs.read_instance(&path)?, output_unit.into());
+
+                if unit["valid"] == false {
+                    out.report.issues();
+                }
+
+                let text = serde_json::to_string_pretty(&unit)?;
+                out.finding(serde_json::json!({ "path": path, "output": unit }), text)?;
+            }
+        } else if stream {
+            for path in instances {
+                let errors = if is_standard_stream(&path) {
+                    validator.validate_reader(std::io::stdin().lock())?
+                } else {
+                    validator.validate_reader(std::fs::File::open(&path)?)?
+                };
+
+                print_validation_errors(&mut out, &path, &errors)?;
+            }
+        } else if ndjson {
+            let jobs = jobs.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, |threads| threads.get())
+            });
+
+            for path in instances {
+                validate_records(&mut out, &path, &validator, jobs)?;
+            }
+        } else {
+            for path in instances {
+                let instance = inputs.read_instance(&path)?;
+                let errors = validator.validate(&instance);
+
+                print_validation_errors(&mut out, &path, &errors)?;
+
+                if annotations {
+                    print_annotations(&mut out, &path, &validator.annotations(&instance))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct MetaValidate {
+    /// Schema paths (directories are searched for JSON and YAML files), if not the configured
+    /// root
+    paths: Vec<PathBuf>,
+    /// Meta-schema to use, instead of the one for the draft each schema declares
+    #[clap(long, value_enum)]
+    draft: Option<DraftVersion>,
+}
+
+impl MetaValidate {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self { paths, draft } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+
+        for path in schema_files(paths, config)? {
+            let errors = meta_validate(&inputs.read_json(&path)?, draft.map(Draft::from));
+
+            print_validation_errors(&mut out, &path, &errors)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Coverage {
+    /// Main schema path (or `-` for stdin)
+    #[clap(short, long)]
+    schema: PathBuf,
+    /// Referenced schema paths to compose with the main schema
+    #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
+    referenced: Vec<PathBuf>,
+    /// Instance document paths (directories are searched for JSON, TOML, and YAML files)
+    #[clap(required = true)]
+    instances: Vec<PathBuf>,
+}
+
+impl Coverage {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            referenced,
+            instances,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let validator = config.validator(inputs.read_composed(&schema, &referenced)?)?;
+        let mut coverage = validator.coverage();
+
+        for path in expand_instance_paths(instances, config)? {
+            let instance = inputs.read_instance(&path)?;
+            let errors = validator.validate(&instance);
+
+            if !errors.is_empty() {
+                print_validation_errors(&mut out, &path, &errors)?;
+            }
+
+            validator.cover(&instance, &mut coverage);
+        }
+
+        let total = coverage.targets().len();
+        let exercised = coverage.exercised_count();
+
+        for target in coverage.unexercised() {
+            let kind = match target.kind {
+                TargetKind::Property => "property",
+                TargetKind::EnumMember => "enum member",
+                TargetKind::Branch => "branch",
+            };
+
+            out.finding(
+                serde_json::json!({
+                    "kind": kind,
+                    "pointer": target.pointer,
+                }),
+                format!("never exercised: {} {}", kind, target.pointer),
+            )?;
+        }
+
+        out.summary(format!(
+            "{}/{} exercised ({:.1}%)",
+            exercised,
+            total,
+            if total == 0 {
+                100.0
+            } else {
+                exercised as f64 * 100.0 / total as f64
+            }
+        ))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Tighten {
+    /// Main schema path
+    #[clap(short, long)]
+    schema: PathBuf,
+    /// Referenced schema paths to compose with the main schema
+    #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
+    referenced: Vec<PathBuf>,
+    /// Instance document paths (directories are searched for JSON, TOML, and YAML files)
+    #[clap(required = true)]
+    instances: Vec<PathBuf>,
+    /// The most distinct values to suggest as an enum
+    #[clap(long, default_value_t = 10)]
+    max_enum_values: usize,
+    /// The fewest values a subschema must describe before anything is suggested for it
+    #[clap(long, default_value_t = 10)]
+    min_count: usize,
+    /// Print the suggestions as a JSON Patch document for the schema
+    #[clap(long)]
+    patch: bool,
+}
+
+impl Tighten {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            referenced,
+            instances,
+            max_enum_values,
+            min_count,
+            patch,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let validator = config.validator(inputs.read_composed(&schema, &referenced)?)?;
+        let mut observations = validator.observations(TighteningOptions {
+            max_enum_values,
+            min_count,
+        });
+
+        for path in expand_instance_paths(instances, config)? {
+            let instance = inputs.read_instance(&path)?;
+            let errors = validator.validate(&instance);
+
+            // Invalid instances aren't evidence of what the schema should accept.
+            if errors.is_empty() {
+                validator.observe(&instance, &mut observations);
+            } else {
+                print_validation_errors(&mut out, &path, &errors)?;
+            }
+        }
+
+        let suggestions = validator.suggestions(&observations);
+
+        if patch {
+            let operations = suggestions.iter().map(Suggestion::patch).collect();
+
+            writeln!(out, "{:#}", Value::Array(operations))?;
+        } else {
+            for suggestion in &suggestions {
+                out.finding(
+                    serde_json::json!({
+                        "pointer": suggestion.pointer,
+                        "keyword": suggestion.tightening.keyword(),
+                        "value": suggestion.tightening.value(),
+                        "count": suggestion.count,
+                        "message": suggestion.to_string(),
+                    }),
+                    suggestion,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Redact {
+    /// Main schema path
+    #[clap(short, long)]
+    schema: PathBuf,
+    /// Referenced schema paths to compose with the main schema
+    #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
+    referenced: Vec<PathBuf>,
+    /// Instance document path (or `-` for stdin)
+    instance: PathBuf,
+    /// Keywords that mark values to redact
+    #[clap(short, long = "marker", value_delimiter = ',')]
+    markers: Vec<String>,
+    /// Replace each value with a digest of it, so that equal values stay equal
+    #[clap(long, conflicts_with = "replacement")]
+    hash: bool,
+    /// JSON value to replace each value with
+    #[clap(long, default_value = "\"REDACTED\"")]
+    replacement: String,
+    /// Print without whitespace
+    #[clap(long)]
+    compact: bool,
+}
+
+impl Redact {
+    pub fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        let Self {
+            schema,
+            referenced,
+            instance,
+            markers,
+            hash,
+            replacement,
+            compact,
+        } = self;
+        let Context {
+            config,
+            inputs,
+            mut out,
+            ..
+        } = ctx;
+        let validator = config.validator(inputs.read_composed(&schema, &referenced)?)?;
+        let mut markers = config
+            .redact
+            .markers
+            .iter()
+            .chain(&markers)
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        if markers.is_empty() {
+            markers.push(DEFAULT_REDACTION_MARKER);
+        }
+
+        let redaction = if hash {
+            Redaction::Hash
+        } else {
+            Redaction::Replace(serde_json::from_str(&replacement)?)
+        };
+        let mut value = inputs.read_instance(&instance)?;
+
+        for pointer in validator.redact(&mut value, &markers, &redaction) {
+            log::info!("Redacted {}", pointer);
+        }
+
+        if compact {
+            writeln!(out, "{}", value)?;
+        } else {
+            writeln!(out, "{:#}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Print the result of validating a file, recording any errors as issues
+pub fn print_validation_errors(
+    out: &mut Output,
+    path: &Path,
+    errors: &[ValidationError],
+) -> Result<(), Error> {
+    if !errors.is_empty() {
+        out.report.issues();
+    }
+
+    if out.is_structured() {
+        for error in errors {
+            out.report.finding(serde_json::json!({
+                "file": path.display().to_string(),
+                "instance": error.instance_pointer(),
+                "schema": error.schema_pointer(),
+                "message": error.failure.to_string(),
+            }));
+        }
+
+        out.report.test_case(
+            path.display().to_string(),
+            errors
+                .iter()
+                .map(|error| TestFailure::new(error, None))
+                .collect(),
+        );
+    } else if errors.is_empty() {
+        writeln!(out, "{}: ok", path.display())?;
+    } else {
+        writeln!(out, "{}: failed", path.display())?;
+
+        for error in errors {
+            writeln!(out, "  {}", error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Print annotations grouped by instance location
+fn print_annotations(
+    out: &mut Output,
+    path: &Path,
+    annotations: &[Annotation],
+) -> Result<(), Error> {
+    if out.is_structured() {
+        for annotation in annotations {
+            out.report.finding(serde_json::json!({
+                "file": path.display().to_string(),
+                "instance": annotation.instance_pointer(),
+                "schema": annotation.schema_pointer(),
+                "keyword": annotation.keyword,
+                "value": annotation.value,
+            }));
+        }
+
+        return Ok(());
+    }
+
+    let mut locations: Vec<(String, Vec<&Annotation>)> = vec![];
+
+    for annotation in annotations {
+        let location = annotation.instance_pointer();
+
+        match locations
+            .iter_mut()
+            .find(|(pointer, _)| *pointer == location)
+        {
+            Some((_, group)) => group.push(annotation),
+            None => locations.push((location, vec![annotation])),
+        }
+    }
+
+    for (location, group) in locations {
+        writeln!(
+            out,
+            "  {}: {}",
+            location,
+            group
+                .iter()
+                .map(|annotation| format!("{} = {}", annotation.keyword, annotation.value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Validate the records in an NDJSON file, printing failures with their line numbers and then
+/// the throughput (with progress on standard error if it's a terminal)
+fn validate_records(
+    out: &mut Output,
+    path: &Path,
+    validator: &Validator,
+    threads: usize,
+) -> Result<(), Error> {
+    let is_structured = out.is_structured();
+    let show_progress = !is_structured && std::io::stderr().is_terminal();
+    let mut failures = vec![];
+
+    let progress = ndjson::validate_records(
+        path,
+        validator,
+        threads,
+        |failure| {
+            out.report.issues();
+
+            if is_structured {
+                for error in &failure.errors {
+                    out.report.finding(serde_json::json!({
+                        "file": path.display().to_string(),
+                        "line": failure.line,
+                        "instance": error.instance_pointer(),
+                        "schema": error.schema_pointer(),
+                        "message": error.failure.to_string(),
+                    }));
+                    failures.push(TestFailure::new(error, Some(failure.line)));
+                }
+            } else {
+                writeln!(out, "{}:{}: failed", path.display(), failure.line)?;
+
+                for error in &failure.errors {
+                    writeln!(out, "  {}", error)?;
+                }
+            }
+
+            Ok(())
+        },
+        |progress| {
+            if show_progress {
+                eprint!(
+                    "\r{}: {} records, {} failed ({:.0} records/s, {:.1} MB/s)",
+                    path.display(),
+                    progress.records,
+                    progress.failed,
+                    progress.records_per_second(),
+                    progress.megabytes_per_second()
+                );
+            }
+        },
+    )?;
+
+    if show_progress {
+        eprintln!();
+    }
+
+    if is_structured {
+        out.report.test_case(path.display().to_string(), failures);
+    } else {
+        writeln!(
+            out,
+            "{}: {} records, {} failed in {:.1}s ({:.0} records/s, {:.1} MB/s)",
+            path.display(),
+            progress.records,
+            progress.failed,
+            progress.elapsed.as_secs_f64(),
+            progress.records_per_second(),
+            progress.megabytes_per_second()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// A standard validation output format
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum OutputUnit {
+    Flag,
+    Basic,
+    Detailed,
+}
+
+impl From<OutputUnit> for validate::Output {
+    fn from(value: OutputUnit) -> Self {
+        match value {
+            OutputUnit::Flag => Self::Flag,
+            OutputUnit::Basic => Self::Basic,
+            OutputUnit::Detailed => Self::Detailed,
+        }
+    }
+}
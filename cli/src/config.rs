@@ -0,0 +1,515 @@
+use super::{Error, OutputFormat};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use json_schema_tools::lint::{Severity, RULES};
+use json_schema_tools::validate::{Dialect, Options, Validator};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// The configuration file name, which is looked for in the current directory and its ancestors
+pub const CONFIG_FILE_NAME: &str = "json-schema-tools.toml";
+
+/// The configuration file written by `init --workspace`
+pub const CONFIG_TEMPLATE: &str = r#"# Searched when commands are given no paths
+root = "schemas"
+# roots = ["shared"]
+ignore = []
+
+# Linted when `lint` is given no schema
+# entry-points = ["schemas/main.json"]
+
+# Prefix for the `$id`s of schemas created with `init` (for example "https://example.com")
+# base-uri = ""
+
+[lint.rules]
+# optional-field = "allow"
+
+# Where `compose` keeps prepared referenced schemas, so that later runs only prepare the ones that
+# have changed
+# [compose]
+# cache = ".cache/compose"
+
+# Built by `compose` when it's given no schema
+# [compose.targets.main]
+# schema = "schemas/main.json"
+# referenced = ["shared/common.json"]
+# output = "dist/main.json"
+
+# Data files described by each schema, for editor settings generated by `catalog`
+[catalog]
+# "schemas/main.json" = ["data/**/*.json"]
+
+# The draft for schemas that don't declare one with `$schema` ("draft-04", "draft-06", "draft-07",
+# "2019-09", or "2020-12")
+[validate]
+# dialect = "2020-12"
+
+# Whether `validate` checks each format (built-in formats are only annotations by default, and
+# formats defined by a pattern are assertions)
+[validate.formats]
+# date-time = "assertion"
+
+# Regular expressions for in-house formats
+[validate.format-patterns]
+# ulid = "^[0-9A-HJKMNP-TV-Z]{26}$"
+
+# Keywords that mark the values `redact` replaces (`writeOnly` if there are none)
+[redact]
+# markers = ["x-pii"]
+
+# Used by `registry`, which only connects to loopback addresses (such as a local TLS tunnel), with
+# the password for basic authentication read from the named environment variable
+# [registry]
+# url = "http://localhost:8081"
+# username = ""
+# password-env = "SCHEMA_REGISTRY_PASSWORD"
+
+[output]
+indent = 2
+"#;
+
+/// The workspace manifest, which describes a multi-file schema project and provides defaults that
+/// are overridden by command-line options
+///
+/// Relative paths in the file are resolved against the directory containing it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Directory to search for schemas when a command is given no paths
+    pub root: Option<PathBuf>,
+    /// Additional directories to search along with `root`
+    pub roots: Vec<PathBuf>,
+    /// Schemas to lint when none is given
+    #[serde(rename = "entry-points")]
+    pub entry_points: Vec<PathBuf>,
+    /// Globs for files to skip when searching directories
+    pub ignore: Vec<String>,
+    /// Globs for files to include when searching directories (all JSON and YAML files if empty)
+    pub include: Vec<String>,
+    /// Prefix for the `$id`s of new schemas, which are otherwise derived from their paths
+    #[serde(rename = "base-uri")]
+    pub base_uri: Option<String>,
+    pub lint: LintConfig,
+    pub compose: ComposeConfig,
+    /// Globs for the data files described by each schema, for `catalog`
+    pub catalog: BTreeMap<PathBuf, Vec<String>>,
+    pub output: OutputConfig,
+    pub registry: RegistryConfig,
+    pub validate: ValidateConfig,
+    pub redact: RedactConfig,
+    #[serde(skip)]
+    base: PathBuf,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Severities by rule name
+    pub rules: BTreeMap<String, Severity>,
+}
+
+/// The schemas to compose when none are given on the command line
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ComposeConfig {
+    pub schema: Option<PathBuf>,
+    pub referenced: Vec<PathBuf>,
+    /// Named compositions, which are all built if there's no default `schema`
+    pub targets: BTreeMap<String, ComposeTarget>,
+    /// Directory to keep prepared referenced schemas in between runs
+    pub cache: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComposeTarget {
+    pub schema: PathBuf,
+    #[serde(default)]
+    pub referenced: Vec<PathBuf>,
+    /// File to write the composed schema to, instead of the command's output
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub format: Option<OutputFormat>,
+    pub indent: Option<usize>,
+    pub compact: Option<bool>,
+}
+
+/// How `validate` treats the `format` keyword
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidateConfig {
+    /// Whether each format is asserted, by name
+    pub formats: BTreeMap<String, FormatMode>,
+    /// Regular expressions for formats that aren't built in, by name
+    #[serde(rename = "format-patterns")]
+    pub format_patterns: BTreeMap<String, String>,
+    /// The draft for schemas that don't declare one
+    pub dialect: Option<DialectName>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub enum DialectName {
+    #[serde(rename = "draft-04")]
+    Draft04,
+    #[serde(rename = "draft-06")]
+    Draft06,
+    #[serde(rename = "draft-07")]
+    Draft07,
+    #[serde(rename = "2019-09")]
+    Draft2019_09,
+    #[serde(rename = "2020-12")]
+    Draft2020_12,
+}
+
+impl From<DialectName> for Dialect {
+    fn from(value: DialectName) -> Self {
+        match value {
+            DialectName::Draft04 => Self::Draft04,
+            DialectName::Draft06 => Self::Draft06,
+            DialectName::Draft07 => Self::Draft07,
+            DialectName::Draft2019_09 => Self::Draft2019_09,
+            DialectName::Draft2020_12 => Self::Draft2020_12,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatMode {
+    /// Values that aren't valid for the format fail validation
+    Assertion,
+    /// The format is only information about values
+    Annotation,
+}
+
+/// The values that `redact` replaces
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactConfig {
+    /// Keywords that mark subschemas whose values are redacted
+    pub markers: Vec<String>,
+}
+
+/// The Confluent Schema Registry used by `registry`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// Base URL of the registry's API (only `http` URLs for loopback addresses are supported)
+    pub url: Option<String>,
+    /// User name for basic authentication (an API key for Confluent Cloud)
+    pub username: Option<String>,
+    /// Environment variable containing the password for basic authentication
+    #[serde(rename = "password-env")]
+    pub password_env: Option<String>,
+}
+
+impl Config {
+    /// Load the closest configuration file, or the defaults if there isn't one
+    pub fn discover() -> Result<Self, Error> {
+        let current_dir = std::env::current_dir()?;
+
+        for dir in current_dir.ancestors() {
+            let path = dir.join(CONFIG_FILE_NAME);
+
+            if path.is_file() {
+                log::info!("Using configuration from {}", path.display());
+                return Self::load(&path);
+            }
+        }
+
+        Ok(Self {
+            base: current_dir,
+            ..Self::default()
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let mut config: Self = toml::from_str(&std::fs::read_to_string(path)?)?;
+
+        if let Some(name) = config
+            .lint
+            .rules
+            .keys()
+            .find(|name| !RULES.contains(&name.as_str()))
+        {
+            return Err(Error::UnknownLintRule(name.clone()));
+        }
+
+        let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let join_all = |paths: &[PathBuf]| paths.iter().map(|path| base.join(path)).collect();
+
+        config.root = config.root.map(|root| base.join(root));
+        config.roots = join_all(&config.roots);
+        config.entry_points = join_all(&config.entry_points);
+        config.compose.schema = config.compose.schema.map(|schema| base.join(schema));
+        config.compose.referenced = join_all(&config.compose.referenced);
+        config.compose.cache = config.compose.cache.map(|cache| base.join(cache));
+
+        config.catalog = std::mem::take(&mut config.catalog)
+            .into_iter()
+            .map(|(schema, file_match)| (base.join(schema), file_match))
+            .collect();
+
+        for target in config.compose.targets.values_mut() {
+            target.schema = base.join(&target.schema);
+            target.referenced = join_all(&target.referenced);
+            target.output = target.output.as_ref().map(|output| base.join(output));
+        }
+
+        config.base = base;
+
+        Ok(config)
+    }
+
+    /// A validator for the schema with the configured formats
+    pub fn validator(&self, schema: serde_json::Value) -> Result<Validator, Error> {
+        let options = Options {
+            dialect: self.validate.dialect.map(Dialect::from).unwrap_or_default(),
+            ..Options::default()
+        };
+        let mut validator = Validator::with_options(schema, options)?;
+
+        for (name, pattern) in &self.validate.format_patterns {
+            validator.register_format_pattern(name, pattern)?;
+        }
+
+        for (name, mode) in &self.validate.formats {
+            if !validator.set_format_assertion(name, *mode == FormatMode::Assertion) {
+                return Err(Error::UnknownFormat(name.clone()));
+            }
+        }
+
+        Ok(validator)
+    }
+
+    /// The directories to search for schemas when a command is given no paths
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.root.iter().chain(&self.roots).cloned().collect()
+    }
+
+    /// The named composition target, or else the default composition or all targets
+    pub fn compose_targets(&self, name: Option<&str>) -> Result<Vec<ComposeTarget>, Error> {
+        match (name, &self.compose.schema) {
+            (Some(name), _) => self
+                .compose
+                .targets
+                .get(name)
+                .cloned()
+                .map(|target| vec![target])
+                .ok_or_else(|| Error::UnknownComposeTarget(name.to_string())),
+            (None, Some(schema)) => Ok(vec![ComposeTarget {
+                schema: schema.clone(),
+                referenced: self.compose.referenced.clone(),
+                output: None,
+            }]),
+            (None, None) if !self.compose.targets.is_empty() => {
+                Ok(self.compose.targets.values().cloned().collect())
+            }
+            (None, None) => Err(Error::MissingPaths),
+        }
+    }
+
+    /// The `$id` for a new schema at the given path (relative to the configuration file)
+    pub fn schema_id(&self, path: &Path) -> String {
+        format!(
+            "{}/{}",
+            self.base_uri.as_deref().unwrap_or("").trim_end_matches('/'),
+            self.relative_path(&path.with_extension(""))
+        )
+    }
+
+    /// The path relative to the configuration file, with `/` separators
+    pub fn relative_path(&self, path: &Path) -> String {
+        let canonical = canonical_path(path);
+        let absolute = absolute(path);
+        let relative = canonical
+            .strip_prefix(canonical_path(&self.base))
+            .or_else(|_| absolute.strip_prefix(&self.base))
+            .unwrap_or(path);
+
+        relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(segment) => segment.to_str(),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The configured catalog globs for a schema
+    pub fn file_match(&self, schema: &Path) -> &[String] {
+        let schema = canonical_path(schema);
+
+        self.catalog
+            .iter()
+            .find(|(path, _)| canonical_path(path) == schema)
+            .map(|(_, file_match)| file_match.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Matchers for files to include and skip when searching directories
+    pub fn filters(&self) -> Result<Filters, Error> {
+        Ok(Filters {
+            include: glob_set(&self.include)?,
+            ignore: glob_set(&self.ignore)?,
+            base: self.base.clone(),
+        })
+    }
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    std::env::current_dir()
+        .map(|current_dir| current_dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The path with symbolic links and `..` resolved, if it exists
+fn canonical_path(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| absolute(path))
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>, Error> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+
+    Ok(Some(builder.build()?))
+}
+
+/// Globs are matched against paths relative to the directory containing the configuration file,
+/// and also against paths as they're given
+#[derive(Clone, Debug)]
+pub struct Filters {
+    include: Option<GlobSet>,
+    ignore: Option<GlobSet>,
+    base: PathBuf,
+}
+
+impl Filters {
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.ignore
+            .as_ref()
+            .is_some_and(|globs| self.is_match(globs, path))
+    }
+
+    /// Whether a file found in a directory should be included, before checking for ignored files
+    pub fn is_included(&self, path: &Path) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|globs| self.is_match(globs, path))
+    }
+
+    fn is_match(&self, globs: &GlobSet, path: &Path) -> bool {
+        globs.is_match(path)
+            || absolute(path)
+                .strip_prefix(&self.base)
+                .is_ok_and(|relative| globs.is_match(relative))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a configuration file to a new directory and load it
+    fn load(name: &str, contents: &str) -> (PathBuf, Result<Config, Error>) {
+        let dir = std::env::temp_dir().join(format!(
+            "json-schema-tools-config-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join(CONFIG_FILE_NAME);
+        std::fs::write(&path, contents).unwrap();
+
+        let config = Config::load(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        (dir, config)
+    }
+
+    #[test]
+    fn template() {
+        let (dir, config) = load("template", CONFIG_TEMPLATE);
+        let config = config.unwrap();
+
+        assert_eq!(config.roots(), vec![dir.join("schemas")]);
+        assert_eq!(config.output.indent, Some(2));
+        assert!(config.lint.rules.is_empty());
+        assert!(matches!(
+            config.compose_targets(None),
+            Err(Error::MissingPaths)
+        ));
+    }
+
+    #[test]
+    fn relative_paths() {
+        let (dir, config) = load(
+            "paths",
+            r#"
+roots = ["a", "b"]
+entry-points = ["a/main.json"]
+
+[compose]
+cache = ".cache"
+
+[compose.targets.main]
+schema = "a/main.json"
+referenced = ["b/common.json"]
+output = "dist/main.json"
+
+[catalog]
+"a/main.json" = ["data/*.json"]
+
+[validate]
+dialect = "draft-07"
+"#,
+        );
+        let config = config.unwrap();
+
+        assert_eq!(config.roots(), vec![dir.join("a"), dir.join("b")]);
+        assert_eq!(config.entry_points, vec![dir.join("a/main.json")]);
+        assert_eq!(config.compose.cache, Some(dir.join(".cache")));
+        assert_eq!(config.validate.dialect, Some(DialectName::Draft07));
+        assert_eq!(
+            config.catalog.get(&dir.join("a/main.json")),
+            Some(&vec!["data/*.json".to_string()])
+        );
+
+        let targets = config.compose_targets(None).unwrap();
+
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].schema, dir.join("a/main.json"));
+        assert_eq!(targets[0].referenced, vec![dir.join("b/common.json")]);
+        assert_eq!(targets[0].output, Some(dir.join("dist/main.json")));
+        assert!(matches!(
+            config.compose_targets(Some("other")),
+            Err(Error::UnknownComposeTarget(name)) if name == "other"
+        ));
+    }
+
+    #[test]
+    fn invalid() {
+        let (_, unknown_rule) = load("rule", "[lint.rules]\nno-such-rule = \"allow\"\n");
+        let (_, unknown_field) = load("field", "rot = \"schemas\"\n");
+        let (_, unknown_dialect) = load("dialect", "[validate]\ndialect = \"draft-05\"\n");
+
+        assert!(
+            matches!(unknown_rule, Err(Error::UnknownLintRule(name)) if name == "no-such-rule")
+        );
+        assert!(matches!(unknown_field, Err(Error::Config(_))));
+        assert!(matches!(unknown_dialect, Err(Error::Config(_))));
+    }
+}
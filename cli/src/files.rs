@@ -0,0 +1,610 @@
+use super::cache::ComposeCache;
+use super::config::{Config, Filters};
+use super::{timings, Error};
+use ignore::WalkBuilder;
+use json_schema_tools::{
+    compose::compose_prepared,
+    import::from_avro,
+    infer::{records, CsvInference, Inference},
+    jsonc, key_order, yaml,
+};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// The path that refers to standard input (or standard output)
+const STANDARD_STREAM_PATH: &str = "-";
+
+/// The extensions of files that are found when searching directories
+const SCHEMA_EXTENSIONS: [&str; 4] = ["json", "jsonc", "yaml", "yml"];
+
+/// The extensions of files that are found when searching directories for instances
+const INSTANCE_EXTENSIONS: [&str; 5] = ["json", "jsonc", "toml", "yaml", "yml"];
+
+/// The extension of TOML files, which are only read as instances
+const TOML_EXTENSION: &str = "toml";
+
+/// The extension of JSON files that may have comments and trailing commas
+const JSONC_EXTENSION: &str = "jsonc";
+
+/// The extensions of YAML files
+const YAML_EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+
+/// The size of the smallest JSON file that's parsed with simd-json (if it's enabled), since the
+/// setup costs more than it saves for smaller files
+const SIMD_JSON_MIN_LEN: usize = 64 * 1024;
+
+/// A format for schemas and instances
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum DataFormat {
+    Json,
+    Jsonc,
+    Yaml,
+}
+
+impl DataFormat {
+    /// The format of a file by extension, defaulting to JSON
+    pub fn by_extension<P: AsRef<Path>>(path: P) -> Self {
+        if has_extension(&path, &YAML_EXTENSIONS) {
+            Self::Yaml
+        } else if has_extension(&path, &[JSONC_EXTENSION]) {
+            Self::Jsonc
+        } else {
+            Self::Json
+        }
+    }
+
+    /// Parse a value, where only JSONC may have comments and trailing commas
+    pub fn parse(&self, contents: &str) -> Result<Value, Error> {
+        match self {
+            Self::Json => Ok(serde_json::from_str(contents)?),
+            Self::Jsonc => Ok(jsonc::from_str(contents)?),
+            Self::Yaml => Ok(yaml::from_str(contents)?),
+        }
+    }
+
+    /// Parse the contents of a file, using simd-json for large JSON files if the `simd-json`
+    /// feature is enabled
+    ///
+    /// Standard input and small files are always parsed with serde_json, as are files that
+    /// simd-json rejects, so that errors are reported the same way.
+    pub fn parse_file<P: AsRef<Path>>(&self, path: P, contents: &str) -> Result<Value, Error> {
+        if *self == Self::Json && contents.len() >= SIMD_JSON_MIN_LEN && !is_standard_stream(path) {
+            if let Some(value) = parse_simd_json(contents) {
+                return Ok(value);
+            }
+        }
+
+        self.parse(contents)
+    }
+
+    /// Write a value with two spaces of indentation and a trailing newline, keeping the key order
+    ///
+    /// JSONC is written as plain JSON (any comments aren't kept).
+    pub fn serialize(&self, value: &Value) -> String {
+        match self {
+            Self::Json | Self::Jsonc => format!("{:#}\n", value),
+            Self::Yaml => yaml::to_string(value),
+        }
+    }
+
+    /// Format a schema with canonical key order, the given number of spaces of indentation, and a
+    /// trailing newline
+    pub fn format(&self, value: &Value, indent: usize) -> String {
+        match self {
+            Self::Json | Self::Jsonc => key_order::format_with_indent(value, indent),
+            Self::Yaml => key_order::format_yaml_with_indent(value, indent),
+        }
+    }
+}
+
+/// How schemas and instances are read
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Inputs {
+    /// The format of every input, if it was given instead of being chosen by extension
+    pub format: Option<DataFormat>,
+}
+
+impl Inputs {
+    /// The format of a file, by extension (or the input format, if it was given)
+    pub fn format_of<P: AsRef<Path>>(&self, path: P) -> DataFormat {
+        self.format
+            .unwrap_or_else(|| DataFormat::by_extension(path))
+    }
+
+    /// Read a JSON or YAML file (or standard input for `-`), depending on its format
+    pub fn read_json<P: AsRef<Path>>(&self, path: P) -> Result<Value, Error> {
+        let format = self.format_of(&path);
+        let contents = read_string(&path)?;
+
+        timings::phase("parse", || format.parse_file(&path, &contents))
+    }
+
+    /// Read an instance, which can also be a TOML file (unless the input format was given)
+    pub fn read_instance<P: AsRef<Path>>(&self, path: P) -> Result<Value, Error> {
+        if self.format.is_none() && has_extension(&path, &[TOML_EXTENSION]) {
+            let contents = read_string(&path)?;
+
+            timings::phase("parse", || {
+                toml_to_json(toml::from_str(&contents).map_err(|error| {
+                    Error::InvalidTomlInstance(path.as_ref().to_path_buf(), error)
+                })?)
+            })
+        } else {
+            self.read_json(path)
+        }
+    }
+
+    /// Read an Avro schema and convert it to a JSON Schema
+    pub fn read_avro(&self, path: &Path) -> Result<Value, Error> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Ok(from_avro(&self.read_json(path)?, &name)?)
+    }
+
+    /// Read a schema and compose it with any referenced schemas (which can be Avro schemas)
+    pub fn read_composed(&self, schema: &Path, referenced: &[PathBuf]) -> Result<Value, Error> {
+        self.read_composed_cached(schema, referenced, &mut ComposeCache::default())
+    }
+
+    /// Read a schema and compose it with any referenced schemas, only preparing the referenced
+    /// schemas that aren't already in the cache
+    pub fn read_composed_cached(
+        &self,
+        schema: &Path,
+        referenced: &[PathBuf],
+        cache: &mut ComposeCache,
+    ) -> Result<Value, Error> {
+        let base = self.read_json(schema)?;
+
+        if referenced.is_empty() {
+            return Ok(base);
+        }
+
+        let referenced = referenced
+            .iter()
+            .map(|path| cache.prepare(path, self.format_of(path)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(timings::phase("compose", || {
+            compose_prepared(&base, &referenced)
+        })?)
+    }
+}
+
+fn has_extension<P: AsRef<Path>>(path: P, extensions: &[&str]) -> bool {
+    path.as_ref()
+        .extension()
+        .is_some_and(|extension| extensions.iter().any(|expected| extension == *expected))
+}
+
+pub fn is_standard_stream<P: AsRef<Path>>(path: P) -> bool {
+    path.as_ref() == Path::new(STANDARD_STREAM_PATH)
+}
+
+/// Open the destination for results, which is standard output if no path is given
+pub fn output(path: Option<PathBuf>) -> Result<Box<dyn Write>, Error> {
+    Ok(match path {
+        Some(path) if !is_standard_stream(&path) => Box::new(File::create(path)?),
+        _ => Box::new(std::io::stdout().lock()),
+    })
+}
+
+/// Collect JSON and YAML files from the given paths, or from the configured roots if there are none
+pub fn schema_files(paths: Vec<PathBuf>, config: &Config) -> Result<Vec<PathBuf>, Error> {
+    let paths = if paths.is_empty() {
+        config.roots()
+    } else {
+        paths
+    };
+
+    if paths.is_empty() {
+        return Err(Error::MissingPaths);
+    }
+
+    expand_paths(paths, config)
+}
+
+/// Replace directories with the JSON and YAML files in them, leaving other paths unchanged
+pub fn expand_paths(paths: Vec<PathBuf>, config: &Config) -> Result<Vec<PathBuf>, Error> {
+    let filters = config.filters()?;
+    let mut files = vec![];
+
+    for path in paths {
+        collect_files(path, &filters, &SCHEMA_EXTENSIONS, &mut files)?;
+    }
+
+    Ok(files)
+}
+
+/// Replace directories with the JSON, TOML, and YAML files in them, leaving other paths unchanged
+pub fn expand_instance_paths(paths: Vec<PathBuf>, config: &Config) -> Result<Vec<PathBuf>, Error> {
+    let filters = config.filters()?;
+    let mut files = vec![];
+
+    for path in paths {
+        collect_files(path, &filters, &INSTANCE_EXTENSIONS, &mut files)?;
+    }
+
+    Ok(files)
+}
+
+/// The instance files directly in a directory, sorted, without applying any filters
+pub fn instance_files_in(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = vec![];
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_file() && has_extension(&path, &INSTANCE_EXTENSIONS) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// The instance files in a directory and its subdirectories, sorted, without applying any filters
+pub fn instance_files_under(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut files = instance_files_in(dir)?;
+    let mut dirs = vec![];
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+
+    dirs.sort();
+
+    for dir in dirs {
+        files.extend(instance_files_under(&dir)?);
+    }
+
+    Ok(files)
+}
+
+/// Read a list of paths (or `-` for stdin), separated by NUL characters if there are any, and
+/// otherwise by newlines
+///
+/// Only JSON and YAML files that pass the filters are included, since lists like the output of
+/// `git diff --name-only` usually contain other files.
+pub fn read_file_list(path: &Path, config: &Config) -> Result<Vec<PathBuf>, Error> {
+    let contents = read_string(path)?;
+    let separator = if contents.contains('\0') { '\0' } else { '\n' };
+    let filters = config.filters()?;
+
+    Ok(contents
+        .split(separator)
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .filter(|path| {
+            has_extension(path, &SCHEMA_EXTENSIONS)
+                && filters.is_included(path)
+                && !filters.is_ignored(path)
+        })
+        .collect())
+}
+
+/// Collect files with the given extensions, searching directories recursively and skipping files
+/// that are filtered out or ignored by Git within them
+fn collect_files(
+    path: PathBuf,
+    filters: &Filters,
+    extensions: &[&str],
+    acc: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    if path.is_dir() {
+        let ignored = filters.clone();
+        let walk = WalkBuilder::new(&path)
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(move |entry| !ignored.is_ignored(entry.path()))
+            .build();
+
+        for entry in walk {
+            let entry = entry?;
+
+            if entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+                && has_extension(entry.path(), extensions)
+                && filters.is_included(entry.path())
+            {
+                acc.push(entry.into_path());
+            }
+        }
+    } else {
+        acc.push(path);
+    }
+
+    Ok(())
+}
+
+/// Read a file, or standard input for `-`
+pub fn read_string<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    if is_standard_stream(&path) {
+        let mut contents = String::new();
+        std::io::stdin().read_to_string(&mut contents)?;
+        Ok(contents)
+    } else {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+
+/// Read a binary file, or standard input for `-`
+pub fn read_bytes<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, Error> {
+    if is_standard_stream(&path) {
+        let mut contents = vec![];
+        std::io::stdin().read_to_end(&mut contents)?;
+        Ok(contents)
+    } else {
+        Ok(std::fs::read(path)?)
+    }
+}
+
+#[cfg(feature = "simd-json")]
+fn parse_simd_json(contents: &str) -> Option<Value> {
+    simd_json::serde::from_slice(&mut contents.as_bytes().to_vec()).ok()
+}
+
+#[cfg(not(feature = "simd-json"))]
+fn parse_simd_json(_contents: &str) -> Option<Value> {
+    None
+}
+
+/// A format for files of records to infer schemas from
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum RecordFormat {
+    Ndjson,
+    Csv,
+    Tsv,
+}
+
+impl RecordFormat {
+    /// The format of a file by extension, defaulting to NDJSON
+    pub fn by_extension<P: AsRef<Path>>(path: P) -> Self {
+        if has_extension(&path, &["csv"]) {
+            Self::Csv
+        } else if has_extension(&path, &["tsv"]) {
+            Self::Tsv
+        } else {
+            Self::Ndjson
+        }
+    }
+}
+
+/// Observe every row of a CSV or TSV file (or standard input for `-`), starting the inference with
+/// the header of the first file
+///
+/// Every other file has to have the same header.
+pub fn observe_rows(
+    path: &Path,
+    format: RecordFormat,
+    inference: &mut Option<CsvInference>,
+) -> Result<(), Error> {
+    let reader: Box<dyn BufRead> = if is_standard_stream(path) {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(path)?))
+    };
+    let delimiter = if format == RecordFormat::Tsv {
+        '\t'
+    } else {
+        ','
+    };
+    let mut rows = records(reader, delimiter)
+        .map(|row| row.map_err(|error| Error::InvalidCsv(path.to_path_buf(), error)));
+    let header = rows.next().transpose()?.unwrap_or_default();
+
+    let inference = match inference {
+        Some(inference) if inference.header().ne(header.iter().map(String::as_str)) => {
+            return Err(Error::MismatchedHeader(path.to_path_buf()));
+        }
+        Some(inference) => inference,
+        None => inference.insert(CsvInference::new(header)),
+    };
+
+    for row in rows {
+        inference.observe(&row?);
+    }
+
+    Ok(())
+}
+
+/// Observe every record in an NDJSON file (or standard input for `-`), one line at a time
+///
+/// Blank lines are skipped.
+pub fn observe_records(path: &Path, inference: &mut Inference) -> Result<(), Error> {
+    let reader: Box<dyn BufRead> = if is_standard_stream(path) {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(path)?))
+    };
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        if !line.trim().is_empty() {
+            let record = serde_json::from_str(&line)
+                .map_err(|error| Error::InvalidRecord(path.to_path_buf(), index + 1, error))?;
+
+            inference.observe(&record);
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert a TOML value, with dates and times as RFC 3339 strings
+fn toml_to_json(value: toml::Value) -> Result<Value, Error> {
+    Ok(match value {
+        toml::Value::String(value) => Value::String(value),
+        toml::Value::Integer(value) => Value::from(value),
+        toml::Value::Float(value) => serde_json::Number::from_f64(value)
+            .map(Value::Number)
+            .ok_or(Error::NonFiniteNumber(value))?,
+        toml::Value::Boolean(value) => Value::Bool(value),
+        toml::Value::Datetime(value) => Value::String(value.to_string()),
+        toml::Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(toml_to_json)
+                .collect::<Result<_, _>>()?,
+        ),
+        toml::Value::Table(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| Ok((key, toml_to_json(value)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+    })
+}
+
+/// The extension for Avro schema files
+pub const AVRO_EXTENSION: &str = "avsc";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_by_extension() {
+        assert_eq!(DataFormat::by_extension("schema.json"), DataFormat::Json);
+        assert_eq!(DataFormat::by_extension("schema.jsonc"), DataFormat::Jsonc);
+        assert_eq!(DataFormat::by_extension("schema.yaml"), DataFormat::Yaml);
+        assert_eq!(DataFormat::by_extension("dir/schema.yml"), DataFormat::Yaml);
+        // Unknown extensions, no extension, and stdin are all JSON.
+        assert_eq!(DataFormat::by_extension("schema.txt"), DataFormat::Json);
+        assert_eq!(DataFormat::by_extension("yaml"), DataFormat::Json);
+        assert_eq!(DataFormat::by_extension("-"), DataFormat::Json);
+
+        assert_eq!(RecordFormat::by_extension("rows.csv"), RecordFormat::Csv);
+        assert_eq!(RecordFormat::by_extension("rows.tsv"), RecordFormat::Tsv);
+        assert_eq!(
+            RecordFormat::by_extension("rows.ndjson"),
+            RecordFormat::Ndjson
+        );
+        assert_eq!(RecordFormat::by_extension("-"), RecordFormat::Ndjson);
+    }
+
+    #[test]
+    fn parse_and_serialize() {
+        let expected = serde_json::json!({ "type": "object", "required": ["id"] });
+
+        assert_eq!(
+            DataFormat::Json
+                .parse(r#"{"type": "object", "required": ["id"]}"#)
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            DataFormat::Yaml
+                .parse("type: object\nrequired:\n  - id\n")
+                .unwrap(),
+            expected
+        );
+
+        for format in [DataFormat::Json, DataFormat::Jsonc, DataFormat::Yaml] {
+            let serialized = format.serialize(&expected);
+
+            assert!(serialized.ends_with('\n'));
+            assert_eq!(format.parse(&serialized).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn comments_only_in_jsonc() {
+        let contents = "{\n  // The type\n  \"type\": \"object\",\n}\n";
+
+        assert_eq!(
+            DataFormat::Jsonc.parse(contents).unwrap(),
+            serde_json::json!({ "type": "object" })
+        );
+        assert!(matches!(
+            DataFormat::Json.parse(contents),
+            Err(Error::Json(_))
+        ));
+    }
+
+    #[test]
+    fn input_format() {
+        let by_extension = Inputs::default();
+        let given = Inputs {
+            format: Some(DataFormat::Yaml),
+        };
+
+        assert_eq!(by_extension.format_of("schema.jsonc"), DataFormat::Jsonc);
+        assert_eq!(by_extension.format_of("-"), DataFormat::Json);
+        assert_eq!(given.format_of("schema.json"), DataFormat::Yaml);
+        assert_eq!(given.format_of("-"), DataFormat::Yaml);
+    }
+
+    #[test]
+    fn toml_instances() {
+        let dir =
+            std::env::temp_dir().join(format!("json-schema-tools-toml-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let valid = dir.join("valid.toml");
+        let invalid = dir.join("invalid.toml");
+        std::fs::write(&valid, "name = \"a\"\ntags = [\"b\"]\n").unwrap();
+        std::fs::write(&invalid, "name = \n").unwrap();
+
+        let inputs = Inputs::default();
+        let valid_value = inputs.read_instance(&valid);
+        let invalid_value = inputs.read_instance(&invalid);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            valid_value.unwrap(),
+            serde_json::json!({ "name": "a", "tags": ["b"] })
+        );
+        assert!(matches!(
+            invalid_value,
+            Err(Error::InvalidTomlInstance(path, _)) if path == invalid
+        ));
+    }
+
+    #[test]
+    fn standard_stream() {
+        assert!(is_standard_stream("-"));
+        assert!(!is_standard_stream("./-"));
+        assert!(!is_standard_stream("schema.json"));
+    }
+
+    #[test]
+    fn large_files() {
+        // Keys that aren't sorted, to check that their order is kept.
+        let properties = (0..SIMD_JSON_MIN_LEN / 32)
+            .rev()
+            .map(|i| format!("\"p{}\": {{\"type\": \"number\", \"maximum\": {}.5}}", i, i))
+            .collect::<Vec<_>>();
+        let contents = format!("{{\"properties\": {{{}}}}}", properties.join(", "));
+        let expected = serde_json::from_str::<Value>(&contents).unwrap();
+
+        for path in ["schema.json", "-"] {
+            let value = DataFormat::Json.parse_file(path, &contents).unwrap();
+
+            assert_eq!(value, expected);
+            assert!(value["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .eq(expected["properties"].as_object().unwrap().keys()));
+        }
+
+        assert!(DataFormat::Json
+            .parse_file("schema.json", &format!("{},", contents))
+            .is_err());
+    }
+}
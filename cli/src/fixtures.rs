@@ -0,0 +1,73 @@
+use super::files::instance_files_in;
+use super::Error;
+use std::path::{Component, Path, PathBuf};
+
+/// The directory next to a schema that contains its fixtures
+const FIXTURES_DIR: &str = "tests";
+
+/// Fixtures that the schema should accept
+const VALID_DIR: &str = "valid";
+
+/// Fixtures that the schema should reject
+const INVALID_DIR: &str = "invalid";
+
+/// The instances that a schema should accept and reject
+#[derive(Debug, Default)]
+pub struct Fixtures {
+    pub valid: Vec<PathBuf>,
+    pub invalid: Vec<PathBuf>,
+}
+
+impl Fixtures {
+    /// The fixtures for a schema, from `tests/<name>/valid` and `tests/<name>/invalid` next to it
+    /// (where the name is the schema's file name without its extension), or else from
+    /// `tests/valid` and `tests/invalid`
+    pub fn find(schema: &Path) -> Result<Option<Self>, Error> {
+        let tests = schema
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(FIXTURES_DIR);
+        let named = schema.file_stem().map(|name| tests.join(name));
+
+        for dir in named.iter().chain(std::iter::once(&tests)) {
+            let (valid, invalid) = (dir.join(VALID_DIR), dir.join(INVALID_DIR));
+
+            if valid.is_dir() || invalid.is_dir() {
+                // Fixtures are listed even if they're ignored when searching for schemas.
+                let list = |dir: PathBuf| -> Result<Vec<PathBuf>, Error> {
+                    if dir.is_dir() {
+                        instance_files_in(&dir)
+                    } else {
+                        Ok(vec![])
+                    }
+                };
+
+                return Ok(Some(Self {
+                    valid: list(valid)?,
+                    invalid: list(invalid)?,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Whether the path is in a fixture directory (and so isn't a schema)
+pub fn is_fixture(path: &Path) -> bool {
+    let names = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    names.iter().enumerate().any(|(index, name)| {
+        *name == FIXTURES_DIR
+            && names[index + 1..]
+                .iter()
+                .take(2)
+                .any(|name| *name == VALID_DIR || *name == INVALID_DIR)
+    })
+}
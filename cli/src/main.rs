@@ -1,45 +1,130 @@
 use clap::Parser;
-use json_schema_tools::{compose::compose, lint::lint};
-use serde_json::Value;
+use json_schema_tools::schema::Draft;
 use simplelog::LevelFilter;
-use std::fs::File;
+use std::io::Write;
 use std::path::PathBuf;
+use std::time::Instant;
 
-fn main() -> Result<(), Error> {
+mod cache;
+mod catalog;
+mod commands;
+mod config;
+mod files;
+mod fixtures;
+mod ndjson;
+mod parallel;
+mod registry;
+mod report;
+mod timings;
+mod tree;
+mod watch;
+
+use commands::{
+    compat, compose, docs, example, generate, import, inspect, lint, project, refactor, test,
+    validate, Context, Output,
+};
+use config::Config;
+use files::{output, DataFormat, Inputs};
+use report::{Report, Status, EXIT_INTERNAL_ERROR};
+
+#[global_allocator]
+static ALLOCATOR: timings::CountingAllocator = timings::CountingAllocator;
+
+fn main() {
     let opts: Opts = Opts::parse();
-    init_logging(opts.verbose)?;
+    let destination = opts.output.clone();
+    let show_timings = opts.timings;
+    let start = Instant::now();
 
-    match opts.command {
-        Command::Lint { schema } => {
-            let value: Value = serde_json::from_reader(File::open(schema)?)?;
+    if show_timings {
+        timings::enable();
+    }
 
-            for issue in lint(&value) {
-                println!("{:?}", issue);
+    let mut report = Report {
+        format: opts.format.unwrap_or(OutputFormat::Text),
+        ..Report::default()
+    };
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run(opts, &mut report)));
+
+    let code = match result {
+        Ok(Ok(())) => report.exit_code(),
+        Ok(Err(error)) => {
+            if !report.is_structured() {
+                eprintln!("Error: {:?}", error);
             }
+
+            report.fail(&error);
+            error.exit_code()
         }
-        Command::Compose { schema, referenced } => {
-            let base: Value = serde_json::from_reader(File::open(schema)?)?;
-            let referenced = referenced
-                .into_iter()
-                .map(|path| {
-                    File::open(path).map_err(Error::from).and_then(|reader| {
-                        serde_json::from_reader::<_, Value>(reader).map_err(Error::from)
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-
-            let composed = compose(
-                &base,
-                &referenced
-                    .into_iter()
-                    .map(|value| (None, value))
-                    .collect::<Vec<_>>(),
-            )?;
-
-            println!("{}", composed)
+        // The panic message has already been printed.
+        Err(_) => {
+            report.status = Status::Error;
+            EXIT_INTERNAL_ERROR
+        }
+    };
+
+    if report.is_structured() {
+        let printed = output(destination).and_then(|mut out| {
+            let report = timings::phase("serialize", || match report.format {
+                OutputFormat::Junit => Ok(report.to_junit(start.elapsed())),
+                _ => serde_json::to_string_pretty(&report),
+            })?;
+            writeln!(out, "{}", report)?;
+            Ok(out.flush()?)
+        });
+
+        if let Err(error) = printed {
+            eprintln!("Error: {:?}", error);
+        }
+    }
+
+    if show_timings {
+        if let Err(error) = timings::print(&mut std::io::stderr().lock(), start.elapsed()) {
+            eprintln!("Error: {:?}", error);
         }
     }
 
+    std::process::exit(code);
+}
+
+fn run(opts: Opts, report: &mut Report) -> Result<(), Error> {
+    init_logging(opts.verbose)?;
+    let mut config = Config::discover()?;
+    config.include.extend(opts.include.iter().cloned());
+    config.ignore.extend(opts.exclude.iter().cloned());
+    let format = opts
+        .format
+        .or(config.output.format)
+        .unwrap_or(OutputFormat::Text);
+    report.format = format;
+
+    // With JSON or JUnit output, anything commands print is included in the report.
+    let mut captured = vec![];
+    let mut destination;
+    let out: &mut dyn Write = match format {
+        OutputFormat::Text => {
+            destination = output(opts.output.clone())?;
+            &mut destination
+        }
+        OutputFormat::Json | OutputFormat::Junit => &mut captured,
+    };
+
+    opts.command.run(Context {
+        config: &config,
+        inputs: Inputs {
+            format: opts.input_format,
+        },
+        destination: opts.output,
+        out: Output {
+            out: &mut *out,
+            report,
+        },
+    })?;
+
+    out.flush()?;
+    report.set_output(&captured);
+
     Ok(())
 }
 
@@ -49,25 +134,254 @@ struct Opts {
     /// Level of verbosity
     #[clap(short, long, global = true, action = clap::ArgAction::Count)]
     verbose: u8,
+    /// Write results to a file instead of stdout
+    #[clap(long, global = true)]
+    output: Option<PathBuf>,
+    /// Output format, where JSON is a report with the status, findings, and files written
+    /// [default: text]
+    #[clap(long, global = true, value_enum)]
+    format: Option<OutputFormat>,
+    /// Format to read schemas and instances in, instead of choosing by extension (where `.yaml`
+    /// and `.yml` files are read as YAML, and only `.jsonc` files may have comments and trailing
+    /// commas)
+    #[clap(long, global = true, value_enum)]
+    input_format: Option<DataFormat>,
+    /// Only include matching files when searching directories
+    #[clap(long, global = true)]
+    include: Vec<String>,
+    /// Skip matching files and directories when searching directories
+    #[clap(long, global = true)]
+    exclude: Vec<String>,
+    /// Print the wall time and peak memory of each phase (parsing, lint rules, etc.) to stderr
+    #[clap(long, global = true)]
+    timings: bool,
     #[clap(subcommand)]
     command: Command,
 }
 
 #[derive(Debug, Parser)]
 enum Command {
-    Lint {
-        /// Main schema path
-        #[clap(short, long)]
-        schema: PathBuf,
-    },
-    Compose {
-        /// Main schema path
-        #[clap(short, long)]
-        schema: PathBuf,
-        /// Referenced schema paths
-        #[clap(short, long, value_parser, num_args = 0.., value_delimiter = ',')]
-        referenced: Vec<PathBuf>,
-    },
+    Lint(lint::Lint),
+    Compose(compose::Compose),
+    /// Validate instance documents against a schema
+    ///
+    /// Instances can be JSON, YAML, or TOML files (by extension).
+    Validate(validate::Validate),
+    /// Validate the fixtures for each schema, failing if a valid fixture is rejected or an invalid
+    /// fixture is accepted
+    ///
+    /// Fixtures are read from `tests/<name>/valid` and `tests/<name>/invalid` next to each schema
+    /// (where the name is the schema's file name without its extension), or else from
+    /// `tests/valid` and `tests/invalid`. Schemas without fixtures are skipped.
+    Test(test::Test),
+    /// Run JSON-Schema-Test-Suite files against the validator and report the proportion of cases
+    /// that pass for each keyword
+    ///
+    /// Cases whose schemas can't be compiled (for example because they refer to remote schemas)
+    /// are reported as unsupported. Failing cases don't change the exit status.
+    Compliance(test::Compliance),
+    /// Report the properties, enum members, and `anyOf` and `oneOf` branches of a schema that no
+    /// instance exercises
+    ///
+    /// Invalid instances are reported, but still count towards coverage.
+    Coverage(validate::Coverage),
+    /// Validate instances and suggest tighter constraints that every valid instance satisfies (an
+    /// enum, numeric bounds, an integer type, a maximum length, or required properties)
+    ///
+    /// Invalid instances are reported and aren't used for suggestions.
+    Tighten(validate::Tighten),
+    /// Create a skeleton schema, or a configuration file with `--workspace`
+    Init(project::Init),
+    /// Validate schemas against the bundled meta-schemas
+    MetaValidate(validate::MetaValidate),
+    /// Format schemas with canonical key order
+    Fmt(refactor::Fmt),
+    /// Convert a schema between drafts
+    ///
+    /// Constructs that can't be converted automatically are left unchanged and reported.
+    Convert(refactor::Convert),
+    /// Rename a definition or schema and update every reference to it
+    Rename(refactor::Rename),
+    /// Replace ID prefixes in the `$id`s and `$ref`s of all schemas
+    ///
+    /// Changed files are printed, along with any IDs and references that no prefix matched.
+    Retarget(refactor::Retarget),
+    /// Merge definitions that are identical apart from annotations, within and across schemas
+    ///
+    /// Each merged definition is printed with the definition that replaced it, and references
+    /// to it are updated.
+    Dedupe(refactor::Dedupe),
+    /// Remove definitions that can't be reached from the top-level schema
+    ///
+    /// The names of the removed definitions are printed. If the schema is read from stdin, the
+    /// pruned schema is printed instead, and the names go to stderr.
+    Prune(refactor::Prune),
+    /// Move an inline subschema into a new definition and refer to it
+    ///
+    /// If the schema is read from stdin, the result is printed.
+    Extract(refactor::Extract),
+    /// Replace references to a definition with copies of it
+    ///
+    /// If the schema is read from stdin, the result is printed.
+    Inline(refactor::Inline),
+    /// Print a standalone schema for a subschema and the definitions it uses
+    Slice(inspect::Slice),
+    /// Describe a subschema in English
+    Explain(inspect::Explain),
+    /// Print an instance of a schema, with only the required properties by default
+    ///
+    /// A warning is printed if the instance isn't valid (for example because of `not`).
+    /// With `--invalid`, an array of instances that each violate one constraint is printed
+    /// instead, labeled with the constraint, and `--boundaries` prints a similar array of valid
+    /// instances at the edges of the constraints (generated instances that don't fail or pass as
+    /// intended are left out, with a warning). With `--seed`, values are random but
+    /// reproducible, and `--count` prints many of them as fixtures.
+    Example(example::Example),
+    /// Print a libFuzzer and AFL dictionary of the property names, literal values, and format
+    /// examples in a schema, and optionally write a seed corpus of valid instances
+    Fuzz(example::Fuzz),
+    /// Print the subschemas matching a JSON Pointer or JSONPath expression
+    Query(inspect::Query),
+    /// Print the location and contents of the target of a reference
+    Resolve(inspect::Resolve),
+    /// Reorder the top-level definitions of a schema
+    ///
+    /// If the schema is read from stdin, the result is printed.
+    SortDefs(refactor::SortDefs),
+    /// Print a schema without annotations that don't affect validation
+    ///
+    /// The `description`, `$comment`, and `examples` keywords are always removed.
+    Strip(refactor::Strip),
+    /// Print an instance with the values that are described by marked subschemas (for example with
+    /// `x-pii: true` or `writeOnly: true`) replaced, so that it can be shared
+    ///
+    /// The markers are the configured `redact.markers` and any given here, or `writeOnly` if there
+    /// are none.
+    Redact(validate::Redact),
+    /// Bump the version in a schema's `version` annotation and `$id`
+    ///
+    /// If a previous version is given, the changes since it determine the default level and are
+    /// added to the changelog.
+    Bump(compat::Bump),
+    /// Compare two versions of a schema structurally
+    Diff(compat::Diff),
+    /// Check whether a new version of a schema is compatible with an old version
+    Compat(compat::Compat),
+    /// Generate instances of each version of a schema and report those that the other version
+    /// rejects, as concrete counterexamples of incompatibility
+    ///
+    /// This complements `compat`, which can't reason about every keyword, but finding no
+    /// counterexamples doesn't show that the versions are compatible.
+    Differential(compat::Differential),
+    /// Generate documentation for schemas
+    Docs(docs::Docs),
+    /// Generate code from a schema and its definitions
+    ///
+    /// Only the constructs in the typed schema model are supported, and references to other files
+    /// aren't followed, so any referenced schemas should be given to compose with.
+    Generate(generate::Generate),
+    /// Convert a schema in another format to a JSON Schema
+    ///
+    /// Files in these formats can also be given directly as referenced schemas.
+    Import(import::Import),
+    /// Infer a schema from NDJSON (JSON Lines) files of instances, or from CSV or TSV files
+    ///
+    /// Records are read one at a time, so files don't have to fit in memory. For CSV and TSV,
+    /// the schema is for a row, with a property for each column in the header.
+    Infer(import::Infer),
+    /// Print a CSV data dictionary with a row for each property in a schema and its definitions
+    ///
+    /// Properties of inline objects (including array items) are included, but references aren't
+    /// followed.
+    Dictionary(inspect::Dictionary),
+    /// Export the reference graph of a collection of schemas
+    Graph(inspect::Graph),
+    /// Report metrics for one or more schemas
+    Stats(inspect::Stats),
+    /// Print VS Code settings or a SchemaStore catalog associating data files with schemas
+    Catalog(docs::Catalog),
+    /// Fetch, check, and publish schemas in the configured Confluent Schema Registry
+    ///
+    /// The registry has to be on a loopback address (such as a local registry, or a proxy or TLS
+    /// tunnel to a remote one). Schemas are composed before they're checked or published, so they
+    /// don't need references to other subjects.
+    Registry(commands::registry::Registry),
+    /// Print a shell completion script
+    Completions(project::Completions),
+}
+
+impl Command {
+    fn run(self, ctx: Context<'_>) -> Result<(), Error> {
+        match self {
+            Self::Lint(command) => command.run(ctx),
+            Self::Compose(command) => command.run(ctx),
+            Self::Validate(command) => command.run(ctx),
+            Self::Test(command) => command.run(ctx),
+            Self::Compliance(command) => command.run(ctx),
+            Self::Coverage(command) => command.run(ctx),
+            Self::Tighten(command) => command.run(ctx),
+            Self::Init(command) => command.run(ctx),
+            Self::MetaValidate(command) => command.run(ctx),
+            Self::Fmt(command) => command.run(ctx),
+            Self::Convert(command) => command.run(ctx),
+            Self::Rename(command) => command.run(ctx),
+            Self::Retarget(command) => command.run(ctx),
+            Self::Dedupe(command) => command.run(ctx),
+            Self::Prune(command) => command.run(ctx),
+            Self::Extract(command) => command.run(ctx),
+            Self::Inline(command) => command.run(ctx),
+            Self::Slice(command) => command.run(ctx),
+            Self::Explain(command) => command.run(ctx),
+            Self::Example(command) => command.run(ctx),
+            Self::Fuzz(command) => command.run(ctx),
+            Self::Query(command) => command.run(ctx),
+            Self::Resolve(command) => command.run(ctx),
+            Self::SortDefs(command) => command.run(ctx),
+            Self::Strip(command) => command.run(ctx),
+            Self::Redact(command) => command.run(ctx),
+            Self::Bump(command) => command.run(ctx),
+            Self::Diff(command) => command.run(ctx),
+            Self::Compat(command) => command.run(ctx),
+            Self::Differential(command) => command.run(ctx),
+            Self::Docs(command) => command.run(ctx),
+            Self::Generate(command) => command.run(ctx),
+            Self::Import(command) => command.run(ctx),
+            Self::Infer(command) => command.run(ctx),
+            Self::Dictionary(command) => command.run(ctx),
+            Self::Graph(command) => command.run(ctx),
+            Self::Stats(command) => command.run(ctx),
+            Self::Catalog(command) => command.run(ctx),
+            Self::Registry(command) => command.run(ctx),
+            Self::Completions(command) => command.run(ctx),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum DraftVersion {
+    #[clap(name = "draft-07")]
+    Draft07,
+    #[clap(name = "2020-12")]
+    Draft2020_12,
+}
+
+impl From<DraftVersion> for Draft {
+    fn from(value: DraftVersion) -> Self {
+        match value {
+            DraftVersion::Draft07 => Self::Draft07,
+            DraftVersion::Draft2020_12 => Self::Draft2020_12,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    /// JUnit XML, for CI test reporting (each instance `validate` checks is a test case)
+    Junit,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -76,10 +390,68 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("Logging initialization error")]
     LogInit(#[from] log::SetLoggerError),
+    #[error("YAML error")]
+    Yaml(#[from] json_schema_tools::yaml::Error),
     #[error("JSON error")]
     Json(#[from] serde_json::Error),
     #[error("Compose error")]
     Compose(#[from] json_schema_tools::compose::Error),
+    #[error("Schema error")]
+    Validate(#[from] json_schema_tools::validate::Error),
+    #[error("Documentation error")]
+    Docs(#[from] json_schema_tools::docs::Error),
+    #[error("Configuration error")]
+    Config(#[from] toml::de::Error),
+    #[error("Invalid TOML instance")]
+    InvalidTomlInstance(PathBuf, toml::de::Error),
+    #[error("Infinite or NaN number")]
+    NonFiniteNumber(f64),
+    #[error("Invalid NDJSON record")]
+    InvalidRecord(PathBuf, usize, serde_json::Error),
+    #[error("Invalid CSV or TSV file")]
+    InvalidCsv(PathBuf, json_schema_tools::infer::CsvError),
+    #[error("CSV header differs from the first file's")]
+    MismatchedHeader(PathBuf),
+    #[error("Invalid ignore pattern")]
+    Glob(#[from] globset::Error),
+    #[error("Directory search error")]
+    Walk(#[from] ignore::Error),
+    #[error("Unknown lint rule")]
+    UnknownLintRule(String),
+    #[error("Unknown format (formats must be built in or have a pattern)")]
+    UnknownFormat(String),
+    #[error("Invalid query")]
+    Query(#[from] json_schema_tools::query::Error),
+    #[error("Invalid reference")]
+    Reference(#[from] json_schema_tools::reference::Error),
+    #[error("Unresolvable reference")]
+    Resolve(#[from] json_schema_tools::resolve::Error),
+    #[error("Version error")]
+    Version(#[from] json_schema_tools::version::Error),
+    #[error("Refactoring error")]
+    Refactor(#[from] json_schema_tools::refactor::Error),
+    #[error("Import error")]
+    Import(#[from] json_schema_tools::import::Error),
+    #[error("No paths given or configured")]
+    MissingPaths,
+    #[error("Standard input can't be watched")]
+    WatchStandardInput,
+    #[error("File already exists")]
+    FileExists(PathBuf),
+    #[error("Unknown composition target")]
+    UnknownComposeTarget(String),
+    #[error("More than one composition target")]
+    AmbiguousComposeTarget,
+    #[error("No registry configured")]
+    MissingRegistry,
+    #[error("Unsupported registry URL (only http URLs for loopback addresses are supported)")]
+    UnsupportedRegistryUrl(String),
+    #[error("Environment variable not set")]
+    MissingEnvironmentVariable(String),
+    #[error("Invalid registry response")]
+    InvalidRegistryResponse,
+    #[error("Registry error")]
+    Registry(i64, String),
 }
 
 fn select_log_level_filter(verbosity: u8) -> LevelFilter {
@@ -0,0 +1,291 @@
+use super::files::is_standard_stream;
+use super::Error;
+use json_schema_tools::validate::{ValidationError, Validator};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How many records each worker validates at a time
+const BATCH_SIZE: usize = 1024;
+
+/// How often progress is reported
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A record that doesn't satisfy the schema
+pub struct RecordFailure {
+    /// The line number of the record (starting at 1)
+    pub line: usize,
+    pub errors: Vec<ValidationError>,
+}
+
+/// How far through the records validation has got
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Progress {
+    pub records: usize,
+    pub failed: usize,
+    pub bytes: u64,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    pub fn records_per_second(&self) -> f64 {
+        self.records as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn megabytes_per_second(&self) -> f64 {
+        self.bytes as f64 / 1_000_000.0 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Lines read from the input, starting at the given line number
+struct Batch {
+    index: usize,
+    first_line: usize,
+    lines: Vec<String>,
+}
+
+/// The outcome of validating a batch: its size in records and bytes, and its failures (or the
+/// line number and error for a record that isn't valid JSON)
+type BatchResult = (
+    usize,
+    u64,
+    Result<Vec<RecordFailure>, (usize, serde_json::Error)>,
+);
+
+/// Validate the records in an NDJSON file on the given number of threads
+///
+/// Failures are passed on in line order, and progress is reported periodically while the file is
+/// read. Blank lines are skipped.
+pub fn validate_records(
+    path: &Path,
+    validator: &Validator,
+    threads: usize,
+    mut on_failure: impl FnMut(RecordFailure) -> Result<(), Error>,
+    mut on_progress: impl FnMut(&Progress),
+) -> Result<Progress, Error> {
+    let reader: Box<dyn BufRead + Send> = if is_standard_stream(path) {
+        Box::new(BufReader::new(std::io::stdin()))
+    } else {
+        Box::new(BufReader::new(File::open(path)?))
+    };
+    let start = Instant::now();
+
+    std::thread::scope(|scope| {
+        let (batch_sender, batch_receiver) = mpsc::sync_channel::<Batch>(threads * 2);
+        let batch_receiver = Arc::new(Mutex::new(batch_receiver));
+        let (result_sender, result_receiver) = mpsc::channel::<(usize, BatchResult)>();
+
+        let read = scope.spawn(move || read_batches(reader, &batch_sender));
+
+        for _ in 0..threads.max(1) {
+            let batch_receiver = batch_receiver.clone();
+            let result_sender = result_sender.clone();
+
+            scope.spawn(move || loop {
+                let batch = match batch_receiver.lock() {
+                    Ok(receiver) => receiver.recv(),
+                    Err(_) => break,
+                };
+
+                match batch {
+                    Ok(batch) => {
+                        let index = batch.index;
+
+                        if result_sender
+                            .send((index, validate_batch(validator, batch)))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            });
+        }
+
+        // The workers hold the only other references, so that they stop the reader if they stop.
+        drop(batch_receiver);
+        drop(result_sender);
+
+        let mut progress = Progress::default();
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        let mut reported = Instant::now();
+
+        for (index, result) in result_receiver {
+            pending.insert(index, result);
+
+            while let Some((records, bytes, failures)) = pending.remove(&next) {
+                let failures = failures.map_err(|(line, error)| {
+                    Error::InvalidRecord(path.to_path_buf(), line, error)
+                })?;
+
+                progress.records += records;
+                progress.failed += failures.len();
+                progress.bytes += bytes;
+
+                for failure in failures {
+                    on_failure(failure)?;
+                }
+
+                next += 1;
+            }
+
+            if reported.elapsed() >= PROGRESS_INTERVAL {
+                progress.elapsed = start.elapsed();
+                on_progress(&progress);
+                reported = Instant::now();
+            }
+        }
+
+        read.join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+
+        progress.elapsed = start.elapsed();
+        Ok(progress)
+    })
+}
+
+/// Read lines into batches until the input ends or the workers stop
+fn read_batches(
+    reader: Box<dyn BufRead + Send>,
+    sender: &mpsc::SyncSender<Batch>,
+) -> Result<(), Error> {
+    let mut batch = Batch {
+        index: 0,
+        first_line: 1,
+        lines: Vec::with_capacity(BATCH_SIZE),
+    };
+
+    for (index, line) in reader.lines().enumerate() {
+        batch.lines.push(line?);
+
+        if batch.lines.len() == BATCH_SIZE {
+            let next = Batch {
+                index: batch.index + 1,
+                first_line: index + 2,
+                lines: Vec::with_capacity(BATCH_SIZE),
+            };
+
+            if sender.send(std::mem::replace(&mut batch, next)).is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    if !batch.lines.is_empty() {
+        // If the workers have stopped, the error that stopped them is reported instead.
+        let _ = sender.send(batch);
+    }
+
+    Ok(())
+}
+
+fn validate_batch(validator: &Validator, batch: Batch) -> BatchResult {
+    let mut records = 0;
+    let mut bytes = 0;
+    let mut failures = vec![];
+
+    for (offset, line) in batch.lines.iter().enumerate() {
+        let line_number = batch.first_line + offset;
+        bytes += line.len() as u64 + 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = match serde_json::from_str(line) {
+            Ok(record) => record,
+            Err(error) => return (records, bytes, Err((line_number, error))),
+        };
+        let errors = validator.validate(&record);
+        records += 1;
+
+        if !errors.is_empty() {
+            failures.push(RecordFailure {
+                line: line_number,
+                errors,
+            });
+        }
+    }
+
+    (records, bytes, Ok(failures))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate_lines(
+        name: &str,
+        lines: &[String],
+        threads: usize,
+    ) -> Result<(Progress, Vec<usize>), Error> {
+        let path = std::env::temp_dir().join(format!(
+            "json-schema-tools-ndjson-{}-{}.ndjson",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let validator = Validator::new(serde_json::json!({ "type": "integer" })).unwrap();
+        let mut failed_lines = vec![];
+        let result = validate_records(
+            &path,
+            &validator,
+            threads,
+            |failure| {
+                failed_lines.push(failure.line);
+                Ok(())
+            },
+            |_| {},
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        result.map(|progress| (progress, failed_lines))
+    }
+
+    #[test]
+    fn failures_in_line_order() {
+        // Enough records for several batches, with every hundredth invalid.
+        let lines = (1..=BATCH_SIZE * 3 + 10)
+            .map(|line| {
+                if line % 100 == 0 {
+                    "\"invalid\"".to_string()
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        let (progress, failed_lines) = validate_lines("order", &lines, 4).unwrap();
+
+        assert_eq!(progress.records, lines.len());
+        assert_eq!(progress.failed, lines.len() / 100);
+        assert_eq!(
+            failed_lines,
+            (1..=lines.len() / 100).map(|n| n * 100).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn blank_lines() {
+        let lines = ["1", "", "  ", "\"a\""].map(str::to_string);
+        let (progress, failed_lines) = validate_lines("blank", &lines, 1).unwrap();
+
+        assert_eq!(progress.records, 2);
+        assert_eq!(failed_lines, vec![4]);
+    }
+
+    #[test]
+    fn invalid_json() {
+        let lines = ["1", "2", "{"].map(str::to_string);
+
+        assert!(matches!(
+            validate_lines("invalid", &lines, 2),
+            Err(Error::InvalidRecord(_, 3, _))
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Apply a function to each item on the given number of threads, returning the results in the
+/// order of the items (whichever thread computed them)
+///
+/// Threads take the next unclaimed item as they finish, so a few large items don't hold up the
+/// rest. With one thread (or one item), everything happens on the current thread.
+pub fn map<'a, T: Sync, R: Send>(
+    items: &'a [T],
+    jobs: usize,
+    f: impl Fn(&'a T) -> R + Sync,
+) -> Vec<R> {
+    let jobs = jobs.clamp(1, items.len().max(1));
+
+    if jobs == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+
+                match items.get(index) {
+                    Some(item) => {
+                        let result = f(item);
+
+                        results.lock().unwrap_or_else(|error| error.into_inner())[index] =
+                            Some(result);
+                    }
+                    None => break,
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|error| error.into_inner())
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn results_in_item_order() {
+        let items = (0..64).collect::<Vec<u64>>();
+        let expected = items.iter().map(|item| item * 2).collect::<Vec<_>>();
+
+        for jobs in [0, 1, 4, 100] {
+            // Earlier items take longer, so they finish after later ones.
+            let results = map(&items, jobs, |item| {
+                std::thread::sleep(Duration::from_micros((64 - item) * 50));
+                item * 2
+            });
+
+            assert_eq!(results, expected);
+        }
+
+        assert!(map(&[] as &[u64], 4, |item| *item).is_empty());
+    }
+}
@@ -0,0 +1,511 @@
+use super::config::RegistryConfig;
+use super::Error;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// The media type of Schema Registry requests and responses
+const CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+
+/// Error codes for subjects and versions that don't exist
+const NOT_FOUND_CODES: [i64; 2] = [40401, 40402];
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A client for the REST API of a Confluent Schema Registry on a loopback address
+///
+/// Only plain HTTP to the local machine is supported, so other registries have to be reached
+/// through a proxy or tunnel (which is assumed to provide TLS).
+pub struct Client {
+    /// The host and port to connect to
+    address: String,
+    /// The host (and port, if it was given) for the `Host` header
+    host: String,
+    /// The path of the API, without a trailing slash
+    base_path: String,
+    authorization: Option<String>,
+}
+
+/// A version of a subject
+pub struct Registered {
+    pub id: u64,
+    pub version: u64,
+    /// `JSON`, `AVRO`, or `PROTOBUF`
+    pub schema_type: String,
+    pub schema: String,
+}
+
+/// The result of the registry's compatibility check
+pub struct Compatibility {
+    pub is_compatible: bool,
+    /// Why the schema isn't compatible
+    pub messages: Vec<String>,
+}
+
+impl Client {
+    pub fn new(config: &RegistryConfig) -> Result<Self, Error> {
+        let url = config.url.as_deref().ok_or(Error::MissingRegistry)?;
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| Error::UnsupportedRegistryUrl(url.to_string()))?;
+        let (host, base_path) = match rest.split_once('/') {
+            Some((host, path)) => (host, format!("/{}", path.trim_end_matches('/'))),
+            None => (rest, String::new()),
+        };
+        let has_port = host
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| !port.contains(']'));
+
+        if !is_loopback(host, has_port) {
+            return Err(Error::UnsupportedRegistryUrl(url.to_string()));
+        }
+
+        let authorization = match &config.username {
+            Some(username) => {
+                let password = match &config.password_env {
+                    Some(name) => std::env::var(name)
+                        .map_err(|_| Error::MissingEnvironmentVariable(name.clone()))?,
+                    None => String::new(),
+                };
+
+                Some(format!(
+                    "Basic {}",
+                    base64(format!("{}:{}", username, password).as_bytes())
+                ))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            address: if has_port {
+                host.to_string()
+            } else {
+                format!("{}:80", host)
+            },
+            host: host.to_string(),
+            base_path: base_path.trim_end_matches('/').to_string(),
+            authorization,
+        })
+    }
+
+    /// A version of a subject (a number or `latest`)
+    pub fn fetch(&self, subject: &str, version: &str) -> Result<Registered, Error> {
+        let response = self.request(
+            "GET",
+            &format!("/subjects/{}/versions/{}", encode(subject), encode(version)),
+            None,
+        )?;
+
+        let field = |name| response.get(name).ok_or(Error::InvalidRegistryResponse);
+
+        Ok(Registered {
+            id: field("id")?
+                .as_u64()
+                .ok_or(Error::InvalidRegistryResponse)?,
+            version: field("version")?
+                .as_u64()
+                .ok_or(Error::InvalidRegistryResponse)?,
+            // The registry leaves out the type of Avro schemas.
+            schema_type: response
+                .get("schemaType")
+                .and_then(Value::as_str)
+                .unwrap_or("AVRO")
+                .to_string(),
+            schema: field("schema")?
+                .as_str()
+                .ok_or(Error::InvalidRegistryResponse)?
+                .to_string(),
+        })
+    }
+
+    /// Check a JSON Schema against the latest version of a subject, using the subject's
+    /// compatibility level
+    ///
+    /// A subject with no versions is compatible with anything.
+    pub fn check(&self, subject: &str, schema: &Value) -> Result<Compatibility, Error> {
+        let path = format!(
+            "/compatibility/subjects/{}/versions/latest?verbose=true",
+            encode(subject)
+        );
+
+        match self.request("POST", &path, Some(&request_body(schema))) {
+            Ok(response) => Ok(Compatibility {
+                is_compatible: response
+                    .get("is_compatible")
+                    .and_then(Value::as_bool)
+                    .ok_or(Error::InvalidRegistryResponse)?,
+                messages: response
+                    .get("messages")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .map(|message| match message {
+                        Value::String(message) => message.clone(),
+                        message => message.to_string(),
+                    })
+                    .collect(),
+            }),
+            Err(Error::Registry(code, _)) if NOT_FOUND_CODES.contains(&code) => {
+                log::info!("{} has no versions to check against", subject);
+
+                Ok(Compatibility {
+                    is_compatible: true,
+                    messages: vec![],
+                })
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Register a JSON Schema as a new version of a subject (or find it, if it's already
+    /// registered), returning its ID
+    pub fn publish(&self, subject: &str, schema: &Value) -> Result<u64, Error> {
+        let response = self.request(
+            "POST",
+            &format!("/subjects/{}/versions", encode(subject)),
+            Some(&request_body(schema)),
+        )?;
+
+        response
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or(Error::InvalidRegistryResponse)
+    }
+
+    fn request(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value, Error> {
+        log::debug!("{} {}{}", method, self.base_path, path);
+
+        let mut stream = TcpStream::connect(&self.address)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        stream.write_all(self.request_text(method, path, body).as_bytes())?;
+
+        let (status, body) = read_response(BufReader::new(stream))?;
+        let value = serde_json::from_slice::<Value>(&body);
+
+        if (200..300).contains(&status) {
+            value.map_err(|_| Error::InvalidRegistryResponse)
+        } else {
+            // Error responses from proxies may not be JSON.
+            let value = value.unwrap_or_default();
+
+            Err(Error::Registry(
+                value
+                    .get("error_code")
+                    .and_then(Value::as_i64)
+                    .unwrap_or(status.into()),
+                value
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            ))
+        }
+    }
+
+    /// The text of an HTTP request, including the body
+    fn request_text(&self, method: &str, path: &str, body: Option<&Value>) -> String {
+        let mut request = format!(
+            "{} {}{} HTTP/1.1\r\nHost: {}\r\nAccept: {}\r\nConnection: close\r\n",
+            method, self.base_path, path, self.host, CONTENT_TYPE
+        );
+
+        if let Some(authorization) = &self.authorization {
+            request.push_str(&format!("Authorization: {}\r\n", authorization));
+        }
+
+        let body = body.map(Value::to_string).unwrap_or_default();
+
+        if !body.is_empty() {
+            request.push_str(&format!(
+                "Content-Type: {}\r\nContent-Length: {}\r\n",
+                CONTENT_TYPE,
+                body.len()
+            ));
+        }
+
+        request.push_str("\r\n");
+        request.push_str(&body);
+        request
+    }
+}
+
+fn request_body(schema: &Value) -> Value {
+    json!({ "schemaType": "JSON", "schema": schema.to_string() })
+}
+
+/// The status and body of an HTTP response, which may be chunked
+fn read_response<R: BufRead>(mut reader: R) -> Result<(u16, Vec<u8>), Error> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or(Error::InvalidRegistryResponse)?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+
+    loop {
+        line.clear();
+
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse::<usize>().ok();
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            }
+        }
+    }
+
+    let mut body = vec![];
+
+    if chunked {
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+
+            // Chunk extensions come after a semicolon.
+            let size = line.split(';').next().unwrap_or_default().trim();
+            let size =
+                usize::from_str_radix(size, 16).map_err(|_| Error::InvalidRegistryResponse)?;
+
+            if size == 0 {
+                break;
+            }
+
+            let start = body.len();
+            body.resize(start + size, 0);
+            reader.read_exact(&mut body[start..])?;
+
+            // The line break after the chunk
+            line.clear();
+            reader.read_line(&mut line)?;
+        }
+    } else if let Some(content_length) = content_length {
+        body.resize(content_length, 0);
+        reader.read_exact(&mut body)?;
+    } else {
+        reader.read_to_end(&mut body)?;
+    }
+
+    Ok((status, body))
+}
+
+/// Whether the host (which may include a port) is a loopback address
+fn is_loopback(host: &str, has_port: bool) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, _)) if has_port => name,
+        _ => host,
+    };
+
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|address| address.is_loopback())
+}
+
+/// Percent-encode a path segment
+fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                (byte as char).to_string()
+            } else {
+                format!("%{:02X}", byte)
+            }
+        })
+        .collect()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut result = String::new();
+
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (index, byte)| {
+            value | (u32::from(*byte) << (16 - 8 * index))
+        });
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (value >> (18 - 6 * index)) & 0x3f;
+                result.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(url: &str, username: Option<&str>) -> RegistryConfig {
+        RegistryConfig {
+            url: Some(url.to_string()),
+            username: username.map(str::to_string),
+            password_env: None,
+        }
+    }
+
+    #[test]
+    fn client_urls() {
+        let client = Client::new(&config("http://localhost:8081/api/", None)).unwrap();
+
+        assert_eq!(client.address, "localhost:8081");
+        assert_eq!(client.host, "localhost:8081");
+        assert_eq!(client.base_path, "/api");
+        assert!(client.authorization.is_none());
+
+        let client = Client::new(&config("http://127.0.0.1", None)).unwrap();
+
+        assert_eq!(client.address, "127.0.0.1:80");
+        assert_eq!(client.base_path, "");
+
+        for url in [
+            "https://localhost",
+            "http://registry:8081",
+            "http://localhost.example.com",
+        ] {
+            assert!(matches!(
+                Client::new(&config(url, None)),
+                Err(Error::UnsupportedRegistryUrl(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn client_credentials() {
+        assert!(matches!(
+            Client::new(&config("http://registry:8081", Some("key"))),
+            Err(Error::UnsupportedRegistryUrl(_))
+        ));
+
+        for url in [
+            "http://localhost:8081",
+            "http://127.0.0.1:8081/api",
+            "http://[::1]:8081",
+            "http://[::1]",
+        ] {
+            let client = Client::new(&config(url, Some("key"))).unwrap();
+
+            assert_eq!(client.authorization.as_deref(), Some("Basic a2V5Og=="));
+        }
+    }
+
+    #[test]
+    fn authorization_header() {
+        let client = Client::new(&config("http://localhost:8081/api", Some("key"))).unwrap();
+
+        assert_eq!(
+            client.request_text("GET", "/subjects", None),
+            "GET /api/subjects HTTP/1.1\r\nHost: localhost:8081\r\n\
+            Accept: application/vnd.schemaregistry.v1+json\r\nConnection: close\r\n\
+            Authorization: Basic a2V5Og==\r\n\r\n"
+        );
+
+        let client = Client::new(&config("http://localhost:8081", None)).unwrap();
+        let request = client.request_text("POST", "/subjects/a/versions", Some(&json!({"a": 1})));
+
+        assert!(!request.contains("Authorization"));
+        assert!(request.ends_with("Content-Length: 7\r\n\r\n{\"a\":1}"));
+    }
+
+    #[test]
+    fn response_with_content_length() {
+        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 8\r\nContent-Type: application/json\r\n\r\n{\"id\":1}extra";
+
+        assert_eq!(
+            read_response(&response[..]).unwrap(),
+            (200, b"{\"id\":1}".to_vec())
+        );
+    }
+
+    #[test]
+    fn response_without_content_length() {
+        let response = b"HTTP/1.0 404 Not Found\r\nconnection: close\r\n\r\nnot found";
+
+        assert_eq!(
+            read_response(&response[..]).unwrap(),
+            (404, b"not found".to_vec())
+        );
+    }
+
+    #[test]
+    fn chunked_response() {
+        let response = b"HTTP/1.1 200 OK\r\ntransfer-encoding: Chunked\r\n\r\n4\r\n{\"id\r\na;ext=1\r\n\":1,\"x\":2}\r\n0\r\n\r\n";
+
+        assert_eq!(
+            read_response(&response[..]).unwrap(),
+            (200, b"{\"id\":1,\"x\":2}".to_vec())
+        );
+
+        let response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+            1\r\n[\r\n0A\r\n1234567890\r\n1;a=\"b;c\"\r\n]\r\n0\r\nTrailer: x\r\n\r\n";
+
+        assert_eq!(
+            read_response(&response[..]).unwrap(),
+            (200, b"[1234567890]".to_vec())
+        );
+
+        let response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n";
+
+        assert!(matches!(
+            read_response(&response[..]),
+            Err(Error::InvalidRegistryResponse)
+        ));
+    }
+
+    #[test]
+    fn invalid_status_line() {
+        assert!(matches!(
+            read_response(&b"garbage\r\n\r\n"[..]),
+            Err(Error::InvalidRegistryResponse)
+        ));
+        assert!(matches!(
+            read_response(&b""[..]),
+            Err(Error::InvalidRegistryResponse)
+        ));
+    }
+
+    #[test]
+    fn base64_rfc_4648_vectors() {
+        for (input, expected) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(base64(input.as_bytes()), expected);
+        }
+
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+    }
+
+    #[test]
+    fn path_encoding() {
+        assert_eq!(encode("orders-value"), "orders-value");
+        assert_eq!(encode("a b/c?é"), "a%20b%2Fc%3F%C3%A9");
+    }
+}
@@ -0,0 +1,302 @@
+use super::{Error, OutputFormat};
+use json_schema_tools::validate::ValidationError;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+
+/// Exit code when a command ran but found problems (lint errors, invalid instances, etc.)
+pub const EXIT_ISSUES: i32 = 1;
+/// Exit code for invalid arguments, configuration, or input files
+pub const EXIT_INPUT_ERROR: i32 = 2;
+/// Exit code for failures that indicate a bug
+pub const EXIT_INTERNAL_ERROR: i32 = 3;
+
+/// The name of the test suite (and class of the test cases) in JUnit output
+const JUNIT_SUITE: &str = "json-schema-tools";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    #[default]
+    Ok,
+    Issues,
+    Error,
+}
+
+/// The result of a command, which is printed as a JSON envelope with `--format json`
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub status: Status,
+    pub findings: Vec<Value>,
+    /// Files written by the command
+    pub artifacts: Vec<String>,
+    /// Anything else the command printed (parsed if it's a single JSON value)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorReport>,
+    #[serde(skip)]
+    pub format: OutputFormat,
+    /// Checked inputs, for JUnit output
+    #[serde(skip)]
+    pub test_cases: Vec<TestCase>,
+}
+
+/// An input that was checked (for example an instance that was validated)
+#[derive(Debug)]
+pub struct TestCase {
+    pub name: String,
+    pub failures: Vec<TestFailure>,
+}
+
+#[derive(Debug)]
+pub struct TestFailure {
+    /// The violated keyword
+    pub keyword: String,
+    pub message: String,
+}
+
+impl TestFailure {
+    /// A validation error, with the line number of the record if it's in an NDJSON file
+    pub fn new(error: &ValidationError, line: Option<usize>) -> Self {
+        let keyword = error.schema_path.last().cloned().unwrap_or_default();
+        let message = match line {
+            Some(line) => format!("line {}: {}", line, error),
+            None => error.to_string(),
+        };
+
+        Self { keyword, message }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub message: String,
+    pub detail: String,
+}
+
+impl Report {
+    /// Whether results are collected in the report instead of being printed
+    pub fn is_structured(&self) -> bool {
+        matches!(self.format, OutputFormat::Json | OutputFormat::Junit)
+    }
+
+    /// Record that the command found problems
+    pub fn issues(&mut self) {
+        self.status = Status::Issues;
+    }
+
+    pub fn finding(&mut self, finding: Value) {
+        self.findings.push(finding);
+    }
+
+    pub fn test_case(&mut self, name: String, failures: Vec<TestFailure>) {
+        self.test_cases.push(TestCase { name, failures });
+    }
+
+    pub fn artifact<P: AsRef<Path>>(&mut self, path: P) {
+        self.artifacts.push(path.as_ref().display().to_string());
+    }
+
+    pub fn set_output(&mut self, output: &[u8]) {
+        let text = String::from_utf8_lossy(output);
+
+        if !text.trim().is_empty() {
+            self.output = Some(
+                serde_json::from_str(&text).unwrap_or_else(|_| Value::String(text.to_string())),
+            );
+        }
+    }
+
+    pub fn fail(&mut self, error: &Error) {
+        self.status = Status::Error;
+        self.error = Some(ErrorReport {
+            message: error.to_string(),
+            detail: format!("{:?}", error),
+        });
+    }
+
+    /// A JUnit XML document with a test suite containing the test cases, where any error is an
+    /// additional test case
+    pub fn to_junit(&self, elapsed: Duration) -> String {
+        let failures = self
+            .test_cases
+            .iter()
+            .filter(|test_case| !test_case.failures.is_empty())
+            .count();
+        let errors = usize::from(self.error.is_some());
+        let tests = self.test_cases.len() + errors;
+
+        let mut cases = String::new();
+
+        for test_case in &self.test_cases {
+            match test_case.failures.first() {
+                None => cases.push_str(&format!(
+                    "    <testcase name=\"{}\" classname=\"{}\"/>\n",
+                    escape(&test_case.name),
+                    JUNIT_SUITE
+                )),
+                Some(first) => {
+                    let details = test_case
+                        .failures
+                        .iter()
+                        .map(|failure| escape(&failure.message))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+
+                    cases.push_str(&format!(
+                        "    <testcase name=\"{}\" classname=\"{}\">\n      <failure message=\"{}\" type=\"{}\">{}</failure>\n    </testcase>\n",
+                        escape(&test_case.name),
+                        JUNIT_SUITE,
+                        escape(&first.message),
+                        escape(&first.keyword),
+                        details
+                    ));
+                }
+            }
+        }
+
+        if let Some(error) = &self.error {
+            cases.push_str(&format!(
+                "    <testcase name=\"error\" classname=\"{}\">\n      <error message=\"{}\">{}</error>\n    </testcase>\n",
+                JUNIT_SUITE,
+                escape(&error.message),
+                escape(&error.detail)
+            ));
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\">\n  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n{}  </testsuite>\n</testsuites>",
+            tests,
+            failures,
+            errors,
+            JUNIT_SUITE,
+            tests,
+            failures,
+            errors,
+            elapsed.as_secs_f64(),
+            cases
+        )
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self.status {
+            Status::Ok => 0,
+            Status::Issues => EXIT_ISSUES,
+            Status::Error => EXIT_INTERNAL_ERROR,
+        }
+    }
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::LogInit(_) => EXIT_INTERNAL_ERROR,
+            _ => EXIT_INPUT_ERROR,
+        }
+    }
+}
+
+/// Escape text for XML content or attribute values
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_envelope() {
+        let mut report = Report {
+            format: OutputFormat::Json,
+            ..Report::default()
+        };
+
+        report.finding(json!({"path": "/a"}));
+        report.artifact("out/schema.json");
+        report.set_output(b"{\"ok\": true}\n");
+        report.issues();
+
+        assert!(report.is_structured());
+        assert_eq!(report.exit_code(), EXIT_ISSUES);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "status": "issues",
+                "findings": [{"path": "/a"}],
+                "artifacts": ["out/schema.json"],
+                "output": {"ok": true}
+            })
+        );
+
+        // Output that isn't JSON is kept as text, and blank output is left out.
+        report.set_output(b"plain text\n");
+        assert_eq!(report.output, Some(json!("plain text\n")));
+
+        let mut failed = Report::default();
+        failed.set_output(b"  \n");
+        failed.fail(&Error::MissingPaths);
+
+        assert!(!failed.is_structured());
+        assert_eq!(failed.exit_code(), EXIT_INTERNAL_ERROR);
+        assert_eq!(
+            serde_json::to_value(&failed).unwrap(),
+            json!({
+                "status": "error",
+                "findings": [],
+                "artifacts": [],
+                "error": {
+                    "message": "No paths given or configured",
+                    "detail": "MissingPaths"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn junit_xml() {
+        let mut report = Report {
+            format: OutputFormat::Junit,
+            ..Report::default()
+        };
+
+        report.test_case("valid.json".to_string(), vec![]);
+        report.test_case(
+            "<invalid>.ndjson".to_string(),
+            vec![
+                TestFailure {
+                    keyword: "type".to_string(),
+                    message: "line 1: \"a\" isn't an integer".to_string(),
+                },
+                TestFailure {
+                    keyword: "minimum".to_string(),
+                    message: "line 2: 0 is less than 1 & more".to_string(),
+                },
+            ],
+        );
+        report.fail(&Error::MissingPaths);
+
+        assert_eq!(
+            report.to_junit(Duration::from_millis(1500)),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" errors="1">
+  <testsuite name="json-schema-tools" tests="3" failures="1" errors="1" time="1.500">
+    <testcase name="valid.json" classname="json-schema-tools"/>
+    <testcase name="&lt;invalid&gt;.ndjson" classname="json-schema-tools">
+      <failure message="line 1: &quot;a&quot; isn't an integer" type="type">line 1: &quot;a&quot; isn't an integer
+line 2: 0 is less than 1 &amp; more</failure>
+    </testcase>
+    <testcase name="error" classname="json-schema-tools">
+      <error message="No paths given or configured">MissingPaths</error>
+    </testcase>
+  </testsuite>
+</testsuites>"#
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use json_schema_tools::lint::Profiler;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The system allocator, keeping track of how much memory is allocated once timings are enabled
+///
+/// Until then (and in runs without `--timings`), each allocation only checks a flag, so that
+/// threads don't contend on the counters.
+pub struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PHASES: Mutex<Vec<Phase>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// The memory allocated by this thread minus what it's freed, which can go below zero when
+    /// it frees memory allocated by another thread
+    static THREAD_ALLOCATED: Cell<isize> = const { Cell::new(0) };
+    /// The most memory allocated by this thread since its current phase started
+    static THREAD_PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+fn allocated(size: usize) {
+    if ENABLED.load(Ordering::Relaxed) {
+        let current = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+
+        let _ = THREAD_ALLOCATED.try_with(|allocated| {
+            let current = allocated.get() + size as isize;
+            allocated.set(current);
+            let _ = THREAD_PEAK.try_with(|peak| peak.set(peak.get().max(current)));
+        });
+    }
+}
+
+fn deallocated(size: usize) {
+    // Memory allocated before timings were enabled was never counted.
+    if ENABLED.load(Ordering::Relaxed) {
+        let _ = ALLOCATED.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_sub(size))
+        });
+        let _ =
+            THREAD_ALLOCATED.try_with(|allocated| allocated.set(allocated.get() - size as isize));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        deallocated(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+
+        if !new_ptr.is_null() {
+            deallocated(layout.size());
+            allocated(new_size);
+        }
+
+        new_ptr
+    }
+}
+
+/// The totals for all runs of a phase
+#[derive(Debug)]
+struct Phase {
+    name: &'static str,
+    calls: usize,
+    duration: Duration,
+    /// The most memory allocated during a run, beyond what was allocated when it started
+    peak_memory: usize,
+}
+
+/// Start recording phases
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Run a phase, recording its wall time and peak memory if timings are enabled
+///
+/// The peak memory only counts what the current thread allocates, so phases running at the same
+/// time on other threads don't add to it. Phases shouldn't be nested, since the peak memory is
+/// reset for each.
+pub fn phase<T, F: FnOnce() -> T>(name: &'static str, f: F) -> T {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return f();
+    }
+
+    let start_memory = THREAD_ALLOCATED.with(Cell::get);
+    THREAD_PEAK.with(|peak| peak.set(start_memory));
+    let start = Instant::now();
+
+    let result = f();
+
+    let duration = start.elapsed();
+    let peak_memory = (THREAD_PEAK.with(Cell::get) - start_memory).max(0) as usize;
+
+    let mut phases = PHASES.lock().unwrap_or_else(|error| error.into_inner());
+
+    match phases.iter_mut().find(|phase| phase.name == name) {
+        Some(phase) => {
+            phase.calls += 1;
+            phase.duration += duration;
+            phase.peak_memory = phase.peak_memory.max(peak_memory);
+        }
+        None => phases.push(Phase {
+            name,
+            calls: 1,
+            duration,
+            peak_memory,
+        }),
+    }
+
+    result
+}
+
+/// Records the phases of library operations like linting
+pub struct Recorder;
+
+impl Profiler for Recorder {
+    fn phase<T, F: FnOnce() -> T>(&mut self, name: &'static str, f: F) -> T {
+        phase(name, f)
+    }
+}
+
+/// Print a table of the recorded phases, followed by the totals for the whole command
+pub fn print<W: Write>(out: &mut W, total: Duration) -> std::io::Result<()> {
+    let phases = PHASES.lock().unwrap_or_else(|error| error.into_inner());
+    let overall_peak = PEAK.load(Ordering::Relaxed);
+
+    writeln!(
+        out,
+        "{:<24} {:>6} {:>12} {:>12}",
+        "phase", "calls", "time", "peak memory"
+    )?;
+
+    for phase in phases.iter() {
+        writeln!(
+            out,
+            "{:<24} {:>6} {:>12} {:>12}",
+            phase.name,
+            phase.calls,
+            format_duration(phase.duration),
+            format_bytes(phase.peak_memory)
+        )?;
+    }
+
+    writeln!(
+        out,
+        "{:<24} {:>6} {:>12} {:>12}",
+        "total",
+        "",
+        format_duration(total),
+        format_bytes(overall_peak)
+    )
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn phase_peaks_are_per_thread() {
+        enable();
+
+        let (allocate_sender, allocate_receiver) = mpsc::channel::<()>();
+        let (allocated_sender, allocated_receiver) = mpsc::channel::<()>();
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                allocate_receiver.recv().unwrap();
+                let other = vec![0u8; 8 << 20];
+                allocated_sender.send(()).unwrap();
+                drop(other);
+            });
+
+            phase("test-per-thread", || {
+                let own = vec![0u8; 1 << 10];
+                allocate_sender.send(()).unwrap();
+                allocated_receiver.recv().unwrap();
+                drop(own);
+            });
+        });
+
+        let phases = PHASES.lock().unwrap();
+        let phase = phases
+            .iter()
+            .find(|phase| phase.name == "test-per-thread")
+            .unwrap();
+
+        assert_eq!(phase.calls, 1);
+        assert!(phase.peak_memory >= 1 << 10);
+        assert!(phase.peak_memory < 8 << 20);
+    }
+
+    #[test]
+    fn byte_formatting() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 30), "3.0 GiB");
+    }
+}
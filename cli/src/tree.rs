@@ -0,0 +1,103 @@
+use json_schema_tools::schema::{Change, ChangeKind};
+
+#[derive(Default)]
+struct Node<'a> {
+    changes: Vec<&'a ChangeKind>,
+    children: Vec<(&'a str, Node<'a>)>,
+}
+
+impl<'a> Node<'a> {
+    fn insert(&mut self, path: &'a [String], change: &'a ChangeKind) {
+        match path.split_first() {
+            None => self.changes.push(change),
+            Some((first, rest)) => {
+                let index = match self.children.iter().position(|(name, _)| name == first) {
+                    Some(index) => index,
+                    None => {
+                        self.children.push((first, Node::default()));
+                        self.children.len() - 1
+                    }
+                };
+
+                self.children[index].1.insert(rest, change);
+            }
+        }
+    }
+
+    fn render(&self, depth: usize, output: &mut String) {
+        let indent = "  ".repeat(depth);
+
+        for change in &self.changes {
+            output.push_str(&format!("{}{} {}\n", indent, marker(change), change));
+        }
+
+        for (name, child) in &self.children {
+            output.push_str(&format!("{}{}\n", indent, name));
+            child.render(depth + 1, output);
+        }
+    }
+}
+
+/// Render changes as an indented tree, grouped by their locations in the new schema
+pub fn render_changes(changes: &[Change]) -> String {
+    let mut root = Node::default();
+
+    for change in changes {
+        root.insert(&change.new_path, &change.kind);
+    }
+
+    let mut output = String::new();
+    root.render(0, &mut output);
+    output
+}
+
+fn marker(change: &ChangeKind) -> char {
+    match change {
+        ChangeKind::DefAdded { .. }
+        | ChangeKind::PropertyAdded { .. }
+        | ChangeKind::EnumMemberAdded { .. }
+        | ChangeKind::BranchAdded { .. } => '+',
+        ChangeKind::DefRemoved { .. }
+        | ChangeKind::PropertyRemoved { .. }
+        | ChangeKind::EnumMemberRemoved { .. }
+        | ChangeKind::BranchRemoved { .. } => '-',
+        _ => '~',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use json_schema_tools::schema::{diff, SchemaFile};
+
+    #[test]
+    fn changes_grouped_by_location() {
+        let old = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "object",
+            "properties": { "a": { "type": "string" }, "b": { "enum": ["x", "y"] } },
+            "$defs": { "old": { "type": "string" } }
+        }))
+        .unwrap();
+        let new = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "a": { "type": "integer" },
+                "b": { "enum": ["x", "z"] },
+                "c": { "type": "string" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            render_changes(&diff(&old, &new)),
+            "- definition old removed
++ property c added
+a
+  ~ type changed from string to integer
+b
+  - enum value y removed
+  + enum value z added
+"
+        );
+    }
+}
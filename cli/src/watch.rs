@@ -0,0 +1,70 @@
+use super::files::is_standard_stream;
+use super::Error;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Run a task, and then run it again whenever any of the given files (or the other files that the
+/// task's last successful run returned) are modified
+///
+/// Errors from the task are reported without stopping, since files are often invalid while
+/// they're being edited.
+pub fn watch<F: FnMut() -> Result<Vec<PathBuf>, Error>>(
+    paths: &[PathBuf],
+    mut task: F,
+) -> Result<(), Error> {
+    if paths.iter().any(is_standard_stream) {
+        return Err(Error::WatchStandardInput);
+    }
+
+    let mut watched = paths.to_vec();
+
+    loop {
+        match task() {
+            Ok(discovered) => {
+                watched = paths.to_vec();
+
+                for path in discovered {
+                    if !watched.contains(&path) {
+                        log::info!("Watching {}", path.display());
+                        watched.push(path);
+                    }
+                }
+            }
+            Err(error) => eprintln!("Error: {:?}", error),
+        }
+
+        let modified = modification_times(&watched);
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let current = modification_times(&watched);
+
+            if current != modified {
+                for (path, _) in watched
+                    .iter()
+                    .zip(current.iter().zip(&modified))
+                    .filter(|(_, (current, previous))| current != previous)
+                {
+                    eprintln!("[watch] {} changed", path.display());
+                }
+
+                break;
+            }
+        }
+    }
+}
+
+/// Files that can't be read (for example while they're being replaced) have no time
+fn modification_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
@@ -0,0 +1,1079 @@
+use super::key::Key;
+use super::reference::Reference;
+use super::schema::{
+    AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaObject, SchemaType,
+};
+use indexmap::IndexMap;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Determine whether every instance valid under the top-level schema of `a` is valid under the
+/// top-level schema of `b`
+///
+/// This check is conservative: `true` means the relationship has been proven for the supported
+/// keywords, while `false` means it either does not hold or could not be established (for example
+/// for two distinct string patterns). Local references are resolved against each file's `$defs`.
+pub fn is_subset(a: &SchemaFile, b: &SchemaFile) -> bool {
+    let empty = SchemaDef::Empty {};
+    let mut checker = SubsetChecker {
+        a: a.definitions.as_ref(),
+        b: b.definitions.as_ref(),
+        assumed: HashSet::new(),
+    };
+
+    checker.check(
+        a.schema.as_ref().unwrap_or(&empty),
+        b.schema.as_ref().unwrap_or(&empty),
+    )
+}
+
+/// Compute a schema that accepts exactly the instances accepted by both schemas
+///
+/// Returns `None` if no instance can be valid under both. Where the two schemas can't be merged
+/// structurally the result is an `allOf` of both. Annotations are not preserved, and references are
+/// not resolved.
+pub fn intersection(a: &Schema, b: &Schema) -> Option<Schema> {
+    intersect_defs(&a.schema, &b.schema).map(schema)
+}
+
+/// Compute a schema that accepts every instance accepted by either schema
+///
+/// The result may accept more than the union (for example `minimum` and `maximum` are widened to
+/// cover both ranges). Where the two schemas can't be merged structurally the result is an `anyOf`
+/// of both. Annotations are not preserved, and references are not resolved.
+pub fn union(a: &Schema, b: &Schema) -> Schema {
+    schema(unite_defs(&a.schema, &b.schema))
+}
+
+fn schema(schema: SchemaDef) -> Schema {
+    Schema {
+        metadata: Metadata::default(),
+        schema,
+    }
+}
+
+fn plain(schema: &Schema) -> Schema {
+    self::schema(schema.schema.clone())
+}
+
+type Definitions = IndexMap<Key, Schema>;
+
+struct SubsetChecker<'a> {
+    a: Option<&'a Definitions>,
+    b: Option<&'a Definitions>,
+    /// Pairs of references currently being compared, which are assumed to be related
+    assumed: HashSet<(String, String)>,
+}
+
+impl<'a> SubsetChecker<'a> {
+    fn resolve(definitions: Option<&'a Definitions>, value: &str) -> Option<&'a SchemaDef> {
+        match value.parse::<Reference>().ok()? {
+            Reference::FragmentOnly { fragment_name } => definitions?
+                .get(fragment_name.as_str())
+                .map(|schema| &schema.schema),
+            _ => None,
+        }
+    }
+
+    fn check(&mut self, a: &SchemaDef, b: &SchemaDef) -> bool {
+        match (a, b) {
+            (_, SchemaDef::Empty {}) => true,
+            (SchemaDef::Ref { value: a_ref }, SchemaDef::Ref { value: b_ref }) => {
+                let key = (a_ref.clone(), b_ref.clone());
+
+                if self.assumed.contains(&key) {
+                    true
+                } else {
+                    match (Self::resolve(self.a, a_ref), Self::resolve(self.b, b_ref)) {
+                        (Some(a), Some(b)) => {
+                            self.assumed.insert(key.clone());
+                            let result = self.check(a, b);
+                            self.assumed.remove(&key);
+                            result
+                        }
+                        _ => false,
+                    }
+                }
+            }
+            (SchemaDef::Ref { value }, _) => {
+                Self::resolve(self.a, value).is_some_and(|a| self.check(a, b))
+            }
+            (_, SchemaDef::Ref { value }) => {
+                Self::resolve(self.b, value).is_some_and(|b| self.check(a, b))
+            }
+            (SchemaDef::AnyOf { value } | SchemaDef::OneOf { value }, _) => {
+                value.iter().all(|a| self.check(&a.schema, b))
+            }
+            (SchemaDef::AllOf { value }, _) => {
+                value.iter().any(|a| self.check(&a.schema, b))
+                    || value
+                        .iter()
+                        .try_fold(SchemaDef::Empty {}, |acc, a| {
+                            intersect_defs(&acc, &a.schema)
+                        })
+                        .filter(|merged| !matches!(merged, SchemaDef::AllOf { .. }))
+                        .is_some_and(|merged| self.check(&merged, b))
+            }
+            (_, SchemaDef::AllOf { value }) => value.iter().all(|b| self.check(a, &b.schema)),
+            (_, SchemaDef::AnyOf { value }) => value.iter().any(|b| self.check(a, &b.schema)),
+            (_, SchemaDef::OneOf { value }) => value.iter().enumerate().any(|(i, b)| {
+                self.check(a, &b.schema)
+                    && value
+                        .iter()
+                        .enumerate()
+                        .all(|(j, other)| i == j || intersect_defs(a, &other.schema).is_none())
+            }),
+            (SchemaDef::Empty {}, _) => false,
+            (SchemaDef::Const { value }, _) => accepts(b, value) == Some(true),
+            (SchemaDef::Enum { value }, _) => value
+                .iter()
+                .all(|member| accepts(b, &Value::String(member.clone())) == Some(true)),
+            (SchemaDef::Type(a), SchemaDef::Type(b)) => self.check_types(a, b),
+            _ => false,
+        }
+    }
+
+    fn check_types(&mut self, a: &SchemaType, b: &SchemaType) -> bool {
+        match (a, b) {
+            (SchemaType::Null {}, SchemaType::Null {}) => true,
+            (SchemaType::Boolean {}, SchemaType::Boolean {}) => true,
+            (SchemaType::String { pattern: a }, SchemaType::String { pattern: b }) => {
+                b.is_none() || a == b
+            }
+            // Exclusive bounds on `a` only make it smaller, but on `b` they're not handled here.
+            (
+                SchemaType::Integer {
+                    minimum: a_minimum,
+                    maximum: a_maximum,
+                    ..
+                },
+                SchemaType::Integer {
+                    minimum: b_minimum,
+                    maximum: b_maximum,
+                    exclusive_minimum: None,
+                    exclusive_maximum: None,
+                },
+            ) => within(*a_minimum, *a_maximum, *b_minimum, *b_maximum),
+            (
+                SchemaType::Integer {
+                    minimum: a_minimum,
+                    maximum: a_maximum,
+                    ..
+                },
+                SchemaType::Number {
+                    minimum: b_minimum,
+                    maximum: b_maximum,
+                    exclusive_minimum: None,
+                    exclusive_maximum: None,
+                },
+            ) => within(
+                a_minimum.map(|value| value as f64),
+                a_maximum.map(|value| value as f64),
+                *b_minimum,
+                *b_maximum,
+            ),
+            (
+                SchemaType::Number {
+                    minimum: a_minimum,
+                    maximum: a_maximum,
+                    ..
+                },
+                SchemaType::Number {
+                    minimum: b_minimum,
+                    maximum: b_maximum,
+                    exclusive_minimum: None,
+                    exclusive_maximum: None,
+                },
+            ) => within(*a_minimum, *a_maximum, *b_minimum, *b_maximum),
+            (
+                SchemaType::Array {
+                    prefix_items: a_prefix_items,
+                    items: a_items,
+                    min_items: a_min_items,
+                    max_items: a_max_items,
+                },
+                SchemaType::Array {
+                    prefix_items: b_prefix_items,
+                    items: b_items,
+                    min_items: b_min_items,
+                    max_items: b_max_items,
+                },
+            ) if a_prefix_items.is_empty() && b_prefix_items.is_empty() => {
+                within(*a_min_items, *a_max_items, *b_min_items, *b_max_items)
+                    && (a_max_items == &Some(0) || self.check(&a_items.schema, &b_items.schema))
+            }
+            (SchemaType::Object(a), SchemaType::Object(b)) => self.check_objects(a, b),
+            _ => false,
+        }
+    }
+
+    fn check_objects(&mut self, a: &SchemaObject, b: &SchemaObject) -> bool {
+        let empty = SchemaDef::Empty {};
+
+        // The schema that `a` applies to a property it does not list, or `None` if it is forbidden.
+        let a_additional = match &a.additional_properties {
+            AdditionalProperties::Boolean(true) => Some(&empty),
+            AdditionalProperties::Boolean(false) => None,
+            AdditionalProperties::Schema(schema) => Some(&schema.schema),
+        };
+
+        b.required.iter().all(|name| a.required.contains(name))
+            && b.dependent_required.is_empty()
+            && b.dependent_schemas.is_empty()
+            && b.properties.iter().all(|(name, b_schema)| {
+                match a.properties.get(name).map(|a_schema| &a_schema.schema) {
+                    Some(a_schema) => self.check(a_schema, &b_schema.schema),
+                    None => {
+                        a_additional.is_none_or(|a_schema| self.check(a_schema, &b_schema.schema))
+                    }
+                }
+            })
+            && a.properties
+                .iter()
+                .filter(|(name, _)| !b.properties.contains_key(*name))
+                .all(|(_, a_schema)| match &b.additional_properties {
+                    AdditionalProperties::Boolean(true) => true,
+                    AdditionalProperties::Boolean(false) => false,
+                    AdditionalProperties::Schema(b_schema) => {
+                        self.check(&a_schema.schema, &b_schema.schema)
+                    }
+                })
+            && match (a_additional, &b.additional_properties) {
+                (None, _) => true,
+                (Some(_), AdditionalProperties::Boolean(true)) => true,
+                (Some(_), AdditionalProperties::Boolean(false)) => false,
+                (Some(a_schema), AdditionalProperties::Schema(b_schema)) => {
+                    self.check(a_schema, &b_schema.schema)
+                }
+            }
+    }
+}
+
+/// Whether the bounds of `a` are within the bounds of `b`
+fn within<T: PartialOrd>(
+    a_minimum: Option<T>,
+    a_maximum: Option<T>,
+    b_minimum: Option<T>,
+    b_maximum: Option<T>,
+) -> bool {
+    let minimum_ok = match (a_minimum, b_minimum) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(a), Some(b)) => a >= b,
+    };
+    let maximum_ok = match (a_maximum, b_maximum) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(a), Some(b)) => a <= b,
+    };
+
+    minimum_ok && maximum_ok
+}
+
+/// Whether the schema accepts the given value, or `None` if this can't be determined without
+/// resolving references
+pub(crate) fn accepts(schema: &SchemaDef, value: &Value) -> Option<bool> {
+    match schema {
+        SchemaDef::Empty {} => Some(true),
+        SchemaDef::Ref { .. } => None,
+        SchemaDef::Const { value: expected } => Some(expected == value),
+        SchemaDef::Enum { value: members } => Some(
+            value
+                .as_str()
+                .is_some_and(|value| members.iter().any(|member| member == value)),
+        ),
+        SchemaDef::OneOf { value: branches } => {
+            let mut count = 0;
+
+            for branch in branches {
+                if accepts(&branch.schema, value)? {
+                    count += 1;
+                }
+            }
+
+            Some(count == 1)
+        }
+        SchemaDef::AnyOf { value: branches } => {
+            let mut result = false;
+
+            for branch in branches {
+                result |= accepts(&branch.schema, value)?;
+            }
+
+            Some(result)
+        }
+        SchemaDef::AllOf { value: branches } => {
+            let mut result = true;
+
+            for branch in branches {
+                result &= accepts(&branch.schema, value)?;
+            }
+
+            Some(result)
+        }
+        SchemaDef::Type(schema_type) => match (schema_type, value) {
+            (SchemaType::Null {}, Value::Null) => Some(true),
+            (SchemaType::Boolean {}, Value::Bool(_)) => Some(true),
+            (SchemaType::String { pattern }, Value::String(value)) => match pattern {
+                Some(pattern) => Regex::new(pattern)
+                    .ok()
+                    .map(|pattern| pattern.is_match(value)),
+                None => Some(true),
+            },
+            (
+                SchemaType::Integer {
+                    minimum,
+                    maximum,
+                    exclusive_minimum: None,
+                    exclusive_maximum: None,
+                },
+                Value::Number(number),
+            ) => Some(number.as_i64().is_some_and(|number| {
+                minimum.is_none_or(|minimum| number >= minimum)
+                    && maximum.is_none_or(|maximum| number <= maximum)
+            })),
+            (
+                SchemaType::Number {
+                    minimum,
+                    maximum,
+                    exclusive_minimum: None,
+                    exclusive_maximum: None,
+                },
+                Value::Number(number),
+            ) => Some(number.as_f64().is_some_and(|number| {
+                minimum.is_none_or(|minimum| number >= minimum)
+                    && maximum.is_none_or(|maximum| number <= maximum)
+            })),
+            // Exclusive bounds aren't checked here.
+            (SchemaType::Integer { .. } | SchemaType::Number { .. }, Value::Number(_)) => None,
+            (
+                SchemaType::Array {
+                    prefix_items,
+                    items,
+                    min_items,
+                    max_items,
+                },
+                Value::Array(values),
+            ) if prefix_items.is_empty() => {
+                if min_items.is_some_and(|min_items| values.len() < min_items)
+                    || max_items.is_some_and(|max_items| values.len() > max_items)
+                {
+                    Some(false)
+                } else {
+                    let mut result = true;
+
+                    for value in values {
+                        result &= accepts(&items.schema, value)?;
+                    }
+
+                    Some(result)
+                }
+            }
+            (SchemaType::Array { .. }, Value::Array(_)) => None,
+            (SchemaType::Object(object), Value::Object(fields)) => {
+                if has_dependents(object) {
+                    return None;
+                }
+
+                if !object
+                    .required
+                    .iter()
+                    .all(|name| fields.contains_key(name.as_str()))
+                {
+                    return Some(false);
+                }
+
+                let mut result = true;
+
+                for (name, value) in fields {
+                    result &= match object.properties.get(name.as_str()) {
+                        Some(schema) => accepts(&schema.schema, value)?,
+                        None => match &object.additional_properties {
+                            AdditionalProperties::Boolean(allowed) => *allowed,
+                            AdditionalProperties::Schema(schema) => accepts(&schema.schema, value)?,
+                        },
+                    };
+                }
+
+                Some(result)
+            }
+            _ => Some(false),
+        },
+    }
+}
+
+fn all_of(a: &SchemaDef, b: &SchemaDef) -> SchemaDef {
+    let mut branches = vec![];
+
+    for value in [a, b] {
+        match value {
+            SchemaDef::AllOf { value } => branches.extend(value.iter().map(plain)),
+            other => branches.push(schema(other.clone())),
+        }
+    }
+
+    SchemaDef::AllOf { value: branches }
+}
+
+fn any_of(a: &SchemaDef, b: &SchemaDef) -> SchemaDef {
+    let mut branches = vec![];
+
+    for value in [a, b] {
+        match value {
+            SchemaDef::AnyOf { value } => branches.extend(value.iter().map(plain)),
+            other => branches.push(schema(other.clone())),
+        }
+    }
+
+    SchemaDef::AnyOf { value: branches }
+}
+
+fn same(a: &SchemaDef, b: &SchemaDef) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+pub(crate) fn intersect_defs(a: &SchemaDef, b: &SchemaDef) -> Option<SchemaDef> {
+    match (a, b) {
+        (SchemaDef::Empty {}, other) | (other, SchemaDef::Empty {}) => Some(other.clone()),
+        (a, b) if same(a, b) => Some(a.clone()),
+        (SchemaDef::Ref { .. }, _) | (_, SchemaDef::Ref { .. }) => Some(all_of(a, b)),
+        (SchemaDef::AllOf { value }, other) | (other, SchemaDef::AllOf { value }) => {
+            let mut result = other.clone();
+
+            for branch in value {
+                result = intersect_defs(&result, &branch.schema)?;
+            }
+
+            Some(result)
+        }
+        (SchemaDef::AnyOf { value }, other) | (other, SchemaDef::AnyOf { value }) => {
+            let branches = value
+                .iter()
+                .filter_map(|branch| intersect_defs(&branch.schema, other))
+                .map(schema)
+                .collect::<Vec<_>>();
+
+            match branches.len() {
+                0 => None,
+                1 => branches.into_iter().next().map(|branch| branch.schema),
+                _ => Some(SchemaDef::AnyOf { value: branches }),
+            }
+        }
+        (SchemaDef::OneOf { .. }, _) | (_, SchemaDef::OneOf { .. }) => Some(all_of(a, b)),
+        (SchemaDef::Const { value }, other) | (other, SchemaDef::Const { value }) => {
+            match accepts(other, value) {
+                Some(true) => Some(SchemaDef::Const {
+                    value: value.clone(),
+                }),
+                Some(false) => None,
+                None => Some(all_of(a, b)),
+            }
+        }
+        (SchemaDef::Enum { value }, other) | (other, SchemaDef::Enum { value }) => {
+            let mut members = vec![];
+
+            for member in value {
+                match accepts(other, &Value::String(member.clone())) {
+                    Some(true) => members.push(member.clone()),
+                    Some(false) => {}
+                    None => return Some(all_of(a, b)),
+                }
+            }
+
+            if members.is_empty() {
+                None
+            } else {
+                Some(SchemaDef::Enum { value: members })
+            }
+        }
+        (SchemaDef::Type(a_type), SchemaDef::Type(b_type)) => intersect_types(a_type, b_type)
+            .map(|result| result.map(SchemaDef::Type).unwrap_or_else(|| all_of(a, b))),
+    }
+}
+
+/// `None` means the intersection is empty, `Some(None)` that it can't be computed structurally
+fn intersect_types(a: &SchemaType, b: &SchemaType) -> Option<Option<SchemaType>> {
+    match (a, b) {
+        (SchemaType::Null {}, SchemaType::Null {}) => Some(Some(SchemaType::Null {})),
+        (SchemaType::Boolean {}, SchemaType::Boolean {}) => Some(Some(SchemaType::Boolean {})),
+        (SchemaType::String { pattern: a }, SchemaType::String { pattern: b }) => match (a, b) {
+            (None, pattern) | (pattern, None) => Some(Some(SchemaType::String {
+                pattern: pattern.clone(),
+            })),
+            (Some(a), Some(b)) if a == b => Some(Some(SchemaType::String {
+                pattern: Some(a.clone()),
+            })),
+            _ => Some(None),
+        },
+        (
+            SchemaType::Integer {
+                minimum: a_minimum,
+                maximum: a_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Integer {
+                minimum: b_minimum,
+                maximum: b_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        ) => {
+            let (minimum, maximum) = narrow(*a_minimum, *a_maximum, *b_minimum, *b_maximum)?;
+
+            Some(Some(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            }))
+        }
+        (
+            SchemaType::Integer {
+                minimum: i_minimum,
+                maximum: i_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Number {
+                minimum: n_minimum,
+                maximum: n_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        )
+        | (
+            SchemaType::Number {
+                minimum: n_minimum,
+                maximum: n_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Integer {
+                minimum: i_minimum,
+                maximum: i_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        ) => {
+            let (minimum, maximum) = narrow(
+                *i_minimum,
+                *i_maximum,
+                n_minimum.map(|value| value.ceil() as i64),
+                n_maximum.map(|value| value.floor() as i64),
+            )?;
+
+            Some(Some(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            }))
+        }
+        (
+            SchemaType::Number {
+                minimum: a_minimum,
+                maximum: a_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Number {
+                minimum: b_minimum,
+                maximum: b_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        ) => {
+            let (minimum, maximum) = narrow(*a_minimum, *a_maximum, *b_minimum, *b_maximum)?;
+
+            Some(Some(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            }))
+        }
+        (
+            SchemaType::Array {
+                prefix_items: a_prefix_items,
+                items: a_items,
+                min_items: a_min_items,
+                max_items: a_max_items,
+            },
+            SchemaType::Array {
+                prefix_items: b_prefix_items,
+                items: b_items,
+                min_items: b_min_items,
+                max_items: b_max_items,
+            },
+        ) if a_prefix_items.is_empty() && b_prefix_items.is_empty() => {
+            let (min_items, max_items) =
+                narrow(*a_min_items, *a_max_items, *b_min_items, *b_max_items)?;
+
+            match intersect_defs(&a_items.schema, &b_items.schema) {
+                Some(items) => Some(Some(SchemaType::Array {
+                    prefix_items: vec![],
+                    items: Box::new(schema(items)),
+                    min_items,
+                    max_items,
+                })),
+                // Only the empty array is valid.
+                None if min_items.unwrap_or_default() == 0 => Some(Some(SchemaType::Array {
+                    prefix_items: vec![],
+                    items: a_items.clone(),
+                    min_items: None,
+                    max_items: Some(0),
+                })),
+                None => None,
+            }
+        }
+        // Exclusive bounds and `prefixItems` aren't handled structurally.
+        (
+            SchemaType::Integer { .. } | SchemaType::Number { .. },
+            SchemaType::Integer { .. } | SchemaType::Number { .. },
+        )
+        | (SchemaType::Array { .. }, SchemaType::Array { .. }) => Some(None),
+        (SchemaType::Object(a), SchemaType::Object(b)) => {
+            intersect_objects(a, b).map(|result| result.map(SchemaType::Object))
+        }
+        _ => None,
+    }
+}
+
+fn intersect_objects(a: &SchemaObject, b: &SchemaObject) -> Option<Option<SchemaObject>> {
+    if has_dependents(a) || has_dependents(b) {
+        return Some(None);
+    }
+
+    let (a_allows, b_allows) = match (&a.additional_properties, &b.additional_properties) {
+        (AdditionalProperties::Boolean(a_allows), AdditionalProperties::Boolean(b_allows)) => {
+            (*a_allows, *b_allows)
+        }
+        _ => return Some(None),
+    };
+
+    let mut required = a.required.clone();
+    required.extend(
+        b.required
+            .iter()
+            .filter(|name| !a.required.contains(name))
+            .cloned(),
+    );
+
+    let mut properties = IndexMap::new();
+
+    for (name, a_schema) in &a.properties {
+        match b.properties.get(name) {
+            Some(b_schema) => match intersect_defs(&a_schema.schema, &b_schema.schema) {
+                Some(merged) => {
+                    properties.insert(name.clone(), schema(merged));
+                }
+                None if required.contains(name) => return None,
+                // The property would have to be absent, which we can't express here.
+                None => return Some(None),
+            },
+            None if b_allows => {
+                properties.insert(name.clone(), plain(a_schema));
+            }
+            None if required.contains(name) => return None,
+            // Dropping the property forbids it, since the result doesn't allow additional properties.
+            None => {}
+        }
+    }
+
+    for (name, b_schema) in &b.properties {
+        if !a.properties.contains_key(name) {
+            if a_allows {
+                properties.insert(name.clone(), plain(b_schema));
+            } else if required.contains(name) {
+                return None;
+            }
+        }
+    }
+
+    if required.iter().any(|name| !properties.contains_key(name)) && !(a_allows && b_allows) {
+        return None;
+    }
+
+    Some(Some(SchemaObject {
+        additional_properties: AdditionalProperties::Boolean(a_allows && b_allows),
+        properties,
+        required,
+        ..SchemaObject::default()
+    }))
+}
+
+fn has_dependents(object: &SchemaObject) -> bool {
+    !object.dependent_required.is_empty() || !object.dependent_schemas.is_empty()
+}
+
+/// Intersect two ranges, returning `None` if the result is empty
+fn narrow<T: PartialOrd + Copy>(
+    a_minimum: Option<T>,
+    a_maximum: Option<T>,
+    b_minimum: Option<T>,
+    b_maximum: Option<T>,
+) -> Option<(Option<T>, Option<T>)> {
+    let minimum = match (a_minimum, b_minimum) {
+        (Some(a), Some(b)) => Some(if a > b { a } else { b }),
+        (a, b) => a.or(b),
+    };
+    let maximum = match (a_maximum, b_maximum) {
+        (Some(a), Some(b)) => Some(if a < b { a } else { b }),
+        (a, b) => a.or(b),
+    };
+
+    match (minimum, maximum) {
+        (Some(minimum), Some(maximum)) if minimum > maximum => None,
+        _ => Some((minimum, maximum)),
+    }
+}
+
+/// Widen two ranges to cover both
+fn widen<T: PartialOrd + Copy>(
+    a_minimum: Option<T>,
+    a_maximum: Option<T>,
+    b_minimum: Option<T>,
+    b_maximum: Option<T>,
+) -> (Option<T>, Option<T>) {
+    let minimum = match (a_minimum, b_minimum) {
+        (Some(a), Some(b)) => Some(if a < b { a } else { b }),
+        _ => None,
+    };
+    let maximum = match (a_maximum, b_maximum) {
+        (Some(a), Some(b)) => Some(if a > b { a } else { b }),
+        _ => None,
+    };
+
+    (minimum, maximum)
+}
+
+fn unite_defs(a: &SchemaDef, b: &SchemaDef) -> SchemaDef {
+    match (a, b) {
+        (SchemaDef::Empty {}, _) | (_, SchemaDef::Empty {}) => SchemaDef::Empty {},
+        (a, b) if same(a, b) => a.clone(),
+        (SchemaDef::Enum { value: a_members }, SchemaDef::Enum { value: b_members }) => {
+            let mut members = a_members.clone();
+            members.extend(
+                b_members
+                    .iter()
+                    .filter(|member| !a_members.contains(member))
+                    .cloned(),
+            );
+
+            SchemaDef::Enum { value: members }
+        }
+        (SchemaDef::Type(a_type), SchemaDef::Type(b_type)) => unite_types(a_type, b_type)
+            .map(SchemaDef::Type)
+            .unwrap_or_else(|| any_of(a, b)),
+        _ => any_of(a, b),
+    }
+}
+
+fn unite_types(a: &SchemaType, b: &SchemaType) -> Option<SchemaType> {
+    match (a, b) {
+        (SchemaType::Null {}, SchemaType::Null {}) => Some(SchemaType::Null {}),
+        (SchemaType::Boolean {}, SchemaType::Boolean {}) => Some(SchemaType::Boolean {}),
+        (SchemaType::String { pattern: a }, SchemaType::String { pattern: b }) => {
+            Some(SchemaType::String {
+                pattern: if a == b { a.clone() } else { None },
+            })
+        }
+        (
+            SchemaType::Integer {
+                minimum: a_minimum,
+                maximum: a_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Integer {
+                minimum: b_minimum,
+                maximum: b_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        ) => {
+            let (minimum, maximum) = widen(*a_minimum, *a_maximum, *b_minimum, *b_maximum);
+
+            Some(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            })
+        }
+        (
+            SchemaType::Integer {
+                minimum: a_minimum,
+                maximum: a_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Number {
+                minimum: b_minimum,
+                maximum: b_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        )
+        | (
+            SchemaType::Number {
+                minimum: b_minimum,
+                maximum: b_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Integer {
+                minimum: a_minimum,
+                maximum: a_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        ) => {
+            let (minimum, maximum) = widen(
+                a_minimum.map(|value| value as f64),
+                a_maximum.map(|value| value as f64),
+                *b_minimum,
+                *b_maximum,
+            );
+
+            Some(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            })
+        }
+        (
+            SchemaType::Number {
+                minimum: a_minimum,
+                maximum: a_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+            SchemaType::Number {
+                minimum: b_minimum,
+                maximum: b_maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            },
+        ) => {
+            let (minimum, maximum) = widen(*a_minimum, *a_maximum, *b_minimum, *b_maximum);
+
+            Some(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum: None,
+                exclusive_maximum: None,
+            })
+        }
+        (
+            SchemaType::Array {
+                prefix_items: a_prefix_items,
+                items: a_items,
+                min_items: a_min_items,
+                max_items: a_max_items,
+            },
+            SchemaType::Array {
+                prefix_items: b_prefix_items,
+                items: b_items,
+                min_items: b_min_items,
+                max_items: b_max_items,
+            },
+        ) if a_prefix_items.is_empty() && b_prefix_items.is_empty() => {
+            let (min_items, max_items) =
+                widen(*a_min_items, *a_max_items, *b_min_items, *b_max_items);
+
+            Some(SchemaType::Array {
+                prefix_items: vec![],
+                items: Box::new(schema(unite_defs(&a_items.schema, &b_items.schema))),
+                min_items,
+                max_items,
+            })
+        }
+        (SchemaType::Object(a), SchemaType::Object(b)) => {
+            if has_dependents(a) || has_dependents(b) {
+                return None;
+            }
+
+            let (a_allows, b_allows) = match (&a.additional_properties, &b.additional_properties) {
+                (
+                    AdditionalProperties::Boolean(a_allows),
+                    AdditionalProperties::Boolean(b_allows),
+                ) => (*a_allows, *b_allows),
+                _ => return None,
+            };
+
+            let mut properties = IndexMap::new();
+
+            for (name, a_schema) in &a.properties {
+                let merged = match b.properties.get(name) {
+                    Some(b_schema) => unite_defs(&a_schema.schema, &b_schema.schema),
+                    None if b_allows => SchemaDef::Empty {},
+                    None => a_schema.schema.clone(),
+                };
+
+                properties.insert(name.clone(), schema(merged));
+            }
+
+            for (name, b_schema) in &b.properties {
+                if !a.properties.contains_key(name) {
+                    let merged = if a_allows {
+                        SchemaDef::Empty {}
+                    } else {
+                        b_schema.schema.clone()
+                    };
+
+                    properties.insert(name.clone(), schema(merged));
+                }
+            }
+
+            Some(SchemaType::Object(SchemaObject {
+                additional_properties: AdditionalProperties::Boolean(a_allows || b_allows),
+                properties,
+                required: a
+                    .required
+                    .iter()
+                    .filter(|name| b.required.contains(name))
+                    .cloned()
+                    .collect(),
+                ..SchemaObject::default()
+            }))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema_file(value: Value) -> SchemaFile {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn schema(value: Value) -> Schema {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn subset() {
+        let response = schema_file(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "minimum": 1, "maximum": 10 },
+                "kind": { "$ref": "#/$defs/kind" }
+            },
+            "required": ["id", "kind"],
+            "$defs": {
+                "kind": { "enum": ["A", "B"] }
+            }
+        }));
+        let contract = schema_file(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "number", "minimum": 0 },
+                "kind": { "type": "string" },
+                "note": { "type": "string" }
+            },
+            "required": ["id"]
+        }));
+
+        assert!(is_subset(&response, &contract));
+        assert!(!is_subset(&contract, &response));
+    }
+
+    #[test]
+    fn subset_recursive() {
+        let list = schema_file(serde_json::json!({
+            "$ref": "#/$defs/list",
+            "$defs": {
+                "list": {
+                    "oneOf": [
+                        { "type": "null" },
+                        {
+                            "type": "object",
+                            "additionalProperties": false,
+                            "properties": {
+                                "head": { "type": "integer" },
+                                "tail": { "$ref": "#/$defs/list" }
+                            },
+                            "required": ["head", "tail"]
+                        }
+                    ]
+                }
+            }
+        }));
+
+        assert!(is_subset(&list, &list));
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let a = schema(serde_json::json!({ "type": "integer", "minimum": 0, "maximum": 10 }));
+        let b = schema(serde_json::json!({ "type": "number", "minimum": 5.5 }));
+        let c = schema(serde_json::json!({ "type": "integer", "minimum": 20 }));
+
+        assert_eq!(
+            serde_json::to_value(intersection(&a, &b).unwrap()).unwrap(),
+            serde_json::json!({ "type": "integer", "minimum": 6, "maximum": 10 })
+        );
+        assert!(intersection(&a, &c).is_none());
+        assert_eq!(
+            serde_json::to_value(union(&a, &c)).unwrap(),
+            serde_json::json!({ "type": "integer", "minimum": 0 })
+        );
+
+        let x = schema(serde_json::json!({ "enum": ["A", "B", "C"] }));
+        let y = schema(serde_json::json!({ "type": "string", "pattern": "^[AB]$" }));
+
+        assert_eq!(
+            serde_json::to_value(intersection(&x, &y).unwrap()).unwrap(),
+            serde_json::json!({ "enum": ["A", "B"] })
+        );
+        assert_eq!(
+            serde_json::to_value(union(&x, &a)).unwrap(),
+            serde_json::json!({ "anyOf": [
+                { "enum": ["A", "B", "C"] },
+                { "type": "integer", "minimum": 0, "maximum": 10 }
+            ] })
+        );
+    }
+
+    #[test]
+    fn intersection_objects() {
+        let a = schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": { "id": { "type": "integer" }, "name": { "type": "string" } },
+            "required": ["id"]
+        }));
+        let b = schema(serde_json::json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        }));
+        let c = schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": { "other": { "type": "string" } },
+            "required": ["other"]
+        }));
+
+        assert_eq!(
+            serde_json::to_value(intersection(&a, &b).unwrap()).unwrap(),
+            serde_json::json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": { "id": { "type": "integer" }, "name": { "type": "string" } },
+                "required": ["id", "name"]
+            })
+        );
+        assert!(intersection(&a, &c).is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! A harness for the [JSON-Schema-Test-Suite](https://github.com/json-schema-org/JSON-Schema-Test-Suite)
+//!
+//! Each file in the suite covers one keyword, and contains groups of instances that a schema
+//! should accept or reject.
+
+use super::validate::Validator;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// A schema and the instances it should accept or reject
+#[derive(Clone, Debug, Deserialize)]
+pub struct Group {
+    pub description: String,
+    pub schema: Value,
+    pub tests: Vec<Case>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Case {
+    pub description: String,
+    pub data: Value,
+    pub valid: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Passed,
+    /// The validator accepted an invalid instance or rejected a valid one
+    Failed,
+    /// The validator couldn't be built for the schema (for example because it refers to a remote
+    /// schema)
+    Unsupported,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passed => write!(f, "passed"),
+            Self::Failed => write!(f, "failed"),
+            Self::Unsupported => write!(f, "unsupported"),
+        }
+    }
+}
+
+/// The outcome for a case in a suite file
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CaseResult {
+    /// The name of the file (usually the keyword it covers)
+    pub keyword: String,
+    pub group: String,
+    pub description: String,
+    pub outcome: Outcome,
+}
+
+/// The number of cases with each outcome for a keyword
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Compliance {
+    pub passed: usize,
+    pub failed: usize,
+    pub unsupported: usize,
+}
+
+impl Compliance {
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.unsupported
+    }
+
+    /// The proportion of cases that passed (1 if there are none)
+    pub fn ratio(&self) -> f64 {
+        if self.total() == 0 {
+            1.0
+        } else {
+            self.passed as f64 / self.total() as f64
+        }
+    }
+
+    fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Passed => self.passed += 1,
+            Outcome::Failed => self.failed += 1,
+            Outcome::Unsupported => self.unsupported += 1,
+        }
+    }
+}
+
+/// Run the groups in a suite file
+pub fn run(keyword: &str, groups: &[Group]) -> Vec<CaseResult> {
+    let mut results = vec![];
+
+    for group in groups {
+        let validator = Validator::new(group.schema.clone()).ok();
+
+        for case in &group.tests {
+            let outcome = match &validator {
+                Some(validator) if validator.is_valid(&case.data) == case.valid => Outcome::Passed,
+                Some(_) => Outcome::Failed,
+                None => Outcome::Unsupported,
+            };
+
+            results.push(CaseResult {
+                keyword: keyword.to_string(),
+                group: group.description.clone(),
+                description: case.description.clone(),
+                outcome,
+            });
+        }
+    }
+
+    results
+}
+
+/// Count the outcomes for each keyword
+pub fn summarize(results: &[CaseResult]) -> BTreeMap<String, Compliance> {
+    let mut summary = BTreeMap::<String, Compliance>::new();
+
+    for result in results {
+        summary
+            .entry(result.keyword.clone())
+            .or_default()
+            .add(result.outcome);
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suite_file() {
+        let groups = serde_json::from_value::<Vec<Group>>(serde_json::json!([
+            {
+                "description": "minLength validation",
+                "schema": { "minLength": 2 },
+                "tests": [
+                    { "description": "longer is valid", "data": "foo", "valid": true },
+                    { "description": "too short is invalid", "data": "f", "valid": false },
+                    { "description": "ignores non-strings", "data": 1, "valid": true }
+                ]
+            },
+            {
+                "description": "remote ref",
+                "schema": { "$ref": "http://localhost:1234/integer.json" },
+                "tests": [
+                    { "description": "remote ref valid", "data": 1, "valid": true }
+                ]
+            }
+        ]))
+        .unwrap();
+
+        let results = run("minLength", &groups);
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.outcome)
+                .collect::<Vec<_>>(),
+            vec![
+                Outcome::Passed,
+                Outcome::Passed,
+                Outcome::Passed,
+                Outcome::Unsupported
+            ]
+        );
+        assert_eq!(
+            summarize(&results)["minLength"],
+            Compliance {
+                passed: 3,
+                failed: 0,
+                unsupported: 1
+            }
+        );
+    }
+}
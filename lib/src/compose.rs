@@ -1,6 +1,8 @@
-use super::{constants::*, reference::Reference};
+use super::{constants::*, reference::Reference, util::Children};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::ops::ControlFlow;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -15,6 +17,83 @@ pub enum Error {
 }
 
 pub fn compose(base: &Value, sub_schemas: &[(Option<&str>, Value)]) -> Result<Value, Error> {
+    let prepared = sub_schemas
+        .iter()
+        .map(|(prefix, sub_schema)| prepare(*prefix, sub_schema))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    compose_prepared(base, &prepared)
+}
+
+/// A referenced schema whose local references have been rewritten, with the definitions it adds
+/// to the composed schema
+///
+/// Preparing doesn't depend on the base schema, so prepared schemas can be cached and reused when
+/// only the base schema changes.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Prepared {
+    pub id: String,
+    pub prefix: Option<String>,
+    /// Definitions with their prefixed names, in order
+    pub defs: Vec<(String, Value)>,
+}
+
+/// Rewrite the local references in a referenced schema and collect the definitions it adds
+pub fn prepare(prefix: Option<&str>, sub_schema: &Value) -> Result<Prepared, Error> {
+    let id = get_id(sub_schema)?;
+
+    let (path_prefix, path_name) = match id.parse::<Reference>() {
+        Ok(Reference::PathOnly {
+            path_prefix,
+            path_name,
+        }) => Ok((path_prefix, path_name)),
+        _ => Err(Error::InvalidId(id.to_string())),
+    }?;
+
+    let mut new_sub_schema = sub_schema.clone();
+
+    modify_references(&mut new_sub_schema, &|old_reference| {
+        Ok(match old_reference {
+            Reference::FragmentOnly { fragment_name } => Some(Reference::new(
+                path_prefix.clone(),
+                path_name.clone(),
+                fragment_name.clone(),
+            )),
+            _ => None,
+        })
+    })?;
+
+    let mut defs = vec![];
+
+    if let Some(top_level_def) = get_top_level_def(&new_sub_schema) {
+        defs.push((
+            format!("{}{}", prefix.unwrap_or_default(), path_name),
+            top_level_def,
+        ));
+    }
+
+    if let Some(fields) = new_sub_schema
+        .get(DEFS_KEY)
+        .and_then(|value| value.as_object())
+    {
+        for (key, value) in fields {
+            defs.push((
+                format!("{}{}", prefix.unwrap_or_default(), key),
+                value.clone(),
+            ));
+        }
+    }
+
+    Ok(Prepared {
+        id: id.to_string(),
+        prefix: prefix.map(str::to_string),
+        defs,
+    })
+}
+
+/// Add the definitions of prepared referenced schemas to a base schema and rewrite its references
+/// to them
+pub fn compose_prepared(base: &Value, prepared: &[Prepared]) -> Result<Value, Error> {
     let mut result = base.clone();
     let defs = result
         .get_mut(DEFS_KEY)
@@ -23,48 +102,11 @@ pub fn compose(base: &Value, sub_schemas: &[(Option<&str>, Value)]) -> Result<Va
 
     let mut prefixes = HashMap::new();
 
-    for (prefix, sub_schema) in sub_schemas {
-        let id = get_id(sub_schema)?;
-        prefixes.insert(id, prefix.map(str::to_string));
-
-        let (path_prefix, path_name) = match id.parse::<Reference>() {
-            Ok(Reference::PathOnly {
-                path_prefix,
-                path_name,
-            }) => Ok((path_prefix, path_name)),
-            _ => Err(Error::InvalidId(id.to_string())),
-        }?;
-
-        let mut new_sub_schema = sub_schema.clone();
-
-        modify_references(&mut new_sub_schema, &|old_reference| {
-            Ok(match old_reference {
-                Reference::FragmentOnly { fragment_name } => Some(Reference::new(
-                    path_prefix.clone(),
-                    path_name.clone(),
-                    fragment_name.clone(),
-                )),
-                _ => None,
-            })
-        })?;
-
-        if let Some(top_level_def) = get_top_level_def(&new_sub_schema) {
-            defs.insert(
-                format!("{}{}", prefix.unwrap_or_default(), path_name),
-                top_level_def,
-            );
-        }
+    for sub_schema in prepared {
+        prefixes.insert(sub_schema.id.as_str(), sub_schema.prefix.clone());
 
-        if let Some(fields) = new_sub_schema
-            .get(DEFS_KEY)
-            .and_then(|value| value.as_object())
-        {
-            for (key, value) in fields {
-                defs.insert(
-                    format!("{}{}", prefix.unwrap_or_default(), key),
-                    value.clone(),
-                );
-            }
+        for (name, value) in &sub_schema.defs {
+            defs.insert(name.clone(), value.clone());
         }
     }
 
@@ -118,23 +160,31 @@ fn modify_references<F: Fn(&Reference) -> Result<Option<Reference>, Error>>(
     value: &mut Value,
     f: &F,
 ) -> Result<(), Error> {
-    if let Some(values) = value.as_array_mut() {
-        for value in values {
-            modify_references(value, f)?;
+    let result = super::util::try_walk_mut(value, |_, value| {
+        match value
+            .get_mut(REF_KEY)
+            .map(|reference| modify_reference(reference, f))
+        {
+            Some(Err(error)) => ControlFlow::Break(error),
+            _ => ControlFlow::Continue(Children::Visit),
         }
-    } else if let Some(fields) = value.as_object_mut() {
-        if let Some(reference) = fields.get_mut(REF_KEY) {
-            if let Some(previous_value) = reference.as_str() {
-                let previous_reference = previous_value.parse::<Reference>()?;
+    });
 
-                if let Some(new_reference) = f(&previous_reference)? {
-                    *reference = Value::String(new_reference.to_string());
-                }
-            }
-        }
+    match result {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(error) => Err(error),
+    }
+}
+
+fn modify_reference<F: Fn(&Reference) -> Result<Option<Reference>, Error>>(
+    reference: &mut Value,
+    f: &F,
+) -> Result<(), Error> {
+    if let Some(previous_value) = reference.as_str() {
+        let previous_reference = previous_value.parse::<Reference>()?;
 
-        for value in fields.values_mut() {
-            modify_references(value, f)?;
+        if let Some(new_reference) = f(&previous_reference)? {
+            *reference = Value::String(new_reference.to_string());
         }
     }
 
@@ -265,7 +315,7 @@ mod tests {
 
         let composed = compose(
             &base_schema,
-            &vec![
+            &[
                 (None, sub_schema_bar),
                 (None, sub_schema_qux),
                 (None, sub_schema_top_level_and_defs),
@@ -276,4 +326,59 @@ mod tests {
 
         assert_eq!(composed, expected);
     }
+
+    #[test]
+    fn compose_with_stored_prepared_schemas() {
+        let base_schema = serde_json::json!({
+            "properties": {
+                "foo": { "$ref": "/schemas/bar" },
+                "baz": { "$ref": "/schemas/qux#/$defs/oof" }
+            },
+            "$defs": {}
+        });
+        let sub_schemas = [
+            (
+                None,
+                serde_json::json!({ "$id": "/schemas/bar", "type": "integer" }),
+            ),
+            (
+                Some("abcd_"),
+                serde_json::json!({
+                    "$id": "/schemas/qux",
+                    "$defs": {
+                        "oof": { "enum": ["ABC"] },
+                        "rab": { "items": { "$ref": "#/$defs/oof" } }
+                    }
+                }),
+            ),
+        ];
+
+        let prepared = sub_schemas
+            .iter()
+            .map(|(prefix, sub_schema)| prepare(*prefix, sub_schema).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            prepared[1].defs,
+            vec![
+                (
+                    "abcd_oof".to_string(),
+                    serde_json::json!({ "enum": ["ABC"] })
+                ),
+                (
+                    "abcd_rab".to_string(),
+                    serde_json::json!({ "items": { "$ref": "/schemas/qux#/$defs/oof" } })
+                )
+            ]
+        );
+
+        // Prepared schemas can be stored and reused.
+        let stored = serde_json::to_string(&prepared).unwrap();
+        let loaded = serde_json::from_str::<Vec<Prepared>>(&stored).unwrap();
+
+        assert_eq!(
+            compose_prepared(&base_schema, &loaded).unwrap(),
+            compose(&base_schema, &sub_schemas).unwrap()
+        );
+    }
 }
@@ -1,11 +1,99 @@
+pub const ADDITIONAL_ITEMS_KEY: &str = "additionalItems";
 pub const ADDITIONAL_PROPERTIES_KEY: &str = "additionalProperties";
+pub const ALL_OF_KEY: &str = "allOf";
+pub const ANCHOR_KEY: &str = "$anchor";
+pub const ANY_OF_KEY: &str = "anyOf";
 pub const COMMENT_KEY: &str = "$comment";
+pub const CONST_KEY: &str = "const";
+pub const CONTAINS_KEY: &str = "contains";
+pub const CONTENT_ENCODING_KEY: &str = "contentEncoding";
+pub const DEFAULT_KEY: &str = "default";
+pub const DEFINITIONS_KEY: &str = "definitions";
 pub const DEFS_KEY: &str = "$defs";
+pub const DEPENDENCIES_KEY: &str = "dependencies";
+pub const DEPENDENT_REQUIRED_KEY: &str = "dependentRequired";
+pub const DEPENDENT_SCHEMAS_KEY: &str = "dependentSchemas";
+pub const DEPRECATED_KEY: &str = "deprecated";
 pub const DESCRIPTION_KEY: &str = "description";
+pub const DYNAMIC_ANCHOR_KEY: &str = "$dynamicAnchor";
+pub const DYNAMIC_REF_KEY: &str = "$dynamicRef";
+pub const ELSE_KEY: &str = "else";
+pub const ENUM_KEY: &str = "enum";
 pub const EXAMPLES_KEY: &str = "examples";
+pub const EXCLUSIVE_MAXIMUM_KEY: &str = "exclusiveMaximum";
+pub const EXCLUSIVE_MINIMUM_KEY: &str = "exclusiveMinimum";
+pub const FORMAT_KEY: &str = "format";
 pub const ID_KEY: &str = "$id";
+pub const IF_KEY: &str = "if";
+pub const ITEMS_KEY: &str = "items";
+pub const MAXIMUM_KEY: &str = "maximum";
+pub const MAX_CONTAINS_KEY: &str = "maxContains";
+pub const MAX_ITEMS_KEY: &str = "maxItems";
+pub const MAX_LENGTH_KEY: &str = "maxLength";
+pub const MAX_PROPERTIES_KEY: &str = "maxProperties";
+pub const MINIMUM_KEY: &str = "minimum";
+pub const MIN_CONTAINS_KEY: &str = "minContains";
+pub const MIN_ITEMS_KEY: &str = "minItems";
+pub const MIN_LENGTH_KEY: &str = "minLength";
+pub const MIN_PROPERTIES_KEY: &str = "minProperties";
+pub const MULTIPLE_OF_KEY: &str = "multipleOf";
+pub const NOT_KEY: &str = "not";
+pub const ONE_OF_KEY: &str = "oneOf";
+pub const PATTERN_KEY: &str = "pattern";
+pub const PATTERN_PROPERTIES_KEY: &str = "patternProperties";
+pub const PREFIX_ITEMS_KEY: &str = "prefixItems";
 pub const PROPERTIES_KEY: &str = "properties";
+pub const PROPERTY_NAMES_KEY: &str = "propertyNames";
+pub const READ_ONLY_KEY: &str = "readOnly";
 pub const REF_KEY: &str = "$ref";
 pub const REQUIRED_KEY: &str = "required";
+pub const SCHEMA_KEY: &str = "$schema";
+pub const THEN_KEY: &str = "then";
 pub const TITLE_KEY: &str = "title";
 pub const TYPE_KEY: &str = "type";
+pub const UNEVALUATED_ITEMS_KEY: &str = "unevaluatedItems";
+pub const UNEVALUATED_PROPERTIES_KEY: &str = "unevaluatedProperties";
+pub const UNIQUE_ITEMS_KEY: &str = "uniqueItems";
+pub const VERSION_KEY: &str = "version";
+pub const VOCABULARY_KEY: &str = "$vocabulary";
+
+/// Keys whose values are maps from names to schemas (including the draft-07 `definitions` and
+/// `dependencies`, whose array values are skipped wherever a schema is expected)
+pub const SCHEMA_MAP_KEYS: [&str; 6] = [
+    PROPERTIES_KEY,
+    DEFS_KEY,
+    PATTERN_PROPERTIES_KEY,
+    DEPENDENT_SCHEMAS_KEY,
+    DEFINITIONS_KEY,
+    DEPENDENCIES_KEY,
+];
+
+/// Keys whose values are single schemas (including the draft-07 `items`, which may also be an
+/// array of schemas)
+pub const SUBSCHEMA_KEYS: [&str; 11] = [
+    ADDITIONAL_ITEMS_KEY,
+    ADDITIONAL_PROPERTIES_KEY,
+    CONTAINS_KEY,
+    ELSE_KEY,
+    IF_KEY,
+    ITEMS_KEY,
+    NOT_KEY,
+    PROPERTY_NAMES_KEY,
+    THEN_KEY,
+    UNEVALUATED_ITEMS_KEY,
+    UNEVALUATED_PROPERTIES_KEY,
+];
+
+/// Keys whose values are arrays of schemas
+pub const SCHEMA_ARRAY_KEYS: [&str; 4] = [ALL_OF_KEY, ANY_OF_KEY, ONE_OF_KEY, PREFIX_ITEMS_KEY];
+
+/// Keys whose values are instance data (or names) rather than schemas, so that objects in them that
+/// look like schemas or references aren't
+pub const DATA_KEYS: [&str; 6] = [
+    CONST_KEY,
+    DEFAULT_KEY,
+    ENUM_KEY,
+    EXAMPLES_KEY,
+    REQUIRED_KEY,
+    DEPENDENT_REQUIRED_KEY,
+];
@@ -0,0 +1,425 @@
+use super::{constants::*, keyword::Keywords, reference::Reference};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+mod dictionary;
+mod html;
+mod markdown;
+mod mermaid;
+
+pub use dictionary::{dictionary, to_delimited, DictionaryEntry};
+pub use html::{
+    search_index, search_index_script, Error, HtmlRenderer, NavPage, SearchEntry, SEARCH_INDEX_NAME,
+};
+pub use markdown::{to_markdown, to_markdown_with_examples};
+pub use mermaid::to_class_diagram;
+
+/// The format that examples on pages are written in
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ExampleFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl ExampleFormat {
+    /// The language of code blocks containing examples
+    pub fn language(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+        }
+    }
+
+    /// Write an example over multiple lines, without a trailing newline
+    pub fn render(&self, value: &Value) -> String {
+        match self {
+            Self::Json => serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
+            Self::Yaml => super::yaml::to_string(value).trim_end().to_string(),
+        }
+    }
+}
+
+/// Documentation for a schema file
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Page {
+    pub title: String,
+    pub id: Option<String>,
+    pub sections: Vec<Section>,
+}
+
+/// Documentation for the top-level schema or a definition
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Section {
+    pub name: String,
+    /// Fragment identifying the section within the page
+    pub anchor: String,
+    pub description: Option<String>,
+    pub type_description: Vec<Fragment>,
+    pub constraints: Vec<String>,
+    pub properties: Vec<Property>,
+    pub examples: Vec<Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Property {
+    pub name: String,
+    pub anchor: String,
+    pub type_description: Vec<Fragment>,
+    pub required: bool,
+    pub constraints: Vec<String>,
+    pub description: Option<String>,
+    pub examples: Vec<Value>,
+}
+
+/// Part of a type description, which may link to another definition
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Fragment {
+    Text { text: String },
+    Link { label: String, reference: String },
+}
+
+/// Maps schema IDs to the locations of their rendered pages, for links between pages
+pub type PageLinks = HashMap<String, String>;
+
+impl Page {
+    pub fn new(value: &Value) -> Self {
+        Self::with_keywords(value, &Keywords::default())
+    }
+
+    /// Document a schema, including the descriptions of any user-defined keywords in constraints
+    pub fn with_keywords(value: &Value, keywords: &Keywords) -> Self {
+        let id = value
+            .get(ID_KEY)
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let title = value
+            .get(TITLE_KEY)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| id.clone())
+            .unwrap_or_else(|| "Schema".to_string());
+
+        let mut sections = vec![];
+
+        if value.as_object().is_some_and(|fields| {
+            fields
+                .keys()
+                .any(|key| ![ID_KEY, SCHEMA_KEY, DEFS_KEY, COMMENT_KEY].contains(&key.as_str()))
+        }) {
+            sections.push(Section::new(&title, "", value, keywords));
+        }
+
+        if let Some(defs) = value.get(DEFS_KEY).and_then(Value::as_object) {
+            for (name, def) in defs {
+                sections.push(Section::new(name, name, def, keywords));
+            }
+        }
+
+        Self {
+            title,
+            id,
+            sections,
+        }
+    }
+
+    /// Resolve a reference to a link target, relative to this page
+    pub fn link_target(&self, reference: &str, links: &PageLinks) -> Option<String> {
+        let parsed = reference.parse::<Reference>().ok()?;
+
+        let page = match parsed.path() {
+            Some(path) if Some(&path) != self.id.as_ref() => Some(links.get(&path)?.clone()),
+            _ => None,
+        };
+        let fragment = match parsed {
+            Reference::PathOnly { .. } => None,
+            _ => Some(parsed.name().to_string()),
+        };
+
+        Some(match (page, fragment) {
+            (Some(page), Some(fragment)) => format!("{}#{}", page, fragment),
+            (Some(page), None) => page,
+            (None, Some(fragment)) => format!("#{}", fragment),
+            (None, None) => "#".to_string(),
+        })
+    }
+}
+
+impl Section {
+    fn new(name: &str, anchor: &str, value: &Value, keywords: &Keywords) -> Self {
+        let all_constraints = |schema: &Value| {
+            let mut result = constraints(schema);
+            result.extend(keywords.constraints(schema));
+            result
+        };
+
+        let required = value
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let properties = value
+            .get(PROPERTIES_KEY)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(property_name, schema)| Property {
+                name: property_name.clone(),
+                anchor: format!(
+                    "{}.{}",
+                    if anchor.is_empty() {
+                        PROPERTIES_KEY
+                    } else {
+                        anchor
+                    },
+                    property_name
+                ),
+                type_description: describe(schema),
+                required: required.contains(&property_name.as_str()),
+                constraints: all_constraints(schema),
+                description: description(schema),
+                examples: examples(schema),
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            anchor: anchor.to_string(),
+            description: description(value),
+            type_description: describe(value),
+            constraints: all_constraints(value),
+            properties,
+            examples: examples(value),
+        }
+    }
+}
+
+fn description(value: &Value) -> Option<String> {
+    value
+        .get(DESCRIPTION_KEY)
+        .or_else(|| value.get(TITLE_KEY))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn examples(value: &Value) -> Vec<Value> {
+    value
+        .get(EXAMPLES_KEY)
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn text(value: &str) -> Fragment {
+    Fragment::Text {
+        text: value.to_string(),
+    }
+}
+
+/// Describe the type of a schema, with links for references
+pub fn describe(value: &Value) -> Vec<Fragment> {
+    let fields = match value {
+        Value::Bool(true) => return vec![text("any")],
+        Value::Bool(false) => return vec![text("never")],
+        Value::Object(fields) => fields,
+        _ => return vec![text("unknown")],
+    };
+
+    if let Some(reference) = fields.get(REF_KEY).and_then(Value::as_str) {
+        let label = reference
+            .parse::<Reference>()
+            .map(|parsed| parsed.name().to_string())
+            .unwrap_or_else(|_| reference.to_string());
+
+        return vec![Fragment::Link {
+            label,
+            reference: reference.to_string(),
+        }];
+    }
+
+    for (keyword, separator) in [
+        (ONE_OF_KEY, " | "),
+        (ANY_OF_KEY, " | "),
+        (ALL_OF_KEY, " & "),
+    ] {
+        if let Some(branches) = fields.get(keyword).and_then(Value::as_array) {
+            let mut result = vec![];
+
+            for (index, branch) in branches.iter().enumerate() {
+                if index > 0 {
+                    result.push(text(separator));
+                }
+                result.extend(describe(branch));
+            }
+
+            return result;
+        }
+    }
+
+    if fields.contains_key(ENUM_KEY) {
+        return vec![text("enum")];
+    }
+
+    if fields.contains_key(CONST_KEY) {
+        return vec![text("const")];
+    }
+
+    let names = match fields.get(TYPE_KEY) {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+
+    if names.is_empty() {
+        return vec![text(if fields.contains_key(PROPERTIES_KEY) {
+            "object"
+        } else {
+            "any"
+        })];
+    }
+
+    let mut result = vec![];
+
+    for (index, name) in names.iter().enumerate() {
+        if index > 0 {
+            result.push(text(" | "));
+        }
+
+        match *name {
+            "array" => match fields.get(ITEMS_KEY) {
+                Some(items) => {
+                    result.push(text("array of "));
+                    result.extend(describe(items));
+                }
+                None => result.push(text("array")),
+            },
+            "object" => match fields.get(ADDITIONAL_PROPERTIES_KEY) {
+                Some(values @ Value::Object(_)) if !fields.contains_key(PROPERTIES_KEY) => {
+                    result.push(text("map of "));
+                    result.extend(describe(values));
+                }
+                _ => result.push(text("object")),
+            },
+            name => result.push(text(name)),
+        }
+    }
+
+    result
+}
+
+/// Human-readable constraints on the values of a schema
+pub fn constraints(value: &Value) -> Vec<String> {
+    let fields = match value.as_object() {
+        Some(fields) => fields,
+        None => return vec![],
+    };
+
+    let mut result = vec![];
+
+    for (keyword, label) in [
+        (MINIMUM_KEY, ">="),
+        (EXCLUSIVE_MINIMUM_KEY, ">"),
+        (MAXIMUM_KEY, "<="),
+        (EXCLUSIVE_MAXIMUM_KEY, "<"),
+        (MULTIPLE_OF_KEY, "multiple of"),
+        (MIN_LENGTH_KEY, "min length"),
+        (MAX_LENGTH_KEY, "max length"),
+        (MIN_ITEMS_KEY, "min items"),
+        (MAX_ITEMS_KEY, "max items"),
+        (MIN_PROPERTIES_KEY, "min properties"),
+        (MAX_PROPERTIES_KEY, "max properties"),
+    ] {
+        if let Some(bound) = fields.get(keyword) {
+            result.push(format!("{} {}", label, bound));
+        }
+    }
+
+    if let Some(pattern) = fields.get(PATTERN_KEY).and_then(Value::as_str) {
+        result.push(format!("pattern `{}`", pattern));
+    }
+
+    if let Some(format) = fields.get(FORMAT_KEY).and_then(Value::as_str) {
+        result.push(format!("format {}", format));
+    }
+
+    if fields.get(UNIQUE_ITEMS_KEY) == Some(&Value::Bool(true)) {
+        result.push("unique items".to_string());
+    }
+
+    if let Some(values) = fields.get(ENUM_KEY).and_then(Value::as_array) {
+        result.push(format!(
+            "one of {}",
+            values
+                .iter()
+                .map(|value| format!("`{}`", value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if let Some(value) = fields.get(CONST_KEY) {
+        result.push(format!("equal to `{}`", value));
+    }
+
+    if let Some(value) = fields.get(DEFAULT_KEY) {
+        result.push(format!("default `{}`", value));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_sections() {
+        let page = Page::new(&serde_json::json!({
+            "$id": "/schemas/user",
+            "title": "User",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "description": "Full name" },
+                "role": { "$ref": "#/$defs/role" },
+                "team": { "$ref": "/schemas/team#/$defs/team" }
+            },
+            "required": ["name"],
+            "$defs": {
+                "role": { "enum": ["admin", "member"], "examples": ["admin"] }
+            }
+        }));
+
+        assert_eq!(
+            page.sections
+                .iter()
+                .map(|section| section.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["User", "role"]
+        );
+
+        let name = &page.sections[0].properties[0];
+        assert!(name.required);
+        assert_eq!(name.constraints, vec!["min length 1"]);
+        assert_eq!(name.description.as_deref(), Some("Full name"));
+        assert_eq!(
+            page.sections[1].constraints,
+            vec!["one of `\"admin\"`, `\"member\"`"]
+        );
+
+        let links = PageLinks::from([("/schemas/team".to_string(), "team.md".to_string())]);
+
+        assert_eq!(
+            page.link_target("#/$defs/role", &links).as_deref(),
+            Some("#role")
+        );
+        assert_eq!(
+            page.link_target("/schemas/team#/$defs/team", &links)
+                .as_deref(),
+            Some("team.md#team")
+        );
+        assert_eq!(page.link_target("/schemas/other", &links), None);
+    }
+}
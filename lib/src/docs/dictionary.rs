@@ -0,0 +1,189 @@
+use super::{constraints, describe, description, Fragment};
+use crate::constants::*;
+use serde_json::Value;
+
+/// The columns of a data dictionary, in order
+const COLUMNS: [&str; 6] = [
+    "path",
+    "type",
+    "required",
+    "constraints",
+    "description",
+    "enum values",
+];
+
+/// A property in a data dictionary
+#[derive(Clone, Debug, PartialEq)]
+pub struct DictionaryEntry {
+    /// Dot-separated property names, starting with the definition name for properties of
+    /// definitions, where `[]` marks array items
+    pub path: String,
+    pub type_description: String,
+    pub required: bool,
+    pub constraints: Vec<String>,
+    pub description: Option<String>,
+    pub enum_values: Vec<Value>,
+}
+
+/// Flatten the properties of the top-level schema and its definitions, including inline objects
+/// nested in them
+pub fn dictionary(value: &Value) -> Vec<DictionaryEntry> {
+    let mut result = vec![];
+
+    collect_entries(value, "", &mut result);
+
+    for (name, def) in value
+        .get(DEFS_KEY)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        collect_entries(def, name, &mut result);
+    }
+
+    result
+}
+
+fn collect_entries(value: &Value, prefix: &str, acc: &mut Vec<DictionaryEntry>) {
+    let required = value
+        .get(REQUIRED_KEY)
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    for (name, schema) in value
+        .get(PROPERTIES_KEY)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+
+        let mut without_enum = schema.clone();
+        let enum_values = match without_enum
+            .as_object_mut()
+            .and_then(|fields| fields.shift_remove(ENUM_KEY))
+        {
+            Some(Value::Array(values)) => values,
+            _ => vec![],
+        };
+
+        acc.push(DictionaryEntry {
+            path: path.clone(),
+            type_description: describe(schema)
+                .into_iter()
+                .map(|fragment| match fragment {
+                    Fragment::Text { text } => text,
+                    Fragment::Link { label, .. } => label,
+                })
+                .collect(),
+            required: required.contains(&name.as_str()),
+            constraints: constraints(&without_enum),
+            description: description(schema),
+            enum_values,
+        });
+
+        collect_entries(schema, &path, acc);
+
+        if let Some(items) = schema.get(ITEMS_KEY) {
+            collect_entries(items, &format!("{}[]", path), acc);
+        }
+    }
+}
+
+/// Render a data dictionary as CSV (with a comma delimiter) or TSV (with a tab), with a header row
+pub fn to_delimited(entries: &[DictionaryEntry], delimiter: char) -> String {
+    let mut result = String::new();
+    let mut push_row = |cells: &[String]| {
+        let cells = cells
+            .iter()
+            .map(|cell| escape(cell, delimiter))
+            .collect::<Vec<_>>();
+
+        result.push_str(&cells.join(&delimiter.to_string()));
+        result.push('\n');
+    };
+
+    push_row(&COLUMNS.map(str::to_string));
+
+    for entry in entries {
+        push_row(&[
+            entry.path.clone(),
+            entry.type_description.clone(),
+            entry.required.to_string(),
+            entry.constraints.join("; "),
+            entry.description.clone().unwrap_or_default(),
+            entry
+                .enum_values
+                .iter()
+                .map(|value| match value {
+                    Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("; "),
+        ]);
+    }
+
+    result
+}
+
+/// Quote CSV cells that need it, and replace tabs and newlines in TSV cells (which can't be quoted)
+fn escape(cell: &str, delimiter: char) -> String {
+    if delimiter == '\t' {
+        cell.replace(['\t', '\n', '\r'], " ")
+    } else if cell.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_properties() {
+        let value = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "description": "Full name, \"as given\"" },
+                "orders": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "status": { "enum": ["open", "closed"] } },
+                        "required": ["status"]
+                    }
+                }
+            },
+            "required": ["name"],
+            "$defs": {
+                "Address": { "properties": { "city": { "$ref": "#/$defs/City" } } }
+            }
+        });
+
+        let entries = dictionary(&value);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["name", "orders", "orders[].status", "Address.city"]
+        );
+
+        assert_eq!(
+            to_delimited(&entries, ','),
+            "path,type,required,constraints,description,enum values\n\
+             name,string,true,min length 1,\"Full name, \"\"as given\"\"\",\n\
+             orders,array of object,false,,,\n\
+             orders[].status,enum,true,,,open; closed\n\
+             Address.city,City,false,,,\n"
+        );
+    }
+}
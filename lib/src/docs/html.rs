@@ -0,0 +1,272 @@
+use super::{ExampleFormat, Fragment, Page, PageLinks};
+use minijinja::Environment;
+use serde::Serialize;
+use serde_json::Value;
+
+const TEMPLATE_NAME: &str = "page.html";
+const DEFAULT_TEMPLATE: &str = include_str!("page.html");
+
+/// The file name of the search index script that pages load
+pub const SEARCH_INDEX_NAME: &str = "search-index.js";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Template error")]
+    Template(#[from] minijinja::Error),
+}
+
+/// A page in the navigation sidebar
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct NavPage {
+    pub title: String,
+    pub href: String,
+}
+
+/// An entry in the search index
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchEntry {
+    pub name: String,
+    pub description: Option<String>,
+    pub href: String,
+}
+
+/// Renders pages as HTML with a Jinja-style template
+///
+/// Templates receive the page title and ID, the sections (with `type_html` for each section and
+/// property), the navigation pages (with `current` set for the page being rendered), and the
+/// location of the search index script.
+pub struct HtmlRenderer {
+    env: Environment<'static>,
+    example_format: ExampleFormat,
+}
+
+impl Default for HtmlRenderer {
+    fn default() -> Self {
+        Self::with_template(DEFAULT_TEMPLATE.to_string()).expect("the default template is valid")
+    }
+}
+
+impl HtmlRenderer {
+    /// Use a custom template instead of the default
+    pub fn with_template(source: String) -> Result<Self, Error> {
+        let mut env = Environment::new();
+        env.add_template_owned(TEMPLATE_NAME, source)?;
+
+        Ok(Self {
+            env,
+            example_format: ExampleFormat::default(),
+        })
+    }
+
+    /// Write examples in the given format instead of JSON
+    pub fn with_example_format(self, example_format: ExampleFormat) -> Self {
+        Self {
+            example_format,
+            ..self
+        }
+    }
+
+    /// The default template, as a starting point for customization
+    pub fn default_template() -> &'static str {
+        DEFAULT_TEMPLATE
+    }
+
+    pub fn render(
+        &self,
+        page: &Page,
+        href: &str,
+        links: &PageLinks,
+        nav: &[NavPage],
+    ) -> Result<String, Error> {
+        let context = PageContext {
+            title: &page.title,
+            id: page.id.as_deref(),
+            sections: page
+                .sections
+                .iter()
+                .map(|section| SectionContext {
+                    name: &section.name,
+                    anchor: &section.anchor,
+                    description: section.description.as_deref(),
+                    type_html: fragments_html(page, &section.type_description, links),
+                    constraints: &section.constraints,
+                    properties: section
+                        .properties
+                        .iter()
+                        .map(|property| PropertyContext {
+                            name: &property.name,
+                            anchor: &property.anchor,
+                            type_html: fragments_html(page, &property.type_description, links),
+                            required: property.required,
+                            constraints: &property.constraints,
+                            description: property.description.as_deref(),
+                            examples: property.examples.iter().map(Value::to_string).collect(),
+                        })
+                        .collect(),
+                    examples: section
+                        .examples
+                        .iter()
+                        .map(|example| self.example_format.render(example))
+                        .collect(),
+                })
+                .collect(),
+            pages: nav
+                .iter()
+                .map(|nav_page| NavContext {
+                    title: &nav_page.title,
+                    href: &nav_page.href,
+                    current: nav_page.href == href,
+                })
+                .collect(),
+            search_index: SEARCH_INDEX_NAME,
+        };
+
+        Ok(self
+            .env
+            .get_template(TEMPLATE_NAME)?
+            .render(minijinja::value::Serde(&context))?)
+    }
+}
+
+/// Entries for every section and property of the given pages
+pub fn search_index(pages: &[(&str, &Page)]) -> Vec<SearchEntry> {
+    let mut result = vec![];
+
+    for (href, page) in pages {
+        for section in &page.sections {
+            result.push(SearchEntry {
+                name: if section.anchor.is_empty() {
+                    page.title.clone()
+                } else {
+                    section.name.clone()
+                },
+                description: section.description.clone(),
+                href: if section.anchor.is_empty() {
+                    href.to_string()
+                } else {
+                    format!("{}#{}", href, section.anchor)
+                },
+            });
+
+            for property in &section.properties {
+                result.push(SearchEntry {
+                    name: format!("{}.{}", section.name, property.name),
+                    description: property.description.clone(),
+                    href: format!("{}#{}", href, property.anchor),
+                });
+            }
+        }
+    }
+
+    result
+}
+
+/// The search index as a script that can be loaded from the local file system
+pub fn search_index_script(entries: &[SearchEntry]) -> String {
+    format!(
+        "window.SEARCH_INDEX = {};\n",
+        serde_json::to_string(entries).unwrap_or_else(|_| "[]".to_string())
+    )
+}
+
+#[derive(Serialize)]
+struct PageContext<'a> {
+    title: &'a str,
+    id: Option<&'a str>,
+    sections: Vec<SectionContext<'a>>,
+    pages: Vec<NavContext<'a>>,
+    search_index: &'a str,
+}
+
+#[derive(Serialize)]
+struct SectionContext<'a> {
+    name: &'a str,
+    anchor: &'a str,
+    description: Option<&'a str>,
+    type_html: String,
+    constraints: &'a [String],
+    properties: Vec<PropertyContext<'a>>,
+    examples: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PropertyContext<'a> {
+    name: &'a str,
+    anchor: &'a str,
+    type_html: String,
+    required: bool,
+    constraints: &'a [String],
+    description: Option<&'a str>,
+    examples: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct NavContext<'a> {
+    title: &'a str,
+    href: &'a str,
+    current: bool,
+}
+
+fn fragments_html(page: &Page, fragments: &[Fragment], links: &PageLinks) -> String {
+    fragments
+        .iter()
+        .map(|fragment| match fragment {
+            Fragment::Text { text } => escape(text),
+            Fragment::Link { label, reference } => match page.link_target(reference, links) {
+                Some(target) => format!("<a href=\"{}\">{}</a>", escape(&target), escape(label)),
+                None => format!("<code>{}</code>", escape(reference)),
+            },
+        })
+        .collect()
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_default_template() {
+        let page = Page::new(&serde_json::json!({
+            "$id": "/schemas/user",
+            "title": "User",
+            "type": "object",
+            "properties": {
+                "role": { "$ref": "#/$defs/role", "description": "<b>Role</b>" }
+            },
+            "$defs": {
+                "role": { "enum": ["admin", "member"] }
+            }
+        }));
+        let nav = vec![NavPage {
+            title: "User".to_string(),
+            href: "user.html".to_string(),
+        }];
+
+        let html = HtmlRenderer::default()
+            .render(&page, "user.html", &PageLinks::new(), &nav)
+            .unwrap();
+
+        assert!(html.contains("<li><a href=\"#role\">role</a></li>"));
+        assert!(html.contains("<tr id=\"properties.role\">"));
+        assert!(html.contains("<td><a href=\"#role\">role</a></td>"));
+        assert!(html.contains("&lt;b&gt;Role&lt;&#x2f;b&gt;"));
+
+        let index = search_index(&[("user.html", &page)]);
+
+        assert_eq!(
+            index
+                .iter()
+                .map(|entry| entry.href.as_str())
+                .collect::<Vec<_>>(),
+            vec!["user.html", "user.html#properties.role", "user.html#role"]
+        );
+    }
+}
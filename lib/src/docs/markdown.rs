@@ -0,0 +1,140 @@
+use super::{ExampleFormat, Fragment, Page, PageLinks, Section};
+use std::fmt::Write;
+
+/// Render a page as Markdown
+///
+/// References to other schemas are rendered as links when their IDs are in `links`.
+pub fn to_markdown(page: &Page, links: &PageLinks) -> String {
+    to_markdown_with_examples(page, links, ExampleFormat::Json)
+}
+
+/// Render a page as Markdown, with examples in the given format
+pub fn to_markdown_with_examples(
+    page: &Page,
+    links: &PageLinks,
+    example_format: ExampleFormat,
+) -> String {
+    let mut output = String::new();
+    let _ = writeln!(output, "# {}\n", page.title);
+
+    if let Some(id) = &page.id {
+        let _ = writeln!(output, "`{}`\n", id);
+    }
+
+    let definitions = page
+        .sections
+        .iter()
+        .filter(|section| !section.anchor.is_empty())
+        .collect::<Vec<_>>();
+
+    if !definitions.is_empty() {
+        output.push_str("## Definitions\n\n");
+
+        for section in &definitions {
+            let _ = writeln!(output, "* [{}](#{})", section.name, section.anchor);
+        }
+
+        output.push('\n');
+    }
+
+    for section in &page.sections {
+        render_section(page, section, links, example_format, &mut output);
+    }
+
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+    output
+}
+
+fn render_section(
+    page: &Page,
+    section: &Section,
+    links: &PageLinks,
+    example_format: ExampleFormat,
+    output: &mut String,
+) {
+    if section.anchor.is_empty() {
+        output.push_str("## Schema\n\n");
+    } else {
+        let _ = writeln!(output, "<a id=\"{}\"></a>\n", section.anchor);
+        let _ = writeln!(output, "## {}\n", section.name);
+    }
+
+    if let Some(description) = &section.description {
+        let _ = writeln!(output, "{}\n", description);
+    }
+
+    let _ = writeln!(
+        output,
+        "**Type:** {}\n",
+        fragments(page, &section.type_description, links)
+    );
+
+    if !section.constraints.is_empty() {
+        let _ = writeln!(
+            output,
+            "**Constraints:** {}\n",
+            section.constraints.join(", ")
+        );
+    }
+
+    if !section.properties.is_empty() {
+        output.push_str("| Property | Type | Required | Constraints | Description |\n");
+        output.push_str("| --- | --- | --- | --- | --- |\n");
+
+        for property in &section.properties {
+            let mut description = property.description.clone().unwrap_or_default();
+
+            for example in &property.examples {
+                if !description.is_empty() {
+                    description.push_str("<br>");
+                }
+                let _ = write!(description, "Example: `{}`", example);
+            }
+
+            let _ = writeln!(
+                output,
+                "| <a id=\"{}\"></a>`{}` | {} | {} | {} | {} |",
+                property.anchor,
+                property.name,
+                cell(&fragments(page, &property.type_description, links)),
+                if property.required { "yes" } else { "no" },
+                cell(&property.constraints.join(", ")),
+                cell(&description)
+            );
+        }
+
+        output.push('\n');
+    }
+
+    if !section.examples.is_empty() {
+        output.push_str("**Examples:**\n\n");
+
+        for example in &section.examples {
+            let _ = writeln!(
+                output,
+                "```{}\n{}\n```\n",
+                example_format.language(),
+                example_format.render(example)
+            );
+        }
+    }
+}
+
+fn fragments(page: &Page, fragments: &[Fragment], links: &PageLinks) -> String {
+    fragments
+        .iter()
+        .map(|fragment| match fragment {
+            Fragment::Text { text } => text.clone(),
+            Fragment::Link { label, reference } => match page.link_target(reference, links) {
+                Some(target) => format!("[{}]({})", label, target),
+                None => format!("`{}`", reference),
+            },
+        })
+        .collect()
+}
+
+/// Escape a value for use in a table cell
+fn cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', "<br>")
+}
@@ -0,0 +1,415 @@
+use crate::constants::*;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Render the object and enum definitions of a schema as a Mermaid class diagram
+///
+/// Properties become fields with their types (marked with `?` if they're optional or nullable).
+/// Inline objects become classes composed into the classes containing them, references to other
+/// classes become associations (including references to other files, whose classes have no
+/// fields), and references in `allOf` become inheritance, while the properties of other `allOf`
+/// members are merged in. Arrays and maps are marked with a multiplicity of `*`.
+pub fn to_class_diagram(value: &Value) -> String {
+    let id = value.get(ID_KEY).and_then(Value::as_str);
+    let definitions = value
+        .get(DEFS_KEY)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut diagram = Diagram {
+        id,
+        classes: definitions
+            .iter()
+            .filter(|(_, definition)| kind(definition).is_some())
+            .map(|(name, _)| (name.as_str(), class_name(name)))
+            .collect(),
+        ..Diagram::default()
+    };
+
+    // Definitions keep their names, so inline classes are named around them.
+    diagram.used = diagram.classes.values().cloned().collect();
+
+    if kind(value).is_some() {
+        let title = value
+            .get(TITLE_KEY)
+            .and_then(Value::as_str)
+            .unwrap_or("Schema");
+        let name = diagram.unique(class_name(title));
+
+        diagram.class(&name, value);
+    }
+
+    for (name, definition) in &definitions {
+        if let Some(class) = diagram.classes.get(name.as_str()).cloned() {
+            diagram.class(&class, definition);
+        }
+    }
+
+    let mut output = "classDiagram\n".to_string();
+
+    for block in &diagram.blocks {
+        output.push_str(block);
+    }
+
+    for relation in &diagram.relations {
+        let _ = writeln!(output, "    {}", relation);
+    }
+
+    output
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    Object,
+    Enumeration,
+}
+
+/// Whether a schema is drawn as a class
+fn kind(value: &Value) -> Option<Kind> {
+    let fields = value.as_object()?;
+
+    if fields.contains_key(PROPERTIES_KEY) || fields.contains_key(ALL_OF_KEY) {
+        Some(Kind::Object)
+    } else if fields.contains_key(ENUM_KEY) {
+        Some(Kind::Enumeration)
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+struct Diagram<'a> {
+    /// The `$id` of the schema, for recognizing local references with a path
+    id: Option<&'a str>,
+    /// Class names for definitions that are drawn as classes
+    classes: HashMap<&'a str, String>,
+    used: HashSet<String>,
+    blocks: Vec<String>,
+    relations: Vec<String>,
+}
+
+impl Diagram<'_> {
+    fn unique(&mut self, name: String) -> String {
+        let mut candidate = name.clone();
+        let mut suffix = 2;
+
+        while self.used.contains(&candidate) {
+            candidate = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+
+        self.used.insert(candidate.clone());
+        candidate
+    }
+
+    fn class(&mut self, name: &str, value: &Value) {
+        let mut block = format!("    class {} {{\n", name);
+
+        if kind(value) == Some(Kind::Enumeration) {
+            block.push_str("        <<enumeration>>\n");
+
+            for enum_value in value
+                .get(ENUM_KEY)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let _ = writeln!(
+                    block,
+                    "        {}",
+                    match enum_value {
+                        Value::String(enum_value) => enum_value.clone(),
+                        enum_value => enum_value.to_string(),
+                    }
+                );
+            }
+        } else {
+            for member in self.members(name, value) {
+                let _ = writeln!(block, "        {}", member);
+            }
+        }
+
+        block.push_str("    }\n");
+        self.blocks.push(block);
+    }
+
+    /// The fields of a class, including those of inline `allOf` members
+    fn members(&mut self, name: &str, value: &Value) -> Vec<String> {
+        let mut members = vec![];
+
+        for member in value
+            .get(ALL_OF_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            match member.get(REF_KEY).and_then(Value::as_str) {
+                Some(reference) => {
+                    if let Some(parent) = self.target(reference) {
+                        self.relations.push(format!("{} <|-- {}", parent, name));
+                    }
+                }
+                None => members.extend(self.members(name, member)),
+            }
+        }
+
+        let required = value
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        for (property, schema) in value
+            .get(PROPERTIES_KEY)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let field_type = self.field_type(name, property, schema, None);
+            let optional = !required.contains(&property.as_str()) || is_nullable(schema);
+
+            members.push(format!(
+                "+{}{} {}",
+                field_type,
+                if optional { "?" } else { "" },
+                property
+            ));
+        }
+
+        members
+    }
+
+    /// The type of a field, adding any classes and relations it needs
+    fn field_type(
+        &mut self,
+        owner: &str,
+        property: &str,
+        schema: &Value,
+        multiplicity: Option<&str>,
+    ) -> String {
+        let label = match multiplicity {
+            Some(multiplicity) => format!("\"{}\" ", multiplicity),
+            None => String::new(),
+        };
+
+        if let Some(reference) = schema.get(REF_KEY).and_then(Value::as_str) {
+            match self.target(reference) {
+                Some(target) => {
+                    self.relations
+                        .push(format!("{} --> {}{} : {}", owner, label, target, property));
+                    target
+                }
+                // Definitions that aren't classes are only named.
+                None => reference_name(reference)
+                    .map(|name| class_name(&name))
+                    .unwrap_or_else(|| "any".to_string()),
+            }
+        } else if schema.get(PROPERTIES_KEY).is_some() {
+            let child = self.unique(format!("{}_{}", owner, class_name(property)));
+            self.class(&child, schema);
+            self.relations
+                .push(format!("{} *-- {}{} : {}", owner, label, child, property));
+
+            child
+        } else if let Some(Value::Array(members)) =
+            schema.get(ONE_OF_KEY).or_else(|| schema.get(ANY_OF_KEY))
+        {
+            let members = members
+                .iter()
+                .filter(|member| member.get(TYPE_KEY) != Some(&Value::from("null")))
+                .map(|member| self.field_type(owner, property, member, multiplicity))
+                .collect::<Vec<_>>();
+
+            if members.is_empty() {
+                "null".to_string()
+            } else {
+                members.join(" | ")
+            }
+        } else if let Some(Value::Array(members)) = schema.get(ALL_OF_KEY) {
+            members
+                .iter()
+                .map(|member| self.field_type(owner, property, member, multiplicity))
+                .collect::<Vec<_>>()
+                .join(" & ")
+        } else if let Some(values) = schema.get(ENUM_KEY).and_then(Value::as_array) {
+            values.first().map(json_type).unwrap_or("any").to_string()
+        } else if let Some(value) = schema.get(CONST_KEY) {
+            json_type(value).to_string()
+        } else {
+            let types = match schema.get(TYPE_KEY) {
+                Some(Value::String(type_name)) => vec![type_name.as_str()],
+                Some(Value::Array(types)) => types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter(|type_name| *type_name != "null")
+                    .collect(),
+                _ => vec![],
+            };
+
+            match types.as_slice() {
+                ["array"] => match schema.get(ITEMS_KEY) {
+                    Some(items) => {
+                        format!("{}[]", self.field_type(owner, property, items, Some("*")))
+                    }
+                    None => "any[]".to_string(),
+                },
+                ["object"] => match schema.get(ADDITIONAL_PROPERTIES_KEY) {
+                    Some(values) if values.is_object() => format!(
+                        "Map~string, {}~",
+                        self.field_type(owner, property, values, Some("*"))
+                    ),
+                    _ => "object".to_string(),
+                },
+                [] => "any".to_string(),
+                types => types.join(" | "),
+            }
+        }
+    }
+
+    /// The class a reference points to, if it's drawn as one
+    fn target(&self, reference: &str) -> Option<String> {
+        let (path, _) = reference.split_once('#').unwrap_or((reference, ""));
+        let name = reference_name(reference)?;
+
+        if path.is_empty() || Some(path) == self.id {
+            self.classes.get(name.as_str()).cloned()
+        } else {
+            Some(class_name(&name))
+        }
+    }
+}
+
+/// The definition name in a reference, or the file name if it has no fragment
+fn reference_name(reference: &str) -> Option<String> {
+    let name = match reference.split_once('#') {
+        Some((_, fragment)) if !fragment.is_empty() => fragment.strip_prefix("/$defs/")?,
+        _ => reference.trim_end_matches('/').rsplit('/').next()?,
+    };
+
+    Some(name.trim_end_matches(".json").to_string()).filter(|name| !name.is_empty())
+}
+
+fn is_nullable(schema: &Value) -> bool {
+    let null = Value::from("null");
+
+    match schema.get(TYPE_KEY) {
+        Some(Value::Array(types)) => types.contains(&null),
+        _ => schema
+            .get(ONE_OF_KEY)
+            .or_else(|| schema.get(ANY_OF_KEY))
+            .and_then(Value::as_array)
+            .is_some_and(|members| {
+                members
+                    .iter()
+                    .any(|member| member.get(TYPE_KEY) == Some(&null))
+            }),
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A name with anything Mermaid doesn't allow in class names replaced by underscores
+fn class_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn class_diagram() {
+        let schema = serde_json::json!({
+            "$id": "/schemas/order",
+            "title": "Order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer" },
+                "status": { "$ref": "#/$defs/status" },
+                "customer": { "$ref": "/schemas/order#/$defs/premium-customer" },
+                "lines": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string" } },
+                        "required": ["sku"]
+                    }
+                },
+                "note": { "type": ["string", "null"] },
+                "sku": { "$ref": "#/$defs/sku" },
+                "warehouse": { "$ref": "/schemas/common#/$defs/address" }
+            },
+            "required": ["id", "status", "note"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "sku": { "type": "string", "pattern": "^[A-Z]+$" },
+                "customer": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } }
+                },
+                "premium-customer": {
+                    "allOf": [
+                        { "$ref": "#/$defs/customer" },
+                        { "properties": { "tags": { "type": "object", "additionalProperties": { "type": "string" } } } }
+                    ]
+                }
+            }
+        });
+
+        assert_eq!(
+            to_class_diagram(&schema),
+            r#"classDiagram
+    class Order_lines {
+        +string sku
+    }
+    class Order {
+        +integer id
+        +status status
+        +premium_customer? customer
+        +Order_lines[]? lines
+        +string? note
+        +sku? sku
+        +address? warehouse
+    }
+    class status {
+        <<enumeration>>
+        open
+        in-progress
+    }
+    class customer {
+        +string? name
+    }
+    class premium_customer {
+        +Map~string, string~? tags
+    }
+    Order --> status : status
+    Order --> premium_customer : customer
+    Order *-- "*" Order_lines : lines
+    Order --> address : warehouse
+    customer <|-- premium_customer
+"#
+        );
+    }
+}
@@ -0,0 +1,787 @@
+use super::constants::*;
+use super::resolve::{resolve, Error};
+use regex::Regex;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Value};
+
+mod boundary;
+mod fake;
+mod fuzz;
+
+pub use boundary::{boundaries, Boundary};
+pub use fake::fake;
+pub use fuzz::{fuzz_dictionary, seed_corpus};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExampleOptions {
+    /// Include every declared property and at least one array item, instead of only what's required
+    pub full: bool,
+    /// Use the first of a schema's own `examples` where there are any
+    pub use_examples: bool,
+}
+
+/// Generate an instance of the schema at the given index, resolving references against the others
+///
+/// Instances honor `required`, `const`, `enum`, bounds, lengths, patterns, and common formats, but
+/// negations and conditionals are ignored, so the result should still be validated.
+pub fn example(schemas: &[Value], schema: usize, options: ExampleOptions) -> Result<Value, Error> {
+    let mut generator = Generator {
+        schemas,
+        options,
+        visiting: vec![(schema, String::new())],
+    };
+
+    generator.generate(schema, &schemas[schema], 0)
+}
+
+/// An instance that's meant to violate a single constraint
+#[derive(Clone, Debug, PartialEq)]
+pub struct NearMiss {
+    /// The keyword of the violated constraint
+    pub keyword: &'static str,
+    /// JSON Pointer to the value in the instance that violates it
+    pub pointer: String,
+    pub instance: Value,
+}
+
+/// Generate a valid instance, and then a copy of it for each constraint that can be violated by
+/// changing one value (a missing required property, a wrong type, an out-of-range number, an extra
+/// property, etc.)
+///
+/// Some near misses may violate other constraints as well (for example a too-short string that no
+/// longer matches a pattern), so they should be validated.
+pub fn near_misses(
+    schemas: &[Value],
+    schema: usize,
+    options: ExampleOptions,
+) -> Result<Vec<NearMiss>, Error> {
+    let options = ExampleOptions {
+        full: true,
+        ..options
+    };
+    let instance = example(schemas, schema, options)?;
+    let mut mutations = vec![];
+    let mut visiting = vec![];
+
+    collect_mutations(
+        schemas,
+        schema,
+        &schemas[schema],
+        &instance,
+        &mut vec![],
+        &mut visiting,
+        &mut mutations,
+    )?;
+
+    Ok(mutations
+        .into_iter()
+        .filter_map(|(keyword, path, mutation)| {
+            let mut instance = instance.clone();
+            let target = instance.pointer_mut(&pointer(&path))?;
+
+            match mutation {
+                Mutation::Replace(value) => *target = value,
+                Mutation::Remove(name) => {
+                    target.as_object_mut()?.shift_remove(&name);
+                }
+                Mutation::Insert(name, value) => {
+                    target.as_object_mut()?.insert(name, value);
+                }
+            }
+
+            Some(NearMiss {
+                keyword,
+                pointer: pointer(&path),
+                instance,
+            })
+        })
+        .collect())
+}
+
+enum Mutation {
+    Replace(Value),
+    Remove(String),
+    Insert(String, Value),
+}
+
+/// Values that are tried in turn when one that differs from some given values is needed
+fn candidates() -> [Value; 6] {
+    [
+        Value::String("invalid".to_string()),
+        Value::from(-1),
+        Value::Bool(true),
+        Value::Null,
+        Value::Object(Map::new()),
+        Value::Array(vec![]),
+    ]
+}
+
+fn collect_mutations(
+    schemas: &[Value],
+    schema: usize,
+    value: &Value,
+    instance: &Value,
+    path: &mut Vec<String>,
+    visiting: &mut Vec<(usize, String)>,
+    acc: &mut Vec<(&'static str, Vec<String>, Mutation)>,
+) -> Result<(), Error> {
+    let fields = match value.as_object() {
+        Some(fields) => fields,
+        None => return Ok(()),
+    };
+    let mut replace = |keyword: &'static str, value: Value| {
+        acc.push((keyword, path.clone(), Mutation::Replace(value)))
+    };
+
+    if let Some(types) = fields.get(TYPE_KEY) {
+        let types = match types {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+
+        if let Some(value) = candidates().into_iter().find(|value| {
+            !types
+                .iter()
+                .any(|name| *name == json_type(value) || (*name == "number" && value.is_number()))
+        }) {
+            replace(TYPE_KEY, value);
+        }
+    }
+
+    if let Some(expected) = fields.get(CONST_KEY) {
+        if let Some(value) = candidates().into_iter().find(|value| value != expected) {
+            replace(CONST_KEY, value);
+        }
+    }
+
+    if let Some(values) = fields.get(ENUM_KEY).and_then(Value::as_array) {
+        if let Some(value) = candidates()
+            .into_iter()
+            .find(|value| !values.contains(value))
+        {
+            replace(ENUM_KEY, value);
+        }
+    }
+
+    if instance.is_number() {
+        let integer = instance.is_i64() || instance.is_u64();
+        let number = |value: f64| {
+            if integer && value.fract() == 0.0 {
+                Value::from(value as i64)
+            } else {
+                serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+            }
+        };
+
+        for (keyword, offset) in [
+            (MINIMUM_KEY, -1.0),
+            (EXCLUSIVE_MINIMUM_KEY, 0.0),
+            (MAXIMUM_KEY, 1.0),
+            (EXCLUSIVE_MAXIMUM_KEY, 0.0),
+        ] {
+            if let Some(limit) = fields.get(keyword).and_then(Value::as_f64) {
+                replace(keyword, number(limit + offset));
+            }
+        }
+
+        if let (Some(divisor), Some(value)) = (
+            fields.get(MULTIPLE_OF_KEY).and_then(Value::as_f64),
+            instance.as_f64(),
+        ) {
+            replace(MULTIPLE_OF_KEY, number(value + divisor / 2.0));
+        }
+    }
+
+    if let Some(string) = instance.as_str() {
+        if let Some(min_length) = get_usize(fields, MIN_LENGTH_KEY).filter(|length| *length > 0) {
+            replace(
+                MIN_LENGTH_KEY,
+                Value::String(string.chars().take(min_length - 1).collect()),
+            );
+        }
+
+        if let Some(max_length) = get_usize(fields, MAX_LENGTH_KEY) {
+            let mut string = string.chars().take(max_length).collect::<String>();
+            string.extend(std::iter::repeat_n(
+                'x',
+                max_length + 1 - string.chars().count(),
+            ));
+            replace(MAX_LENGTH_KEY, Value::String(string));
+        }
+
+        if let Some(regex) = fields
+            .get(PATTERN_KEY)
+            .and_then(Value::as_str)
+            .and_then(|pattern| Regex::new(pattern).ok())
+        {
+            let min_length = get_usize(fields, MIN_LENGTH_KEY).unwrap_or(0);
+            let max_length = get_usize(fields, MAX_LENGTH_KEY).unwrap_or(usize::MAX);
+            let mut strings = vec![
+                format!("{}!", string),
+                format!("!{}", string),
+                "invalid".to_string(),
+                String::new(),
+            ];
+            // Strings that don't also violate the length bounds are tried first.
+            strings.sort_by_key(|string| {
+                let length = string.chars().count();
+                length < min_length || length > max_length
+            });
+
+            if let Some(string) = strings.into_iter().find(|string| !regex.is_match(string)) {
+                replace(PATTERN_KEY, Value::String(string));
+            }
+        }
+    }
+
+    if let Some(items) = instance.as_array() {
+        if let Some(min_items) = get_usize(fields, MIN_ITEMS_KEY).filter(|count| *count > 0) {
+            replace(MIN_ITEMS_KEY, Value::Array(items[..min_items - 1].to_vec()));
+        }
+
+        if let Some(max_items) = get_usize(fields, MAX_ITEMS_KEY) {
+            let mut items = items[..items.len().min(max_items)].to_vec();
+            let filler = items.last().cloned().unwrap_or(Value::Null);
+            items.resize(max_items + 1, filler);
+            replace(MAX_ITEMS_KEY, Value::Array(items));
+        }
+
+        if fields.get(UNIQUE_ITEMS_KEY) == Some(&Value::Bool(true)) {
+            if let Some(first) = items.first() {
+                let mut items = items.clone();
+                items.push(first.clone());
+                replace(UNIQUE_ITEMS_KEY, Value::Array(items));
+            }
+        }
+    }
+
+    if let Some(properties) = instance.as_object() {
+        for name in fields
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if properties.contains_key(name) {
+                acc.push((
+                    REQUIRED_KEY,
+                    path.clone(),
+                    Mutation::Remove(name.to_string()),
+                ));
+            }
+        }
+
+        if fields.get(ADDITIONAL_PROPERTIES_KEY) == Some(&Value::Bool(false)) {
+            let declared = fields.get(PROPERTIES_KEY).and_then(Value::as_object);
+            let name = (0..)
+                .map(|index| match index {
+                    0 => "unexpected".to_string(),
+                    _ => format!("unexpected{}", index),
+                })
+                .find(|name| declared.is_none_or(|declared| !declared.contains_key(name)))
+                .unwrap_or_default();
+
+            acc.push((
+                ADDITIONAL_PROPERTIES_KEY,
+                path.clone(),
+                Mutation::Insert(name, Value::Null),
+            ));
+        }
+    }
+
+    if let Some(Value::String(reference)) = fields.get(REF_KEY) {
+        let resolved = resolve(schemas, reference, Some(schema))?;
+        let key = (resolved.schema, resolved.pointer);
+
+        // Recursive schemas are only followed once for each value.
+        if !visiting.contains(&key) {
+            visiting.push(key);
+            collect_mutations(
+                schemas,
+                resolved.schema,
+                resolved.value,
+                instance,
+                path,
+                visiting,
+                acc,
+            )?;
+            visiting.pop();
+        }
+    }
+
+    let branches = fields
+        .get(ALL_OF_KEY)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .chain(
+            [ANY_OF_KEY, ONE_OF_KEY]
+                .iter()
+                .filter_map(|key| fields.get(*key).and_then(Value::as_array)?.first()),
+        );
+
+    for branch in branches {
+        collect_mutations(schemas, schema, branch, instance, path, visiting, acc)?;
+    }
+
+    // Values in the instance are new locations, so references may be followed again.
+    let mut nested = vec![];
+
+    if let (Some(properties), Some(schemas_by_name)) = (
+        instance.as_object(),
+        fields.get(PROPERTIES_KEY).and_then(Value::as_object),
+    ) {
+        for (name, property) in properties {
+            if let Some(value) = schemas_by_name.get(name) {
+                path.push(name.clone());
+                collect_mutations(schemas, schema, value, property, path, &mut nested, acc)?;
+                path.pop();
+            }
+        }
+    }
+
+    if let Some(items) = instance.as_array() {
+        let prefix = fields
+            .get(PREFIX_ITEMS_KEY)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        for (index, item) in items.iter().enumerate() {
+            if let Some(value) = prefix.get(index).or_else(|| fields.get(ITEMS_KEY)) {
+                path.push(index.to_string());
+                collect_mutations(schemas, schema, value, item, path, &mut nested, acc)?;
+                path.pop();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|entry| format!("/{}", entry.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+struct Generator<'a> {
+    schemas: &'a [Value],
+    options: ExampleOptions,
+    /// Reference targets currently being generated, to avoid looping on recursive schemas
+    visiting: Vec<(usize, String)>,
+}
+
+impl Generator<'_> {
+    /// Generate a value, where the variant distinguishes items in arrays that must be unique
+    fn generate(&mut self, schema: usize, value: &Value, variant: usize) -> Result<Value, Error> {
+        let fields = match value {
+            Value::Object(fields) => fields,
+            _ => return Ok(Value::Null),
+        };
+
+        if let Some(value) = fields.get(CONST_KEY) {
+            return Ok(value.clone());
+        }
+
+        if let Some(values) = fields.get(ENUM_KEY).and_then(Value::as_array) {
+            if !values.is_empty() {
+                return Ok(values[variant % values.len()].clone());
+            }
+        }
+
+        if self.options.use_examples {
+            if let Some(example) = fields
+                .get(EXAMPLES_KEY)
+                .and_then(Value::as_array)
+                .and_then(|examples| examples.first())
+            {
+                return Ok(example.clone());
+            }
+        }
+
+        let mut result = match type_name(fields) {
+            Some("object") => Value::Object(self.generate_object(schema, fields)?),
+            Some("array") => Value::Array(self.generate_array(schema, fields)?),
+            Some("string") => Value::String(self.generate_string(fields, variant)),
+            Some(name @ ("integer" | "number")) => generate_number(fields, name, variant),
+            Some("boolean") => Value::Bool(variant % 2 == 1),
+            _ => Value::Null,
+        };
+
+        if let Some(Value::String(reference)) = fields.get(REF_KEY) {
+            let resolved = resolve(self.schemas, reference, Some(schema))?;
+            let key = (resolved.schema, resolved.pointer);
+
+            if !self.visiting.contains(&key) {
+                self.visiting.push(key);
+                let target = self.generate(resolved.schema, resolved.value, variant)?;
+                self.visiting.pop();
+
+                merge(&mut result, target);
+            } else if self.options.full {
+                // Recursive schemas are only populated once, so they end with minimal instances.
+                self.options.full = false;
+                let target = self.generate(resolved.schema, resolved.value, variant);
+                self.options.full = true;
+
+                merge(&mut result, target?);
+            }
+        }
+
+        let branches = fields
+            .get(ALL_OF_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .chain(
+                [ANY_OF_KEY, ONE_OF_KEY]
+                    .iter()
+                    .filter_map(|key| fields.get(*key).and_then(Value::as_array)?.first()),
+            );
+
+        for branch in branches {
+            let branch = self.generate(schema, branch, variant)?;
+            merge(&mut result, branch);
+        }
+
+        Ok(result)
+    }
+
+    fn generate_object(
+        &mut self,
+        schema: usize,
+        fields: &Map<String, Value>,
+    ) -> Result<Map<String, Value>, Error> {
+        let properties = fields.get(PROPERTIES_KEY).and_then(Value::as_object);
+        let required = fields
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>();
+        let mut result = Map::new();
+
+        for (name, property) in properties.into_iter().flatten() {
+            if self.options.full || required.contains(&name.as_str()) {
+                result.insert(name.clone(), self.generate(schema, property, 0)?);
+            }
+        }
+
+        for name in required {
+            if !result.contains_key(name) {
+                let value = match fields.get(ADDITIONAL_PROPERTIES_KEY) {
+                    Some(additional) => self.generate(schema, additional, 0)?,
+                    None => Value::Null,
+                };
+
+                result.insert(name.to_string(), value);
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn generate_array(
+        &mut self,
+        schema: usize,
+        fields: &Map<String, Value>,
+    ) -> Result<Vec<Value>, Error> {
+        let prefix = fields
+            .get(PREFIX_ITEMS_KEY)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let items = fields
+            .get(ITEMS_KEY)
+            .filter(|items| **items != Value::Bool(false));
+        let min_items = get_usize(fields, MIN_ITEMS_KEY).unwrap_or(0);
+        let max_items = get_usize(fields, MAX_ITEMS_KEY).unwrap_or(usize::MAX);
+        let available = prefix.len() + if items.is_some() { usize::MAX } else { 0 };
+
+        let count = if self.options.full {
+            min_items.max(prefix.len()).max(1)
+        } else {
+            min_items
+        }
+        .min(max_items)
+        .min(available);
+
+        (0..count)
+            .map(|index| match prefix.get(index) {
+                Some(schema_value) => self.generate(schema, schema_value, 0),
+                None => self.generate(schema, items.unwrap_or(&Value::Bool(true)), index),
+            })
+            .collect()
+    }
+
+    fn generate_string(&self, fields: &Map<String, Value>, variant: usize) -> String {
+        let mut result = if let Some(pattern) = fields.get(PATTERN_KEY).and_then(Value::as_str) {
+            // Patterns that can't be parsed are reported by validation.
+            regex_syntax::parse(pattern)
+                .map(|hir| generate_match(&hir))
+                .unwrap_or_default()
+        } else if let Some(example) = fields
+            .get(FORMAT_KEY)
+            .and_then(Value::as_str)
+            .and_then(format_example)
+        {
+            return example.to_string();
+        } else if self.options.full {
+            "example".to_string()
+        } else {
+            String::new()
+        };
+
+        if variant > 0 && !fields.contains_key(PATTERN_KEY) {
+            result.push_str(&variant.to_string());
+        }
+
+        let min_length = get_usize(fields, MIN_LENGTH_KEY).unwrap_or(0);
+        let max_length = get_usize(fields, MAX_LENGTH_KEY).unwrap_or(usize::MAX);
+        let length = result.chars().count();
+
+        if length < min_length {
+            result.extend(std::iter::repeat_n('x', min_length - length));
+        } else if length > max_length {
+            result = result.chars().take(max_length).collect();
+        }
+
+        result
+    }
+}
+
+/// The declared type, or the type implied by the keywords that are used
+fn type_name(fields: &Map<String, Value>) -> Option<&str> {
+    match fields.get(TYPE_KEY) {
+        Some(Value::String(name)) => Some(name),
+        Some(Value::Array(names)) => {
+            let names = names.iter().filter_map(Value::as_str).collect::<Vec<_>>();
+
+            names
+                .iter()
+                .find(|name| **name != "null")
+                .or(names.first())
+                .copied()
+        }
+        _ => {
+            let has = |keys: &[&str]| keys.iter().any(|key| fields.contains_key(*key));
+
+            if has(&[PROPERTIES_KEY, REQUIRED_KEY, ADDITIONAL_PROPERTIES_KEY]) {
+                Some("object")
+            } else if has(&[ITEMS_KEY, PREFIX_ITEMS_KEY, MIN_ITEMS_KEY]) {
+                Some("array")
+            } else if has(&[PATTERN_KEY, FORMAT_KEY, MIN_LENGTH_KEY, MAX_LENGTH_KEY]) {
+                Some("string")
+            } else if has(&[MINIMUM_KEY, MAXIMUM_KEY, MULTIPLE_OF_KEY]) {
+                Some("number")
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Merge generated values, where objects are combined and anything else replaces a missing value
+fn merge(target: &mut Value, source: Value) {
+    match (target, source) {
+        (Value::Object(target), Value::Object(source)) => {
+            for (key, value) in source {
+                target.entry(key).or_insert(value);
+            }
+        }
+        (target @ Value::Null, source) => *target = source,
+        _ => {}
+    }
+}
+
+fn get_usize(fields: &Map<String, Value>, key: &str) -> Option<usize> {
+    fields
+        .get(key)
+        .and_then(Value::as_u64)
+        .map(|value| value as usize)
+}
+
+/// The smallest number within the bounds (preferring zero), adjusted to be a multiple if needed
+fn generate_number(fields: &Map<String, Value>, type_name: &str, variant: usize) -> Value {
+    let integer = type_name == "integer";
+    let step = fields
+        .get(MULTIPLE_OF_KEY)
+        .and_then(Value::as_f64)
+        .filter(|step| *step > 0.0)
+        .unwrap_or(1.0);
+    let minimum = fields.get(MINIMUM_KEY).and_then(Value::as_f64);
+    let exclusive_minimum = fields.get(EXCLUSIVE_MINIMUM_KEY).and_then(Value::as_f64);
+    let maximum = fields.get(MAXIMUM_KEY).and_then(Value::as_f64);
+    let exclusive_maximum = fields.get(EXCLUSIVE_MAXIMUM_KEY).and_then(Value::as_f64);
+
+    let mut value = variant as f64 * step;
+
+    if let Some(minimum) = minimum {
+        value = value.max(minimum + variant as f64 * step);
+    }
+
+    if let Some(exclusive_minimum) = exclusive_minimum {
+        if value <= exclusive_minimum {
+            let increment = if integer || fields.contains_key(MULTIPLE_OF_KEY) {
+                step
+            } else {
+                // Halfway to the maximum if there is one, so that the value stays within it.
+                maximum.or(exclusive_maximum).map_or(1.0, |maximum| {
+                    ((maximum - exclusive_minimum) / 2.0).min(1.0)
+                })
+            };
+            value = exclusive_minimum + increment + variant as f64 * step;
+        }
+    }
+
+    if fields.contains_key(MULTIPLE_OF_KEY) || integer {
+        value = (value / step).ceil() * step;
+    }
+
+    if let Some(maximum) = maximum {
+        value = value.min(maximum);
+    }
+
+    if integer || (value.fract() == 0.0 && !fields.contains_key(MULTIPLE_OF_KEY)) {
+        Value::from(value as i64)
+    } else {
+        serde_json::Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+fn format_example(format: &str) -> Option<&'static str> {
+    Some(match format {
+        "date-time" => "2024-01-01T00:00:00Z",
+        "date" => "2024-01-01",
+        "time" => "00:00:00Z",
+        "duration" => "P1D",
+        "email" | "idn-email" => "user@example.com",
+        "hostname" | "idn-hostname" => "example.com",
+        "ipv4" => "192.0.2.1",
+        "ipv6" => "2001:db8::1",
+        "uri" | "iri" => "https://example.com/",
+        "uri-reference" | "iri-reference" => "/example",
+        "uri-template" => "https://example.com/{id}",
+        "uuid" => "00000000-0000-4000-8000-000000000000",
+        "json-pointer" => "/example",
+        "relative-json-pointer" => "0",
+        "regex" => ".*",
+        _ => return None,
+    })
+}
+
+/// A short string matching a regular expression, taking the first alternative and the fewest
+/// repetitions
+fn generate_match(hir: &Hir) -> String {
+    match hir.kind() {
+        HirKind::Literal(literal) => String::from_utf8_lossy(&literal.0).to_string(),
+        HirKind::Class(Class::Unicode(class)) => class
+            .ranges()
+            .iter()
+            .flat_map(|range| {
+                ['a', 'A', '0']
+                    .into_iter()
+                    .chain([range.start()])
+                    .filter(move |c| range.start() <= *c && *c <= range.end())
+            })
+            .next()
+            .map(String::from)
+            .unwrap_or_default(),
+        HirKind::Class(Class::Bytes(class)) => class
+            .ranges()
+            .first()
+            .map(|range| char::from(range.start()).to_string())
+            .unwrap_or_default(),
+        HirKind::Repetition(repetition) => {
+            generate_match(&repetition.sub).repeat(repetition.min as usize)
+        }
+        HirKind::Capture(capture) => generate_match(&capture.sub),
+        HirKind::Concat(hirs) => hirs.iter().map(generate_match).collect(),
+        HirKind::Alternation(hirs) => hirs.first().map(generate_match).unwrap_or_default(),
+        HirKind::Empty | HirKind::Look(_) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::Validator;
+
+    #[test]
+    fn generate_examples() {
+        let schema = serde_json::json!({
+            "$id": "/schemas/user",
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "pattern": "^[A-Z]{2}-[0-9]{3}$" },
+                "email": { "type": "string", "format": "email" },
+                "age": { "type": "integer", "exclusiveMinimum": 17, "maximum": 130 },
+                "role": { "$ref": "#/$defs/role" },
+                "tags": { "type": "array", "items": { "type": "string" }, "uniqueItems": true, "minItems": 2 },
+                "ratio": { "type": "number", "exclusiveMinimum": 0, "exclusiveMaximum": 1 },
+                "nickname": { "type": "string", "minLength": 3, "examples": ["Bob"] }
+            },
+            "required": ["id", "age", "role"],
+            "$defs": { "role": { "enum": ["admin", "user"] } }
+        });
+        let validator = Validator::new(schema.clone()).unwrap();
+        let schemas = [schema];
+
+        let minimal = example(&schemas, 0, ExampleOptions::default()).unwrap();
+        assert_eq!(
+            minimal,
+            serde_json::json!({ "id": "AA-000", "age": 18, "role": "admin" })
+        );
+        assert!(validator.is_valid(&minimal));
+
+        let full = example(
+            &schemas,
+            0,
+            ExampleOptions {
+                full: true,
+                use_examples: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(full["tags"], serde_json::json!(["example", "example1"]));
+        assert_eq!(full["ratio"], serde_json::json!(0.5));
+        assert_eq!(full["nickname"], "Bob");
+        assert!(validator.is_valid(&full), "{:?}", validator.validate(&full));
+
+        let near_misses = near_misses(&schemas, 0, ExampleOptions::default()).unwrap();
+        let labels = near_misses
+            .iter()
+            .map(|near_miss| (near_miss.keyword, near_miss.pointer.as_str()))
+            .collect::<Vec<_>>();
+
+        assert!(labels.contains(&(REQUIRED_KEY, "")));
+        assert!(labels.contains(&(EXCLUSIVE_MINIMUM_KEY, "/age")));
+        assert!(labels.contains(&(ENUM_KEY, "/role")));
+        assert!(labels.contains(&(UNIQUE_ITEMS_KEY, "/tags")));
+
+        for near_miss in near_misses {
+            let errors = validator.validate(&near_miss.instance);
+
+            assert_eq!(errors.len(), 1, "{:?}", near_miss);
+            assert_eq!(errors[0].instance_pointer(), near_miss.pointer);
+        }
+    }
+}
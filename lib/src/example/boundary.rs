@@ -0,0 +1,375 @@
+use super::{example, get_usize, pointer, ExampleOptions, Generator, Mutation};
+use crate::constants::*;
+use crate::resolve::{resolve, Error};
+use serde_json::{Map, Value};
+
+/// A valid instance with a value at the edge of what a constraint allows
+#[derive(Clone, Debug, PartialEq)]
+pub struct Boundary {
+    /// The keyword of the constraint (`properties` for an optional property that's left out, or
+    /// for the instance with every optional property)
+    pub keyword: &'static str,
+    /// JSON Pointer to the value in the instance that's at the boundary
+    pub pointer: String,
+    pub instance: Value,
+}
+
+/// Generate an instance with every optional property, and then a copy of it for each boundary
+/// that can be reached by changing one value (each numeric bound, the shortest and longest
+/// strings and arrays, each enum member, and the absence of each optional property)
+///
+/// Exclusive bounds on numbers that aren't integers or multiples have no boundary value, so they
+/// aren't included. Some instances may violate other constraints (for example a longest string
+/// that no longer matches a pattern), so they should be validated.
+pub fn boundaries(
+    schemas: &[Value],
+    schema: usize,
+    options: ExampleOptions,
+) -> Result<Vec<Boundary>, Error> {
+    let options = ExampleOptions {
+        full: true,
+        ..options
+    };
+    let instance = example(schemas, schema, options)?;
+    let mut generator = Generator {
+        schemas,
+        options,
+        visiting: vec![],
+    };
+    let mut mutations = vec![];
+
+    collect_boundaries(
+        &mut generator,
+        schema,
+        &schemas[schema],
+        &instance,
+        &mut vec![],
+        &mut vec![],
+        &mut mutations,
+    )?;
+
+    let mut result = vec![Boundary {
+        keyword: PROPERTIES_KEY,
+        pointer: String::new(),
+        instance: instance.clone(),
+    }];
+
+    for (keyword, path, mutation) in mutations {
+        let mut instance = instance.clone();
+
+        if let Some(target) = instance.pointer_mut(&pointer(&path)) {
+            let pointer = match mutation {
+                Mutation::Replace(value) => {
+                    *target = value;
+                    pointer(&path)
+                }
+                Mutation::Remove(name) => {
+                    if let Some(fields) = target.as_object_mut() {
+                        fields.shift_remove(&name);
+                    }
+
+                    let mut path = path;
+                    path.push(name);
+                    pointer(&path)
+                }
+                Mutation::Insert(name, value) => {
+                    if let Some(fields) = target.as_object_mut() {
+                        fields.insert(name, value);
+                    }
+                    pointer(&path)
+                }
+            };
+
+            let boundary = Boundary {
+                keyword,
+                pointer,
+                instance,
+            };
+
+            // The same boundary can be reached through more than one subschema.
+            if !result.contains(&boundary) {
+                result.push(boundary);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn collect_boundaries(
+    generator: &mut Generator,
+    schema: usize,
+    value: &Value,
+    instance: &Value,
+    path: &mut Vec<String>,
+    visiting: &mut Vec<(usize, String)>,
+    acc: &mut Vec<(&'static str, Vec<String>, Mutation)>,
+) -> Result<(), Error> {
+    let fields = match value.as_object() {
+        Some(fields) => fields,
+        None => return Ok(()),
+    };
+    let mut replace = |keyword: &'static str, value: Value| {
+        acc.push((keyword, path.clone(), Mutation::Replace(value)))
+    };
+
+    if let Some(values) = fields.get(ENUM_KEY).and_then(Value::as_array) {
+        for value in values {
+            replace(ENUM_KEY, value.clone());
+        }
+    }
+
+    if instance.is_number() {
+        for (keyword, value) in number_boundaries(fields, instance.is_i64() || instance.is_u64()) {
+            replace(keyword, value);
+        }
+    }
+
+    if let Some(string) = instance.as_str() {
+        if let Some(min_length) = get_usize(fields, MIN_LENGTH_KEY) {
+            replace(MIN_LENGTH_KEY, Value::String(resize(string, min_length)));
+        }
+
+        if let Some(max_length) = get_usize(fields, MAX_LENGTH_KEY) {
+            replace(MAX_LENGTH_KEY, Value::String(resize(string, max_length)));
+        }
+    }
+
+    if let Some(items) = instance.as_array() {
+        let min_items = get_usize(fields, MIN_ITEMS_KEY).unwrap_or(0);
+
+        if min_items < items.len() {
+            replace(MIN_ITEMS_KEY, Value::Array(items[..min_items].to_vec()));
+        }
+
+        if let Some(max_items) = get_usize(fields, MAX_ITEMS_KEY).filter(|max| *max > items.len()) {
+            let prefix = fields
+                .get(PREFIX_ITEMS_KEY)
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let item_schema = fields.get(ITEMS_KEY).unwrap_or(&Value::Bool(true));
+            let mut items = items.clone();
+
+            for index in items.len()..max_items {
+                let item = match prefix.get(index) {
+                    Some(item_schema) => generator.generate(schema, item_schema, 0)?,
+                    None => generator.generate(schema, item_schema, index)?,
+                };
+
+                items.push(item);
+            }
+
+            replace(MAX_ITEMS_KEY, Value::Array(items));
+        }
+    }
+
+    if let Some(properties) = instance.as_object() {
+        let required = fields
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        for name in fields
+            .get(PROPERTIES_KEY)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(Map::keys)
+        {
+            if properties.contains_key(name) && !required.contains(&Value::String(name.clone())) {
+                acc.push((PROPERTIES_KEY, path.clone(), Mutation::Remove(name.clone())));
+            }
+        }
+    }
+
+    if let Some(Value::String(reference)) = fields.get(REF_KEY) {
+        let resolved = resolve(generator.schemas, reference, Some(schema))?;
+        let key = (resolved.schema, resolved.pointer);
+
+        // Recursive schemas are only followed once for each value.
+        if !visiting.contains(&key) {
+            visiting.push(key);
+            collect_boundaries(
+                generator,
+                resolved.schema,
+                resolved.value,
+                instance,
+                path,
+                visiting,
+                acc,
+            )?;
+            visiting.pop();
+        }
+    }
+
+    let branches = fields
+        .get(ALL_OF_KEY)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .chain(
+            [ANY_OF_KEY, ONE_OF_KEY]
+                .iter()
+                .filter_map(|key| fields.get(*key).and_then(Value::as_array)?.first()),
+        );
+
+    for branch in branches {
+        collect_boundaries(generator, schema, branch, instance, path, visiting, acc)?;
+    }
+
+    // Values in the instance are new locations, so references may be followed again.
+    let mut nested = vec![];
+
+    if let (Some(properties), Some(schemas_by_name)) = (
+        instance.as_object(),
+        fields.get(PROPERTIES_KEY).and_then(Value::as_object),
+    ) {
+        for (name, property) in properties {
+            if let Some(value) = schemas_by_name.get(name) {
+                path.push(name.clone());
+                collect_boundaries(generator, schema, value, property, path, &mut nested, acc)?;
+                path.pop();
+            }
+        }
+    }
+
+    if let Some(items) = instance.as_array() {
+        let prefix = fields
+            .get(PREFIX_ITEMS_KEY)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        for (index, item) in items.iter().enumerate() {
+            if let Some(value) = prefix.get(index).or_else(|| fields.get(ITEMS_KEY)) {
+                path.push(index.to_string());
+                collect_boundaries(generator, schema, value, item, path, &mut nested, acc)?;
+                path.pop();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The values at each numeric bound, where exclusive bounds are stepped past by the multiple (or
+/// by one for integers)
+fn number_boundaries(fields: &Map<String, Value>, integer: bool) -> Vec<(&'static str, Value)> {
+    let step = fields
+        .get(MULTIPLE_OF_KEY)
+        .and_then(Value::as_f64)
+        .filter(|step| *step > 0.0)
+        .or(integer.then_some(1.0));
+    let number = |value: f64| {
+        if integer || value.fract() == 0.0 {
+            Value::from(value as i64)
+        } else {
+            serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+        }
+    };
+    let mut result = vec![];
+
+    for (keyword, is_minimum, is_exclusive) in [
+        (MINIMUM_KEY, true, false),
+        (EXCLUSIVE_MINIMUM_KEY, true, true),
+        (MAXIMUM_KEY, false, false),
+        (EXCLUSIVE_MAXIMUM_KEY, false, true),
+    ] {
+        let limit = match fields.get(keyword).and_then(Value::as_f64) {
+            Some(limit) => limit,
+            None => continue,
+        };
+
+        let value = match step {
+            Some(step) => {
+                let multiple = limit / step;
+                let multiple = match (is_minimum, is_exclusive) {
+                    (true, false) => multiple.ceil(),
+                    (true, true) => multiple.floor() + 1.0,
+                    (false, false) => multiple.floor(),
+                    (false, true) => multiple.ceil() - 1.0,
+                };
+
+                multiple * step
+            }
+            None if is_exclusive => continue,
+            None => limit,
+        };
+
+        result.push((keyword, number(value)));
+    }
+
+    result
+}
+
+/// The string cut or padded to a length
+fn resize(string: &str, length: usize) -> String {
+    let mut result = string.chars().take(length).collect::<String>();
+    let padding = length - result.chars().count();
+    result.extend(std::iter::repeat_n('x', padding));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::Validator;
+
+    #[test]
+    fn boundary_instances() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "age": { "type": "integer", "exclusiveMinimum": 17, "maximum": 130 },
+                "ratio": { "type": "number", "minimum": 0.5, "exclusiveMaximum": 1, "multipleOf": 0.25 },
+                "name": { "type": "string", "minLength": 1, "maxLength": 4 },
+                "role": { "enum": ["admin", "user", "guest"] },
+                "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 3, "uniqueItems": true }
+            },
+            "required": ["age", "role"]
+        });
+        let validator = Validator::new(schema.clone()).unwrap();
+        let boundaries = boundaries(&[schema], 0, ExampleOptions::default()).unwrap();
+
+        let labels = boundaries
+            .iter()
+            .map(|boundary| (boundary.keyword, boundary.pointer.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            labels,
+            vec![
+                (PROPERTIES_KEY, ""),
+                (PROPERTIES_KEY, "/ratio"),
+                (PROPERTIES_KEY, "/name"),
+                (PROPERTIES_KEY, "/tags"),
+                (EXCLUSIVE_MINIMUM_KEY, "/age"),
+                (MAXIMUM_KEY, "/age"),
+                (MINIMUM_KEY, "/ratio"),
+                (EXCLUSIVE_MAXIMUM_KEY, "/ratio"),
+                (MIN_LENGTH_KEY, "/name"),
+                (MAX_LENGTH_KEY, "/name"),
+                (ENUM_KEY, "/role"),
+                (ENUM_KEY, "/role"),
+                (ENUM_KEY, "/role"),
+                (MIN_ITEMS_KEY, "/tags"),
+                (MAX_ITEMS_KEY, "/tags"),
+            ]
+        );
+        assert_eq!(boundaries[4].instance["age"], 18);
+        assert_eq!(boundaries[5].instance["age"], 130);
+        assert_eq!(boundaries[7].instance["ratio"], 0.75);
+        assert_eq!(boundaries[8].instance["name"], "e");
+        assert_eq!(boundaries[12].instance["role"], "guest");
+        assert_eq!(boundaries[13].instance["tags"], serde_json::json!([]));
+        assert_eq!(
+            boundaries[14].instance["tags"],
+            serde_json::json!(["example", "example1", "example2"])
+        );
+
+        for boundary in &boundaries {
+            assert!(validator.is_valid(&boundary.instance), "{:?}", boundary);
+        }
+    }
+}
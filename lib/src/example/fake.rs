@@ -0,0 +1,616 @@
+use super::{get_usize, merge, type_name, ExampleOptions};
+use crate::constants::*;
+use crate::resolve::{resolve, Error};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde_json::{Map, Value};
+
+/// Words for strings without a format or pattern
+const WORDS: [&str; 16] = [
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet",
+    "kilo", "lima", "mike", "november", "oscar", "papa",
+];
+
+const DOMAINS: [&str; 3] = ["example.com", "example.org", "example.net"];
+
+/// How many more repetitions than the minimum an unbounded pattern repetition can have
+const EXTRA_REPETITIONS: u32 = 3;
+
+/// How many more items than the minimum an array can have
+const EXTRA_ITEMS: usize = 3;
+
+/// The range of numbers that are generated for a side with no bound
+const DEFAULT_RANGE: f64 = 1000.0;
+
+/// How many times an item is regenerated when it's a duplicate in an array of unique items
+const UNIQUE_ATTEMPTS: usize = 16;
+
+/// Generate random instances of the schema at the given index, resolving references against the
+/// others
+///
+/// The same seed always gives the same instances. Strings match patterns and look like values of
+/// common formats (email addresses, UUIDs, date-times, etc.), numbers are within their bounds, and
+/// each optional property is included half of the time (or always, with `full`). Like `example`,
+/// negations and conditionals are ignored, and a random branch of a `oneOf` may also match
+/// another, so instances should still be validated.
+pub fn fake(
+    schemas: &[Value],
+    schema: usize,
+    options: ExampleOptions,
+    seed: u64,
+    count: usize,
+) -> Result<Vec<Value>, Error> {
+    let mut generator = Generator {
+        schemas,
+        options,
+        rng: Rng(seed),
+        visiting: vec![],
+        minimal: false,
+    };
+
+    (0..count)
+        .map(|_| generator.generate(schema, &schemas[schema]))
+        .collect()
+}
+
+/// A SplitMix64 generator, which is enough for test data
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound` (or zero if the bound is zero)
+    fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            0
+        } else {
+            (self.next_u64() % bound as u64) as usize
+        }
+    }
+
+    /// A number in `minimum..=maximum`
+    fn between(&mut self, minimum: usize, maximum: usize) -> usize {
+        minimum + self.below(maximum.saturating_sub(minimum).saturating_add(1))
+    }
+
+    /// A number in `[0, 1)`
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn chance(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    fn choose<'a, T>(&mut self, values: &'a [T]) -> &'a T {
+        &values[self.below(values.len())]
+    }
+}
+
+struct Generator<'a> {
+    schemas: &'a [Value],
+    options: ExampleOptions,
+    rng: Rng,
+    /// Reference targets currently being generated, to avoid looping on recursive schemas
+    visiting: Vec<(usize, String)>,
+    /// Whether only required properties and items are being generated (inside a recursion)
+    minimal: bool,
+}
+
+impl Generator<'_> {
+    fn generate(&mut self, schema: usize, value: &Value) -> Result<Value, Error> {
+        let fields = match value {
+            Value::Object(fields) => fields,
+            Value::Bool(true) => return Ok(Value::String(self.words(1))),
+            _ => return Ok(Value::Null),
+        };
+
+        if let Some(value) = fields.get(CONST_KEY) {
+            return Ok(value.clone());
+        }
+
+        if let Some(values) = fields.get(ENUM_KEY).and_then(Value::as_array) {
+            if !values.is_empty() {
+                return Ok(self.rng.choose(values).clone());
+            }
+        }
+
+        if let Some(examples) = fields
+            .get(EXAMPLES_KEY)
+            .and_then(Value::as_array)
+            .filter(|examples| !examples.is_empty())
+        {
+            if self.options.use_examples || self.rng.chance() {
+                return Ok(self.rng.choose(examples).clone());
+            }
+        }
+
+        let mut result = match self.type_name(fields) {
+            Some("object") => Value::Object(self.generate_object(schema, fields)?),
+            Some("array") => Value::Array(self.generate_array(schema, fields)?),
+            Some("string") => Value::String(self.generate_string(fields)),
+            Some(name @ ("integer" | "number")) => self.generate_number(fields, name),
+            Some("boolean") => Value::Bool(self.rng.chance()),
+            _ => Value::Null,
+        };
+
+        if let Some(Value::String(reference)) = fields.get(REF_KEY) {
+            let resolved = resolve(self.schemas, reference, Some(schema))?;
+            let key = (resolved.schema, resolved.pointer);
+            let recursive = self.visiting.contains(&key);
+            let minimal = self.minimal;
+
+            // Recursive schemas are only populated once, so they end with minimal instances.
+            self.minimal |= recursive;
+            self.visiting.push(key);
+            let target = self.generate(resolved.schema, resolved.value);
+            self.visiting.pop();
+            self.minimal = minimal;
+
+            merge(&mut result, target?);
+        }
+
+        for branch in fields
+            .get(ALL_OF_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let branch = self.generate(schema, branch)?;
+            merge(&mut result, branch);
+        }
+
+        for key in [ANY_OF_KEY, ONE_OF_KEY] {
+            if let Some(branches) = fields
+                .get(key)
+                .and_then(Value::as_array)
+                .filter(|branches| !branches.is_empty())
+            {
+                // Only the first branch is used in a recursion, since it's the most likely to end.
+                let branch = if self.minimal {
+                    &branches[0]
+                } else {
+                    self.rng.choose(branches)
+                };
+                let branch = self.generate(schema, branch)?;
+                merge(&mut result, branch);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The declared type (chosen at random if there are several), or the implied type
+    fn type_name<'b>(&mut self, fields: &'b Map<String, Value>) -> Option<&'b str> {
+        match fields.get(TYPE_KEY) {
+            Some(Value::Array(names)) => {
+                let names = names.iter().filter_map(Value::as_str).collect::<Vec<_>>();
+                let non_null = names
+                    .iter()
+                    .copied()
+                    .filter(|name| *name != "null")
+                    .collect::<Vec<_>>();
+
+                if non_null.is_empty() {
+                    names.first().copied()
+                } else {
+                    Some(*self.rng.choose(&non_null))
+                }
+            }
+            _ => type_name(fields),
+        }
+    }
+
+    fn generate_object(
+        &mut self,
+        schema: usize,
+        fields: &Map<String, Value>,
+    ) -> Result<Map<String, Value>, Error> {
+        let properties = fields.get(PROPERTIES_KEY).and_then(Value::as_object);
+        let required = fields
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>();
+        let mut result = Map::new();
+
+        for (name, property) in properties.into_iter().flatten() {
+            let include = required.contains(&name.as_str())
+                || (!self.minimal && (self.options.full || self.rng.chance()));
+
+            if include {
+                result.insert(name.clone(), self.generate(schema, property)?);
+            }
+        }
+
+        for name in required {
+            if !result.contains_key(name) {
+                let value = match fields.get(ADDITIONAL_PROPERTIES_KEY) {
+                    Some(additional) => self.generate(schema, additional)?,
+                    None => Value::Null,
+                };
+
+                result.insert(name.to_string(), value);
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn generate_array(
+        &mut self,
+        schema: usize,
+        fields: &Map<String, Value>,
+    ) -> Result<Vec<Value>, Error> {
+        let prefix = fields
+            .get(PREFIX_ITEMS_KEY)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let items = fields
+            .get(ITEMS_KEY)
+            .filter(|items| **items != Value::Bool(false));
+        let min_items = get_usize(fields, MIN_ITEMS_KEY).unwrap_or(0);
+        let max_items = get_usize(fields, MAX_ITEMS_KEY).unwrap_or(usize::MAX);
+        let available = prefix.len() + if items.is_some() { usize::MAX } else { 0 };
+        let unique = fields.get(UNIQUE_ITEMS_KEY) == Some(&Value::Bool(true));
+
+        let count = if self.minimal {
+            min_items
+        } else {
+            let minimum = if self.options.full {
+                min_items.max(1)
+            } else {
+                min_items
+            };
+
+            self.rng
+                .between(minimum, min_items.saturating_add(EXTRA_ITEMS).max(minimum))
+        }
+        .min(max_items)
+        .min(available);
+
+        let mut result: Vec<Value> = Vec::with_capacity(count);
+
+        for index in 0..count {
+            let item_schema = prefix.get(index).or(items).unwrap_or(&Value::Bool(true));
+            let mut item = self.generate(schema, item_schema)?;
+
+            for _ in 0..UNIQUE_ATTEMPTS {
+                if !unique || !result.contains(&item) {
+                    break;
+                }
+
+                item = self.generate(schema, item_schema)?;
+            }
+
+            // Items that can't be made unique are left out if they aren't needed.
+            if unique && result.contains(&item) && index >= min_items.max(prefix.len()) {
+                break;
+            }
+
+            result.push(item);
+        }
+
+        Ok(result)
+    }
+
+    fn generate_string(&mut self, fields: &Map<String, Value>) -> String {
+        let min_length = get_usize(fields, MIN_LENGTH_KEY).unwrap_or(0);
+        let max_length = get_usize(fields, MAX_LENGTH_KEY).unwrap_or(usize::MAX);
+
+        let mut result = if let Some(pattern) = fields.get(PATTERN_KEY).and_then(Value::as_str) {
+            // Patterns that can't be parsed are reported by validation.
+            match regex_syntax::parse(pattern) {
+                Ok(hir) => self.generate_match(&hir),
+                Err(_) => String::new(),
+            }
+        } else if let Some(value) = fields
+            .get(FORMAT_KEY)
+            .and_then(Value::as_str)
+            .and_then(|format| self.format_value(format))
+        {
+            return value;
+        } else {
+            let count = self.rng.between(1, 3);
+            let mut words = self.words(count);
+
+            // Short limits are filled with single words rather than truncated phrases.
+            if words.chars().count() > max_length {
+                words = self.words(1);
+            }
+
+            words
+        };
+
+        let length = result.chars().count();
+
+        if length < min_length {
+            while result.chars().count() < min_length {
+                result.push(char::from(b'a' + self.rng.below(26) as u8));
+            }
+        } else if length > max_length {
+            result = result.chars().take(max_length).collect();
+        }
+
+        result
+    }
+
+    /// A number within the bounds, which are treated as inclusive by stepping past exclusive ones
+    fn generate_number(&mut self, fields: &Map<String, Value>, type_name: &str) -> Value {
+        let integer = type_name == "integer";
+        let step = fields
+            .get(MULTIPLE_OF_KEY)
+            .and_then(Value::as_f64)
+            .filter(|step| *step > 0.0);
+        let get = |key| fields.get(key).and_then(Value::as_f64);
+
+        // The smallest increment that makes an exclusive bound inclusive.
+        let epsilon = step.unwrap_or(if integer { 1.0 } else { 0.01 });
+        let minimum = get(MINIMUM_KEY)
+            .into_iter()
+            .chain(get(EXCLUSIVE_MINIMUM_KEY).map(|minimum| minimum + epsilon))
+            .reduce(f64::max);
+        let maximum = get(MAXIMUM_KEY)
+            .into_iter()
+            .chain(get(EXCLUSIVE_MAXIMUM_KEY).map(|maximum| maximum - epsilon))
+            .reduce(f64::min);
+
+        let (minimum, maximum) = match (minimum, maximum) {
+            (Some(minimum), Some(maximum)) => (minimum, maximum.max(minimum)),
+            (Some(minimum), None) => (minimum, minimum + DEFAULT_RANGE),
+            (None, Some(maximum)) => ((maximum - DEFAULT_RANGE).min(0.0), maximum),
+            (None, None) => (0.0, DEFAULT_RANGE),
+        };
+        let minimum = minimum.min(maximum);
+
+        match step {
+            Some(step) => {
+                let first = (minimum / step).ceil();
+                let last = (maximum / step).floor().max(first);
+                let multiple = first
+                    + self
+                        .rng
+                        .between(0, (last - first).min(usize::MAX as f64) as usize)
+                        as f64;
+
+                number(multiple * step, integer)
+            }
+            None if integer => {
+                let first = minimum.ceil() as i64;
+                let last = (maximum.floor() as i64).max(first);
+
+                Value::from(first + self.rng.below((last - first + 1) as usize) as i64)
+            }
+            None => {
+                // Two decimal places, kept within the bounds.
+                let value = minimum + self.rng.unit() * (maximum - minimum);
+                let rounded = (value * 100.0).round() / 100.0;
+
+                number(rounded.clamp(minimum, maximum), false)
+            }
+        }
+    }
+
+    /// A random string matching a regular expression
+    fn generate_match(&mut self, hir: &Hir) -> String {
+        match hir.kind() {
+            HirKind::Literal(literal) => String::from_utf8_lossy(&literal.0).to_string(),
+            HirKind::Class(Class::Unicode(class)) => {
+                // Printable ASCII is preferred where the class allows it.
+                let ranges = class
+                    .ranges()
+                    .iter()
+                    .filter_map(|range| {
+                        let start = range.start().max(' ');
+                        let end = range.end().min('~');
+
+                        (start <= end).then_some((start, end))
+                    })
+                    .collect::<Vec<_>>();
+
+                let (start, end) = match ranges.as_slice() {
+                    [] => match class.ranges().first() {
+                        Some(range) => (range.start(), range.end()),
+                        None => return String::new(),
+                    },
+                    ranges => *self.rng.choose(ranges),
+                };
+                let offset = self.rng.between(0, end as usize - start as usize) as u32;
+
+                char::from_u32(start as u32 + offset)
+                    .unwrap_or(start)
+                    .to_string()
+            }
+            HirKind::Class(Class::Bytes(class)) => match class.ranges() {
+                [] => String::new(),
+                ranges => {
+                    let range = self.rng.choose(ranges);
+                    let byte = self
+                        .rng
+                        .between(range.start() as usize, range.end() as usize);
+
+                    char::from(byte as u8).to_string()
+                }
+            },
+            HirKind::Repetition(repetition) => {
+                let maximum = repetition
+                    .max
+                    .unwrap_or(repetition.min.saturating_add(EXTRA_REPETITIONS));
+                let count = self.rng.between(repetition.min as usize, maximum as usize);
+
+                (0..count)
+                    .map(|_| self.generate_match(&repetition.sub))
+                    .collect()
+            }
+            HirKind::Capture(capture) => self.generate_match(&capture.sub),
+            HirKind::Concat(hirs) => hirs.iter().map(|hir| self.generate_match(hir)).collect(),
+            HirKind::Alternation(hirs) => match hirs.as_slice() {
+                [] => String::new(),
+                hirs => {
+                    let hir = self.rng.choose(hirs);
+                    self.generate_match(hir)
+                }
+            },
+            HirKind::Empty | HirKind::Look(_) => String::new(),
+        }
+    }
+
+    /// A realistic value for a format
+    fn format_value(&mut self, format: &str) -> Option<String> {
+        Some(match format {
+            "date-time" => format!("{}T{}Z", self.date(), self.time()),
+            "date" => self.date(),
+            "time" => format!("{}Z", self.time()),
+            "duration" => format!("P{}DT{}H", self.rng.between(0, 30), self.rng.between(0, 23)),
+            "email" | "idn-email" => format!(
+                "{}.{}@{}",
+                self.rng.choose(&WORDS),
+                self.rng.choose(&WORDS),
+                self.rng.choose(&DOMAINS)
+            ),
+            "hostname" | "idn-hostname" => {
+                format!("{}.{}", self.rng.choose(&WORDS), self.rng.choose(&DOMAINS))
+            }
+            "ipv4" => format!(
+                "{}.{}.{}.{}",
+                self.rng.between(1, 223),
+                self.rng.below(256),
+                self.rng.below(256),
+                self.rng.between(1, 254)
+            ),
+            "ipv6" => format!(
+                "2001:db8::{:x}:{:x}",
+                self.rng.below(0x10000),
+                self.rng.below(0x10000)
+            ),
+            "uri" | "iri" => format!(
+                "https://{}/{}",
+                self.rng.choose(&DOMAINS),
+                self.rng.choose(&WORDS)
+            ),
+            "uri-reference" | "iri-reference" | "json-pointer" => {
+                format!("/{}/{}", self.rng.choose(&WORDS), self.rng.choose(&WORDS))
+            }
+            "uuid" => {
+                let high = self.rng.next_u64();
+                let low = self.rng.next_u64();
+
+                // Version 4 (random), with the RFC 4122 variant.
+                format!(
+                    "{:08x}-{:04x}-4{:03x}-{:x}{:03x}-{:012x}",
+                    high >> 32,
+                    (high >> 16) & 0xffff,
+                    high & 0xfff,
+                    8 + (low >> 62),
+                    (low >> 48) & 0xfff,
+                    low & 0xffff_ffff_ffff
+                )
+            }
+            "relative-json-pointer" => self.rng.below(4).to_string(),
+            _ => return None,
+        })
+    }
+
+    fn date(&mut self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}",
+            self.rng.between(2000, 2030),
+            self.rng.between(1, 12),
+            self.rng.between(1, 28)
+        )
+    }
+
+    fn time(&mut self) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            self.rng.below(24),
+            self.rng.below(60),
+            self.rng.below(60)
+        )
+    }
+
+    fn words(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| *self.rng.choose(&WORDS))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn number(value: f64, integer: bool) -> Value {
+    if integer || value.fract() == 0.0 {
+        Value::from(value as i64)
+    } else {
+        serde_json::Number::from_f64(value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::Validator;
+
+    #[test]
+    fn fake_instances() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "code": { "type": "string", "pattern": "^[A-Z]{2}-[0-9]{3,5}$" },
+                "email": { "type": "string", "format": "email" },
+                "created": { "type": "string", "format": "date-time" },
+                "age": { "type": "integer", "exclusiveMinimum": 17, "maximum": 130 },
+                "ratio": { "type": "number", "minimum": 0, "exclusiveMaximum": 1 },
+                "price": { "type": "number", "multipleOf": 0.25, "minimum": 1, "maximum": 10 },
+                "role": { "enum": ["admin", "user"] },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string", "maxLength": 8 },
+                    "uniqueItems": true,
+                    "minItems": 1
+                },
+                "manager": { "$ref": "#" }
+            },
+            "required": ["id", "code", "email", "created", "age", "ratio", "price", "role", "tags"],
+            "additionalProperties": false
+        });
+        let validator = Validator::new(schema.clone()).unwrap();
+        let schemas = [schema];
+
+        let instances = fake(&schemas, 0, ExampleOptions::default(), 7, 50).unwrap();
+
+        for instance in &instances {
+            assert!(
+                validator.is_valid(instance),
+                "{} {:?}",
+                instance,
+                validator.validate(instance)
+            );
+        }
+
+        assert_eq!(
+            instances,
+            fake(&schemas, 0, ExampleOptions::default(), 7, 50).unwrap()
+        );
+        assert_ne!(
+            instances,
+            fake(&schemas, 0, ExampleOptions::default(), 8, 50).unwrap()
+        );
+        assert!(instances[1..]
+            .iter()
+            .any(|instance| instance != &instances[0]));
+        assert!(instances
+            .iter()
+            .any(|instance| instance.get("manager").is_some()));
+    }
+}
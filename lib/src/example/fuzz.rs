@@ -0,0 +1,206 @@
+use super::{boundaries, example, fake, format_example, ExampleOptions};
+use crate::constants::*;
+use crate::resolve::Error;
+use serde_json::Value;
+
+/// Keys whose values are maps from property names to schemas
+const PROPERTY_MAP_KEYS: [&str; 2] = [PROPERTIES_KEY, DEPENDENT_SCHEMAS_KEY];
+
+/// Keys whose values are literal instance data
+const LITERAL_KEYS: [&str; 4] = [CONST_KEY, DEFAULT_KEY, ENUM_KEY, EXAMPLES_KEY];
+
+/// A libFuzzer and AFL dictionary of the tokens that appear in instances of a schema: quoted
+/// property names, serialized scalar `const`, `default`, `enum`, and `examples` values, and
+/// serialized examples of each `format`
+///
+/// Each token is on its own line with a name (like `key_1="\"id\""`), and bytes that aren't
+/// printable ASCII are escaped.
+pub fn fuzz_dictionary(schema: &Value) -> String {
+    let mut tokens = vec![];
+    collect_tokens(schema, &mut tokens);
+
+    let mut counts = [0; 3];
+    let mut result = String::new();
+
+    for (kind, token) in tokens {
+        let index = ["key", "literal", "format"]
+            .iter()
+            .position(|name| *name == kind)
+            .unwrap_or(0);
+        counts[index] += 1;
+
+        result.push_str(&format!(
+            "{}_{}=\"{}\"\n",
+            kind,
+            counts[index],
+            escape(&token)
+        ));
+    }
+
+    result
+}
+
+/// Valid-looking instances of the schema at the given index for seeding a fuzzer: a minimal and
+/// a full example, an instance at each boundary, and random instances, without duplicates
+///
+/// Like the generators, this doesn't handle every keyword, so instances should be validated.
+pub fn seed_corpus(
+    schemas: &[Value],
+    schema: usize,
+    seed: u64,
+    count: usize,
+) -> Result<Vec<Value>, Error> {
+    let minimal = ExampleOptions::default();
+    let full = ExampleOptions {
+        full: true,
+        ..minimal
+    };
+
+    let mut candidates = vec![
+        example(schemas, schema, minimal)?,
+        example(schemas, schema, full)?,
+    ];
+
+    candidates.extend(
+        boundaries(schemas, schema, minimal)?
+            .into_iter()
+            .map(|boundary| boundary.instance),
+    );
+    candidates.extend(fake(schemas, schema, minimal, seed, count)?);
+
+    let mut result: Vec<Value> = vec![];
+
+    for candidate in candidates {
+        if !result.contains(&candidate) {
+            result.push(candidate);
+        }
+    }
+
+    Ok(result)
+}
+
+fn collect_tokens(schema: &Value, tokens: &mut Vec<(&'static str, String)>) {
+    let mut add = |kind: &'static str, token: String| {
+        if !tokens.iter().any(|(_, other)| *other == token) {
+            tokens.push((kind, token));
+        }
+    };
+
+    for (key, value) in schema.as_object().into_iter().flatten() {
+        match key.as_str() {
+            key if PROPERTY_MAP_KEYS.contains(&key) => {
+                for name in value
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, _)| name)
+                {
+                    add("key", Value::String(name.clone()).to_string());
+                }
+            }
+            REQUIRED_KEY => {
+                for name in value.as_array().into_iter().flatten() {
+                    add("key", name.to_string());
+                }
+            }
+            ENUM_KEY | EXAMPLES_KEY => {
+                for literal in value.as_array().into_iter().flatten() {
+                    if !literal.is_array() && !literal.is_object() {
+                        add("literal", literal.to_string());
+                    }
+                }
+            }
+            CONST_KEY | DEFAULT_KEY if !value.is_array() && !value.is_object() => {
+                add("literal", value.to_string());
+            }
+            FORMAT_KEY => {
+                if let Some(example) = value.as_str().and_then(format_example) {
+                    add("format", Value::String(example.to_string()).to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (key, value) in schema.as_object().into_iter().flatten() {
+        match key.as_str() {
+            key if SCHEMA_MAP_KEYS.contains(&key) => {
+                for schema in value.as_object().into_iter().flatten().map(|(_, v)| v) {
+                    collect_tokens(schema, tokens);
+                }
+            }
+            key if LITERAL_KEYS.contains(&key) => {}
+            _ => match value.as_array() {
+                Some(schemas) => {
+                    for schema in schemas {
+                        collect_tokens(schema, tokens);
+                    }
+                }
+                None => collect_tokens(value, tokens),
+            },
+        }
+    }
+}
+
+/// Escape a token for a dictionary, where backslashes, quotes, and bytes that aren't printable
+/// ASCII must be escaped
+fn escape(token: &str) -> String {
+    token
+        .bytes()
+        .map(|byte| match byte {
+            b'\\' | b'"' => format!("\\{}", byte as char),
+            0x20..=0x7e => (byte as char).to_string(),
+            _ => format!("\\x{:02x}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dictionary_and_corpus() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "size": { "enum": ["small", "large", 3] },
+                "label": { "$ref": "#/$defs/label" }
+            },
+            "required": ["id"],
+            "$defs": {
+                "label": { "type": "string", "const": "naïve" }
+            }
+        });
+
+        assert_eq!(
+            fuzz_dictionary(&schema),
+            [
+                r#"key_1="\"id\"""#,
+                r#"key_2="\"size\"""#,
+                r#"key_3="\"label\"""#,
+                r#"format_1="\"00000000-0000-4000-8000-000000000000\"""#,
+                r#"literal_1="\"small\"""#,
+                r#"literal_2="\"large\"""#,
+                r#"literal_3="3""#,
+                r#"literal_4="\"na\xc3\xafve\"""#,
+                "",
+            ]
+            .join("\n")
+        );
+
+        let corpus = seed_corpus(std::slice::from_ref(&schema), 0, 0, 10).unwrap();
+
+        assert_eq!(
+            corpus[0],
+            json!({ "id": "00000000-0000-4000-8000-000000000000" })
+        );
+        assert!(corpus.len() > 2);
+        assert!(corpus
+            .iter()
+            .enumerate()
+            .all(|(index, instance)| !corpus[..index].contains(instance)));
+    }
+}
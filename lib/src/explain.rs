@@ -0,0 +1,420 @@
+use super::constants::*;
+use super::reference::Reference;
+use super::resolve::{resolve, Error};
+use serde_json::Value;
+
+/// Describe a subschema in English, following references
+///
+/// The target is a definition name or a JSON Pointer (empty for the whole schema) into the schema
+/// at the given index, and references are resolved against the other schemas by `$id`.
+pub fn explain(schemas: &[Value], schema: usize, target: &str) -> Result<String, Error> {
+    let pointer = if target.is_empty() || target.starts_with('/') || target.starts_with('#') {
+        target.strip_prefix('#').unwrap_or(target).to_string()
+    } else {
+        format!("/{}/{}", DEFS_KEY, target)
+    };
+
+    let resolved = resolve(schemas, &format!("#{}", pointer), Some(schema))?;
+    let mut explainer = Explainer {
+        schemas,
+        visiting: vec![(resolved.schema, resolved.pointer)],
+    };
+
+    Ok(explainer.describe(resolved.schema, resolved.value).phrase())
+}
+
+/// A type name (if there is a single one) and the qualifications that follow it
+struct Description {
+    noun: Option<String>,
+    qualifiers: Vec<String>,
+}
+
+impl Description {
+    fn phrase(&self) -> String {
+        let noun = self.noun.as_deref().unwrap_or("value");
+
+        let mut result = match article(noun) {
+            Some(article) => format!("{} {}", article, noun),
+            None => noun.to_string(),
+        };
+
+        if !self.qualifiers.is_empty() {
+            result.push(' ');
+            result.push_str(&self.qualifiers.join(" and "));
+        }
+
+        result
+    }
+}
+
+struct Explainer<'a> {
+    schemas: &'a [Value],
+    /// Reference targets currently being described, to avoid looping on recursive schemas
+    visiting: Vec<(usize, String)>,
+}
+
+impl Explainer<'_> {
+    fn describe(&mut self, schema: usize, value: &Value) -> Description {
+        let fields = match value {
+            Value::Object(fields) => fields,
+            Value::Bool(false) => {
+                return Description {
+                    noun: None,
+                    qualifiers: vec!["that is never valid".to_string()],
+                }
+            }
+            _ => {
+                return Description {
+                    noun: None,
+                    qualifiers: vec![],
+                }
+            }
+        };
+
+        let mut description = match fields.get(REF_KEY).and_then(Value::as_str) {
+            Some(reference) => self.describe_reference(schema, reference),
+            None => Description {
+                noun: None,
+                qualifiers: vec![],
+            },
+        };
+
+        if description.noun.is_none() {
+            description.noun = type_noun(fields);
+        }
+
+        self.qualify(schema, fields, &mut description.qualifiers);
+
+        description
+    }
+
+    fn describe_reference(&mut self, schema: usize, reference: &str) -> Description {
+        let name = || {
+            let name = reference
+                .parse::<Reference>()
+                .map(|parsed| parsed.name().to_string())
+                .unwrap_or_else(|_| reference.to_string());
+
+            Description {
+                noun: Some(format!("`{}`", name)),
+                qualifiers: vec![],
+            }
+        };
+
+        match resolve(self.schemas, reference, Some(schema)) {
+            Ok(resolved) => {
+                let key = (resolved.schema, resolved.pointer);
+
+                if self.visiting.contains(&key) {
+                    return name();
+                }
+
+                self.visiting.push(key);
+                let description = self.describe(resolved.schema, resolved.value);
+                self.visiting.pop();
+
+                description
+            }
+            Err(_) => name(),
+        }
+    }
+
+    fn qualify(
+        &mut self,
+        schema: usize,
+        fields: &serde_json::Map<String, Value>,
+        qualifiers: &mut Vec<String>,
+    ) {
+        let number = |key| fields.get(key).filter(|value| value.is_number());
+
+        match (number(MINIMUM_KEY), number(MAXIMUM_KEY)) {
+            (Some(minimum), Some(maximum)) => {
+                qualifiers.push(format!("between {} and {}", minimum, maximum))
+            }
+            (Some(minimum), None) => qualifiers.push(format!("at least {}", minimum)),
+            (None, Some(maximum)) => qualifiers.push(format!("at most {}", maximum)),
+            (None, None) => {}
+        }
+
+        if let Some(minimum) = number(EXCLUSIVE_MINIMUM_KEY) {
+            qualifiers.push(format!("greater than {}", minimum));
+        }
+
+        if let Some(maximum) = number(EXCLUSIVE_MAXIMUM_KEY) {
+            qualifiers.push(format!("less than {}", maximum));
+        }
+
+        if let Some(divisor) = number(MULTIPLE_OF_KEY) {
+            qualifiers.push(format!("divisible by {}", divisor));
+        }
+
+        match (number(MIN_LENGTH_KEY), number(MAX_LENGTH_KEY)) {
+            (Some(minimum), Some(maximum)) => {
+                qualifiers.push(format!("with length between {} and {}", minimum, maximum))
+            }
+            (Some(minimum), None) => qualifiers.push(format!("with length at least {}", minimum)),
+            (None, Some(maximum)) => qualifiers.push(format!("with length at most {}", maximum)),
+            (None, None) => {}
+        }
+
+        if let Some(pattern) = fields.get(PATTERN_KEY).and_then(Value::as_str) {
+            qualifiers.push(format!("matching `{}`", pattern));
+        }
+
+        if let Some(format) = fields.get(FORMAT_KEY).and_then(Value::as_str) {
+            qualifiers.push(format!("in `{}` format", format));
+        }
+
+        if let Some(values) = fields.get(ENUM_KEY).and_then(Value::as_array) {
+            let values = values
+                .iter()
+                .map(|value| format!("`{}`", value))
+                .collect::<Vec<_>>();
+
+            qualifiers.push(format!("that is one of {}", join(&values, "or")));
+        }
+
+        if let Some(value) = fields.get(CONST_KEY) {
+            qualifiers.push(format!("equal to `{}`", value));
+        }
+
+        if let Some(items) = fields.get(PREFIX_ITEMS_KEY).and_then(Value::as_array) {
+            let items = items
+                .iter()
+                .map(|item| self.describe(schema, item).phrase())
+                .collect::<Vec<_>>();
+
+            qualifiers.push(format!("starting with {}", items.join(", ")));
+        }
+
+        if let Some(items) = fields.get(ITEMS_KEY).filter(|items| !items.is_array()) {
+            qualifiers.push(format!(
+                "where every item is {}",
+                self.describe(schema, items).phrase()
+            ));
+        }
+
+        if let Some(contains) = fields.get(CONTAINS_KEY) {
+            qualifiers.push(format!(
+                "containing {}",
+                self.describe(schema, contains).phrase()
+            ));
+        }
+
+        if let Some(count) = count(fields, MIN_ITEMS_KEY, MAX_ITEMS_KEY, "item", "items") {
+            qualifiers.push(count);
+        }
+
+        if fields.get(UNIQUE_ITEMS_KEY) == Some(&Value::Bool(true)) {
+            qualifiers.push("with unique items".to_string());
+        }
+
+        self.qualify_properties(schema, fields, qualifiers);
+
+        if let Some(count) = count(
+            fields,
+            MIN_PROPERTIES_KEY,
+            MAX_PROPERTIES_KEY,
+            "property",
+            "properties",
+        ) {
+            qualifiers.push(count);
+        }
+
+        for (keyword, prefix, conjunction) in [
+            (ALL_OF_KEY, "that is", "and"),
+            (ANY_OF_KEY, "that is", "or"),
+            (ONE_OF_KEY, "that is exactly one of", "or"),
+        ] {
+            if let Some(branches) = fields.get(keyword).and_then(Value::as_array) {
+                let branches = branches
+                    .iter()
+                    .map(|branch| self.describe(schema, branch).phrase())
+                    .collect::<Vec<_>>();
+
+                qualifiers.push(format!("{} {}", prefix, join(&branches, conjunction)));
+            }
+        }
+
+        if let Some(negated) = fields.get(NOT_KEY) {
+            qualifiers.push(format!(
+                "that is not {}",
+                self.describe(schema, negated).phrase()
+            ));
+        }
+    }
+
+    fn qualify_properties(
+        &mut self,
+        schema: usize,
+        fields: &serde_json::Map<String, Value>,
+        qualifiers: &mut Vec<String>,
+    ) {
+        let required = fields
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let properties = fields.get(PROPERTIES_KEY).and_then(Value::as_object);
+
+        let mut phrases = vec![];
+
+        for (name, property) in properties.into_iter().flatten() {
+            let description = self.describe(schema, property);
+            let mut phrase = if required.contains(&name.as_str()) {
+                "required ".to_string()
+            } else {
+                "optional ".to_string()
+            };
+
+            if let Some(noun) = &description.noun {
+                phrase.push_str(noun);
+                phrase.push(' ');
+            }
+
+            phrase.push_str(&format!("`{}`", name));
+
+            if !description.qualifiers.is_empty() {
+                phrase.push(' ');
+                phrase.push_str(&description.qualifiers.join(" and "));
+            }
+
+            phrases.push(phrase);
+        }
+
+        for name in required {
+            if !properties.is_some_and(|properties| properties.contains_key(name)) {
+                phrases.push(format!("required `{}`", name));
+            }
+        }
+
+        if !phrases.is_empty() {
+            qualifiers.push(format!("with {}", phrases.join(", ")));
+        }
+
+        match fields.get(ADDITIONAL_PROPERTIES_KEY) {
+            Some(Value::Bool(false)) => qualifiers.push("no other properties".to_string()),
+            Some(Value::Bool(true)) | None => {}
+            Some(values) => {
+                let phrase = self.describe(schema, values).phrase();
+
+                qualifiers.push(if phrases.is_empty() {
+                    format!("where every property is {}", phrase)
+                } else {
+                    format!("where every other property is {}", phrase)
+                });
+            }
+        }
+    }
+}
+
+/// The name of the schema's type, if it has a single one (which may include `null`)
+fn type_noun(fields: &serde_json::Map<String, Value>) -> Option<String> {
+    match fields.get(TYPE_KEY) {
+        Some(Value::String(name)) => Some(name.clone()),
+        Some(Value::Array(names)) => {
+            let names = names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+
+            if names.is_empty() {
+                None
+            } else {
+                Some(join(&names, "or"))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn count(
+    fields: &serde_json::Map<String, Value>,
+    minimum_key: &str,
+    maximum_key: &str,
+    singular: &str,
+    plural: &str,
+) -> Option<String> {
+    let get = |key| fields.get(key).and_then(Value::as_u64);
+    let noun = |count| if count == 1 { singular } else { plural };
+
+    match (get(minimum_key), get(maximum_key)) {
+        (Some(minimum), Some(maximum)) => Some(format!(
+            "with between {} and {} {}",
+            minimum,
+            maximum,
+            noun(maximum)
+        )),
+        (Some(minimum), None) => Some(format!("with at least {} {}", minimum, noun(minimum))),
+        (None, Some(maximum)) => Some(format!("with at most {} {}", maximum, noun(maximum))),
+        (None, None) => None,
+    }
+}
+
+/// Join phrases as in `a, b, or c`
+fn join(phrases: &[String], conjunction: &str) -> String {
+    match phrases {
+        [] => String::new(),
+        [phrase] => phrase.clone(),
+        [first, second] => format!("{} {} {}", first, conjunction, second),
+        [init @ .., last] => format!("{}, {} {}", init.join(", "), conjunction, last),
+    }
+}
+
+fn article(noun: &str) -> Option<&'static str> {
+    match noun.trim_start_matches('`').chars().next() {
+        _ if noun.starts_with("null") => None,
+        Some('a' | 'e' | 'i' | 'o' | 'u' | 'A' | 'E' | 'I' | 'O' | 'U') => Some("an"),
+        _ => Some("a"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explain_subschemas() {
+        let schemas = vec![
+            serde_json::json!({
+                "$id": "https://example.com/schemas/user",
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "pattern": "^[a-z]+$" },
+                    "count": { "type": "integer", "minimum": 0, "maximum": 10 }
+                },
+                "required": ["id"],
+                "$defs": {
+                    "node": {
+                        "type": "object",
+                        "properties": {
+                            "children": { "type": "array", "items": { "$ref": "#/$defs/node" } },
+                            "team": { "$ref": "team#/$defs/team" }
+                        },
+                        "additionalProperties": false
+                    }
+                }
+            }),
+            serde_json::json!({
+                "$id": "https://example.com/schemas/team",
+                "$defs": { "team": { "enum": ["a", "b"] } }
+            }),
+        ];
+
+        assert_eq!(
+            explain(&schemas, 0, "").unwrap(),
+            "an object with required string `id` matching `^[a-z]+$`, \
+             optional integer `count` between 0 and 10"
+        );
+        assert_eq!(
+            explain(&schemas, 0, "node").unwrap(),
+            "an object with optional array `children` where every item is a `node`, \
+             optional `team` that is one of `\"a\"` or `\"b\"` and no other properties"
+        );
+        assert_eq!(
+            explain(&schemas, 0, "missing"),
+            Err(Error::UnresolvableFragment("/$defs/missing".to_string()))
+        );
+    }
+}
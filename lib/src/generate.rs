@@ -0,0 +1,187 @@
+//! Generating types, validators, and other schema languages from schemas
+//!
+//! Generators that declare a type (or rule) for each schema name the one for the top-level schema
+//! after its title, or else the name they're given, and the others after their definitions, in
+//! the target's naming convention and with numbers appended to names that would otherwise clash.
+//! Only local references are followed, so schemas should be composed first, and each generator
+//! documents what it uses for references to other files.
+
+use super::reference::Reference;
+use super::schema::{Schema, SchemaFile};
+use std::collections::{HashMap, HashSet};
+
+mod avro;
+mod cddl;
+mod csharp;
+mod go;
+mod graphql;
+mod java;
+mod jsonld;
+mod jtd;
+mod proptest;
+mod proto;
+mod python;
+mod sql;
+mod xsd;
+mod zod;
+
+pub use avro::avro;
+pub use cddl::cddl;
+pub use csharp::csharp;
+pub use go::go;
+pub use graphql::graphql;
+pub use java::java;
+pub use jsonld::jsonld;
+pub use jtd::{jtd, Jtd, LossyConstruct};
+pub use proptest::proptest;
+pub use proto::{proto, Proto, UnmappedConstraint};
+pub use python::pydantic;
+pub use sql::postgres;
+pub use xsd::xsd;
+pub use zod::zod;
+
+/// The definition in the file that a reference points to, if it's local
+fn local_definition<'a>(file: &'a SchemaFile, reference: &str) -> Option<(&'a str, &'a Schema)> {
+    let reference = reference.parse::<Reference>().ok()?;
+    let is_local = match &reference {
+        Reference::FragmentOnly { .. } => true,
+        Reference::Both { .. } => reference.path() == file.metadata.id,
+        Reference::PathOnly { .. } => false,
+    };
+
+    if is_local {
+        file.definitions
+            .as_ref()?
+            .get_key_value(reference.name())
+            .map(|(name, schema)| (name.as_str(), schema))
+    } else {
+        None
+    }
+}
+
+/// Split a name into words at non-alphanumeric characters and lowercase-to-uppercase boundaries
+fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut previous = None;
+
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        } else {
+            let is_boundary = c.is_ascii_uppercase()
+                && previous.is_some_and(|previous: char| {
+                    previous.is_ascii_lowercase() || previous.is_ascii_digit()
+                });
+
+            if is_boundary && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+
+            current.push(c);
+        }
+
+        previous = Some(c);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// `OrderItem` for `order_item` or `orderItem` (acronyms are kept as they are)
+fn pascal_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// `order_item` for `OrderItem` or `orderItem`
+fn snake_case(name: &str) -> String {
+    words(name)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `ORDER_ITEM` for `OrderItem` or `order-item`
+fn screaming_snake_case(name: &str) -> String {
+    snake_case(name).to_ascii_uppercase()
+}
+
+/// The pattern matching the whole string, for languages where patterns are anchored (JSON Schema
+/// patterns can match anywhere)
+fn anchored(pattern: &str) -> String {
+    let (start, pattern) = match pattern.strip_prefix('^') {
+        Some(pattern) => ("", pattern),
+        None => (".*", pattern),
+    };
+    let (pattern, end) = match pattern.strip_suffix('$') {
+        Some(pattern) if !pattern.ends_with('\\') => (pattern, ""),
+        _ => (pattern, ".*"),
+    };
+
+    if start.is_empty() && end.is_empty() {
+        pattern.to_string()
+    } else {
+        format!("{}({}){}", start, pattern, end)
+    }
+}
+
+/// Type names for definitions, by definition name
+type DefinitionNames = HashMap<String, String>;
+
+/// Names that have been used in some scope
+#[derive(Debug, Default)]
+struct Names {
+    used: HashSet<String>,
+}
+
+impl Names {
+    /// The name, with a number appended if it's already been used
+    fn unique(&mut self, name: String) -> String {
+        let mut candidate = name.clone();
+        let mut suffix = 2;
+
+        while self.used.contains(&candidate) {
+            candidate = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+
+        self.used.insert(candidate.clone());
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_cases() {
+        assert_eq!(
+            words("orderID2fa-value_x"),
+            vec!["order", "ID2fa", "value", "x"]
+        );
+        assert_eq!(pascal_case("order_item"), "OrderItem");
+        assert_eq!(pascal_case("HTTPStatus"), "HTTPStatus");
+        assert_eq!(snake_case("orderItem"), "order_item");
+        assert_eq!(screaming_snake_case("in-progress"), "IN_PROGRESS");
+
+        let mut names = Names::default();
+        assert_eq!(names.unique("Order".to_string()), "Order");
+        assert_eq!(names.unique("Order".to_string()), "Order2");
+    }
+}
@@ -0,0 +1,463 @@
+use super::{local_definition, pascal_case, DefinitionNames, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// The primitive types, for values that could be anything
+const ANY_TYPES: [&str; 5] = ["null", "boolean", "long", "double", "string"];
+
+/// Generate an Avro schema for the top-level schema, or a union of the definitions if there isn't
+/// one
+///
+/// Objects with properties become records (named after their titles, or the given name for the
+/// top-level schema), other objects become maps, and string enums become enums. Properties that
+/// aren't required are unions with `null` that default to `null`. Named types are defined where
+/// they're first used, as Avro requires. Avro names can only contain letters, digits, and
+/// underscores, so other characters in field names and enum symbols are replaced. References to
+/// other files are treated as any primitive value.
+pub fn avro(file: &SchemaFile, name: &str, namespace: Option<&str>) -> Value {
+    let mut names = Names::default();
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| {
+            (
+                name.to_string(),
+                names.unique(avro_name(&pascal_case(name))),
+            )
+        })
+        .collect();
+
+    let mut generator = Generator {
+        file,
+        definitions,
+        names,
+        defined: HashMap::new(),
+        definition_name: None,
+    };
+
+    let mut result = match &file.schema {
+        Some(schema) if !matches!(schema, SchemaDef::Empty {}) => {
+            let schema = Schema {
+                metadata: Metadata {
+                    title: file.metadata.title.clone(),
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+            let name = pascal_case(file.metadata.title.as_deref().unwrap_or(name));
+
+            generator.render(&schema, &avro_name(&name))
+        }
+        _ => Value::Array(
+            file.definitions
+                .iter()
+                .flatten()
+                .map(|(name, _)| {
+                    generator.render(
+                        &Schema {
+                            metadata: Metadata::default(),
+                            schema: SchemaDef::Ref {
+                                value: format!("#/$defs/{}", name),
+                            },
+                        },
+                        name,
+                    )
+                })
+                .collect(),
+        ),
+    };
+
+    if let (Some(namespace), Some(fields)) = (namespace, result.as_object_mut()) {
+        fields.insert("namespace".to_string(), json!(namespace));
+    }
+
+    result
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    definitions: DefinitionNames,
+    /// Names of records and enums
+    names: Names,
+    /// Definitions whose named types have already been defined, with what to use for later
+    /// references
+    defined: HashMap<String, Value>,
+    /// The name of the definition that's about to be rendered, if it's a record or enum
+    definition_name: Option<String>,
+}
+
+impl Generator<'_> {
+    /// The definition's reserved name, or else a new name for an inline record or enum
+    fn type_name(&mut self, name: &str) -> String {
+        self.definition_name
+            .take()
+            .unwrap_or_else(|| self.names.unique(name.to_string()))
+    }
+
+    /// The Avro schema for a schema, where the name is used for any record or enum
+    fn render(&mut self, schema: &Schema, name: &str) -> Value {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Null {}) => json!("null"),
+            SchemaDef::Type(SchemaType::Boolean {}) => json!("boolean"),
+            SchemaDef::Type(SchemaType::String { .. }) => json!("string"),
+            SchemaDef::Type(SchemaType::Integer { .. }) => json!("long"),
+            SchemaDef::Type(SchemaType::Number { .. }) => json!("double"),
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                ..
+            }) => {
+                let items = if prefix_items.is_empty() {
+                    self.render(items, &format!("{}Item", name))
+                } else {
+                    let members = prefix_items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, schema)| {
+                            self.render(schema, &format!("{}Item{}", name, index + 1))
+                        })
+                        .collect();
+
+                    union(members)
+                };
+
+                json!({ "type": "array", "items": items })
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if is_named(schema) {
+                    let name = self.type_name(name);
+                    let mut field_names = Names::default();
+                    let mut fields = vec![];
+
+                    for (property, schema) in &object.properties {
+                        let mut field_type =
+                            self.render(schema, &format!("{}{}", name, pascal_case(property)));
+                        let mut field = Map::new();
+
+                        field.insert(
+                            "name".to_string(),
+                            json!(field_names.unique(avro_name(property))),
+                        );
+
+                        if let Some(description) = &schema.metadata.description {
+                            field.insert("doc".to_string(), json!(description));
+                        }
+
+                        if !object.required.contains(property) {
+                            field_type = nullable(field_type);
+                            field.insert("default".to_string(), Value::Null);
+                        }
+
+                        field.insert("type".to_string(), field_type);
+                        fields.push(Value::Object(field));
+                    }
+
+                    named(&schema.metadata, "record", &name, "fields", fields)
+                } else {
+                    let values = match &object.additional_properties {
+                        AdditionalProperties::Schema(schema) => {
+                            self.render(schema, &format!("{}Value", name))
+                        }
+                        AdditionalProperties::Boolean(_) => json!(ANY_TYPES),
+                    };
+
+                    json!({ "type": "map", "values": values })
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, schema)) => match self.defined.get(definition) {
+                    Some(reference) => reference.clone(),
+                    None => {
+                        let name = self.definitions[definition].clone();
+                        // Records can refer to themselves by name, but anything else that does
+                        // can't be represented.
+                        let reference = if is_named(schema) {
+                            self.definition_name = Some(name.clone());
+                            json!(name)
+                        } else {
+                            json!(ANY_TYPES)
+                        };
+
+                        self.defined.insert(definition.to_string(), reference);
+                        let rendered = self.render(schema, &name);
+
+                        // Named types are referred to by name after their definition.
+                        let reference = match rendered.get("name") {
+                            Some(name) => name.clone(),
+                            None => rendered.clone(),
+                        };
+                        self.defined.insert(definition.to_string(), reference);
+
+                        rendered
+                    }
+                },
+                None => json!(ANY_TYPES),
+            },
+            SchemaDef::Enum { value } => {
+                let name = self.type_name(name);
+                let mut symbols = Names::default();
+                let symbols = value
+                    .iter()
+                    .map(|value| json!(symbols.unique(avro_name(value))))
+                    .collect();
+
+                named(&schema.metadata, "enum", &name, "symbols", symbols)
+            }
+            SchemaDef::Const { value } => match value {
+                Value::Bool(_) => json!("boolean"),
+                Value::Number(number) if number.is_i64() || number.is_u64() => json!("long"),
+                Value::Number(_) => json!("double"),
+                Value::String(_) => json!("string"),
+                Value::Null => json!("null"),
+                _ => json!(ANY_TYPES),
+            },
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let members = value
+                    .iter()
+                    .enumerate()
+                    .map(|(index, schema)| self.render(schema, &format!("{}{}", name, index + 1)))
+                    .collect();
+
+                union(members)
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => self.render(&value[0], name),
+            SchemaDef::AllOf { .. } | SchemaDef::Empty {} => json!(ANY_TYPES),
+        }
+    }
+}
+
+/// Whether the schema is a record or enum
+fn is_named(schema: &Schema) -> bool {
+    match &schema.schema {
+        SchemaDef::Type(SchemaType::Object(object)) => {
+            !object.properties.is_empty() || object.no_additional_properties()
+        }
+        SchemaDef::Enum { .. } => true,
+        _ => false,
+    }
+}
+
+/// A record or enum
+fn named(
+    metadata: &Metadata,
+    avro_type: &str,
+    name: &str,
+    members_key: &str,
+    members: Vec<Value>,
+) -> Value {
+    let mut result = Map::new();
+    result.insert("type".to_string(), json!(avro_type));
+    result.insert("name".to_string(), json!(name));
+
+    if let Some(doc) = metadata.description.as_ref().or(metadata.title.as_ref()) {
+        result.insert("doc".to_string(), json!(doc));
+    }
+
+    result.insert(members_key.to_string(), Value::Array(members));
+    Value::Object(result)
+}
+
+/// A union of the members (flattening nested unions and skipping duplicates), or the only member
+fn union(members: Vec<Value>) -> Value {
+    let mut result = vec![];
+
+    for member in members {
+        let member = match member {
+            Value::Array(members) => members,
+            member => vec![member],
+        };
+
+        for member in member {
+            if !result.contains(&member) {
+                result.push(member);
+            }
+        }
+    }
+
+    if result.len() == 1 {
+        result.remove(0)
+    } else {
+        Value::Array(result)
+    }
+}
+
+/// A union with `null` first, so that it can be the default
+fn nullable(value: Value) -> Value {
+    let mut members = match union(vec![value]) {
+        Value::Array(members) => members,
+        member => vec![member],
+    };
+
+    members.retain(|member| member != "null");
+    members.insert(0, json!("null"));
+    Value::Array(members)
+}
+
+/// A valid Avro name, with other characters replaced by underscores
+fn avro_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avro_records() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "description": "The ID" },
+                "status": { "$ref": "#/$defs/status" },
+                "previousStatus": { "$ref": "#/$defs/status" },
+                "line-items": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/line" }
+                },
+                "note": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                "attributes": { "type": "object", "additionalProperties": { "type": "string" } }
+            },
+            "required": ["id", "status", "previousStatus", "note"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "line": {
+                    "type": "object",
+                    "properties": {
+                        "sku": { "type": "string" },
+                        "parts": { "type": "array", "items": { "$ref": "#/$defs/line" } }
+                    },
+                    "required": ["sku"]
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            avro(&file, "ignored", Some("com.example")),
+            serde_json::json!({
+                "type": "record",
+                "name": "Order",
+                "doc": "Order",
+                "fields": [
+                    { "name": "id", "doc": "The ID", "type": "long" },
+                    {
+                        "name": "status",
+                        "type": { "type": "enum", "name": "Status", "symbols": ["open", "in_progress"] }
+                    },
+                    { "name": "previousStatus", "type": "Status" },
+                    {
+                        "name": "line_items",
+                        "default": null,
+                        "type": ["null", {
+                            "type": "array",
+                            "items": {
+                                "type": "record",
+                                "name": "Line",
+                                "fields": [
+                                    { "name": "sku", "type": "string" },
+                                    {
+                                        "name": "parts",
+                                        "default": null,
+                                        "type": ["null", { "type": "array", "items": "Line" }]
+                                    }
+                                ]
+                            }
+                        }]
+                    },
+                    { "name": "note", "type": ["string", "null"] },
+                    {
+                        "name": "attributes",
+                        "default": null,
+                        "type": ["null", { "type": "map", "values": "string" }]
+                    }
+                ],
+                "namespace": "com.example"
+            })
+        );
+    }
+
+    #[test]
+    fn avro_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            avro(&node, "tags", None),
+            serde_json::json!({
+                "type": "record",
+                "name": "Node2",
+                "doc": "Node",
+                "fields": [
+                    { "name": "label", "type": "string" },
+                    {
+                        "name": "children",
+                        "default": null,
+                        "type": [
+                            "null",
+                            {
+                                "type": "array",
+                                "items": {
+                                    "type": "record",
+                                    "name": "Node",
+                                    "fields": [
+                                        {
+                                            "name": "value",
+                                            "default": null,
+                                            "type": ["null", "long"]
+                                        },
+                                        {
+                                            "name": "parent",
+                                            "default": null,
+                                            "type": ["null", "Node"]
+                                        }
+                                    ]
+                                }
+                            }
+                        ]
+                    }
+                ]
+            })
+        );
+
+        assert_eq!(
+            avro(&tags, "tags", None),
+            serde_json::json!({ "type": "array", "items": "string" })
+        );
+    }
+}
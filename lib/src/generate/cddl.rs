@@ -0,0 +1,520 @@
+use super::{anchored, local_definition, words, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const INDENT: &str = "  ";
+
+/// Names defined by the standard prelude, which rules can't reuse
+const PRELUDE: [&str; 39] = [
+    "any",
+    "b64legacy",
+    "b64url",
+    "bigfloat",
+    "bigint",
+    "biguint",
+    "bool",
+    "bstr",
+    "bytes",
+    "cbor-any",
+    "decfrac",
+    "eb16",
+    "eb64legacy",
+    "eb64url",
+    "encoded-cbor",
+    "false",
+    "float",
+    "float16",
+    "float16-32",
+    "float32",
+    "float32-64",
+    "float64",
+    "int",
+    "integer",
+    "mime-message",
+    "nil",
+    "nint",
+    "null",
+    "number",
+    "regexp",
+    "tdate",
+    "text",
+    "time",
+    "true",
+    "tstr",
+    "uint",
+    "undefined",
+    "unsigned",
+    "uri",
+];
+
+/// Generate CDDL (RFC 8610) rules for the top-level schema and each definition
+///
+/// The first rule is the top-level schema, or a choice of the definitions if there isn't one. Objects become maps that allow other text keys
+/// unless additional properties are disallowed, patterns become `.regexp` controls (which are
+/// anchored, so unanchored patterns are padded with `.*`), numeric bounds become ranges or
+/// comparison controls, and enums and unions become choices. References to other files are
+/// treated as `any`.
+pub fn cddl(file: &SchemaFile, name: &str) -> String {
+    let mut names = Names::default();
+
+    for name in PRELUDE {
+        names.unique(name.to_string());
+    }
+
+    let root = file
+        .schema
+        .as_ref()
+        .filter(|schema| !matches!(schema, SchemaDef::Empty {}))
+        .map(|schema| {
+            let name = names.unique(rule_name(file.metadata.title.as_deref().unwrap_or(name)));
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            (name, schema)
+        });
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| (name.to_string(), names.unique(rule_name(name))))
+        .collect::<HashMap<_, _>>();
+
+    let generator = Generator { file, definitions };
+    let mut rules = vec![];
+
+    match &root {
+        Some((name, schema)) => rules.push(generator.rule(name, schema)),
+        None if !generator.definitions.is_empty() => {
+            let choices = file
+                .definitions
+                .iter()
+                .flatten()
+                .map(|(name, _)| generator.definitions[name.as_str()].as_str())
+                .collect::<Vec<_>>();
+            let name = rule_name(name);
+            let name = if generator.definitions.values().any(|used| *used == name)
+                || PRELUDE.contains(&name.as_str())
+            {
+                format!("{}-root", name)
+            } else {
+                name
+            };
+
+            rules.push(format!("{} = {}\n", name, choices.join(" / ")));
+        }
+        None => {}
+    }
+
+    for (name, schema) in file.definitions.iter().flatten() {
+        rules.push(generator.rule(&generator.definitions[name.as_str()], schema));
+    }
+
+    rules.join("\n")
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    /// Rule names for definitions
+    definitions: HashMap<String, String>,
+}
+
+impl Generator<'_> {
+    fn rule(&self, name: &str, schema: &Schema) -> String {
+        format!(
+            "{}{} = {}\n",
+            comment(&schema.metadata, ""),
+            name,
+            self.render(schema, "")
+        )
+    }
+
+    /// The type for a schema, where the indentation is used for the entries of any maps
+    fn render(&self, schema: &Schema, indent: &str) -> String {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Null {}) => "null".to_string(),
+            SchemaDef::Type(SchemaType::Boolean {}) => "bool".to_string(),
+            SchemaDef::Type(SchemaType::String { pattern }) => match pattern {
+                Some(pattern) => format!("tstr .regexp {}", Value::String(anchored(pattern))),
+                None => "tstr".to_string(),
+            },
+            SchemaDef::Type(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => {
+                // Integer bounds can always be made inclusive.
+                let lower = match (minimum, exclusive_minimum) {
+                    (Some(minimum), Some(exclusive)) => Some((*minimum).max(exclusive + 1)),
+                    (Some(minimum), None) => Some(*minimum),
+                    (None, exclusive) => exclusive.map(|exclusive| exclusive + 1),
+                };
+                let upper = match (maximum, exclusive_maximum) {
+                    (Some(maximum), Some(exclusive)) => Some((*maximum).min(exclusive - 1)),
+                    (Some(maximum), None) => Some(*maximum),
+                    (None, exclusive) => exclusive.map(|exclusive| exclusive - 1),
+                };
+
+                match (lower, upper) {
+                    (Some(lower), Some(upper)) => format!("{}..{}", lower, upper),
+                    (Some(0), None) => "uint".to_string(),
+                    (Some(lower), None) => format!("int .ge {}", lower),
+                    (None, Some(upper)) => format!("int .le {}", upper),
+                    (None, None) => "int".to_string(),
+                }
+            }
+            SchemaDef::Type(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => {
+                let controls = [
+                    ("ge", minimum),
+                    ("gt", exclusive_minimum),
+                    ("le", maximum),
+                    ("lt", exclusive_maximum),
+                ]
+                .iter()
+                .filter_map(|(operator, bound)| {
+                    bound.map(|bound| format!("number .{} {}", operator, bound))
+                })
+                .collect::<Vec<_>>();
+
+                if controls.is_empty() {
+                    "number".to_string()
+                } else {
+                    and(controls)
+                }
+            }
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                min_items,
+                max_items,
+            }) => {
+                if prefix_items.is_empty() {
+                    let occurrence = match (min_items, max_items) {
+                        (None | Some(0), None) => "*".to_string(),
+                        (Some(1), None) => "+".to_string(),
+                        (min_items, max_items) => format!(
+                            "{}*{}",
+                            min_items.unwrap_or(0),
+                            max_items.map(|max| max.to_string()).unwrap_or_default()
+                        ),
+                    };
+
+                    format!("[{} {}]", occurrence, self.render(items, indent))
+                } else {
+                    let mut members = prefix_items
+                        .iter()
+                        .map(|schema| self.render(schema, indent))
+                        .collect::<Vec<_>>();
+                    members.push(format!("* {}", self.render(items, indent)));
+
+                    format!("[{}]", members.join(", "))
+                }
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                let entry_indent = format!("{}{}", indent, INDENT);
+                let mut entries = String::new();
+
+                for (property, schema) in &object.properties {
+                    let occurrence = if object.required.contains(property) {
+                        ""
+                    } else {
+                        "? "
+                    };
+
+                    entries.push_str(&comment(&schema.metadata, &entry_indent));
+                    entries.push_str(&format!(
+                        "{}{}{}: {},\n",
+                        entry_indent,
+                        occurrence,
+                        key(property),
+                        self.render(schema, &entry_indent)
+                    ));
+                }
+
+                match &object.additional_properties {
+                    AdditionalProperties::Boolean(false) => {}
+                    AdditionalProperties::Boolean(true) => {
+                        entries.push_str(&format!("{}* tstr => any,\n", entry_indent));
+                    }
+                    AdditionalProperties::Schema(schema) => {
+                        entries.push_str(&format!(
+                            "{}* tstr => {},\n",
+                            entry_indent,
+                            self.render(schema, &entry_indent)
+                        ));
+                    }
+                }
+
+                if entries.is_empty() {
+                    "{}".to_string()
+                } else {
+                    format!("{{\n{}{}}}", entries, indent)
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, _)) => self.definitions[definition].clone(),
+                None => "any".to_string(),
+            },
+            SchemaDef::Enum { value } => value
+                .iter()
+                .map(|value| Value::String(value.clone()).to_string())
+                .collect::<Vec<_>>()
+                .join(" / "),
+            SchemaDef::Const { value } => literal(value),
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let mut choices: Vec<String> = vec![];
+
+                for schema in value {
+                    let choice = self.render(schema, indent);
+
+                    if !choices.contains(&choice) {
+                        choices.push(choice);
+                    }
+                }
+
+                match choices.len() {
+                    0 => "any".to_string(),
+                    _ => choices.join(" / "),
+                }
+            }
+            SchemaDef::AllOf { value } => match value.as_slice() {
+                [] => "any".to_string(),
+                [schema] => self.render(schema, indent),
+                schemas => and(schemas
+                    .iter()
+                    .map(|schema| self.render(schema, indent))
+                    .collect()),
+            },
+            SchemaDef::Empty {} => "any".to_string(),
+        }
+    }
+}
+
+/// A type that matches all of the given types
+fn and(mut types: Vec<String>) -> String {
+    let first = types.remove(0);
+
+    types.into_iter().fold(first, |result, next| {
+        let result = if result.contains(' ') {
+            format!("({})", result)
+        } else {
+            result
+        };
+        let next = if next.contains(' ') {
+            format!("({})", next)
+        } else {
+            next
+        };
+
+        format!("{} .and {}", result, next)
+    })
+}
+
+/// A CDDL literal for a JSON value
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Array(values) => format!(
+            "[{}]",
+            values.iter().map(literal).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(fields) => format!(
+            "{{ {} }}",
+            fields
+                .iter()
+                .map(|(name, value)| format!("{}: {}", key(name), literal(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        // Other values are written in the same way in JSON.
+        value => value.to_string(),
+    }
+}
+
+/// A map key, which is a bare word if the name is a valid identifier
+fn key(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        Value::String(name.to_string()).to_string()
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let is_start = |c: char| c.is_ascii_alphabetic() || c == '@' || c == '_' || c == '$';
+    let mut chars = name.chars().peekable();
+
+    match chars.next() {
+        Some(first) if is_start(first) => {}
+        _ => return false,
+    }
+
+    // Hyphens and periods have to be followed by a letter or digit.
+    while let Some(c) = chars.next() {
+        if c == '-' || c == '.' {
+            match chars.peek() {
+                Some(&next) if is_start(next) || next.is_ascii_digit() => {}
+                Some(&'-') | Some(&'.') => {}
+                _ => return false,
+            }
+        } else if !is_start(c) && !c.is_ascii_digit() {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// A lower-case rule name with words separated by hyphens
+fn rule_name(name: &str) -> String {
+    let name = words(name)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("_{}", name)
+    }
+}
+
+fn comment(metadata: &Metadata, indent: &str) -> String {
+    metadata
+        .description
+        .as_deref()
+        .or(metadata.title.as_deref())
+        .map(|description| {
+            description
+                .lines()
+                .map(|line| format!("{}; {}", indent, line).trim_end().to_string() + "\n")
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cddl_rules() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 0 },
+                "status": { "$ref": "#/$defs/status" },
+                "line-items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string", "pattern": "^[A-Z]+$" } },
+                        "required": ["sku"],
+                        "additionalProperties": false
+                    },
+                    "minItems": 1
+                },
+                "quantity": { "type": "integer", "minimum": 1, "exclusiveMaximum": 100 },
+                "discount": { "type": "number", "minimum": 0, "maximum": 1 },
+                "note": {
+                    "description": "Free text",
+                    "anyOf": [{ "type": "string" }, { "type": "null" }]
+                },
+                "version": { "const": 2 }
+            },
+            "required": ["id", "status", "note"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "int": { "type": "string", "pattern": "a" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            cddl(&file, "ignored"),
+            r#"; An order
+order = {
+  id: uint,
+  status: status,
+  ? line-items: [+ {
+    sku: tstr .regexp "[A-Z]+",
+  }],
+  ? quantity: 1..99,
+  ? discount: (number .ge 0) .and (number .le 1),
+  ; Free text
+  note: tstr / null,
+  ? version: 2,
+  * tstr => any,
+}
+
+status = "open" / "in-progress"
+
+int2 = tstr .regexp ".*(a).*"
+"#
+        );
+    }
+
+    #[test]
+    fn cddl_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            cddl(&node, "tags"),
+            r#"node = {
+  label: tstr .regexp "[a-z]+",
+  ? children: [+ node2],
+  * tstr => any,
+}
+
+node2 = {
+  ? value: 0..10,
+  ? parent: node2,
+  * tstr => any,
+}
+"#
+        );
+
+        assert_eq!(
+            cddl(&tags, "tags"),
+            r#"tags = [0*3 tstr .regexp "[a-z]+"]
+"#
+        );
+    }
+}
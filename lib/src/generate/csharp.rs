@@ -0,0 +1,533 @@
+use super::{local_definition, pascal_case, DefinitionNames, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+const INDENT: &str = "    ";
+
+const COLLECTIONS: &str = "System.Collections.Generic";
+const JSON: &str = "System.Text.Json";
+const SERIALIZATION: &str = "System.Text.Json.Serialization";
+
+/// Type names that the generated code uses, which generated types can't shadow
+const RESERVED_TYPES: [&str; 9] = [
+    "Dictionary",
+    "JsonConverter",
+    "JsonElement",
+    "JsonIgnore",
+    "JsonPropertyName",
+    "JsonStringEnumConverter",
+    "JsonStringEnumMemberName",
+    "JsonUnmappedMemberHandling",
+    "List",
+];
+
+/// Generate a C# file with records for the top-level schema and each definition
+///
+/// Objects with properties become sealed records with `System.Text.Json` attributes, where
+/// required properties are `required` and properties that aren't are nullable and left out when
+/// they're null, and objects that don't allow additional properties reject unmapped members.
+/// String enums become enums (using `JsonStringEnumMemberName`, so .NET 9 is needed). C# has no
+/// type aliases, so other definitions are used directly, and unions (and references to other
+/// files) are left as `JsonElement`.
+pub fn csharp(file: &SchemaFile, name: &str, namespace: Option<&str>) -> String {
+    let mut names = Names::default();
+
+    for reserved in RESERVED_TYPES {
+        names.unique(reserved.to_string());
+    }
+
+    let root = file
+        .schema
+        .as_ref()
+        .filter(|schema| !matches!(schema, SchemaDef::Empty {}))
+        .map(|schema| {
+            let name = names.unique(type_name(file.metadata.title.as_deref().unwrap_or(name)));
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            (name, schema)
+        });
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| (name.to_string(), names.unique(type_name(name))))
+        .collect();
+
+    let mut generator = Generator {
+        file,
+        definitions,
+        names,
+        usings: BTreeSet::new(),
+        expanding: vec![],
+        blocks: vec![],
+    };
+
+    if let Some((name, schema)) = &root {
+        generator.definition(name, schema);
+    }
+
+    for (name, schema) in file.definitions.iter().flatten() {
+        let name = generator.definitions[name.as_str()].clone();
+        generator.definition(&name, schema);
+    }
+
+    let mut result =
+        "// Code generated by json-schema-tools. DO NOT EDIT.\n\n#nullable enable\n\n".to_string();
+
+    if !generator.usings.is_empty() {
+        for using in &generator.usings {
+            result.push_str(&format!("using {};\n", using));
+        }
+
+        result.push('\n');
+    }
+
+    if let Some(namespace) = namespace {
+        result.push_str(&format!("namespace {};\n\n", namespace));
+    }
+
+    result.push_str(&generator.blocks.join("\n"));
+    result
+}
+
+/// Whether the schema is a record
+fn is_record(schema: &Schema) -> bool {
+    matches!(
+        &schema.schema,
+        SchemaDef::Type(SchemaType::Object(object))
+            if !object.properties.is_empty() || object.no_additional_properties()
+    )
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    definitions: DefinitionNames,
+    /// Type names across the file
+    names: Names,
+    usings: BTreeSet<&'static str>,
+    /// Definitions that are being used directly, to avoid expanding recursive ones forever
+    expanding: Vec<String>,
+    /// Type declarations
+    blocks: Vec<String>,
+}
+
+impl Generator<'_> {
+    fn json_element(&mut self) -> String {
+        self.usings.insert(JSON);
+        "JsonElement".to_string()
+    }
+
+    /// Add a record or enum for the top-level schema or a definition
+    fn definition(&mut self, name: &str, schema: &Schema) {
+        match &schema.schema {
+            _ if is_record(schema) => self.record(name, schema),
+            SchemaDef::Enum { value } => self.enumeration(name, &schema.metadata, value),
+            // Other definitions are used directly where they're referenced.
+            _ => {}
+        }
+    }
+
+    fn record(&mut self, name: &str, schema: &Schema) {
+        let object = match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object)) => object,
+            _ => return,
+        };
+
+        self.usings.insert(SERIALIZATION);
+
+        let mut members = Names::default();
+        // Members can't have the same name as the type.
+        members.unique(name.to_string());
+
+        let mut properties = vec![];
+
+        for (property, schema) in &object.properties {
+            let member = members.unique(member_name(property));
+            let member_type = self.render(schema, &format!("{}{}", name, type_name(property)));
+            let key = Value::String(property.to_string()).to_string();
+
+            let mut lines = summary(&schema.metadata, INDENT);
+            lines.push_str(&format!("{}[JsonPropertyName({})]\n", INDENT, key));
+
+            if object.required.contains(property) {
+                let nullable = if is_nullable(schema) { "?" } else { "" };
+
+                lines.push_str(&format!(
+                    "{}public required {}{} {} {{ get; init; }}\n",
+                    INDENT, member_type, nullable, member
+                ));
+            } else {
+                lines.push_str(&format!(
+                    "{}[JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]\n",
+                    INDENT
+                ));
+                lines.push_str(&format!(
+                    "{}public {}? {} {{ get; init; }}\n",
+                    INDENT, member_type, member
+                ));
+            }
+
+            properties.push(lines);
+        }
+
+        let mut block = summary(&schema.metadata, "");
+
+        if object.no_additional_properties() {
+            block.push_str("[JsonUnmappedMemberHandling(JsonUnmappedMemberHandling.Disallow)]\n");
+        }
+
+        block.push_str(&format!(
+            "public sealed record {}\n{{\n{}}}\n",
+            name,
+            properties.join("\n")
+        ));
+
+        self.blocks.push(block);
+    }
+
+    fn enumeration(&mut self, name: &str, metadata: &Metadata, values: &[String]) {
+        self.usings.insert(SERIALIZATION);
+
+        let mut members = Names::default();
+        let members = values
+            .iter()
+            .map(|value| {
+                let member = type_name(value);
+                let member = if member == "_" {
+                    "Empty".to_string()
+                } else {
+                    member
+                };
+
+                format!(
+                    "{}[JsonStringEnumMemberName({})]\n{}{}",
+                    INDENT,
+                    Value::String(value.clone()),
+                    INDENT,
+                    members.unique(member)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut block = summary(metadata, "");
+        block.push_str(&format!(
+            "[JsonConverter(typeof(JsonStringEnumConverter<{}>))]\npublic enum {}\n{{\n",
+            name, name
+        ));
+
+        if !members.is_empty() {
+            block.push_str(&members.join(",\n"));
+            block.push('\n');
+        }
+
+        block.push_str("}\n");
+        self.blocks.push(block);
+    }
+
+    /// The type of a schema, where the name is used for any records or enums it needs
+    fn render(&mut self, schema: &Schema, name: &str) -> String {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Boolean {}) => "bool".to_string(),
+            SchemaDef::Type(SchemaType::String { .. }) => "string".to_string(),
+            SchemaDef::Type(SchemaType::Integer { .. }) => "long".to_string(),
+            SchemaDef::Type(SchemaType::Number { .. }) => "double".to_string(),
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                ..
+            }) => {
+                let item = if prefix_items.is_empty() {
+                    self.render(items, &format!("{}Item", name))
+                } else {
+                    // Tuples can have items of different types.
+                    self.json_element()
+                };
+                self.usings.insert(COLLECTIONS);
+
+                format!("List<{}>", item)
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if is_record(schema) {
+                    let name = self.names.unique(name.to_string());
+                    self.record(&name, schema);
+
+                    name
+                } else {
+                    let value = match &object.additional_properties {
+                        AdditionalProperties::Schema(schema) => {
+                            self.render(schema, &format!("{}Value", name))
+                        }
+                        AdditionalProperties::Boolean(_) => self.json_element(),
+                    };
+                    self.usings.insert(COLLECTIONS);
+
+                    format!("Dictionary<string, {}>", value)
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, schema)) => {
+                    if is_record(schema) || matches!(schema.schema, SchemaDef::Enum { .. }) {
+                        self.definitions[definition].clone()
+                    } else if self.expanding.iter().any(|name| name == definition) {
+                        self.json_element()
+                    } else {
+                        self.expanding.push(definition.to_string());
+                        let name = self.definitions[definition].clone();
+                        let result = self.render(schema, &name);
+                        self.expanding.pop();
+
+                        result
+                    }
+                }
+                None => self.json_element(),
+            },
+            SchemaDef::Enum { value } => {
+                let name = self.names.unique(name.to_string());
+                self.enumeration(&name, &schema.metadata, value);
+
+                name
+            }
+            SchemaDef::Const { value } => match value {
+                Value::Bool(_) => "bool".to_string(),
+                Value::Number(number) if number.is_i64() => "long".to_string(),
+                Value::Number(_) => "double".to_string(),
+                Value::String(_) => "string".to_string(),
+                _ => self.json_element(),
+            },
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let members = value
+                    .iter()
+                    .filter(|schema| !matches!(schema.schema, SchemaDef::Type(SchemaType::Null {})))
+                    .collect::<Vec<_>>();
+
+                match members.as_slice() {
+                    // A union with null is nullable where it's used.
+                    [member] => self.render(member, name),
+                    _ => self.json_element(),
+                }
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => self.render(&value[0], name),
+            SchemaDef::Type(SchemaType::Null {})
+            | SchemaDef::AllOf { .. }
+            | SchemaDef::Empty {} => self.json_element(),
+        }
+    }
+}
+
+/// Whether the schema allows null (in which case required properties are still nullable)
+fn is_nullable(schema: &Schema) -> bool {
+    match &schema.schema {
+        SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => value
+            .iter()
+            .any(|schema| matches!(schema.schema, SchemaDef::Type(SchemaType::Null {}))),
+        _ => false,
+    }
+}
+
+fn type_name(name: &str) -> String {
+    let name = pascal_case(name);
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// A Pascal case property name (which can't be a keyword, since keywords are lower case)
+fn member_name(property: &str) -> String {
+    type_name(property)
+}
+
+/// An XML documentation comment with the description, or else the title
+fn summary(metadata: &Metadata, indent: &str) -> String {
+    match metadata.description.as_ref().or(metadata.title.as_ref()) {
+        Some(description) => {
+            let lines = description
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .lines()
+                .map(|line| format!("{}/// {}", indent, line).trim_end().to_string() + "\n")
+                .collect::<String>();
+
+            format!(
+                "{}/// <summary>\n{}{}/// </summary>\n",
+                indent, lines, indent
+            )
+        }
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csharp_records() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "description": "The ID" },
+                "status": { "$ref": "#/$defs/status" },
+                "line-items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string" } },
+                        "required": ["sku"],
+                        "additionalProperties": false
+                    }
+                },
+                "order": { "type": "string" },
+                "note": { "anyOf": [{ "type": "number" }, { "type": "null" }] },
+                "payment": { "oneOf": [{ "type": "string" }, { "type": "integer" }] }
+            },
+            "required": ["id", "status", "note"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            csharp(&file, "order", Some("Example.Orders")),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+#nullable enable
+
+using System.Collections.Generic;
+using System.Text.Json;
+using System.Text.Json.Serialization;
+
+namespace Example.Orders;
+
+[JsonUnmappedMemberHandling(JsonUnmappedMemberHandling.Disallow)]
+public sealed record OrderLineItemsItem
+{
+    [JsonPropertyName("sku")]
+    public required string Sku { get; init; }
+}
+
+/// <summary>
+/// An order
+/// </summary>
+public sealed record Order
+{
+    /// <summary>
+    /// The ID
+    /// </summary>
+    [JsonPropertyName("id")]
+    public required long Id { get; init; }
+
+    [JsonPropertyName("status")]
+    public required Status Status { get; init; }
+
+    [JsonPropertyName("line-items")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public List<OrderLineItemsItem>? LineItems { get; init; }
+
+    [JsonPropertyName("order")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public string? Order2 { get; init; }
+
+    [JsonPropertyName("note")]
+    public required double? Note { get; init; }
+
+    [JsonPropertyName("payment")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public JsonElement? Payment { get; init; }
+}
+
+[JsonConverter(typeof(JsonStringEnumConverter<Status>))]
+public enum Status
+{
+    [JsonStringEnumMemberName("open")]
+    Open,
+    [JsonStringEnumMemberName("in-progress")]
+    InProgress
+}
+"#
+        );
+    }
+
+    #[test]
+    fn csharp_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            csharp(&node, "tags", None),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+#nullable enable
+
+using System.Collections.Generic;
+using System.Text.Json.Serialization;
+
+public sealed record Node
+{
+    [JsonPropertyName("label")]
+    public required string Label { get; init; }
+
+    [JsonPropertyName("children")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public List<Node2>? Children { get; init; }
+}
+
+public sealed record Node2
+{
+    [JsonPropertyName("value")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public long? Value { get; init; }
+
+    [JsonPropertyName("parent")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public Node2? Parent { get; init; }
+}
+"#
+        );
+
+        assert_eq!(
+            csharp(&tags, "tags", None),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+#nullable enable
+
+"#
+        );
+    }
+}
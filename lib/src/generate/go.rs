@@ -0,0 +1,480 @@
+use super::{local_definition, words, DefinitionNames, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+
+/// Words that Go names write in upper case
+const INITIALISMS: [&str; 39] = [
+    "ACL", "API", "ASCII", "CPU", "CSS", "DNS", "EOF", "GUID", "HTML", "HTTP", "HTTPS", "ID", "IP",
+    "JSON", "LHS", "QPS", "RAM", "RHS", "RPC", "SLA", "SMTP", "SQL", "SSH", "TCP", "TLS", "TTL",
+    "UDP", "UI", "UID", "UUID", "URI", "URL", "UTF8", "VM", "XML", "XMPP", "XSRF", "XSS", "YAML",
+];
+
+const RAW_MESSAGE: &str = "json.RawMessage";
+
+/// Generate a Go file declaring types for the top-level schema and each definition
+///
+/// Objects with properties become structs with `json` tags, where properties that aren't required
+/// are pointers (unless they're slices or maps) with `omitempty`. String enums become named string
+/// types with a constant for each value. Other unions are left as `json.RawMessage`, and
+/// references to other files are typed as `any`.
+pub fn go(file: &SchemaFile, name: &str, package: &str) -> String {
+    let mut names = Names::default();
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| (name.to_string(), names.unique(type_name(name))))
+        .collect();
+
+    let mut generator = Generator {
+        file,
+        definitions,
+        names,
+        uses_json: false,
+        blocks: vec![],
+    };
+
+    if let Some(schema) = file.schema.as_ref() {
+        if !matches!(schema, SchemaDef::Empty {}) {
+            let name = type_name(file.metadata.title.as_deref().unwrap_or(name));
+            let name = generator.names.unique(name);
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            generator.definition(&name, &schema);
+        }
+    }
+
+    for (name, schema) in file.definitions.iter().flatten() {
+        let name = generator.definitions[name.as_str()].clone();
+        generator.definition(&name, schema);
+    }
+
+    let mut result = format!(
+        "// Code generated by json-schema-tools. DO NOT EDIT.\n\npackage {}\n",
+        package
+    );
+
+    if generator.uses_json {
+        result.push_str("\nimport \"encoding/json\"\n");
+    }
+
+    for block in generator.blocks {
+        result.push('\n');
+        result.push_str(&block);
+        result.push('\n');
+    }
+
+    result
+}
+
+/// A type expression, and whether it can already be nil (so doesn't need a pointer when optional)
+struct Type {
+    expression: String,
+    nilable: bool,
+}
+
+impl Type {
+    fn new(expression: &str) -> Self {
+        Self {
+            expression: expression.to_string(),
+            nilable: expression.starts_with(['*', '['])
+                || expression.starts_with("map[")
+                || expression == "any"
+                || expression == RAW_MESSAGE,
+        }
+    }
+
+    fn pointer(self) -> Self {
+        if self.nilable {
+            self
+        } else {
+            Self::new(&format!("*{}", self.expression))
+        }
+    }
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    definitions: DefinitionNames,
+    /// Type and constant names across the package
+    names: Names,
+    uses_json: bool,
+    /// Type declarations, in an order where inline structs come before the structs using them
+    blocks: Vec<String>,
+}
+
+impl Generator<'_> {
+    /// Add a struct, enum, or other named type for the top-level schema or a definition
+    fn definition(&mut self, name: &str, schema: &Schema) {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object))
+                if !object.properties.is_empty() || object.no_additional_properties() =>
+            {
+                self.structure(name, schema);
+            }
+            SchemaDef::Enum { value } => self.enumeration(name, &schema.metadata, value),
+            _ => {
+                let underlying = self.render(schema, name).expression;
+                // A new type wouldn't keep the methods that decode raw messages.
+                let separator = if underlying == RAW_MESSAGE {
+                    " = "
+                } else {
+                    " "
+                };
+                let block = format!(
+                    "{}type {}{}{}",
+                    comment(&schema.metadata),
+                    name,
+                    separator,
+                    underlying
+                );
+
+                self.blocks.push(block);
+            }
+        }
+    }
+
+    fn structure(&mut self, name: &str, schema: &Schema) {
+        let object = match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object)) => object,
+            _ => return,
+        };
+
+        let mut fields = Names::default();
+        let mut rows = vec![];
+
+        for (property, schema) in &object.properties {
+            let field = fields.unique(type_name(property));
+            let field_type = self.render(schema, &format!("{}{}", name, type_name(property)));
+            let key = property.replace('\\', "\\\\").replace('"', "\\\"");
+
+            rows.push(if object.required.contains(property) {
+                (field, field_type.expression, format!("`json:\"{}\"`", key))
+            } else {
+                (
+                    field,
+                    field_type.pointer().expression,
+                    format!("`json:\"{},omitempty\"`", key),
+                )
+            });
+        }
+
+        let block = if rows.is_empty() {
+            format!("{}type {} struct{{}}", comment(&schema.metadata), name)
+        } else {
+            let name_width = rows
+                .iter()
+                .map(|(name, _, _)| name.len())
+                .max()
+                .unwrap_or(0);
+            let type_width = rows
+                .iter()
+                .map(|(_, field_type, _)| field_type.len())
+                .max()
+                .unwrap_or(0);
+
+            let fields = rows
+                .iter()
+                .map(|(name, field_type, tag)| {
+                    format!(
+                        "\t{:name_width$} {:type_width$} {}\n",
+                        name, field_type, tag
+                    )
+                })
+                .collect::<String>();
+
+            format!(
+                "{}type {} struct {{\n{}}}",
+                comment(&schema.metadata),
+                name,
+                fields
+            )
+        };
+
+        self.blocks.push(block);
+    }
+
+    fn enumeration(&mut self, name: &str, metadata: &Metadata, values: &[String]) {
+        let constants = values
+            .iter()
+            .map(|value| {
+                let suffix = type_name(value);
+                let suffix = if suffix.is_empty() { "Empty" } else { &suffix };
+
+                (
+                    self.names.unique(format!("{}{}", name, suffix)),
+                    Value::String(value.clone()).to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut block = format!("{}type {} string", comment(metadata), name);
+
+        if !constants.is_empty() {
+            let width = constants
+                .iter()
+                .map(|(constant, _)| constant.len())
+                .max()
+                .unwrap_or(0);
+
+            block.push_str("\n\nconst (\n");
+
+            for (constant, value) in constants {
+                block.push_str(&format!("\t{:width$} {} = {}\n", constant, name, value));
+            }
+
+            block.push(')');
+        }
+
+        self.blocks.push(block);
+    }
+
+    /// The type of a schema, where the name is used for any inline structs or enums
+    fn render(&mut self, schema: &Schema, name: &str) -> Type {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Null {}) | SchemaDef::Empty {} => Type::new("any"),
+            SchemaDef::Type(SchemaType::Boolean {}) => Type::new("bool"),
+            SchemaDef::Type(SchemaType::String { .. }) => Type::new("string"),
+            SchemaDef::Type(SchemaType::Integer { .. }) => Type::new("int64"),
+            SchemaDef::Type(SchemaType::Number { .. }) => Type::new("float64"),
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                ..
+            }) => {
+                if prefix_items.is_empty() {
+                    let item = self.render(items, &format!("{}Item", name));
+                    Type::new(&format!("[]{}", item.expression))
+                } else {
+                    // Tuples can have items of different types.
+                    Type::new("[]any")
+                }
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if !object.properties.is_empty() || object.no_additional_properties() {
+                    let name = self.names.unique(name.to_string());
+                    self.structure(&name, schema);
+
+                    Type::new(&name)
+                } else {
+                    let value = match &object.additional_properties {
+                        AdditionalProperties::Schema(schema) => {
+                            self.render(schema, &format!("{}Value", name)).expression
+                        }
+                        AdditionalProperties::Boolean(_) => "any".to_string(),
+                    };
+
+                    Type::new(&format!("map[string]{}", value))
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, _)) => Type::new(&self.definitions[definition]),
+                None => Type::new("any"),
+            },
+            SchemaDef::Enum { value } => {
+                let name = self.names.unique(name.to_string());
+                self.enumeration(&name, &schema.metadata, value);
+
+                Type::new(&name)
+            }
+            SchemaDef::Const { value } => Type::new(match value {
+                Value::Bool(_) => "bool",
+                Value::Number(number) if number.is_i64() || number.is_u64() => "int64",
+                Value::Number(_) => "float64",
+                Value::String(_) => "string",
+                _ => "any",
+            }),
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let members = value
+                    .iter()
+                    .filter(|schema| !matches!(schema.schema, SchemaDef::Type(SchemaType::Null {})))
+                    .collect::<Vec<_>>();
+
+                match members.as_slice() {
+                    // A union with null is a pointer.
+                    [member] if members.len() < value.len() => self.render(member, name).pointer(),
+                    [member] => self.render(member, name),
+                    _ => {
+                        self.uses_json = true;
+                        Type::new(RAW_MESSAGE)
+                    }
+                }
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => self.render(&value[0], name),
+            SchemaDef::AllOf { .. } => Type::new("any"),
+        }
+    }
+}
+
+/// An exported name, with initialisms in upper case
+fn type_name(name: &str) -> String {
+    let name = words(name)
+        .iter()
+        .map(|word| {
+            let upper = word.to_ascii_uppercase();
+
+            if INITIALISMS.contains(&upper.as_str()) {
+                upper
+            } else {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+        })
+        .collect::<String>();
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("X{}", name)
+    } else {
+        name
+    }
+}
+
+fn comment(metadata: &Metadata) -> String {
+    metadata
+        .description
+        .as_deref()
+        .or(metadata.title.as_deref())
+        .map(|description| {
+            description
+                .lines()
+                .map(|line| format!("// {}", line).trim_end().to_string() + "\n")
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn go_structs() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer" },
+                "status": { "$ref": "#/$defs/status" },
+                "customerUrl": { "type": "string" },
+                "lines": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string" } },
+                        "required": ["sku"]
+                    }
+                },
+                "note": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                "payment": { "oneOf": [{ "$ref": "#/$defs/card" }, { "type": "string" }] }
+            },
+            "required": ["id", "status", "note"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "card": { "type": "object", "properties": { "number": { "type": "string" } } }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            go(&file, "ignored", "orders"),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+package orders
+
+import "encoding/json"
+
+type OrderLinesItem struct {
+	Sku string `json:"sku"`
+}
+
+// An order
+type Order struct {
+	ID          int64            `json:"id"`
+	Status      Status           `json:"status"`
+	CustomerURL *string          `json:"customerUrl,omitempty"`
+	Lines       []OrderLinesItem `json:"lines,omitempty"`
+	Note        *string          `json:"note"`
+	Payment     json.RawMessage  `json:"payment,omitempty"`
+}
+
+type Status string
+
+const (
+	StatusOpen       Status = "open"
+	StatusInProgress Status = "in-progress"
+)
+
+type Card struct {
+	Number *string `json:"number,omitempty"`
+}
+"#
+        );
+    }
+
+    #[test]
+    fn go_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            go(&node, "tags", "models"),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+package models
+
+type Node2 struct {
+	Label    string `json:"label"`
+	Children []Node `json:"children,omitempty"`
+}
+
+type Node struct {
+	Value  *int64 `json:"value,omitempty"`
+	Parent *Node  `json:"parent,omitempty"`
+}
+"#
+        );
+
+        assert_eq!(
+            go(&tags, "tags", "models"),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+package models
+
+type Tags []string
+"#
+        );
+    }
+}
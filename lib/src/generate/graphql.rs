@@ -0,0 +1,582 @@
+use super::{local_definition, pascal_case, screaming_snake_case, DefinitionNames, Names};
+use crate::schema::{Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const INDENT: &str = "  ";
+
+/// A custom scalar for values without a GraphQL equivalent
+const JSON_SCALAR: &str = "JSON";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    Output,
+    Input,
+}
+
+/// Generate GraphQL type definitions for the top-level schema and each definition
+///
+/// Objects with properties become object types (and input types too if `inputs` is set), required
+/// properties are non-null unless they can be null, string enums become enums, and unions of
+/// objects become unions. Names can only contain letters, digits, and underscores, so other
+/// characters are replaced, and enum values are written in upper case. Anything else uses a `JSON`
+/// scalar, as do references to other files.
+pub fn graphql(file: &SchemaFile, name: &str, inputs: bool) -> String {
+    let mut names = Names::default();
+    let root = file
+        .schema
+        .as_ref()
+        .filter(|schema| !matches!(schema, SchemaDef::Empty {}))
+        .map(|schema| {
+            let name = names.unique(type_name(file.metadata.title.as_deref().unwrap_or(name)));
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            (name, schema)
+        });
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| (name.to_string(), names.unique(type_name(name))))
+        .collect::<HashMap<_, _>>();
+    // Input types are only needed for objects.
+    let input_definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .filter(|(_, schema)| inputs && is_object_type(schema))
+        .map(|(name, _)| {
+            (
+                name.to_string(),
+                names.unique(format!("{}Input", definitions[name.as_str()])),
+            )
+        })
+        .collect();
+
+    let mut generator = Generator {
+        file,
+        definitions,
+        input_definitions,
+        names,
+        enums: HashMap::new(),
+        expanding: vec![],
+        uses_json: false,
+        blocks: vec![],
+    };
+
+    let mut modes = vec![Mode::Output];
+
+    if inputs {
+        modes.push(Mode::Input);
+    }
+
+    for mode in modes {
+        if let Some((name, schema)) = &root {
+            if mode == Mode::Output {
+                generator.definition(name, schema, mode);
+            } else if is_object_type(schema) {
+                let name = generator.names.unique(format!("{}Input", name));
+                generator.definition(&name, schema, mode);
+            }
+        }
+
+        for (name, schema) in file.definitions.iter().flatten() {
+            let name = match mode {
+                Mode::Output => generator.definitions.get(name.as_str()),
+                Mode::Input => generator.input_definitions.get(name.as_str()),
+            };
+
+            if let Some(name) = name.cloned() {
+                generator.definition(&name, schema, mode);
+            }
+        }
+    }
+
+    let mut blocks = generator.blocks;
+
+    if generator.uses_json {
+        blocks.insert(
+            0,
+            format!("\"\"\"\nAny JSON value\n\"\"\"\nscalar {}", JSON_SCALAR),
+        );
+    }
+
+    if blocks.is_empty() {
+        String::new()
+    } else {
+        blocks.join("\n\n") + "\n"
+    }
+}
+
+fn is_object_type(schema: &Schema) -> bool {
+    matches!(
+        &schema.schema,
+        SchemaDef::Type(SchemaType::Object(object)) if !object.properties.is_empty()
+    )
+}
+
+/// A type reference, and whether it can be null
+struct Type {
+    expression: String,
+    nullable: bool,
+}
+
+impl Type {
+    fn new(expression: &str) -> Self {
+        Self {
+            expression: expression.to_string(),
+            nullable: false,
+        }
+    }
+
+    /// The type for a field, which is non-null if it's required and can't be null
+    fn field(&self, required: bool) -> String {
+        if required && !self.nullable {
+            format!("{}!", self.expression)
+        } else {
+            self.expression.clone()
+        }
+    }
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    definitions: DefinitionNames,
+    /// Input type names for definitions that are objects
+    input_definitions: HashMap<String, String>,
+    /// Type names across the schema
+    names: Names,
+    /// Names of inline enums, which are shared by object and input types
+    enums: HashMap<String, String>,
+    /// Definitions that are being used directly, to avoid expanding recursive ones forever
+    expanding: Vec<String>,
+    uses_json: bool,
+    /// Definitions, in an order where inline types come before the types using them
+    blocks: Vec<String>,
+}
+
+impl Generator<'_> {
+    fn json(&mut self) -> Type {
+        self.uses_json = true;
+        Type::new(JSON_SCALAR)
+    }
+
+    fn definition(&mut self, name: &str, schema: &Schema, mode: Mode) {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object)) if !object.properties.is_empty() => {
+                self.object(name, schema, mode)
+            }
+            SchemaDef::Enum { value } => self.enumeration(name, &schema.metadata, value),
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value }
+                if self.is_union(value) && mode == Mode::Output =>
+            {
+                self.union(name, &schema.metadata, value);
+            }
+            // Other definitions are only used through references.
+            _ => {}
+        }
+    }
+
+    fn object(&mut self, name: &str, schema: &Schema, mode: Mode) {
+        let object = match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object)) => object,
+            _ => return,
+        };
+
+        // Inline types are named after the object type, not the input type.
+        let base = match mode {
+            Mode::Output => name,
+            Mode::Input => name.strip_suffix("Input").unwrap_or(name),
+        };
+        let mut fields = Names::default();
+        let mut body = String::new();
+
+        for (property, schema) in &object.properties {
+            let field = fields.unique(field_name(property));
+            let field_type = self.render(schema, &format!("{}{}", base, type_name(property)), mode);
+
+            body.push_str(&description(&schema.metadata, INDENT));
+            body.push_str(&format!(
+                "{}{}: {}\n",
+                INDENT,
+                field,
+                field_type.field(object.required.contains(property))
+            ));
+        }
+
+        let keyword = match mode {
+            Mode::Output => "type",
+            Mode::Input => "input",
+        };
+
+        self.blocks.push(format!(
+            "{}{} {} {{\n{}}}",
+            description(&schema.metadata, ""),
+            keyword,
+            name,
+            body
+        ));
+    }
+
+    fn enumeration(&mut self, name: &str, metadata: &Metadata, values: &[String]) {
+        let mut names = Names::default();
+        let body = values
+            .iter()
+            .map(|value| {
+                let mut value = screaming_snake_case(value);
+
+                if value.is_empty() || value.starts_with(|c: char| c.is_ascii_digit()) {
+                    value = format!("_{}", value);
+                }
+
+                format!("{}{}\n", INDENT, names.unique(value))
+            })
+            .collect::<String>();
+
+        self.blocks.push(format!(
+            "{}enum {} {{\n{}}}",
+            description(metadata, ""),
+            name,
+            body
+        ));
+    }
+
+    /// Whether the non-null members are all object types, so can be a union
+    fn is_union(&self, schemas: &[Schema]) -> bool {
+        let mut members = schemas
+            .iter()
+            .filter(|schema| !matches!(schema.schema, SchemaDef::Type(SchemaType::Null {})))
+            .peekable();
+
+        members.peek().is_some()
+            && members.all(|schema| match &schema.schema {
+                SchemaDef::Ref { value } => local_definition(self.file, value)
+                    .is_some_and(|(_, schema)| is_object_type(schema)),
+                _ => is_object_type(schema),
+            })
+    }
+
+    fn union(&mut self, name: &str, metadata: &Metadata, schemas: &[Schema]) {
+        let mut members = vec![];
+
+        for (index, schema) in schemas.iter().enumerate() {
+            if !matches!(schema.schema, SchemaDef::Type(SchemaType::Null {})) {
+                let member = self.render(schema, &format!("{}{}", name, index + 1), Mode::Output);
+
+                if !members.contains(&member.expression) {
+                    members.push(member.expression);
+                }
+            }
+        }
+
+        self.blocks.push(format!(
+            "{}union {} = {}",
+            description(metadata, ""),
+            name,
+            members.join(" | ")
+        ));
+    }
+
+    /// The type of a schema, where the name is used for any inline types
+    fn render(&mut self, schema: &Schema, name: &str, mode: Mode) -> Type {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Boolean {}) => Type::new("Boolean"),
+            SchemaDef::Type(SchemaType::String { .. }) => Type::new("String"),
+            SchemaDef::Type(SchemaType::Integer { .. }) => Type::new("Int"),
+            SchemaDef::Type(SchemaType::Number { .. }) => Type::new("Float"),
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                ..
+            }) => {
+                let item =
+                    if prefix_items.is_empty() && !matches!(items.schema, SchemaDef::Empty {}) {
+                        let item = self.render(items, &format!("{}Item", name), mode);
+                        item.field(true)
+                    } else {
+                        self.json().expression
+                    };
+
+                Type::new(&format!("[{}]", item))
+            }
+            SchemaDef::Type(SchemaType::Object(object)) if !object.properties.is_empty() => {
+                let name = match mode {
+                    Mode::Output => self.names.unique(name.to_string()),
+                    Mode::Input => self.names.unique(format!("{}Input", name)),
+                };
+                self.object(&name, schema, mode);
+
+                Type::new(&name)
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, schema)) => match &schema.schema {
+                    SchemaDef::Type(SchemaType::Object(object))
+                        if !object.properties.is_empty() =>
+                    {
+                        match mode {
+                            Mode::Output => Type::new(&self.definitions[definition]),
+                            Mode::Input => Type::new(&self.input_definitions[definition]),
+                        }
+                    }
+                    SchemaDef::Enum { .. } => Type::new(&self.definitions[definition]),
+                    SchemaDef::OneOf { value } | SchemaDef::AnyOf { value }
+                        if self.is_union(value) && mode == Mode::Output =>
+                    {
+                        Type {
+                            nullable: value.iter().any(|schema| {
+                                matches!(schema.schema, SchemaDef::Type(SchemaType::Null {}))
+                            }),
+                            ..Type::new(&self.definitions[definition])
+                        }
+                    }
+                    _ if self.expanding.iter().any(|name| name == definition) => self.json(),
+                    _ => {
+                        self.expanding.push(definition.to_string());
+                        let name = self.definitions[definition].clone();
+                        let result = self.render(schema, &name, mode);
+                        self.expanding.pop();
+
+                        result
+                    }
+                },
+                None => self.json(),
+            },
+            SchemaDef::Enum { value } => match self.enums.get(name) {
+                Some(name) => Type::new(name),
+                None => {
+                    let enumeration = self.names.unique(name.to_string());
+                    self.enums.insert(name.to_string(), enumeration.clone());
+                    self.enumeration(&enumeration, &schema.metadata, value);
+
+                    Type::new(&enumeration)
+                }
+            },
+            SchemaDef::Const { value } => match value {
+                Value::Bool(_) => Type::new("Boolean"),
+                Value::Number(number) if number.is_i64() => Type::new("Int"),
+                Value::Number(_) => Type::new("Float"),
+                Value::String(_) => Type::new("String"),
+                _ => self.json(),
+            },
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let non_null = value
+                    .iter()
+                    .filter(|schema| !matches!(schema.schema, SchemaDef::Type(SchemaType::Null {})))
+                    .collect::<Vec<_>>();
+                let nullable = non_null.len() < value.len();
+
+                let mut result = match non_null.as_slice() {
+                    [member] => self.render(member, name, mode),
+                    _ if self.is_union(value) && mode == Mode::Output => {
+                        let name = self.names.unique(name.to_string());
+                        self.union(&name, &schema.metadata, value);
+
+                        Type::new(&name)
+                    }
+                    // Input types can't include unions.
+                    _ => self.json(),
+                };
+
+                result.nullable |= nullable;
+                result
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => self.render(&value[0], name, mode),
+            SchemaDef::Type(SchemaType::Null {})
+            | SchemaDef::Type(SchemaType::Object(_))
+            | SchemaDef::AllOf { .. }
+            | SchemaDef::Empty {} => self.json(),
+        }
+    }
+}
+
+fn type_name(name: &str) -> String {
+    let name = pascal_case(name);
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// The property name, with characters that aren't allowed in GraphQL names replaced
+fn field_name(property: &str) -> String {
+    let name = property
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+fn description(metadata: &Metadata, indent: &str) -> String {
+    match metadata
+        .description
+        .as_deref()
+        .or(metadata.title.as_deref())
+    {
+        Some(description) => {
+            let lines = description
+                .replace("\"\"\"", "\\\"\"\"")
+                .lines()
+                .map(|line| format!("{}{}", indent, line).trim_end().to_string() + "\n")
+                .collect::<String>();
+
+            format!("{}\"\"\"\n{}{}\"\"\"\n", indent, lines, indent)
+        }
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graphql_types() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "description": "The ID" },
+                "status": { "$ref": "#/$defs/status" },
+                "line-items": { "type": "array", "items": { "$ref": "#/$defs/line" } },
+                "note": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                "payment": { "$ref": "#/$defs/payment" },
+                "metadata": { "type": "object" }
+            },
+            "required": ["id", "status", "note", "payment"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "line": {
+                    "type": "object",
+                    "properties": { "sku": { "type": "string" } },
+                    "required": ["sku"]
+                },
+                "payment": { "oneOf": [{ "$ref": "#/$defs/card" }, { "$ref": "#/$defs/cash" }] },
+                "card": { "type": "object", "properties": { "number": { "type": "string" } } },
+                "cash": { "type": "object", "properties": { "currency": { "type": "string" } } }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            graphql(&file, "ignored", true),
+            r#""""
+Any JSON value
+"""
+scalar JSON
+
+type Order {
+  """
+  The ID
+  """
+  id: Int!
+  status: Status!
+  line_items: [Line!]
+  note: String
+  payment: Payment!
+  metadata: JSON
+}
+
+enum Status {
+  OPEN
+  IN_PROGRESS
+}
+
+type Line {
+  sku: String!
+}
+
+union Payment = Card | Cash
+
+type Card {
+  number: String
+}
+
+type Cash {
+  currency: String
+}
+
+input OrderInput {
+  """
+  The ID
+  """
+  id: Int!
+  status: Status!
+  line_items: [LineInput!]
+  note: String
+  payment: JSON!
+  metadata: JSON
+}
+
+input LineInput {
+  sku: String!
+}
+
+input CardInput {
+  number: String
+}
+
+input CashInput {
+  currency: String
+}
+"#
+        );
+    }
+
+    #[test]
+    fn graphql_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            graphql(&node, "tags", false),
+            r#"type Node {
+  label: String!
+  children: [Node2!]
+}
+
+type Node2 {
+  value: Int
+  parent: Node2
+}
+"#
+        );
+
+        assert_eq!(graphql(&tags, "tags", false), "");
+    }
+}
@@ -0,0 +1,657 @@
+use super::{local_definition, pascal_case, screaming_snake_case, words, DefinitionNames, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+const INDENT: &str = "    ";
+
+const JSON_IGNORE_PROPERTIES: &str = "com.fasterxml.jackson.annotation.JsonIgnoreProperties";
+const JSON_INCLUDE: &str = "com.fasterxml.jackson.annotation.JsonInclude";
+const JSON_NODE: &str = "com.fasterxml.jackson.databind.JsonNode";
+const JSON_PROPERTY: &str = "com.fasterxml.jackson.annotation.JsonProperty";
+const LIST: &str = "java.util.List";
+const MAP: &str = "java.util.Map";
+
+const KEYWORDS: [&str; 53] = [
+    "_",
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+];
+
+/// Type names that the generated code uses, which nested classes can't shadow
+const RESERVED_TYPES: [&str; 10] = [
+    "Boolean",
+    "Double",
+    "JsonIgnoreProperties",
+    "JsonInclude",
+    "JsonNode",
+    "JsonProperty",
+    "List",
+    "Long",
+    "Map",
+    "String",
+];
+
+/// Generate a Java file with records for the top-level schema and each definition
+///
+/// The types are nested in a class named after the given name with a `Models` suffix. Objects with
+/// properties become records with Jackson annotations, where required properties are checked when
+/// deserializing and properties that aren't required are boxed and left out when they're null.
+/// String enums become enums. Types can't be aliased in Java, so other definitions are used
+/// directly, and unions (and references to other files) are left as `JsonNode`.
+pub fn java(file: &SchemaFile, name: &str, package: Option<&str>) -> String {
+    let mut names = Names::default();
+
+    for reserved in RESERVED_TYPES {
+        names.unique(reserved.to_string());
+    }
+
+    let class = names.unique(format!("{}Models", type_name(name)));
+    let root = file
+        .schema
+        .as_ref()
+        .filter(|schema| !matches!(schema, SchemaDef::Empty {}))
+        .map(|schema| {
+            let name = names.unique(type_name(file.metadata.title.as_deref().unwrap_or(name)));
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            (name, schema)
+        });
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| (name.to_string(), names.unique(type_name(name))))
+        .collect();
+
+    let mut generator = Generator {
+        file,
+        definitions,
+        names,
+        imports: BTreeSet::new(),
+        expanding: vec![],
+        blocks: vec![],
+    };
+
+    if let Some((name, schema)) = &root {
+        generator.definition(name, schema);
+    }
+
+    for (name, schema) in file.definitions.iter().flatten() {
+        let name = generator.definitions[name.as_str()].clone();
+        generator.definition(&name, schema);
+    }
+
+    let mut result = "// Code generated by json-schema-tools. DO NOT EDIT.\n\n".to_string();
+
+    if let Some(package) = package {
+        result.push_str(&format!("package {};\n\n", package));
+    }
+
+    if !generator.imports.is_empty() {
+        for import in &generator.imports {
+            result.push_str(&format!("import {};\n", import));
+        }
+
+        result.push('\n');
+    }
+
+    result.push_str(&format!(
+        "public final class {} {{\n{}private {}() {{}}\n",
+        class, INDENT, class
+    ));
+
+    for block in generator.blocks {
+        result.push('\n');
+        result.push_str(&block);
+    }
+
+    result.push_str("}\n");
+    result
+}
+
+/// Whether the schema is a record
+fn is_record(schema: &Schema) -> bool {
+    matches!(
+        &schema.schema,
+        SchemaDef::Type(SchemaType::Object(object))
+            if !object.properties.is_empty() || object.no_additional_properties()
+    )
+}
+
+struct Type {
+    expression: String,
+}
+
+impl Type {
+    fn new(expression: &str) -> Self {
+        Self {
+            expression: expression.to_string(),
+        }
+    }
+
+    /// The wrapper class for a primitive type, which can be null
+    fn boxed(self) -> Self {
+        match self.expression.as_str() {
+            "boolean" => Self::new("Boolean"),
+            "double" => Self::new("Double"),
+            "long" => Self::new("Long"),
+            _ => self,
+        }
+    }
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    definitions: DefinitionNames,
+    /// Nested type names
+    names: Names,
+    imports: BTreeSet<&'static str>,
+    /// Definitions that are being used directly, to avoid expanding recursive ones forever
+    expanding: Vec<String>,
+    /// Nested type declarations
+    blocks: Vec<String>,
+}
+
+impl Generator<'_> {
+    fn import(&mut self, import: &'static str) -> &'static str {
+        self.imports.insert(import);
+        import.rsplit('.').next().unwrap_or(import)
+    }
+
+    fn json_node(&mut self) -> Type {
+        Type::new(self.import(JSON_NODE))
+    }
+
+    /// Add a record or enum for the top-level schema or a definition
+    fn definition(&mut self, name: &str, schema: &Schema) {
+        match &schema.schema {
+            _ if is_record(schema) => self.record(name, schema),
+            SchemaDef::Enum { value } => self.enumeration(name, &schema.metadata, value),
+            // Other definitions are used directly where they're referenced.
+            _ => {}
+        }
+    }
+
+    fn record(&mut self, name: &str, schema: &Schema) {
+        let object = match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object)) => object,
+            _ => return,
+        };
+
+        let mut fields = Names::default();
+        let mut components = vec![];
+        let mut parameters = vec![];
+
+        for (property, schema) in &object.properties {
+            let field = fields.unique(field_name(property));
+            let field_type = self.render(schema, &format!("{}{}", name, type_name(property)));
+            let key = Value::String(property.to_string()).to_string();
+            let json_property = self.import(JSON_PROPERTY);
+
+            let component = if object.required.contains(property) {
+                let field_type = if is_nullable(schema) {
+                    field_type.boxed()
+                } else {
+                    field_type
+                };
+
+                format!(
+                    "@{}(value = {}, required = true) {} {}",
+                    json_property, key, field_type.expression, field
+                )
+            } else {
+                let json_include = self.import(JSON_INCLUDE);
+
+                format!(
+                    "@{}({}) @{}({}.Include.NON_NULL) {} {}",
+                    json_property,
+                    key,
+                    json_include,
+                    json_include,
+                    field_type.boxed().expression,
+                    field
+                )
+            };
+
+            if let Some(description) = &schema.metadata.description {
+                parameters.push((field, description.clone()));
+            }
+
+            components.push(component);
+        }
+
+        let mut block = javadoc(&schema.metadata, &parameters);
+
+        if !object.no_additional_properties() {
+            block.push_str(&format!(
+                "{}@{}(ignoreUnknown = true)\n",
+                INDENT,
+                self.import(JSON_IGNORE_PROPERTIES)
+            ));
+        }
+
+        if components.is_empty() {
+            block.push_str(&format!("{}public record {}() {{}}\n", INDENT, name));
+        } else {
+            block.push_str(&format!("{}public record {}(\n", INDENT, name));
+            block.push_str(
+                &components
+                    .iter()
+                    .map(|component| format!("{}{}{}", INDENT, INDENT, component))
+                    .collect::<Vec<_>>()
+                    .join(",\n"),
+            );
+            block.push_str(&format!("\n{}) {{}}\n", INDENT));
+        }
+
+        self.blocks.push(block);
+    }
+
+    fn enumeration(&mut self, name: &str, metadata: &Metadata, values: &[String]) {
+        let json_property = self.import(JSON_PROPERTY);
+        let mut constants = Names::default();
+        let constants = values
+            .iter()
+            .map(|value| {
+                let constant = screaming_snake_case(value);
+                let constant = if constant.is_empty() {
+                    "EMPTY".to_string()
+                } else if constant.starts_with(|c: char| c.is_ascii_digit()) {
+                    format!("_{}", constant)
+                } else {
+                    constant
+                };
+
+                format!(
+                    "{}{}@{}({})\n{}{}{}",
+                    INDENT,
+                    INDENT,
+                    json_property,
+                    Value::String(value.clone()),
+                    INDENT,
+                    INDENT,
+                    constants.unique(constant)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut block = javadoc(metadata, &[]);
+        block.push_str(&format!("{}public enum {} {{\n", INDENT, name));
+
+        if !constants.is_empty() {
+            block.push_str(&constants.join(",\n"));
+            block.push('\n');
+        }
+
+        block.push_str(&format!("{}}}\n", INDENT));
+        self.blocks.push(block);
+    }
+
+    /// The type of a schema, where the name is used for any nested records or enums
+    fn render(&mut self, schema: &Schema, name: &str) -> Type {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Boolean {}) => Type::new("boolean"),
+            SchemaDef::Type(SchemaType::String { .. }) => Type::new("String"),
+            SchemaDef::Type(SchemaType::Integer { .. }) => Type::new("long"),
+            SchemaDef::Type(SchemaType::Number { .. }) => Type::new("double"),
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                ..
+            }) => {
+                let item = if prefix_items.is_empty() {
+                    self.render(items, &format!("{}Item", name)).boxed()
+                } else {
+                    // Tuples can have items of different types.
+                    self.json_node()
+                };
+                let list = self.import(LIST);
+
+                Type::new(&format!("{}<{}>", list, item.expression))
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if is_record(schema) {
+                    let name = self.names.unique(name.to_string());
+                    self.record(&name, schema);
+
+                    Type::new(&name)
+                } else {
+                    let value = match &object.additional_properties {
+                        AdditionalProperties::Schema(schema) => {
+                            self.render(schema, &format!("{}Value", name)).boxed()
+                        }
+                        AdditionalProperties::Boolean(_) => self.json_node(),
+                    };
+                    let map = self.import(MAP);
+
+                    Type::new(&format!("{}<String, {}>", map, value.expression))
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, schema)) => {
+                    if is_record(schema) || matches!(schema.schema, SchemaDef::Enum { .. }) {
+                        Type::new(&self.definitions[definition])
+                    } else if self.expanding.iter().any(|name| name == definition) {
+                        self.json_node()
+                    } else {
+                        self.expanding.push(definition.to_string());
+                        let name = self.definitions[definition].clone();
+                        let result = self.render(schema, &name);
+                        self.expanding.pop();
+
+                        result
+                    }
+                }
+                None => self.json_node(),
+            },
+            SchemaDef::Enum { value } => {
+                let name = self.names.unique(name.to_string());
+                self.enumeration(&name, &schema.metadata, value);
+
+                Type::new(&name)
+            }
+            SchemaDef::Const { value } => match value {
+                Value::Bool(_) => Type::new("boolean"),
+                Value::Number(number) if number.is_i64() => Type::new("long"),
+                Value::Number(_) => Type::new("double"),
+                Value::String(_) => Type::new("String"),
+                _ => self.json_node(),
+            },
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let members = value
+                    .iter()
+                    .filter(|schema| !matches!(schema.schema, SchemaDef::Type(SchemaType::Null {})))
+                    .collect::<Vec<_>>();
+
+                match members.as_slice() {
+                    // A union with null is boxed.
+                    [member] if members.len() < value.len() => self.render(member, name).boxed(),
+                    [member] => self.render(member, name),
+                    _ => self.json_node(),
+                }
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => self.render(&value[0], name),
+            SchemaDef::Type(SchemaType::Null {})
+            | SchemaDef::AllOf { .. }
+            | SchemaDef::Empty {} => self.json_node(),
+        }
+    }
+}
+
+/// Whether the schema allows null (in which case required values still need to be boxed)
+fn is_nullable(schema: &Schema) -> bool {
+    match &schema.schema {
+        SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => value
+            .iter()
+            .any(|schema| matches!(schema.schema, SchemaDef::Type(SchemaType::Null {}))),
+        _ => false,
+    }
+}
+
+fn type_name(name: &str) -> String {
+    let name = pascal_case(name);
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// A lower camel case name that isn't a keyword
+fn field_name(property: &str) -> String {
+    let words = words(property);
+    let name = match words.split_first() {
+        Some((first, rest)) => first.to_ascii_lowercase() + &pascal_case(&rest.join("_")),
+        None => String::new(),
+    };
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field{}", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// A Javadoc comment with the description and any component descriptions
+fn javadoc(metadata: &Metadata, parameters: &[(String, String)]) -> String {
+    let mut lines = vec![];
+
+    if let Some(description) = metadata.description.as_ref().or(metadata.title.as_ref()) {
+        lines.extend(description.lines().map(str::to_string));
+    }
+
+    if !parameters.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        for (name, description) in parameters {
+            lines.push(format!(
+                "@param {} {}",
+                name,
+                description.replace('\n', " ")
+            ));
+        }
+    }
+
+    if lines.is_empty() {
+        String::new()
+    } else {
+        let body = lines
+            .iter()
+            .map(|line| {
+                format!("{} * {}", INDENT, line.replace("*/", "*&#47;"))
+                    .trim_end()
+                    .to_string()
+                    + "\n"
+            })
+            .collect::<String>();
+
+        format!("{}/**\n{}{} */\n", INDENT, body, INDENT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn java_records() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "description": "The ID" },
+                "status": { "$ref": "#/$defs/status" },
+                "line-items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string" } },
+                        "required": ["sku"],
+                        "additionalProperties": false
+                    }
+                },
+                "quantity": { "type": "integer" },
+                "note": { "anyOf": [{ "type": "number" }, { "type": "null" }] },
+                "payment": { "oneOf": [{ "type": "string" }, { "type": "integer" }] }
+            },
+            "required": ["id", "status", "note"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            java(&file, "order", Some("com.example")),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+package com.example;
+
+import com.fasterxml.jackson.annotation.JsonIgnoreProperties;
+import com.fasterxml.jackson.annotation.JsonInclude;
+import com.fasterxml.jackson.annotation.JsonProperty;
+import com.fasterxml.jackson.databind.JsonNode;
+import java.util.List;
+
+public final class OrderModels {
+    private OrderModels() {}
+
+    public record OrderLineItemsItem(
+        @JsonProperty(value = "sku", required = true) String sku
+    ) {}
+
+    /**
+     * An order
+     *
+     * @param id The ID
+     */
+    @JsonIgnoreProperties(ignoreUnknown = true)
+    public record Order(
+        @JsonProperty(value = "id", required = true) long id,
+        @JsonProperty(value = "status", required = true) Status status,
+        @JsonProperty("line-items") @JsonInclude(JsonInclude.Include.NON_NULL) List<OrderLineItemsItem> lineItems,
+        @JsonProperty("quantity") @JsonInclude(JsonInclude.Include.NON_NULL) Long quantity,
+        @JsonProperty(value = "note", required = true) Double note,
+        @JsonProperty("payment") @JsonInclude(JsonInclude.Include.NON_NULL) JsonNode payment
+    ) {}
+
+    public enum Status {
+        @JsonProperty("open")
+        OPEN,
+        @JsonProperty("in-progress")
+        IN_PROGRESS
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn java_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            java(&node, "tags", None),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+import com.fasterxml.jackson.annotation.JsonIgnoreProperties;
+import com.fasterxml.jackson.annotation.JsonInclude;
+import com.fasterxml.jackson.annotation.JsonProperty;
+import java.util.List;
+
+public final class TagsModels {
+    private TagsModels() {}
+
+    @JsonIgnoreProperties(ignoreUnknown = true)
+    public record Node(
+        @JsonProperty(value = "label", required = true) String label,
+        @JsonProperty("children") @JsonInclude(JsonInclude.Include.NON_NULL) List<Node2> children
+    ) {}
+
+    @JsonIgnoreProperties(ignoreUnknown = true)
+    public record Node2(
+        @JsonProperty("value") @JsonInclude(JsonInclude.Include.NON_NULL) Long value,
+        @JsonProperty("parent") @JsonInclude(JsonInclude.Include.NON_NULL) Node2 parent
+    ) {}
+}
+"#
+        );
+
+        assert_eq!(
+            java(&tags, "tags", None),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+public final class TagsModels {
+    private TagsModels() {}
+}
+"#
+        );
+    }
+}
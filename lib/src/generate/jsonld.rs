@@ -0,0 +1,481 @@
+use crate::constants::*;
+use crate::reference::Reference;
+use serde_json::{json, Map, Value};
+
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Type mappings for formats (where `@id` means the values are IRIs)
+const FORMATS: [(&str, &str); 8] = [
+    ("date", "xsd:date"),
+    ("date-time", "xsd:dateTime"),
+    ("duration", "xsd:duration"),
+    ("iri", "@id"),
+    ("iri-reference", "@id"),
+    ("time", "xsd:time"),
+    ("uri", "@id"),
+    ("uri-reference", "@id"),
+];
+
+/// Type mappings for types without a format
+const TYPES: [(&str, &str); 3] = [
+    ("boolean", "xsd:boolean"),
+    ("integer", "xsd:integer"),
+    ("number", "xsd:double"),
+];
+
+/// How many references can be followed for a single property
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Generate a JSON-LD `@context` mapping properties to terms in the given vocabulary
+///
+/// Properties of the top-level schema (or of each definition, if there isn't one) become terms
+/// with IRIs in the vocabulary, which is also the `@vocab` for properties the schema doesn't
+/// list. Values are typed from their formats (dates and times as XML Schema types, and URIs as
+/// IRIs) or else their types, and arrays are lists (or sets if their items are unique). Nested
+/// objects with properties that need different term definitions get property-scoped contexts
+/// (which need JSON-LD 1.1). References to other files aren't followed.
+pub fn jsonld(value: &Value, vocab: &str) -> Value {
+    let mut generator = Generator {
+        root: value,
+        vocab,
+        uses_xsd: false,
+        is_scoped: false,
+        expanding: vec![],
+    };
+
+    let has_schema = value.as_object().is_some_and(|fields| {
+        fields
+            .keys()
+            .any(|key| ![ID_KEY, SCHEMA_KEY, DEFS_KEY, COMMENT_KEY].contains(&key.as_str()))
+    });
+
+    let terms = if has_schema {
+        generator.terms(value, &Map::new())
+    } else {
+        let mut terms = Map::new();
+
+        for (name, schema) in value
+            .get(DEFS_KEY)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            generator.expanding.push(name.clone());
+
+            for (term, definition) in generator.terms(schema, &terms) {
+                terms.entry(term).or_insert(definition);
+            }
+
+            generator.expanding.pop();
+        }
+
+        terms
+    };
+
+    let mut context = Map::new();
+
+    if generator.is_scoped {
+        context.insert("@version".to_string(), json!(1.1));
+    }
+
+    context.insert("@vocab".to_string(), json!(vocab));
+
+    if generator.uses_xsd {
+        context.insert("xsd".to_string(), json!(XSD));
+    }
+
+    context.extend(terms);
+
+    json!({ "@context": context })
+}
+
+struct Generator<'a> {
+    root: &'a Value,
+    vocab: &'a str,
+    uses_xsd: bool,
+    /// Whether any property-scoped contexts have been added
+    is_scoped: bool,
+    /// Definitions whose terms are being generated, to avoid expanding recursive ones forever
+    expanding: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+    /// Term definitions for an object's properties that differ from the ones in effect
+    fn terms(&mut self, schema: &'a Value, inherited: &Map<String, Value>) -> Map<String, Value> {
+        let mut properties = vec![];
+        self.properties(schema, &mut properties, 0);
+
+        let mut definitions = Map::new();
+        let mut objects = vec![];
+
+        for (property, schema) in properties {
+            // Terms that look like keywords are ignored by JSON-LD processors.
+            if property.starts_with('@') || definitions.contains_key(property) {
+                continue;
+            }
+
+            let (definition, object) = self.definition(property, schema);
+            definitions.insert(property.to_string(), definition);
+
+            if let Some(object) = object {
+                objects.push((property, object));
+            }
+        }
+
+        let mut in_effect = inherited.clone();
+        in_effect.extend(definitions.clone());
+
+        let mut result = Map::new();
+
+        for (property, definition) in definitions {
+            let scoped = match objects.iter().find(|(name, _)| *name == property) {
+                Some((_, (name, object))) => {
+                    if let Some(name) = name {
+                        self.expanding.push(name.clone());
+                    }
+
+                    let scoped = self.terms(object, &in_effect);
+
+                    if name.is_some() {
+                        self.expanding.pop();
+                    }
+
+                    scoped
+                }
+                None => Map::new(),
+            };
+
+            if scoped.is_empty() {
+                if inherited.get(&property) != Some(&definition) {
+                    result.insert(property, definition);
+                }
+            } else {
+                self.is_scoped = true;
+
+                let mut definition = match definition {
+                    Value::Object(definition) => definition,
+                    id => Map::from_iter([("@id".to_string(), id)]),
+                };
+                definition.insert("@context".to_string(), Value::Object(scoped));
+                result.insert(property, Value::Object(definition));
+            }
+        }
+
+        result
+    }
+
+    /// An object's properties, including those in its `allOf`, `anyOf`, and `oneOf` members
+    fn properties(
+        &self,
+        schema: &'a Value,
+        properties: &mut Vec<(&'a str, &'a Value)>,
+        depth: usize,
+    ) {
+        let fields = match schema.as_object() {
+            Some(fields) => fields,
+            None => return,
+        };
+
+        if let Some(reference) = fields.get(REF_KEY).and_then(Value::as_str) {
+            if depth < MAX_REFERENCE_DEPTH {
+                if let Some((_, schema)) = self.local_definition(reference) {
+                    self.properties(schema, properties, depth + 1);
+                }
+            }
+        }
+
+        if let Some(object) = fields.get(PROPERTIES_KEY).and_then(Value::as_object) {
+            properties.extend(object.iter().map(|(name, schema)| (name.as_str(), schema)));
+        }
+
+        for key in [ALL_OF_KEY, ANY_OF_KEY, ONE_OF_KEY] {
+            for member in fields
+                .get(key)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                self.properties(member, properties, depth);
+            }
+        }
+    }
+
+    /// The term definition for a property, and the object schema for any scoped context (with
+    /// the name of the definition it comes from, if it's referenced)
+    fn definition(
+        &mut self,
+        property: &str,
+        schema: &'a Value,
+    ) -> (Value, Option<(Option<String>, &'a Value)>) {
+        let (name, schema) = self.resolve(schema);
+        let mut definition = Map::new();
+        definition.insert("@id".to_string(), json!(self.iri(property)));
+
+        let (name, value) = if schema.get(TYPE_KEY).and_then(Value::as_str) == Some("array")
+            && schema.get(PREFIX_ITEMS_KEY).is_none()
+        {
+            let container = if schema.get(UNIQUE_ITEMS_KEY) == Some(&Value::Bool(true)) {
+                "@set"
+            } else {
+                "@list"
+            };
+            definition.insert("@container".to_string(), json!(container));
+
+            match schema.get(ITEMS_KEY) {
+                Some(items) => self.resolve(items),
+                None => (None, &Value::Null),
+            }
+        } else {
+            (name, schema)
+        };
+
+        if let Some(value_type) = self.value_type(value) {
+            definition.insert("@type".to_string(), json!(value_type));
+        }
+
+        let is_object = value.get(PROPERTIES_KEY).is_some()
+            || [ALL_OF_KEY, ANY_OF_KEY, ONE_OF_KEY]
+                .iter()
+                .any(|key| value.get(*key).is_some());
+        let is_expanding = name
+            .as_ref()
+            .is_some_and(|name| self.expanding.contains(name));
+        let object = (is_object && !is_expanding).then_some((name, value));
+
+        let definition = if definition.len() == 1 {
+            definition.remove("@id").unwrap_or_default()
+        } else {
+            Value::Object(definition)
+        };
+
+        (definition, object)
+    }
+
+    /// The type mapping for a value, from its format or else its type
+    fn value_type(&mut self, schema: &Value) -> Option<&'static str> {
+        let format = schema.get(FORMAT_KEY).and_then(Value::as_str);
+        let value_type = schema.get(TYPE_KEY).and_then(Value::as_str);
+
+        let result = FORMATS
+            .iter()
+            .find(|(name, _)| Some(*name) == format)
+            .or_else(|| TYPES.iter().find(|(name, _)| Some(*name) == value_type))
+            .map(|(_, mapping)| *mapping)?;
+
+        if result.starts_with("xsd:") {
+            self.uses_xsd = true;
+        }
+
+        Some(result)
+    }
+
+    /// Follow local references and nullable unions to the schema for a value (with the name of
+    /// the last definition followed)
+    fn resolve(&self, mut schema: &'a Value) -> (Option<String>, &'a Value) {
+        let mut name = None;
+
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            let fields = match schema.as_object() {
+                Some(fields) => fields,
+                None => break,
+            };
+
+            let next = if let Some(reference) = fields.get(REF_KEY).and_then(Value::as_str) {
+                self.local_definition(reference)
+                    .map(|(definition, schema)| {
+                        name = Some(definition.to_string());
+                        schema
+                    })
+            } else {
+                [ANY_OF_KEY, ONE_OF_KEY, ALL_OF_KEY]
+                    .iter()
+                    .filter_map(|key| fields.get(*key).and_then(Value::as_array))
+                    .find_map(|members| {
+                        let members = members
+                            .iter()
+                            .filter(|member| {
+                                member.get(TYPE_KEY).and_then(Value::as_str) != Some("null")
+                            })
+                            .collect::<Vec<_>>();
+
+                        match members.as_slice() {
+                            [member] => Some(*member),
+                            _ => None,
+                        }
+                    })
+            };
+
+            match next {
+                Some(next) => schema = next,
+                None => break,
+            }
+        }
+
+        (name, schema)
+    }
+
+    /// The IRI for a property in the vocabulary
+    fn iri(&self, property: &str) -> String {
+        let mut result = self.vocab.to_string();
+
+        for byte in property.bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                result.push(byte as char);
+            } else {
+                result.push_str(&format!("%{:02X}", byte));
+            }
+        }
+
+        result
+    }
+
+    fn local_definition(&self, reference: &str) -> Option<(&'a str, &'a Value)> {
+        let reference = reference.parse::<Reference>().ok()?;
+        let is_local = match &reference {
+            Reference::FragmentOnly { .. } => true,
+            Reference::Both { .. } => {
+                reference.path().as_deref() == self.root.get(ID_KEY).and_then(Value::as_str)
+            }
+            Reference::PathOnly { .. } => false,
+        };
+
+        if is_local {
+            self.root
+                .get(DEFS_KEY)?
+                .as_object()?
+                .get_key_value(reference.name())
+                .map(|(name, schema)| (name.as_str(), schema))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jsonld_context() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "format": "uri" },
+                "name": { "type": "string" },
+                "created": { "type": "string", "format": "date-time" },
+                "quantity": { "type": "integer" },
+                "tags": { "type": "array", "items": { "type": "string" }, "uniqueItems": true },
+                "events": { "type": "array", "items": { "$ref": "#/$defs/event" } },
+                "customer": { "$ref": "#/$defs/customer" },
+                "@type": { "type": "string" }
+            },
+            "$defs": {
+                "event": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "at": { "anyOf": [{ "type": "string", "format": "date" }, { "type": "null" }] }
+                    }
+                },
+                "customer": {
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "integer" },
+                        "referrer": { "$ref": "#/$defs/customer" }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            jsonld(&schema, "https://example.com/vocab#"),
+            json!({
+                "@context": {
+                    "@version": 1.1,
+                    "@vocab": "https://example.com/vocab#",
+                    "xsd": "http://www.w3.org/2001/XMLSchema#",
+                    "id": { "@id": "https://example.com/vocab#id", "@type": "@id" },
+                    "name": "https://example.com/vocab#name",
+                    "created": {
+                        "@id": "https://example.com/vocab#created",
+                        "@type": "xsd:dateTime"
+                    },
+                    "quantity": {
+                        "@id": "https://example.com/vocab#quantity",
+                        "@type": "xsd:integer"
+                    },
+                    "tags": { "@id": "https://example.com/vocab#tags", "@container": "@set" },
+                    "events": {
+                        "@id": "https://example.com/vocab#events",
+                        "@container": "@list",
+                        "@context": {
+                            "at": { "@id": "https://example.com/vocab#at", "@type": "xsd:date" }
+                        }
+                    },
+                    "customer": {
+                        "@id": "https://example.com/vocab#customer",
+                        "@context": {
+                            "id": { "@id": "https://example.com/vocab#id", "@type": "xsd:integer" },
+                            "referrer": "https://example.com/vocab#referrer"
+                        }
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn jsonld_edge_cases() {
+        let node = serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        });
+        let tags = serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        });
+
+        assert_eq!(
+            jsonld(&node, "https://example.com/vocab#"),
+            serde_json::json!({
+                "@context": {
+                    "@version": 1.1,
+                    "@vocab": "https://example.com/vocab#",
+                    "xsd": "http://www.w3.org/2001/XMLSchema#",
+                    "label": "https://example.com/vocab#label",
+                    "children": {
+                        "@id": "https://example.com/vocab#children",
+                        "@container": "@list",
+                        "@context": {
+                            "value": {
+                                "@id": "https://example.com/vocab#value",
+                                "@type": "xsd:integer"
+                            },
+                            "parent": "https://example.com/vocab#parent"
+                        }
+                    }
+                }
+            })
+        );
+
+        assert_eq!(
+            jsonld(&tags, "https://example.com/vocab#"),
+            serde_json::json!({ "@context": { "@vocab": "https://example.com/vocab#" } })
+        );
+    }
+}
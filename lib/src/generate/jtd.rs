@@ -0,0 +1,707 @@
+use crate::constants::*;
+use crate::reference::Reference;
+use serde_json::{json, Map, Value};
+
+/// Keywords that only annotate schemas, which don't need JTD equivalents
+const ANNOTATION_KEYS: [&str; 10] = [
+    COMMENT_KEY,
+    DEFAULT_KEY,
+    DEFS_KEY,
+    "deprecated",
+    EXAMPLES_KEY,
+    ID_KEY,
+    "readOnly",
+    SCHEMA_KEY,
+    TITLE_KEY,
+    "writeOnly",
+];
+
+/// JTD integer types and their ranges, narrowest (and unsigned) first
+const INTEGER_TYPES: [(&str, i64, i64); 6] = [
+    ("uint8", 0, 255),
+    ("int8", -128, 127),
+    ("uint16", 0, 65535),
+    ("int16", -32768, 32767),
+    ("uint32", 0, 4294967295),
+    ("int32", -2147483648, 2147483647),
+];
+
+/// A JSON Schema keyword with no exact JTD equivalent
+#[derive(Clone, Debug, PartialEq)]
+pub struct LossyConstruct {
+    /// The path to the schema with the keyword
+    pub path: Vec<String>,
+    pub keyword: String,
+    pub value: Value,
+}
+
+/// A generated JTD schema
+#[derive(Clone, Debug, PartialEq)]
+pub struct Jtd {
+    pub schema: Value,
+    /// Keywords that were dropped, so that the JTD schema accepts more than the JSON Schema
+    pub lossy: Vec<LossyConstruct>,
+}
+
+/// Generate a JSON Type Definition (RFC 8927) schema, with a definition for each definition
+///
+/// Objects become properties forms (or values forms if they only have additional properties),
+/// string enums and constants become enums, date-time strings become timestamps, and unions with
+/// `null` become nullable. Integers get the narrowest JTD integer type that exactly matches their
+/// bounds, or else `float64`. Unions of objects with a required property that has a different
+/// string constant in each become discriminators. Every other keyword (like `pattern` or
+/// `minItems`, or a union that isn't discriminated) is reported and dropped.
+pub fn jtd(value: &Value) -> Jtd {
+    let mut generator = Generator {
+        root: value,
+        lossy: vec![],
+    };
+
+    let root = generator.convert(value, &[], None);
+    let definitions = value
+        .get(DEFS_KEY)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, schema)| {
+            let schema = generator.convert(schema, &[DEFS_KEY.to_string(), name.clone()], None);
+            (name.clone(), Value::Object(schema))
+        })
+        .collect::<Map<_, _>>();
+
+    let mut result = Map::new();
+
+    if !definitions.is_empty() {
+        result.insert("definitions".to_string(), Value::Object(definitions));
+    }
+
+    result.extend(root);
+
+    // Definitions that are discriminator branches are converted twice.
+    let mut lossy: Vec<LossyConstruct> = vec![];
+
+    for construct in generator.lossy {
+        if !lossy.contains(&construct) {
+            lossy.push(construct);
+        }
+    }
+
+    Jtd {
+        schema: Value::Object(result),
+        lossy,
+    }
+}
+
+struct Generator<'a> {
+    root: &'a Value,
+    lossy: Vec<LossyConstruct>,
+}
+
+impl<'a> Generator<'a> {
+    /// Convert a schema, where a discriminator tag is left out of the properties
+    fn convert(
+        &mut self,
+        schema: &'a Value,
+        path: &[String],
+        tag: Option<&str>,
+    ) -> Map<String, Value> {
+        let fields = match schema {
+            Value::Object(fields) => fields,
+            Value::Bool(true) => return Map::new(),
+            // `false` is the same as `{ "not": {} }`.
+            _ => {
+                self.lossy.push(LossyConstruct {
+                    path: path.to_vec(),
+                    keyword: NOT_KEY.to_string(),
+                    value: json!({}),
+                });
+
+                return Map::new();
+            }
+        };
+
+        let start = self.lossy.len();
+        let mut consumed = vec![DESCRIPTION_KEY];
+        let mut result = self.form(fields, path, tag, &mut consumed);
+
+        // Keywords of the schema are reported before those of its subschemas.
+        let unconsumed = fields
+            .iter()
+            .filter(|(key, _)| {
+                !consumed.contains(&key.as_str()) && !ANNOTATION_KEYS.contains(&key.as_str())
+            })
+            .map(|(key, value)| LossyConstruct {
+                path: path.to_vec(),
+                keyword: key.clone(),
+                value: value.clone(),
+            })
+            .collect::<Vec<_>>();
+        self.lossy.splice(start..start, unconsumed);
+
+        if let Some(description) = fields.get(DESCRIPTION_KEY) {
+            result.insert(
+                "metadata".to_string(),
+                json!({ DESCRIPTION_KEY: description }),
+            );
+        }
+
+        result
+    }
+
+    /// The JTD form for a schema, recording the keywords it accounts for
+    fn form(
+        &mut self,
+        fields: &'a Map<String, Value>,
+        path: &[String],
+        tag: Option<&str>,
+        consumed: &mut Vec<&'static str>,
+    ) -> Map<String, Value> {
+        let mut types = match fields.get(TYPE_KEY) {
+            Some(Value::String(name)) => vec![name.as_str()],
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let mut nullable = types.len() > 1 && types.contains(&"null");
+        types.retain(|name| *name != "null" || !nullable);
+
+        let mut result = if let Some(reference) = fields.get(REF_KEY).and_then(Value::as_str) {
+            match self.local_definition(reference) {
+                Some((name, _)) => {
+                    consumed.push(REF_KEY);
+                    json!({ "ref": name })
+                }
+                None => json!({}),
+            }
+        } else if let Some(values) = fields.get(ENUM_KEY).and_then(Value::as_array) {
+            let strings = values
+                .iter()
+                .filter(|value| value.is_string())
+                .collect::<Vec<_>>();
+            let nulls = values.iter().filter(|value| value.is_null()).count();
+
+            if !strings.is_empty() && strings.len() + nulls == values.len() {
+                consumed.extend([ENUM_KEY, TYPE_KEY]);
+                nullable |= nulls > 0;
+                json!({ ENUM_KEY: strings })
+            } else {
+                json!({})
+            }
+        } else if let Some(Value::String(value)) = fields.get(CONST_KEY) {
+            consumed.extend([CONST_KEY, TYPE_KEY]);
+            json!({ ENUM_KEY: [value] })
+        } else if let Some((keyword, branches)) = [ONE_OF_KEY, ANY_OF_KEY]
+            .into_iter()
+            .find_map(|keyword| Some((keyword, fields.get(keyword)?.as_array()?)))
+        {
+            let null = json!({ TYPE_KEY: "null" });
+            let non_null = branches
+                .iter()
+                .enumerate()
+                .filter(|(_, branch)| **branch != null)
+                .collect::<Vec<_>>();
+
+            if let ([(index, branch)], true) = (non_null.as_slice(), branches.len() == 2) {
+                consumed.push(keyword);
+                nullable = true;
+                Value::Object(self.convert(
+                    branch,
+                    &child(path, &[keyword, &index.to_string()]),
+                    None,
+                ))
+            } else {
+                match self.discriminator(branches, path, keyword) {
+                    Some(discriminator) => {
+                        consumed.push(keyword);
+                        discriminator
+                    }
+                    None => json!({}),
+                }
+            }
+        } else if let Some([schema]) = fields
+            .get(ALL_OF_KEY)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+        {
+            consumed.push(ALL_OF_KEY);
+            Value::Object(self.convert(schema, &child(path, &[ALL_OF_KEY, "0"]), tag))
+        } else {
+            match types.as_slice() {
+                ["boolean"] => {
+                    consumed.push(TYPE_KEY);
+                    json!({ TYPE_KEY: "boolean" })
+                }
+                ["string"] => {
+                    consumed.push(TYPE_KEY);
+
+                    if fields.get(FORMAT_KEY) == Some(&json!("date-time")) {
+                        consumed.push(FORMAT_KEY);
+                        json!({ TYPE_KEY: "timestamp" })
+                    } else {
+                        json!({ TYPE_KEY: "string" })
+                    }
+                }
+                ["integer"] => json!({ TYPE_KEY: integer_type(fields, consumed) }),
+                ["number"] => {
+                    consumed.push(TYPE_KEY);
+                    json!({ TYPE_KEY: "float64" })
+                }
+                ["array"] => {
+                    consumed.extend([TYPE_KEY, ITEMS_KEY]);
+                    let elements = match fields.get(ITEMS_KEY) {
+                        Some(items) => self.convert(items, &child(path, &[ITEMS_KEY]), None),
+                        None => Map::new(),
+                    };
+
+                    json!({ "elements": elements })
+                }
+                ["object"] => {
+                    consumed.push(TYPE_KEY);
+                    Value::Object(self.object(fields, path, tag, consumed))
+                }
+                _ => json!({}),
+            }
+        };
+
+        if nullable {
+            result["nullable"] = json!(true);
+        }
+
+        match result {
+            Value::Object(result) => result,
+            _ => Map::new(),
+        }
+    }
+
+    /// A properties form (or a values form, if there are no properties and it isn't a mapping)
+    fn object(
+        &mut self,
+        fields: &'a Map<String, Value>,
+        path: &[String],
+        tag: Option<&str>,
+        consumed: &mut Vec<&'static str>,
+    ) -> Map<String, Value> {
+        let properties = fields
+            .get(PROPERTIES_KEY)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter(|(name, _)| Some(name.as_str()) != tag)
+            .collect::<Vec<_>>();
+        let required = fields
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .filter(|name| Some(*name) != tag)
+            .collect::<Vec<_>>();
+        let additional_properties = fields.get(ADDITIONAL_PROPERTIES_KEY);
+
+        consumed.push(PROPERTIES_KEY);
+
+        // Required properties without schemas can't be expressed.
+        if required
+            .iter()
+            .all(|name| properties.iter().any(|(property, _)| property == name))
+        {
+            consumed.push(REQUIRED_KEY);
+        }
+
+        let mut result = Map::new();
+
+        if properties.is_empty() && tag.is_none() && additional_properties != Some(&json!(false)) {
+            consumed.push(ADDITIONAL_PROPERTIES_KEY);
+
+            let values = match additional_properties {
+                Some(schema @ Value::Object(_)) => {
+                    self.convert(schema, &child(path, &[ADDITIONAL_PROPERTIES_KEY]), None)
+                }
+                _ => Map::new(),
+            };
+
+            result.insert("values".to_string(), Value::Object(values));
+
+            return result;
+        }
+
+        let mut required_properties = Map::new();
+        let mut optional_properties = Map::new();
+
+        for (name, schema) in properties {
+            let schema = self.convert(schema, &child(path, &[PROPERTIES_KEY, name]), None);
+
+            if required.contains(&name.as_str()) {
+                required_properties.insert(name.clone(), Value::Object(schema));
+            } else {
+                optional_properties.insert(name.clone(), Value::Object(schema));
+            }
+        }
+
+        // A properties form needs at least one of these.
+        if !required_properties.is_empty() || optional_properties.is_empty() {
+            result.insert(
+                PROPERTIES_KEY.to_string(),
+                Value::Object(required_properties),
+            );
+        }
+
+        if !optional_properties.is_empty() {
+            result.insert(
+                "optionalProperties".to_string(),
+                Value::Object(optional_properties),
+            );
+        }
+
+        match additional_properties {
+            Some(Value::Bool(false)) => consumed.push(ADDITIONAL_PROPERTIES_KEY),
+            Some(Value::Bool(true)) | None => {
+                consumed.push(ADDITIONAL_PROPERTIES_KEY);
+                result.insert(ADDITIONAL_PROPERTIES_KEY.to_string(), json!(true));
+            }
+            // A schema for additional properties is reported and loosened.
+            Some(_) => {
+                result.insert(ADDITIONAL_PROPERTIES_KEY.to_string(), json!(true));
+            }
+        }
+
+        result
+    }
+
+    /// A discriminator form, if every branch is an object (or a reference to one) with a required
+    /// property that has a different string constant in each
+    fn discriminator(
+        &mut self,
+        branches: &'a [Value],
+        path: &[String],
+        keyword: &str,
+    ) -> Option<Value> {
+        let objects = branches
+            .iter()
+            .enumerate()
+            .map(|(index, branch)| {
+                let (schema, path) = match branch.get(REF_KEY).and_then(Value::as_str) {
+                    Some(reference) => {
+                        let (name, schema) = self.local_definition(reference)?;
+                        (schema, vec![DEFS_KEY.to_string(), name.to_string()])
+                    }
+                    None => (branch, child(path, &[keyword, &index.to_string()])),
+                };
+
+                if schema.get(TYPE_KEY) == Some(&json!("object")) {
+                    Some((schema, path))
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let required = |schema: &Value| {
+            schema
+                .get(REQUIRED_KEY)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        let (first, _) = objects.first()?;
+        let tag = required(first).into_iter().find(|name| {
+            let mut values = objects
+                .iter()
+                .map(|(schema, _)| {
+                    required(schema)
+                        .contains(name)
+                        .then(|| tag_value(schema.get(PROPERTIES_KEY)?.get(name)?))
+                        .flatten()
+                })
+                .collect::<Option<Vec<_>>>()
+                .unwrap_or_default();
+            let count = values.len();
+
+            values.sort_unstable();
+            values.dedup();
+
+            count == objects.len() && values.len() == count
+        })?;
+
+        let mapping = objects
+            .iter()
+            .filter_map(|(schema, path)| {
+                let value = tag_value(schema.get(PROPERTIES_KEY)?.get(&tag)?)?;
+                Some((value.to_string(), self.convert(schema, path, Some(&tag))))
+            })
+            .map(|(value, schema)| (value, Value::Object(schema)))
+            .collect::<Map<_, _>>();
+
+        Some(json!({ "discriminator": tag, "mapping": mapping }))
+    }
+
+    /// The name and schema of the definition that a reference points to, if it's local
+    fn local_definition(&self, reference: &str) -> Option<(&'a str, &'a Value)> {
+        let reference = reference.parse::<Reference>().ok()?;
+        let is_local = match &reference {
+            Reference::FragmentOnly { .. } => true,
+            Reference::Both { .. } => {
+                reference.path().as_deref() == self.root.get(ID_KEY).and_then(Value::as_str)
+            }
+            Reference::PathOnly { .. } => false,
+        };
+
+        if is_local {
+            self.root
+                .get(DEFS_KEY)?
+                .as_object()?
+                .get_key_value(reference.name())
+                .map(|(name, schema)| (name.as_str(), schema))
+        } else {
+            None
+        }
+    }
+}
+
+/// The JTD integer type for an integer schema, recording the bounds that it matches exactly
+fn integer_type(fields: &Map<String, Value>, consumed: &mut Vec<&'static str>) -> &'static str {
+    let bound = |key, offset: i64| {
+        fields
+            .get(key)
+            .and_then(Value::as_i64)
+            .and_then(|bound| bound.checked_add(offset))
+            .map(|bound| (key, bound))
+    };
+
+    let lower = bound(MINIMUM_KEY, 0).or_else(|| bound(EXCLUSIVE_MINIMUM_KEY, 1));
+    let upper = bound(MAXIMUM_KEY, 0).or_else(|| bound(EXCLUSIVE_MAXIMUM_KEY, -1));
+
+    if let (Some((lower_key, lower)), Some((upper_key, upper))) = (lower, upper) {
+        let exact = INTEGER_TYPES
+            .iter()
+            .find(|(_, min, max)| lower == *min && upper == *max);
+
+        if let Some((name, min, max)) = exact.or_else(|| {
+            INTEGER_TYPES
+                .iter()
+                .find(|(_, min, max)| lower >= *min && upper <= *max)
+        }) {
+            consumed.push(TYPE_KEY);
+
+            // Narrower bounds are reported.
+            if lower == *min {
+                consumed.push(lower_key);
+            }
+
+            if upper == *max {
+                consumed.push(upper_key);
+            }
+
+            return name;
+        }
+    }
+
+    // Integers without 32-bit bounds are reported, since `float64` accepts fractions.
+    "float64"
+}
+
+/// The value of a discriminator tag, which has to be a single string
+fn tag_value(schema: &Value) -> Option<&str> {
+    match (schema.get(CONST_KEY), schema.get(ENUM_KEY)) {
+        (Some(Value::String(value)), _) => Some(value),
+        (_, Some(Value::Array(values))) => match values.as_slice() {
+            [Value::String(value)] => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn child(path: &[String], segments: &[&str]) -> Vec<String> {
+    let mut result = path.to_vec();
+    result.extend(segments.iter().map(|segment| segment.to_string()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jtd_schema() {
+        let value = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 0, "maximum": 4294967295_u64 },
+                "quantity": { "type": "integer", "minimum": 1, "maximum": 100 },
+                "total": { "type": "integer" },
+                "placed": { "type": "string", "format": "date-time" },
+                "code": { "description": "A code", "type": "string", "pattern": "^[A-Z]+$" },
+                "note": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                "status": { "enum": ["open", "closed", null] },
+                "tags": { "type": "object", "additionalProperties": { "type": "boolean" } },
+                "payment": {
+                    "oneOf": [
+                        { "$ref": "#/$defs/card" },
+                        {
+                            "type": "object",
+                            "properties": { "kind": { "const": "cash" } },
+                            "required": ["kind"],
+                            "additionalProperties": false
+                        }
+                    ]
+                },
+                "lines": { "type": "array", "items": { "$ref": "#/$defs/line" }, "minItems": 1 }
+            },
+            "required": ["id", "quantity", "placed", "payment"],
+            "additionalProperties": false,
+            "$defs": {
+                "card": {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "const": "card" },
+                        "last4": { "type": "string", "minLength": 4 }
+                    },
+                    "required": ["kind", "last4"],
+                    "additionalProperties": false
+                },
+                "line": { "anyOf": [{ "type": "string" }, { "type": "number" }] }
+            }
+        });
+
+        let jtd = jtd(&value);
+
+        assert_eq!(
+            jtd.schema,
+            json!({
+                "definitions": {
+                    "card": {
+                        "properties": {
+                            "kind": { "enum": ["card"] },
+                            "last4": { "type": "string" }
+                        }
+                    },
+                    "line": {}
+                },
+                "properties": {
+                    "id": { "type": "uint32" },
+                    "quantity": { "type": "uint8" },
+                    "placed": { "type": "timestamp" },
+                    "payment": {
+                        "discriminator": "kind",
+                        "mapping": {
+                            "card": { "properties": { "last4": { "type": "string" } } },
+                            "cash": { "properties": {} }
+                        }
+                    }
+                },
+                "optionalProperties": {
+                    "total": { "type": "float64" },
+                    "code": { "type": "string", "metadata": { "description": "A code" } },
+                    "note": { "type": "string", "nullable": true },
+                    "status": { "enum": ["open", "closed"], "nullable": true },
+                    "tags": { "values": { "type": "boolean" } },
+                    "lines": { "elements": { "ref": "line" } }
+                }
+            })
+        );
+
+        assert_eq!(
+            jtd.lossy
+                .into_iter()
+                .map(|construct| (construct.path.join("/"), construct.keyword))
+                .collect::<Vec<_>>(),
+            vec![
+                ("properties/quantity".to_string(), "minimum".to_string()),
+                ("properties/quantity".to_string(), "maximum".to_string()),
+                ("properties/total".to_string(), "type".to_string()),
+                ("properties/code".to_string(), "pattern".to_string()),
+                (
+                    "$defs/card/properties/last4".to_string(),
+                    "minLength".to_string()
+                ),
+                ("properties/lines".to_string(), "minItems".to_string()),
+                ("$defs/line".to_string(), "anyOf".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn jtd_edge_cases() {
+        let node = serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        });
+        let tags = serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        });
+
+        let result = jtd(&node);
+
+        assert_eq!(
+            result.schema,
+            serde_json::json!({
+                "definitions": {
+                    "node": {
+                        "optionalProperties": { "value": { "type": "uint8" }, "parent": { "ref": "node" } },
+                        "additionalProperties": true
+                    }
+                },
+                "properties": { "label": { "type": "string" } },
+                "optionalProperties": { "children": { "elements": { "ref": "node" } } },
+                "additionalProperties": true
+            })
+        );
+
+        assert_eq!(
+            result
+                .lossy
+                .into_iter()
+                .map(|construct| (construct.path.join("/"), construct.keyword))
+                .collect::<Vec<_>>(),
+            vec![
+                ("properties/label".to_string(), "pattern".to_string()),
+                ("properties/children".to_string(), "minItems".to_string()),
+                (
+                    "$defs/node/properties/value".to_string(),
+                    "maximum".to_string()
+                )
+            ]
+        );
+
+        let result = jtd(&tags);
+
+        assert_eq!(
+            result.schema,
+            serde_json::json!({ "elements": { "type": "string" } })
+        );
+
+        assert_eq!(
+            result
+                .lossy
+                .into_iter()
+                .map(|construct| (construct.path.join("/"), construct.keyword))
+                .collect::<Vec<_>>(),
+            vec![
+                ("".to_string(), "maxItems".to_string()),
+                ("items".to_string(), "pattern".to_string())
+            ]
+        );
+    }
+}
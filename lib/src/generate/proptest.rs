@@ -0,0 +1,691 @@
+use super::{local_definition, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// The largest number of extra array items or map entries to generate
+const EXTRA_ITEMS: usize = 4;
+
+/// Functions used by the generated strategies, which are only included if they're needed
+const HELPERS: [(&str, &str); 6] = [
+    (
+        "all_of",
+        r#"/// Merge the members' values, which only works well for objects
+fn all_of(members: Vec<BoxedStrategy<Value>>) -> BoxedStrategy<Value> {
+    members
+        .into_iter()
+        .fold(Just(Map::new()).boxed(), |merged, member| {
+            (merged, member)
+                .prop_map(|(mut merged, value)| {
+                    if let Value::Object(value) = value {
+                        merged.extend(value);
+                    }
+                    merged
+                })
+                .boxed()
+        })
+        .prop_map(Value::Object)
+        .boxed()
+}"#,
+    ),
+    (
+        "any_json",
+        r#"fn any_json() -> BoxedStrategy<Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        any::<String>().prop_map(Value::from),
+    ]
+    .boxed()
+}"#,
+    ),
+    (
+        "lazy",
+        r#"/// Build the strategy when a value is generated, so that recursive schemas don't recurse forever
+fn lazy(strategy: fn() -> BoxedStrategy<Value>) -> BoxedStrategy<Value> {
+    Just(()).prop_flat_map(move |_| strategy()).boxed()
+}"#,
+    ),
+    (
+        "object",
+        r#"fn object(properties: Vec<BoxedStrategy<Option<(String, Value)>>>) -> BoxedStrategy<Value> {
+    properties
+        .into_iter()
+        .fold(Just(Map::new()).boxed(), |object, property| {
+            (object, property)
+                .prop_map(|(mut object, property)| {
+                    object.extend(property);
+                    object
+                })
+                .boxed()
+        })
+        .prop_map(Value::Object)
+        .boxed()
+}"#,
+    ),
+    (
+        "property",
+        r#"fn property(
+    name: &'static str,
+    value: BoxedStrategy<Value>,
+    required: bool,
+) -> BoxedStrategy<Option<(String, Value)>> {
+    if required {
+        value.prop_map(move |value| Some((name.to_string(), value))).boxed()
+    } else {
+        proptest::option::of(value)
+            .prop_map(move |value| value.map(|value| (name.to_string(), value)))
+            .boxed()
+    }
+}"#,
+    ),
+    (
+        "tuple",
+        r#"fn tuple(prefix: Vec<BoxedStrategy<Value>>, rest: BoxedStrategy<Vec<Value>>) -> BoxedStrategy<Value> {
+    prefix
+        .into_iter()
+        .rev()
+        .fold(rest, |items, item| {
+            (item, items)
+                .prop_map(|(item, mut items)| {
+                    items.insert(0, item);
+                    items
+                })
+                .boxed()
+        })
+        .prop_map(Value::from)
+        .boxed()
+}"#,
+    ),
+];
+
+/// Generate Rust functions returning `proptest` strategies for values of the top-level schema and
+/// each definition
+///
+/// Each function is named `arb_` followed by the snake-case name of the schema. Values honor
+/// `required`, `const`, `enum`, bounds, item counts, and patterns (generated with `string_regex`).
+/// Unions pick a member, and `allOf` members are merged as objects, so values should still be
+/// validated where schemas rely on those. References to other files generate arbitrary JSON.
+///
+/// If a module path is given, `Arbitrary` is also implemented for the types in that module (as
+/// named by Typify), by deserializing the generated values.
+pub fn proptest(file: &SchemaFile, name: &str, types: Option<&str>) -> String {
+    let mut names = Names::default();
+    let root_name = file.metadata.title.as_deref().unwrap_or(name);
+    let root = file
+        .schema
+        .as_ref()
+        .filter(|schema| !matches!(schema, SchemaDef::Empty {}))
+        .map(|schema| {
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            (
+                root_name.to_string(),
+                names.unique(function_name(root_name)),
+                schema,
+            )
+        });
+
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, schema)| {
+            (
+                name.to_string(),
+                names.unique(function_name(name)),
+                schema.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut generator = Generator {
+        file,
+        functions: definitions
+            .iter()
+            .map(|(name, function, _)| (name.clone(), function.clone()))
+            .collect(),
+        helpers: BTreeSet::new(),
+    };
+
+    let mut blocks = vec![];
+    let mut impls = vec![];
+
+    for (name, function, schema) in root.iter().chain(&definitions) {
+        let strategy = generator.render(schema, 1);
+        let comment = schema
+            .metadata
+            .description
+            .as_deref()
+            .or(schema.metadata.title.as_deref())
+            .map(|description| {
+                description
+                    .lines()
+                    .map(|line| format!("/// {}", line).trim_end().to_string() + "\n")
+                    .collect::<String>()
+            })
+            .unwrap_or_default();
+
+        blocks.push(format!(
+            "{}pub fn {}() -> BoxedStrategy<Value> {{\n    {}\n}}",
+            comment, function, strategy
+        ));
+
+        if let Some(types) = types {
+            impls.push(arbitrary_impl(types, &type_name(name), function));
+        }
+    }
+
+    let mut result = "// Code generated by json-schema-tools. DO NOT EDIT.\n\n".to_string();
+    result.push_str("use proptest::prelude::*;\n");
+
+    let uses_map = generator
+        .helpers
+        .iter()
+        .any(|helper| matches!(*helper, "all_of" | "object"));
+
+    result.push_str(if uses_map {
+        "use serde_json::{Map, Value};\n"
+    } else {
+        "use serde_json::Value;\n"
+    });
+
+    for block in blocks.iter().chain(&impls) {
+        result.push('\n');
+        result.push_str(block);
+        result.push('\n');
+    }
+
+    for (name, source) in HELPERS {
+        if generator.helpers.contains(name) {
+            result.push('\n');
+            result.push_str(source);
+            result.push('\n');
+        }
+    }
+
+    result
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    /// Function names for definitions
+    functions: HashMap<String, String>,
+    helpers: BTreeSet<&'static str>,
+}
+
+impl Generator<'_> {
+    /// A strategy expression, where continuation lines are indented by the given number of levels
+    fn render(&mut self, schema: &Schema, indent: usize) -> String {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Null {}) => "Just(Value::Null).boxed()".to_string(),
+            SchemaDef::Type(SchemaType::Boolean {}) => values("any::<bool>()"),
+            SchemaDef::Type(SchemaType::String { pattern }) => match pattern {
+                Some(pattern) => values(&format!(
+                    "proptest::string::string_regex({:?}).unwrap()",
+                    unanchored(pattern)
+                )),
+                None => values("any::<String>()"),
+            },
+            SchemaDef::Type(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => {
+                let minimum = [*minimum, exclusive_minimum.map(|value| value + 1)]
+                    .into_iter()
+                    .flatten()
+                    .max();
+                let maximum = [*maximum, exclusive_maximum.map(|value| value - 1)]
+                    .into_iter()
+                    .flatten()
+                    .min();
+
+                values(&match (minimum, maximum) {
+                    (Some(minimum), Some(maximum)) => format!("({}_i64..={})", minimum, maximum),
+                    (Some(minimum), None) => format!("({}_i64..)", minimum),
+                    (None, Some(maximum)) => format!("(..={}_i64)", maximum),
+                    (None, None) => "any::<i64>()".to_string(),
+                })
+            }
+            SchemaDef::Type(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => {
+                let lower = exclusive_minimum.or(*minimum);
+                let upper = exclusive_maximum.or(*maximum);
+
+                let mut strategy = match (lower, upper) {
+                    (Some(lower), Some(upper)) if exclusive_maximum.is_some() => {
+                        format!("({:?}_f64..{:?})", lower, upper)
+                    }
+                    (Some(lower), Some(upper)) => format!("({:?}_f64..={:?})", lower, upper),
+                    (Some(lower), None) => format!("({:?}_f64..)", lower),
+                    (None, Some(upper)) if exclusive_maximum.is_some() => {
+                        format!("(..{:?}_f64)", upper)
+                    }
+                    (None, Some(upper)) => format!("(..={:?}_f64)", upper),
+                    // Infinities and NaN aren't JSON numbers.
+                    (None, None) => {
+                        "(proptest::num::f64::NORMAL | proptest::num::f64::ZERO)".to_string()
+                    }
+                };
+
+                if let Some(lower) = exclusive_minimum {
+                    strategy.push_str(&format!(
+                        ".prop_filter(\"exclusive minimum\", |value| *value > {:?})",
+                        lower
+                    ));
+                }
+
+                values(&strategy)
+            }
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                min_items,
+                max_items,
+            }) => {
+                let minimum = min_items.unwrap_or(0).saturating_sub(prefix_items.len());
+                let maximum = max_items
+                    .map(|maximum| maximum.saturating_sub(prefix_items.len()))
+                    .unwrap_or(minimum + EXTRA_ITEMS);
+                let rest = if prefix_items.is_empty() || !items.is_empty() {
+                    format!(
+                        "proptest::collection::vec({}, {}..={})",
+                        self.render(items, indent),
+                        minimum,
+                        maximum
+                    )
+                } else {
+                    // Further items are allowed but not described.
+                    "Just(vec![])".to_string()
+                };
+
+                if prefix_items.is_empty() {
+                    values(&rest)
+                } else {
+                    self.helpers.insert("tuple");
+
+                    let prefix = prefix_items
+                        .iter()
+                        .map(|schema| self.render(schema, indent + 2))
+                        .collect::<Vec<_>>();
+
+                    format!(
+                        "tuple(\n{}vec![\n{}{}],\n{}{}.boxed(),\n{})",
+                        spaces(indent + 1),
+                        lines(&prefix, indent + 2),
+                        spaces(indent + 1),
+                        spaces(indent + 1),
+                        rest,
+                        spaces(indent)
+                    )
+                }
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if object.properties.is_empty() {
+                    match &object.additional_properties {
+                        AdditionalProperties::Schema(schema) if !schema.is_empty() => {
+                            format!(
+                                "proptest::collection::btree_map(any::<String>(), {}, 0..={}).prop_map(|entries| Value::Object(entries.into_iter().collect())).boxed()",
+                                self.render(schema, indent),
+                                EXTRA_ITEMS
+                            )
+                        }
+                        _ => "Just(Value::Object(Default::default())).boxed()".to_string(),
+                    }
+                } else {
+                    self.helpers.insert("object");
+                    self.helpers.insert("property");
+
+                    // Only declared properties are generated, even if others are allowed.
+                    let properties = object
+                        .properties
+                        .iter()
+                        .map(|(name, schema)| {
+                            format!(
+                                "property({:?}, {}, {})",
+                                name,
+                                self.render(schema, indent + 1),
+                                object.required.contains(name)
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    format!(
+                        "object(vec![\n{}{}])",
+                        lines(&properties, indent + 1),
+                        spaces(indent)
+                    )
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, _)) => {
+                    self.helpers.insert("lazy");
+                    format!("lazy({})", self.functions[definition])
+                }
+                None => {
+                    self.helpers.insert("any_json");
+                    "any_json()".to_string()
+                }
+            },
+            SchemaDef::Enum { value } => values(&format!(
+                "proptest::sample::select(vec![{}])",
+                value
+                    .iter()
+                    .map(|value| format!("{:?}", value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            SchemaDef::Const { value } => format!("Just({}).boxed()", literal(value)),
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let members = value
+                    .iter()
+                    .map(|schema| self.render(schema, indent + 1))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "proptest::strategy::Union::new(vec![\n{}{}]).boxed()",
+                    lines(&members, indent + 1),
+                    spaces(indent)
+                )
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => self.render(&value[0], indent),
+            SchemaDef::AllOf { value } => {
+                self.helpers.insert("all_of");
+
+                let members = value
+                    .iter()
+                    .map(|schema| self.render(schema, indent + 1))
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "all_of(vec![\n{}{}])",
+                    lines(&members, indent + 1),
+                    spaces(indent)
+                )
+            }
+            SchemaDef::Empty {} => {
+                self.helpers.insert("any_json");
+                "any_json()".to_string()
+            }
+        }
+    }
+}
+
+/// A boxed strategy for JSON values, given a strategy for something that converts to them
+fn values(strategy: &str) -> String {
+    format!("{}.prop_map(Value::from).boxed()", strategy)
+}
+
+/// A Rust expression for a JSON value
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => "Value::Null".to_string(),
+        Value::Bool(value) => format!("Value::from({})", value),
+        Value::Number(number) if number.is_i64() => format!("Value::from({}_i64)", number),
+        Value::Number(number) if number.is_u64() => format!("Value::from({}_u64)", number),
+        Value::Number(number) => format!("Value::from({:?}_f64)", number.as_f64().unwrap_or(0.0)),
+        Value::String(value) => format!("Value::from({:?})", value),
+        value => format!(
+            "serde_json::from_str::<Value>({:?}).unwrap()",
+            value.to_string()
+        ),
+    }
+}
+
+fn arbitrary_impl(types: &str, type_name: &str, function: &str) -> String {
+    format!(
+        r#"impl Arbitrary for {types}::{type_name} {{
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {{
+        {function}()
+            .prop_map(|value| serde_json::from_value(value).expect("value should deserialize to {type_name}"))
+            .boxed()
+    }}
+}}"#
+    )
+}
+
+/// `arb_order_item` for `OrderItem` or `order-item`
+fn function_name(name: &str) -> String {
+    let words = type_words(name);
+
+    if words.is_empty() {
+        "arb_value".to_string()
+    } else {
+        format!("arb_{}", words.join("_").to_ascii_lowercase())
+    }
+}
+
+/// The name Typify gives a type, where acronyms are only capitalized (`HttpStatus` for
+/// `HTTPStatus`)
+fn type_name(name: &str) -> String {
+    type_words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| {
+                    first.to_ascii_uppercase().to_string() + &chars.as_str().to_lowercase()
+                })
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Split a name into words like `words`, but also before the last capital of an acronym that's
+/// followed by a lowercase letter (`HTTP` and `Status` for `HTTPStatus`)
+fn type_words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut current = String::new();
+
+    for (index, c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+
+            continue;
+        }
+
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1);
+        let is_boundary = c.is_ascii_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_ascii_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_ascii_uppercase()
+                        && next.is_some_and(|next| next.is_ascii_lowercase()))
+            });
+
+        if is_boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+
+        current.push(*c);
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// The pattern without anchors, since generated strings match the whole pattern anyway
+fn unanchored(pattern: &str) -> &str {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+
+    match pattern.strip_suffix('$') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => pattern,
+    }
+}
+
+fn spaces(indent: usize) -> String {
+    "    ".repeat(indent)
+}
+
+/// Indented expressions, one per line with trailing commas
+fn lines(expressions: &[String], indent: usize) -> String {
+    expressions
+        .iter()
+        .map(|expression| format!("{}{},\n", spaces(indent), expression))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proptest_strategies() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "exclusiveMinimum": 0 },
+                "sku": { "type": "string", "pattern": "^[A-Z]{3}-[0-9]+$" },
+                "status": { "$ref": "#/$defs/status" },
+                "total": { "type": "number", "minimum": 0, "exclusiveMaximum": 1000 },
+                "lines": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/order" },
+                    "maxItems": 2
+                },
+                "note": { "anyOf": [{ "type": "string" }, { "type": "null" }] }
+            },
+            "required": ["id", "status"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "HTTPStatus": { "const": 200 },
+                "order": {}
+            }
+        }))
+        .unwrap();
+
+        let code = proptest(&file, "ignored", Some("crate::types"));
+
+        assert!(code.starts_with(
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+use proptest::prelude::*;
+use serde_json::{Map, Value};
+
+/// An order
+pub fn arb_order() -> BoxedStrategy<Value> {
+    object(vec![
+        property("id", (1_i64..).prop_map(Value::from).boxed(), true),
+        property("sku", proptest::string::string_regex("[A-Z]{3}-[0-9]+").unwrap().prop_map(Value::from).boxed(), false),
+        property("status", lazy(arb_status), true),
+        property("total", (0.0_f64..1000.0).prop_map(Value::from).boxed(), false),
+        property("lines", proptest::collection::vec(lazy(arb_order2), 0..=2).prop_map(Value::from).boxed(), false),
+        property("note", proptest::strategy::Union::new(vec![
+            any::<String>().prop_map(Value::from).boxed(),
+            Just(Value::Null).boxed(),
+        ]).boxed(), false),
+    ])
+}
+
+pub fn arb_status() -> BoxedStrategy<Value> {
+    proptest::sample::select(vec!["open", "in-progress"]).prop_map(Value::from).boxed()
+}
+
+pub fn arb_http_status() -> BoxedStrategy<Value> {
+    Just(Value::from(200_i64)).boxed()
+}
+"#
+        ));
+
+        assert!(code.contains("impl Arbitrary for crate::types::HttpStatus {"));
+        assert!(code.contains("pub fn arb_order2() -> BoxedStrategy<Value> {\n    any_json()\n}"));
+        assert!(code.contains("\nfn lazy("));
+        assert!(!code.contains("\nfn all_of("));
+    }
+
+    #[test]
+    fn proptest_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        let code = proptest(&node, "tags", None);
+
+        assert!(code.starts_with(
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+use proptest::prelude::*;
+use serde_json::{Map, Value};
+
+pub fn arb_node() -> BoxedStrategy<Value> {
+    object(vec![
+        property("label", proptest::string::string_regex("[a-z]+").unwrap().prop_map(Value::from).boxed(), true),
+        property("children", proptest::collection::vec(lazy(arb_node2), 1..=5).prop_map(Value::from).boxed(), false),
+    ])
+}
+
+pub fn arb_node2() -> BoxedStrategy<Value> {
+    object(vec![
+        property("value", (0_i64..=10).prop_map(Value::from).boxed(), false),
+        property("parent", lazy(arb_node2), false),
+    ])
+}
+
+"#
+        ));
+
+        assert!(code.contains("\nfn lazy("));
+
+        assert_eq!(
+            proptest(&tags, "tags", None),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+use proptest::prelude::*;
+use serde_json::Value;
+
+pub fn arb_tags() -> BoxedStrategy<Value> {
+    proptest::collection::vec(proptest::string::string_regex("[a-z]+").unwrap().prop_map(Value::from).boxed(), 0..=3).prop_map(Value::from).boxed()
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,776 @@
+use super::{local_definition, pascal_case, screaming_snake_case, snake_case, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const INDENT: &str = "  ";
+const STRUCT_IMPORT: &str = "google/protobuf/struct.proto";
+const VALUE_TYPE: &str = "google.protobuf.Value";
+
+/// Definitions that are only used through references nested this deeply are treated as `Value`
+const MAX_INLINE_DEPTH: usize = 16;
+
+/// A JSON Schema constraint that can't be expressed in a proto file
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnmappedConstraint {
+    /// The message and field (or message alone for definitions), like `Order.id`
+    pub location: String,
+    pub keyword: &'static str,
+    pub value: String,
+}
+
+/// A generated proto file
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proto {
+    pub source: String,
+    /// Constraints that are only recorded in comments
+    pub unmapped: Vec<UnmappedConstraint>,
+}
+
+/// Generate a proto3 file with messages and enums for the top-level schema and each definition
+///
+/// Objects become messages (including inline objects), string enums become enums (with an
+/// unspecified zero value), and unions become `oneof`s, while other definitions are used directly
+/// wherever they're referenced. Patterns, bounds, and other constraints that proto doesn't support are reported
+/// and left in comments. References to other files are typed as `google.protobuf.Value`.
+pub fn proto(file: &SchemaFile, name: &str, package: Option<&str>) -> Proto {
+    let mut names = Names::default();
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .filter(|(_, schema)| is_declared(schema))
+        .map(|(name, _)| (name.to_string(), names.unique(pascal_case(name))))
+        .collect();
+
+    let mut generator = Generator {
+        file,
+        definitions,
+        names,
+        enum_values: Names::default(),
+        uses_struct: false,
+        blocks: vec![],
+        unmapped: vec![],
+        depth: 0,
+    };
+
+    if let Some(schema) = file.schema.as_ref() {
+        let schema = Schema {
+            metadata: Metadata {
+                description: file.metadata.description.clone(),
+                ..Metadata::default()
+            },
+            schema: schema.clone(),
+        };
+
+        // Proto files can only declare messages and enums, so other top-level schemas are wrapped.
+        if !matches!(schema.schema, SchemaDef::Empty {}) {
+            let name = pascal_case(file.metadata.title.as_deref().unwrap_or(name));
+            let name = generator.names.unique(name);
+
+            generator.definition(&name, &schema);
+        }
+    }
+
+    for (name, schema) in file.definitions.iter().flatten() {
+        if let Some(name) = generator.definitions.get(name.as_str()).cloned() {
+            generator.definition(&name, schema);
+        }
+    }
+
+    let mut source = "syntax = \"proto3\";\n".to_string();
+
+    if let Some(package) = package {
+        source.push_str(&format!("\npackage {};\n", package));
+    }
+
+    if generator.uses_struct {
+        source.push_str(&format!("\nimport \"{}\";\n", STRUCT_IMPORT));
+    }
+
+    for block in generator.blocks {
+        source.push('\n');
+        source.push_str(&block);
+        source.push('\n');
+    }
+
+    Proto {
+        source,
+        unmapped: generator.unmapped,
+    }
+}
+
+/// Whether a definition gets its own message or enum, instead of being used directly
+fn is_declared(schema: &Schema) -> bool {
+    match &schema.schema {
+        SchemaDef::Type(SchemaType::Object(object)) => {
+            !object.properties.is_empty() || object.no_additional_properties()
+        }
+        SchemaDef::Enum { .. } => true,
+        SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => non_null(value).len() > 1,
+        _ => false,
+    }
+}
+
+fn non_null(schemas: &[Schema]) -> Vec<&Schema> {
+    schemas
+        .iter()
+        .filter(|schema| !matches!(schema.schema, SchemaDef::Type(SchemaType::Null {})))
+        .collect()
+}
+
+/// The shape of a field
+enum Field {
+    Single(String),
+    Repeated(String),
+    Map(String),
+    /// Member types, with the names of any definitions they come from
+    OneOf(Vec<(String, Option<String>)>),
+}
+
+/// The fields of a message that's being generated
+struct Fields {
+    message: String,
+    names: Names,
+    next_number: usize,
+    lines: String,
+}
+
+impl Fields {
+    fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            names: Names::default(),
+            next_number: 0,
+            lines: String::new(),
+        }
+    }
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    /// Message and enum names for declared definitions
+    definitions: HashMap<String, String>,
+    /// Message and enum names across the file
+    names: Names,
+    /// Enum values are scoped to the package, not the enum
+    enum_values: Names,
+    uses_struct: bool,
+    /// Declarations, in an order where inline messages come before the messages using them
+    blocks: Vec<String>,
+    unmapped: Vec<UnmappedConstraint>,
+    /// How many references are currently being used directly
+    depth: usize,
+}
+
+impl Generator<'_> {
+    fn definition(&mut self, name: &str, schema: &Schema) {
+        match &schema.schema {
+            SchemaDef::Enum { value } => self.enumeration(name, &schema.metadata, value),
+            SchemaDef::Type(SchemaType::Object(_)) => self.message(name, schema),
+            // Unions need a message to hold the `oneof`.
+            _ => {
+                let mut constraints = vec![];
+                let field = self.render(schema, name, &mut constraints);
+                let mut fields = Fields::new(name);
+                self.field(&mut fields, "value", field, true, &constraints, None);
+
+                self.blocks.push(format!(
+                    "{}message {} {{\n{}}}",
+                    comment(&schema.metadata, ""),
+                    name,
+                    fields.lines
+                ));
+            }
+        }
+    }
+
+    fn message(&mut self, name: &str, schema: &Schema) {
+        let object = match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object)) => object,
+            _ => return,
+        };
+
+        let mut fields = Fields::new(name);
+
+        for (property, schema) in &object.properties {
+            let mut constraints = vec![];
+            let field = self.render(
+                schema,
+                &format!("{}{}", name, pascal_case(property)),
+                &mut constraints,
+            );
+
+            self.field(
+                &mut fields,
+                property,
+                field,
+                object.required.contains(property),
+                &constraints,
+                schema.metadata.description.as_deref(),
+            );
+        }
+
+        let body = fields.lines;
+
+        self.blocks.push(if body.is_empty() {
+            format!("{}message {} {{}}", comment(&schema.metadata, ""), name)
+        } else {
+            format!(
+                "{}message {} {{\n{}}}",
+                comment(&schema.metadata, ""),
+                name,
+                body
+            )
+        });
+    }
+
+    /// Add the lines declaring a field (or a `oneof` with its members)
+    fn field(
+        &mut self,
+        fields: &mut Fields,
+        property: &str,
+        field: Field,
+        required: bool,
+        constraints: &[(&'static str, String)],
+        description: Option<&str>,
+    ) {
+        let field_name = fields.names.unique(valid_name(&snake_case(property)));
+        let lines = &mut fields.lines;
+
+        if let Some(description) = description {
+            lines.push_str(&comment_lines(description, INDENT));
+        }
+
+        for (keyword, value) in constraints {
+            lines.push_str(&format!("{}// {}: {}\n", INDENT, keyword, value));
+            self.unmapped.push(UnmappedConstraint {
+                location: format!("{}.{}", fields.message, field_name),
+                keyword,
+                value: value.clone(),
+            });
+        }
+
+        // The field name is converted to lower camel case in JSON by default.
+        let options = if lower_camel_case(&field_name) == property {
+            String::new()
+        } else {
+            format!(" [json_name = {}]", Value::String(property.to_string()))
+        };
+
+        let declaration = match field {
+            Field::Single(field_type) if required => format!("{} {}", field_type, field_name),
+            Field::Single(field_type) => format!("optional {} {}", field_type, field_name),
+            Field::Repeated(field_type) => format!("repeated {} {}", field_type, field_name),
+            Field::Map(field_type) => format!("map<string, {}> {}", field_type, field_name),
+            Field::OneOf(members) => {
+                lines.push_str(&format!("{}oneof {} {{\n", INDENT, field_name));
+
+                for (member, definition) in members {
+                    let base = definition
+                        .as_deref()
+                        .unwrap_or_else(|| member.rsplit('.').next().unwrap_or_default());
+                    let member_name = fields.names.unique(valid_name(&snake_case(base)));
+
+                    fields.next_number += 1;
+                    lines.push_str(&format!(
+                        "{}{}{} {} = {};\n",
+                        INDENT, INDENT, member, member_name, fields.next_number
+                    ));
+                }
+
+                lines.push_str(&format!("{}}}\n", INDENT));
+                return;
+            }
+        };
+
+        fields.next_number += 1;
+        lines.push_str(&format!(
+            "{}{} = {}{};\n",
+            INDENT, declaration, fields.next_number, options
+        ));
+    }
+
+    fn enumeration(&mut self, name: &str, metadata: &Metadata, values: &[String]) {
+        let prefix = screaming_snake_case(name);
+        let mut body = format!(
+            "{}{} = 0;\n",
+            INDENT,
+            self.enum_values.unique(format!("{}_UNSPECIFIED", prefix))
+        );
+
+        for (index, value) in values.iter().enumerate() {
+            let suffix = screaming_snake_case(value);
+            let suffix = if suffix.is_empty() { "EMPTY" } else { &suffix };
+
+            // The JSON mapping uses the value names, so the original values are kept in comments.
+            body.push_str(&format!(
+                "{}{} = {}; // {}\n",
+                INDENT,
+                self.enum_values.unique(format!("{}_{}", prefix, suffix)),
+                index + 1,
+                Value::String(value.clone())
+            ));
+        }
+
+        self.blocks.push(format!(
+            "{}enum {} {{\n{}}}",
+            comment(metadata, ""),
+            name,
+            body
+        ));
+    }
+
+    fn value(&mut self) -> Field {
+        self.uses_struct = true;
+        Field::Single(VALUE_TYPE.to_string())
+    }
+
+    /// The shape of the field for a schema, where the name is used for any inline declarations
+    fn render(
+        &mut self,
+        schema: &Schema,
+        name: &str,
+        constraints: &mut Vec<(&'static str, String)>,
+    ) -> Field {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Null {}) => {
+                self.uses_struct = true;
+                Field::Single("google.protobuf.NullValue".to_string())
+            }
+            SchemaDef::Type(SchemaType::Boolean {}) => Field::Single("bool".to_string()),
+            SchemaDef::Type(SchemaType::String { pattern }) => {
+                if let Some(pattern) = pattern {
+                    constraints.push(("pattern", pattern.clone()));
+                }
+
+                Field::Single("string".to_string())
+            }
+            SchemaDef::Type(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => {
+                bounds(
+                    constraints,
+                    minimum,
+                    maximum,
+                    exclusive_minimum,
+                    exclusive_maximum,
+                );
+                Field::Single("int64".to_string())
+            }
+            SchemaDef::Type(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => {
+                bounds(
+                    constraints,
+                    minimum,
+                    maximum,
+                    exclusive_minimum,
+                    exclusive_maximum,
+                );
+                Field::Single("double".to_string())
+            }
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                min_items,
+                max_items,
+            }) => {
+                if let Some(min_items) = min_items {
+                    constraints.push(("minItems", min_items.to_string()));
+                }
+
+                if let Some(max_items) = max_items {
+                    constraints.push(("maxItems", max_items.to_string()));
+                }
+
+                if prefix_items.is_empty() {
+                    let item = self.element(items, &format!("{}Item", name), constraints);
+                    Field::Repeated(item)
+                } else {
+                    constraints.push(("prefixItems", format!("{} items", prefix_items.len())));
+                    self.uses_struct = true;
+                    Field::Repeated(VALUE_TYPE.to_string())
+                }
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if !object.properties.is_empty() || object.no_additional_properties() {
+                    let name = self.names.unique(name.to_string());
+                    self.message(&name, schema);
+
+                    Field::Single(name)
+                } else {
+                    match &object.additional_properties {
+                        AdditionalProperties::Schema(schema) => {
+                            Field::Map(self.element(schema, &format!("{}Value", name), constraints))
+                        }
+                        AdditionalProperties::Boolean(_) => {
+                            self.uses_struct = true;
+                            Field::Single("google.protobuf.Struct".to_string())
+                        }
+                    }
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, schema)) => match self.definitions.get(definition) {
+                    Some(name) => Field::Single(name.clone()),
+                    None if self.depth < MAX_INLINE_DEPTH => {
+                        self.depth += 1;
+                        let field = self.render(schema, &pascal_case(definition), constraints);
+                        self.depth -= 1;
+
+                        field
+                    }
+                    None => self.value(),
+                },
+                None => self.value(),
+            },
+            SchemaDef::Enum { value } => {
+                let name = self.names.unique(name.to_string());
+                self.enumeration(&name, &schema.metadata, value);
+
+                Field::Single(name)
+            }
+            SchemaDef::Const { value } => {
+                constraints.push(("const", value.to_string()));
+
+                match value {
+                    Value::Bool(_) => Field::Single("bool".to_string()),
+                    Value::Number(number) if number.is_i64() || number.is_u64() => {
+                        Field::Single("int64".to_string())
+                    }
+                    Value::Number(_) => Field::Single("double".to_string()),
+                    Value::String(_) => Field::Single("string".to_string()),
+                    _ => self.value(),
+                }
+            }
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                match non_null(value).as_slice() {
+                    // Nullable values are just optional.
+                    [member] => self.render(member, name, constraints),
+                    members => {
+                        if matches!(schema.schema, SchemaDef::AnyOf { .. }) {
+                            constraints.push(("anyOf", "mapped to oneof".to_string()));
+                        }
+
+                        Field::OneOf(
+                            members
+                                .iter()
+                                .enumerate()
+                                .map(|(index, schema)| {
+                                    let definition = match &schema.schema {
+                                        SchemaDef::Ref { value } => {
+                                            local_definition(self.file, value)
+                                                .map(|(definition, _)| definition.to_string())
+                                        }
+                                        _ => None,
+                                    };
+                                    let element = self.element(
+                                        schema,
+                                        &format!("{}{}", name, index + 1),
+                                        constraints,
+                                    );
+
+                                    (element, definition)
+                                })
+                                .collect(),
+                        )
+                    }
+                }
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => {
+                self.render(&value[0], name, constraints)
+            }
+            SchemaDef::AllOf { value } => {
+                constraints.push(("allOf", format!("{} schemas", value.len())));
+                self.value()
+            }
+            SchemaDef::Empty {} => self.value(),
+        }
+    }
+
+    /// A single type for the schema, wrapping repeated fields, maps, and unions in a message
+    fn element(
+        &mut self,
+        schema: &Schema,
+        name: &str,
+        constraints: &mut Vec<(&'static str, String)>,
+    ) -> String {
+        match self.render(schema, name, constraints) {
+            Field::Single(field_type) => field_type,
+            field => {
+                let name = self.names.unique(name.to_string());
+                let mut fields = Fields::new(&name);
+                self.field(&mut fields, "value", field, true, &[], None);
+
+                self.blocks
+                    .push(format!("message {} {{\n{}}}", name, fields.lines));
+
+                name
+            }
+        }
+    }
+}
+
+fn bounds<T: std::fmt::Display>(
+    constraints: &mut Vec<(&'static str, String)>,
+    minimum: &Option<T>,
+    maximum: &Option<T>,
+    exclusive_minimum: &Option<T>,
+    exclusive_maximum: &Option<T>,
+) {
+    for (keyword, value) in [
+        ("minimum", minimum),
+        ("maximum", maximum),
+        ("exclusiveMinimum", exclusive_minimum),
+        ("exclusiveMaximum", exclusive_maximum),
+    ] {
+        if let Some(value) = value {
+            constraints.push((keyword, value.to_string()));
+        }
+    }
+}
+
+/// A field name that starts with a letter
+fn valid_name(name: &str) -> String {
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// The default JSON name for a field
+fn lower_camel_case(field_name: &str) -> String {
+    let mut result = String::new();
+    let mut upper = false;
+
+    for c in field_name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            result.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+fn comment(metadata: &Metadata, indent: &str) -> String {
+    metadata
+        .description
+        .as_deref()
+        .or(metadata.title.as_deref())
+        .map(|description| comment_lines(description, indent))
+        .unwrap_or_default()
+}
+
+fn comment_lines(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| format!("{}// {}", indent, line).trim_end().to_string() + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proto_messages() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "status": { "$ref": "#/$defs/status" },
+                "customerCode": { "$ref": "#/$defs/code" },
+                "line-items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string" } },
+                        "required": ["sku"]
+                    }
+                },
+                "payment": { "oneOf": [{ "$ref": "#/$defs/card" }, { "type": "string" }] }
+            },
+            "required": ["id", "status"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "code": { "type": "string", "pattern": "^C[0-9]+$" },
+                "card": { "type": "object", "properties": { "number": { "type": "string" } } }
+            }
+        }))
+        .unwrap();
+
+        let result = proto(&file, "ignored", Some("shop.v1"));
+
+        assert_eq!(
+            result.source,
+            r#"syntax = "proto3";
+
+package shop.v1;
+
+message OrderLineItemsItem {
+  string sku = 1;
+}
+
+// An order
+message Order {
+  // minimum: 1
+  int64 id = 1;
+  Status status = 2;
+  // pattern: ^C[0-9]+$
+  optional string customer_code = 3;
+  repeated OrderLineItemsItem line_items = 4 [json_name = "line-items"];
+  oneof payment {
+    Card card = 5;
+    string string = 6;
+  }
+}
+
+enum Status {
+  STATUS_UNSPECIFIED = 0;
+  STATUS_OPEN = 1; // "open"
+  STATUS_IN_PROGRESS = 2; // "in-progress"
+}
+
+message Card {
+  optional string number = 1;
+}
+"#
+        );
+        assert_eq!(
+            result.unmapped,
+            vec![
+                UnmappedConstraint {
+                    location: "Order.id".to_string(),
+                    keyword: "minimum",
+                    value: "1".to_string()
+                },
+                UnmappedConstraint {
+                    location: "Order.customer_code".to_string(),
+                    keyword: "pattern",
+                    value: "^C[0-9]+$".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn proto_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        let result = proto(&node, "tags", None);
+
+        assert_eq!(
+            result.source,
+            r#"syntax = "proto3";
+
+message Node2 {
+  // pattern: ^[a-z]+$
+  string label = 1;
+  // minItems: 1
+  repeated Node children = 2;
+}
+
+message Node {
+  // minimum: 0
+  // maximum: 10
+  optional int64 value = 1;
+  optional Node parent = 2;
+}
+"#
+        );
+
+        assert_eq!(
+            result.unmapped,
+            vec![
+                UnmappedConstraint {
+                    location: "Node2.label".to_string(),
+                    keyword: "pattern",
+                    value: "^[a-z]+$".to_string()
+                },
+                UnmappedConstraint {
+                    location: "Node2.children".to_string(),
+                    keyword: "minItems",
+                    value: "1".to_string()
+                },
+                UnmappedConstraint {
+                    location: "Node.value".to_string(),
+                    keyword: "minimum",
+                    value: "0".to_string()
+                },
+                UnmappedConstraint {
+                    location: "Node.value".to_string(),
+                    keyword: "maximum",
+                    value: "10".to_string()
+                }
+            ]
+        );
+
+        let result = proto(&tags, "tags", None);
+
+        assert_eq!(
+            result.source,
+            r#"syntax = "proto3";
+
+message Tags {
+  // maxItems: 3
+  // pattern: ^[a-z]+$
+  repeated string value = 1;
+}
+"#
+        );
+
+        assert_eq!(
+            result.unmapped,
+            vec![
+                UnmappedConstraint {
+                    location: "Tags.value".to_string(),
+                    keyword: "maxItems",
+                    value: "3".to_string()
+                },
+                UnmappedConstraint {
+                    location: "Tags.value".to_string(),
+                    keyword: "pattern",
+                    value: "^[a-z]+$".to_string()
+                }
+            ]
+        );
+    }
+}
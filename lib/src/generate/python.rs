@@ -0,0 +1,673 @@
+use super::{local_definition, pascal_case, screaming_snake_case, snake_case, Names};
+use crate::schema::{AdditionalProperties, Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+const INDENT: &str = "    ";
+
+/// Reserved words that can't be used as attribute names
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Generate a module of pydantic v2 models for the top-level schema and each definition
+///
+/// Objects with properties become models (with one for each inline object), string enums become
+/// `Enum` classes, and other definitions become type aliases. Patterns and numeric bounds are
+/// checked with `Field` constraints, and properties that aren't required are `Optional`.
+/// References to other files are typed as `Any`.
+pub fn pydantic(file: &SchemaFile, name: &str) -> String {
+    let mut names = Names::default();
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| (name.to_string(), names.unique(class_name(name))))
+        .collect();
+
+    let mut generator = Generator {
+        file,
+        definitions,
+        names,
+        imports: BTreeSet::from([("__future__", "annotations")]),
+        blocks: vec![],
+        quote_references: false,
+    };
+
+    if let Some(schema) = file.schema.as_ref() {
+        if !matches!(schema, SchemaDef::Empty {}) {
+            let name = class_name(file.metadata.title.as_deref().unwrap_or(name));
+            let name = generator.names.unique(name);
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            generator.definition(&name, &schema);
+        }
+    }
+
+    for (name, schema) in file.definitions.iter().flatten() {
+        let name = generator.definitions[name.as_str()].clone();
+        generator.definition(&name, schema);
+    }
+
+    // Standard library imports are separated from third-party ones.
+    let sections = [vec!["__future__"], vec!["enum", "typing"], vec!["pydantic"]]
+        .into_iter()
+        .map(|modules| {
+            modules
+                .into_iter()
+                .filter_map(|module| {
+                    let imported = generator
+                        .imports
+                        .iter()
+                        .filter(|(from, _)| *from == module)
+                        .map(|(_, name)| *name)
+                        .collect::<Vec<_>>();
+
+                    (!imported.is_empty())
+                        .then(|| format!("from {} import {}", module, imported.join(", ")))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .filter(|section| !section.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut result = sections.join("\n\n");
+
+    for block in generator.blocks {
+        result.push_str("\n\n\n");
+        result.push_str(&block);
+    }
+
+    result.push('\n');
+    result
+}
+
+/// A type expression along with any constraints that apply to it
+struct Type {
+    expression: String,
+    constraints: Vec<String>,
+}
+
+impl Type {
+    fn new(expression: &str) -> Self {
+        Self {
+            expression: expression.to_string(),
+            constraints: vec![],
+        }
+    }
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    /// Class names for definitions
+    definitions: HashMap<String, String>,
+    /// Class names across the module
+    names: Names,
+    /// Module and name pairs
+    imports: BTreeSet<(&'static str, &'static str)>,
+    /// Classes and aliases, in an order where inline models come before the models using them
+    blocks: Vec<String>,
+    /// Whether references should be forward references (which is necessary in type aliases)
+    quote_references: bool,
+}
+
+impl Generator<'_> {
+    fn import(&mut self, module: &'static str, name: &'static str) {
+        self.imports.insert((module, name));
+    }
+
+    /// Add a model, enum, or alias for the top-level schema or a definition
+    fn definition(&mut self, name: &str, schema: &Schema) {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object))
+                if !object.properties.is_empty() || object.no_additional_properties() =>
+            {
+                self.model(name, schema);
+            }
+            SchemaDef::Enum { value } => self.enumeration(name, &schema.metadata, value),
+            _ => {
+                self.quote_references = true;
+                let nested = self.nested(schema, name);
+                self.quote_references = false;
+
+                let mut block = docstring(&schema.metadata, "")
+                    .map(|docstring| {
+                        // Aliases can't have docstrings, but a comment is still useful.
+                        docstring
+                            .trim_matches('"')
+                            .lines()
+                            .map(|line| format!("# {}\n", line).replace("# \n", "#\n"))
+                            .collect::<String>()
+                    })
+                    .unwrap_or_default();
+                block.push_str(&format!("{} = {}", name, nested));
+
+                self.blocks.push(block);
+            }
+        }
+    }
+
+    fn model(&mut self, name: &str, schema: &Schema) {
+        let object = match &schema.schema {
+            SchemaDef::Type(SchemaType::Object(object)) => object,
+            _ => return,
+        };
+
+        self.import("pydantic", "BaseModel");
+
+        let mut body = vec![];
+
+        if let Some(docstring) = docstring(&schema.metadata, INDENT) {
+            body.push(docstring);
+        }
+
+        if object.no_additional_properties() {
+            self.import("pydantic", "ConfigDict");
+            body.push(format!(
+                "{}model_config = ConfigDict(extra=\"forbid\")",
+                INDENT
+            ));
+        }
+
+        let mut attributes = Names::default();
+        let mut fields = vec![];
+
+        for (property, schema) in &object.properties {
+            let attribute = attributes.unique(attribute_name(property));
+            let mut field_type = self.render(schema, &format!("{}{}", name, pascal_case(property)));
+            let mut arguments = vec![];
+            let required = object.required.contains(property);
+
+            if !required {
+                arguments.push("default=None".to_string());
+
+                if !field_type.expression.starts_with("Optional[")
+                    && field_type.expression != "None"
+                {
+                    self.import("typing", "Optional");
+                    field_type.expression = format!("Optional[{}]", field_type.expression);
+                }
+            }
+
+            if attribute != property.as_str() {
+                arguments.push(format!("alias={}", string_literal(property)));
+            }
+
+            if let Some(description) = &schema.metadata.description {
+                arguments.push(format!("description={}", string_literal(description)));
+            }
+
+            arguments.extend(field_type.constraints);
+
+            let default = match arguments.as_slice() {
+                [] => String::new(),
+                [default] if !required && default == "default=None" => " = None".to_string(),
+                _ => {
+                    self.import("pydantic", "Field");
+                    format!(" = Field({})", arguments.join(", "))
+                }
+            };
+
+            fields.push(format!(
+                "{}{}: {}{}",
+                INDENT, attribute, field_type.expression, default
+            ));
+        }
+
+        if fields.is_empty() && body.is_empty() {
+            fields.push(format!("{}pass", INDENT));
+        }
+
+        if !fields.is_empty() {
+            body.push(fields.join("\n"));
+        }
+
+        self.blocks
+            .push(format!("class {}(BaseModel):\n{}", name, body.join("\n\n")));
+    }
+
+    fn enumeration(&mut self, name: &str, metadata: &Metadata, values: &[String]) {
+        self.import("enum", "Enum");
+
+        let mut body = vec![];
+
+        if let Some(docstring) = docstring(metadata, INDENT) {
+            body.push(docstring);
+        }
+
+        let mut members = Names::default();
+
+        body.push(
+            values
+                .iter()
+                .map(|value| {
+                    let mut member = screaming_snake_case(value);
+
+                    if member.is_empty() {
+                        member = "EMPTY".to_string();
+                    } else if member.starts_with(|c: char| c.is_ascii_digit()) {
+                        member = format!("VALUE_{}", member);
+                    }
+
+                    format!(
+                        "{}{} = {}",
+                        INDENT,
+                        members.unique(member),
+                        string_literal(value)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+
+        self.blocks
+            .push(format!("class {}(str, Enum):\n{}", name, body.join("\n\n")));
+    }
+
+    /// A type expression with its constraints applied using `Annotated`
+    fn nested(&mut self, schema: &Schema, name: &str) -> String {
+        let nested = self.render(schema, name);
+
+        if nested.constraints.is_empty() {
+            nested.expression
+        } else {
+            self.import("typing", "Annotated");
+            self.import("pydantic", "Field");
+
+            format!(
+                "Annotated[{}, Field({})]",
+                nested.expression,
+                nested.constraints.join(", ")
+            )
+        }
+    }
+
+    /// The type of a schema, where the name is used for any inline models or enums
+    fn render(&mut self, schema: &Schema, name: &str) -> Type {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Null {}) => Type::new("None"),
+            SchemaDef::Type(SchemaType::Boolean {}) => Type::new("bool"),
+            SchemaDef::Type(SchemaType::String { pattern }) => Type {
+                expression: "str".to_string(),
+                constraints: pattern
+                    .iter()
+                    .map(|pattern| format!("pattern={}", string_literal(pattern)))
+                    .collect(),
+            },
+            SchemaDef::Type(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => Type {
+                expression: "int".to_string(),
+                constraints: bounds(minimum, maximum, exclusive_minimum, exclusive_maximum),
+            },
+            SchemaDef::Type(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => Type {
+                expression: "float".to_string(),
+                constraints: bounds(minimum, maximum, exclusive_minimum, exclusive_maximum),
+            },
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                min_items,
+                max_items,
+            }) => {
+                let expression = if prefix_items.is_empty() {
+                    let item = self.item(items, &format!("{}Item", name));
+                    format!("list[{}]", item)
+                } else {
+                    // Any items after the prefix are left out.
+                    let members = prefix_items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, schema)| {
+                            self.nested(schema, &format!("{}Item{}", name, index + 1))
+                        })
+                        .collect::<Vec<_>>();
+
+                    format!("tuple[{}]", members.join(", "))
+                };
+
+                let mut constraints = vec![];
+
+                if let Some(min_items) = min_items {
+                    constraints.push(format!("min_length={}", min_items));
+                }
+
+                if let Some(max_items) = max_items {
+                    constraints.push(format!("max_length={}", max_items));
+                }
+
+                Type {
+                    expression,
+                    constraints,
+                }
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if !object.properties.is_empty() || object.no_additional_properties() {
+                    let name = self.names.unique(name.to_string());
+                    self.model(&name, schema);
+
+                    Type::new(&name)
+                } else {
+                    let value = match &object.additional_properties {
+                        AdditionalProperties::Schema(schema) => {
+                            self.item(schema, &format!("{}Value", name))
+                        }
+                        AdditionalProperties::Boolean(_) => self.any(),
+                    };
+
+                    Type::new(&format!("dict[str, {}]", value))
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, _)) => {
+                    let name = self.definitions[definition].clone();
+
+                    if self.quote_references {
+                        Type::new(&string_literal(&name))
+                    } else {
+                        Type::new(&name)
+                    }
+                }
+                None => Type::new(&self.any()),
+            },
+            SchemaDef::Enum { value } => {
+                let name = self.names.unique(name.to_string());
+                self.enumeration(&name, &schema.metadata, value);
+
+                Type::new(&name)
+            }
+            SchemaDef::Const { value } => match literal(value) {
+                Some(literal) => {
+                    self.import("typing", "Literal");
+                    Type::new(&format!("Literal[{}]", literal))
+                }
+                None => Type::new(&self.any()),
+            },
+            SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } => {
+                let mut members = vec![];
+
+                for (index, schema) in value.iter().enumerate() {
+                    let member = self.nested(schema, &format!("{}{}", name, index + 1));
+
+                    if !members.contains(&member) {
+                        members.push(member);
+                    }
+                }
+
+                let has_none = members.iter().any(|member| member == "None");
+                members.retain(|member| member != "None");
+
+                let expression = match members.as_slice() {
+                    [] => "None".to_string(),
+                    [member] => member.clone(),
+                    _ => {
+                        self.import("typing", "Union");
+                        format!("Union[{}]", members.join(", "))
+                    }
+                };
+
+                if has_none && expression != "None" {
+                    self.import("typing", "Optional");
+                    Type::new(&format!("Optional[{}]", expression))
+                } else {
+                    Type::new(&expression)
+                }
+            }
+            SchemaDef::AllOf { value } if value.len() == 1 => self.render(&value[0], name),
+            SchemaDef::AllOf { .. } | SchemaDef::Empty {} => Type::new(&self.any()),
+        }
+    }
+
+    /// The type of array items or map values, which is `Any` when there's no schema
+    fn item(&mut self, schema: &Schema, name: &str) -> String {
+        if matches!(schema.schema, SchemaDef::Empty {}) {
+            self.any()
+        } else {
+            self.nested(schema, name)
+        }
+    }
+
+    fn any(&mut self) -> String {
+        self.import("typing", "Any");
+        "Any".to_string()
+    }
+}
+
+fn bounds<T: std::fmt::Display>(
+    minimum: &Option<T>,
+    maximum: &Option<T>,
+    exclusive_minimum: &Option<T>,
+    exclusive_maximum: &Option<T>,
+) -> Vec<String> {
+    [
+        ("ge", minimum),
+        ("le", maximum),
+        ("gt", exclusive_minimum),
+        ("lt", exclusive_maximum),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.as_ref().map(|value| format!("{}={}", name, value)))
+    .collect()
+}
+
+fn class_name(name: &str) -> String {
+    let name = pascal_case(name);
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Model{}", name)
+    } else {
+        name
+    }
+}
+
+/// A snake-case attribute name, which isn't a keyword and doesn't start with an underscore
+fn attribute_name(property: &str) -> String {
+    let name = snake_case(property);
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{}", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn docstring(metadata: &Metadata, indent: &str) -> Option<String> {
+    let description = metadata
+        .description
+        .as_deref()
+        .or(metadata.title.as_deref())?
+        .replace('\\', "\\\\")
+        .replace("\"\"\"", "\\\"\\\"\\\"");
+
+    if description.contains('\n') {
+        let lines = description
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", indent, line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Some(format!(
+            "{}\"\"\"{}\n{}\"\"\"",
+            indent,
+            lines.trim_start(),
+            indent
+        ))
+    } else {
+        Some(format!("{}\"\"\"{}\"\"\"", indent, description))
+    }
+}
+
+/// A Python string literal, using JSON escapes (which Python understands)
+fn string_literal(value: &str) -> String {
+    Value::String(value.to_string()).to_string()
+}
+
+fn literal(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(true) => Some("True".to_string()),
+        Value::Bool(false) => Some("False".to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::String(value) => Some(string_literal(value)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pydantic_models() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "status": { "$ref": "#/$defs/status" },
+                "customerCode": { "$ref": "#/$defs/code", "description": "The customer" },
+                "discount": { "type": "number", "exclusiveMinimum": 0, "maximum": 0.5 },
+                "lines": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string", "pattern": "^[A-Z]+$" } },
+                        "required": ["sku"],
+                        "additionalProperties": false
+                    }
+                },
+                "from": { "oneOf": [{ "type": "string" }, { "type": "null" }] }
+            },
+            "required": ["id", "status"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "code": { "type": "string", "pattern": "^C[0-9]+$" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            pydantic(&file, "ignored"),
+            r#"from __future__ import annotations
+
+from enum import Enum
+from typing import Annotated, Optional
+
+from pydantic import BaseModel, ConfigDict, Field
+
+
+class OrderLinesItem(BaseModel):
+    model_config = ConfigDict(extra="forbid")
+
+    sku: str = Field(pattern="^[A-Z]+$")
+
+
+class Order(BaseModel):
+    """An order"""
+
+    id: int = Field(ge=1)
+    status: Status
+    customer_code: Optional[Code] = Field(default=None, alias="customerCode", description="The customer")
+    discount: Optional[float] = Field(default=None, le=0.5, gt=0)
+    lines: Optional[list[OrderLinesItem]] = None
+    from_: Optional[str] = Field(default=None, alias="from")
+
+
+class Status(str, Enum):
+    OPEN = "open"
+    IN_PROGRESS = "in-progress"
+
+
+Code = Annotated[str, Field(pattern="^C[0-9]+$")]
+"#
+        );
+    }
+
+    #[test]
+    fn pydantic_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            pydantic(&node, "tags"),
+            r#"from __future__ import annotations
+
+from typing import Optional
+
+from pydantic import BaseModel, Field
+
+
+class Node2(BaseModel):
+    label: str = Field(pattern="^[a-z]+$")
+    children: Optional[list[Node]] = Field(default=None, min_length=1)
+
+
+class Node(BaseModel):
+    value: Optional[int] = Field(default=None, ge=0, le=10)
+    parent: Optional[Node] = None
+"#
+        );
+
+        assert_eq!(
+            pydantic(&tags, "tags"),
+            r#"from __future__ import annotations
+
+from typing import Annotated
+
+from pydantic import Field
+
+
+Tags = Annotated[list[Annotated[str, Field(pattern="^[a-z]+$")]], Field(max_length=3)]
+"#
+        );
+    }
+}
@@ -0,0 +1,594 @@
+use super::{snake_case, Names};
+use crate::constants::*;
+use crate::resolve::resolve;
+use serde_json::Value;
+
+const JSONB: &str = "jsonb";
+
+/// Words that have to be quoted to be used as table or column names
+const RESERVED: [&str; 58] = [
+    "all",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "both",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "column",
+    "constraint",
+    "create",
+    "current_date",
+    "current_user",
+    "default",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "false",
+    "fetch",
+    "for",
+    "foreign",
+    "from",
+    "grant",
+    "group",
+    "having",
+    "in",
+    "into",
+    "is",
+    "join",
+    "key",
+    "limit",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "primary",
+    "references",
+    "select",
+    "table",
+    "then",
+    "to",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+/// How many references can be followed for a single column
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Generate PostgreSQL `CREATE TABLE` statements for the top-level schema and each definition
+/// that's an object
+///
+/// Column types come from `type` and `format`, `required` properties are `NOT NULL` (unless they
+/// can be null), and enums, constants, bounds, length limits, and patterns become `CHECK`
+/// constraints. Nested objects (including referenced ones) are flattened into prefixed columns,
+/// or stored as `jsonb` if `jsonb` is set. Arrays of scalars become SQL arrays, and anything else
+/// is `jsonb`.
+pub fn postgres(value: &Value, name: &str, jsonb: bool) -> String {
+    let generator = Generator {
+        root: std::slice::from_ref(value),
+        jsonb,
+    };
+    let mut tables = vec![];
+    let mut table_names = Names::default();
+
+    if is_object(value) {
+        let name = value.get(TITLE_KEY).and_then(Value::as_str).unwrap_or(name);
+        let name = table_names.unique(snake_case(name));
+        tables.push(generator.table(&name, value, String::new()));
+    }
+
+    for (name, schema) in value
+        .get(DEFS_KEY)
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        if is_object(schema) {
+            let pointer = format!(
+                "/{}/{}",
+                DEFS_KEY,
+                name.replace('~', "~0").replace('/', "~1")
+            );
+            let name = table_names.unique(snake_case(name));
+            tables.push(generator.table(&name, schema, pointer));
+        }
+    }
+
+    tables.join("\n")
+}
+
+struct Column {
+    name: String,
+    sql_type: String,
+    not_null: bool,
+    checks: Vec<String>,
+    description: Option<String>,
+}
+
+struct Generator<'a> {
+    root: &'a [Value],
+    jsonb: bool,
+}
+
+impl Generator<'_> {
+    /// The statements for a table, where the pointer locates the schema in the file
+    fn table(&self, name: &str, schema: &Value, pointer: String) -> String {
+        let name = identifier(name);
+        let mut columns = vec![];
+        self.columns(
+            schema,
+            "",
+            true,
+            &mut vec![pointer],
+            &mut Names::default(),
+            &mut columns,
+        );
+
+        let mut result = format!("CREATE TABLE {} (\n", name);
+        result.push_str(
+            &columns
+                .iter()
+                .map(|column| {
+                    let mut line = format!("    {} {}", column.name, column.sql_type);
+
+                    if column.not_null {
+                        line.push_str(" NOT NULL");
+                    }
+
+                    if !column.checks.is_empty() {
+                        line.push_str(&format!(" CHECK ({})", column.checks.join(" AND ")));
+                    }
+
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join(",\n"),
+        );
+        result.push_str("\n);\n");
+
+        let mut comments = vec![];
+
+        if let Some(description) = description(schema) {
+            comments.push(format!(
+                "COMMENT ON TABLE {} IS {};",
+                name,
+                string_literal(description)
+            ));
+        }
+
+        for column in &columns {
+            if let Some(description) = &column.description {
+                comments.push(format!(
+                    "COMMENT ON COLUMN {}.{} IS {};",
+                    name,
+                    column.name,
+                    string_literal(description)
+                ));
+            }
+        }
+
+        if !comments.is_empty() {
+            result.push('\n');
+            result.push_str(&comments.join("\n"));
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// Add a column for each property, flattening nested objects unless they're stored as `jsonb`
+    ///
+    /// The visited pointers are used to store recursive objects as `jsonb`. Property names that
+    /// are the same in snake case get numbers appended, and so do flattened names that are already
+    /// used by another column in the table.
+    fn columns(
+        &self,
+        schema: &Value,
+        prefix: &str,
+        required: bool,
+        visiting: &mut Vec<String>,
+        names: &mut Names,
+        acc: &mut Vec<Column>,
+    ) {
+        let mut property_names = Names::default();
+        let required_names = schema
+            .get(REQUIRED_KEY)
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        for (property, schema) in schema
+            .get(PROPERTIES_KEY)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let property_name = property_names.unique(snake_case(property));
+            let name = if prefix.is_empty() {
+                property_name
+            } else {
+                format!("{}_{}", prefix, property_name)
+            };
+            let required = required && required_names.contains(&property.as_str());
+            let description = description(schema).map(str::to_string);
+            let (schema, pointers) = self.dereference(schema);
+
+            if is_object(schema) && !self.jsonb {
+                if pointers.iter().any(|pointer| visiting.contains(pointer)) {
+                    acc.push(Column {
+                        name: identifier(&names.unique(name)),
+                        sql_type: JSONB.to_string(),
+                        not_null: required,
+                        checks: vec![],
+                        description,
+                    });
+                } else {
+                    let depth = visiting.len();
+                    visiting.extend(pointers);
+                    self.columns(schema, &name, required, visiting, names, acc);
+                    visiting.truncate(depth);
+                }
+            } else {
+                let name = identifier(&names.unique(name));
+                let (sql_type, nullable) = self.column_type(schema);
+                let checks = if sql_type == JSONB {
+                    vec![]
+                } else {
+                    checks(&name, schema)
+                };
+
+                acc.push(Column {
+                    name,
+                    sql_type,
+                    not_null: required && !nullable,
+                    checks,
+                    description,
+                });
+            }
+        }
+    }
+
+    /// Follow references, returning the target and the pointers of the references that were
+    /// followed
+    fn dereference<'b>(&'b self, mut schema: &'b Value) -> (&'b Value, Vec<String>) {
+        let mut pointers = vec![];
+
+        while let Some(reference) = schema.get(REF_KEY).and_then(Value::as_str) {
+            match resolve(self.root, reference, Some(0)) {
+                Ok(resolved) if pointers.len() < MAX_REFERENCE_DEPTH => {
+                    pointers.push(resolved.pointer);
+                    schema = resolved.value;
+                }
+                _ => break,
+            }
+        }
+
+        (schema, pointers)
+    }
+
+    /// The column type, and whether the value can be null
+    fn column_type(&self, schema: &Value) -> (String, bool) {
+        let (types, nullable) = types(schema);
+
+        let sql_type = match types.as_slice() {
+            ["string"] => match schema.get(FORMAT_KEY).and_then(Value::as_str) {
+                Some("date-time") => "timestamptz".to_string(),
+                Some("date") => "date".to_string(),
+                Some("time") => "time".to_string(),
+                Some("uuid") => "uuid".to_string(),
+                _ => match schema.get(MAX_LENGTH_KEY).and_then(Value::as_u64) {
+                    Some(max_length) => format!("varchar({})", max_length),
+                    None => "text".to_string(),
+                },
+            },
+            ["integer"] => "bigint".to_string(),
+            ["number"] => "double precision".to_string(),
+            ["boolean"] => "boolean".to_string(),
+            ["array"] => {
+                let items = schema.get(ITEMS_KEY).map(|items| self.dereference(items).0);
+
+                match items.map(|items| self.column_type(items)) {
+                    Some((items, _)) if items != JSONB && !items.ends_with("[]") => {
+                        format!("{}[]", items)
+                    }
+                    _ => JSONB.to_string(),
+                }
+            }
+            _ => JSONB.to_string(),
+        };
+
+        (sql_type, nullable)
+    }
+}
+
+/// The non-null types a schema allows (from `type`, `enum`, or `const`), and whether it allows null
+fn types(schema: &Value) -> (Vec<&'static str>, bool) {
+    let names = match (
+        schema.get(TYPE_KEY),
+        schema.get(ENUM_KEY),
+        schema.get(CONST_KEY),
+    ) {
+        (Some(Value::String(name)), _, _) => vec![name.as_str()],
+        (Some(Value::Array(names)), _, _) => names.iter().filter_map(Value::as_str).collect(),
+        (None, Some(Value::Array(values)), _) => values.iter().map(json_type).collect(),
+        (None, None, Some(value)) => vec![json_type(value)],
+        _ => vec![],
+    };
+
+    let mut types = vec![];
+    let mut nullable = false;
+
+    for name in names {
+        match name {
+            "null" => nullable = true,
+            // Integers are also numbers.
+            "integer" if types.contains(&"number") => {}
+            "number" => {
+                types.retain(|name| *name != "integer");
+                types.push("number");
+            }
+            name => {
+                if let Some(name) = ["string", "integer", "boolean", "array", "object"]
+                    .into_iter()
+                    .find(|known| *known == name)
+                {
+                    if !types.contains(&name) {
+                        types.push(name);
+                    }
+                }
+            }
+        }
+    }
+
+    (types, nullable)
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_object(schema: &Value) -> bool {
+    schema.get(PROPERTIES_KEY).is_some()
+        || schema.get(TYPE_KEY).and_then(Value::as_str) == Some("object")
+}
+
+fn checks(column: &str, schema: &Value) -> Vec<String> {
+    let mut checks = vec![];
+
+    if let Some(values) = schema.get(ENUM_KEY).and_then(Value::as_array) {
+        let values = values.iter().filter_map(literal).collect::<Vec<_>>();
+
+        if !values.is_empty() {
+            checks.push(format!("{} IN ({})", column, values.join(", ")));
+        }
+    }
+
+    if let Some(value) = schema.get(CONST_KEY).and_then(literal) {
+        checks.push(format!("{} = {}", column, value));
+    }
+
+    for (keyword, operator) in [
+        (MINIMUM_KEY, ">="),
+        (EXCLUSIVE_MINIMUM_KEY, ">"),
+        (MAXIMUM_KEY, "<="),
+        (EXCLUSIVE_MAXIMUM_KEY, "<"),
+    ] {
+        if let Some(bound) = schema.get(keyword).filter(|bound| bound.is_number()) {
+            checks.push(format!("{} {} {}", column, operator, bound));
+        }
+    }
+
+    // Lengths that are checked by `varchar` don't need to be repeated.
+    if let Some(min_length) = schema.get(MIN_LENGTH_KEY).and_then(Value::as_u64) {
+        checks.push(format!("char_length({}) >= {}", column, min_length));
+    }
+
+    // POSIX regular expressions agree with ECMA-262 ones for most patterns in practice.
+    if let Some(pattern) = schema.get(PATTERN_KEY).and_then(Value::as_str) {
+        checks.push(format!("{} ~ {}", column, string_literal(pattern)));
+    }
+
+    checks
+}
+
+/// A SQL literal for a scalar value
+fn literal(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::String(value) => Some(string_literal(value)),
+        _ => None,
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn description(schema: &Value) -> Option<&str> {
+    schema
+        .get(DESCRIPTION_KEY)
+        .or_else(|| schema.get(TITLE_KEY))
+        .and_then(Value::as_str)
+}
+
+/// A name, quoted if it's reserved or isn't a simple lower-case identifier
+fn identifier(name: &str) -> String {
+    let is_simple = name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if is_simple && !RESERVED.contains(&name) {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn postgres_tables() {
+        let value = serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "status": { "enum": ["open", "can't ship"] },
+                "placedAt": { "type": "string", "format": "date-time", "description": "When" },
+                "note": { "type": ["string", "null"], "maxLength": 200 },
+                "shipping": { "$ref": "#/$defs/address" },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "user": { "type": "string", "minLength": 1 }
+            },
+            "required": ["id", "status", "note", "shipping"],
+            "$defs": {
+                "address": {
+                    "type": "object",
+                    "properties": {
+                        "city": { "type": "string" },
+                        "zip": { "type": "string" },
+                        "parent": { "$ref": "#/$defs/address" }
+                    },
+                    "required": ["city"]
+                }
+            }
+        });
+
+        assert_eq!(
+            postgres(&value, "ignored", false),
+            r#"CREATE TABLE "order" (
+    id bigint NOT NULL CHECK (id >= 1),
+    status text NOT NULL CHECK (status IN ('open', 'can''t ship')),
+    placed_at timestamptz,
+    note varchar(200),
+    shipping_city text NOT NULL,
+    shipping_zip text,
+    shipping_parent jsonb,
+    tags text[],
+    "user" text CHECK (char_length("user") >= 1)
+);
+
+COMMENT ON TABLE "order" IS 'An order';
+COMMENT ON COLUMN "order".placed_at IS 'When';
+
+CREATE TABLE address (
+    city text NOT NULL,
+    zip text,
+    parent jsonb
+);
+"#
+        );
+    }
+
+    #[test]
+    fn postgres_name_collisions() {
+        let value = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "fooBar": { "type": "string" },
+                "foo_bar": { "type": "integer" },
+                "home": { "$ref": "#/$defs/address" },
+                "home_street": { "type": "boolean" }
+            },
+            "$defs": {
+                "address": {
+                    "type": "object",
+                    "properties": {
+                        "street": { "type": "string" },
+                        "Street": { "type": "string" }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            postgres(&value, "Address", false),
+            r#"CREATE TABLE address (
+    foo_bar text,
+    foo_bar2 bigint,
+    home_street text,
+    home_street2 text,
+    home_street3 boolean
+);
+
+CREATE TABLE address2 (
+    street text,
+    street2 text
+);
+"#
+        );
+    }
+
+    #[test]
+    fn postgres_edge_cases() {
+        let node = serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        });
+        let tags = serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        });
+
+        assert_eq!(
+            postgres(&node, "tags", false),
+            r#"CREATE TABLE node (
+    label text NOT NULL CHECK (label ~ '^[a-z]+$'),
+    children jsonb
+);
+
+COMMENT ON TABLE node IS 'Node';
+
+CREATE TABLE node2 (
+    value bigint CHECK (value >= 0 AND value <= 10),
+    parent jsonb
+);
+"#
+        );
+
+        assert_eq!(postgres(&tags, "tags", false), r#""#);
+    }
+}
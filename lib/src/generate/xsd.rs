@@ -0,0 +1,666 @@
+use super::{anchored, local_definition, pascal_case, DefinitionNames, Names};
+use crate::schema::{Metadata, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const INDENT: &str = "  ";
+
+/// How many references can be followed to decide whether a type is simple
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Generate an XML Schema (experimental), with a named type for each definition
+///
+/// The top-level schema (or each definition, if there isn't one) is a global element. Objects
+/// become complex types with a sequence of elements (where optional properties have
+/// `minOccurs="0"`), arrays become repeated elements, enums and bounds become restrictions (and
+/// patterns are anchored, since XSD patterns always match the whole string), and nullable schemas
+/// become nillable elements. Additional properties aren't represented, and tuples, other unions,
+/// and references to other files become `xs:anyType`.
+pub fn xsd(file: &SchemaFile, name: &str, namespace: Option<&str>) -> String {
+    let mut names = Names::default();
+
+    let root = file
+        .schema
+        .as_ref()
+        .filter(|schema| !matches!(schema, SchemaDef::Empty {}))
+        .map(|schema| {
+            let type_name = names.unique(type_name(file.metadata.title.as_deref().unwrap_or(name)));
+            let schema = Schema {
+                metadata: Metadata {
+                    description: file.metadata.description.clone(),
+                    ..Metadata::default()
+                },
+                schema: schema.clone(),
+            };
+
+            (type_name, schema)
+        });
+    let definitions = file
+        .definitions
+        .iter()
+        .flatten()
+        .map(|(name, _)| (name.to_string(), names.unique(type_name(name))))
+        .collect::<HashMap<_, _>>();
+
+    let generator = Generator {
+        file,
+        definitions,
+        prefix: if namespace.is_some() { "tns:" } else { "" },
+    };
+
+    let mut result = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    result.push_str("<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\"");
+
+    if let Some(namespace) = namespace {
+        result.push_str(&format!(
+            " targetNamespace=\"{0}\" xmlns:tns=\"{0}\"",
+            escape(namespace)
+        ));
+    }
+
+    result.push_str(" elementFormDefault=\"qualified\">\n");
+
+    let mut sections = vec![];
+
+    match &root {
+        Some((type_name, _)) => sections.push(generator.global_element(name, type_name)),
+        None => sections.push(
+            file.definitions
+                .iter()
+                .flatten()
+                .map(|(name, _)| {
+                    generator.global_element(name, &generator.definitions[name.as_str()])
+                })
+                .collect(),
+        ),
+    }
+
+    if let Some((type_name, schema)) = &root {
+        sections.push(generator.named_type(type_name, schema));
+    }
+
+    for (name, schema) in file.definitions.iter().flatten() {
+        sections.push(generator.named_type(&generator.definitions[name.as_str()], schema));
+    }
+
+    result.push_str(&sections.join("\n"));
+    result.push_str("</xs:schema>\n");
+    result
+}
+
+struct Generator<'a> {
+    file: &'a SchemaFile,
+    definitions: DefinitionNames,
+    /// The prefix for references to types in the target namespace
+    prefix: &'static str,
+}
+
+/// The type of an element
+enum XsdType {
+    /// A built-in or named type
+    Named(String),
+    /// The content of an anonymous simple type
+    Simple(String),
+    /// The content of an anonymous complex type
+    Complex(String),
+}
+
+impl Generator<'_> {
+    fn global_element(&self, name: &str, type_name: &str) -> String {
+        format!(
+            "{}<xs:element name=\"{}\" type=\"{}{}\"/>\n",
+            INDENT,
+            element_name(name),
+            self.prefix,
+            type_name
+        )
+    }
+
+    fn named_type(&self, name: &str, schema: &Schema) -> String {
+        let content_indent = format!("{}{}", INDENT, INDENT);
+        let documentation = annotation(&schema.metadata, &content_indent);
+
+        let (tag, content) = match self.xsd_type(schema, INDENT) {
+            XsdType::Simple(content) => ("simpleType", content),
+            XsdType::Complex(content) => ("complexType", content),
+            XsdType::Named(base) if self.is_simple(schema, 0) => {
+                ("simpleType", restriction(&base, &[], &content_indent))
+            }
+            // Complex types (including `xs:anyType`) can only be aliased by extension.
+            XsdType::Named(base) => (
+                "complexType",
+                format!(
+                    "{0}<xs:complexContent>\n{0}{1}<xs:extension base=\"{2}\"/>\n{0}</xs:complexContent>\n",
+                    content_indent, INDENT, base
+                ),
+            ),
+        };
+
+        let content = documentation + &content;
+
+        if content.is_empty() {
+            format!("{}<xs:{} name=\"{}\"/>\n", INDENT, tag, name)
+        } else {
+            format!(
+                "{0}<xs:{1} name=\"{2}\">\n{3}{0}</xs:{1}>\n",
+                INDENT, tag, name, content
+            )
+        }
+    }
+
+    /// An element for a property (or array item), which is repeated if the schema is an array
+    fn element(&self, name: &str, schema: &Schema, required: bool, indent: &str) -> String {
+        let (outer, outer_nullable) = nullable(schema);
+
+        let (item, min_occurs, max_occurs, nillable) = match &outer.schema {
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                min_items,
+                max_items,
+            }) if prefix_items.is_empty() => {
+                let (item, item_nullable) = nullable(items);
+                let min_occurs = if required && !outer_nullable {
+                    min_items.unwrap_or(0)
+                } else {
+                    0
+                };
+                let max_occurs = max_items
+                    .map(|max_items| max_items.to_string())
+                    .unwrap_or_else(|| "unbounded".to_string());
+
+                (item, min_occurs, max_occurs, item_nullable)
+            }
+            _ => (
+                outer,
+                usize::from(required),
+                "1".to_string(),
+                outer_nullable,
+            ),
+        };
+
+        let content_indent = format!("{}{}", indent, INDENT);
+        let mut attributes = format!("name=\"{}\"", element_name(name));
+        let mut content = annotation(&schema.metadata, &content_indent);
+
+        match self.xsd_type(item, &content_indent) {
+            XsdType::Named(type_name) => attributes.push_str(&format!(" type=\"{}\"", type_name)),
+            XsdType::Simple(simple) => content.push_str(&format!(
+                "{0}<xs:simpleType>\n{1}{0}</xs:simpleType>\n",
+                content_indent, simple
+            )),
+            XsdType::Complex(complex) if complex.is_empty() => {
+                content.push_str(&format!("{}<xs:complexType/>\n", content_indent))
+            }
+            XsdType::Complex(complex) => content.push_str(&format!(
+                "{0}<xs:complexType>\n{1}{0}</xs:complexType>\n",
+                content_indent, complex
+            )),
+        }
+
+        if min_occurs != 1 {
+            attributes.push_str(&format!(" minOccurs=\"{}\"", min_occurs));
+        }
+
+        if max_occurs != "1" {
+            attributes.push_str(&format!(" maxOccurs=\"{}\"", max_occurs));
+        }
+
+        if nillable {
+            attributes.push_str(" nillable=\"true\"");
+        }
+
+        if content.is_empty() {
+            format!("{}<xs:element {}/>\n", indent, attributes)
+        } else {
+            format!(
+                "{0}<xs:element {1}>\n{2}{0}</xs:element>\n",
+                indent, attributes, content
+            )
+        }
+    }
+
+    /// The type for a schema, where the indentation is for the tag of an anonymous type
+    fn xsd_type(&self, schema: &Schema, indent: &str) -> XsdType {
+        let content_indent = format!("{}{}", indent, INDENT);
+
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Null {}) => XsdType::Complex(String::new()),
+            SchemaDef::Type(SchemaType::Boolean {}) => XsdType::Named("xs:boolean".to_string()),
+            SchemaDef::Type(SchemaType::String { pattern }) => match pattern {
+                Some(pattern) => XsdType::Simple(restriction(
+                    "xs:string",
+                    &[("pattern", anchored(pattern))],
+                    &content_indent,
+                )),
+                None => XsdType::Named("xs:string".to_string()),
+            },
+            SchemaDef::Type(SchemaType::Integer {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => bounded(
+                "xs:long",
+                [minimum, exclusive_minimum, maximum, exclusive_maximum]
+                    .map(|bound| bound.map(|bound| bound.to_string())),
+                &content_indent,
+            ),
+            SchemaDef::Type(SchemaType::Number {
+                minimum,
+                maximum,
+                exclusive_minimum,
+                exclusive_maximum,
+            }) => bounded(
+                "xs:double",
+                [minimum, exclusive_minimum, maximum, exclusive_maximum]
+                    .map(|bound| bound.map(|bound| bound.to_string())),
+                &content_indent,
+            ),
+            // Arrays that aren't properties are wrappers for repeated `item` elements.
+            SchemaDef::Type(SchemaType::Array { prefix_items, .. }) if prefix_items.is_empty() => {
+                let element_indent = format!("{}{}", content_indent, INDENT);
+
+                XsdType::Complex(sequence(
+                    self.element("item", schema, true, &element_indent),
+                    &content_indent,
+                ))
+            }
+            SchemaDef::Type(SchemaType::Array { .. }) => XsdType::Named("xs:anyType".to_string()),
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                let element_indent = format!("{}{}", content_indent, INDENT);
+                let elements = object
+                    .properties
+                    .iter()
+                    .map(|(property, schema)| {
+                        self.element(
+                            property,
+                            schema,
+                            object.required.contains(property),
+                            &element_indent,
+                        )
+                    })
+                    .collect::<String>();
+
+                if elements.is_empty() {
+                    XsdType::Complex(String::new())
+                } else {
+                    XsdType::Complex(sequence(elements, &content_indent))
+                }
+            }
+            SchemaDef::Ref { value } => match local_definition(self.file, value) {
+                Some((definition, _)) => {
+                    XsdType::Named(format!("{}{}", self.prefix, self.definitions[definition]))
+                }
+                None => XsdType::Named("xs:anyType".to_string()),
+            },
+            SchemaDef::Enum { value } => XsdType::Simple(restriction(
+                "xs:string",
+                &value
+                    .iter()
+                    .map(|value| ("enumeration", value.clone()))
+                    .collect::<Vec<_>>(),
+                &content_indent,
+            )),
+            SchemaDef::Const { value } => {
+                let (base, facet) = match value {
+                    Value::String(value) => ("xs:string", ("enumeration", value.clone())),
+                    // Booleans can't be enumerated.
+                    Value::Bool(value) => ("xs:boolean", ("pattern", value.to_string())),
+                    Value::Number(value) if value.is_f64() => {
+                        ("xs:double", ("enumeration", value.to_string()))
+                    }
+                    Value::Number(value) => ("xs:long", ("enumeration", value.to_string())),
+                    _ => return XsdType::Named("xs:anyType".to_string()),
+                };
+
+                XsdType::Simple(restriction(base, &[facet], &content_indent))
+            }
+            SchemaDef::OneOf { .. } | SchemaDef::AnyOf { .. } => match nullable(schema) {
+                (schema, true) => self.xsd_type(schema, indent),
+                _ => XsdType::Named("xs:anyType".to_string()),
+            },
+            SchemaDef::AllOf { value } => match value.as_slice() {
+                [schema] => self.xsd_type(schema, indent),
+                _ => XsdType::Named("xs:anyType".to_string()),
+            },
+            SchemaDef::Empty {} => XsdType::Named("xs:anyType".to_string()),
+        }
+    }
+
+    /// Whether the schema is represented by a simple type (following local references)
+    fn is_simple(&self, schema: &Schema, depth: usize) -> bool {
+        match &schema.schema {
+            SchemaDef::Type(SchemaType::Boolean {})
+            | SchemaDef::Type(SchemaType::String { .. })
+            | SchemaDef::Type(SchemaType::Integer { .. })
+            | SchemaDef::Type(SchemaType::Number { .. })
+            | SchemaDef::Enum { .. } => true,
+            SchemaDef::Const { value } => {
+                matches!(value, Value::Bool(_) | Value::Number(_) | Value::String(_))
+            }
+            SchemaDef::Ref { value } => {
+                depth < MAX_REFERENCE_DEPTH
+                    && local_definition(self.file, value)
+                        .is_some_and(|(_, schema)| self.is_simple(schema, depth + 1))
+            }
+            SchemaDef::OneOf { .. } | SchemaDef::AnyOf { .. } => match nullable(schema) {
+                (schema, true) => self.is_simple(schema, depth),
+                _ => false,
+            },
+            SchemaDef::AllOf { value } => match value.as_slice() {
+                [schema] => self.is_simple(schema, depth),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// The non-null branch of a union with `null`, and whether the schema was one
+fn nullable(schema: &Schema) -> (&Schema, bool) {
+    if let SchemaDef::OneOf { value } | SchemaDef::AnyOf { value } = &schema.schema {
+        let branches = value
+            .iter()
+            .filter(|branch| !matches!(branch.schema, SchemaDef::Type(SchemaType::Null {})))
+            .collect::<Vec<_>>();
+
+        if let [branch] = branches.as_slice() {
+            if branches.len() < value.len() {
+                return (branch, true);
+            }
+        }
+    }
+
+    (schema, false)
+}
+
+/// A simple type for bounds (minimum, exclusive minimum, maximum, and exclusive maximum)
+fn bounded(base: &str, bounds: [Option<String>; 4], indent: &str) -> XsdType {
+    let facets = [
+        "minInclusive",
+        "minExclusive",
+        "maxInclusive",
+        "maxExclusive",
+    ]
+    .into_iter()
+    .zip(bounds)
+    .filter_map(|(facet, bound)| bound.map(|bound| (facet, bound)))
+    .collect::<Vec<_>>();
+
+    if facets.is_empty() {
+        XsdType::Named(base.to_string())
+    } else {
+        XsdType::Simple(restriction(base, &facets, indent))
+    }
+}
+
+fn restriction(base: &str, facets: &[(&str, String)], indent: &str) -> String {
+    if facets.is_empty() {
+        format!("{}<xs:restriction base=\"{}\"/>\n", indent, base)
+    } else {
+        let facets = facets
+            .iter()
+            .map(|(facet, value)| {
+                format!(
+                    "{}{}<xs:{} value=\"{}\"/>\n",
+                    indent,
+                    INDENT,
+                    facet,
+                    escape(value)
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            "{0}<xs:restriction base=\"{1}\">\n{2}{0}</xs:restriction>\n",
+            indent, base, facets
+        )
+    }
+}
+
+fn sequence(elements: String, indent: &str) -> String {
+    format!("{0}<xs:sequence>\n{1}{0}</xs:sequence>\n", indent, elements)
+}
+
+fn annotation(metadata: &Metadata, indent: &str) -> String {
+    metadata
+        .description
+        .as_deref()
+        .or(metadata.title.as_deref())
+        .map(|description| {
+            format!(
+                "{0}<xs:annotation>\n{0}{1}<xs:documentation>{2}</xs:documentation>\n{0}</xs:annotation>\n",
+                indent,
+                INDENT,
+                escape(description)
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// A type name, which has to be an XML name
+fn type_name(name: &str) -> String {
+    let name = pascal_case(name);
+
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("_{}", name)
+    }
+}
+
+/// An element name for a property, with characters that can't be in XML names replaced
+fn element_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '.' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    if name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        name
+    } else {
+        format!("_{}", name)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xsd_types() {
+        let file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 0 },
+                "status": { "$ref": "#/$defs/status" },
+                "line items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "sku": { "type": "string", "pattern": "^[A-Z]+$" } },
+                        "required": ["sku"]
+                    },
+                    "minItems": 1
+                },
+                "note": {
+                    "description": "Free text & more",
+                    "anyOf": [{ "type": "string" }, { "type": "null" }]
+                },
+                "total": { "$ref": "#/$defs/amount" },
+                "extra": {}
+            },
+            "required": ["id", "status", "line items", "note"],
+            "$defs": {
+                "status": { "enum": ["open", "in-progress"] },
+                "amount": { "$ref": "#/$defs/money" },
+                "money": { "type": "number", "exclusiveMinimum": 0 }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            xsd(&file, "order", Some("urn:shop")),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:shop" xmlns:tns="urn:shop" elementFormDefault="qualified">
+  <xs:element name="order" type="tns:Order"/>
+
+  <xs:complexType name="Order">
+    <xs:annotation>
+      <xs:documentation>An order</xs:documentation>
+    </xs:annotation>
+    <xs:sequence>
+      <xs:element name="id">
+        <xs:simpleType>
+          <xs:restriction base="xs:long">
+            <xs:minInclusive value="0"/>
+          </xs:restriction>
+        </xs:simpleType>
+      </xs:element>
+      <xs:element name="status" type="tns:Status"/>
+      <xs:element name="line_items" maxOccurs="unbounded">
+        <xs:complexType>
+          <xs:sequence>
+            <xs:element name="sku">
+              <xs:simpleType>
+                <xs:restriction base="xs:string">
+                  <xs:pattern value="[A-Z]+"/>
+                </xs:restriction>
+              </xs:simpleType>
+            </xs:element>
+          </xs:sequence>
+        </xs:complexType>
+      </xs:element>
+      <xs:element name="note" type="xs:string" nillable="true">
+        <xs:annotation>
+          <xs:documentation>Free text &amp; more</xs:documentation>
+        </xs:annotation>
+      </xs:element>
+      <xs:element name="total" type="tns:Amount" minOccurs="0"/>
+      <xs:element name="extra" type="xs:anyType" minOccurs="0"/>
+    </xs:sequence>
+  </xs:complexType>
+
+  <xs:simpleType name="Status">
+    <xs:restriction base="xs:string">
+      <xs:enumeration value="open"/>
+      <xs:enumeration value="in-progress"/>
+    </xs:restriction>
+  </xs:simpleType>
+
+  <xs:simpleType name="Amount">
+    <xs:restriction base="tns:Money"/>
+  </xs:simpleType>
+
+  <xs:simpleType name="Money">
+    <xs:restriction base="xs:double">
+      <xs:minExclusive value="0"/>
+    </xs:restriction>
+  </xs:simpleType>
+</xs:schema>
+"#
+        );
+    }
+
+    #[test]
+    fn xsd_edge_cases() {
+        let node = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let tags = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        }))
+        .unwrap();
+
+        assert_eq!(
+            xsd(&node, "tags", None),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" elementFormDefault="qualified">
+  <xs:element name="tags" type="Node"/>
+
+  <xs:complexType name="Node">
+    <xs:sequence>
+      <xs:element name="label">
+        <xs:simpleType>
+          <xs:restriction base="xs:string">
+            <xs:pattern value="[a-z]+"/>
+          </xs:restriction>
+        </xs:simpleType>
+      </xs:element>
+      <xs:element name="children" type="Node2" minOccurs="0" maxOccurs="unbounded"/>
+    </xs:sequence>
+  </xs:complexType>
+
+  <xs:complexType name="Node2">
+    <xs:sequence>
+      <xs:element name="value" minOccurs="0">
+        <xs:simpleType>
+          <xs:restriction base="xs:long">
+            <xs:minInclusive value="0"/>
+            <xs:maxInclusive value="10"/>
+          </xs:restriction>
+        </xs:simpleType>
+      </xs:element>
+      <xs:element name="parent" type="Node2" minOccurs="0"/>
+    </xs:sequence>
+  </xs:complexType>
+</xs:schema>
+"#
+        );
+
+        assert_eq!(
+            xsd(&tags, "tags", None),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" elementFormDefault="qualified">
+  <xs:element name="tags" type="Tags"/>
+
+  <xs:complexType name="Tags">
+    <xs:sequence>
+      <xs:element name="item" minOccurs="0" maxOccurs="3">
+        <xs:simpleType>
+          <xs:restriction base="xs:string">
+            <xs:pattern value="[a-z]+"/>
+          </xs:restriction>
+        </xs:simpleType>
+      </xs:element>
+    </xs:sequence>
+  </xs:complexType>
+</xs:schema>
+"#
+        );
+    }
+}
@@ -0,0 +1,606 @@
+use super::{pascal_case, Names};
+use crate::constants::*;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Zod string methods for formats
+const FORMATS: [(&str, &str); 9] = [
+    ("date", ".date()"),
+    ("date-time", ".datetime({ offset: true })"),
+    ("email", ".email()"),
+    ("ipv4", ".ip({ version: \"v4\" })"),
+    ("ipv6", ".ip({ version: \"v6\" })"),
+    ("time", ".time()"),
+    ("uri", ".url()"),
+    ("url", ".url()"),
+    ("uuid", ".uuid()"),
+];
+
+/// Generate a TypeScript module exporting a Zod validator and its inferred type for the top-level
+/// schema and each definition
+///
+/// Definitions are declared before the schemas that use them, and references that can't be
+/// (because they're recursive) are wrapped in `z.lazy`, although TypeScript needs an explicit type
+/// for those. Patterns, bounds, lengths, item counts, and common formats become refinements,
+/// properties that aren't required are optional, and objects that don't allow additional
+/// properties are strict. References to other files are `z.unknown()`.
+pub fn zod(value: &Value, name: &str) -> String {
+    let definitions = value
+        .get(DEFS_KEY)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut names = Names::default();
+    let root_name = value.get(TITLE_KEY).and_then(Value::as_str).unwrap_or(name);
+    let definition_names = definitions
+        .keys()
+        .map(|name| (name.to_string(), names.unique(type_name(name))))
+        .collect::<HashMap<_, _>>();
+    let root_name = names.unique(type_name(root_name));
+
+    let mut generator = Generator {
+        id: value.get(ID_KEY).and_then(Value::as_str),
+        definitions: &definitions,
+        names: definition_names,
+        declared: HashSet::new(),
+        visiting: HashSet::new(),
+        blocks: vec![],
+    };
+
+    for name in definitions.keys() {
+        generator.declare(name);
+    }
+
+    let has_schema = value.as_object().is_some_and(|fields| {
+        fields
+            .keys()
+            .any(|key| ![ID_KEY, SCHEMA_KEY, DEFS_KEY, COMMENT_KEY].contains(&key.as_str()))
+    });
+
+    if has_schema {
+        let expression = generator.render(value, 0);
+        generator
+            .blocks
+            .push(declaration(&root_name, value, &expression));
+    }
+
+    let mut result = "// Code generated by json-schema-tools. DO NOT EDIT.\n\n".to_string();
+    result.push_str("import { z } from \"zod\";\n");
+
+    for block in generator.blocks {
+        result.push('\n');
+        result.push_str(&block);
+    }
+
+    result
+}
+
+struct Generator<'a> {
+    /// The `$id` of the schema, for recognizing local references with a path
+    id: Option<&'a str>,
+    definitions: &'a Map<String, Value>,
+    /// Constant names for definitions
+    names: HashMap<String, String>,
+    declared: HashSet<String>,
+    /// Definitions whose declarations are being rendered
+    visiting: HashSet<String>,
+    blocks: Vec<String>,
+}
+
+impl Generator<'_> {
+    /// Declare a definition, after any definitions it refers to
+    fn declare(&mut self, name: &str) {
+        if self.declared.contains(name) || self.visiting.contains(name) {
+            return;
+        }
+
+        if let Some(schema) = self.definitions.get(name) {
+            self.visiting.insert(name.to_string());
+            let expression = self.render(schema, 0);
+            self.visiting.remove(name);
+
+            self.declared.insert(name.to_string());
+            self.blocks
+                .push(declaration(&self.names[name], schema, &expression));
+        }
+    }
+
+    /// A Zod expression, where continuation lines are indented by the given number of levels
+    fn render(&mut self, schema: &Value, indent: usize) -> String {
+        let fields = match schema {
+            Value::Object(fields) => fields,
+            Value::Bool(false) => return "z.never()".to_string(),
+            _ => return "z.unknown()".to_string(),
+        };
+
+        if let Some(reference) = fields.get(REF_KEY).and_then(Value::as_str) {
+            match self.local_definition(reference) {
+                Some(definition) => {
+                    self.declare(&definition);
+
+                    let name = &self.names[&definition];
+
+                    if self.declared.contains(&definition) {
+                        name.clone()
+                    } else {
+                        format!("z.lazy(() => {})", name)
+                    }
+                }
+                None => "z.unknown()".to_string(),
+            }
+        } else if let Some(value) = fields.get(CONST_KEY) {
+            literal(value)
+        } else if let Some(Value::Array(values)) = fields.get(ENUM_KEY) {
+            let strings = values.iter().filter_map(Value::as_str).collect::<Vec<_>>();
+
+            if !strings.is_empty() && strings.len() == values.len() {
+                format!(
+                    "z.enum([{}])",
+                    strings
+                        .iter()
+                        .map(|value| Value::from(*value).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            } else {
+                union(values.iter().map(literal).collect(), indent)
+            }
+        } else if let Some(Value::Array(members)) =
+            fields.get(ONE_OF_KEY).or_else(|| fields.get(ANY_OF_KEY))
+        {
+            let is_null = |member: &&Value| member.get(TYPE_KEY) == Some(&Value::from("null"));
+            let non_null = members
+                .iter()
+                .filter(|member| !is_null(member))
+                .collect::<Vec<_>>();
+            let expressions = non_null
+                .iter()
+                .map(|member| self.render(member, indent + 1))
+                .collect::<Vec<_>>();
+
+            match expressions.as_slice() {
+                [] => "z.null()".to_string(),
+                [expression] if non_null.len() < members.len() => {
+                    format!("{}.nullable()", expression)
+                }
+                [expression] => expression.clone(),
+                _ if non_null.len() < members.len() => {
+                    format!("{}.nullable()", union(expressions, indent))
+                }
+                _ => union(expressions, indent),
+            }
+        } else if let Some(Value::Array(members)) = fields.get(ALL_OF_KEY) {
+            let expressions = members
+                .iter()
+                .map(|member| self.render(member, indent))
+                .collect::<Vec<_>>();
+
+            match expressions.split_first() {
+                Some((first, rest)) => rest.iter().fold(first.clone(), |expression, member| {
+                    format!("{}.and({})", expression, member)
+                }),
+                None => "z.unknown()".to_string(),
+            }
+        } else {
+            let types = match fields.get(TYPE_KEY) {
+                Some(Value::String(type_name)) => vec![type_name.as_str()],
+                Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+                _ if fields.contains_key(PROPERTIES_KEY) => vec!["object"],
+                _ => vec![],
+            };
+            let nullable = types.len() > 1 && types.contains(&"null");
+            let expressions = types
+                .iter()
+                .filter(|type_name| !nullable || **type_name != "null")
+                .map(|type_name| self.typed(type_name, fields, indent))
+                .collect::<Vec<_>>();
+
+            let expression = match expressions.as_slice() {
+                [] => "z.unknown()".to_string(),
+                [expression] => expression.clone(),
+                _ => union(expressions, indent),
+            };
+
+            if nullable {
+                format!("{}.nullable()", expression)
+            } else {
+                expression
+            }
+        }
+    }
+
+    /// A Zod expression for a schema with a single type
+    fn typed(&mut self, type_name: &str, fields: &Map<String, Value>, indent: usize) -> String {
+        let number = |key| fields.get(key).filter(|value| value.is_number());
+
+        match type_name {
+            "null" => "z.null()".to_string(),
+            "boolean" => "z.boolean()".to_string(),
+            "string" => {
+                let mut expression = "z.string()".to_string();
+
+                if let Some(length) = number(MIN_LENGTH_KEY) {
+                    expression.push_str(&format!(".min({})", length));
+                }
+
+                if let Some(length) = number(MAX_LENGTH_KEY) {
+                    expression.push_str(&format!(".max({})", length));
+                }
+
+                if let Some(pattern) = fields.get(PATTERN_KEY).and_then(Value::as_str) {
+                    expression.push_str(&format!(".regex({})", regex_literal(pattern)));
+                }
+
+                if let Some((_, method)) = fields
+                    .get(FORMAT_KEY)
+                    .and_then(Value::as_str)
+                    .and_then(|format| FORMATS.iter().find(|(name, _)| *name == format))
+                {
+                    expression.push_str(method);
+                }
+
+                expression
+            }
+            "integer" | "number" => {
+                let mut expression = "z.number()".to_string();
+
+                if type_name == "integer" {
+                    expression.push_str(".int()");
+                }
+
+                for (key, method) in [
+                    (MINIMUM_KEY, "gte"),
+                    (EXCLUSIVE_MINIMUM_KEY, "gt"),
+                    (MAXIMUM_KEY, "lte"),
+                    (EXCLUSIVE_MAXIMUM_KEY, "lt"),
+                    (MULTIPLE_OF_KEY, "multipleOf"),
+                ] {
+                    if let Some(value) = number(key) {
+                        expression.push_str(&format!(".{}({})", method, value));
+                    }
+                }
+
+                expression
+            }
+            "array" => {
+                let items = fields.get(ITEMS_KEY);
+
+                match fields.get(PREFIX_ITEMS_KEY).and_then(Value::as_array) {
+                    Some(prefix_items) => {
+                        let prefix = prefix_items
+                            .iter()
+                            .map(|schema| self.render(schema, indent + 1))
+                            .collect::<Vec<_>>();
+                        let mut expression = format!("z.tuple({})", list(&prefix, indent));
+
+                        match items {
+                            Some(Value::Bool(false)) => {}
+                            Some(items) => {
+                                expression
+                                    .push_str(&format!(".rest({})", self.render(items, indent)));
+                            }
+                            None => expression.push_str(".rest(z.unknown())"),
+                        }
+
+                        expression
+                    }
+                    None => {
+                        let items = items.map_or_else(
+                            || "z.unknown()".to_string(),
+                            |items| self.render(items, indent),
+                        );
+                        let mut expression = format!("z.array({})", items);
+
+                        if let Some(count) = number(MIN_ITEMS_KEY) {
+                            expression.push_str(&format!(".min({})", count));
+                        }
+
+                        if let Some(count) = number(MAX_ITEMS_KEY) {
+                            expression.push_str(&format!(".max({})", count));
+                        }
+
+                        expression
+                    }
+                }
+            }
+            "object" => {
+                let additional = fields.get(ADDITIONAL_PROPERTIES_KEY);
+
+                match fields.get(PROPERTIES_KEY).and_then(Value::as_object) {
+                    Some(properties) => {
+                        let required = fields
+                            .get(REQUIRED_KEY)
+                            .and_then(Value::as_array)
+                            .map(|names| names.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+                            .unwrap_or_default();
+
+                        let mut expression = "z.object({\n".to_string();
+
+                        for (name, schema) in properties {
+                            let optional = if required.contains(&name.as_str()) {
+                                ""
+                            } else {
+                                ".optional()"
+                            };
+
+                            expression.push_str(&format!(
+                                "{}{}: {}{},\n",
+                                spaces(indent + 1),
+                                property_key(name),
+                                self.render(schema, indent + 1),
+                                optional
+                            ));
+                        }
+
+                        expression.push_str(&spaces(indent));
+                        expression.push_str("})");
+
+                        match additional {
+                            Some(Value::Bool(false)) => expression.push_str(".strict()"),
+                            Some(values @ Value::Object(_)) => {
+                                let values = self.render(values, indent);
+                                expression.push_str(&format!(".catchall({})", values));
+                            }
+                            _ => {}
+                        }
+
+                        expression
+                    }
+                    None => match additional {
+                        Some(Value::Bool(false)) => "z.object({}).strict()".to_string(),
+                        Some(values @ Value::Object(_)) => {
+                            format!("z.record(z.string(), {})", self.render(values, indent))
+                        }
+                        _ => "z.record(z.string(), z.unknown())".to_string(),
+                    },
+                }
+            }
+            _ => "z.unknown()".to_string(),
+        }
+    }
+
+    /// The name of the definition a reference points to, if it's in this schema
+    fn local_definition(&self, reference: &str) -> Option<String> {
+        let (path, fragment) = reference.split_once('#')?;
+        let name = fragment.strip_prefix(&format!("/{}/", DEFS_KEY))?;
+
+        if (path.is_empty() || Some(path) == self.id) && self.definitions.contains_key(name) {
+            Some(name.to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// An exported constant and type for a schema
+fn declaration(name: &str, schema: &Value, expression: &str) -> String {
+    let comment = schema
+        .get(DESCRIPTION_KEY)
+        .and_then(Value::as_str)
+        .map(|description| {
+            if description.contains('\n') {
+                let lines = description
+                    .lines()
+                    .map(|line| format!(" * {}", line).trim_end().to_string() + "\n")
+                    .collect::<String>();
+
+                format!("/**\n{} */\n", lines)
+            } else {
+                format!("/** {} */\n", description)
+            }
+        })
+        .unwrap_or_default();
+
+    format!(
+        "{}export const {} = {};\nexport type {} = z.infer<typeof {}>;\n",
+        comment, name, expression, name, name
+    )
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => "z.null()".to_string(),
+        Value::Bool(_) | Value::Number(_) | Value::String(_) => format!("z.literal({})", value),
+        _ => "z.unknown()".to_string(),
+    }
+}
+
+fn union(expressions: Vec<String>, indent: usize) -> String {
+    match expressions.as_slice() {
+        [] => "z.never()".to_string(),
+        [expression] => expression.clone(),
+        _ => format!("z.union({})", list(&expressions, indent)),
+    }
+}
+
+/// An array of expressions on separate lines
+fn list(expressions: &[String], indent: usize) -> String {
+    if expressions.is_empty() {
+        "[]".to_string()
+    } else {
+        let items = expressions
+            .iter()
+            .map(|expression| format!("{}{},\n", spaces(indent + 1), expression))
+            .collect::<String>();
+
+        format!("[\n{}{}]", items, spaces(indent))
+    }
+}
+
+/// A JavaScript regular expression literal
+fn regex_literal(pattern: &str) -> String {
+    let mut literal = "/".to_string();
+    let mut escaped = false;
+
+    for c in pattern.chars() {
+        if c == '/' && !escaped {
+            literal.push('\\');
+        }
+
+        literal.push(c);
+        escaped = c == '\\' && !escaped;
+    }
+
+    literal.push('/');
+    literal
+}
+
+/// A property name, quoted unless it's an identifier
+fn property_key(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        name.to_string()
+    } else {
+        Value::from(name).to_string()
+    }
+}
+
+/// A name for a constant and type, which can't start with a digit
+fn type_name(name: &str) -> String {
+    let name = pascal_case(name);
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+fn spaces(indent: usize) -> String {
+    "  ".repeat(indent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zod_validators() {
+        let schema = serde_json::json!({
+            "title": "Order",
+            "description": "An order",
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "sku": { "type": "string", "pattern": "^[A-Z]+/[0-9]+$", "minLength": 3 },
+                "status": { "$ref": "#/$defs/status" },
+                "placed": { "type": "string", "format": "date-time" },
+                "total": { "type": "number", "exclusiveMaximum": 1000 },
+                "lines": { "type": "array", "items": { "$ref": "#/$defs/line" }, "maxItems": 10 },
+                "note": { "type": ["string", "null"] },
+                "content-type": { "const": "order" }
+            },
+            "required": ["id", "status", "note"],
+            "additionalProperties": false,
+            "$defs": {
+                "line": {
+                    "type": "object",
+                    "properties": {
+                        "sku": { "type": "string" },
+                        "children": { "type": "array", "items": { "$ref": "#/$defs/line" } },
+                        "status": { "$ref": "#/$defs/status" }
+                    },
+                    "required": ["sku"]
+                },
+                "status": { "enum": ["open", "closed"] }
+            }
+        });
+
+        assert_eq!(
+            zod(&schema, "ignored"),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+import { z } from "zod";
+
+export const Status = z.enum(["open", "closed"]);
+export type Status = z.infer<typeof Status>;
+
+export const Line = z.object({
+  sku: z.string(),
+  children: z.array(z.lazy(() => Line)).optional(),
+  status: Status.optional(),
+});
+export type Line = z.infer<typeof Line>;
+
+/** An order */
+export const Order = z.object({
+  id: z.number().int().gte(1),
+  sku: z.string().min(3).regex(/^[A-Z]+\/[0-9]+$/).optional(),
+  status: Status,
+  placed: z.string().datetime({ offset: true }).optional(),
+  total: z.number().lt(1000).optional(),
+  lines: z.array(Line).max(10).optional(),
+  note: z.string().nullable(),
+  "content-type": z.literal("order").optional(),
+}).strict();
+export type Order = z.infer<typeof Order>;
+"#
+        );
+    }
+
+    #[test]
+    fn zod_edge_cases() {
+        let node = serde_json::json!({
+            "title": "Node",
+            "type": "object",
+            "properties": {
+                "label": { "type": "string", "pattern": "^[a-z]+$" },
+                "children": { "type": "array", "items": { "$ref": "#/$defs/node" }, "minItems": 1 }
+            },
+            "required": ["label"],
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer", "minimum": 0, "maximum": 10 },
+                        "parent": { "$ref": "#/$defs/node" }
+                    }
+                }
+            }
+        });
+        let tags = serde_json::json!({
+            "type": "array",
+            "items": { "type": "string", "pattern": "^[a-z]+$" },
+            "maxItems": 3
+        });
+
+        assert_eq!(
+            zod(&node, "tags"),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+import { z } from "zod";
+
+export const Node = z.object({
+  value: z.number().int().gte(0).lte(10).optional(),
+  parent: z.lazy(() => Node).optional(),
+});
+export type Node = z.infer<typeof Node>;
+
+export const Node2 = z.object({
+  label: z.string().regex(/^[a-z]+$/),
+  children: z.array(Node).min(1).optional(),
+});
+export type Node2 = z.infer<typeof Node2>;
+"#
+        );
+
+        assert_eq!(
+            zod(&tags, "tags"),
+            r#"// Code generated by json-schema-tools. DO NOT EDIT.
+
+import { z } from "zod";
+
+export const Tags = z.array(z.string().regex(/^[a-z]+$/)).max(3);
+export type Tags = z.infer<typeof Tags>;
+"#
+        );
+    }
+}
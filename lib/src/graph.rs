@@ -0,0 +1,637 @@
+use super::{constants::*, reference::Reference};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// A top-level schema or one of its definitions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Node {
+    /// Index of the file in the input
+    pub file: usize,
+    pub def: Option<String>,
+}
+
+/// A reference from one node to another, or to a location that doesn't exist
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Target {
+    Node(usize),
+    Dangling(String),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: Target,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderOptions {
+    /// Group the nodes for each file
+    pub cluster_by_file: bool,
+    pub highlight_cycles: bool,
+    pub highlight_dangling: bool,
+}
+
+/// The `$ref` dependency graph of a collection of schema files
+///
+/// Files are identified by their `$id` (or the given name if they don't have one), and references
+/// between files are resolved by `$id`.
+#[derive(Clone, Debug)]
+pub struct RefGraph {
+    pub files: Vec<String>,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl RefGraph {
+    pub fn new(schemas: &[(&str, &Value)]) -> Self {
+        let files = schemas
+            .iter()
+            .map(|(name, value)| {
+                value
+                    .get(ID_KEY)
+                    .and_then(Value::as_str)
+                    .unwrap_or(name)
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        let mut nodes = vec![];
+        let mut indices = HashMap::new();
+
+        for (file, (_, value)) in schemas.iter().enumerate() {
+            indices.insert((file, None), nodes.len());
+            nodes.push(Node { file, def: None });
+
+            for name in value
+                .get(DEFS_KEY)
+                .and_then(Value::as_object)
+                .into_iter()
+                .flat_map(|defs| defs.keys())
+            {
+                indices.insert((file, Some(name.clone())), nodes.len());
+                nodes.push(Node {
+                    file,
+                    def: Some(name.clone()),
+                });
+            }
+        }
+
+        let file_indices = files
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.as_str(), index))
+            .collect::<HashMap<_, _>>();
+
+        let mut edges = vec![];
+
+        for (file, (_, value)) in schemas.iter().enumerate() {
+            let mut sources = vec![(None, *value)];
+
+            if let Some(defs) = value.get(DEFS_KEY).and_then(Value::as_object) {
+                sources.extend(defs.iter().map(|(name, def)| (Some(name.clone()), def)));
+            }
+
+            for (def, source) in sources {
+                let from = indices[&(file, def.clone())];
+                let mut references = vec![];
+
+                match (&def, source.as_object()) {
+                    (None, Some(fields)) => {
+                        for (key, value) in fields {
+                            if key != DEFS_KEY {
+                                collect_references(value, &mut references);
+                            }
+                        }
+                    }
+                    _ => collect_references(source, &mut references),
+                }
+
+                for reference in references {
+                    let target = reference
+                        .parse::<Reference>()
+                        .ok()
+                        .and_then(|parsed| {
+                            let target_file = match parsed.path() {
+                                Some(path) => *file_indices.get(path.as_str())?,
+                                None => file,
+                            };
+                            let target_def = match parsed {
+                                Reference::PathOnly { .. } => None,
+                                _ => Some(parsed.name().to_string()),
+                            };
+
+                            indices.get(&(target_file, target_def)).copied()
+                        })
+                        .map_or_else(|| Target::Dangling(reference.to_string()), Target::Node);
+
+                    let edge = Edge { from, to: target };
+
+                    if !edges.contains(&edge) {
+                        edges.push(edge);
+                    }
+                }
+            }
+        }
+
+        // Top-level schemas that only hold definitions aren't interesting.
+        let mut graph = Self {
+            files,
+            nodes,
+            edges,
+        };
+        graph.retain_nodes(|graph, index| {
+            let node = &graph.nodes[index];
+
+            node.def.is_some()
+                || schemas[node.file].1.as_object().is_some_and(|fields| {
+                    fields.keys().any(|key| {
+                        ![ID_KEY, SCHEMA_KEY, DEFS_KEY, COMMENT_KEY].contains(&key.as_str())
+                    })
+                })
+                || graph
+                    .edges
+                    .iter()
+                    .any(|edge| edge.to == Target::Node(index))
+        });
+
+        graph
+    }
+
+    fn retain_nodes<F: Fn(&Self, usize) -> bool>(&mut self, f: F) {
+        let keep = (0..self.nodes.len())
+            .map(|index| f(self, index))
+            .collect::<Vec<_>>();
+        let mut mapping = vec![None; self.nodes.len()];
+        let mut next = 0;
+
+        for (index, keep) in keep.iter().enumerate() {
+            if *keep {
+                mapping[index] = Some(next);
+                next += 1;
+            }
+        }
+
+        let mut index = 0;
+        self.nodes.retain(|_| {
+            index += 1;
+            keep[index - 1]
+        });
+
+        self.edges = std::mem::take(&mut self.edges)
+            .into_iter()
+            .filter_map(|edge| {
+                let from = mapping[edge.from]?;
+                let to = match edge.to {
+                    Target::Node(to) => Target::Node(mapping[to]?),
+                    dangling => dangling,
+                };
+
+                Some(Edge { from, to })
+            })
+            .collect();
+    }
+
+    pub fn label(&self, index: usize) -> &str {
+        let node = &self.nodes[index];
+
+        node.def.as_deref().unwrap_or(&self.files[node.file])
+    }
+
+    /// Groups of nodes that reference each other, including nodes that reference themselves
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            index: 0,
+            indices: vec![None; self.nodes.len()],
+            low_links: vec![0; self.nodes.len()],
+            stack: vec![],
+            on_stack: vec![false; self.nodes.len()],
+            components: vec![],
+        };
+
+        for node in 0..self.nodes.len() {
+            if tarjan.indices[node].is_none() {
+                tarjan.visit(node);
+            }
+        }
+
+        let mut components = tarjan
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || self.edges.contains(&Edge {
+                        from: component[0],
+                        to: Target::Node(component[0]),
+                    })
+            })
+            .map(|mut component| {
+                component.sort_unstable();
+                component
+            })
+            .collect::<Vec<_>>();
+        components.sort();
+        components
+    }
+
+    pub fn dangling(&self) -> Vec<(usize, &str)> {
+        self.edges
+            .iter()
+            .filter_map(|edge| match &edge.to {
+                Target::Dangling(reference) => Some((edge.from, reference.as_str())),
+                Target::Node(_) => None,
+            })
+            .collect()
+    }
+
+    /// Component index of each node that is part of a cycle
+    fn cycle_membership(&self) -> HashMap<usize, usize> {
+        self.cycles()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(component, nodes)| nodes.into_iter().map(move |node| (node, component)))
+            .collect()
+    }
+
+    fn is_cycle_edge(membership: &HashMap<usize, usize>, edge: &Edge) -> bool {
+        match edge.to {
+            Target::Node(to) => membership
+                .get(&edge.from)
+                .is_some_and(|component| membership.get(&to) == Some(component)),
+            Target::Dangling(_) => false,
+        }
+    }
+
+    /// Render as a Graphviz DOT digraph
+    pub fn to_dot(&self, options: RenderOptions) -> String {
+        let membership = self.cycle_membership();
+        let mut output = String::from("digraph refs {\n    rankdir=LR;\n");
+
+        let node_line = |output: &mut String, index: usize, indent: &str| {
+            let highlight = if options.highlight_cycles && membership.contains_key(&index) {
+                ", color=red"
+            } else {
+                ""
+            };
+
+            let _ = writeln!(
+                output,
+                "{}n{} [label={}{}];",
+                indent,
+                index,
+                quote(self.label(index)),
+                highlight
+            );
+        };
+
+        if options.cluster_by_file {
+            for (file, name) in self.files.iter().enumerate() {
+                let members = (0..self.nodes.len())
+                    .filter(|index| self.nodes[*index].file == file)
+                    .collect::<Vec<_>>();
+
+                if !members.is_empty() {
+                    let _ = writeln!(output, "    subgraph cluster_{} {{", file);
+                    let _ = writeln!(output, "        label={};", quote(name));
+
+                    for index in members {
+                        node_line(&mut output, index, "        ");
+                    }
+
+                    output.push_str("    }\n");
+                }
+            }
+        } else {
+            for index in 0..self.nodes.len() {
+                node_line(&mut output, index, "    ");
+            }
+        }
+
+        for (index, (_, reference)) in self.dangling().into_iter().enumerate() {
+            let style = if options.highlight_dangling {
+                ", style=dashed, color=red"
+            } else {
+                ""
+            };
+
+            let _ = writeln!(
+                output,
+                "    d{} [label={}, shape=box{}];",
+                index,
+                quote(reference),
+                style
+            );
+        }
+
+        let mut dangling_index = 0;
+
+        for edge in &self.edges {
+            match edge.to {
+                Target::Node(to) => {
+                    let highlight =
+                        if options.highlight_cycles && Self::is_cycle_edge(&membership, edge) {
+                            " [color=red]"
+                        } else {
+                            ""
+                        };
+
+                    let _ = writeln!(output, "    n{} -> n{}{};", edge.from, to, highlight);
+                }
+                Target::Dangling(_) => {
+                    let highlight = if options.highlight_dangling {
+                        " [style=dashed, color=red]"
+                    } else {
+                        ""
+                    };
+
+                    let _ = writeln!(
+                        output,
+                        "    n{} -> d{}{};",
+                        edge.from, dangling_index, highlight
+                    );
+                    dangling_index += 1;
+                }
+            }
+        }
+
+        output.push_str("}\n");
+        output
+    }
+
+    /// Render as a Mermaid flowchart
+    pub fn to_mermaid(&self, options: RenderOptions) -> String {
+        let membership = self.cycle_membership();
+        let mut output = String::from("flowchart LR\n");
+
+        let node_line = |output: &mut String, index: usize, indent: &str| {
+            let _ = writeln!(output, "{}n{}[{}]", indent, index, quote(self.label(index)));
+        };
+
+        if options.cluster_by_file {
+            for (file, name) in self.files.iter().enumerate() {
+                let members = (0..self.nodes.len())
+                    .filter(|index| self.nodes[*index].file == file)
+                    .collect::<Vec<_>>();
+
+                if !members.is_empty() {
+                    let _ = writeln!(output, "    subgraph f{} [{}]", file, quote(name));
+
+                    for index in members {
+                        node_line(&mut output, index, "        ");
+                    }
+
+                    output.push_str("    end\n");
+                }
+            }
+        } else {
+            for index in 0..self.nodes.len() {
+                node_line(&mut output, index, "    ");
+            }
+        }
+
+        let dangling = self.dangling();
+
+        for (index, (_, reference)) in dangling.iter().enumerate() {
+            let _ = writeln!(output, "    d{}[{}]", index, quote(reference));
+        }
+
+        let mut dangling_index = 0;
+        let mut highlighted_links = vec![];
+
+        for (link, edge) in self.edges.iter().enumerate() {
+            match edge.to {
+                Target::Node(to) => {
+                    if options.highlight_cycles && Self::is_cycle_edge(&membership, edge) {
+                        highlighted_links.push(link);
+                    }
+
+                    let _ = writeln!(output, "    n{} --> n{}", edge.from, to);
+                }
+                Target::Dangling(_) => {
+                    if options.highlight_dangling {
+                        highlighted_links.push(link);
+                    }
+
+                    let _ = writeln!(output, "    n{} -.-> d{}", edge.from, dangling_index);
+                    dangling_index += 1;
+                }
+            }
+        }
+
+        if options.highlight_cycles {
+            let mut cycle_nodes = membership.keys().copied().collect::<Vec<_>>();
+            cycle_nodes.sort_unstable();
+
+            if !cycle_nodes.is_empty() {
+                output.push_str("    classDef cycle stroke:red\n");
+                let _ = writeln!(
+                    output,
+                    "    class {} cycle",
+                    cycle_nodes
+                        .iter()
+                        .map(|index| format!("n{}", index))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                );
+            }
+        }
+
+        if options.highlight_dangling && !dangling.is_empty() {
+            output.push_str("    classDef dangling stroke:red,stroke-dasharray:5 5\n");
+            let _ = writeln!(
+                output,
+                "    class {} dangling",
+                (0..dangling.len())
+                    .map(|index| format!("d{}", index))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+        }
+
+        if !highlighted_links.is_empty() {
+            let _ = writeln!(
+                output,
+                "    linkStyle {} stroke:red",
+                highlighted_links
+                    .iter()
+                    .map(|link| link.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+        }
+
+        output
+    }
+}
+
+struct Tarjan<'a> {
+    graph: &'a RefGraph,
+    index: usize,
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.indices[node] = Some(self.index);
+        self.low_links[node] = self.index;
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        let successors = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == node)
+            .filter_map(|edge| match edge.to {
+                Target::Node(to) => Some(to),
+                Target::Dangling(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        for successor in successors {
+            match self.indices[successor] {
+                None => {
+                    self.visit(successor);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[successor]);
+                }
+                Some(index) if self.on_stack[successor] => {
+                    self.low_links[node] = self.low_links[node].min(index);
+                }
+                _ => {}
+            }
+        }
+
+        if Some(self.low_links[node]) == self.indices[node] {
+            let mut component = vec![];
+
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+
+                if member == node {
+                    break;
+                }
+            }
+
+            self.components.push(component);
+        }
+    }
+}
+
+fn collect_references<'a>(value: &'a Value, acc: &mut Vec<&'a str>) {
+    if let Some(values) = value.as_array() {
+        for value in values {
+            collect_references(value, acc);
+        }
+    } else if let Some(fields) = value.as_object() {
+        if let Some(reference) = fields.get(REF_KEY).and_then(Value::as_str) {
+            acc.push(reference);
+        }
+
+        for value in fields.values() {
+            collect_references(value, acc);
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_cycles_and_dangling() {
+        let foo = serde_json::json!({
+            "$id": "/schemas/foo",
+            "$defs": {
+                "a": { "$ref": "#/$defs/b" },
+                "b": { "type": "array", "items": { "$ref": "#/$defs/a" } },
+                "c": { "$ref": "/schemas/bar#/$defs/d" }
+            }
+        });
+        let bar = serde_json::json!({
+            "$id": "/schemas/bar",
+            "type": "object",
+            "properties": {
+                "x": { "$ref": "/schemas/foo#/$defs/c" },
+                "y": { "$ref": "#/$defs/missing" }
+            },
+            "$defs": {
+                "d": { "$ref": "#/$defs/d" }
+            }
+        });
+
+        let graph = RefGraph::new(&[("foo.json", &foo), ("bar.json", &bar)]);
+
+        assert_eq!(
+            (0..graph.nodes.len())
+                .map(|index| graph.label(index))
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c", "/schemas/bar", "d"]
+        );
+        assert_eq!(graph.cycles(), vec![vec![0, 1], vec![4]]);
+        assert_eq!(graph.dangling(), vec![(3, "#/$defs/missing")]);
+        assert_eq!(graph.edges.iter().filter(|edge| edge.from == 3).count(), 2);
+
+        let dot = graph.to_dot(RenderOptions {
+            cluster_by_file: true,
+            highlight_cycles: true,
+            highlight_dangling: true,
+        });
+
+        assert!(dot.contains("    subgraph cluster_1 {\n        label=\"/schemas/bar\";\n"));
+        assert!(dot.contains("    n0 -> n1 [color=red];\n"));
+        assert!(dot.contains("    n2 -> n4;\n"));
+        assert!(dot.contains("    n3 -> d0 [style=dashed, color=red];\n"));
+    }
+
+    #[test]
+    fn graph_edge_cases() {
+        assert!(RefGraph::new(&[]).nodes.is_empty());
+
+        let a = serde_json::json!({
+            "$defs": {
+                "x": { "anyOf": [{ "$ref": "/schemas/b" }, { "$ref": "/schemas/b" }] },
+                "y": { "$ref": "https://example.com/z" }
+            }
+        });
+        let b = serde_json::json!({
+            "$id": "/schemas/b",
+            "$defs": { "only": { "type": "string" } }
+        });
+
+        let graph = RefGraph::new(&[("a.json", &a), ("b.json", &b)]);
+
+        // A top-level schema that only holds definitions is kept if something refers to it.
+        assert_eq!(graph.files, vec!["a.json", "/schemas/b"]);
+        assert_eq!(
+            (0..graph.nodes.len())
+                .map(|index| graph.label(index))
+                .collect::<Vec<_>>(),
+            vec!["x", "y", "/schemas/b", "only"]
+        );
+        assert_eq!(
+            graph.edges,
+            vec![
+                Edge {
+                    from: 0,
+                    to: Target::Node(2)
+                },
+                Edge {
+                    from: 1,
+                    to: Target::Dangling("https://example.com/z".to_string())
+                }
+            ]
+        );
+        assert!(graph.cycles().is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use serde_json::Value;
+
+mod avro;
+mod jtd;
+mod openapi;
+mod protobuf;
+mod typescript;
+
+pub use avro::from_avro;
+pub use jtd::from_jtd;
+pub use openapi::from_openapi;
+pub use protobuf::from_descriptor_set;
+pub use typescript::from_typescript;
+
+pub(crate) use openapi::{convert_schema, is_legacy};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid Avro schema")]
+    InvalidAvro(Value),
+    #[error("Unknown Avro type")]
+    UnknownAvroType(String),
+    #[error("Invalid JTD schema")]
+    InvalidJtd(Value),
+    #[error("Unknown JTD type")]
+    UnknownJtdType(String),
+    #[error("Invalid protobuf descriptor set")]
+    InvalidDescriptor,
+    #[error("Unsupported OpenAPI version")]
+    UnsupportedOpenApiVersion(Option<String>),
+    #[error("No component schemas in OpenAPI document")]
+    MissingComponents,
+    #[error("Invalid TypeScript at line {0}")]
+    InvalidTypeScript(usize),
+    #[error("Unsupported TypeScript at line {0}: {1}")]
+    UnsupportedTypeScript(usize, String),
+    #[error("Unknown TypeScript type at line {0}: {1}")]
+    UnknownTypeScriptType(usize, String),
+}
+
+/// A name that can be used in references, with other characters replaced by underscores
+fn reference_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
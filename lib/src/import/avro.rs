@@ -0,0 +1,412 @@
+use super::{reference_name, Error};
+use crate::constants::*;
+use crate::schema::DRAFT_2020_12_URI;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+const PRIMITIVES: [&str; 8] = [
+    "boolean", "bytes", "double", "float", "int", "long", "null", "string",
+];
+
+/// Convert an Avro schema to a 2020-12 JSON Schema describing the same data
+///
+/// Named types are added to `$defs`, apart from a named top-level type, which is the top-level
+/// schema. The `$id` is the full name of that type as a path (so `com.example.Order` is
+/// `/com/example/Order`), or else the given name, so that other schemas can refer to the result
+/// and be composed with it. A file containing a list of types only has definitions.
+///
+/// Records become closed objects where fields without defaults are required, unions with `null`
+/// become `anyOf` with `null`, enums become string enums, maps become objects, and bytes and fixed
+/// values become strings with base64 `contentEncoding`.
+pub fn from_avro(schema: &Value, name: &str) -> Result<Value, Error> {
+    let mut importer = Importer::default();
+    let root_name = match schema {
+        Value::Object(fields) if is_named_type(schema) => Some(full_name(fields, "")?),
+        _ => None,
+    };
+
+    let id = match &root_name {
+        Some(full_name) => format!(
+            "/{}",
+            full_name
+                .split('.')
+                .map(reference_name)
+                .collect::<Vec<_>>()
+                .join("/")
+        ),
+        None => format!("/{}", reference_name(name)),
+    };
+
+    if let Some(full_name) = &root_name {
+        importer
+            .references
+            .insert(full_name.clone(), json!({ REF_KEY: id }));
+    }
+
+    let root = match schema {
+        // A list of types just declares them.
+        Value::Array(schemas) => {
+            for schema in schemas {
+                importer.convert(schema, "")?;
+            }
+
+            None
+        }
+        _ => Some(importer.convert(schema, "")?),
+    };
+
+    let mut result = Map::new();
+    result.insert(SCHEMA_KEY.to_string(), json!(DRAFT_2020_12_URI));
+    result.insert(ID_KEY.to_string(), json!(id));
+
+    // A named top-level type is converted to a reference to itself.
+    if let Some(Value::Object(fields)) = importer.root.or(root) {
+        result.extend(fields);
+    }
+
+    result.insert(DEFS_KEY.to_string(), Value::Object(importer.definitions));
+
+    Ok(Value::Object(result))
+}
+
+#[derive(Default)]
+struct Importer {
+    definitions: Map<String, Value>,
+    /// References to named types by full name
+    references: HashMap<String, Value>,
+    /// The converted top-level type, if it's named
+    root: Option<Value>,
+}
+
+impl Importer {
+    /// Convert a type in the given namespace, defining any named types
+    fn convert(&mut self, schema: &Value, namespace: &str) -> Result<Value, Error> {
+        match schema {
+            Value::String(name) => self.named_or_primitive(name, namespace),
+            Value::Array(members) => {
+                let mut result = vec![];
+                let mut nullable = false;
+
+                for member in members {
+                    if member == "null" {
+                        nullable = true;
+                    } else {
+                        result.push(self.convert(member, namespace)?);
+                    }
+                }
+
+                if nullable {
+                    result.push(json!({ TYPE_KEY: "null" }));
+                }
+
+                Ok(if result.len() == 1 {
+                    result.remove(0)
+                } else {
+                    json!({ ANY_OF_KEY: result })
+                })
+            }
+            Value::Object(fields) => {
+                let avro_type = fields
+                    .get(TYPE_KEY)
+                    .ok_or_else(|| Error::InvalidAvro(schema.clone()))?;
+
+                match avro_type.as_str() {
+                    Some("record" | "error") => self.record(schema, fields, namespace),
+                    Some("enum") => {
+                        let symbols = fields
+                            .get("symbols")
+                            .and_then(|symbols| symbols.as_array())
+                            .ok_or_else(|| Error::InvalidAvro(schema.clone()))?;
+
+                        self.define(fields, namespace, |_, _| Ok(json!({ ENUM_KEY: symbols })))
+                    }
+                    Some("fixed") => {
+                        let size = fields
+                            .get("size")
+                            .and_then(|size| size.as_u64())
+                            .ok_or_else(|| Error::InvalidAvro(schema.clone()))?;
+                        // The base64 encoding of the bytes, with padding
+                        let length = size.div_ceil(3) * 4;
+
+                        self.define(fields, namespace, |_, _| {
+                            Ok(json!({
+                                TYPE_KEY: "string",
+                                CONTENT_ENCODING_KEY: "base64",
+                                MIN_LENGTH_KEY: length,
+                                MAX_LENGTH_KEY: length,
+                            }))
+                        })
+                    }
+                    Some("array") => {
+                        let items = fields
+                            .get(ITEMS_KEY)
+                            .ok_or_else(|| Error::InvalidAvro(schema.clone()))?;
+
+                        Ok(json!({
+                            TYPE_KEY: "array",
+                            ITEMS_KEY: self.convert(items, namespace)?,
+                        }))
+                    }
+                    Some("map") => {
+                        let values = fields
+                            .get("values")
+                            .ok_or_else(|| Error::InvalidAvro(schema.clone()))?;
+
+                        Ok(json!({
+                            TYPE_KEY: "object",
+                            ADDITIONAL_PROPERTIES_KEY: self.convert(values, namespace)?,
+                        }))
+                    }
+                    // Logical types are annotations on primitives.
+                    Some(name) if PRIMITIVES.contains(&name) => {
+                        let mut result = primitive(name).unwrap_or_default();
+
+                        if name == "string" && fields.get("logicalType") == Some(&json!("uuid")) {
+                            result.insert(FORMAT_KEY.to_string(), json!("uuid"));
+                        }
+
+                        Ok(Value::Object(result))
+                    }
+                    Some(name) => self.named_or_primitive(name, namespace),
+                    None => self.convert(avro_type, namespace),
+                }
+            }
+            _ => Err(Error::InvalidAvro(schema.clone())),
+        }
+    }
+
+    fn named_or_primitive(&self, name: &str, namespace: &str) -> Result<Value, Error> {
+        if let Some(result) = primitive(name) {
+            return Ok(Value::Object(result));
+        }
+
+        let candidates = if name.contains('.') || namespace.is_empty() {
+            vec![name.to_string()]
+        } else {
+            vec![format!("{}.{}", namespace, name), name.to_string()]
+        };
+
+        candidates
+            .iter()
+            .find_map(|candidate| self.references.get(candidate))
+            .cloned()
+            .ok_or_else(|| Error::UnknownAvroType(name.to_string()))
+    }
+
+    fn record(
+        &mut self,
+        schema: &Value,
+        fields: &Map<String, Value>,
+        namespace: &str,
+    ) -> Result<Value, Error> {
+        let record_fields = fields
+            .get("fields")
+            .and_then(|fields| fields.as_array())
+            .ok_or_else(|| Error::InvalidAvro(schema.clone()))?;
+
+        self.define(fields, namespace, |importer, namespace| {
+            let mut properties = Map::new();
+            let mut required = vec![];
+
+            for field in record_fields {
+                let name = field
+                    .get("name")
+                    .and_then(|name| name.as_str())
+                    .ok_or_else(|| Error::InvalidAvro(field.clone()))?;
+                let field_type = field
+                    .get(TYPE_KEY)
+                    .ok_or_else(|| Error::InvalidAvro(field.clone()))?;
+
+                let mut property = match importer.convert(field_type, namespace)? {
+                    Value::Object(property) => property,
+                    _ => Map::new(),
+                };
+
+                if let Some(doc) = field.get("doc") {
+                    property.insert(DESCRIPTION_KEY.to_string(), doc.clone());
+                }
+
+                match field.get(DEFAULT_KEY) {
+                    Some(default) => {
+                        property.insert(DEFAULT_KEY.to_string(), default.clone());
+                    }
+                    None => required.push(json!(name)),
+                }
+
+                properties.insert(name.to_string(), Value::Object(property));
+            }
+
+            Ok(json!({
+                TYPE_KEY: "object",
+                PROPERTIES_KEY: properties,
+                REQUIRED_KEY: required,
+                ADDITIONAL_PROPERTIES_KEY: false,
+            }))
+        })
+    }
+
+    /// Define a named type, returning a reference to it
+    ///
+    /// Its name is reserved before converting the rest of the type, so that records can refer to
+    /// themselves.
+    fn define<F>(
+        &mut self,
+        fields: &Map<String, Value>,
+        namespace: &str,
+        convert: F,
+    ) -> Result<Value, Error>
+    where
+        F: FnOnce(&mut Self, &str) -> Result<Value, Error>,
+    {
+        let full_name = full_name(fields, namespace)?;
+        let (namespace, name) = full_name.rsplit_once('.').unwrap_or(("", &full_name));
+
+        let is_root = self.references.contains_key(&full_name) && self.root.is_none();
+        let key = if is_root {
+            None
+        } else {
+            let key = reference_name(name);
+            let key = if self.definitions.contains_key(&key) {
+                reference_name(&full_name)
+            } else {
+                key
+            };
+
+            let reference = json!({ REF_KEY: format!("#/{}/{}", DEFS_KEY, key) });
+            self.references.insert(full_name.clone(), reference);
+            // Reserve the key until the definition is complete.
+            self.definitions.insert(key.clone(), Value::Null);
+
+            Some(key)
+        };
+
+        let mut result = Map::new();
+        result.insert(TITLE_KEY.to_string(), json!(name));
+
+        if let Some(doc) = fields.get("doc") {
+            result.insert(DESCRIPTION_KEY.to_string(), doc.clone());
+        }
+
+        if let Value::Object(converted) = convert(self, namespace)? {
+            result.extend(converted);
+        }
+
+        match key {
+            Some(key) => {
+                self.definitions.insert(key, Value::Object(result));
+            }
+            None => {
+                self.root = Some(Value::Object(result));
+            }
+        }
+
+        Ok(self.references[&full_name].clone())
+    }
+}
+
+fn is_named_type(schema: &Value) -> bool {
+    matches!(
+        schema.get(TYPE_KEY).and_then(|value| value.as_str()),
+        Some("record" | "error" | "enum" | "fixed")
+    )
+}
+
+/// The full name of a named type, using the enclosing namespace if it doesn't have its own
+fn full_name(fields: &Map<String, Value>, namespace: &str) -> Result<String, Error> {
+    let name = fields
+        .get("name")
+        .and_then(|name| name.as_str())
+        .ok_or_else(|| Error::InvalidAvro(Value::Object(fields.clone())))?;
+
+    let namespace = fields
+        .get("namespace")
+        .and_then(|namespace| namespace.as_str())
+        .unwrap_or(namespace);
+
+    Ok(if name.contains('.') || namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", namespace, name)
+    })
+}
+
+fn primitive(name: &str) -> Option<Map<String, Value>> {
+    let result = match name {
+        "null" => json!({ TYPE_KEY: "null" }),
+        "boolean" => json!({ TYPE_KEY: "boolean" }),
+        "int" => json!({ TYPE_KEY: "integer", MINIMUM_KEY: i32::MIN, MAXIMUM_KEY: i32::MAX }),
+        "long" => json!({ TYPE_KEY: "integer" }),
+        "float" | "double" => json!({ TYPE_KEY: "number" }),
+        "bytes" => json!({ TYPE_KEY: "string", CONTENT_ENCODING_KEY: "base64" }),
+        "string" => json!({ TYPE_KEY: "string" }),
+        _ => return None,
+    };
+
+    match result {
+        Value::Object(fields) => Some(fields),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avro_import() {
+        let schema = json!({
+            "type": "record",
+            "name": "Order",
+            "namespace": "com.example",
+            "doc": "An order",
+            "fields": [
+                { "name": "id", "type": "long", "doc": "The ID" },
+                {
+                    "name": "status",
+                    "type": { "type": "enum", "name": "Status", "symbols": ["OPEN", "CLOSED"] }
+                },
+                { "name": "previous", "type": ["null", "Status"], "default": null },
+                { "name": "hash", "type": { "type": "fixed", "name": "Hash", "size": 16 } },
+                { "name": "tags", "type": { "type": "map", "values": "string" } },
+                { "name": "parent", "type": ["null", "Order"], "default": null }
+            ]
+        });
+
+        assert_eq!(
+            from_avro(&schema, "ignored").unwrap(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "/com/example/Order",
+                "title": "Order",
+                "description": "An order",
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer", "description": "The ID" },
+                    "status": { "$ref": "#/$defs/Status" },
+                    "previous": {
+                        "anyOf": [{ "$ref": "#/$defs/Status" }, { "type": "null" }],
+                        "default": null
+                    },
+                    "hash": { "$ref": "#/$defs/Hash" },
+                    "tags": { "type": "object", "additionalProperties": { "type": "string" } },
+                    "parent": {
+                        "anyOf": [{ "$ref": "/com/example/Order" }, { "type": "null" }],
+                        "default": null
+                    }
+                },
+                "required": ["id", "status", "hash", "tags"],
+                "additionalProperties": false,
+                "$defs": {
+                    "Status": { "title": "Status", "enum": ["OPEN", "CLOSED"] },
+                    "Hash": {
+                        "title": "Hash",
+                        "type": "string",
+                        "contentEncoding": "base64",
+                        "minLength": 24,
+                        "maxLength": 24
+                    }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use super::{reference_name, Error};
+use crate::constants::*;
+use crate::schema::DRAFT_2020_12_URI;
+use serde_json::{json, Map, Value};
+
+/// The keywords of a JTD schema (apart from `definitions`, which is only allowed at the top level)
+const KEYWORDS: [&str; 12] = [
+    ADDITIONAL_PROPERTIES_KEY,
+    "discriminator",
+    "elements",
+    ENUM_KEY,
+    "mapping",
+    "metadata",
+    "nullable",
+    "optionalProperties",
+    PROPERTIES_KEY,
+    "ref",
+    TYPE_KEY,
+    "values",
+];
+
+/// The keywords that determine the form of a JTD schema (where `optionalProperties` is the same
+/// form as `properties`)
+const FORM_KEYWORDS: [&str; 7] = [
+    "discriminator",
+    "elements",
+    ENUM_KEY,
+    PROPERTIES_KEY,
+    "ref",
+    TYPE_KEY,
+    "values",
+];
+
+/// Convert a JSON Type Definition (RFC 8927) schema to a 2020-12 JSON Schema accepting the same
+/// values
+///
+/// Definitions become `$defs` (with characters that can't be in references replaced by
+/// underscores), and the `$id` is the given name. Properties forms become objects where optional
+/// properties aren't required (closed unless `additionalProperties` is set), values forms become
+/// maps, discriminators become `oneOf` with the tag as a constant in each branch, integer types
+/// become bounded integers, timestamps become date-time strings, and nullable schemas become
+/// `anyOf` with `null`. Descriptions in metadata become descriptions.
+pub fn from_jtd(schema: &Value, name: &str) -> Result<Value, Error> {
+    let mut fields = schema
+        .as_object()
+        .cloned()
+        .ok_or_else(|| Error::InvalidJtd(schema.clone()))?;
+
+    let definitions = match fields.shift_remove("definitions") {
+        Some(Value::Object(definitions)) => definitions,
+        Some(definitions) => return Err(Error::InvalidJtd(definitions)),
+        None => Map::new(),
+    };
+
+    let mut result = Map::new();
+    result.insert(SCHEMA_KEY.to_string(), json!(DRAFT_2020_12_URI));
+    result.insert(
+        ID_KEY.to_string(),
+        json!(format!("/{}", reference_name(name))),
+    );
+
+    if let Value::Object(root) = convert(&Value::Object(fields), &definitions)? {
+        result.extend(root);
+    }
+
+    if !definitions.is_empty() {
+        let definitions = definitions
+            .iter()
+            .map(|(name, schema)| Ok((reference_name(name), convert(schema, &definitions)?)))
+            .collect::<Result<Map<_, _>, Error>>()?;
+
+        result.insert(DEFS_KEY.to_string(), Value::Object(definitions));
+    }
+
+    Ok(Value::Object(result))
+}
+
+fn convert(schema: &Value, definitions: &Map<String, Value>) -> Result<Value, Error> {
+    let invalid = || Error::InvalidJtd(schema.clone());
+    let fields = schema.as_object().ok_or_else(invalid)?;
+
+    let forms = FORM_KEYWORDS
+        .iter()
+        .filter(|keyword| {
+            fields.contains_key(**keyword)
+                || (**keyword == PROPERTIES_KEY && fields.contains_key("optionalProperties"))
+        })
+        .count();
+
+    let is_properties_form =
+        fields.contains_key(PROPERTIES_KEY) || fields.contains_key("optionalProperties");
+    let misplaced = fields.contains_key("mapping") != fields.contains_key("discriminator")
+        || (fields.contains_key(ADDITIONAL_PROPERTIES_KEY) && !is_properties_form);
+
+    if forms > 1 || misplaced || fields.keys().any(|key| !KEYWORDS.contains(&key.as_str())) {
+        return Err(invalid());
+    }
+
+    let mut result = Map::new();
+
+    if let Some(description) = fields
+        .get("metadata")
+        .and_then(|metadata| metadata.get(DESCRIPTION_KEY))
+    {
+        result.insert(DESCRIPTION_KEY.to_string(), description.clone());
+    }
+
+    let schema = if let Some(reference) = fields.get("ref") {
+        let name = reference
+            .as_str()
+            .filter(|name| definitions.contains_key(*name))
+            .ok_or_else(invalid)?;
+
+        json!({ REF_KEY: format!("#/{}/{}", DEFS_KEY, reference_name(name)) })
+    } else if let Some(type_name) = fields.get(TYPE_KEY) {
+        let type_name = type_name.as_str().ok_or_else(invalid)?;
+
+        match type_name {
+            "boolean" => json!({ TYPE_KEY: "boolean" }),
+            "float32" | "float64" => json!({ TYPE_KEY: "number" }),
+            "string" => json!({ TYPE_KEY: "string" }),
+            "timestamp" => json!({ TYPE_KEY: "string", FORMAT_KEY: "date-time" }),
+            _ => {
+                let (minimum, maximum) = integer_range(type_name)
+                    .ok_or_else(|| Error::UnknownJtdType(type_name.to_string()))?;
+
+                json!({ TYPE_KEY: "integer", MINIMUM_KEY: minimum, MAXIMUM_KEY: maximum })
+            }
+        }
+    } else if let Some(values) = fields.get(ENUM_KEY) {
+        let values = values
+            .as_array()
+            .filter(|values| !values.is_empty() && values.iter().all(Value::is_string));
+
+        json!({ ENUM_KEY: values.ok_or_else(invalid)? })
+    } else if let Some(elements) = fields.get("elements") {
+        json!({ TYPE_KEY: "array", ITEMS_KEY: convert(elements, definitions)? })
+    } else if let Some(values) = fields.get("values") {
+        json!({ TYPE_KEY: "object", ADDITIONAL_PROPERTIES_KEY: convert(values, definitions)? })
+    } else if is_properties_form {
+        Value::Object(properties(fields, None, definitions).ok_or_else(invalid)??)
+    } else if let Some(tag) = fields.get("discriminator") {
+        let tag = tag.as_str().ok_or_else(invalid)?;
+        let mapping = fields
+            .get("mapping")
+            .and_then(Value::as_object)
+            .ok_or_else(invalid)?;
+
+        let branches = mapping
+            .iter()
+            .map(|(value, branch)| {
+                let branch_fields = branch
+                    .as_object()
+                    .filter(|fields| fields.get("nullable") != Some(&json!(true)))
+                    .ok_or_else(|| Error::InvalidJtd(branch.clone()))?;
+
+                properties(branch_fields, Some((tag, value)), definitions)
+                    .ok_or_else(|| Error::InvalidJtd(branch.clone()))?
+                    .map(Value::Object)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        json!({ ONE_OF_KEY: branches })
+    } else {
+        json!({})
+    };
+
+    if let Value::Object(schema) = schema {
+        result.extend(schema);
+    }
+
+    match fields.get("nullable") {
+        Some(Value::Bool(true)) if !result.is_empty() => {
+            let mut outer = Map::new();
+
+            // The description stays outside the union.
+            if let Some(description) = result.shift_remove(DESCRIPTION_KEY) {
+                outer.insert(DESCRIPTION_KEY.to_string(), description);
+            }
+
+            // The empty form already accepts `null`.
+            if !result.is_empty() {
+                outer.insert(
+                    ANY_OF_KEY.to_string(),
+                    json!([result, { TYPE_KEY: "null" }]),
+                );
+            }
+
+            Ok(Value::Object(outer))
+        }
+        Some(Value::Bool(_)) | None => Ok(Value::Object(result)),
+        Some(_) => Err(invalid()),
+    }
+}
+
+/// An object for a properties form (with a discriminator tag and its value), or `None` if the
+/// schema isn't a properties form
+fn properties(
+    fields: &Map<String, Value>,
+    tag: Option<(&str, &str)>,
+    definitions: &Map<String, Value>,
+) -> Option<Result<Map<String, Value>, Error>> {
+    let required = fields.get(PROPERTIES_KEY).map(Value::as_object);
+    let optional = fields.get("optionalProperties").map(Value::as_object);
+
+    let (required, optional) = match (required, optional) {
+        (None, None) | (Some(None), _) | (_, Some(None)) => return None,
+        (required, optional) => (required.flatten(), optional.flatten()),
+    };
+
+    let mut properties = Map::new();
+    let mut required_names = vec![];
+
+    if let Some((tag, value)) = tag {
+        properties.insert(tag.to_string(), json!({ CONST_KEY: value }));
+        required_names.push(json!(tag));
+    }
+
+    for (name, schema) in required.into_iter().flatten() {
+        match convert(schema, definitions) {
+            Ok(schema) => properties.insert(name.clone(), schema),
+            Err(error) => return Some(Err(error)),
+        };
+
+        required_names.push(json!(name));
+    }
+
+    for (name, schema) in optional.into_iter().flatten() {
+        match convert(schema, definitions) {
+            Ok(schema) => properties.insert(name.clone(), schema),
+            Err(error) => return Some(Err(error)),
+        };
+    }
+
+    let mut result = Map::new();
+    result.insert(TYPE_KEY.to_string(), json!("object"));
+    result.insert(PROPERTIES_KEY.to_string(), Value::Object(properties));
+
+    if !required_names.is_empty() {
+        result.insert(REQUIRED_KEY.to_string(), Value::Array(required_names));
+    }
+
+    match fields.get(ADDITIONAL_PROPERTIES_KEY) {
+        Some(Value::Bool(true)) => {}
+        Some(Value::Bool(false)) | None => {
+            result.insert(ADDITIONAL_PROPERTIES_KEY.to_string(), json!(false));
+        }
+        Some(_) => return None,
+    }
+
+    Some(Ok(result))
+}
+
+/// The range of a JTD integer type
+fn integer_range(type_name: &str) -> Option<(i64, i64)> {
+    match type_name {
+        "int8" => Some((i8::MIN.into(), i8::MAX.into())),
+        "uint8" => Some((u8::MIN.into(), u8::MAX.into())),
+        "int16" => Some((i16::MIN.into(), i16::MAX.into())),
+        "uint16" => Some((u16::MIN.into(), u16::MAX.into())),
+        "int32" => Some((i32::MIN.into(), i32::MAX.into())),
+        "uint32" => Some((u32::MIN.into(), u32::MAX.into())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jtd_import() {
+        let schema = json!({
+            "definitions": {
+                "payment": {
+                    "metadata": { "description": "How it was paid" },
+                    "discriminator": "kind",
+                    "mapping": {
+                        "card": { "properties": { "last4": { "type": "string" } } },
+                        "cash": { "properties": {}, "additionalProperties": true }
+                    }
+                }
+            },
+            "properties": {
+                "id": { "type": "uint32" },
+                "placed": { "type": "timestamp" },
+                "payment": { "ref": "payment", "nullable": true }
+            },
+            "optionalProperties": {
+                "status": { "enum": ["open", "closed"] },
+                "tags": { "values": { "type": "boolean" } },
+                "lines": { "elements": { "type": "float64" } },
+                "extra": { "nullable": true }
+            }
+        });
+
+        assert_eq!(
+            from_jtd(&schema, "order").unwrap(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "/order",
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer", "minimum": 0, "maximum": 4294967295_u64 },
+                    "placed": { "type": "string", "format": "date-time" },
+                    "payment": { "anyOf": [{ "$ref": "#/$defs/payment" }, { "type": "null" }] },
+                    "status": { "enum": ["open", "closed"] },
+                    "tags": { "type": "object", "additionalProperties": { "type": "boolean" } },
+                    "lines": { "type": "array", "items": { "type": "number" } },
+                    "extra": {}
+                },
+                "required": ["id", "placed", "payment"],
+                "additionalProperties": false,
+                "$defs": {
+                    "payment": {
+                        "description": "How it was paid",
+                        "oneOf": [
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "kind": { "const": "card" },
+                                    "last4": { "type": "string" }
+                                },
+                                "required": ["kind", "last4"],
+                                "additionalProperties": false
+                            },
+                            {
+                                "type": "object",
+                                "properties": { "kind": { "const": "cash" } },
+                                "required": ["kind"]
+                            }
+                        ]
+                    }
+                }
+            })
+        );
+
+        assert!(matches!(
+            from_jtd(&json!({ "type": "int64" }), "a"),
+            Err(Error::UnknownJtdType(name)) if name == "int64"
+        ));
+        assert!(matches!(
+            from_jtd(&json!({ "type": "string", "elements": {} }), "a"),
+            Err(Error::InvalidJtd(_))
+        ));
+        assert!(matches!(
+            from_jtd(&json!({ "ref": "missing" }), "a"),
+            Err(Error::InvalidJtd(_))
+        ));
+    }
+}
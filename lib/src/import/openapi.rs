@@ -0,0 +1,253 @@
+use super::{reference_name, Error};
+use crate::constants::*;
+use crate::schema::{upgrade, DRAFT_2020_12_URI};
+use serde_json::{json, Map, Value};
+
+const COMPONENTS_PREFIX: &str = "#/components/schemas/";
+
+/// Keywords that only add annotations, which are kept outside `anyOf` for nullable schemas and
+/// next to references in 3.0 documents
+const ANNOTATION_KEYS: [&str; 7] = [
+    DEFAULT_KEY,
+    "deprecated",
+    DESCRIPTION_KEY,
+    EXAMPLES_KEY,
+    "readOnly",
+    TITLE_KEY,
+    "writeOnly",
+];
+
+/// Keywords that are part of OpenAPI but not JSON Schema
+const OPENAPI_KEYS: [&str; 3] = ["discriminator", "externalDocs", "xml"];
+
+/// OpenAPI's single instance example, which is data like the keys in `DATA_KEYS`
+const EXAMPLE_KEY: &str = "example";
+
+/// Extract the component schemas from an OpenAPI 3.0 or 3.1 document as 2020-12 schemas
+///
+/// Each component has an `$id` under the given base path (and `$defs`, so that it can be composed
+/// with the schemas it refers to), and references to other components are replaced with
+/// references to their IDs. OpenAPI keywords (including extensions) are
+/// removed, and from 3.0 documents, `nullable` becomes `anyOf` with `null`, `example` becomes
+/// `examples`, boolean exclusive bounds become numbers, and keywords next to references are
+/// dropped (apart from annotations), since they're ignored in 3.0.
+pub fn from_openapi(document: &Value, base: &str) -> Result<Vec<(String, Value)>, Error> {
+    let legacy = is_legacy(document)?;
+    let schemas = document
+        .pointer("/components/schemas")
+        .and_then(|schemas| schemas.as_object())
+        .ok_or(Error::MissingComponents)?;
+    let base = base.trim_end_matches('/');
+
+    schemas
+        .iter()
+        .map(|(name, schema)| {
+            let name = reference_name(name);
+            let schema = convert_schema(schema, base, legacy);
+
+            let mut result = Map::new();
+            result.insert(SCHEMA_KEY.to_string(), json!(DRAFT_2020_12_URI));
+            result.insert(ID_KEY.to_string(), json!(format!("{}/{}", base, name)));
+
+            match schema {
+                Value::Object(fields) => {
+                    if !fields.contains_key(TITLE_KEY) {
+                        result.insert(TITLE_KEY.to_string(), json!(name));
+                    }
+
+                    result.extend(fields);
+                }
+                // Boolean schemas have to be wrapped.
+                schema => {
+                    result.insert(ALL_OF_KEY.to_string(), json!([schema]));
+                }
+            }
+
+            // Composition needs somewhere to put referenced schemas.
+            if !result.contains_key(DEFS_KEY) {
+                result.insert(DEFS_KEY.to_string(), json!({}));
+            }
+
+            Ok((name, Value::Object(result)))
+        })
+        .collect()
+}
+
+/// Whether the document is OpenAPI 3.0 (as opposed to 3.1, which uses 2020-12 schemas)
+pub(crate) fn is_legacy(document: &Value) -> Result<bool, Error> {
+    let version = document
+        .get("openapi")
+        .and_then(|version| version.as_str())
+        .ok_or_else(|| Error::UnsupportedOpenApiVersion(None))?;
+
+    if version.starts_with("3.0") {
+        Ok(true)
+    } else if version.starts_with("3.1") {
+        Ok(false)
+    } else {
+        Err(Error::UnsupportedOpenApiVersion(Some(version.to_string())))
+    }
+}
+
+/// Convert a schema from an OpenAPI document, with references to components under the base path
+pub(crate) fn convert_schema(schema: &Value, base: &str, legacy: bool) -> Value {
+    let mut schema = schema.clone();
+    convert(&mut schema, base, legacy);
+
+    if legacy {
+        upgrade(&mut schema);
+    }
+
+    schema
+}
+
+fn convert(value: &mut Value, base: &str, legacy: bool) {
+    let fields = match value {
+        Value::Object(fields) => fields,
+        Value::Array(values) => {
+            for value in values {
+                convert(value, base, legacy);
+            }
+
+            return;
+        }
+        _ => return,
+    };
+
+    fields.retain(|key, _| !key.starts_with("x-") && !OPENAPI_KEYS.contains(&key.as_str()));
+
+    if let Some(example) = fields.shift_remove(EXAMPLE_KEY) {
+        if !fields.contains_key(EXAMPLES_KEY) {
+            fields.insert(EXAMPLES_KEY.to_string(), json!([example]));
+        }
+    }
+
+    if let Some(Value::String(reference)) = fields.get_mut(REF_KEY) {
+        if let Some(pointer) = reference.strip_prefix(COMPONENTS_PREFIX) {
+            let (name, rest) = pointer.split_once('/').unwrap_or((pointer, ""));
+            let name = reference_name(&name.replace("~1", "/").replace("~0", "~"));
+
+            *reference = if rest.is_empty() {
+                format!("{}/{}", base, name)
+            } else {
+                format!("{}/{}#/{}", base, name, rest)
+            };
+        }
+
+        if legacy {
+            fields.retain(|key, _| key == REF_KEY || ANNOTATION_KEYS.contains(&key.as_str()));
+        }
+    }
+
+    for (key, child) in fields.iter_mut() {
+        if key == PROPERTIES_KEY || key == DEFS_KEY || key == PATTERN_PROPERTIES_KEY {
+            if let Some(schemas) = child.as_object_mut() {
+                for schema in schemas.values_mut() {
+                    convert(schema, base, legacy);
+                }
+            }
+        } else if !DATA_KEYS.contains(&key.as_str()) && key != EXAMPLE_KEY {
+            convert(child, base, legacy);
+        }
+    }
+
+    if legacy {
+        if let Some(Value::Bool(nullable)) = fields.shift_remove("nullable") {
+            if nullable {
+                let mut outer = Map::new();
+                let mut inner = Map::new();
+
+                for (key, value) in std::mem::take(fields) {
+                    if ANNOTATION_KEYS.contains(&key.as_str()) {
+                        outer.insert(key, value);
+                    } else {
+                        inner.insert(key, value);
+                    }
+                }
+
+                outer.insert(ANY_OF_KEY.to_string(), json!([inner, { TYPE_KEY: "null" }]));
+                *fields = outer;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openapi_components() {
+        let document = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Shop", "version": "1.0.0" },
+            "paths": {},
+            "components": {
+                "schemas": {
+                    "Order": {
+                        "type": "object",
+                        "description": "An order",
+                        "properties": {
+                            "id": { "type": "integer", "minimum": 0, "exclusiveMinimum": true },
+                            "note": {
+                                "type": "string",
+                                "nullable": true,
+                                "description": "Free text",
+                                "example": "Leave at the door"
+                            },
+                            "customer": {
+                                "$ref": "#/components/schemas/Customer",
+                                "description": "Who placed it",
+                                "nullable": true
+                            }
+                        },
+                        "required": ["id"],
+                        "discriminator": { "propertyName": "kind" },
+                        "x-internal": true
+                    },
+                    "Customer": { "type": "object", "title": "A customer" }
+                }
+            }
+        });
+
+        assert_eq!(
+            from_openapi(&document, "/schemas/").unwrap(),
+            vec![
+                (
+                    "Order".to_string(),
+                    json!({
+                        "$schema": "https://json-schema.org/draft/2020-12/schema",
+                        "$id": "/schemas/Order",
+                        "title": "Order",
+                        "type": "object",
+                        "description": "An order",
+                        "properties": {
+                            "id": { "type": "integer", "exclusiveMinimum": 0 },
+                            "note": {
+                                "description": "Free text",
+                                "examples": ["Leave at the door"],
+                                "anyOf": [{ "type": "string" }, { "type": "null" }]
+                            },
+                            "customer": {
+                                "$ref": "/schemas/Customer",
+                                "description": "Who placed it"
+                            }
+                        },
+                        "required": ["id"],
+                        "$defs": {}
+                    })
+                ),
+                (
+                    "Customer".to_string(),
+                    json!({
+                        "$schema": "https://json-schema.org/draft/2020-12/schema",
+                        "$id": "/schemas/Customer",
+                        "type": "object",
+                        "title": "A customer",
+                        "$defs": {}
+                    })
+                )
+            ]
+        );
+    }
+}
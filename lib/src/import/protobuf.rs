@@ -0,0 +1,770 @@
+use super::{reference_name, Error};
+use crate::constants::*;
+use crate::schema::DRAFT_2020_12_URI;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+const VARINT: u64 = 0;
+const FIXED_64: u64 = 1;
+const LENGTH_DELIMITED: u64 = 2;
+const FIXED_32: u64 = 5;
+
+const LABEL_REQUIRED: u64 = 2;
+const LABEL_REPEATED: u64 = 3;
+
+const TYPE_MESSAGE: u64 = 11;
+const TYPE_ENUM: u64 = 14;
+
+const WELL_KNOWN_PACKAGE: &str = "google.protobuf";
+
+/// Convert a compiled `FileDescriptorSet` to a 2020-12 JSON Schema with a definition for each
+/// message and enum
+///
+/// Definitions are named after the type within its package (with nested types joined by
+/// underscores), unless that's ambiguous, and the `$id` is the given name. Properties use the JSON
+/// names of fields and the types of the proto3 JSON mapping, so 64-bit integers are strings, maps
+/// are objects, and well-known types like `Timestamp` have their special representations. Repeated
+/// fields become arrays, oneofs become `oneOf` requiring one of their fields, and comments become
+/// descriptions.
+pub fn from_descriptor_set(bytes: &[u8], name: &str) -> Result<Value, Error> {
+    let mut files = vec![];
+
+    for (number, value) in fields(bytes)? {
+        if number == 1 {
+            files.push(FileDescriptor::decode(value.bytes()?)?);
+        }
+    }
+
+    let mut importer = Importer::default();
+
+    for file in &files {
+        if file.package != WELL_KNOWN_PACKAGE {
+            importer.name_types(&file.package, "", &file.messages, &file.enums);
+        }
+    }
+
+    for file in &files {
+        if file.package != WELL_KNOWN_PACKAGE {
+            let prefix = package_prefix(&file.package);
+
+            for (index, message) in file.messages.iter().enumerate() {
+                importer.message(file, &prefix, message, &[4, index as i32])?;
+            }
+
+            for (index, enumeration) in file.enums.iter().enumerate() {
+                importer.enumeration(file, &prefix, enumeration, &[5, index as i32]);
+            }
+        }
+    }
+
+    Ok(json!({
+        SCHEMA_KEY: DRAFT_2020_12_URI,
+        ID_KEY: format!("/{}", reference_name(name)),
+        DEFS_KEY: importer.definitions,
+    }))
+}
+
+/// A decoded field value
+enum WireValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+impl<'a> WireValue<'a> {
+    fn bytes(&self) -> Result<&'a [u8], Error> {
+        match self {
+            Self::Bytes(bytes) => Ok(bytes),
+            _ => Err(Error::InvalidDescriptor),
+        }
+    }
+
+    fn string(&self) -> Result<String, Error> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| Error::InvalidDescriptor)
+    }
+
+    fn varint(&self) -> Result<u64, Error> {
+        match self {
+            Self::Varint(value) => Ok(*value),
+            _ => Err(Error::InvalidDescriptor),
+        }
+    }
+
+    /// Packed or unpacked repeated integers
+    fn varints(&self) -> Result<Vec<u64>, Error> {
+        match self {
+            Self::Varint(value) => Ok(vec![*value]),
+            Self::Bytes(mut bytes) => {
+                let mut result = vec![];
+
+                while !bytes.is_empty() {
+                    result.push(varint(&mut bytes)?);
+                }
+
+                Ok(result)
+            }
+            Self::Fixed => Err(Error::InvalidDescriptor),
+        }
+    }
+}
+
+fn varint(bytes: &mut &[u8]) -> Result<u64, Error> {
+    let mut result = 0;
+
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(Error::InvalidDescriptor)?;
+        *bytes = rest;
+        result |= u64::from(byte & 0x7f) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+
+    Err(Error::InvalidDescriptor)
+}
+
+/// The fields of an encoded message, with their numbers
+fn fields(mut bytes: &[u8]) -> Result<Vec<(u64, WireValue<'_>)>, Error> {
+    let mut result = vec![];
+
+    while !bytes.is_empty() {
+        let key = varint(&mut bytes)?;
+        let value = match key & 7 {
+            VARINT => WireValue::Varint(varint(&mut bytes)?),
+            LENGTH_DELIMITED => {
+                let length =
+                    usize::try_from(varint(&mut bytes)?).map_err(|_| Error::InvalidDescriptor)?;
+
+                if length > bytes.len() {
+                    return Err(Error::InvalidDescriptor);
+                }
+
+                let (value, rest) = bytes.split_at(length);
+                bytes = rest;
+                WireValue::Bytes(value)
+            }
+            wire_type @ (FIXED_64 | FIXED_32) => {
+                let length = if wire_type == FIXED_64 { 8 } else { 4 };
+                bytes = bytes.get(length..).ok_or(Error::InvalidDescriptor)?;
+                WireValue::Fixed
+            }
+            _ => return Err(Error::InvalidDescriptor),
+        };
+
+        result.push((key >> 3, value));
+    }
+
+    Ok(result)
+}
+
+#[derive(Default)]
+struct FileDescriptor {
+    package: String,
+    messages: Vec<MessageDescriptor>,
+    enums: Vec<EnumDescriptor>,
+    /// Leading comments by source location path
+    comments: HashMap<Vec<i32>, String>,
+}
+
+impl FileDescriptor {
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut result = Self::default();
+
+        for (number, value) in fields(bytes)? {
+            match number {
+                2 => result.package = value.string()?,
+                4 => result
+                    .messages
+                    .push(MessageDescriptor::decode(value.bytes()?)?),
+                5 => result.enums.push(EnumDescriptor::decode(value.bytes()?)?),
+                9 => {
+                    for (number, location) in fields(value.bytes()?)? {
+                        if number == 1 {
+                            result.location(location.bytes()?)?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn location(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let mut path = vec![];
+        let mut comment = None;
+
+        for (number, value) in fields(bytes)? {
+            match number {
+                1 => path.extend(value.varints()?.into_iter().map(|value| value as i32)),
+                3 => comment = Some(value.string()?),
+                _ => {}
+            }
+        }
+
+        if let Some(comment) = comment {
+            let comment = comment
+                .lines()
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let comment = comment.trim();
+
+            if !comment.is_empty() {
+                self.comments.insert(path, comment.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn comment(&self, path: &[i32]) -> Option<&String> {
+        self.comments.get(path)
+    }
+}
+
+#[derive(Default)]
+struct MessageDescriptor {
+    name: String,
+    fields: Vec<FieldDescriptor>,
+    nested: Vec<MessageDescriptor>,
+    enums: Vec<EnumDescriptor>,
+    oneofs: Vec<String>,
+    map_entry: bool,
+}
+
+impl MessageDescriptor {
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut result = Self::default();
+
+        for (number, value) in fields(bytes)? {
+            match number {
+                1 => result.name = value.string()?,
+                2 => result.fields.push(FieldDescriptor::decode(value.bytes()?)?),
+                3 => result
+                    .nested
+                    .push(MessageDescriptor::decode(value.bytes()?)?),
+                4 => result.enums.push(EnumDescriptor::decode(value.bytes()?)?),
+                7 => {
+                    for (number, option) in fields(value.bytes()?)? {
+                        if number == 7 {
+                            result.map_entry = option.varint()? != 0;
+                        }
+                    }
+                }
+                8 => {
+                    for (number, name) in fields(value.bytes()?)? {
+                        if number == 1 {
+                            result.oneofs.push(name.string()?);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[derive(Default)]
+struct FieldDescriptor {
+    name: String,
+    label: u64,
+    field_type: u64,
+    type_name: String,
+    oneof_index: Option<usize>,
+    json_name: Option<String>,
+    proto3_optional: bool,
+}
+
+impl FieldDescriptor {
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut result = Self::default();
+
+        for (number, value) in fields(bytes)? {
+            match number {
+                1 => result.name = value.string()?,
+                4 => result.label = value.varint()?,
+                5 => result.field_type = value.varint()?,
+                6 => result.type_name = value.string()?,
+                9 => result.oneof_index = Some(value.varint()? as usize),
+                10 => result.json_name = Some(value.string()?),
+                17 => result.proto3_optional = value.varint()? != 0,
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The name in JSON, which is lower camel case unless the descriptor says otherwise
+    fn json_name(&self) -> String {
+        self.json_name.clone().unwrap_or_else(|| {
+            let mut result = String::new();
+            let mut upper = false;
+
+            for c in self.name.chars() {
+                if c == '_' {
+                    upper = true;
+                } else if upper {
+                    result.push(c.to_ascii_uppercase());
+                    upper = false;
+                } else {
+                    result.push(c);
+                }
+            }
+
+            result
+        })
+    }
+}
+
+#[derive(Default)]
+struct EnumDescriptor {
+    name: String,
+    values: Vec<String>,
+}
+
+impl EnumDescriptor {
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut result = Self::default();
+
+        for (number, value) in fields(bytes)? {
+            match number {
+                1 => result.name = value.string()?,
+                2 => {
+                    for (number, name) in fields(value.bytes()?)? {
+                        if number == 1 {
+                            result.values.push(name.string()?);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// A definition name and what to use for references to it
+enum TypeEntry {
+    Definition(String),
+    /// The value schema of a map entry
+    MapEntry(Value),
+}
+
+#[derive(Default)]
+struct Importer {
+    definitions: Map<String, Value>,
+    /// Types by fully-qualified name (with a leading period, as in field descriptors)
+    types: HashMap<String, TypeEntry>,
+}
+
+impl Importer {
+    /// Reserve definition names for messages and enums, with their relative names as a prefix
+    fn name_types(
+        &mut self,
+        package: &str,
+        parent: &str,
+        messages: &[MessageDescriptor],
+        enums: &[EnumDescriptor],
+    ) {
+        let prefix = package_prefix(package);
+
+        for message in messages {
+            let relative = format!("{}{}", parent, message.name);
+
+            if !message.map_entry {
+                self.reserve(&prefix, &relative);
+            }
+
+            self.name_types(
+                package,
+                &format!("{}.", relative),
+                &message.nested,
+                &message.enums,
+            );
+        }
+
+        for enumeration in enums {
+            self.reserve(&prefix, &format!("{}{}", parent, enumeration.name));
+        }
+    }
+
+    fn reserve(&mut self, prefix: &str, relative: &str) {
+        let taken = |name: &String| {
+            self.types.values().any(
+                |entry| matches!(entry, TypeEntry::Definition(definition) if definition == name),
+            )
+        };
+
+        let mut name = reference_name(relative);
+
+        if taken(&name) {
+            name = reference_name(&format!("{}{}", prefix, relative));
+        }
+
+        self.types.insert(
+            format!(".{}{}", prefix, relative),
+            TypeEntry::Definition(name),
+        );
+    }
+
+    fn message(
+        &mut self,
+        file: &FileDescriptor,
+        prefix: &str,
+        message: &MessageDescriptor,
+        path: &[i32],
+    ) -> Result<(), Error> {
+        let full_name = format!(".{}{}", prefix, message.name);
+        let nested_prefix = format!("{}{}.", prefix, message.name);
+
+        // Map entries have to be converted before fields can use them.
+        for nested in message.nested.iter().filter(|nested| nested.map_entry) {
+            let value = nested
+                .fields
+                .iter()
+                .find(|field| field.name == "value")
+                .ok_or(Error::InvalidDescriptor)?;
+            let value = self.field_type(value);
+
+            self.types.insert(
+                format!(".{}{}", nested_prefix, nested.name),
+                TypeEntry::MapEntry(value),
+            );
+        }
+
+        for (index, nested) in message.nested.iter().enumerate() {
+            if !nested.map_entry {
+                let mut path = path.to_vec();
+                path.extend([3, index as i32]);
+                self.message(file, &nested_prefix, nested, &path)?;
+            }
+        }
+
+        for (index, enumeration) in message.enums.iter().enumerate() {
+            let mut path = path.to_vec();
+            path.extend([4, index as i32]);
+            self.enumeration(file, &nested_prefix, enumeration, &path);
+        }
+
+        let mut properties = Map::new();
+        let mut required = vec![];
+        let mut oneofs = vec![vec![]; message.oneofs.len()];
+
+        for (index, field) in message.fields.iter().enumerate() {
+            let name = field.json_name();
+            let mut property = match self.types.get(&field.type_name) {
+                Some(TypeEntry::MapEntry(value)) if field.label == LABEL_REPEATED => json!({
+                    TYPE_KEY: "object",
+                    ADDITIONAL_PROPERTIES_KEY: value,
+                }),
+                _ if field.label == LABEL_REPEATED => json!({
+                    TYPE_KEY: "array",
+                    ITEMS_KEY: self.field_type(field),
+                }),
+                _ => self.field_type(field),
+            };
+
+            let mut field_path = path.to_vec();
+            field_path.extend([2, index as i32]);
+
+            if let (Some(comment), Some(fields)) =
+                (file.comment(&field_path), property.as_object_mut())
+            {
+                fields.insert(DESCRIPTION_KEY.to_string(), json!(comment));
+            }
+
+            if field.label == LABEL_REQUIRED {
+                required.push(json!(name));
+            }
+
+            // Optional proto3 fields are in synthetic oneofs.
+            match field.oneof_index {
+                Some(oneof) if !field.proto3_optional && oneof < oneofs.len() => {
+                    oneofs[oneof].push(json!({ REQUIRED_KEY: [name] }));
+                }
+                _ => {}
+            }
+
+            properties.insert(name, property);
+        }
+
+        let mut result = Map::new();
+        result.insert(TITLE_KEY.to_string(), json!(message.name));
+
+        if let Some(comment) = file.comment(path) {
+            result.insert(DESCRIPTION_KEY.to_string(), json!(comment));
+        }
+
+        result.insert(TYPE_KEY.to_string(), json!("object"));
+        result.insert(PROPERTIES_KEY.to_string(), Value::Object(properties));
+
+        if !required.is_empty() {
+            result.insert(REQUIRED_KEY.to_string(), Value::Array(required));
+        }
+
+        result.insert(ADDITIONAL_PROPERTIES_KEY.to_string(), json!(false));
+
+        let mut oneofs = oneofs
+            .into_iter()
+            .filter(|branches| !branches.is_empty())
+            .map(|branches| json!({ ONE_OF_KEY: branches }))
+            .collect::<Vec<_>>();
+
+        match oneofs.len() {
+            0 => {}
+            1 => {
+                if let Value::Object(oneof) = oneofs.remove(0) {
+                    result.extend(oneof);
+                }
+            }
+            _ => {
+                result.insert(ALL_OF_KEY.to_string(), Value::Array(oneofs));
+            }
+        }
+
+        self.insert(&full_name, Value::Object(result));
+        Ok(())
+    }
+
+    fn enumeration(
+        &mut self,
+        file: &FileDescriptor,
+        prefix: &str,
+        enumeration: &EnumDescriptor,
+        path: &[i32],
+    ) {
+        let mut result = Map::new();
+        result.insert(TITLE_KEY.to_string(), json!(enumeration.name));
+
+        if let Some(comment) = file.comment(path) {
+            result.insert(DESCRIPTION_KEY.to_string(), json!(comment));
+        }
+
+        result.insert(ENUM_KEY.to_string(), json!(enumeration.values));
+        self.insert(
+            &format!(".{}{}", prefix, enumeration.name),
+            Value::Object(result),
+        );
+    }
+
+    fn insert(&mut self, full_name: &str, schema: Value) {
+        if let Some(TypeEntry::Definition(name)) = self.types.get(full_name) {
+            self.definitions.insert(name.clone(), schema);
+        }
+    }
+
+    /// The schema for a single value of a field
+    fn field_type(&self, field: &FieldDescriptor) -> Value {
+        match field.field_type {
+            TYPE_MESSAGE | TYPE_ENUM => {
+                if let Some(schema) = well_known_type(&field.type_name) {
+                    return schema;
+                }
+
+                match self.types.get(&field.type_name) {
+                    Some(TypeEntry::Definition(name)) => {
+                        json!({ REF_KEY: format!("#/{}/{}", DEFS_KEY, name) })
+                    }
+                    Some(TypeEntry::MapEntry(value)) => value.clone(),
+                    // Types from files that aren't in the set could be anything.
+                    None => json!({}),
+                }
+            }
+            // Doubles and floats, which can also be strings like "NaN"
+            1 | 2 => json!({ TYPE_KEY: "number" }),
+            // 64-bit integers are strings in JSON.
+            3 | 16 | 18 => json!({ TYPE_KEY: "string", PATTERN_KEY: "^-?[0-9]+$" }),
+            4 | 6 => json!({ TYPE_KEY: "string", PATTERN_KEY: "^[0-9]+$" }),
+            5 | 15 | 17 => json!({
+                TYPE_KEY: "integer",
+                MINIMUM_KEY: i32::MIN,
+                MAXIMUM_KEY: i32::MAX,
+            }),
+            7 | 13 => json!({ TYPE_KEY: "integer", MINIMUM_KEY: 0, MAXIMUM_KEY: u32::MAX }),
+            8 => json!({ TYPE_KEY: "boolean" }),
+            9 => json!({ TYPE_KEY: "string" }),
+            12 => json!({ TYPE_KEY: "string", CONTENT_ENCODING_KEY: "base64" }),
+            // Groups aren't supported.
+            _ => json!({}),
+        }
+    }
+}
+
+fn package_prefix(package: &str) -> String {
+    if package.is_empty() {
+        String::new()
+    } else {
+        format!("{}.", package)
+    }
+}
+
+/// The JSON representation of a well-known type
+fn well_known_type(type_name: &str) -> Option<Value> {
+    let name = type_name.strip_prefix(&format!(".{}.", WELL_KNOWN_PACKAGE))?;
+
+    Some(match name {
+        "Any" => json!({
+            TYPE_KEY: "object",
+            PROPERTIES_KEY: { "@type": { TYPE_KEY: "string" } },
+            REQUIRED_KEY: ["@type"],
+        }),
+        "BoolValue" => json!({ TYPE_KEY: "boolean" }),
+        "BytesValue" => json!({ TYPE_KEY: "string", CONTENT_ENCODING_KEY: "base64" }),
+        "DoubleValue" | "FloatValue" => json!({ TYPE_KEY: "number" }),
+        "Duration" => json!({ TYPE_KEY: "string", PATTERN_KEY: "^-?[0-9]+(\\.[0-9]{1,9})?s$" }),
+        "Empty" => json!({ TYPE_KEY: "object", ADDITIONAL_PROPERTIES_KEY: false }),
+        "FieldMask" | "StringValue" => json!({ TYPE_KEY: "string" }),
+        "Int32Value" => {
+            json!({ TYPE_KEY: "integer", MINIMUM_KEY: i32::MIN, MAXIMUM_KEY: i32::MAX })
+        }
+        "Int64Value" => json!({ TYPE_KEY: "string", PATTERN_KEY: "^-?[0-9]+$" }),
+        "ListValue" => json!({ TYPE_KEY: "array" }),
+        "NullValue" => json!({ TYPE_KEY: "null" }),
+        "Struct" => json!({ TYPE_KEY: "object" }),
+        "Timestamp" => json!({ TYPE_KEY: "string", FORMAT_KEY: "date-time" }),
+        "UInt32Value" => json!({ TYPE_KEY: "integer", MINIMUM_KEY: 0, MAXIMUM_KEY: u32::MAX }),
+        "UInt64Value" => json!({ TYPE_KEY: "string", PATTERN_KEY: "^[0-9]+$" }),
+        "Value" => json!({}),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint_bytes(mut value: u64) -> Vec<u8> {
+        let mut result = vec![];
+
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+
+            if value == 0 {
+                result.push(byte);
+                return result;
+            }
+
+            result.push(byte | 0x80);
+        }
+    }
+
+    fn message(fields: &[(u64, Vec<u8>)]) -> Vec<u8> {
+        let mut result = vec![];
+
+        for (number, bytes) in fields {
+            result.extend(varint_bytes(number << 3 | LENGTH_DELIMITED));
+            result.extend(varint_bytes(bytes.len() as u64));
+            result.extend(bytes);
+        }
+
+        result
+    }
+
+    fn integer(number: u64, value: u64) -> Vec<u8> {
+        let mut result = varint_bytes(number << 3);
+        result.extend(varint_bytes(value));
+        result
+    }
+
+    fn field(name: &str, label: u64, field_type: u64, type_name: &str, extra: &[u8]) -> Vec<u8> {
+        let mut result = message(&[(1, name.as_bytes().to_vec())]);
+        result.extend(integer(4, label));
+        result.extend(integer(5, field_type));
+
+        if !type_name.is_empty() {
+            result.extend(message(&[(6, type_name.as_bytes().to_vec())]));
+        }
+
+        result.extend(extra);
+        result
+    }
+
+    #[test]
+    fn descriptor_set_import() {
+        let status = message(&[
+            (1, b"Status".to_vec()),
+            (2, message(&[(1, b"STATUS_UNSPECIFIED".to_vec())])),
+            (2, message(&[(1, b"STATUS_OPEN".to_vec())])),
+        ]);
+        let entry = message(&[
+            (1, b"LabelsEntry".to_vec()),
+            (2, field("key", 1, 9, "", &[])),
+            (2, field("value", 1, 3, "", &[])),
+            (7, integer(7, 1)),
+        ]);
+        let order = message(&[
+            (1, b"Order".to_vec()),
+            (2, field("order_id", 1, 9, "", &[])),
+            (2, field("status", 1, 14, ".shop.Order.Status", &[])),
+            (2, field("labels", 3, 11, ".shop.Order.LabelsEntry", &[])),
+            (2, field("lines", 3, 11, ".shop.Line", &[])),
+            (
+                2,
+                field("created", 1, 11, ".google.protobuf.Timestamp", &[]),
+            ),
+            (2, field("card", 1, 9, "", &integer(9, 0))),
+            (2, field("cash", 1, 8, "", &integer(9, 0))),
+            (3, entry),
+            (4, status),
+            (8, message(&[(1, b"payment".to_vec())])),
+        ]);
+        let line = message(&[(1, b"Line".to_vec()), (2, field("sku", 2, 9, "", &[]))]);
+        // The packed path of the second top-level message
+        let location = message(&[(1, vec![4, 1]), (3, b" A line\n".to_vec())]);
+        let file = message(&[
+            (1, b"shop.proto".to_vec()),
+            (2, b"shop".to_vec()),
+            (4, order),
+            (4, line),
+            (9, message(&[(1, location)])),
+        ]);
+
+        assert_eq!(
+            from_descriptor_set(&message(&[(1, file)]), "shop").unwrap(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "/shop",
+                "$defs": {
+                    "Order": {
+                        "title": "Order",
+                        "type": "object",
+                        "properties": {
+                            "orderId": { "type": "string" },
+                            "status": { "$ref": "#/$defs/Order_Status" },
+                            "labels": {
+                                "type": "object",
+                                "additionalProperties": { "type": "string", "pattern": "^-?[0-9]+$" }
+                            },
+                            "lines": { "type": "array", "items": { "$ref": "#/$defs/Line" } },
+                            "created": { "type": "string", "format": "date-time" },
+                            "card": { "type": "string" },
+                            "cash": { "type": "boolean" }
+                        },
+                        "additionalProperties": false,
+                        "oneOf": [{ "required": ["card"] }, { "required": ["cash"] }]
+                    },
+                    "Order_Status": {
+                        "title": "Status",
+                        "enum": ["STATUS_UNSPECIFIED", "STATUS_OPEN"]
+                    },
+                    "Line": {
+                        "title": "Line",
+                        "description": "A line",
+                        "type": "object",
+                        "properties": { "sku": { "type": "string" } },
+                        "required": ["sku"],
+                        "additionalProperties": false
+                    }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,865 @@
+use super::{reference_name, Error};
+use crate::constants::*;
+use crate::schema::DRAFT_2020_12_URI;
+use serde_json::{json, Map, Value};
+
+const PUNCTUATION: &str = "&(),-.:;<=>?[]{|}";
+
+/// Keywords that start type operators, which aren't supported
+const TYPE_OPERATORS: [&str; 4] = ["infer", "keyof", "typeof", "unique"];
+
+/// Primitive types with no JSON representation
+const UNREPRESENTABLE_TYPES: [&str; 3] = ["bigint", "never", "symbol"];
+
+/// Convert TypeScript declarations to a 2020-12 JSON Schema with a definition for each interface,
+/// type alias, and enum
+///
+/// Only a subset of TypeScript is supported: primitives, literal types, arrays, tuples, object
+/// types (with index signatures), `Record`, `Date` (as a date-time string), unions,
+/// intersections, and references to other declarations in the source. Optional properties (and
+/// properties whose type includes `undefined`) aren't required, unions of string literals become
+/// enums, `extends` becomes `allOf`, numeric enums become `oneOf` constants, and JSDoc comments
+/// become descriptions. Anything else (like generics, imports, or methods) is an error.
+pub fn from_typescript(source: &str, name: &str) -> Result<Value, Error> {
+    let mut parser = Parser {
+        lexemes: lex(source)?,
+        index: 0,
+        references: vec![],
+    };
+    let mut definitions = Map::new();
+
+    while parser.index < parser.lexemes.len() {
+        let (line, declaration, schema) = parser.declaration()?;
+        let key = reference_name(&declaration);
+
+        if definitions.contains_key(&key) {
+            return Err(unsupported(
+                line,
+                &format!("merged declarations of {}", declaration),
+            ));
+        }
+
+        definitions.insert(key, schema);
+    }
+
+    for (reference, line) in parser.references {
+        if !definitions.contains_key(&reference_name(&reference)) {
+            return Err(Error::UnknownTypeScriptType(line, reference));
+        }
+    }
+
+    Ok(json!({
+        SCHEMA_KEY: DRAFT_2020_12_URI,
+        ID_KEY: format!("/{}", reference_name(name)),
+        DEFS_KEY: definitions,
+    }))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    String(String),
+    Number(String),
+    Punctuation(char),
+}
+
+struct Lexeme {
+    token: Token,
+    line: usize,
+    /// The text of a JSDoc comment immediately before the token
+    doc: Option<String>,
+}
+
+fn lex(source: &str) -> Result<Vec<Lexeme>, Error> {
+    let mut result = vec![];
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+    let mut doc = None;
+
+    while let Some(c) = chars.next() {
+        let start = line;
+
+        let token = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut comment = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('*') if chars.peek() == Some(&'/') => {
+                            chars.next();
+                            break;
+                        }
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+
+                            comment.push(c);
+                        }
+                        None => return Err(Error::InvalidTypeScript(start)),
+                    }
+                }
+
+                // Only `/** */` comments are documentation.
+                if let Some(text) = comment.strip_prefix('*') {
+                    doc = Some(doc_text(text)).filter(|text| !text.is_empty());
+                }
+
+                continue;
+            }
+            '"' | '\'' => {
+                let mut value = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('r') => value.push('\r'),
+                            Some('t') => value.push('\t'),
+                            Some(escaped) if escaped != '\n' => value.push(escaped),
+                            _ => return Err(Error::InvalidTypeScript(start)),
+                        },
+                        Some('\n') | None => return Err(Error::InvalidTypeScript(start)),
+                        Some(c) => value.push(c),
+                    }
+                }
+
+                Token::String(value)
+            }
+            '`' => return Err(unsupported(line, "template literal type")),
+            c if c.is_ascii_digit() => {
+                let mut text = c.to_string();
+
+                while let Some(c) =
+                    chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '_')
+                {
+                    // Underscores are only separators.
+                    if c != '_' {
+                        text.push(c);
+                    }
+
+                    if matches!(c, 'e' | 'E') {
+                        if let Some(sign) = chars.next_if(|c| matches!(c, '+' | '-')) {
+                            text.push(sign);
+                        }
+                    }
+                }
+
+                Token::Number(text)
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut text = c.to_string();
+
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+                {
+                    text.push(c);
+                }
+
+                Token::Identifier(text)
+            }
+            c if PUNCTUATION.contains(c) => Token::Punctuation(c),
+            _ => return Err(Error::InvalidTypeScript(line)),
+        };
+
+        result.push(Lexeme {
+            token,
+            line: start,
+            doc: doc.take(),
+        });
+    }
+
+    Ok(result)
+}
+
+/// The text of a JSDoc comment, without leading asterisks or block tags (like `@deprecated`)
+fn doc_text(comment: &str) -> String {
+    comment
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*').unwrap_or(line).trim()
+        })
+        .take_while(|line| !line.starts_with('@'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+struct Parser {
+    lexemes: Vec<Lexeme>,
+    index: usize,
+    /// Names of declarations that have been referred to, with the line of the reference
+    references: Vec<(String, usize)>,
+}
+
+impl Parser {
+    fn peek(&self, offset: usize) -> Option<&Token> {
+        self.lexemes
+            .get(self.index + offset)
+            .map(|lexeme| &lexeme.token)
+    }
+
+    /// The line of the next token (or of the last, at the end)
+    fn line(&self) -> usize {
+        self.lexemes
+            .get(self.index)
+            .or(self.lexemes.last())
+            .map_or(1, |lexeme| lexeme.line)
+    }
+
+    fn doc(&self) -> Option<String> {
+        self.lexemes
+            .get(self.index)
+            .and_then(|lexeme| lexeme.doc.clone())
+    }
+
+    fn next(&mut self) -> Result<Token, Error> {
+        let token = self
+            .peek(0)
+            .cloned()
+            .ok_or_else(|| Error::InvalidTypeScript(self.line()))?;
+        self.index += 1;
+
+        Ok(token)
+    }
+
+    fn is(&self, c: char) -> bool {
+        self.peek(0) == Some(&Token::Punctuation(c))
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let is_next = self.is(c);
+
+        if is_next {
+            self.index += 1;
+        }
+
+        is_next
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(Error::InvalidTypeScript(self.line()))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is_next = matches!(self.peek(0), Some(Token::Identifier(name)) if name == keyword);
+
+        if is_next {
+            self.index += 1;
+        }
+
+        is_next
+    }
+
+    fn identifier(&mut self) -> Result<String, Error> {
+        let line = self.line();
+
+        match self.next()? {
+            Token::Identifier(name) => Ok(name),
+            _ => Err(Error::InvalidTypeScript(line)),
+        }
+    }
+
+    /// An interface, type alias, or enum, with its line and name
+    fn declaration(&mut self) -> Result<(usize, String, Value), Error> {
+        let description = self.doc();
+        self.eat_keyword("export");
+        self.eat_keyword("declare");
+
+        let line = self.line();
+        let keyword = self.identifier()?;
+        let is_enum = keyword == "enum" || (keyword == "const" && self.eat_keyword("enum"));
+
+        if !is_enum && keyword != "interface" && keyword != "type" {
+            return Err(unsupported(line, &format!("`{}` statement", keyword)));
+        }
+
+        let name = self.identifier()?;
+
+        if self.is('<') {
+            return Err(unsupported(line, &format!("generic declaration {}", name)));
+        }
+
+        let schema = if is_enum {
+            self.enumeration()?
+        } else if keyword == "interface" {
+            let mut bases = vec![];
+
+            if self.eat_keyword("extends") {
+                loop {
+                    let line = self.line();
+                    let base = self.identifier()?;
+
+                    if self.is('<') || self.is('.') {
+                        return Err(unsupported(line, &format!("base type {}", base)));
+                    }
+
+                    bases.push(self.reference(base, line));
+
+                    if !self.eat(',') {
+                        break;
+                    }
+                }
+            }
+
+            self.expect('{')?;
+            let object = self.members()?;
+
+            if bases.is_empty() {
+                object
+            } else {
+                bases.push(object);
+                json!({ ALL_OF_KEY: bases })
+            }
+        } else {
+            self.expect('=')?;
+            self.schema()?
+        };
+
+        self.eat(';');
+
+        let mut result = Map::new();
+        result.insert(TITLE_KEY.to_string(), json!(name));
+
+        Ok((line, name, described(schema, description, result)))
+    }
+
+    /// The members of an object type, after the opening brace
+    fn members(&mut self) -> Result<Value, Error> {
+        let mut properties = Map::new();
+        let mut required = vec![];
+        let mut additional_properties = None;
+
+        while !self.eat('}') {
+            let description = self.doc();
+            let line = self.line();
+
+            // `readonly` is a modifier unless it's the name of the property.
+            if !matches!(
+                self.peek(1),
+                Some(Token::Punctuation(':' | '?' | '(' | '<'))
+            ) {
+                self.eat_keyword("readonly");
+            }
+
+            if self.eat('[') {
+                self.identifier()?;
+                self.expect(':')?;
+                self.schema()?;
+                self.expect(']')?;
+                self.expect(':')?;
+                additional_properties = Some(self.schema()?);
+            } else {
+                let name = match self.next()? {
+                    Token::Identifier(name) | Token::String(name) | Token::Number(name) => name,
+                    _ => return Err(Error::InvalidTypeScript(line)),
+                };
+                let mut optional = self.eat('?');
+
+                if self.is('(') || self.is('<') {
+                    return Err(unsupported(line, &format!("method {}", name)));
+                }
+
+                self.expect(':')?;
+                let (schema, undefined) = self.union()?;
+                optional |= undefined;
+
+                if !optional {
+                    required.push(json!(name));
+                }
+
+                properties.insert(name, described(schema, description, Map::new()));
+            }
+
+            if !self.eat(';') {
+                self.eat(',');
+            }
+        }
+
+        let mut result = Map::new();
+        result.insert(TYPE_KEY.to_string(), json!("object"));
+
+        if !properties.is_empty() {
+            result.insert(PROPERTIES_KEY.to_string(), Value::Object(properties));
+        }
+
+        if !required.is_empty() {
+            result.insert(REQUIRED_KEY.to_string(), Value::Array(required));
+        }
+
+        if let Some(additional_properties) = additional_properties {
+            result.insert(ADDITIONAL_PROPERTIES_KEY.to_string(), additional_properties);
+        }
+
+        Ok(Value::Object(result))
+    }
+
+    /// The members of an enum, as a string enum or (if any are numbers) constants with titles
+    fn enumeration(&mut self) -> Result<Value, Error> {
+        self.expect('{')?;
+
+        let mut members = vec![];
+        // The value of a member without an initializer, if it can have one
+        let mut next = Some(0);
+
+        while !self.eat('}') {
+            let line = self.line();
+            let name = match self.next()? {
+                Token::Identifier(name) | Token::String(name) => name,
+                _ => return Err(Error::InvalidTypeScript(line)),
+            };
+
+            let value = if self.eat('=') {
+                match self.next()? {
+                    Token::String(value) => json!(value),
+                    Token::Number(text) => json!(number(&text, line)?),
+                    Token::Punctuation('-') => json!(self.negative_number(line)?),
+                    _ => return Err(unsupported(line, "computed enum member")),
+                }
+            } else {
+                json!(next.ok_or_else(|| unsupported(line, "enum member without a value"))?)
+            };
+
+            next = value.as_i64().map(|value| value + 1);
+            members.push((name, value));
+
+            if !self.is('}') {
+                self.expect(',')?;
+            }
+        }
+
+        Ok(if members.iter().all(|(_, value)| value.is_string()) {
+            json!({ ENUM_KEY: members.into_iter().map(|(_, value)| value).collect::<Vec<_>>() })
+        } else {
+            json!({
+                ONE_OF_KEY: members
+                    .into_iter()
+                    .map(|(name, value)| json!({ TITLE_KEY: name, CONST_KEY: value }))
+                    .collect::<Vec<_>>()
+            })
+        })
+    }
+
+    /// A type that can't include `undefined`
+    fn schema(&mut self) -> Result<Value, Error> {
+        let line = self.line();
+
+        match self.union()? {
+            (schema, false) => Ok(schema),
+            _ => Err(unsupported(line, "undefined outside of a property")),
+        }
+    }
+
+    /// A union type, and whether it includes `undefined`
+    fn union(&mut self) -> Result<(Value, bool), Error> {
+        let line = self.line();
+        let mut members = vec![];
+        let mut undefined = false;
+
+        self.eat('|');
+
+        loop {
+            match self.intersection()? {
+                Some(schema) => members.push(schema),
+                None => undefined = true,
+            }
+
+            if !self.eat('|') {
+                break;
+            }
+        }
+
+        if members.is_empty() {
+            Err(unsupported(line, "undefined type"))
+        } else {
+            Ok((union_schema(members), undefined))
+        }
+    }
+
+    /// An intersection type, or `None` for `undefined`
+    fn intersection(&mut self) -> Result<Option<Value>, Error> {
+        let line = self.line();
+
+        self.eat('&');
+        let first = self.postfix()?;
+
+        if !self.is('&') {
+            return Ok(first);
+        }
+
+        let mut members = vec![first];
+
+        while self.eat('&') {
+            members.push(self.postfix()?);
+        }
+
+        members
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .map(|members| Some(json!({ ALL_OF_KEY: members })))
+            .ok_or_else(|| unsupported(line, "undefined in an intersection"))
+    }
+
+    /// A type with any array suffixes, or `None` for `undefined`
+    fn postfix(&mut self) -> Result<Option<Value>, Error> {
+        let line = self.line();
+        let mut schema = self.primary()?;
+
+        while self.is('[') {
+            if self.peek(1) != Some(&Token::Punctuation(']')) {
+                return Err(unsupported(line, "indexed access type"));
+            }
+
+            self.index += 2;
+
+            let items = schema.ok_or_else(|| unsupported(line, "array of undefined"))?;
+            schema = Some(json!({ TYPE_KEY: "array", ITEMS_KEY: items }));
+        }
+
+        Ok(schema)
+    }
+
+    fn primary(&mut self) -> Result<Option<Value>, Error> {
+        let line = self.line();
+
+        let schema = match self.next()? {
+            Token::Punctuation('(') => {
+                let schema = self.schema()?;
+                self.expect(')')?;
+                schema
+            }
+            Token::Punctuation('{') => self.members()?,
+            Token::Punctuation('[') => self.tuple()?,
+            Token::Punctuation('-') => json!({ CONST_KEY: self.negative_number(line)? }),
+            Token::String(value) => json!({ CONST_KEY: value }),
+            Token::Number(text) => json!({ CONST_KEY: number(&text, line)? }),
+            Token::Identifier(name) => return self.named(name, line),
+            Token::Punctuation(_) => return Err(Error::InvalidTypeScript(line)),
+        };
+
+        Ok(Some(schema))
+    }
+
+    /// The elements of a tuple type, after the opening bracket
+    fn tuple(&mut self) -> Result<Value, Error> {
+        let mut items = vec![];
+        // The number of elements before the first optional one
+        let mut min_items = None;
+
+        while !self.eat(']') {
+            let line = self.line();
+
+            if self.is('.') {
+                return Err(unsupported(line, "rest element"));
+            }
+
+            items.push(self.schema()?);
+
+            if self.eat('?') {
+                min_items.get_or_insert(items.len() - 1);
+            } else if min_items.is_some() {
+                return Err(Error::InvalidTypeScript(line));
+            }
+
+            if !self.is(']') {
+                self.expect(',')?;
+            }
+        }
+
+        Ok(json!({
+            TYPE_KEY: "array",
+            PREFIX_ITEMS_KEY: items,
+            MIN_ITEMS_KEY: min_items.unwrap_or(items.len()),
+            MAX_ITEMS_KEY: items.len(),
+        }))
+    }
+
+    /// A primitive, built-in generic, or declared type, or `None` for `undefined`
+    fn named(&mut self, name: String, line: usize) -> Result<Option<Value>, Error> {
+        if self.eat('<') {
+            let mut arguments = vec![self.schema()?];
+
+            while self.eat(',') {
+                arguments.push(self.schema()?);
+            }
+
+            self.expect('>')?;
+
+            let schema = match (name.as_str(), arguments.as_slice()) {
+                ("Array" | "ReadonlyArray", [items]) => {
+                    json!({ TYPE_KEY: "array", ITEMS_KEY: items })
+                }
+                ("Record", [keys, values]) => record(keys, values, line)?,
+                _ => return Err(unsupported(line, &format!("generic type {}", name))),
+            };
+
+            return Ok(Some(schema));
+        }
+
+        let schema = match name.as_str() {
+            "undefined" | "void" => return Ok(None),
+            "any" | "unknown" => json!({}),
+            "boolean" => json!({ TYPE_KEY: "boolean" }),
+            "null" => json!({ TYPE_KEY: "null" }),
+            "number" => json!({ TYPE_KEY: "number" }),
+            "object" => json!({ TYPE_KEY: "object" }),
+            "string" => json!({ TYPE_KEY: "string" }),
+            "false" => json!({ CONST_KEY: false }),
+            "true" => json!({ CONST_KEY: true }),
+            // Dates are serialized as ISO 8601 strings.
+            "Date" => json!({ TYPE_KEY: "string", FORMAT_KEY: "date-time" }),
+            name if UNREPRESENTABLE_TYPES.contains(&name) => {
+                return Err(unsupported(line, &format!("{} type", name)))
+            }
+            name if TYPE_OPERATORS.contains(&name) => {
+                return Err(unsupported(line, &format!("`{}` type", name)))
+            }
+            name if self.is('.') => {
+                return Err(unsupported(line, &format!("qualified name {}", name)))
+            }
+            _ => self.reference(name, line),
+        };
+
+        Ok(Some(schema))
+    }
+
+    fn reference(&mut self, name: String, line: usize) -> Value {
+        let schema = json!({ REF_KEY: format!("#/{}/{}", DEFS_KEY, reference_name(&name)) });
+        self.references.push((name, line));
+        schema
+    }
+
+    /// A number after a minus sign
+    fn negative_number(&mut self, line: usize) -> Result<serde_json::Number, Error> {
+        match self.next()? {
+            Token::Number(text) => number(&format!("-{}", text), line),
+            _ => Err(Error::InvalidTypeScript(line)),
+        }
+    }
+}
+
+/// A schema for a union, where string literals become an enum and `null` comes last
+fn union_schema(members: Vec<Value>) -> Value {
+    let null = json!({ TYPE_KEY: "null" });
+    let mut result = vec![];
+    let mut strings = vec![];
+    // Where the enum of string literals goes
+    let mut strings_index = None;
+    let mut nullable = false;
+
+    for member in members {
+        let literal = member
+            .as_object()
+            .filter(|fields| fields.len() == 1)
+            .and_then(|fields| fields.get(CONST_KEY))
+            .filter(|value| value.is_string());
+
+        if let Some(literal) = literal {
+            strings_index.get_or_insert(result.len());
+
+            if !strings.contains(literal) {
+                strings.push(literal.clone());
+            }
+        } else if member == null {
+            nullable = true;
+        } else if !result.contains(&member) {
+            result.push(member);
+        }
+    }
+
+    if let Some(index) = strings_index {
+        let schema = if strings.len() == 1 {
+            json!({ CONST_KEY: strings.remove(0) })
+        } else {
+            json!({ ENUM_KEY: strings })
+        };
+
+        result.insert(index, schema);
+    }
+
+    if nullable {
+        result.push(null);
+    }
+
+    if result.len() == 1 {
+        result.remove(0)
+    } else {
+        json!({ ANY_OF_KEY: result })
+    }
+}
+
+/// An object for `Record<K, V>`, where string literal keys are required properties
+fn record(keys: &Value, values: &Value, line: usize) -> Result<Value, Error> {
+    let names = match (keys.get(ENUM_KEY), keys.get(CONST_KEY)) {
+        (Some(Value::Array(names)), _) => names.clone(),
+        (_, Some(name @ Value::String(_))) => vec![name.clone()],
+        _ if *keys == json!({ TYPE_KEY: "string" }) => {
+            return Ok(json!({ TYPE_KEY: "object", ADDITIONAL_PROPERTIES_KEY: values }))
+        }
+        _ => return Err(unsupported(line, "record key type")),
+    };
+
+    let properties = names
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|name| (name.to_string(), values.clone()))
+        .collect::<Map<_, _>>();
+
+    Ok(json!({ TYPE_KEY: "object", PROPERTIES_KEY: properties, REQUIRED_KEY: names }))
+}
+
+/// Add a description (after the given fields) to the start of a schema
+fn described(schema: Value, description: Option<String>, mut fields: Map<String, Value>) -> Value {
+    if let Some(description) = description {
+        fields.insert(DESCRIPTION_KEY.to_string(), json!(description));
+    }
+
+    match schema {
+        Value::Object(schema) => {
+            fields.extend(schema);
+            Value::Object(fields)
+        }
+        schema => schema,
+    }
+}
+
+fn number(text: &str, line: usize) -> Result<serde_json::Number, Error> {
+    serde_json::from_str(text).map_err(|_| Error::InvalidTypeScript(line))
+}
+
+fn unsupported(line: usize, construct: &str) -> Error {
+    Error::UnsupportedTypeScript(line, construct.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typescript_import() {
+        let source = r#"
+import type { Ignored } from "./elsewhere";
+
+/** A customer order */
+export interface Order extends Entity {
+  readonly id: number;
+  /**
+   * Where it is
+   * @see Status
+   */
+  status: Status;
+  items: Array<LineItem>;
+  note?: string | null;
+  shipped: Date | undefined;
+  "tracking-codes": [string, number?];
+  labels: Record<string, string>;
+}
+
+interface Entity { created: Date }
+
+export type LineItem = { sku: string; quantity: 1 | 2 | 3, kind: "physical" | "digital" }[];
+
+export enum Status { Open = "open", Closed = "closed" }
+
+const enum Priority { Low = -1, Normal, High }
+"#;
+
+        let error = from_typescript(source, "orders").unwrap_err();
+        assert!(matches!(error, Error::UnsupportedTypeScript(2, _)));
+
+        let source = source.replace("import type { Ignored } from \"./elsewhere\";", "");
+
+        assert_eq!(
+            from_typescript(&source, "orders").unwrap(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "/orders",
+                "$defs": {
+                    "Order": {
+                        "title": "Order",
+                        "description": "A customer order",
+                        "allOf": [
+                            { "$ref": "#/$defs/Entity" },
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "id": { "type": "number" },
+                                    "status": {
+                                        "description": "Where it is",
+                                        "$ref": "#/$defs/Status"
+                                    },
+                                    "items": {
+                                        "type": "array",
+                                        "items": { "$ref": "#/$defs/LineItem" }
+                                    },
+                                    "note": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                                    "shipped": { "type": "string", "format": "date-time" },
+                                    "tracking-codes": {
+                                        "type": "array",
+                                        "prefixItems": [{ "type": "string" }, { "type": "number" }],
+                                        "minItems": 1,
+                                        "maxItems": 2
+                                    },
+                                    "labels": {
+                                        "type": "object",
+                                        "additionalProperties": { "type": "string" }
+                                    }
+                                },
+                                "required": ["id", "status", "items", "tracking-codes", "labels"]
+                            }
+                        ]
+                    },
+                    "Entity": {
+                        "title": "Entity",
+                        "type": "object",
+                        "properties": { "created": { "type": "string", "format": "date-time" } },
+                        "required": ["created"]
+                    },
+                    "LineItem": {
+                        "title": "LineItem",
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "sku": { "type": "string" },
+                                "quantity": {
+                                    "anyOf": [{ "const": 1 }, { "const": 2 }, { "const": 3 }]
+                                },
+                                "kind": { "enum": ["physical", "digital"] }
+                            },
+                            "required": ["sku", "quantity", "kind"]
+                        }
+                    },
+                    "Status": { "title": "Status", "enum": ["open", "closed"] },
+                    "Priority": {
+                        "title": "Priority",
+                        "oneOf": [
+                            { "title": "Low", "const": -1 },
+                            { "title": "Normal", "const": 0 },
+                            { "title": "High", "const": 1 }
+                        ]
+                    }
+                }
+            })
+        );
+
+        let error = from_typescript("type A = { b: B };", "a").unwrap_err();
+        assert!(matches!(error, Error::UnknownTypeScriptType(1, name) if name == "B"));
+    }
+}
@@ -0,0 +1,292 @@
+use super::constants::*;
+use super::schema::DRAFT_2020_12_URI;
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+
+mod csv;
+
+pub use csv::{records, CsvInference, Error as CsvError, Records};
+
+/// A schema inferred from instances, which are observed one at a time
+///
+/// Only counts are kept (not the instances), so memory use depends on the number of distinct
+/// fields, not the number of instances.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Inference {
+    count: usize,
+    nulls: usize,
+    booleans: usize,
+    integers: usize,
+    /// Numbers that aren't integers
+    numbers: usize,
+    strings: usize,
+    arrays: usize,
+    items: Option<Box<Inference>>,
+    objects: usize,
+    properties: IndexMap<String, Inference>,
+}
+
+/// How often a field appears in the objects that could contain it
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldFrequency {
+    /// A JSON Pointer to the field, with `*` for the items of arrays
+    pub pointer: String,
+    pub count: usize,
+    pub total: usize,
+}
+
+impl FieldFrequency {
+    /// The proportion of the objects that could contain the field that do
+    pub fn proportion(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.count as f64 / self.total as f64
+        }
+    }
+}
+
+impl Inference {
+    /// The number of instances observed
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn observe(&mut self, value: &Value) {
+        self.count += 1;
+
+        match value {
+            Value::Null => self.nulls += 1,
+            Value::Bool(_) => self.booleans += 1,
+            Value::Number(number) if number.is_f64() => self.numbers += 1,
+            Value::Number(_) => self.integers += 1,
+            Value::String(_) => self.strings += 1,
+            Value::Array(values) => {
+                self.arrays += 1;
+
+                let items = self.items.get_or_insert_with(Default::default);
+
+                for value in values {
+                    items.observe(value);
+                }
+            }
+            Value::Object(fields) => {
+                self.objects += 1;
+
+                for (key, value) in fields {
+                    self.properties
+                        .entry(key.clone())
+                        .or_default()
+                        .observe(value);
+                }
+            }
+        }
+    }
+
+    /// Combine the observations of two inferences
+    pub fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        self.nulls += other.nulls;
+        self.booleans += other.booleans;
+        self.integers += other.integers;
+        self.numbers += other.numbers;
+        self.strings += other.strings;
+        self.arrays += other.arrays;
+        self.objects += other.objects;
+
+        if let Some(other_items) = other.items {
+            match &mut self.items {
+                Some(items) => items.merge(*other_items),
+                None => self.items = Some(other_items),
+            }
+        }
+
+        for (key, other_property) in other.properties {
+            self.properties
+                .entry(key)
+                .or_default()
+                .merge(other_property);
+        }
+    }
+
+    /// A 2020-12 schema for the instances observed
+    ///
+    /// Properties are required if they appeared in every object, and integers and other numbers
+    /// together are numbers.
+    pub fn to_schema(&self) -> Value {
+        let mut result = Map::new();
+        result.insert(SCHEMA_KEY.to_string(), json!(DRAFT_2020_12_URI));
+
+        result.extend(self.sub_schema());
+
+        Value::Object(result)
+    }
+
+    /// The frequency of every field, depth-first
+    pub fn frequencies(&self) -> Vec<FieldFrequency> {
+        let mut result = vec![];
+        self.frequencies_rec(String::new(), &mut result);
+        result
+    }
+
+    fn sub_schema(&self) -> Map<String, Value> {
+        let mut types = vec![];
+
+        if self.arrays > 0 {
+            types.push("array");
+        }
+        if self.booleans > 0 {
+            types.push("boolean");
+        }
+        if self.numbers > 0 {
+            types.push("number");
+        } else if self.integers > 0 {
+            types.push("integer");
+        }
+        if self.nulls > 0 {
+            types.push("null");
+        }
+        if self.objects > 0 {
+            types.push("object");
+        }
+        if self.strings > 0 {
+            types.push("string");
+        }
+
+        let mut result = Map::new();
+
+        match types.as_slice() {
+            [] => {}
+            [single] => {
+                result.insert(TYPE_KEY.to_string(), json!(single));
+            }
+            types => {
+                result.insert(TYPE_KEY.to_string(), json!(types));
+            }
+        }
+
+        if self.objects > 0 {
+            let properties = self
+                .properties
+                .iter()
+                .map(|(key, property)| (key.clone(), Value::Object(property.sub_schema())))
+                .collect::<Map<_, _>>();
+            let required = self
+                .properties
+                .iter()
+                .filter(|(_, property)| property.count == self.objects)
+                .map(|(key, _)| json!(key))
+                .collect::<Vec<_>>();
+
+            if !properties.is_empty() {
+                result.insert(PROPERTIES_KEY.to_string(), Value::Object(properties));
+            }
+
+            if !required.is_empty() {
+                result.insert(REQUIRED_KEY.to_string(), Value::Array(required));
+            }
+        }
+
+        if let Some(items) = self.items.as_ref().filter(|items| items.count > 0) {
+            result.insert(ITEMS_KEY.to_string(), Value::Object(items.sub_schema()));
+        }
+
+        result
+    }
+
+    fn frequencies_rec(&self, pointer: String, acc: &mut Vec<FieldFrequency>) {
+        for (key, property) in &self.properties {
+            let pointer = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+
+            acc.push(FieldFrequency {
+                pointer: pointer.clone(),
+                count: property.count,
+                total: self.objects,
+            });
+
+            property.frequencies_rec(pointer, acc);
+        }
+
+        if let Some(items) = &self.items {
+            items.frequencies_rec(format!("{}/*", pointer), acc);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inference() {
+        let instances = [
+            json!({ "id": 1, "name": "a", "tags": ["x"], "user": { "id": 1 } }),
+            json!({ "id": 2, "name": null, "tags": [], "score": 1.5 }),
+            json!({ "id": 3, "tags": [{ "k/v": 1 }], "score": 2, "user": { "id": 2, "admin": true } }),
+        ];
+
+        // Observing in two parts and merging is the same as observing everything.
+        let mut inference = Inference::default();
+        let mut rest = Inference::default();
+        inference.observe(&instances[0]);
+        rest.observe(&instances[1]);
+        rest.observe(&instances[2]);
+        inference.merge(rest);
+
+        let mut expected = Inference::default();
+        for instance in &instances {
+            expected.observe(instance);
+        }
+
+        assert_eq!(inference, expected);
+        assert_eq!(inference.count(), 3);
+
+        assert_eq!(
+            inference.to_schema(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer" },
+                    "name": { "type": ["null", "string"] },
+                    "tags": {
+                        "type": "array",
+                        "items": {
+                            "type": ["object", "string"],
+                            "properties": { "k/v": { "type": "integer" } },
+                            "required": ["k/v"]
+                        }
+                    },
+                    "user": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "admin": { "type": "boolean" }
+                        },
+                        "required": ["id"]
+                    },
+                    "score": { "type": "number" }
+                },
+                "required": ["id", "tags"]
+            })
+        );
+
+        assert_eq!(
+            inference
+                .frequencies()
+                .into_iter()
+                .map(|frequency| (frequency.pointer, frequency.count, frequency.total))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/id".to_string(), 3, 3),
+                ("/name".to_string(), 2, 3),
+                ("/tags".to_string(), 3, 3),
+                ("/tags/*/k~1v".to_string(), 1, 1),
+                ("/user".to_string(), 2, 3),
+                ("/user/id".to_string(), 2, 2),
+                ("/user/admin".to_string(), 1, 2),
+                ("/score".to_string(), 2, 3),
+            ]
+        );
+    }
+}
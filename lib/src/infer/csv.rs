@@ -0,0 +1,404 @@
+use super::FieldFrequency;
+use crate::constants::*;
+use crate::schema::DRAFT_2020_12_URI;
+use crate::validate::formats::{is_date, is_date_time};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::io::BufRead;
+
+static INTEGER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[+-]?(0|[1-9][0-9]*)$").unwrap());
+static NUMBER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[+-]?((0|[1-9][0-9]*)(\.[0-9]+)?|\.[0-9]+)([eE][+-]?[0-9]+)?$").unwrap()
+});
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    /// A quoted cell that's still open at the end of the input (with the line it starts on)
+    #[error("Unterminated quote")]
+    UnterminatedQuote(usize),
+}
+
+/// A schema for the rows of a CSV or TSV file, inferred one record at a time
+///
+/// Each column is a property, and its type is the narrowest of integer, number, boolean, date,
+/// date-time, and string that fits every non-empty cell (dates and date-times are checked the same
+/// way as the `date` and `date-time` formats). Columns with empty cells are nullable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvInference {
+    rows: usize,
+    columns: Vec<Column>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Column {
+    name: String,
+    empty: usize,
+    integers: usize,
+    numbers: usize,
+    booleans: usize,
+    dates: usize,
+    date_times: usize,
+    strings: usize,
+}
+
+impl CsvInference {
+    /// Start an inference for the columns in a header record
+    pub fn new(header: Vec<String>) -> Self {
+        Self {
+            rows: 0,
+            columns: header
+                .into_iter()
+                .map(|name| Column {
+                    name,
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of rows observed
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The column names
+    pub fn header(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|column| column.name.as_str())
+    }
+
+    /// How often each column is non-empty
+    pub fn frequencies(&self) -> Vec<FieldFrequency> {
+        self.columns
+            .iter()
+            .map(|column| FieldFrequency {
+                pointer: format!("/{}", column.name.replace('~', "~0").replace('/', "~1")),
+                count: self.rows - column.empty,
+                total: self.rows,
+            })
+            .collect()
+    }
+
+    /// Observe a record, where missing cells are empty and extra cells are ignored
+    pub fn observe(&mut self, record: &[String]) {
+        self.rows += 1;
+
+        for (index, column) in self.columns.iter_mut().enumerate() {
+            let cell = record.get(index).map_or("", |cell| cell.as_str());
+
+            if cell.is_empty() {
+                column.empty += 1;
+            } else if INTEGER.is_match(cell) {
+                column.integers += 1;
+            } else if NUMBER.is_match(cell) {
+                column.numbers += 1;
+            } else if cell.eq_ignore_ascii_case("true") || cell.eq_ignore_ascii_case("false") {
+                column.booleans += 1;
+            } else if is_date_time(cell) {
+                column.date_times += 1;
+            } else if is_date(cell) {
+                column.dates += 1;
+            } else {
+                column.strings += 1;
+            }
+        }
+    }
+
+    /// A 2020-12 schema for a row, with every column required
+    pub fn to_schema(&self) -> Value {
+        let properties = self
+            .columns
+            .iter()
+            .map(|column| (column.name.clone(), column.schema()))
+            .collect::<Map<_, _>>();
+        let required = self
+            .columns
+            .iter()
+            .map(|column| json!(column.name))
+            .collect::<Vec<_>>();
+
+        json!({
+            SCHEMA_KEY: DRAFT_2020_12_URI,
+            TYPE_KEY: "object",
+            PROPERTIES_KEY: properties,
+            REQUIRED_KEY: required,
+        })
+    }
+}
+
+impl Column {
+    fn schema(&self) -> Value {
+        let values = self.integers
+            + self.numbers
+            + self.booleans
+            + self.dates
+            + self.date_times
+            + self.strings;
+
+        let (type_name, format) = if values == 0 {
+            return json!({ TYPE_KEY: "null" });
+        } else if self.integers == values {
+            ("integer", None)
+        } else if self.integers + self.numbers == values {
+            ("number", None)
+        } else if self.booleans == values {
+            ("boolean", None)
+        } else if self.date_times == values {
+            ("string", Some("date-time"))
+        } else if self.dates == values {
+            ("string", Some("date"))
+        } else {
+            ("string", None)
+        };
+
+        let mut result = Map::new();
+
+        if self.empty > 0 {
+            result.insert(TYPE_KEY.to_string(), json!([type_name, "null"]));
+        } else {
+            result.insert(TYPE_KEY.to_string(), json!(type_name));
+        }
+
+        if let Some(format) = format {
+            result.insert(FORMAT_KEY.to_string(), json!(format));
+        }
+
+        Value::Object(result)
+    }
+}
+
+/// Read the records of a CSV file (with quoted cells) or a TSV file (if the delimiter is a tab,
+/// which has no quoting), one at a time
+pub fn records<R: BufRead>(reader: R, delimiter: char) -> Records<R> {
+    Records {
+        reader,
+        delimiter,
+        lines: 0,
+    }
+}
+
+pub struct Records<R> {
+    reader: R,
+    delimiter: char,
+    /// The number of lines read so far
+    lines: usize,
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<Vec<String>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = vec![];
+        let mut cell = String::new();
+        let mut quoted = false;
+        let mut line = String::new();
+        let mut start = self.lines + 1;
+
+        loop {
+            line.clear();
+
+            match self.reader.read_line(&mut line) {
+                Ok(0) if quoted => return Some(Err(Error::UnterminatedQuote(start))),
+                Ok(0) if record.is_empty() && cell.is_empty() => return None,
+                Ok(0) => break,
+                Ok(_) => self.lines += 1,
+                Err(error) => return Some(Err(error.into())),
+            }
+
+            let content = line.trim_end_matches(['\n', '\r']);
+
+            // Blank lines between records are skipped.
+            if content.is_empty() && record.is_empty() && !quoted {
+                start = self.lines + 1;
+                continue;
+            }
+
+            if self.delimiter == '\t' {
+                record.extend(content.split('\t').map(str::to_string));
+                return Some(Ok(record));
+            }
+
+            let mut chars = content.chars().peekable();
+
+            while let Some(c) = chars.next() {
+                if quoted {
+                    if c == '"' {
+                        if chars.peek() == Some(&'"') {
+                            chars.next();
+                            cell.push('"');
+                        } else {
+                            quoted = false;
+                        }
+                    } else {
+                        cell.push(c);
+                    }
+                } else if c == '"' && cell.is_empty() {
+                    quoted = true;
+                } else if c == self.delimiter {
+                    record.push(std::mem::take(&mut cell));
+                } else {
+                    cell.push(c);
+                }
+            }
+
+            if quoted {
+                // The line break is part of the quoted cell.
+                cell.push_str(&line[content.len()..]);
+            } else {
+                break;
+            }
+        }
+
+        record.push(cell);
+
+        Some(Ok(record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_inference() {
+        let input = "id,price,active,created,day,name,zip,notes,nothing\n\
+            1,9.99,true,2024-01-01T10:00:00Z,2024-01-01,\"Smith, J\",02134,,\n\
+            2,10,FALSE,2024-01-02t11:30:00+01:00,2024-01-02,\"A \"\"quoted\"\"\nname\",10001,x,\n\
+            3,-1e3,false,2024-01-03T00:00:00.5Z,2024-01-03,C,94103\n";
+
+        let mut rows = records(input.as_bytes(), ',');
+        let mut inference = CsvInference::new(rows.next().unwrap().unwrap());
+
+        for record in rows {
+            inference.observe(&record.unwrap());
+        }
+
+        assert_eq!(inference.rows(), 3);
+        assert_eq!(
+            inference
+                .frequencies()
+                .into_iter()
+                .map(|frequency| frequency.count)
+                .collect::<Vec<_>>(),
+            vec![3, 3, 3, 3, 3, 3, 3, 1, 0]
+        );
+        assert_eq!(
+            inference.to_schema(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer" },
+                    "price": { "type": "number" },
+                    "active": { "type": "boolean" },
+                    "created": { "type": "string", "format": "date-time" },
+                    "day": { "type": "string", "format": "date" },
+                    "name": { "type": "string" },
+                    "zip": { "type": "string" },
+                    "notes": { "type": ["string", "null"] },
+                    "nothing": { "type": "null" }
+                },
+                "required": [
+                    "id", "price", "active", "created", "day", "name", "zip", "notes", "nothing"
+                ]
+            })
+        );
+
+        let tsv = records("a\tb\n\n\"x\"\t1\n".as_bytes(), '\t')
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(tsv, vec![vec!["a", "b"], vec!["\"x\"", "1"]]);
+    }
+
+    #[test]
+    fn csv_records_edge_cases() {
+        let parse = |input: &str| {
+            records(input.as_bytes(), ',')
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        assert!(parse("").is_empty());
+        assert_eq!(
+            parse("a,b\r\n1,2\r\n"),
+            vec![vec!["a", "b"], vec!["1", "2"]]
+        );
+        assert_eq!(parse("a,\"b\n\nc\",d\r\n"), vec![vec!["a", "b\n\nc", "d"]]);
+        assert_eq!(parse("a\"b,\n"), vec![vec!["a\"b", ""]]);
+        assert!(matches!(
+            records("a\n\nx,\"open\nmore".as_bytes(), ',').nth(1),
+            Some(Err(Error::UnterminatedQuote(3)))
+        ));
+    }
+
+    #[test]
+    fn csv_inference_edge_cases() {
+        let mut inference = CsvInference::new(
+            ["a/b", "amount", "mixed", "when", "sparse", "day", "time"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        );
+
+        assert_eq!(
+            inference.to_schema()["properties"]["amount"],
+            json!({ "type": "null" })
+        );
+
+        for record in [
+            vec![
+                "1",
+                "+1",
+                "true",
+                "2024-01-01",
+                "",
+                "2024-02-29",
+                "2024-01-01T00:00:00Z",
+            ],
+            vec![
+                "2",
+                ".5",
+                "1",
+                "2024-01-01T00:00:00Z",
+                "x",
+                "2024-02-30",
+                "2024-01-01 00:00:00Z",
+            ],
+            vec!["3"],
+        ] {
+            inference.observe(&record.into_iter().map(str::to_string).collect::<Vec<_>>());
+        }
+
+        assert_eq!(
+            inference
+                .frequencies()
+                .into_iter()
+                .map(|frequency| (frequency.pointer, frequency.count))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/a~1b".to_string(), 3),
+                ("/amount".to_string(), 2),
+                ("/mixed".to_string(), 2),
+                ("/when".to_string(), 2),
+                ("/sparse".to_string(), 1),
+                ("/day".to_string(), 2),
+                ("/time".to_string(), 2)
+            ]
+        );
+        assert_eq!(
+            inference.to_schema()["properties"],
+            json!({
+                "a/b": { "type": "integer" },
+                "amount": { "type": ["number", "null"] },
+                "mixed": { "type": ["string", "null"] },
+                "when": { "type": ["string", "null"] },
+                "sparse": { "type": ["string", "null"] },
+                "day": { "type": ["string", "null"] },
+                "time": { "type": ["string", "null"] }
+            })
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use serde_json::Value;
+
+/// Parse JSON that may contain `//` and `/* */` comments and trailing commas
+///
+/// Errors have the line and column of the problem in the original text.
+pub fn from_str(input: &str) -> serde_json::Result<Value> {
+    serde_json::from_str(&strip(input))
+}
+
+/// Replace comments and trailing commas with spaces
+///
+/// Line breaks in comments are kept and every other byte becomes a space, so positions in the
+/// result are the same as in the input.
+pub fn strip(input: &str) -> String {
+    let mut bytes = input.as_bytes().to_vec();
+    let mut index = 0;
+
+    // Comments first, so that only whitespace can come between a comma and a closing bracket.
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => index = skip_string(&bytes, index),
+            b'/' if bytes.get(index + 1) == Some(&b'/') => {
+                while index < bytes.len() && bytes[index] != b'\n' {
+                    blank(&mut bytes, index);
+                    index += 1;
+                }
+            }
+            b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                let end = bytes[index + 2..]
+                    .windows(2)
+                    .position(|window| window == b"*/")
+                    .map_or(bytes.len(), |position| index + 2 + position + 2);
+
+                while index < end {
+                    blank(&mut bytes, index);
+                    index += 1;
+                }
+            }
+            _ => index += 1,
+        }
+    }
+
+    let mut index = 0;
+    let mut previous = None;
+    let mut last_comma = None;
+
+    while index < bytes.len() {
+        let byte = bytes[index];
+
+        match byte {
+            b'"' => {
+                last_comma = None;
+                previous = Some(byte);
+                index = skip_string(&bytes, index);
+                continue;
+            }
+            // A comma is only trailing if it follows a value.
+            b',' if !matches!(previous, None | Some(b'[' | b'{' | b',')) => {
+                last_comma = Some(index);
+            }
+            b'}' | b']' => {
+                if let Some(comma) = last_comma.take() {
+                    bytes[comma] = b' ';
+                }
+            }
+            byte if byte.is_ascii_whitespace() => {
+                index += 1;
+                continue;
+            }
+            _ => last_comma = None,
+        }
+
+        previous = Some(byte);
+        index += 1;
+    }
+
+    // Only whole characters (and ASCII commas) have been replaced.
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+/// The index after the end of the string starting at the given index
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let mut index = start + 1;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' => return index + 1,
+            _ => index += 1,
+        }
+    }
+
+    index
+}
+
+fn blank(bytes: &mut [u8], index: usize) {
+    if bytes[index] != b'\n' && bytes[index] != b'\r' {
+        bytes[index] = b' ';
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn jsonc_parsing() {
+        let input = r#"{
+  // The type
+  "type": "object", /* a "comment" with // inside */
+  "properties": {
+    "url": { "type": "string", "default": "http://example.com/*", },
+    "tags": { "type": "array", "items": [1, 2, /* last */ ] },
+  },
+}"#;
+
+        assert_eq!(strip(input).len(), input.len());
+        assert_eq!(strip(input).lines().count(), input.lines().count());
+        assert_eq!(
+            from_str(input).unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "default": "http://example.com/*" },
+                    "tags": { "type": "array", "items": [1, 2] }
+                }
+            })
+        );
+
+        let error = from_str("{\n  /* é */ \"a\": 1,\n  \"b\" 2\n}").unwrap_err();
+        assert_eq!((error.line(), error.column()), (3, 7));
+
+        assert!(from_str("[1,,]").is_err());
+        assert!(from_str("[,]").is_err());
+    }
+
+    #[test]
+    fn jsonc_edge_cases() {
+        assert_eq!(
+            from_str("[\"a\\\"//b\", \"c/*\", 1 // one\r\n]").unwrap(),
+            json!(["a\"//b", "c/*", 1])
+        );
+        assert_eq!(
+            from_str("{\"a\": [\"é\", // last\n],}").unwrap(),
+            json!({ "a": ["é"] })
+        );
+
+        // An unterminated block comment runs to the end of the input.
+        assert_eq!(strip("1 /* open\n*"), "1        \n ");
+        assert_eq!(from_str("1 /* open").unwrap(), json!(1));
+
+        assert!(from_str("{,}").is_err());
+        assert!(from_str("[1 /* , */ 2]").is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Interned keys for the typed schema model
+//!
+//! Property names and other keys repeat many times across a large schema (and across the paths in
+//! lint issues), so keys read with the same [`Interner`] share an allocation for each distinct
+//! value. Nothing is shared between interners, so memory is released when a run's interner and
+//! keys are dropped.
+
+use serde::de::{DeserializeOwned, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+thread_local! {
+    /// The keys of the interner that's deserializing on this thread, if any
+    static ACTIVE: RefCell<Option<HashSet<Arc<str>>>> = const { RefCell::new(None) };
+}
+
+/// A shared, immutable string that's cheap to clone, and that compares and hashes like a `str`
+#[derive(Clone, Ord, PartialOrd)]
+pub struct Key(Arc<str>);
+
+impl Key {
+    /// A key that isn't shared with any other (use an [`Interner`] for keys that repeat)
+    pub fn new(value: &str) -> Self {
+        Self(Arc::from(value))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the keys share an allocation (which keys with equal values from the same interner
+    /// always do)
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The keys for one run, where each distinct value is only allocated the first time it's seen
+#[derive(Debug, Default)]
+pub struct Interner {
+    keys: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    pub fn key(&mut self, value: &str) -> Key {
+        intern(&mut self.keys, value)
+    }
+
+    /// Deserialize a value, interning any keys it contains
+    pub fn deserialize<T: DeserializeOwned>(
+        &mut self,
+        value: serde_json::Value,
+    ) -> Result<T, serde_json::Error> {
+        let previous = ACTIVE.with(|active| active.replace(Some(std::mem::take(&mut self.keys))));
+        let result = serde_json::from_value(value);
+        self.keys = ACTIVE
+            .with(|active| active.replace(previous))
+            .unwrap_or_default();
+
+        result
+    }
+}
+
+fn intern(keys: &mut HashSet<Arc<str>>, value: &str) -> Key {
+    match keys.get(value) {
+        Some(key) => Key(key.clone()),
+        None => {
+            let key: Arc<str> = Arc::from(value);
+            keys.insert(key.clone());
+
+            Key(key)
+        }
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.0 == other.0
+    }
+}
+
+impl Eq for Key {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl Deref for Key {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Key {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Key {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&String> for Key {
+    fn from(value: &String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Key {
+    fn from(value: String) -> Self {
+        Self::new(&value)
+    }
+}
+
+impl From<Key> for String {
+    fn from(value: Key) -> Self {
+        value.0.to_string()
+    }
+}
+
+impl PartialEq<str> for Key {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Key {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Key {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(KeyVisitor)
+    }
+}
+
+/// Interns strings (with the active interner, if there is one) without first copying them into a
+/// `String`, where the format allows it
+struct KeyVisitor;
+
+impl Visitor<'_> for KeyVisitor {
+    type Value = Key;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Key, E> {
+        Ok(ACTIVE.with(|active| match active.borrow_mut().as_mut() {
+            Some(keys) => intern(keys, value),
+            None => Key::new(value),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn interning() {
+        let mut interner = Interner::default();
+        let first = interner.key("name");
+        let second = interner
+            .deserialize::<Vec<Key>>(serde_json::json!(["name", "name"]))
+            .unwrap();
+
+        assert!(first.ptr_eq(&second[0]));
+        assert!(first.ptr_eq(&second[1]));
+        assert_eq!(first, "name");
+        assert_eq!(format!("{:?}", first), "\"name\"");
+        assert_eq!(serde_json::to_string(&second[0]).unwrap(), "\"name\"");
+
+        let mut map = IndexMap::new();
+        map.insert(first.clone(), 1);
+
+        assert_eq!(map.get("name"), Some(&1));
+
+        // Keys from different interners (or none) are equal but not shared.
+        let other = Interner::default().key("name");
+        let plain = serde_json::from_str::<Key>("\"name\"").unwrap();
+
+        assert!(!first.ptr_eq(&other));
+        assert!(!first.ptr_eq(&plain));
+        assert_eq!(first, other);
+        assert_eq!(map.get(&plain), Some(&1));
+    }
+
+    #[test]
+    fn separate_interners() {
+        let mut first = Interner::default();
+        let mut second = Interner::default();
+        let key = first.key("a");
+
+        let first_keys = first
+            .deserialize::<Vec<Key>>(serde_json::json!(["a"]))
+            .unwrap();
+        let second_keys = second
+            .deserialize::<Vec<Key>>(serde_json::json!(["a"]))
+            .unwrap();
+
+        assert!(key.ptr_eq(&first_keys[0]));
+        assert!(!key.ptr_eq(&second_keys[0]));
+        assert!(second.key("a").ptr_eq(&second_keys[0]));
+    }
+}
@@ -1,6 +1,9 @@
+use super::util::{walk, walk_mut, Children};
 use super::{constants::*, path::Path};
-use serde_json::Value;
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Value};
 use std::cmp::Ordering;
+use std::collections::HashSet;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct KeyOrderMismatch<'a> {
@@ -9,55 +12,289 @@ pub struct KeyOrderMismatch<'a> {
     pub second: &'a str,
 }
 
-pub fn check_key_order(value: &Value) -> Vec<KeyOrderMismatch> {
-    super::util::nodes_with_path(value)
-        .iter()
-        .filter(|(path, _)| !path.allows_arbitrary_keys())
-        .filter_map(|(path, value)| {
-            value.as_object().and_then(|fields| {
-                let keys = fields.keys().map(|key| Key(key)).collect::<Vec<_>>();
-
-                keys.windows(2)
-                    .find(|window| window[0] >= window[1])
-                    .map(|bad_window| KeyOrderMismatch {
-                        path: path.clone(),
-                        first: bad_window[0].0,
-                        second: bad_window[1].0,
-                    })
-            })
-        })
-        .collect()
+pub fn check_key_order(value: &Value) -> Vec<KeyOrderMismatch<'_>> {
+    let mut result = vec![];
+
+    walk(value, |path, value| {
+        if path.allows_arbitrary_keys() {
+            return Children::Visit;
+        }
+
+        if let Some(fields) = value.as_object() {
+            let keys = fields.keys().map(|key| Key(key)).collect::<Vec<_>>();
+
+            if let Some(bad_window) = keys.windows(2).find(|window| window[0] >= window[1]) {
+                result.push(KeyOrderMismatch {
+                    path: path.to_path(),
+                    first: bad_window[0].0,
+                    second: bad_window[1].0,
+                });
+            }
+        }
+
+        Children::Visit
+    });
+
+    result
+}
+
+/// Reorder the keys of every object (other than those with arbitrary keys) to match the order
+/// expected by `check_key_order`
+///
+/// Keys without a fixed position keep their relative order.
+pub fn canonicalize(value: &mut Value) {
+    walk_mut(value, |path, value| {
+        if let Some(fields) = value
+            .as_object_mut()
+            .filter(|_| !path.allows_arbitrary_keys())
+        {
+            let mut entries = std::mem::take(fields).into_iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| Key(key).position());
+            fields.extend(entries);
+        }
+
+        Children::Visit
+    })
+}
+
+/// An order for the definitions in `$defs`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DefOrder {
+    Alphabetical,
+    /// Definitions come before the definitions that refer to them (except in cycles), and otherwise
+    /// keep their relative order
+    Dependency,
+}
+
+/// Reorder the top-level `$defs` of a schema, leaving everything else unchanged
+pub fn sort_defs(value: &mut Value, order: DefOrder) {
+    let id = value
+        .get(ID_KEY)
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let defs = match value.get_mut(DEFS_KEY).and_then(Value::as_object_mut) {
+        Some(defs) => defs,
+        None => return,
+    };
+
+    match order {
+        DefOrder::Alphabetical => defs.sort_keys(),
+        DefOrder::Dependency => {
+            let mut visited = HashSet::new();
+            let mut names = vec![];
+
+            for name in defs.keys() {
+                visit_def(defs, id.as_deref(), name, &mut visited, &mut names);
+            }
+
+            let mut remaining = std::mem::take(defs);
+
+            for name in names {
+                if let Some(def) = remaining.shift_remove(&name) {
+                    defs.insert(name, def);
+                }
+            }
+        }
+    }
+}
+
+fn visit_def(
+    defs: &serde_json::Map<String, Value>,
+    id: Option<&str>,
+    name: &str,
+    visited: &mut HashSet<String>,
+    acc: &mut Vec<String>,
+) {
+    if let Some(def) = defs.get(name) {
+        if visited.insert(name.to_string()) {
+            let mut dependencies = vec![];
+            super::refactor::internal_refs(def, id, &mut dependencies);
+
+            for dependency in dependencies {
+                visit_def(defs, id, &dependency, visited, acc);
+            }
+
+            acc.push(name.to_string());
+        }
+    }
 }
 
+/// Format a schema with canonical key order, two-space indentation, and a trailing newline
+pub fn format(value: &Value) -> String {
+    format_with_indent(value, 2)
+}
+
+/// Format a schema with canonical key order, the given number of spaces of indentation, and a
+/// trailing newline
+pub fn format_with_indent(value: &Value, indent: usize) -> String {
+    let mut value = value.clone();
+    canonicalize(&mut value);
+
+    let indent = " ".repeat(indent);
+    let mut output = vec![];
+    let mut serializer = serde_json::Serializer::with_formatter(
+        &mut output,
+        PrettyFormatter::with_indent(indent.as_bytes()),
+    );
+    // Writing a value to a vector cannot fail, and the output is always valid UTF-8.
+    value.serialize(&mut serializer).unwrap();
+    output.push(b'\n');
+
+    String::from_utf8(output).unwrap()
+}
+
+/// Format a schema as YAML with canonical key order and the given number of spaces of indentation
+pub fn format_yaml_with_indent(value: &Value, indent: usize) -> String {
+    let mut value = value.clone();
+    canonicalize(&mut value);
+
+    super::yaml::to_string_with_indent(&value, indent)
+}
+
+/// Keys that must appear first, in this order
+const LEADING_KEYS: [&str; 8] = [
+    ID_KEY,
+    TITLE_KEY,
+    DESCRIPTION_KEY,
+    COMMENT_KEY,
+    TYPE_KEY,
+    ADDITIONAL_PROPERTIES_KEY,
+    PROPERTIES_KEY,
+    REQUIRED_KEY,
+];
+
+/// Keys that must appear last, in this order
+const TRAILING_KEYS: [&str; 1] = [EXAMPLES_KEY];
+
 #[derive(Clone, Copy, Eq, PartialEq)]
 struct Key<'a>(&'a str);
 
+impl<'a> Key<'a> {
+    /// All keys without a fixed position share the same value
+    fn position(&self) -> usize {
+        LEADING_KEYS
+            .iter()
+            .position(|key| *key == self.0)
+            .or_else(|| {
+                TRAILING_KEYS
+                    .iter()
+                    .position(|key| *key == self.0)
+                    .map(|index| LEADING_KEYS.len() + 1 + index)
+            })
+            .unwrap_or(LEADING_KEYS.len())
+    }
+}
+
 impl<'a> PartialOrd for Key<'a> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         if self == other {
             Some(Ordering::Equal)
         } else {
-            match (self.0, other.0) {
-                (ID_KEY, _) => Some(Ordering::Less),
-                (_, ID_KEY) => Some(Ordering::Greater),
-                (TITLE_KEY, _) => Some(Ordering::Less),
-                (_, TITLE_KEY) => Some(Ordering::Greater),
-                (DESCRIPTION_KEY, _) => Some(Ordering::Less),
-                (_, DESCRIPTION_KEY) => Some(Ordering::Greater),
-                (COMMENT_KEY, _) => Some(Ordering::Less),
-                (_, COMMENT_KEY) => Some(Ordering::Greater),
-                (TYPE_KEY, _) => Some(Ordering::Less),
-                (_, TYPE_KEY) => Some(Ordering::Greater),
-                (ADDITIONAL_PROPERTIES_KEY, _) => Some(Ordering::Less),
-                (_, ADDITIONAL_PROPERTIES_KEY) => Some(Ordering::Greater),
-                (PROPERTIES_KEY, _) => Some(Ordering::Less),
-                (_, PROPERTIES_KEY) => Some(Ordering::Greater),
-                (REQUIRED_KEY, _) => Some(Ordering::Less),
-                (_, REQUIRED_KEY) => Some(Ordering::Greater),
-                (EXAMPLES_KEY, _) => Some(Ordering::Greater),
-                (_, EXAMPLES_KEY) => Some(Ordering::Less),
-                (_, _) => None,
+            let position = self.position();
+            let other_position = other.position();
+
+            if position == other_position {
+                None
+            } else {
+                Some(position.cmp(&other_position))
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalize_fixes_mismatches() {
+        let mut value = serde_json::json!({
+            "examples": [{}],
+            "required": ["type"],
+            "properties": {
+                "type": { "type": "string", "title": "Type" },
+                "$id": { "type": "integer" }
+            },
+            "type": "object",
+            "$id": "/schemas/foo",
+            "$defs": {
+                "required": { "enum": ["A"], "description": "Required" }
+            }
+        });
+
+        assert!(!check_key_order(&value).is_empty());
+
+        canonicalize(&mut value);
+
+        assert!(check_key_order(&value).is_empty());
+        assert_eq!(
+            value.as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["$id", "type", "properties", "required", "$defs", "examples"]
+        );
+        assert_eq!(
+            value["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["type", "$id"]
+        );
+        assert_eq!(
+            value["$defs"]["required"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["description", "enum"]
+        );
+    }
+
+    #[test]
+    fn format_is_canonical() {
+        let value = serde_json::json!({ "required": ["a"], "type": "object" });
+
+        assert_eq!(
+            format(&value),
+            "{\n  \"type\": \"object\",\n  \"required\": [\n    \"a\"\n  ]\n}\n"
+        );
+        assert_eq!(
+            format_with_indent(&value, 4),
+            "{\n    \"type\": \"object\",\n    \"required\": [\n        \"a\"\n    ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn sort_defs_by_dependency() {
+        let mut value = serde_json::json!({
+            "$id": "/schemas/foo",
+            "$defs": {
+                "c": { "$ref": "#/$defs/b" },
+                "a": {},
+                "b": { "items": { "$ref": "/schemas/foo#/$defs/a" } },
+                "d": { "$ref": "#/$defs/d" }
+            }
+        });
+
+        sort_defs(&mut value, DefOrder::Dependency);
+        assert_eq!(
+            value["$defs"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d"]
+        );
+
+        value["$defs"]["e"] = serde_json::json!({});
+        value["$defs"]["0"] = serde_json::json!({});
+        sort_defs(&mut value, DefOrder::Alphabetical);
+        assert_eq!(
+            value["$defs"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["0", "a", "b", "c", "d", "e"]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Handlers for user-defined keywords (like `x-precision` or `x-pii`)
+//!
+//! A [`Keywords`] registry can be given to the validator, the linter, and documentation pages, so
+//! that custom keywords are checked and described instead of being ignored.
+
+use super::constants::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// A user-defined keyword, which every method may ignore
+pub trait Keyword: Send + Sync {
+    /// The name of the keyword in schemas
+    fn name(&self) -> &str;
+
+    /// Check an instance against the keyword's value in a schema, returning a message if it fails
+    fn validate(&self, value: &Value, instance: &Value) -> Option<String> {
+        let _ = (value, instance);
+        None
+    }
+
+    /// Check that the keyword's value is well-formed, returning a message if it isn't
+    fn lint(&self, value: &Value) -> Option<String> {
+        let _ = value;
+        None
+    }
+
+    /// A human-readable description of the keyword's value, for documentation
+    fn describe(&self, value: &Value) -> Option<String> {
+        let _ = value;
+        None
+    }
+}
+
+/// The registered keyword handlers, by name
+#[derive(Clone, Default)]
+pub struct Keywords {
+    handlers: BTreeMap<String, Arc<dyn Keyword>>,
+}
+
+impl std::fmt::Debug for Keywords {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+impl Keywords {
+    /// Register a handler (replacing any existing handler for the same keyword)
+    pub fn register(&mut self, keyword: impl Keyword + 'static) -> &mut Self {
+        self.handlers
+            .insert(keyword.name().to_string(), Arc::new(keyword));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Keyword> {
+        self.handlers.get(name).map(|handler| handler.as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Descriptions of the registered keywords in a schema, in schema order
+    pub fn constraints(&self, schema: &Value) -> Vec<String> {
+        schema
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, value)| self.get(name)?.describe(value))
+            .collect()
+    }
+
+    /// The path, keyword, and message for each registered keyword in the schema (or any of its
+    /// subschemas) with a value that's not well-formed
+    pub fn lint(&self, schema: &Value) -> Vec<(Vec<String>, String, String)> {
+        let mut acc = vec![];
+
+        if !self.is_empty() {
+            self.lint_rec(schema, &mut vec![], &mut acc);
+        }
+
+        acc
+    }
+
+    fn lint_rec(
+        &self,
+        schema: &Value,
+        path: &mut Vec<String>,
+        acc: &mut Vec<(Vec<String>, String, String)>,
+    ) {
+        for (key, child) in schema.as_object().into_iter().flatten() {
+            if let Some(message) = self.get(key).and_then(|handler| handler.lint(child)) {
+                acc.push((path.clone(), key.clone(), message));
+            }
+
+            path.push(key.clone());
+
+            match key.as_str() {
+                key if SCHEMA_MAP_KEYS.contains(&key) => {
+                    for (name, schema) in child.as_object().into_iter().flatten() {
+                        path.push(name.clone());
+                        self.lint_rec(schema, path, acc);
+                        path.pop();
+                    }
+                }
+                key if DATA_KEYS.contains(&key) || self.get(key).is_some() => {}
+                _ => match child.as_array() {
+                    Some(schemas) => {
+                        for (index, schema) in schemas.iter().enumerate() {
+                            path.push(index.to_string());
+                            self.lint_rec(schema, path, acc);
+                            path.pop();
+                        }
+                    }
+                    None => self.lint_rec(child, path, acc),
+                },
+            }
+
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Numbers with at most the given number of decimal places
+    struct Precision;
+
+    impl Keyword for Precision {
+        fn name(&self) -> &str {
+            "x-precision"
+        }
+
+        fn validate(&self, value: &Value, instance: &Value) -> Option<String> {
+            let places = value.as_u64()? as usize;
+            let text = instance.as_f64()?.to_string();
+            let actual = text
+                .split_once('.')
+                .map_or(0, |(_, fraction)| fraction.len());
+
+            (actual > places).then(|| format!("more than {} decimal places", places))
+        }
+
+        fn lint(&self, value: &Value) -> Option<String> {
+            (!value.is_u64()).then(|| "expected a non-negative integer".to_string())
+        }
+
+        fn describe(&self, value: &Value) -> Option<String> {
+            Some(format!("at most {} decimal places", value))
+        }
+    }
+
+    #[test]
+    fn keyword_handlers() {
+        let mut keywords = Keywords::default();
+        keywords.register(Precision);
+
+        let schema = serde_json::json!({
+            "properties": {
+                "x-precision": { "type": "number", "x-precision": 2 },
+                "price": { "type": "number", "x-precision": "two" }
+            }
+        });
+
+        assert_eq!(
+            keywords.lint(&schema),
+            vec![(
+                vec!["properties".to_string(), "price".to_string()],
+                "x-precision".to_string(),
+                "expected a non-negative integer".to_string()
+            )]
+        );
+        assert_eq!(
+            keywords.constraints(&schema["properties"]["x-precision"]),
+            vec!["at most 2 decimal places"]
+        );
+        assert!(keywords.constraints(&schema).is_empty());
+
+        let page = crate::docs::Page::with_keywords(&schema, &keywords);
+
+        assert_eq!(
+            page.sections[0].properties[0].constraints,
+            vec!["at most 2 decimal places"]
+        );
+    }
+
+    /// A keyword that only has a name
+    struct Marker;
+
+    impl Keyword for Marker {
+        fn name(&self) -> &str {
+            "x-precision"
+        }
+    }
+
+    #[test]
+    fn keyword_lint_edge_cases() {
+        let schema = serde_json::json!({
+            "x-precision": { "x-precision": "nested" },
+            "allOf": [{}, { "x-precision": -1 }],
+            "examples": [{ "x-precision": "data" }],
+            "default": { "x-precision": "data" }
+        });
+
+        assert!(Keywords::default().lint(&schema).is_empty());
+
+        let mut keywords = Keywords::default();
+        keywords.register(Precision);
+
+        // The keyword's own value and data keywords aren't searched for subschemas.
+        assert_eq!(
+            keywords.lint(&schema),
+            vec![
+                (
+                    vec![],
+                    "x-precision".to_string(),
+                    "expected a non-negative integer".to_string()
+                ),
+                (
+                    vec!["allOf".to_string(), "1".to_string()],
+                    "x-precision".to_string(),
+                    "expected a non-negative integer".to_string()
+                )
+            ]
+        );
+        assert!(keywords.constraints(&serde_json::json!(true)).is_empty());
+        assert_eq!(format!("{:?}", keywords), "{\"x-precision\"}");
+
+        keywords.register(Marker);
+
+        assert!(keywords.lint(&schema).is_empty());
+        assert!(keywords.constraints(&schema).is_empty());
+        assert_eq!(
+            keywords
+                .get("x-precision")
+                .unwrap()
+                .validate(&serde_json::json!(0), &serde_json::json!(1.5)),
+            None
+        );
+    }
+}
@@ -1,8 +1,30 @@
+pub mod algebra;
+pub mod compliance;
 pub mod compose;
 mod constants;
+pub mod docs;
+pub mod example;
+pub mod explain;
+pub mod generate;
+pub mod graph;
+pub mod import;
+pub mod infer;
+pub mod jsonc;
+pub mod key;
 pub mod key_order;
+pub mod keyword;
 pub mod lint;
+pub mod mutation;
 pub mod path;
+pub mod query;
+pub mod refactor;
 pub mod reference;
+pub mod resolve;
+pub mod satisfiability;
 pub mod schema;
+pub mod stats;
+pub mod strip;
 mod util;
+pub mod validate;
+pub mod version;
+pub mod yaml;
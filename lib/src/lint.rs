@@ -1,43 +1,271 @@
+use super::key::{Interner, Key};
+use super::keyword::Keywords;
+use super::path::Entry;
 use super::schema::SchemaFile;
+use serde::Deserialize;
 use serde_json::Value;
+use std::fmt::Display;
+
+mod openapi;
+
+pub use openapi::{check_references, openapi_schemas};
+
+const JSON: &str = "json";
+const KEY_ORDER: &str = "key-order";
+const UNRESTRICTED_PROPERTIES: &str = "unrestricted-properties";
+const OPTIONAL_FIELD: &str = "optional-field";
+const MISORDERED_REQUIRED: &str = "misordered-required";
+const UNSATISFIABLE: &str = "unsatisfiable";
+const DANGLING_REFERENCE: &str = "dangling-reference";
+const UNUSED_COMPONENT: &str = "unused-component";
+const CUSTOM_KEYWORD: &str = "custom-keyword";
+
+/// The names of the lint rules, which identify them in configuration
+pub const RULES: [&str; 9] = [
+    JSON,
+    KEY_ORDER,
+    UNRESTRICTED_PROPERTIES,
+    OPTIONAL_FIELD,
+    MISORDERED_REQUIRED,
+    UNSATISFIABLE,
+    DANGLING_REFERENCE,
+    UNUSED_COMPONENT,
+    CUSTOM_KEYWORD,
+];
+
+/// How seriously to treat the issues reported by a rule
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Allow,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum Issue<'a> {
     Json(serde_json::Error),
     MisorderedKeys(super::key_order::KeyOrderMismatch<'a>),
-    UnrestrictedProperties(Vec<String>),
-    OptionalField(Vec<String>, String),
-    MisorderedRequires(Vec<String>),
+    /// An object schema (with the keys leading to it in the file) that allows any other properties
+    UnrestrictedProperties(Vec<Key>),
+    OptionalField(Vec<Key>, Key),
+    MisorderedRequires(Vec<Key>),
+    Unsatisfiable(super::satisfiability::Unsatisfiable),
+    /// A local reference in an OpenAPI document that doesn't point to anything
+    DanglingReference(String),
+    /// A component schema in an OpenAPI document that nothing else refers to
+    UnusedComponent(String),
+    /// A user-defined keyword whose handler rejected its value (with the path to the schema, the
+    /// keyword, and the handler's message)
+    CustomKeyword(Vec<Key>, String, String),
 }
 
-pub fn lint(schema_file_value: &Value) -> Vec<Issue> {
-    let mut result = vec![];
-
-    for key_order_mismatch in super::key_order::check_key_order(schema_file_value) {
-        result.push(Issue::MisorderedKeys(key_order_mismatch));
+impl Issue<'_> {
+    /// The name of the rule that reported this issue
+    pub fn rule(&self) -> &'static str {
+        match self {
+            Self::Json(_) => JSON,
+            Self::MisorderedKeys(_) => KEY_ORDER,
+            Self::UnrestrictedProperties(_) => UNRESTRICTED_PROPERTIES,
+            Self::OptionalField(_, _) => OPTIONAL_FIELD,
+            Self::MisorderedRequires(_) => MISORDERED_REQUIRED,
+            Self::Unsatisfiable(_) => UNSATISFIABLE,
+            Self::DanglingReference(_) => DANGLING_REFERENCE,
+            Self::UnusedComponent(_) => UNUSED_COMPONENT,
+            Self::CustomKeyword(_, _, _) => CUSTOM_KEYWORD,
+        }
     }
 
-    match serde_json::from_value::<SchemaFile>(schema_file_value.clone()) {
-        Ok(schema_file) => {
-            for (path, object) in schema_file.objects() {
-                if !object.no_additional_properties() {
-                    result.push(Issue::UnrestrictedProperties(path.clone()));
-                }
+    /// A JSON Pointer to the schema in the file that the issue is about, if it's about one
+    pub fn pointer(&self) -> Option<String> {
+        let path = match self {
+            Self::MisorderedKeys(mismatch) => mismatch
+                .path
+                .entries()
+                .iter()
+                .map(|entry| match entry {
+                    Entry::Key(key) => key.to_string(),
+                    Entry::Index(index) => index.to_string(),
+                })
+                .collect(),
+            Self::UnrestrictedProperties(path)
+            | Self::OptionalField(path, _)
+            | Self::MisorderedRequires(path)
+            | Self::CustomKeyword(path, _, _) => path.iter().map(ToString::to_string).collect(),
+            Self::Unsatisfiable(unsatisfiable) => unsatisfiable.path.clone(),
+            Self::Json(_) | Self::DanglingReference(_) | Self::UnusedComponent(_) => return None,
+        };
 
-                let mut optional_fields = object.properties.keys().collect::<Vec<_>>();
-                optional_fields.retain(|value| !object.required.contains(value));
+        Some(
+            path.iter()
+                .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+                .collect::<Vec<_>>()
+                .concat(),
+        )
+    }
 
-                for optional_field in optional_fields {
-                    result.push(Issue::OptionalField(path.clone(), optional_field.clone()));
-                }
+    /// Schemas that can't be parsed and dangling references are errors, and everything else is a
+    /// warning by default
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Self::Json(_) | Self::DanglingReference(_) => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
 
-                let mut required_fields = object.properties.keys().collect::<Vec<_>>();
-                required_fields.retain(|value| object.required.contains(value));
+/// A description of the issue, starting with the pointer to the schema it's about unless that's
+/// the root
+impl Display for Issue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(pointer) = self.pointer().filter(|pointer| !pointer.is_empty()) {
+            write!(f, "{}: ", pointer)?;
+        }
 
-                if required_fields != object.required.iter().collect::<Vec<_>>() {
-                    result.push(Issue::MisorderedRequires(path.clone()));
-                }
+        match self {
+            Self::Json(error) => write!(f, "not a valid schema: {}", error),
+            Self::MisorderedKeys(mismatch) => write!(
+                f,
+                "key {} should come before {}",
+                mismatch.second, mismatch.first
+            ),
+            Self::UnrestrictedProperties(_) => {
+                write!(f, "additional properties are not restricted")
+            }
+            Self::OptionalField(_, name) => write!(f, "property {} is optional", name),
+            Self::MisorderedRequires(_) => write!(
+                f,
+                "required properties are not in the order of the properties"
+            ),
+            Self::Unsatisfiable(unsatisfiable) => {
+                write!(f, "no instance can be valid: {}", unsatisfiable.reason)
             }
+            Self::DanglingReference(reference) => {
+                write!(f, "reference {} doesn't point to anything", reference)
+            }
+            Self::UnusedComponent(name) => {
+                write!(f, "component schema {} isn't referred to", name)
+            }
+            Self::CustomKeyword(_, keyword, message) => write!(f, "{}: {}", keyword, message),
+        }
+    }
+}
+
+/// Measures the phases of an operation (for example to report timings)
+///
+/// Phases aren't nested.
+pub trait Profiler {
+    fn phase<T, F: FnOnce() -> T>(&mut self, name: &'static str, f: F) -> T {
+        let _ = name;
+        f()
+    }
+}
+
+impl Profiler for () {}
+
+pub fn lint(schema_file_value: &Value) -> Vec<Issue<'_>> {
+    lint_profiled(schema_file_value, &mut Interner::default(), &mut ())
+}
+
+/// Check the values of user-defined keywords with their registered handlers
+pub fn lint_keywords(schema_file_value: &Value, keywords: &Keywords) -> Vec<Issue<'static>> {
+    let mut interner = Interner::default();
+
+    keywords
+        .lint(schema_file_value)
+        .into_iter()
+        .map(|(path, keyword, message)| {
+            let path = path.iter().map(|segment| interner.key(segment)).collect();
+
+            Issue::CustomKeyword(path, keyword, message)
+        })
+        .collect()
+}
+
+/// Lint a schema, measuring parsing, traversal, and each rule separately
+///
+/// Property names in the issues are interned with the given interner, which can be shared by the
+/// schemas linted in one run.
+pub fn lint_profiled<'a, P: Profiler>(
+    schema_file_value: &'a Value,
+    interner: &mut Interner,
+    profiler: &mut P,
+) -> Vec<Issue<'a>> {
+    let mut result = profiler.phase(KEY_ORDER, || {
+        super::key_order::check_key_order(schema_file_value)
+            .into_iter()
+            .map(Issue::MisorderedKeys)
+            .collect::<Vec<_>>()
+    });
+
+    match profiler.phase("traverse", || {
+        interner
+            .deserialize::<SchemaFile>(schema_file_value.clone())
+            .map(|schema_file| (schema_file.objects_with_locations(interner), schema_file))
+    }) {
+        Ok((objects, schema_file)) => {
+            // Issues for the same object are kept together, in the order of the rules.
+            let mut object_issues = profiler.phase(UNRESTRICTED_PROPERTIES, || {
+                objects
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, object))| !object.no_additional_properties())
+                    .map(|(index, (path, _))| (index, Issue::UnrestrictedProperties(path.clone())))
+                    .collect::<Vec<_>>()
+            });
+
+            object_issues.extend(profiler.phase(OPTIONAL_FIELD, || {
+                objects
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, (path, object))| {
+                        object
+                            .properties
+                            .keys()
+                            .filter(|value| !object.required.contains(value))
+                            .map(move |optional_field| {
+                                (
+                                    index,
+                                    Issue::OptionalField(path.clone(), optional_field.clone()),
+                                )
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            }));
+
+            object_issues.extend(profiler.phase(MISORDERED_REQUIRED, || {
+                objects
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, object))| {
+                        let mut required_fields = object.properties.keys().collect::<Vec<_>>();
+                        required_fields.retain(|value| object.required.contains(value));
+
+                        required_fields != object.required.iter().collect::<Vec<_>>()
+                    })
+                    .map(|(index, (path, _))| (index, Issue::MisorderedRequires(path.clone())))
+                    .collect::<Vec<_>>()
+            }));
+
+            object_issues.sort_by_key(|(index, _)| *index);
+            result.extend(object_issues.into_iter().map(|(_, issue)| issue));
+
+            result.extend(profiler.phase(UNSATISFIABLE, || {
+                super::satisfiability::check_satisfiability(&schema_file)
+                    .into_iter()
+                    .map(Issue::Unsatisfiable)
+                    .collect::<Vec<_>>()
+            }));
         }
         Err(error) => {
             result.push(Issue::Json(error));
@@ -46,3 +274,67 @@ pub fn lint(schema_file_value: &Value) -> Vec<Issue> {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_messages_and_paths() {
+        let schema = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$defs": {
+                "user": {
+                    "type": "object",
+                    "required": ["id"],
+                    "properties": {
+                        "a/b": { "type": "string" },
+                        "id": { "enum": [] }
+                    }
+                }
+            }
+        });
+        let issues = lint(&schema);
+
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| (issue.rule(), issue.pointer(), issue.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    KEY_ORDER,
+                    Some("/$defs/user".to_string()),
+                    "/$defs/user: key properties should come before required".to_string()
+                ),
+                (
+                    UNRESTRICTED_PROPERTIES,
+                    Some("/$defs/user".to_string()),
+                    "/$defs/user: additional properties are not restricted".to_string()
+                ),
+                (
+                    OPTIONAL_FIELD,
+                    Some("/$defs/user".to_string()),
+                    "/$defs/user: property a/b is optional".to_string()
+                ),
+                (
+                    UNSATISFIABLE,
+                    Some("/$defs/user".to_string()),
+                    "/$defs/user: no instance can be valid: required property id can never be valid"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unparseable_schema() {
+        let schema = serde_json::json!({ "$defs": [] });
+        let issues = lint(&schema);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule(), JSON);
+        assert_eq!(issues[0].pointer(), None);
+        assert!(issues[0].to_string().starts_with("not a valid schema: "));
+    }
+}
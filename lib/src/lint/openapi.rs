@@ -0,0 +1,377 @@
+use super::Issue;
+use crate::constants::REF_KEY;
+use crate::import::{convert_schema, is_legacy, Error};
+use serde_json::Value;
+use std::collections::HashSet;
+
+const COMPONENTS_POINTER: &str = "/components/schemas";
+
+/// The fields of a path item that are operations
+const METHODS: [&str; 8] = [
+    "delete", "get", "head", "options", "patch", "post", "put", "trace",
+];
+
+/// The schemas embedded in an OpenAPI 3.0 or 3.1 document, with JSON Pointers to their locations
+///
+/// These are the component schemas and the schemas of request bodies and responses (in operations
+/// and components), converted as by [`crate::import::from_openapi`], with references to components
+/// as paths under `/components/schemas`. Boolean schemas are skipped.
+pub fn openapi_schemas(document: &Value) -> Result<Vec<(String, Value)>, Error> {
+    let legacy = is_legacy(document)?;
+    let mut schemas = vec![];
+
+    if let Some(components) = document
+        .pointer(COMPONENTS_POINTER)
+        .and_then(Value::as_object)
+    {
+        for (name, schema) in components {
+            schemas.push((format!("{}/{}", COMPONENTS_POINTER, escape(name)), schema));
+        }
+    }
+
+    for key in ["requestBodies", "responses"] {
+        if let Some(components) = document
+            .pointer(&format!("/components/{}", key))
+            .and_then(Value::as_object)
+        {
+            for (name, component) in components {
+                let location = format!("/components/{}/{}", key, escape(name));
+                content_schemas(component, location, &mut schemas);
+            }
+        }
+    }
+
+    if let Some(paths) = document.get("paths").and_then(Value::as_object) {
+        for (path, item) in paths {
+            let Some(item) = item.as_object() else {
+                continue;
+            };
+
+            for (method, operation) in item {
+                if !METHODS.contains(&method.as_str()) {
+                    continue;
+                }
+
+                let location = format!("/paths/{}/{}", escape(path), method);
+
+                if let Some(request_body) = operation.get("requestBody") {
+                    content_schemas(
+                        request_body,
+                        format!("{}/requestBody", location),
+                        &mut schemas,
+                    );
+                }
+
+                if let Some(responses) = operation.get("responses").and_then(Value::as_object) {
+                    for (status, response) in responses {
+                        content_schemas(
+                            response,
+                            format!("{}/responses/{}", location, escape(status)),
+                            &mut schemas,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(schemas
+        .into_iter()
+        .filter(|(_, schema)| schema.is_object())
+        .map(|(location, schema)| (location, convert_schema(schema, COMPONENTS_POINTER, legacy)))
+        .collect())
+}
+
+/// Check the local references in an OpenAPI document
+///
+/// Every `$ref` that starts with `#` has to point to something in the document, and every
+/// component schema has to be referred to from somewhere other than inside itself.
+pub fn check_references(document: &Value) -> Vec<(String, Issue<'static>)> {
+    let mut references = vec![];
+    collect_references(document, String::new(), &mut references);
+
+    let mut result = vec![];
+    let mut used = HashSet::new();
+
+    for (location, reference) in references {
+        let Some(pointer) = reference.strip_prefix('#') else {
+            continue;
+        };
+
+        if document.pointer(pointer).is_none() {
+            result.push((
+                location.clone(),
+                Issue::DanglingReference(reference.to_string()),
+            ));
+        }
+
+        if let Some(rest) = pointer
+            .strip_prefix(COMPONENTS_POINTER)
+            .and_then(|rest| rest.strip_prefix('/'))
+        {
+            let name = rest.split('/').next().unwrap_or(rest);
+            let own_location = format!("{}/{}", COMPONENTS_POINTER, name);
+
+            if location != own_location && !location.starts_with(&format!("{}/", own_location)) {
+                used.insert(name.to_string());
+            }
+        }
+    }
+
+    if let Some(components) = document
+        .pointer(COMPONENTS_POINTER)
+        .and_then(Value::as_object)
+    {
+        for name in components.keys() {
+            let name = escape(name);
+
+            if !used.contains(&name) {
+                result.push((
+                    format!("{}/{}", COMPONENTS_POINTER, name),
+                    Issue::UnusedComponent(name),
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+/// Add the schemas of the media types of a request body or response
+fn content_schemas<'a>(value: &'a Value, location: String, acc: &mut Vec<(String, &'a Value)>) {
+    if let Some(content) = value.get("content").and_then(Value::as_object) {
+        for (media_type, media_type_object) in content {
+            if let Some(schema) = media_type_object.get("schema") {
+                acc.push((
+                    format!("{}/content/{}/schema", location, escape(media_type)),
+                    schema,
+                ));
+            }
+        }
+    }
+}
+
+fn collect_references<'a>(value: &'a Value, location: String, acc: &mut Vec<(String, &'a str)>) {
+    match value {
+        Value::Object(fields) => {
+            if let Some(Value::String(reference)) = fields.get(REF_KEY) {
+                acc.push((location.clone(), reference));
+            }
+
+            for (key, value) in fields {
+                collect_references(value, format!("{}/{}", location, escape(key)), acc);
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_references(value, format!("{}/{}", location, index), acc);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn openapi_lint() {
+        let document = json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Shop", "version": "1.0.0" },
+            "paths": {
+                "/orders": {
+                    "parameters": [],
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Order" }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "201": {
+                                "description": "Created",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": { "id": { "type": "integer" } }
+                                        }
+                                    }
+                                }
+                            },
+                            "400": { "$ref": "#/components/responses/BadRequest" }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Order": {
+                        "type": "object",
+                        "properties": {
+                            "note": { "type": "string", "nullable": true },
+                            "parent": { "$ref": "#/components/schemas/Order" },
+                            "customer": { "$ref": "#/components/schemas/Customer" }
+                        },
+                        "additionalProperties": false
+                    },
+                    "Item": {
+                        "type": "object",
+                        "properties": { "item": { "$ref": "#/components/schemas/Item" } }
+                    }
+                }
+            }
+        });
+
+        let schemas = openapi_schemas(&document).unwrap();
+
+        assert_eq!(
+            schemas,
+            vec![
+                (
+                    "/components/schemas/Order".to_string(),
+                    json!({
+                        "type": "object",
+                        "properties": {
+                            "note": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+                            "parent": { "$ref": "/components/schemas/Order" },
+                            "customer": { "$ref": "/components/schemas/Customer" }
+                        },
+                        "additionalProperties": false
+                    })
+                ),
+                (
+                    "/components/schemas/Item".to_string(),
+                    json!({
+                        "type": "object",
+                        "properties": { "item": { "$ref": "/components/schemas/Item" } }
+                    })
+                ),
+                (
+                    "/paths/~1orders/post/requestBody/content/application~1json/schema".to_string(),
+                    json!({ "$ref": "/components/schemas/Order" })
+                ),
+                (
+                    "/paths/~1orders/post/responses/201/content/application~1json/schema"
+                        .to_string(),
+                    json!({ "type": "object", "properties": { "id": { "type": "integer" } } })
+                )
+            ]
+        );
+
+        let issues = check_references(&document)
+            .into_iter()
+            .map(|(location, issue)| (location, issue.rule(), format!("{:?}", issue)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "/paths/~1orders/post/responses/400".to_string(),
+                    "dangling-reference",
+                    "DanglingReference(\"#/components/responses/BadRequest\")".to_string()
+                ),
+                (
+                    "/components/schemas/Order/properties/customer".to_string(),
+                    "dangling-reference",
+                    "DanglingReference(\"#/components/schemas/Customer\")".to_string()
+                ),
+                (
+                    "/components/schemas/Item".to_string(),
+                    "unused-component",
+                    "UnusedComponent(\"Item\")".to_string()
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn openapi_lint_edge_cases() {
+        let document = json!({
+            "openapi": "3.1.0",
+            "paths": {
+                "/orders/{id}": {
+                    "summary": "An order",
+                    "parameters": [{ "$ref": "#/components/parameters/Id" }],
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/OrderItem" }
+                                    },
+                                    "text/plain": { "schema": true }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "parameters": { "Id": { "name": "id", "in": "path" } },
+                "schemas": {
+                    "a/b": { "type": "string" },
+                    "Order": { "$ref": "#/components/schemas/a~1b" },
+                    "OrderItem": {
+                        "properties": { "order": { "$ref": "#/components/schemas/Order" } }
+                    },
+                    "Any": true,
+                    "External": { "$ref": "other.yaml#/components/schemas/Order" }
+                }
+            }
+        });
+
+        assert_eq!(
+            openapi_schemas(&document)
+                .unwrap()
+                .into_iter()
+                .map(|(location, _)| location)
+                .collect::<Vec<_>>(),
+            vec![
+                "/components/schemas/a~1b",
+                "/components/schemas/Order",
+                "/components/schemas/OrderItem",
+                "/components/schemas/External",
+                "/paths/~1orders~1{id}/get/responses/200/content/application~1json/schema"
+            ]
+        );
+
+        // A reference to a component from another whose name starts with the same characters
+        // counts as a use, and references to other documents are ignored.
+        assert_eq!(
+            check_references(&document)
+                .into_iter()
+                .map(|(location, issue)| (location, issue.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/components/schemas/Any".to_string(),
+                    "component schema Any isn't referred to".to_string()
+                ),
+                (
+                    "/components/schemas/External".to_string(),
+                    "component schema External isn't referred to".to_string()
+                )
+            ]
+        );
+
+        assert!(matches!(
+            openapi_schemas(&json!({ "swagger": "2.0" })),
+            Err(Error::UnsupportedOpenApiVersion(None))
+        ));
+        assert!(matches!(
+            openapi_schemas(&json!({ "openapi": "4.0.0" })),
+            Err(Error::UnsupportedOpenApiVersion(Some(version))) if version == "4.0.0"
+        ));
+    }
+}
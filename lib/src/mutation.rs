@@ -0,0 +1,279 @@
+//! Mutation testing for schemas
+//!
+//! Each mutant loosens one constraint in a schema. A mutant that accepts every valid instance
+//! and rejects every invalid instance in a corpus shows a constraint that the corpus never
+//! exercises, so a regression that dropped it wouldn't be caught.
+
+use super::constants::*;
+use super::validate::Validator;
+use serde_json::Value;
+use std::fmt::Display;
+
+/// Keywords that only restrict the values they apply to, and can be removed to widen a schema
+const REMOVABLE_KEYS: [&str; 15] = [
+    PATTERN_KEY,
+    ENUM_KEY,
+    CONST_KEY,
+    MINIMUM_KEY,
+    MAXIMUM_KEY,
+    EXCLUSIVE_MINIMUM_KEY,
+    EXCLUSIVE_MAXIMUM_KEY,
+    MULTIPLE_OF_KEY,
+    MIN_LENGTH_KEY,
+    MAX_LENGTH_KEY,
+    MIN_ITEMS_KEY,
+    MAX_ITEMS_KEY,
+    UNIQUE_ITEMS_KEY,
+    MIN_PROPERTIES_KEY,
+    MAX_PROPERTIES_KEY,
+];
+
+/// A change that makes a schema accept more instances
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Mutation {
+    /// Make a required property optional
+    DropRequired(String),
+    /// Replace `integer` with `number`, or remove any other `type`
+    WidenType,
+    /// Remove a constraint (including `additionalProperties: false`)
+    Remove(&'static str),
+}
+
+impl Display for Mutation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DropRequired(name) => write!(f, "drop required property \"{}\"", name),
+            Self::WidenType => write!(f, "widen type"),
+            Self::Remove(keyword) => write!(f, "remove {}", keyword),
+        }
+    }
+}
+
+/// A copy of a schema with one mutation applied
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mutant {
+    /// The path to the mutated subschema
+    pub path: Vec<String>,
+    pub mutation: Mutation,
+    pub schema: Value,
+}
+
+impl Mutant {
+    /// The location of the mutated subschema as a JSON pointer fragment
+    pub fn schema_pointer(&self) -> String {
+        format!("#{}", pointer(&self.path))
+    }
+}
+
+impl Display for Mutant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.schema_pointer(), self.mutation)
+    }
+}
+
+/// Every mutant of a schema, in schema order
+pub fn mutants(schema: &Value) -> Vec<Mutant> {
+    let mut acc = vec![];
+
+    mutants_rec(schema, schema, &mut vec![], &mut acc);
+
+    acc
+}
+
+/// Whether a mutant's validator rejects a valid instance or accepts an invalid instance (and so the
+/// mutant would be caught by the corpus)
+pub fn is_killed(validator: &Validator, valid: &[Value], invalid: &[Value]) -> bool {
+    valid.iter().any(|instance| !validator.is_valid(instance))
+        || invalid.iter().any(|instance| validator.is_valid(instance))
+}
+
+fn mutants_rec(root: &Value, schema: &Value, path: &mut Vec<String>, acc: &mut Vec<Mutant>) {
+    let fields = match schema.as_object() {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    let mut mutate = |mutation: Mutation, apply: &dyn Fn(&mut Value)| {
+        let mut mutated = root.clone();
+
+        if let Some(target) = mutated.pointer_mut(&pointer(path)) {
+            apply(target);
+        }
+
+        acc.push(Mutant {
+            path: path.clone(),
+            mutation,
+            schema: mutated,
+        });
+    };
+
+    for (key, value) in fields {
+        match key.as_str() {
+            REQUIRED_KEY => {
+                for name in value
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                {
+                    mutate(Mutation::DropRequired(name.to_string()), &|target| {
+                        if let Some(required) = target[REQUIRED_KEY].as_array_mut() {
+                            required.retain(|other| other.as_str() != Some(name));
+                        }
+                    });
+                }
+            }
+            TYPE_KEY => mutate(Mutation::WidenType, &|target| {
+                if let Some(fields) = target.as_object_mut() {
+                    if fields[TYPE_KEY] == "integer" {
+                        fields.insert(TYPE_KEY.to_string(), "number".into());
+                    } else {
+                        fields.remove(TYPE_KEY);
+                    }
+                }
+            }),
+            ADDITIONAL_PROPERTIES_KEY if value == &Value::Bool(false) => {
+                mutate(Mutation::Remove(ADDITIONAL_PROPERTIES_KEY), &|target| {
+                    if let Some(fields) = target.as_object_mut() {
+                        fields.remove(ADDITIONAL_PROPERTIES_KEY);
+                    }
+                })
+            }
+            key => {
+                if let Some(keyword) = REMOVABLE_KEYS.iter().find(|keyword| **keyword == key) {
+                    mutate(Mutation::Remove(keyword), &|target| {
+                        if let Some(fields) = target.as_object_mut() {
+                            fields.remove(*keyword);
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    for (key, child) in fields {
+        path.push(key.clone());
+
+        match key.as_str() {
+            key if SCHEMA_MAP_KEYS.contains(&key) => {
+                for (name, schema) in child.as_object().into_iter().flatten() {
+                    path.push(name.clone());
+                    mutants_rec(root, schema, path, acc);
+                    path.pop();
+                }
+            }
+            key if DATA_KEYS.contains(&key) => {}
+            _ => match child.as_array() {
+                Some(schemas) => {
+                    for (index, schema) in schemas.iter().enumerate() {
+                        path.push(index.to_string());
+                        mutants_rec(root, schema, path, acc);
+                        path.pop();
+                    }
+                }
+                None => mutants_rec(root, child, path, acc),
+            },
+        }
+
+        path.pop();
+    }
+}
+
+fn pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|entry| format!("/{}", entry.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn surviving_mutants() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "name": { "type": "string", "pattern": "^[a-z]+$" }
+            },
+            "required": ["id", "name"],
+            "additionalProperties": false
+        });
+
+        let all = mutants(&schema);
+
+        assert_eq!(
+            all.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "#: widen type",
+                "#: drop required property \"id\"",
+                "#: drop required property \"name\"",
+                "#: remove additionalProperties",
+                "#/properties/id: widen type",
+                "#/properties/id: remove minimum",
+                "#/properties/name: widen type",
+                "#/properties/name: remove pattern",
+            ]
+        );
+        assert_eq!(all[4].schema["properties"]["id"]["type"], "number");
+
+        let valid = vec![json!({ "id": 1, "name": "foo" })];
+        let invalid = vec![json!({ "id": 0, "name": "foo" }), json!({ "id": 1 })];
+
+        let survivors = all
+            .iter()
+            .filter(|mutant| {
+                let validator = Validator::new(mutant.schema.clone()).unwrap();
+                !is_killed(&validator, &valid, &invalid)
+            })
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            survivors,
+            vec![
+                "#: widen type",
+                "#: drop required property \"id\"",
+                "#: remove additionalProperties",
+                "#/properties/id: widen type",
+                "#/properties/name: widen type",
+                "#/properties/name: remove pattern",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutant_edge_cases() {
+        assert!(mutants(&json!(true)).is_empty());
+
+        let schema = json!({
+            "properties": {
+                "a/b~c": { "type": ["string", "null"], "const": { "type": "string" } }
+            },
+            "prefixItems": [{ "minLength": 1 }],
+            "additionalProperties": { "maxLength": 2 },
+            "required": ["a/b~c", 1]
+        });
+
+        let all = mutants(&schema);
+
+        assert_eq!(
+            all.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "#: drop required property \"a/b~c\"",
+                "#/properties/a~1b~0c: widen type",
+                "#/properties/a~1b~0c: remove const",
+                "#/prefixItems/0: remove minLength",
+                "#/additionalProperties: remove maxLength",
+            ]
+        );
+        assert_eq!(all[0].schema["required"], json!([1]));
+        assert_eq!(
+            all[1].schema["properties"]["a/b~c"],
+            json!({ "const": { "type": "string" } })
+        );
+        assert_eq!(all[4].schema["additionalProperties"], json!({}));
+    }
+}
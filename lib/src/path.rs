@@ -1,5 +1,6 @@
-use super::constants::{DEFS_KEY, PROPERTIES_KEY};
-use std::fmt::Display;
+use super::constants::{DATA_KEYS, DEFS_KEY, PROPERTIES_KEY, SCHEMA_MAP_KEYS};
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Entry<'a> {
@@ -19,49 +20,331 @@ impl<'a> From<usize> for Entry<'a> {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+/// A path that shares its prefix with the path it was extended from, so cloning is cheap
+#[derive(Clone, Default)]
 pub struct Path<'a> {
-    entries: Vec<Entry<'a>>,
+    last: Option<Arc<Node<'a>>>,
+}
+
+struct Node<'a> {
+    parent: Option<Arc<Node<'a>>>,
+    entry: Entry<'a>,
+    len: usize,
 }
 
 impl<'a> Path<'a> {
     pub fn is_empty(&self) -> bool {
-        self.entries.is_empty()
+        self.last.is_none()
     }
 
     pub fn len(&self) -> usize {
-        self.entries.len()
+        self.last.as_ref().map_or(0, |node| node.len)
     }
 
     pub fn push<E>(&mut self, value: E)
     where
         Entry<'a>: From<E>,
     {
-        self.entries.push(Entry::from(value));
+        let len = self.len() + 1;
+
+        self.last = Some(Arc::new(Node {
+            parent: self.last.take(),
+            entry: Entry::from(value),
+            len,
+        }));
+    }
+
+    /// This path extended by an entry (which leaves this path unchanged)
+    pub fn child<E>(&self, value: E) -> Self
+    where
+        Entry<'a>: From<E>,
+    {
+        let mut result = self.clone();
+        result.push(value);
+        result
+    }
+
+    pub fn pop(&mut self) -> Option<Entry<'a>> {
+        let node = self.last.take()?;
+        let entry = node.entry;
+
+        self.last = match Arc::try_unwrap(node) {
+            Ok(node) => node.parent,
+            Err(node) => node.parent.clone(),
+        };
+
+        Some(entry)
+    }
+
+    pub fn last(&self) -> Option<Entry<'a>> {
+        self.last.as_ref().map(|node| node.entry)
+    }
+
+    /// The entries, from the root
+    pub fn entries(&self) -> Vec<Entry<'a>> {
+        let mut result = Vec::with_capacity(self.len());
+        let mut current = self.last.as_deref();
+
+        while let Some(node) = current {
+            result.push(node.entry);
+            current = node.parent.as_deref();
+        }
+
+        result.reverse();
+        result
     }
 
     /// This path points to a JSON object with arbitrary keys
     pub fn allows_arbitrary_keys(&self) -> bool {
+        is_arbitrary_key_container(self.last())
+    }
+}
+
+impl PartialEq for Path<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut current = (self.last.as_ref(), other.last.as_ref());
+
+        loop {
+            match current {
+                (None, None) => return true,
+                (Some(left), Some(right)) if Arc::ptr_eq(left, right) => return true,
+                (Some(left), Some(right)) if left.len == right.len && left.entry == right.entry => {
+                    current = (left.parent.as_ref(), right.parent.as_ref());
+                }
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl Eq for Path<'_> {}
+
+impl Debug for Path<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Path")
+            .field("entries", &self.entries())
+            .finish()
+    }
+}
+
+impl Display for Path<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_entries(f, &self.entries())
+    }
+}
+
+/// A borrowed path, which is what traversals pass to their callbacks instead of cloning a path
+/// for every node
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PathRef<'p, 'a> {
+    entries: &'p [Entry<'a>],
+}
+
+impl<'p, 'a> PathRef<'p, 'a> {
+    pub fn new(entries: &'p [Entry<'a>]) -> Self {
+        Self { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entries(&self) -> &'p [Entry<'a>] {
         self.entries
-            .last()
-            .filter(|entry| matches!(entry, Entry::Key(PROPERTIES_KEY) | Entry::Key(DEFS_KEY)))
-            .is_some()
+    }
+
+    /// An owned copy of the path
+    pub fn to_path(&self) -> Path<'a> {
+        let mut result = Path::default();
+
+        for entry in self.entries {
+            result.push(*entry);
+        }
+
+        result
+    }
+
+    /// This path points to a JSON object with arbitrary keys
+    pub fn allows_arbitrary_keys(&self) -> bool {
+        is_arbitrary_key_container(self.entries.last().copied())
+    }
+
+    /// This path points to data (such as an `enum` or `default` value) rather than a schema
+    pub fn is_data(&self) -> bool {
+        match self.entries {
+            [.., parent, last] => is_data(Some(*last), Some(*parent)),
+            [last] => is_data(Some(*last), None),
+            [] => false,
+        }
     }
 }
 
-impl<'a> Display for Path<'a> {
+impl Display for PathRef<'_, '_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for entry in &self.entries {
-            match entry {
-                Entry::Key(key) => {
-                    write!(f, ".{}", key)?;
-                }
-                Entry::Index(index) => {
-                    write!(f, "[{}]", index)?;
-                }
+        write_entries(f, self.entries)
+    }
+}
+
+/// A path made of frames on a traversal's call stack, so extending it allocates nothing
+///
+/// Use `to_path` for a path that can be kept after the traversal.
+#[derive(Clone, Copy, Default)]
+pub struct PathFrame<'a> {
+    frame: Option<(&'a PathFrame<'a>, Entry<'a>)>,
+}
+
+impl<'a> PathFrame<'a> {
+    /// This path extended by an entry
+    pub fn child<E>(&'a self, value: E) -> Self
+    where
+        Entry<'a>: From<E>,
+    {
+        Self {
+            frame: Some((self, Entry::from(value))),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent().map_or(0, |parent| parent.len() + 1)
+    }
+
+    pub fn last(&self) -> Option<Entry<'a>> {
+        self.frame.map(|(_, entry)| entry)
+    }
+
+    pub fn parent(&self) -> Option<&'a PathFrame<'a>> {
+        self.frame.map(|(parent, _)| parent)
+    }
+
+    /// An owned copy of the path
+    pub fn to_path(&self) -> Path<'a> {
+        let mut result = self.parent().map(Self::to_path).unwrap_or_default();
+
+        if let Some(entry) = self.last() {
+            result.push(entry);
+        }
+
+        result
+    }
+
+    /// This path points to a JSON object with arbitrary keys
+    pub fn allows_arbitrary_keys(&self) -> bool {
+        is_arbitrary_key_container(self.last())
+    }
+
+    /// This path points to a JSON object whose values are all schemas (such as `properties`)
+    pub fn is_schema_map(&self) -> bool {
+        is_schema_map(self.last())
+    }
+
+    /// This path points to data (such as an `enum` or `default` value) rather than a schema
+    pub fn is_data(&self) -> bool {
+        is_data(self.last(), self.parent().and_then(PathFrame::last))
+    }
+}
+
+impl Debug for PathFrame<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.to_path(), f)
+    }
+}
+
+impl Display for PathFrame<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.to_path(), f)
+    }
+}
+
+fn is_arbitrary_key_container(last: Option<Entry<'_>>) -> bool {
+    matches!(
+        last,
+        Some(Entry::Key(PROPERTIES_KEY) | Entry::Key(DEFS_KEY))
+    )
+}
+
+fn is_schema_map(last: Option<Entry<'_>>) -> bool {
+    matches!(last, Some(Entry::Key(key)) if SCHEMA_MAP_KEYS.contains(&key))
+}
+
+fn is_data(last: Option<Entry<'_>>, parent: Option<Entry<'_>>) -> bool {
+    matches!(last, Some(Entry::Key(key)) if DATA_KEYS.contains(&key)) && !is_schema_map(parent)
+}
+
+fn write_entries(f: &mut std::fmt::Formatter<'_>, entries: &[Entry<'_>]) -> std::fmt::Result {
+    for entry in entries {
+        match entry {
+            Entry::Key(key) => {
+                write!(f, ".{}", key)?;
+            }
+            Entry::Index(index) => {
+                write!(f, "[{}]", index)?;
             }
         }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_prefixes() {
+        let mut parent = Path::default();
+        parent.push(PROPERTIES_KEY);
+
+        let mut child = parent.child("tags");
+        child.push(0);
+
+        assert_eq!(child.to_string(), ".properties.tags[0]");
+        assert_eq!(child.len(), 3);
+        assert!(parent.allows_arbitrary_keys());
+        assert!(!child.allows_arbitrary_keys());
+
+        assert_eq!(child.pop(), Some(Entry::Index(0)));
+        assert_eq!(child.pop(), Some(Entry::Key("tags")));
+        assert_eq!(child, parent);
+        assert_eq!(parent.to_string(), ".properties");
+        assert_eq!(
+            format!("{:?}", parent),
+            "Path { entries: [Key(\"properties\")] }"
+        );
+        assert_eq!(
+            PathRef::new(&[Entry::Key(PROPERTIES_KEY)]).to_path(),
+            parent
+        );
+    }
+
+    #[test]
+    fn frames() {
+        let root = PathFrame::default();
+        let properties = root.child(PROPERTIES_KEY);
+        let default = properties.child("default");
+        let data = default.child("default");
+        let item = data.child(0);
 
-        Ok(())
+        assert_eq!(item.to_string(), ".properties.default.default[0]");
+        assert_eq!(item.len(), 4);
+        assert_eq!(
+            item.to_path(),
+            PathRef::new(&item.to_path().entries()).to_path()
+        );
+        assert!(root.is_empty());
+        assert!(properties.allows_arbitrary_keys());
+        assert!(properties.is_schema_map());
+        assert!(!default.is_data());
+        assert!(data.is_data());
+        assert!(!item.is_data());
+        assert!(PathRef::new(&[Entry::Key("default"), Entry::Key("default")]).is_data());
+        assert!(!PathRef::new(&[Entry::Key(PROPERTIES_KEY), Entry::Key("default")]).is_data());
     }
 }
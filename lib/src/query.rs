@@ -0,0 +1,477 @@
+use serde_json::Value;
+use std::str::FromStr;
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum Error {
+    #[error("Invalid query")]
+    InvalidQuery { query: String, position: usize },
+}
+
+/// A JSON Pointer, or a JSONPath expression using a simple subset of the syntax
+///
+/// The supported JSONPath syntax includes child (`.name`, `['name']`, `[0]`, `*`) and descendant
+/// (`..`) segments, and filters that test whether a member exists (`[?(@.format)]`) or has a given
+/// value (`[?(@.format == 'date-time')]`, which also supports `!=`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Query {
+    Pointer(String),
+    Path(Vec<Segment>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    Child(Selector),
+    Descendant(Selector),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selector {
+    Name(String),
+    Index(usize),
+    Wildcard,
+    Filter(Filter),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+    pub path: Vec<String>,
+    pub comparison: Option<(Comparison, Value)>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+}
+
+impl Query {
+    /// Find the matching values, with JSON Pointers to their locations
+    pub fn select<'a>(&self, value: &'a Value) -> Vec<(String, &'a Value)> {
+        match self {
+            Self::Pointer(pointer) => value
+                .pointer(pointer)
+                .map(|selected| vec![(pointer.clone(), selected)])
+                .unwrap_or_default(),
+            Self::Path(segments) => {
+                let mut nodes = vec![(String::new(), value)];
+
+                for segment in segments {
+                    let mut next = vec![];
+
+                    for (pointer, node) in nodes {
+                        match segment {
+                            Segment::Child(selector) => {
+                                selector.select(&pointer, node, &mut next);
+                            }
+                            Segment::Descendant(selector) => {
+                                for (pointer, descendant) in descendants(pointer, node) {
+                                    selector.select(&pointer, descendant, &mut next);
+                                }
+                            }
+                        }
+                    }
+
+                    nodes = next;
+                }
+
+                nodes
+            }
+        }
+    }
+}
+
+impl Selector {
+    fn select<'a>(&self, pointer: &str, value: &'a Value, acc: &mut Vec<(String, &'a Value)>) {
+        for (key, child) in children(value) {
+            let matches = match (self, &key) {
+                (Self::Name(name), Key::Name(key)) => name == key,
+                (Self::Index(index), Key::Index(key)) => index == key,
+                (Self::Wildcard, _) => true,
+                (Self::Filter(filter), _) => filter.matches(child),
+                _ => false,
+            };
+
+            if matches {
+                acc.push((format!("{}/{}", pointer, key), child));
+            }
+        }
+    }
+}
+
+impl Filter {
+    fn matches(&self, value: &Value) -> bool {
+        let selected = self
+            .path
+            .iter()
+            .try_fold(value, |value, name| value.get(name));
+
+        match (&self.comparison, selected) {
+            (None, selected) => selected.is_some(),
+            (Some((Comparison::Equal, expected)), selected) => selected == Some(expected),
+            (Some((Comparison::NotEqual, expected)), selected) => {
+                selected.is_some_and(|selected| selected != expected)
+            }
+        }
+    }
+}
+
+enum Key<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
+impl std::fmt::Display for Key<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{}", name.replace('~', "~0").replace('/', "~1")),
+            Self::Index(index) => write!(f, "{}", index),
+        }
+    }
+}
+
+fn children(value: &Value) -> Vec<(Key<'_>, &Value)> {
+    match value {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, child)| (Key::Name(key), child))
+            .collect(),
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (Key::Index(index), child))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// The value and all values nested in it, depth-first
+fn descendants(pointer: String, value: &Value) -> Vec<(String, &Value)> {
+    let mut result = vec![];
+    let mut pending = vec![(pointer, value)];
+
+    while let Some((pointer, value)) = pending.pop() {
+        for (key, child) in children(value).into_iter().rev() {
+            pending.push((format!("{}/{}", pointer, key), child));
+        }
+
+        result.push((pointer, value));
+    }
+
+    result
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pointer = s.strip_prefix('#').unwrap_or(s);
+
+        if pointer.is_empty() || pointer.starts_with('/') {
+            return Ok(Self::Pointer(pointer.to_string()));
+        }
+
+        Parser {
+            input: s,
+            position: 0,
+        }
+        .parse()
+        .map(Self::Path)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> Result<Vec<Segment>, Error> {
+        self.expect("$")?;
+
+        let mut segments = vec![];
+
+        while self.position < self.input.len() {
+            if self.eat("..") {
+                segments.push(Segment::Descendant(self.selector()?));
+            } else if self.eat(".") || self.rest().starts_with('[') {
+                segments.push(Segment::Child(self.selector()?));
+            } else {
+                return Err(self.error());
+            }
+        }
+
+        Ok(segments)
+    }
+
+    fn selector(&mut self) -> Result<Selector, Error> {
+        if self.eat("*") {
+            Ok(Selector::Wildcard)
+        } else if self.eat("[") {
+            let selector = if self.eat("*") {
+                Selector::Wildcard
+            } else if self.eat("?(") {
+                let filter = self.filter()?;
+                self.expect(")")?;
+                Selector::Filter(filter)
+            } else if self.rest().starts_with(['\'', '"']) {
+                match self.literal()? {
+                    Value::String(name) => Selector::Name(name),
+                    _ => return Err(self.error()),
+                }
+            } else {
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                Selector::Index(digits.parse().map_err(|_| self.error())?)
+            };
+
+            self.expect("]")?;
+            Ok(selector)
+        } else {
+            let name = self.name();
+
+            if name.is_empty() {
+                Err(self.error())
+            } else {
+                Ok(Selector::Name(name))
+            }
+        }
+    }
+
+    fn filter(&mut self) -> Result<Filter, Error> {
+        self.expect("@")?;
+
+        let mut path = vec![];
+
+        while self.eat(".") {
+            let name = self.name();
+
+            if name.is_empty() {
+                return Err(self.error());
+            }
+
+            path.push(name);
+        }
+
+        self.skip_whitespace();
+
+        let comparison = if self.eat("==") {
+            Some(Comparison::Equal)
+        } else if self.eat("!=") {
+            Some(Comparison::NotEqual)
+        } else {
+            None
+        };
+
+        Ok(Filter {
+            path,
+            comparison: match comparison {
+                Some(comparison) => {
+                    self.skip_whitespace();
+                    let literal = self.literal()?;
+                    self.skip_whitespace();
+                    Some((comparison, literal))
+                }
+                None => None,
+            },
+        })
+    }
+
+    /// A JSON literal, or a string in single quotes
+    fn literal(&mut self) -> Result<Value, Error> {
+        if self.eat("'") {
+            let text = self.take_while(|c| c != '\'');
+            self.expect("'")?;
+            return Ok(Value::String(text));
+        }
+
+        // Numbers and keywords have to be followed by whitespace or a delimiter to be parsed from a
+        // stream, so they're taken up to the end of the filter.
+        if !self.rest().starts_with(['"', '[', '{']) {
+            let start = self.position;
+            let text = self.take_while(|c| !c.is_whitespace() && c != ')');
+
+            return serde_json::from_str(&text).map_err(|_| {
+                self.position = start;
+                self.error()
+            });
+        }
+
+        let mut stream = serde_json::Deserializer::from_str(self.rest()).into_iter::<Value>();
+
+        match stream.next() {
+            Some(Ok(value)) => {
+                self.position += stream.byte_offset();
+                Ok(value)
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    fn name(&mut self) -> String {
+        self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '-')
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> String {
+        let length = self.rest().find(|c| !f(c)).unwrap_or(self.rest().len());
+        let result = self.rest()[..length].to_string();
+        self.position += length;
+        result
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.position..]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn error(&self) -> Error {
+        Error::InvalidQuery {
+            query: self.input.to_string(),
+            position: self.position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_matches() {
+        let value = serde_json::json!({
+            "properties": {
+                "created": { "type": "string", "format": "date-time" },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string", "format": "date-time" }
+                },
+                "a/b": { "type": "integer" }
+            }
+        });
+
+        let select = |query: &str| {
+            query
+                .parse::<Query>()
+                .unwrap()
+                .select(&value)
+                .into_iter()
+                .map(|(pointer, _)| pointer)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            select("$..[?(@.format == 'date-time')]"),
+            vec!["/properties/created", "/properties/tags/items"]
+        );
+        assert_eq!(
+            select("$.properties[?(@.type != \"string\")]"),
+            vec!["/properties/tags", "/properties/a~1b"]
+        );
+        assert_eq!(
+            select("$.properties['a/b'].type"),
+            vec!["/properties/a~1b/type"]
+        );
+        assert_eq!(
+            select("#/properties/tags/items"),
+            vec!["/properties/tags/items"]
+        );
+        assert_eq!(select("$..format").len(), 2);
+
+        assert_eq!(
+            "$.properties[".parse::<Query>(),
+            Err(Error::InvalidQuery {
+                query: "$.properties[".to_string(),
+                position: 13
+            })
+        );
+    }
+
+    #[test]
+    fn select_edge_cases() {
+        let value = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "0": { "type": "integer", "minimum": 0 },
+                "a": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["0", "a"]
+        });
+
+        let select = |query: &str| {
+            query
+                .parse::<Query>()
+                .unwrap()
+                .select(&value)
+                .into_iter()
+                .map(|(pointer, _)| pointer)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(select("$"), vec![""]);
+        assert_eq!(select("#"), vec![""]);
+        assert!(select("/properties/missing").is_empty());
+        assert_eq!(select("$.required[1]"), vec!["/required/1"]);
+        assert!(select("$.properties[0]").is_empty());
+        assert_eq!(select("$.properties['0']"), vec!["/properties/0"]);
+        assert_eq!(
+            select("$..type"),
+            vec![
+                "/type",
+                "/properties/0/type",
+                "/properties/a/type",
+                "/properties/a/items/type"
+            ]
+        );
+        assert_eq!(
+            select("$.properties[?(@.items.type == 'string')]"),
+            vec!["/properties/a"]
+        );
+        assert_eq!(
+            select("$.properties[?(@.type != 'array')]"),
+            vec!["/properties/0"]
+        );
+        assert_eq!(select("$.properties.*.items"), vec!["/properties/a/items"]);
+        assert_eq!(
+            select("$.properties[?(@.minimum == 0)]"),
+            vec!["/properties/0"]
+        );
+        assert_eq!(
+            select("$.properties[?(@.minimum != -1.5)]"),
+            vec!["/properties/0"]
+        );
+        assert!(select("$.properties[?(@.minimum == null)]").is_empty());
+
+        for (query, position) in [
+            ("properties", 0),
+            ("$.", 2),
+            ("$.a[?(@.type == )]", 16),
+            ("$.a[?(@.type == nope)]", 16),
+        ] {
+            assert_eq!(
+                query.parse::<Query>(),
+                Err(Error::InvalidQuery {
+                    query: query.to_string(),
+                    position
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,1316 @@
+use super::schema::{fingerprint_with_options, FingerprintOptions};
+use super::util::{rename_key, walk, walk_mut, Children};
+use super::{constants::*, reference::Reference};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unknown schema")]
+    UnknownSchema(String),
+    #[error("Unknown definition")]
+    UnknownDefinition(String),
+    #[error("Name already in use")]
+    NameInUse(String),
+    #[error("Invalid name")]
+    InvalidName(String),
+    #[error("Invalid pointer")]
+    InvalidPointer(String),
+    #[error("Reference can't be resolved in the slice")]
+    UnsupportedReference(String),
+}
+
+/// Rename a definition in the schema with the given ID, and update references to it in all schemas
+///
+/// Returns the indices of the schemas that were changed.
+pub fn rename_definition(
+    schemas: &mut [Value],
+    id: &str,
+    old: &str,
+    new: &str,
+) -> Result<Vec<usize>, Error> {
+    check_name(new)?;
+
+    let target = find_schema(schemas, id)?;
+    let defs = schemas[target]
+        .get_mut(DEFS_KEY)
+        .and_then(Value::as_object_mut)
+        .filter(|defs| defs.contains_key(old))
+        .ok_or_else(|| Error::UnknownDefinition(old.to_string()))?;
+
+    if defs.contains_key(new) {
+        return Err(Error::NameInUse(new.to_string()));
+    }
+
+    rename_key(defs, old, new);
+
+    let mut result = vec![];
+
+    for (index, schema) in schemas.iter_mut().enumerate() {
+        let changed = rewrite_refs(schema, &mut |reference| match reference {
+            Reference::Both {
+                path_prefix,
+                path_name,
+                fragment_name,
+            } if fragment_name == old && reference.path().as_deref() == Some(id) => Some(
+                Reference::new(path_prefix.clone(), path_name.clone(), new.to_string()),
+            ),
+            Reference::FragmentOnly { fragment_name }
+                if index == target && fragment_name == old =>
+            {
+                Some(Reference::from_fragment_name(new.to_string()))
+            }
+            _ => None,
+        });
+
+        if changed || index == target {
+            result.push(index);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Change the last segment of a schema's ID, and update references to it in all schemas
+///
+/// Returns the indices of the schemas that were changed.
+pub fn rename_schema(schemas: &mut [Value], id: &str, new: &str) -> Result<Vec<usize>, Error> {
+    check_name(new)?;
+
+    let target = find_schema(schemas, id)?;
+    let path_prefix = match id.parse::<Reference>() {
+        Ok(Reference::PathOnly { path_prefix, .. }) => path_prefix,
+        _ => return Err(Error::UnknownSchema(id.to_string())),
+    };
+    let new_id = Reference::from_path(path_prefix, new.to_string()).to_string();
+
+    if find_schema(schemas, &new_id).is_ok() {
+        return Err(Error::NameInUse(new_id));
+    }
+
+    schemas[target][ID_KEY] = Value::String(new_id);
+
+    let mut result = vec![];
+
+    for (index, schema) in schemas.iter_mut().enumerate() {
+        let changed = rewrite_refs(schema, &mut |reference| {
+            if reference.path().as_deref() != Some(id) {
+                return None;
+            }
+
+            match reference {
+                Reference::PathOnly { path_prefix, .. } => {
+                    Some(Reference::from_path(path_prefix.clone(), new.to_string()))
+                }
+                Reference::Both {
+                    path_prefix,
+                    fragment_name,
+                    ..
+                } => Some(Reference::new(
+                    path_prefix.clone(),
+                    new.to_string(),
+                    fragment_name.clone(),
+                )),
+                Reference::FragmentOnly { .. } => None,
+            }
+        });
+
+        if changed || index == target {
+            result.push(index);
+        }
+    }
+
+    Ok(result)
+}
+
+/// The result of replacing ID prefixes
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Retargeted {
+    /// Indices of the schemas that were changed
+    pub changed: Vec<usize>,
+    /// `$id` and `$ref` values (with the indices of their schemas) that no prefix matched
+    pub unmatched: Vec<(usize, String)>,
+}
+
+/// Replace ID prefixes in the `$id`s and `$ref`s of all schemas, using the longest matching prefix
+///
+/// References within a schema (starting with `#`) are left as they are.
+pub fn retarget(schemas: &mut [Value], prefixes: &[(String, String)]) -> Retargeted {
+    let mut result = Retargeted::default();
+
+    for (index, schema) in schemas.iter_mut().enumerate() {
+        let mut unmatched = vec![];
+
+        if retarget_value(schema, prefixes, &mut unmatched) {
+            result.changed.push(index);
+        }
+
+        result
+            .unmatched
+            .extend(unmatched.into_iter().map(|value| (index, value)));
+    }
+
+    result
+}
+
+fn retarget_value(
+    value: &mut Value,
+    prefixes: &[(String, String)],
+    unmatched: &mut Vec<String>,
+) -> bool {
+    let mut changed = false;
+
+    walk_mut(value, |path, value| {
+        if path.is_data() {
+            return Children::Skip;
+        }
+
+        for key in [ID_KEY, REF_KEY] {
+            if let Some(Value::String(uri)) = value.get_mut(key) {
+                if uri.starts_with('#') {
+                    continue;
+                }
+
+                match prefixes
+                    .iter()
+                    .filter(|(old, _)| uri.starts_with(old.as_str()))
+                    .max_by_key(|(old, _)| old.len())
+                {
+                    Some((old, new)) => {
+                        let retargeted = format!("{}{}", new, &uri[old.len()..]);
+
+                        if *uri != retargeted {
+                            *uri = retargeted;
+                            changed = true;
+                        }
+                    }
+                    None => unmatched.push(uri.clone()),
+                }
+            }
+        }
+
+        Children::Visit
+    });
+
+    changed
+}
+
+/// A definition that was replaced by an identical one
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Duplicate {
+    pub schema: usize,
+    pub name: String,
+    pub canonical_schema: usize,
+    pub canonical_name: String,
+}
+
+/// Replace definitions with the first definition that's identical apart from annotations, update
+/// references to them, and remove them
+///
+/// Definitions are only replaced by definitions in other schemas if those schemas have IDs. This is
+/// repeated until there's nothing left to merge, since definitions that refer to merged definitions
+/// may become identical.
+pub fn dedupe(schemas: &mut [Value]) -> Vec<Duplicate> {
+    let mut result = vec![];
+
+    loop {
+        let duplicates = find_duplicates(schemas);
+
+        if duplicates.is_empty() {
+            break;
+        }
+
+        let ids = schemas
+            .iter()
+            .map(|schema| {
+                schema
+                    .get(ID_KEY)
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            })
+            .collect::<Vec<_>>();
+        let replacements = duplicates
+            .iter()
+            .map(|duplicate| {
+                (
+                    (duplicate.schema, duplicate.name.as_str()),
+                    (
+                        duplicate.canonical_schema,
+                        duplicate.canonical_name.as_str(),
+                    ),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        for (index, schema) in schemas.iter_mut().enumerate() {
+            rewrite_refs(schema, &mut |reference| {
+                let target = match reference {
+                    Reference::FragmentOnly { fragment_name } => (index, fragment_name.as_str()),
+                    Reference::Both { fragment_name, .. } => (
+                        ids.iter()
+                            .position(|id| id.is_some() && *id == reference.path())?,
+                        fragment_name.as_str(),
+                    ),
+                    Reference::PathOnly { .. } => return None,
+                };
+                let (canonical_schema, canonical_name) = replacements.get(&target)?;
+
+                if *canonical_schema == index {
+                    Some(Reference::from_fragment_name(canonical_name.to_string()))
+                } else {
+                    // Only schemas with IDs are chosen for other schemas.
+                    match ids[*canonical_schema].as_deref()?.parse().ok()? {
+                        Reference::PathOnly {
+                            path_prefix,
+                            path_name,
+                        } => Some(Reference::new(
+                            path_prefix,
+                            path_name,
+                            canonical_name.to_string(),
+                        )),
+                        _ => None,
+                    }
+                }
+            });
+        }
+
+        for (index, schema) in schemas.iter_mut().enumerate() {
+            let names = duplicates
+                .iter()
+                .filter(|duplicate| duplicate.schema == index)
+                .map(|duplicate| duplicate.name.clone())
+                .collect::<Vec<_>>();
+
+            remove_definitions(schema, &names);
+        }
+
+        result.extend(duplicates);
+    }
+
+    result
+}
+
+/// Group definitions by their fingerprints (with local references qualified by the schema ID), and
+/// pick the first in each group
+fn find_duplicates(schemas: &[Value]) -> Vec<Duplicate> {
+    let mut canonical = HashMap::<_, (usize, String, bool)>::new();
+    let mut result = vec![];
+
+    for (index, schema) in schemas.iter().enumerate() {
+        let id = schema.get(ID_KEY).and_then(Value::as_str);
+        let path = id.and_then(|id| match id.parse() {
+            Ok(Reference::PathOnly {
+                path_prefix,
+                path_name,
+            }) => Some((path_prefix, path_name)),
+            _ => None,
+        });
+
+        for (name, value) in schema
+            .get(DEFS_KEY)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let mut value = value.clone();
+            let mut local = false;
+
+            rewrite_refs(&mut value, &mut |reference| match (reference, &path) {
+                (Reference::FragmentOnly { fragment_name }, Some((path_prefix, path_name))) => {
+                    Some(Reference::new(
+                        path_prefix.clone(),
+                        path_name.clone(),
+                        fragment_name.clone(),
+                    ))
+                }
+                (Reference::FragmentOnly { .. }, None) => {
+                    local = true;
+                    None
+                }
+                _ => None,
+            });
+
+            let fingerprint = fingerprint_with_options(
+                &value,
+                FingerprintOptions {
+                    ignore_annotations: true,
+                },
+            );
+            // Definitions with unqualified local references can only match within the schema.
+            let scope = if local { Some(index) } else { None };
+
+            match canonical.get(&(scope, fingerprint)) {
+                Some((canonical_schema, canonical_name, has_id))
+                    if *canonical_schema == index || *has_id =>
+                {
+                    result.push(Duplicate {
+                        schema: index,
+                        name: name.clone(),
+                        canonical_schema: *canonical_schema,
+                        canonical_name: canonical_name.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    canonical.insert((scope, fingerprint), (index, name.clone(), path.is_some()));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Definitions that can't be reached from the top-level schema or any of the given definitions
+///
+/// Only references within the schema are followed, so definitions that are only used by other
+/// schemas must be given as entry points.
+pub fn unreachable_definitions(value: &Value, entry_points: &[&str]) -> Result<Vec<String>, Error> {
+    let id = value.get(ID_KEY).and_then(Value::as_str);
+    let defs = match value.get(DEFS_KEY).and_then(Value::as_object) {
+        Some(defs) => defs,
+        None => return Ok(vec![]),
+    };
+
+    let mut pending = vec![];
+
+    for name in entry_points {
+        if !defs.contains_key(*name) {
+            return Err(Error::UnknownDefinition(name.to_string()));
+        }
+
+        pending.push(name.to_string());
+    }
+
+    if let Some(fields) = value.as_object() {
+        for (key, child) in fields {
+            if key != DEFS_KEY {
+                internal_refs(child, id, &mut pending);
+            }
+        }
+    }
+
+    let mut reachable = HashSet::new();
+
+    while let Some(name) = pending.pop() {
+        if let Some(def) = defs.get(&name) {
+            if reachable.insert(name) {
+                internal_refs(def, id, &mut pending);
+            }
+        }
+    }
+
+    Ok(defs
+        .keys()
+        .filter(|name| !reachable.contains(*name))
+        .cloned()
+        .collect())
+}
+
+/// Remove unreachable definitions, and return their names
+pub fn prune(value: &mut Value, entry_points: &[&str]) -> Result<Vec<String>, Error> {
+    let unreachable = unreachable_definitions(value, entry_points)?;
+
+    remove_definitions(value, &unreachable);
+
+    Ok(unreachable)
+}
+
+/// Remove the given definitions, and `$defs` itself if it's left empty
+fn remove_definitions(value: &mut Value, names: &[String]) {
+    if let Some(fields) = value.as_object_mut() {
+        if let Some(Value::Object(defs)) = fields.get_mut(DEFS_KEY) {
+            defs.retain(|name, _| !names.contains(name));
+
+            if defs.is_empty() {
+                fields.shift_remove(DEFS_KEY);
+            }
+        }
+    }
+}
+
+/// Move the subschema at a JSON Pointer into a new definition, and replace it with a reference
+///
+/// The pointer may be given as a URI fragment (starting with `#`).
+pub fn extract(value: &mut Value, pointer: &str, name: &str) -> Result<(), Error> {
+    check_name(name)?;
+
+    let pointer = pointer.strip_prefix('#').unwrap_or(pointer);
+
+    match value.get(DEFS_KEY) {
+        Some(Value::Object(defs)) if defs.contains_key(name) => {
+            return Err(Error::NameInUse(name.to_string()))
+        }
+        Some(Value::Object(_)) | None => {}
+        Some(_) => return Err(Error::InvalidPointer(format!("/{}", DEFS_KEY))),
+    }
+
+    // The root schema and existing definitions can't be extracted.
+    let is_def = pointer
+        .strip_prefix(&format!("/{}/", DEFS_KEY))
+        .is_some_and(|rest| !rest.contains('/'));
+
+    let target = value
+        .pointer_mut(pointer)
+        .filter(|target| {
+            !pointer.is_empty()
+                && !is_def
+                && (target.is_object() || target.is_boolean())
+                && is_schema_position(pointer)
+        })
+        .ok_or_else(|| Error::InvalidPointer(pointer.to_string()))?;
+
+    let reference = Value::Object(serde_json::Map::from_iter([(
+        REF_KEY.to_string(),
+        Value::String(Reference::from_fragment_name(name.to_string()).to_string()),
+    )]));
+    let subschema = std::mem::replace(target, reference);
+
+    match value.as_object_mut() {
+        Some(fields) => {
+            if let Value::Object(defs) = fields
+                .entry(DEFS_KEY)
+                .or_insert_with(|| Value::Object(serde_json::Map::new()))
+            {
+                defs.insert(name.to_string(), subschema);
+            }
+
+            Ok(())
+        }
+        None => Err(Error::InvalidPointer(pointer.to_string())),
+    }
+}
+
+/// Whether a JSON Pointer leads to a place where a schema is expected (as opposed to a keyword's
+/// value, like a `type` or `enum`)
+fn is_schema_position(pointer: &str) -> bool {
+    #[derive(Clone, Copy)]
+    enum Position {
+        Schema,
+        // The draft-07 `items` can be either a schema or an array of schemas.
+        SchemaOrArray,
+        SchemaMap,
+        SchemaArray,
+    }
+
+    let mut position = Position::Schema;
+
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        let is_index = segment.parse::<usize>().is_ok();
+
+        position = match position {
+            Position::SchemaMap => Position::Schema,
+            Position::SchemaArray | Position::SchemaOrArray if is_index => Position::Schema,
+            Position::SchemaArray => return false,
+            Position::Schema | Position::SchemaOrArray => match segment.as_str() {
+                ITEMS_KEY => Position::SchemaOrArray,
+                key if SUBSCHEMA_KEYS.contains(&key) => Position::Schema,
+                key if SCHEMA_MAP_KEYS.contains(&key) => Position::SchemaMap,
+                key if SCHEMA_ARRAY_KEYS.contains(&key) => Position::SchemaArray,
+                _ => return false,
+            },
+        };
+    }
+
+    matches!(position, Position::Schema | Position::SchemaOrArray)
+}
+
+/// Build a standalone schema from a subschema and the definitions it transitively refers to
+///
+/// The subschema can be given as a JSON Pointer (which may start with `#`) or as a definition name.
+/// Local references into the subschema are rewritten to point into the slice, and references into
+/// definitions bring in the whole definition. Other local references (including `#` when slicing
+/// anything but the root) are errors, and references to other schemas are left as they are.
+pub fn slice(value: &Value, target: &str) -> Result<Value, Error> {
+    let pointer = if target.starts_with('/') || target.starts_with('#') {
+        target.strip_prefix('#').unwrap_or(target).to_string()
+    } else {
+        format!("/{}/{}", DEFS_KEY, target)
+    };
+    let id = value.get(ID_KEY).and_then(Value::as_str);
+    let mut subschema = value
+        .pointer(&pointer)
+        .cloned()
+        .ok_or_else(|| Error::InvalidPointer(pointer.clone()))?;
+    let defs = value.get(DEFS_KEY).and_then(Value::as_object);
+
+    let mut pending = vec![];
+    sliced_refs(&subschema, id, &pointer, &mut pending)?;
+
+    let mut reachable = HashSet::new();
+
+    while let Some(name) = pending.pop() {
+        match defs.and_then(|defs| defs.get(&name)) {
+            Some(def) => {
+                if reachable.insert(name) {
+                    sliced_refs(def, id, &pointer, &mut pending)?;
+                }
+            }
+            None => {
+                return Err(Error::UnsupportedReference(format!(
+                    "#/{}/{}",
+                    DEFS_KEY, name
+                )))
+            }
+        }
+    }
+
+    localize_refs(&mut subschema, id, &pointer);
+
+    let mut sliced_defs = serde_json::Map::new();
+
+    for (name, def) in defs.into_iter().flatten() {
+        if reachable.contains(name) {
+            let mut def = def.clone();
+            localize_refs(&mut def, id, &pointer);
+            sliced_defs.insert(name.clone(), def);
+        }
+    }
+
+    if sliced_defs.is_empty() && value.get(SCHEMA_KEY).is_none() {
+        return Ok(subschema);
+    }
+
+    let mut fields = serde_json::Map::new();
+
+    if let Some(dialect) = value.get(SCHEMA_KEY) {
+        fields.insert(SCHEMA_KEY.to_string(), dialect.clone());
+    }
+
+    match subschema {
+        Value::Object(subschema_fields) => fields.extend(subschema_fields),
+        Value::Bool(false) => {
+            fields.insert(NOT_KEY.to_string(), Value::Bool(true));
+        }
+        _ => {}
+    }
+
+    if !sliced_defs.is_empty() {
+        fields.insert(DEFS_KEY.to_string(), Value::Object(sliced_defs));
+    }
+
+    Ok(Value::Object(fields))
+}
+
+/// The JSON Pointer in a reference to a location within the schema with the given ID
+fn local_pointer<'a>(reference: &'a str, id: Option<&str>) -> Option<&'a str> {
+    let (path, fragment) = reference.split_once('#')?;
+
+    if (path.is_empty() || Some(path) == id) && (fragment.is_empty() || fragment.starts_with('/')) {
+        Some(fragment)
+    } else {
+        None
+    }
+}
+
+/// The name of the definition a JSON Pointer leads into, if it does
+fn pointer_definition(pointer: &str) -> Option<String> {
+    let rest = pointer.strip_prefix(&format!("/{}/", DEFS_KEY))?;
+    let name = rest.split('/').next().unwrap_or(rest);
+
+    Some(name.replace("~1", "/").replace("~0", "~"))
+}
+
+/// The rest of a JSON Pointer below a prefix, if it starts with it
+fn pointer_below<'a>(pointer: &'a str, prefix: &str) -> Option<&'a str> {
+    pointer
+        .strip_prefix(prefix)
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Collect the definitions that a slice of the subschema at the given pointer needs, and check
+/// that every other local reference can be rewritten to point into the slice
+fn sliced_refs(
+    value: &Value,
+    id: Option<&str>,
+    target: &str,
+    acc: &mut Vec<String>,
+) -> Result<(), Error> {
+    let mut result = Ok(());
+
+    walk(value, |path, value| {
+        if path.is_data() || result.is_err() {
+            return Children::Skip;
+        }
+
+        if let Some(reference) = value.get(REF_KEY).and_then(Value::as_str) {
+            if let Some(pointer) = local_pointer(reference, id) {
+                if let Some(name) = pointer_definition(pointer) {
+                    acc.push(name);
+                } else if pointer_below(pointer, target).is_none() {
+                    result = Err(Error::UnsupportedReference(reference.to_string()));
+                }
+            }
+        }
+
+        Children::Visit
+    });
+
+    result
+}
+
+/// Rewrite local references for a slice of the subschema at the given pointer (definitions keep
+/// their names, and references that include the schema's ID would no longer resolve)
+fn localize_refs(value: &mut Value, id: Option<&str>, target: &str) {
+    walk_mut(value, |path, value| {
+        if path.is_data() {
+            return Children::Skip;
+        }
+
+        if let Some(Value::String(reference)) = value.get_mut(REF_KEY) {
+            if let Some(pointer) = local_pointer(reference, id) {
+                let new = match pointer_below(pointer, target) {
+                    Some(rest) if pointer_definition(pointer).is_none() => format!("#{}", rest),
+                    _ => format!("#{}", pointer),
+                };
+
+                *reference = new;
+            }
+        }
+
+        Children::Visit
+    });
+}
+
+/// Replace references to a definition with copies of it, and return the number replaced
+///
+/// The definition can be given by name or as a pointer (such as `#/$defs/foo`). If a pointer to a
+/// reference is given, only that reference is replaced. If `remove` is true, the definition is
+/// deleted if nothing else refers to it.
+pub fn inline(
+    value: &mut Value,
+    definition: &str,
+    at: Option<&str>,
+    remove: bool,
+) -> Result<usize, Error> {
+    let name = definition.strip_prefix('#').unwrap_or(definition);
+    let name = name
+        .strip_prefix(&format!("/{}/", DEFS_KEY))
+        .unwrap_or(name)
+        .to_string();
+    let id = value
+        .get(ID_KEY)
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let body = value
+        .get(DEFS_KEY)
+        .and_then(|defs| defs.get(&name))
+        .cloned()
+        .ok_or_else(|| Error::UnknownDefinition(name.clone()))?;
+
+    let count = match at {
+        Some(pointer) => {
+            let pointer = pointer.strip_prefix('#').unwrap_or(pointer);
+
+            match value.pointer_mut(pointer) {
+                Some(target) if refers_to(target, id.as_deref(), &name) => {
+                    replace_ref(target, &body);
+                    1
+                }
+                _ => return Err(Error::InvalidPointer(pointer.to_string())),
+            }
+        }
+        None => {
+            let mut count = 0;
+
+            if let Some(fields) = value.as_object_mut() {
+                for (key, child) in fields.iter_mut() {
+                    if key == DEFS_KEY {
+                        if let Some(defs) = child.as_object_mut() {
+                            for (_, def) in
+                                defs.iter_mut().filter(|(def_name, _)| **def_name != name)
+                            {
+                                count += inline_refs(def, id.as_deref(), &name, &body);
+                            }
+                        }
+                    } else if !DATA_KEYS.contains(&key.as_str()) {
+                        count += inline_refs(child, id.as_deref(), &name, &body);
+                    }
+                }
+            }
+
+            count
+        }
+    };
+
+    if remove {
+        let mut refs = vec![];
+
+        if let Some(fields) = value.as_object() {
+            for (key, child) in fields {
+                if key == DEFS_KEY {
+                    for (def_name, def) in child.as_object().into_iter().flatten() {
+                        if *def_name != name {
+                            internal_refs(def, id.as_deref(), &mut refs);
+                        }
+                    }
+                } else {
+                    internal_refs(child, id.as_deref(), &mut refs);
+                }
+            }
+        }
+
+        if !refs.contains(&name) {
+            remove_definitions(value, &[name]);
+        }
+    }
+
+    Ok(count)
+}
+
+fn refers_to(value: &Value, id: Option<&str>, name: &str) -> bool {
+    value
+        .get(REF_KEY)
+        .and_then(Value::as_str)
+        .and_then(|reference| internal_ref_name(reference, id))
+        .is_some_and(|reference_name| reference_name == name)
+}
+
+fn inline_refs(value: &mut Value, id: Option<&str>, name: &str, body: &Value) -> usize {
+    let mut count = 0;
+
+    walk_mut(value, |path, value| {
+        if path.is_data() {
+            Children::Skip
+        } else if refers_to(value, id, name) {
+            replace_ref(value, body);
+            count += 1;
+
+            Children::Skip
+        } else {
+            Children::Visit
+        }
+    });
+
+    count
+}
+
+/// Replace a reference with a copy of its target, keeping any keywords alongside it
+///
+/// Merging the target into the referring schema would change the meaning of keywords like
+/// `additionalProperties`, which only see properties declared next to them, so if there are other
+/// keywords the target is wrapped in an `allOf` instead.
+fn replace_ref(value: &mut Value, body: &Value) {
+    let mut siblings = match std::mem::take(value) {
+        Value::Object(fields) => fields,
+        _ => serde_json::Map::new(),
+    };
+    siblings.shift_remove(REF_KEY);
+
+    *value = if siblings.is_empty() {
+        body.clone()
+    } else {
+        let mut fields = serde_json::Map::from_iter([(
+            ALL_OF_KEY.to_string(),
+            Value::Array(vec![body.clone()]),
+        )]);
+        fields.extend(siblings);
+        Value::Object(fields)
+    };
+}
+
+/// Collect the names of the definitions referred to within a schema with the given ID
+pub(crate) fn internal_refs(value: &Value, id: Option<&str>, acc: &mut Vec<String>) {
+    walk(value, |path, value| {
+        if path.is_data() {
+            return Children::Skip;
+        }
+
+        if let Some(name) = value
+            .get(REF_KEY)
+            .and_then(Value::as_str)
+            .and_then(|reference| internal_ref_name(reference, id))
+        {
+            acc.push(name);
+        }
+
+        Children::Visit
+    });
+}
+
+/// The name of the definition a reference refers to, if it's within the schema with the given ID
+fn internal_ref_name(reference: &str, id: Option<&str>) -> Option<String> {
+    let reference = reference.parse::<Reference>().ok()?;
+
+    match &reference {
+        Reference::FragmentOnly { fragment_name } => Some(fragment_name.clone()),
+        Reference::Both { fragment_name, .. } if reference.path().as_deref() == id => {
+            Some(fragment_name.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Names must be usable in references
+fn check_name(name: &str) -> Result<(), Error> {
+    if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(Error::InvalidName(name.to_string()))
+    }
+}
+
+fn find_schema(schemas: &[Value], id: &str) -> Result<usize, Error> {
+    schemas
+        .iter()
+        .position(|schema| schema.get(ID_KEY).and_then(Value::as_str) == Some(id))
+        .ok_or_else(|| Error::UnknownSchema(id.to_string()))
+}
+
+/// Replace references for which the function returns a new reference, and return whether any were
+/// replaced
+fn rewrite_refs<F: FnMut(&Reference) -> Option<Reference>>(value: &mut Value, f: &mut F) -> bool {
+    let mut changed = false;
+
+    walk_mut(value, |path, value| {
+        if path.is_data() {
+            return Children::Skip;
+        }
+
+        if let Some(Value::String(reference)) = value.get_mut(REF_KEY) {
+            if let Some(new) = reference
+                .parse::<Reference>()
+                .ok()
+                .and_then(|parsed| f(&parsed))
+            {
+                *reference = new.to_string();
+                changed = true;
+            }
+        }
+
+        Children::Visit
+    });
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rename_skips_data() {
+        let mut schemas = vec![serde_json::json!({
+            "$id": "/schemas/user",
+            "properties": {
+                "default": { "$ref": "#/$defs/role" },
+                "fixed": { "const": { "$ref": "#/$defs/role" } }
+            },
+            "$defs": { "role": {} }
+        })];
+
+        assert_eq!(
+            rename_definition(&mut schemas, "/schemas/user", "role", "user_role").unwrap(),
+            vec![0]
+        );
+        assert_eq!(
+            schemas[0]["properties"]["default"]["$ref"],
+            "#/$defs/user_role"
+        );
+        assert_eq!(
+            schemas[0]["properties"]["fixed"]["const"]["$ref"],
+            "#/$defs/role"
+        );
+    }
+
+    #[test]
+    fn rename_across_schemas() {
+        let mut schemas = vec![
+            serde_json::json!({
+                "$id": "/schemas/user",
+                "properties": { "role": { "$ref": "#/$defs/role" } },
+                "$defs": { "role": { "enum": ["admin"] }, "other": {} }
+            }),
+            serde_json::json!({
+                "$id": "/schemas/team",
+                "properties": {
+                    "members": { "items": { "$ref": "/schemas/user" } },
+                    "lead": { "$ref": "/schemas/user#/$defs/role" },
+                    "local": { "$ref": "#/$defs/role" }
+                },
+                "$defs": { "role": {} }
+            }),
+            serde_json::json!({ "$id": "/schemas/unrelated" }),
+        ];
+
+        assert_eq!(
+            rename_definition(&mut schemas, "/schemas/user", "role", "user_role").unwrap(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            schemas[0]["$defs"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["user_role", "other"]
+        );
+        assert_eq!(
+            schemas[0]["properties"]["role"]["$ref"],
+            "#/$defs/user_role"
+        );
+        assert_eq!(
+            schemas[1]["properties"]["lead"]["$ref"],
+            "/schemas/user#/$defs/user_role"
+        );
+        assert_eq!(schemas[1]["properties"]["local"]["$ref"], "#/$defs/role");
+
+        assert!(matches!(
+            rename_definition(&mut schemas, "/schemas/user", "other", "user_role"),
+            Err(Error::NameInUse(_))
+        ));
+
+        assert_eq!(
+            rename_schema(&mut schemas, "/schemas/user", "account").unwrap(),
+            vec![0, 1]
+        );
+        assert_eq!(schemas[0]["$id"], "/schemas/account");
+        assert_eq!(
+            schemas[1]["properties"]["members"]["items"]["$ref"],
+            "/schemas/account"
+        );
+        assert_eq!(
+            schemas[1]["properties"]["lead"]["$ref"],
+            "/schemas/account#/$defs/user_role"
+        );
+    }
+
+    #[test]
+    fn prune_unreachable() {
+        let mut value = serde_json::json!({
+            "$id": "/schemas/user",
+            "properties": { "role": { "$ref": "#/$defs/role" } },
+            "$defs": {
+                "role": { "anyOf": [{ "$ref": "/schemas/user#/$defs/name" }] },
+                "name": { "type": "string" },
+                "unused": { "$ref": "#/$defs/also_unused" },
+                "also_unused": { "default": { "$ref": "#/$defs/kept" } },
+                "kept": {}
+            }
+        });
+
+        assert_eq!(
+            prune(&mut value, &["kept"]).unwrap(),
+            vec!["unused", "also_unused"]
+        );
+        assert_eq!(
+            value["$defs"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["role", "name", "kept"]
+        );
+        assert!(matches!(
+            prune(&mut value, &["missing"]),
+            Err(Error::UnknownDefinition(_))
+        ));
+    }
+
+    #[test]
+    fn extract_subschema() {
+        let mut value = serde_json::json!({
+            "properties": {
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } }
+                }
+            },
+            "$defs": { "other": {} }
+        });
+
+        extract(&mut value, "#/properties/address", "address").unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "properties": { "address": { "$ref": "#/$defs/address" } },
+                "$defs": {
+                    "other": {},
+                    "address": {
+                        "type": "object",
+                        "properties": { "city": { "type": "string" } }
+                    }
+                }
+            })
+        );
+        assert!(matches!(
+            extract(&mut value, "/$defs/other", "copy"),
+            Err(Error::InvalidPointer(_))
+        ));
+        assert!(matches!(
+            extract(&mut value, "/properties/missing", "missing"),
+            Err(Error::InvalidPointer(_))
+        ));
+    }
+
+    #[test]
+    fn extract_only_schemas() {
+        let original = serde_json::json!({
+            "properties": {
+                "b": { "type": "string", "enum": [{ "type": "object" }] },
+                "c": { "items": [true, { "type": "null" }] },
+                "type": { "anyOf": [false] }
+            }
+        });
+
+        for pointer in [
+            "/properties",
+            "/properties/b/type",
+            "/properties/b/enum/0",
+            "/properties/c/items/0/type",
+            "/properties/type/anyOf",
+        ] {
+            let mut value = original.clone();
+
+            assert!(matches!(
+                extract(&mut value, pointer, "extracted"),
+                Err(Error::InvalidPointer(_))
+            ));
+            assert_eq!(value, original);
+        }
+
+        for pointer in [
+            "/properties/b",
+            "/properties/c/items/0",
+            "/properties/c/items/1",
+            "/properties/type/anyOf/0",
+        ] {
+            let mut value = original.clone();
+
+            assert!(extract(&mut value, pointer, "extracted").is_ok());
+        }
+    }
+
+    #[test]
+    fn inline_definition() {
+        let mut value = serde_json::json!({
+            "properties": {
+                "a": { "$ref": "#/$defs/name" },
+                "b": { "$ref": "#/$defs/name", "description": "B" },
+                "c": { "$ref": "#/$defs/name", "type": "null" }
+            },
+            "$defs": { "name": { "type": "string" } }
+        });
+
+        assert_eq!(
+            inline(&mut value, "name", Some("/properties/a"), true).unwrap(),
+            1
+        );
+        assert_eq!(
+            value["properties"]["a"],
+            serde_json::json!({ "type": "string" })
+        );
+        assert!(value["$defs"].get("name").is_some());
+
+        assert_eq!(inline(&mut value, "#/$defs/name", None, true).unwrap(), 2);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "properties": {
+                    "a": { "type": "string" },
+                    "b": { "allOf": [{ "type": "string" }], "description": "B" },
+                    "c": { "allOf": [{ "type": "string" }], "type": "null" }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn inline_keeps_sibling_keywords_separate() {
+        let mut value = serde_json::json!({
+            "$ref": "#/$defs/a",
+            "additionalProperties": false,
+            "$defs": { "a": { "properties": { "x": { "type": "string" } } } }
+        });
+
+        assert_eq!(inline(&mut value, "a", Some(""), true).unwrap(), 1);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "allOf": [{ "properties": { "x": { "type": "string" } } }],
+                "additionalProperties": false
+            })
+        );
+    }
+
+    #[test]
+    fn slice_definition() {
+        let value = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "/schemas/api",
+            "properties": { "order": { "$ref": "#/$defs/order" } },
+            "$defs": {
+                "customer": { "properties": { "name": { "type": "string" } } },
+                "order": {
+                    "properties": {
+                        "items": { "items": { "$ref": "#/$defs/item" } },
+                        "customer": { "$ref": "/schemas/api#/$defs/customer" }
+                    }
+                },
+                "item": { "properties": { "parent": { "$ref": "#/$defs/order" } } },
+                "unrelated": {}
+            }
+        });
+
+        let sliced = slice(&value, "order").unwrap();
+
+        assert_eq!(sliced, slice(&value, "#/$defs/order").unwrap());
+        assert_eq!(
+            sliced,
+            serde_json::json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "properties": {
+                    "items": { "items": { "$ref": "#/$defs/item" } },
+                    "customer": { "$ref": "#/$defs/customer" }
+                },
+                "$defs": {
+                    "customer": { "properties": { "name": { "type": "string" } } },
+                    "order": {
+                        "properties": {
+                            "items": { "items": { "$ref": "#/$defs/item" } },
+                            "customer": { "$ref": "#/$defs/customer" }
+                        }
+                    },
+                    "item": { "properties": { "parent": { "$ref": "#/$defs/order" } } }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn slice_local_pointers() {
+        let value = serde_json::json!({
+            "$id": "/schemas/tree",
+            "properties": {
+                "node": {
+                    "properties": {
+                        "children": { "items": { "$ref": "#/properties/node" } },
+                        "label": { "$ref": "/schemas/tree#/properties/node/properties/name" },
+                        "name": { "$ref": "#/$defs/name/allOf/0" }
+                    }
+                },
+                "root": { "$ref": "#" }
+            },
+            "$defs": { "name": { "allOf": [{ "type": "string" }] } }
+        });
+
+        assert_eq!(
+            slice(&value, "/properties/node").unwrap(),
+            serde_json::json!({
+                "properties": {
+                    "children": { "items": { "$ref": "#" } },
+                    "label": { "$ref": "#/properties/name" },
+                    "name": { "$ref": "#/$defs/name/allOf/0" }
+                },
+                "$defs": { "name": { "allOf": [{ "type": "string" }] } }
+            })
+        );
+        assert!(matches!(
+            slice(&value, "/properties/root"),
+            Err(Error::UnsupportedReference(reference)) if reference == "#"
+        ));
+        assert!(matches!(
+            slice(&value, "/properties/node/properties/children"),
+            Err(Error::UnsupportedReference(reference)) if reference == "#/properties/node"
+        ));
+    }
+
+    #[test]
+    fn retarget_prefixes() {
+        let mut schemas = vec![
+            serde_json::json!({
+                "$id": "/schemas/user",
+                "properties": {
+                    "team": { "$ref": "/schemas/shared/team#/$defs/name" },
+                    "role": { "$ref": "#/$defs/role" },
+                    "other": { "$ref": "https://example.org/other" }
+                },
+                "$defs": { "role": { "const": { "$ref": "/schemas/user" } } }
+            }),
+            serde_json::json!({ "$id": "https://schemas.example.com/v2/team" }),
+        ];
+
+        let result = retarget(
+            &mut schemas,
+            &[
+                (
+                    "/schemas/".to_string(),
+                    "https://schemas.example.com/v2/".to_string(),
+                ),
+                (
+                    "/schemas/shared/".to_string(),
+                    "https://shared.example.com/".to_string(),
+                ),
+                (
+                    "https://schemas.example.com/v2/".to_string(),
+                    "https://schemas.example.com/v2/".to_string(),
+                ),
+            ],
+        );
+
+        assert_eq!(
+            result,
+            Retargeted {
+                changed: vec![0],
+                unmatched: vec![(0, "https://example.org/other".to_string())]
+            }
+        );
+        assert_eq!(schemas[0][ID_KEY], "https://schemas.example.com/v2/user");
+        assert_eq!(
+            schemas[0]["properties"]["team"][REF_KEY],
+            "https://shared.example.com/team#/$defs/name"
+        );
+        assert_eq!(schemas[0]["properties"]["role"][REF_KEY], "#/$defs/role");
+        assert_eq!(
+            schemas[0]["$defs"]["role"]["const"][REF_KEY],
+            "/schemas/user"
+        );
+    }
+
+    #[test]
+    fn dedupe_definitions() {
+        let mut schemas = vec![
+            serde_json::json!({
+                "$id": "/schemas/common",
+                "$defs": {
+                    "name": { "type": "string", "minLength": 1 },
+                    "person": { "properties": { "name": { "$ref": "#/$defs/name" } } }
+                }
+            }),
+            serde_json::json!({
+                "$id": "/schemas/user",
+                "properties": {
+                    "name": { "$ref": "#/$defs/userName" },
+                    "manager": { "$ref": "#/$defs/manager" }
+                },
+                "$defs": {
+                    "userName": { "minLength": 1, "type": "string", "description": "A name" },
+                    "manager": { "properties": { "name": { "$ref": "#/$defs/userName" } } }
+                }
+            }),
+        ];
+
+        let duplicates = dedupe(&mut schemas);
+
+        assert_eq!(
+            duplicates
+                .iter()
+                .map(|duplicate| (duplicate.name.as_str(), duplicate.canonical_name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("userName", "name"), ("manager", "person")]
+        );
+        assert_eq!(
+            schemas[1],
+            serde_json::json!({
+                "$id": "/schemas/user",
+                "properties": {
+                    "name": { "$ref": "/schemas/common#/$defs/name" },
+                    "manager": { "$ref": "/schemas/common#/$defs/person" }
+                }
+            })
+        );
+        assert_eq!(dedupe(&mut schemas), vec![]);
+    }
+}
@@ -0,0 +1,201 @@
+use super::constants::*;
+use serde_json::Value;
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum Error {
+    #[error("Unknown schema")]
+    UnknownSchema(String),
+    #[error("Unresolvable fragment")]
+    UnresolvableFragment(String),
+    #[error("Missing base schema")]
+    MissingBase(String),
+}
+
+/// The target of a reference
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resolved<'a> {
+    /// Index of the schema containing the target
+    pub schema: usize,
+    /// JSON Pointer to the target within the schema
+    pub pointer: String,
+    pub value: &'a Value,
+}
+
+/// Resolve a reference against a collection of schemas, identified by their `$id`s
+///
+/// Relative references (including fragment-only references) are resolved against the base
+/// schema, if one is given. Fragments can be JSON Pointers or `$anchor` names.
+pub fn resolve<'a>(
+    schemas: &'a [Value],
+    reference: &str,
+    base: Option<usize>,
+) -> Result<Resolved<'a>, Error> {
+    let (path, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+
+    let schema = if path.is_empty() {
+        base.ok_or_else(|| Error::MissingBase(reference.to_string()))?
+    } else {
+        let base_id = base.and_then(|base| schemas[base].get(ID_KEY).and_then(Value::as_str));
+        let id = match base_id {
+            Some(base_id) => join(base_id, path),
+            None => path.to_string(),
+        };
+
+        schemas
+            .iter()
+            .position(|schema| schema.get(ID_KEY).and_then(Value::as_str) == Some(&id))
+            .ok_or(Error::UnknownSchema(id))?
+    };
+
+    let value = &schemas[schema];
+
+    let found = if fragment.is_empty() || fragment.starts_with('/') {
+        value
+            .pointer(fragment)
+            .map(|target| (fragment.to_string(), target))
+    } else {
+        find_anchor(value, fragment, String::new())
+    };
+
+    found
+        .map(|(pointer, value)| Resolved {
+            schema,
+            pointer,
+            value,
+        })
+        .ok_or_else(|| Error::UnresolvableFragment(fragment.to_string()))
+}
+
+/// Resolve a path against a base ID, for the simple cases that appear in schema IDs
+fn join(base: &str, path: &str) -> String {
+    if path.contains("://") {
+        return path.to_string();
+    }
+
+    let origin_length = base
+        .find("://")
+        .map(|scheme_end| {
+            base[scheme_end + 3..]
+                .find('/')
+                .map(|host_end| scheme_end + 3 + host_end)
+                .unwrap_or(base.len())
+        })
+        .unwrap_or(0);
+
+    if path.starts_with('/') {
+        format!("{}{}", &base[..origin_length], path)
+    } else {
+        match base.rfind('/').map(|index| index + 1) {
+            Some(directory_end) if directory_end > origin_length => {
+                format!("{}{}", &base[..directory_end], path)
+            }
+            // A base with no path (like `https://example.com`) has the root as its directory.
+            _ if origin_length > 0 => format!("{}/{}", &base[..origin_length], path),
+            _ => path.to_string(),
+        }
+    }
+}
+
+/// Find a subschema with the given `$anchor` (or a draft-07 style `$id` fragment)
+fn find_anchor<'a>(value: &'a Value, name: &str, pointer: String) -> Option<(String, &'a Value)> {
+    match value {
+        Value::Object(fields) => {
+            let plain_name = format!("#{}", name);
+
+            if fields.get(ANCHOR_KEY).and_then(Value::as_str) == Some(name)
+                || fields.get(ID_KEY).and_then(Value::as_str) == Some(&plain_name)
+            {
+                return Some((pointer, value));
+            }
+
+            fields
+                .iter()
+                .filter(|(key, _)| !DATA_KEYS.contains(&key.as_str()))
+                .find_map(|(key, child)| {
+                    find_anchor(
+                        child,
+                        name,
+                        format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1")),
+                    )
+                })
+        }
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .find_map(|(index, child)| find_anchor(child, name, format!("{}/{}", pointer, index))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_references() {
+        let schemas = vec![
+            serde_json::json!({
+                "$id": "https://example.com/schemas/user",
+                "properties": { "team": { "$ref": "team#/$defs/team" } },
+                "$defs": { "name": { "$anchor": "name", "type": "string" } }
+            }),
+            serde_json::json!({
+                "$id": "https://example.com/schemas/team",
+                "$defs": { "team": { "type": "object" } }
+            }),
+        ];
+
+        let resolved = resolve(&schemas, "team#/$defs/team", Some(0)).unwrap();
+        assert_eq!(
+            (resolved.schema, resolved.pointer.as_str()),
+            (1, "/$defs/team")
+        );
+
+        let resolved = resolve(&schemas, "/schemas/user#name", Some(1)).unwrap();
+        assert_eq!(
+            (resolved.schema, resolved.pointer.as_str()),
+            (0, "/$defs/name")
+        );
+
+        assert_eq!(
+            resolve(&schemas, "https://example.com/schemas/team", None)
+                .unwrap()
+                .pointer,
+            ""
+        );
+        assert_eq!(
+            resolve(&schemas, "#/$defs/missing", Some(0)),
+            Err(Error::UnresolvableFragment("/$defs/missing".to_string()))
+        );
+        assert_eq!(
+            resolve(&schemas, "#/$defs/name", None),
+            Err(Error::MissingBase("#/$defs/name".to_string()))
+        );
+    }
+
+    #[test]
+    fn join_paths() {
+        assert_eq!(
+            join("https://example.com/schemas/user", "team"),
+            "https://example.com/schemas/team"
+        );
+        assert_eq!(
+            join("https://example.com/schemas/user", "/team"),
+            "https://example.com/team"
+        );
+        assert_eq!(
+            join("https://example.com", "team"),
+            "https://example.com/team"
+        );
+        assert_eq!(
+            join("https://example.com/", "team"),
+            "https://example.com/team"
+        );
+        assert_eq!(join("/schemas/user", "team"), "/schemas/team");
+        assert_eq!(join("user", "team"), "team");
+        assert_eq!(
+            join("/schemas/user", "https://example.com/team"),
+            "https://example.com/team"
+        );
+    }
+}
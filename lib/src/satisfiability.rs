@@ -0,0 +1,470 @@
+use super::constants::*;
+use super::key::Key;
+use super::reference::Reference;
+use super::schema::{AdditionalProperties, Schema, SchemaDef, SchemaFile, SchemaType};
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// A schema that no instance can satisfy
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unsatisfiable {
+    /// The keys leading to the schema in the file
+    pub path: Vec<String>,
+    pub reason: Reason,
+}
+
+/// Why a schema can't be satisfied, possibly in terms of the reasons its sub-schemas can't
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reason {
+    EmptyEnum,
+    EmptyRange {
+        minimum_keyword: &'static str,
+        maximum_keyword: &'static str,
+        minimum: Value,
+        maximum: Value,
+    },
+    /// A required property is not listed and additional properties are forbidden
+    MissingRequired {
+        name: String,
+    },
+    /// The branch at this index contradicts the preceding branches of an `allOf`
+    ContradictoryAllOf {
+        index: usize,
+        kinds: Vec<&'static str>,
+    },
+    RequiredProperty {
+        name: String,
+        cause: Box<Reason>,
+    },
+    Items {
+        min_items: usize,
+        cause: Box<Reason>,
+    },
+    Branch {
+        keyword: &'static str,
+        index: usize,
+        cause: Box<Reason>,
+    },
+    AllBranches {
+        keyword: &'static str,
+        causes: Vec<Reason>,
+    },
+    Ref {
+        value: String,
+        cause: Box<Reason>,
+    },
+}
+
+impl Reason {
+    /// The reasoning chain, from this schema down to the root causes
+    pub fn chain(&self) -> Vec<String> {
+        let mut result = vec![self.to_string()];
+
+        match self {
+            Self::RequiredProperty { cause, .. }
+            | Self::Items { cause, .. }
+            | Self::Branch { cause, .. }
+            | Self::Ref { cause, .. } => result.extend(cause.chain()),
+            Self::AllBranches { causes, .. } => {
+                for cause in causes {
+                    result.extend(cause.chain());
+                }
+            }
+            _ => {}
+        }
+
+        result
+    }
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyEnum => write!(f, "enum has no members"),
+            Self::EmptyRange {
+                minimum_keyword,
+                maximum_keyword,
+                minimum,
+                maximum,
+            } => write!(
+                f,
+                "{} {} is greater than {} {}",
+                minimum_keyword, minimum, maximum_keyword, maximum
+            ),
+            Self::MissingRequired { name } => write!(
+                f,
+                "property {} is required but additional properties are not allowed",
+                name
+            ),
+            Self::ContradictoryAllOf { index, kinds } => write!(
+                f,
+                "allOf branch {} contradicts the preceding branches (branch kinds: {})",
+                index,
+                kinds.join(", ")
+            ),
+            Self::RequiredProperty { name, .. } => {
+                write!(f, "required property {} can never be valid", name)
+            }
+            Self::Items { min_items, .. } => write!(
+                f,
+                "at least {} items are required but no item can be valid",
+                min_items
+            ),
+            Self::Branch { keyword, index, .. } => {
+                write!(f, "{} branch {} can never be valid", keyword, index)
+            }
+            Self::AllBranches { keyword, .. } => {
+                write!(f, "no {} branch can ever be valid", keyword)
+            }
+            Self::Ref { value, .. } => write!(f, "reference {} can never be valid", value),
+        }
+    }
+}
+
+/// Find all schemas and sub-schemas in the file that no instance can satisfy
+///
+/// Sub-schemas that are unsatisfiable without making their parent unsatisfiable (for example an
+/// optional property) are reported separately.
+pub fn check_satisfiability(schema_file: &SchemaFile) -> Vec<Unsatisfiable> {
+    let mut checker = Checker {
+        definitions: schema_file.definitions.as_ref(),
+        refs: HashMap::new(),
+        nested: vec![],
+    };
+    let mut result = vec![];
+
+    if let Some(schema) = &schema_file.schema {
+        if let Some(reason) = checker.check(schema, &[]) {
+            result.push(Unsatisfiable {
+                path: vec![],
+                reason,
+            });
+        }
+    }
+
+    if let Some(definitions) = &schema_file.definitions {
+        for (name, schema) in definitions {
+            let path = vec![DEFS_KEY.to_string(), name.to_string()];
+
+            if let Some(reason) = checker.check(&schema.schema, &path) {
+                result.push(Unsatisfiable { path, reason });
+            }
+        }
+    }
+
+    result.extend(checker.nested);
+    result
+}
+
+#[derive(Clone)]
+enum RefState {
+    InProgress,
+    Done(Option<Reason>),
+}
+
+struct Checker<'a> {
+    definitions: Option<&'a IndexMap<Key, Schema>>,
+    refs: HashMap<String, RefState>,
+    nested: Vec<Unsatisfiable>,
+}
+
+impl<'a> Checker<'a> {
+    fn check(&mut self, schema: &SchemaDef, path: &[String]) -> Option<Reason> {
+        let with_segments = |segments: &[&str]| {
+            let mut new_path = path.to_vec();
+            new_path.extend(segments.iter().map(|segment| segment.to_string()));
+            new_path
+        };
+
+        match schema {
+            SchemaDef::Enum { value } if value.is_empty() => Some(Reason::EmptyEnum),
+            SchemaDef::Ref { value } => self.check_ref(value).map(|cause| Reason::Ref {
+                value: value.clone(),
+                cause: Box::new(cause),
+            }),
+            SchemaDef::Type(SchemaType::Integer {
+                minimum: Some(minimum),
+                maximum: Some(maximum),
+                ..
+            }) if minimum > maximum => Some(Reason::EmptyRange {
+                minimum_keyword: "minimum",
+                maximum_keyword: "maximum",
+                minimum: (*minimum).into(),
+                maximum: (*maximum).into(),
+            }),
+            SchemaDef::Type(SchemaType::Number {
+                minimum: Some(minimum),
+                maximum: Some(maximum),
+                ..
+            }) if minimum > maximum => Some(Reason::EmptyRange {
+                minimum_keyword: "minimum",
+                maximum_keyword: "maximum",
+                minimum: (*minimum).into(),
+                maximum: (*maximum).into(),
+            }),
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                min_items,
+                max_items,
+            }) => match (min_items, max_items) {
+                (Some(min_items), Some(max_items)) if min_items > max_items => {
+                    Some(Reason::EmptyRange {
+                        minimum_keyword: "minItems",
+                        maximum_keyword: "maxItems",
+                        minimum: (*min_items).into(),
+                        maximum: (*max_items).into(),
+                    })
+                }
+                _ => {
+                    let items_path = with_segments(&[ITEMS_KEY]);
+                    let cause = self.check(&items.schema, &items_path)?;
+
+                    match min_items {
+                        // Items after the `prefixItems` are only required past their length.
+                        Some(min_items) if *min_items > prefix_items.len() => Some(Reason::Items {
+                            min_items: *min_items,
+                            cause: Box::new(cause),
+                        }),
+                        _ => {
+                            self.nested.push(Unsatisfiable {
+                                path: items_path,
+                                reason: cause,
+                            });
+                            None
+                        }
+                    }
+                }
+            },
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                if !object.no_additional_properties() {
+                    if let AdditionalProperties::Schema(schema) = &object.additional_properties {
+                        let additional_path = with_segments(&[ADDITIONAL_PROPERTIES_KEY]);
+
+                        if let Some(reason) = self.check(&schema.schema, &additional_path) {
+                            self.nested.push(Unsatisfiable {
+                                path: additional_path,
+                                reason,
+                            });
+                        }
+                    }
+                }
+
+                let mut result = None;
+
+                for (name, schema) in &object.properties {
+                    let property_path = with_segments(&[PROPERTIES_KEY, name]);
+
+                    if let Some(cause) = self.check(&schema.schema, &property_path) {
+                        if object.required.contains(name) {
+                            result.get_or_insert(Reason::RequiredProperty {
+                                name: name.to_string(),
+                                cause: Box::new(cause),
+                            });
+                        } else {
+                            self.nested.push(Unsatisfiable {
+                                path: property_path,
+                                reason: cause,
+                            });
+                        }
+                    }
+                }
+
+                result.or_else(|| {
+                    if object.no_additional_properties() {
+                        object
+                            .required
+                            .iter()
+                            .find(|name| !object.properties.contains_key(*name))
+                            .map(|name| Reason::MissingRequired {
+                                name: name.to_string(),
+                            })
+                    } else {
+                        None
+                    }
+                })
+            }
+            SchemaDef::OneOf { value } => self.check_branches("oneOf", value, path),
+            SchemaDef::AnyOf { value } => self.check_branches("anyOf", value, path),
+            SchemaDef::AllOf { value } => {
+                for (index, branch) in value.iter().enumerate() {
+                    let branch_path = with_segments(&[ALL_OF_KEY, &index.to_string()]);
+
+                    if let Some(cause) = self.check(&branch.schema, &branch_path) {
+                        return Some(Reason::Branch {
+                            keyword: "allOf",
+                            index,
+                            cause: Box::new(cause),
+                        });
+                    }
+                }
+
+                let mut merged = SchemaDef::Empty {};
+
+                for (index, branch) in value.iter().enumerate() {
+                    match super::algebra::intersect_defs(&merged, &branch.schema) {
+                        Some(next) => merged = next,
+                        None => {
+                            return Some(Reason::ContradictoryAllOf {
+                                index,
+                                kinds: value
+                                    .iter()
+                                    .take(index + 1)
+                                    .map(|branch| super::schema::kind_name(&branch.schema))
+                                    .collect(),
+                            })
+                        }
+                    }
+                }
+
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn check_branches(
+        &mut self,
+        keyword: &'static str,
+        branches: &[Schema],
+        path: &[String],
+    ) -> Option<Reason> {
+        let mut causes = vec![];
+
+        for (index, branch) in branches.iter().enumerate() {
+            let mut branch_path = path.to_vec();
+            branch_path.push(keyword.to_string());
+            branch_path.push(index.to_string());
+
+            if let Some(cause) = self.check(&branch.schema, &branch_path) {
+                causes.push((branch_path, index, cause));
+            }
+        }
+
+        if !branches.is_empty() && causes.len() == branches.len() {
+            Some(Reason::AllBranches {
+                keyword,
+                causes: causes.into_iter().map(|(_, _, cause)| cause).collect(),
+            })
+        } else {
+            for (branch_path, index, cause) in causes {
+                self.nested.push(Unsatisfiable {
+                    path: branch_path,
+                    reason: Reason::Branch {
+                        keyword,
+                        index,
+                        cause: Box::new(cause),
+                    },
+                });
+            }
+
+            None
+        }
+    }
+
+    fn check_ref(&mut self, value: &str) -> Option<Reason> {
+        match self.refs.get(value) {
+            // Recursive references are assumed to be satisfiable.
+            Some(RefState::InProgress) => None,
+            Some(RefState::Done(result)) => result.clone(),
+            None => {
+                let target = match value.parse::<Reference>() {
+                    Ok(Reference::FragmentOnly { fragment_name }) => self
+                        .definitions
+                        .and_then(|definitions| definitions.get(fragment_name.as_str())),
+                    _ => None,
+                }?;
+
+                self.refs.insert(value.to_string(), RefState::InProgress);
+
+                // Problems inside the definition are reported when it is checked directly.
+                let nested_len = self.nested.len();
+                let result = self.check(&target.schema, &[]);
+                self.nested.truncate(nested_len);
+
+                self.refs
+                    .insert(value.to_string(), RefState::Done(result.clone()));
+                result
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsatisfiable_schemas() {
+        let schema_file = serde_json::from_value::<SchemaFile>(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "id": { "$ref": "#/$defs/id" },
+                "note": { "enum": [] }
+            },
+            "required": ["id"],
+            "$defs": {
+                "id": { "type": "integer", "minimum": 10, "maximum": 1 },
+                "both": { "allOf": [{ "type": "string" }, { "type": "integer" }] },
+                "closed": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["missing"]
+                },
+                "fine": { "oneOf": [{ "type": "null" }, { "enum": [] }] }
+            }
+        }))
+        .unwrap();
+
+        let result = check_satisfiability(&schema_file);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|unsatisfiable| (unsatisfiable.path.join("/"), unsatisfiable.reason.chain()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "".to_string(),
+                    vec![
+                        "required property id can never be valid".to_string(),
+                        "reference #/$defs/id can never be valid".to_string(),
+                        "minimum 10 is greater than maximum 1".to_string()
+                    ]
+                ),
+                (
+                    "$defs/id".to_string(),
+                    vec!["minimum 10 is greater than maximum 1".to_string()]
+                ),
+                (
+                    "$defs/both".to_string(),
+                    vec![
+                        "allOf branch 1 contradicts the preceding branches (branch kinds: string, integer)"
+                            .to_string()
+                    ]
+                ),
+                (
+                    "$defs/closed".to_string(),
+                    vec![
+                        "property missing is required but additional properties are not allowed"
+                            .to_string()
+                    ]
+                ),
+                (
+                    "properties/note".to_string(),
+                    vec!["enum has no members".to_string()]
+                ),
+                (
+                    "$defs/fine/oneOf/1".to_string(),
+                    vec![
+                        "oneOf branch 1 can never be valid".to_string(),
+                        "enum has no members".to_string()
+                    ]
+                ),
+            ]
+        );
+    }
+}
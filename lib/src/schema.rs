@@ -1,7 +1,45 @@
+use super::constants::*;
+use super::key::{Interner, Key};
+use super::reference::Reference;
 use indexmap::map::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+mod compat;
+mod convert;
+mod diff;
+mod differential;
+mod fingerprint;
+mod legacy;
+mod meta;
+mod normalize;
+mod schemars;
+
+pub use compat::{violations, Compatibility, CompatibilityMode};
+pub use convert::{convert, Draft, Unconverted, DRAFT_07_URI};
+pub use diff::{diff, kind_name, Change, ChangeKind};
+pub use differential::{counterexamples, Counterexample, DifferentialOptions};
+pub use fingerprint::{fingerprint, fingerprint_with_options, FingerprintOptions};
+pub use legacy::{is_legacy, upgrade, LegacyConstruct, LegacyKind, DRAFT_2020_12_URI};
+pub use meta::{declared_draft, meta_validate};
+pub use normalize::{normalize, Simplification, SimplificationKind};
+pub use schemars::{adapt_schemars, Adaptation, AdaptationKind};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unknown definition")]
+    UnknownDef(String),
+    #[error("Duplicate definition")]
+    DuplicateDef(String),
+}
+
+/// A reference that no longer resolves after a definition was removed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DanglingRef {
+    pub path: Vec<Key>,
+    pub value: String,
+}
+
 /// A schema file that may contain a top-level schema and related definitions
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SchemaFile {
@@ -9,34 +47,227 @@ pub struct SchemaFile {
     pub metadata: Metadata,
     #[serde(flatten)]
     pub schema: Option<SchemaDef>,
-    #[serde(rename = "$defs")]
-    pub definitions: Option<IndexMap<String, Schema>>,
+    #[serde(rename = "$defs", skip_serializing_if = "Option::is_none")]
+    pub definitions: Option<IndexMap<Key, Schema>>,
 }
 
 impl SchemaFile {
-    pub fn objects(&self) -> Vec<(Vec<String>, SchemaObject)> {
+    /// Serialize to a JSON value with keys in the order expected by the linter
+    pub fn to_canonical_value(&self) -> Result<Value, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        super::key_order::canonicalize(&mut value);
+
+        Ok(value)
+    }
+
+    /// Serialize to a pretty-printed JSON string with keys in the order expected by the linter
+    pub fn to_canonical_string_pretty(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.to_canonical_value()?)
+    }
+
+    /// Add a new definition
+    pub fn add_def(&mut self, name: &str, schema: Schema) -> Result<(), Error> {
+        let definitions = self.definitions.get_or_insert_with(IndexMap::new);
+
+        if definitions.contains_key(name) {
+            Err(Error::DuplicateDef(name.to_string()))
+        } else {
+            definitions.insert(Key::new(name), schema);
+
+            Ok(())
+        }
+    }
+
+    /// Rename a definition (keeping its position) and rewrite all references to it
+    ///
+    /// Returns the number of references that were rewritten.
+    pub fn rename_def(&mut self, old: &str, new: &str) -> Result<usize, Error> {
+        let definitions = self
+            .definitions
+            .as_mut()
+            .ok_or_else(|| Error::UnknownDef(old.to_string()))?;
+
+        if old == new {
+            return if definitions.contains_key(old) {
+                Ok(0)
+            } else {
+                Err(Error::UnknownDef(old.to_string()))
+            };
+        }
+
+        if definitions.contains_key(new) {
+            return Err(Error::DuplicateDef(new.to_string()));
+        }
+
+        let index = definitions
+            .get_index_of(old)
+            .ok_or_else(|| Error::UnknownDef(old.to_string()))?;
+        let schema = definitions.shift_remove_index(index).unwrap().1;
+        definitions.shift_insert(index, Key::new(new), schema);
+
+        let id = self.metadata.id.clone();
+        let mut count = 0;
+
+        for (_, value) in self.refs_mut() {
+            if let Some(new_reference) = Self::rename_reference(id.as_deref(), value, old, new) {
+                *value = new_reference.to_string();
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Remove a definition, returning it along with any references to it that are left dangling
+    pub fn remove_def(&mut self, name: &str) -> Result<(Schema, Vec<DanglingRef>), Error> {
+        let schema = self
+            .definitions
+            .as_mut()
+            .and_then(|definitions| definitions.shift_remove(name))
+            .ok_or_else(|| Error::UnknownDef(name.to_string()))?;
+
+        let id = self.metadata.id.clone();
+        let dangling = self
+            .refs_mut()
+            .into_iter()
+            .filter(|(_, value)| Self::rename_reference(id.as_deref(), value, name, name).is_some())
+            .map(|(path, value)| DanglingRef {
+                path,
+                value: value.clone(),
+            })
+            .collect();
+
+        Ok((schema, dangling))
+    }
+
+    /// If the reference points to the given definition in this file, return it with the new name
+    fn rename_reference(id: Option<&str>, value: &str, old: &str, new: &str) -> Option<Reference> {
+        let reference = value.parse::<Reference>().ok()?;
+        let is_local = match reference {
+            Reference::FragmentOnly { .. } => true,
+            Reference::Both { .. } => reference.path().as_deref() == id,
+            Reference::PathOnly { .. } => false,
+        };
+
+        if is_local && reference.name() == old {
+            Some(match reference {
+                Reference::Both {
+                    path_prefix,
+                    path_name,
+                    ..
+                } => Reference::new(path_prefix, path_name, new.to_string()),
+                _ => Reference::from_fragment_name(new.to_string()),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// All reference values in the file with their paths
+    fn refs_mut(&mut self) -> Vec<(Vec<Key>, &mut String)> {
+        let mut result = vec![];
+
+        if let Some(schema) = &mut self.schema {
+            schema.refs_mut_rec(&[], &mut result);
+        }
+
+        if let Some(definitions) = &mut self.definitions {
+            for (key, value) in definitions {
+                value
+                    .schema
+                    .refs_mut_rec(std::slice::from_ref(key), &mut result);
+            }
+        }
+
+        result
+    }
+
+    /// Every object schema with its path (of property names, `array` for items, and `oneOf[i]`
+    /// and similar for alternatives)
+    pub fn objects(&self) -> Vec<(Vec<Key>, SchemaObject)> {
+        self.objects_with(&mut Interner::default())
+    }
+
+    /// Every object schema with its path, with the path segments that aren't property names
+    /// interned with the given interner
+    pub fn objects_with(&self, interner: &mut Interner) -> Vec<(Vec<Key>, SchemaObject)> {
+        self.collect_objects(false, interner)
+    }
+
+    /// Every object schema with the keys leading to it in the file (like `$defs`, `user`,
+    /// `properties`, `address`), interned with the given interner
+    pub fn objects_with_locations(&self, interner: &mut Interner) -> Vec<(Vec<Key>, SchemaObject)> {
+        self.collect_objects(true, interner)
+    }
+
+    fn collect_objects(
+        &self,
+        located: bool,
+        interner: &mut Interner,
+    ) -> Vec<(Vec<Key>, SchemaObject)> {
         let mut result = vec![];
 
         if let Some(schema) = &self.schema {
-            Self::objects_rec(&Schema::new(schema), &[], &mut result);
+            Self::objects_rec(&Schema::new(schema), &[], located, interner, &mut result);
         }
 
         if let Some(definitions) = &self.definitions {
             for (key, value) in definitions {
-                Self::objects_rec(value, &[key.clone()], &mut result);
+                let path = if located {
+                    vec![interner.key(DEFS_KEY), key.clone()]
+                } else {
+                    vec![key.clone()]
+                };
+
+                Self::objects_rec(value, &path, located, interner, &mut result);
             }
         }
 
         result
     }
 
-    fn objects_rec(schema: &Schema, path: &[String], acc: &mut Vec<(Vec<String>, SchemaObject)>) {
+    fn objects_rec(
+        schema: &Schema,
+        path: &[Key],
+        located: bool,
+        interner: &mut Interner,
+        acc: &mut Vec<(Vec<Key>, SchemaObject)>,
+    ) {
+        // A keyword with a segment (an index or name) is `keyword[segment]` in a logical path, and
+        // the only keyword without one is `items`, which is `array`.
+        let with_step = |interner: &mut Interner, keyword: &str, segment: Option<&str>| {
+            let mut new_path = path.to_vec();
+
+            match (located, segment) {
+                (true, Some(segment)) => {
+                    new_path.push(interner.key(keyword));
+                    new_path.push(interner.key(segment));
+                }
+                (true, None) => new_path.push(interner.key(keyword)),
+                (false, Some(segment)) => {
+                    new_path.push(interner.key(&format!("{}[{}]", keyword, segment)))
+                }
+                (false, None) => new_path.push(interner.key("array")),
+            }
+
+            new_path
+        };
+
         match &schema.schema {
-            SchemaDef::Type(SchemaType::Array { items, .. }) => {
-                let mut new_path = path.to_vec();
-                new_path.push("array".to_string());
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                ..
+            }) => {
+                for (i, schema) in prefix_items.iter().enumerate() {
+                    let new_path = with_step(interner, PREFIX_ITEMS_KEY, Some(&i.to_string()));
+
+                    Self::objects_rec(schema, &new_path, located, interner, acc);
+                }
+
+                let new_path = with_step(interner, ITEMS_KEY, None);
 
-                Self::objects_rec(items, &new_path, acc);
+                Self::objects_rec(items, &new_path, located, interner, acc);
             }
             SchemaDef::Type(other) => {
                 if let Some(object) = other.as_object() {
@@ -44,34 +275,42 @@ impl SchemaFile {
 
                     for (key, value) in &object.properties {
                         let mut new_path = path.to_vec();
+
+                        if located {
+                            new_path.push(interner.key(PROPERTIES_KEY));
+                        }
+
                         new_path.push(key.clone());
 
-                        Self::objects_rec(value, &new_path, acc);
+                        Self::objects_rec(value, &new_path, located, interner, acc);
+                    }
+
+                    for (key, value) in &object.dependent_schemas {
+                        let new_path = with_step(interner, DEPENDENT_SCHEMAS_KEY, Some(key));
+
+                        Self::objects_rec(value, &new_path, located, interner, acc);
                     }
                 }
             }
             SchemaDef::OneOf { value } => {
                 for (i, schema) in value.iter().enumerate() {
-                    let mut new_path = path.to_vec();
-                    new_path.push(format!("oneOf[{}]", i));
+                    let new_path = with_step(interner, ONE_OF_KEY, Some(&i.to_string()));
 
-                    Self::objects_rec(schema, &new_path, acc);
+                    Self::objects_rec(schema, &new_path, located, interner, acc);
                 }
             }
             SchemaDef::AnyOf { value } => {
                 for (i, schema) in value.iter().enumerate() {
-                    let mut new_path = path.to_vec();
-                    new_path.push(format!("anyOf[{}]", i));
+                    let new_path = with_step(interner, ANY_OF_KEY, Some(&i.to_string()));
 
-                    Self::objects_rec(schema, &new_path, acc);
+                    Self::objects_rec(schema, &new_path, located, interner, acc);
                 }
             }
             SchemaDef::AllOf { value } => {
                 for (i, schema) in value.iter().enumerate() {
-                    let mut new_path = path.to_vec();
-                    new_path.push(format!("allOf[{}]", i));
+                    let new_path = with_step(interner, ALL_OF_KEY, Some(&i.to_string()));
 
-                    Self::objects_rec(schema, &new_path, acc);
+                    Self::objects_rec(schema, &new_path, located, interner, acc);
                 }
             }
             _ => {}
@@ -94,17 +333,42 @@ impl Schema {
             schema: schema.clone(),
         }
     }
+
+    /// This schema has no metadata and accepts any value
+    pub fn is_empty(&self) -> bool {
+        matches!(self.schema, SchemaDef::Empty {})
+            && self.metadata.schema.is_none()
+            && self.metadata.id.is_none()
+            && self.metadata.title.is_none()
+            && self.metadata.description.is_none()
+            && self.metadata.comment.is_none()
+            && self.metadata.examples.is_none()
+    }
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Self::new(&SchemaDef::Empty {})
+    }
 }
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Metadata {
-    #[serde(rename = "$id")]
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(rename = "$id", skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
+    /// A common (but not standard) annotation for the version of the schema
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(rename = "$comment")]
+    #[serde(rename = "$comment", skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub examples: Option<Vec<Value>>,
 }
 
@@ -140,6 +404,75 @@ pub enum SchemaDef {
     Empty {},
 }
 
+impl SchemaDef {
+    fn refs_mut_rec<'a>(&'a mut self, path: &[Key], acc: &mut Vec<(Vec<Key>, &'a mut String)>) {
+        let with_segment = |segment: Key| {
+            let mut new_path = path.to_vec();
+            new_path.push(segment);
+            new_path
+        };
+
+        match self {
+            SchemaDef::Ref { value } => acc.push((path.to_vec(), value)),
+            SchemaDef::Type(SchemaType::Array {
+                prefix_items,
+                items,
+                ..
+            }) => {
+                for (i, schema) in prefix_items.iter_mut().enumerate() {
+                    schema
+                        .schema
+                        .refs_mut_rec(&with_segment(Key::from(format!("prefixItems[{}]", i))), acc);
+                }
+
+                items
+                    .schema
+                    .refs_mut_rec(&with_segment(Key::new("array")), acc);
+            }
+            SchemaDef::Type(SchemaType::Object(object)) => {
+                for (key, value) in &mut object.properties {
+                    value.schema.refs_mut_rec(&with_segment(key.clone()), acc);
+                }
+
+                if let AdditionalProperties::Schema(schema) = &mut object.additional_properties {
+                    schema
+                        .schema
+                        .refs_mut_rec(&with_segment(Key::new("additionalProperties")), acc);
+                }
+
+                for (key, value) in &mut object.dependent_schemas {
+                    value.schema.refs_mut_rec(
+                        &with_segment(Key::from(format!("dependentSchemas[{}]", key))),
+                        acc,
+                    );
+                }
+            }
+            SchemaDef::OneOf { value } => {
+                for (i, schema) in value.iter_mut().enumerate() {
+                    schema
+                        .schema
+                        .refs_mut_rec(&with_segment(Key::from(format!("oneOf[{}]", i))), acc);
+                }
+            }
+            SchemaDef::AnyOf { value } => {
+                for (i, schema) in value.iter_mut().enumerate() {
+                    schema
+                        .schema
+                        .refs_mut_rec(&with_segment(Key::from(format!("anyOf[{}]", i))), acc);
+                }
+            }
+            SchemaDef::AllOf { value } => {
+                for (i, schema) in value.iter_mut().enumerate() {
+                    schema
+                        .schema
+                        .refs_mut_rec(&with_segment(Key::from(format!("allOf[{}]", i))), acc);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
 #[serde(deny_unknown_fields)]
@@ -149,23 +482,41 @@ pub enum SchemaType {
     #[serde(rename = "boolean")]
     Boolean {},
     #[serde(rename = "string")]
-    String { pattern: Option<String> },
+    String {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
     #[serde(rename = "integer")]
     Integer {
+        #[serde(skip_serializing_if = "Option::is_none")]
         minimum: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         maximum: Option<i64>,
+        #[serde(rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
+        exclusive_minimum: Option<i64>,
+        #[serde(rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
+        exclusive_maximum: Option<i64>,
     },
     #[serde(rename = "number")]
     Number {
+        #[serde(skip_serializing_if = "Option::is_none")]
         minimum: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         maximum: Option<f64>,
+        #[serde(rename = "exclusiveMinimum", skip_serializing_if = "Option::is_none")]
+        exclusive_minimum: Option<f64>,
+        #[serde(rename = "exclusiveMaximum", skip_serializing_if = "Option::is_none")]
+        exclusive_maximum: Option<f64>,
     },
     #[serde(rename = "array")]
     Array {
+        #[serde(rename = "prefixItems", default, skip_serializing_if = "Vec::is_empty")]
+        prefix_items: Vec<Schema>,
+        #[serde(default, skip_serializing_if = "Schema::is_empty")]
         items: Box<Schema>,
-        #[serde(rename = "minItems")]
+        #[serde(rename = "minItems", skip_serializing_if = "Option::is_none")]
         min_items: Option<usize>,
-        #[serde(rename = "maxItems")]
+        #[serde(rename = "maxItems", skip_serializing_if = "Option::is_none")]
         max_items: Option<usize>,
     },
     #[serde(rename = "object")]
@@ -181,15 +532,27 @@ impl SchemaType {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct SchemaObject {
     #[serde(rename = "additionalProperties", default)]
     pub additional_properties: AdditionalProperties,
     #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
-    pub properties: IndexMap<String, Schema>,
+    pub properties: IndexMap<Key, Schema>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub required: Vec<String>,
+    pub required: Vec<Key>,
+    #[serde(
+        rename = "dependentRequired",
+        default,
+        skip_serializing_if = "IndexMap::is_empty"
+    )]
+    pub dependent_required: IndexMap<Key, Vec<Key>>,
+    #[serde(
+        rename = "dependentSchemas",
+        default,
+        skip_serializing_if = "IndexMap::is_empty"
+    )]
+    pub dependent_schemas: IndexMap<Key, Schema>,
 }
 
 impl SchemaObject {
@@ -214,3 +577,191 @@ impl Default for AdditionalProperties {
         Self::Boolean(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_round_trip() {
+        let value = serde_json::json!({
+            "$id": "/schemas/foo",
+            "required": ["bar"],
+            "properties": {
+                "bar": { "type": "integer", "description": "A bar", "minimum": 0 },
+                "baz": { "$ref": "#/$defs/baz" }
+            },
+            "additionalProperties": false,
+            "type": "object",
+            "title": "Foo",
+            "$defs": {
+                "baz": { "enum": ["A", "B"], "title": "Baz" }
+            }
+        });
+
+        let schema_file = serde_json::from_value::<SchemaFile>(value).unwrap();
+        let canonical = schema_file.to_canonical_value().unwrap();
+
+        assert!(super::super::key_order::check_key_order(&canonical).is_empty());
+        assert_eq!(
+            canonical,
+            serde_json::json!({
+                "$id": "/schemas/foo",
+                "title": "Foo",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "bar": { "description": "A bar", "type": "integer", "minimum": 0 },
+                    "baz": { "$ref": "#/$defs/baz" }
+                },
+                "required": ["bar"],
+                "$defs": {
+                    "baz": { "title": "Baz", "enum": ["A", "B"] }
+                }
+            })
+        );
+        assert!(schema_file
+            .to_canonical_string_pretty()
+            .unwrap()
+            .starts_with("{\n  \"$id\": \"/schemas/foo\",\n  \"title\": \"Foo\","));
+    }
+
+    fn def_file() -> SchemaFile {
+        serde_json::from_value(serde_json::json!({
+            "$id": "/schemas/foo",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "bar": { "$ref": "#/$defs/bar" },
+                "bars": { "type": "array", "items": { "$ref": "/schemas/foo#/$defs/bar" } },
+                "other": { "$ref": "/schemas/other#/$defs/bar" }
+            },
+            "$defs": {
+                "bar": { "oneOf": [{ "type": "null" }, { "$ref": "#/$defs/bar" }] },
+                "baz": { "type": "string" }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn rename_def() {
+        let mut schema_file = def_file();
+
+        assert_eq!(schema_file.rename_def("bar", "qux").unwrap(), 3);
+
+        let value = serde_json::to_value(&schema_file).unwrap();
+
+        assert_eq!(
+            value["$defs"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["qux", "baz"]
+        );
+        assert_eq!(value["properties"]["bar"]["$ref"], "#/$defs/qux");
+        assert_eq!(
+            value["properties"]["bars"]["items"]["$ref"],
+            "/schemas/foo#/$defs/qux"
+        );
+        assert_eq!(
+            value["properties"]["other"]["$ref"],
+            "/schemas/other#/$defs/bar"
+        );
+        assert_eq!(value["$defs"]["qux"]["oneOf"][1]["$ref"], "#/$defs/qux");
+
+        assert!(matches!(
+            schema_file.rename_def("qux", "baz"),
+            Err(Error::DuplicateDef(_))
+        ));
+        assert!(matches!(
+            schema_file.rename_def("bar", "abc"),
+            Err(Error::UnknownDef(_))
+        ));
+    }
+
+    #[test]
+    fn add_and_remove_def() {
+        let mut schema_file = def_file();
+        let baz = schema_file.definitions.as_ref().unwrap()["baz"].clone();
+
+        assert!(matches!(
+            schema_file.add_def("baz", baz.clone()),
+            Err(Error::DuplicateDef(_))
+        ));
+        schema_file.add_def("baz2", baz).unwrap();
+
+        let (_, dangling) = schema_file.remove_def("baz2").unwrap();
+        assert!(dangling.is_empty());
+
+        let (_, dangling) = schema_file.remove_def("bar").unwrap();
+        assert_eq!(
+            dangling,
+            vec![
+                DanglingRef {
+                    path: vec![Key::new("bar")],
+                    value: "#/$defs/bar".to_string()
+                },
+                DanglingRef {
+                    path: vec![Key::new("bars"), Key::new("array")],
+                    value: "/schemas/foo#/$defs/bar".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn refs_in_prefix_items_and_dependent_schemas() {
+        let mut schema_file: SchemaFile = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "pair": {
+                    "type": "array",
+                    "prefixItems": [{ "type": "string" }, { "$ref": "#/$defs/bar" }]
+                }
+            },
+            "dependentSchemas": {
+                "pair": { "type": "object", "properties": { "bar": { "$ref": "#/$defs/bar" } } }
+            },
+            "$defs": {
+                "bar": { "type": "integer" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            schema_file
+                .objects()
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>(),
+            vec![vec![], vec![Key::new("dependentSchemas[pair]")]]
+        );
+
+        assert_eq!(schema_file.rename_def("bar", "qux").unwrap(), 2);
+
+        let value = serde_json::to_value(&schema_file).unwrap();
+
+        assert_eq!(
+            value["properties"]["pair"]["prefixItems"][1]["$ref"],
+            "#/$defs/qux"
+        );
+        assert_eq!(
+            value["dependentSchemas"]["pair"]["properties"]["bar"]["$ref"],
+            "#/$defs/qux"
+        );
+
+        let (_, dangling) = schema_file.remove_def("qux").unwrap();
+        assert_eq!(
+            dangling
+                .into_iter()
+                .map(|dangling| dangling.path)
+                .collect::<Vec<_>>(),
+            vec![
+                vec!["pair".to_string(), "prefixItems[1]".to_string()],
+                vec!["dependentSchemas[pair]".to_string(), "bar".to_string()]
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use super::{Change, ChangeKind};
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Which readers and writers can still interoperate after a change
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    /// Data written with either version is valid under the other
+    Full,
+    /// Data written with the old version is valid under the new version
+    Backward,
+    /// Data written with the new version is valid under the old version
+    Forward,
+    Breaking,
+}
+
+impl Compatibility {
+    fn new(backward: bool, forward: bool) -> Self {
+        match (backward, forward) {
+            (true, true) => Self::Full,
+            (true, false) => Self::Backward,
+            (false, true) => Self::Forward,
+            (false, false) => Self::Breaking,
+        }
+    }
+
+    pub fn is_backward(self) -> bool {
+        matches!(self, Self::Full | Self::Backward)
+    }
+
+    pub fn is_forward(self) -> bool {
+        matches!(self, Self::Full | Self::Forward)
+    }
+
+    pub fn satisfies(self, mode: CompatibilityMode) -> bool {
+        match mode {
+            CompatibilityMode::Backward => self.is_backward(),
+            CompatibilityMode::Forward => self.is_forward(),
+            CompatibilityMode::Full => self == Self::Full,
+            CompatibilityMode::None => true,
+        }
+    }
+}
+
+impl Display for Compatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "compatible"),
+            Self::Backward => write!(f, "backward-compatible"),
+            Self::Forward => write!(f, "forward-compatible"),
+            Self::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+/// The compatibility required of a new schema version
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompatibilityMode {
+    Backward,
+    Forward,
+    Full,
+    None,
+}
+
+impl ChangeKind {
+    /// Classify this change conservatively
+    ///
+    /// Changes to references and constraints that are neither tighter nor looser are treated as
+    /// breaking, since their effects can't be determined locally.
+    pub fn compatibility(&self) -> Compatibility {
+        match self {
+            Self::DefAdded { .. } | Self::DefRemoved { .. } | Self::DefRenamed { .. } => {
+                Compatibility::Full
+            }
+            Self::PropertyAdded { required, .. } => Compatibility::new(!required, true),
+            Self::PropertyRemoved { required, .. } => Compatibility::new(true, !required),
+            Self::RequiredAdded { .. } => Compatibility::Forward,
+            Self::RequiredRemoved { .. } => Compatibility::Backward,
+            Self::TypeChanged { old, new } => match (old.as_str(), new.as_str()) {
+                ("integer", "number") | (_, "any") => Compatibility::Backward,
+                ("number", "integer") | ("any", _) => Compatibility::Forward,
+                _ => Compatibility::Breaking,
+            },
+            Self::ConstraintTightened { .. } => Compatibility::Forward,
+            Self::ConstraintLoosened { .. } => Compatibility::Backward,
+            Self::ConstraintChanged { .. }
+            | Self::ConstChanged { .. }
+            | Self::RefChanged { .. } => Compatibility::Breaking,
+            Self::EnumMemberAdded { .. } => Compatibility::Backward,
+            Self::EnumMemberRemoved { .. } => Compatibility::Forward,
+            // Adding an `allOf` branch adds a constraint, while adding any other branch adds an
+            // alternative (although for `oneOf` a new branch may overlap with an existing one).
+            Self::BranchAdded { keyword, .. } if keyword == "allOf" => Compatibility::Forward,
+            Self::BranchAdded { .. } => Compatibility::Backward,
+            Self::BranchRemoved { keyword, .. } if keyword == "allOf" => Compatibility::Backward,
+            Self::BranchRemoved { .. } => Compatibility::Forward,
+        }
+    }
+}
+
+/// The changes that violate the given compatibility mode
+pub fn violations(changes: &[Change], mode: CompatibilityMode) -> Vec<&Change> {
+    changes
+        .iter()
+        .filter(|change| !change.kind.compatibility().satisfies(mode))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{diff, SchemaFile};
+    use super::*;
+
+    #[test]
+    fn classify_changes() {
+        let old: SchemaFile = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 0 },
+                "kind": { "enum": ["A", "B"] }
+            },
+            "required": ["id"]
+        }))
+        .unwrap();
+        let new: SchemaFile = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "number", "minimum": 0 },
+                "kind": { "enum": ["A", "B", "C"] },
+                "name": { "type": "string" },
+                "extra": { "type": "string" }
+            },
+            "required": ["id", "extra"]
+        }))
+        .unwrap();
+
+        let changes = diff(&old, &new);
+
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| change.kind.compatibility())
+                .collect::<Vec<_>>(),
+            vec![
+                Compatibility::Full,
+                Compatibility::Forward,
+                Compatibility::Backward,
+                Compatibility::Backward,
+            ]
+        );
+
+        assert_eq!(violations(&changes, CompatibilityMode::Backward).len(), 1);
+        assert_eq!(violations(&changes, CompatibilityMode::Forward).len(), 2);
+        assert_eq!(violations(&changes, CompatibilityMode::Full).len(), 3);
+        assert!(violations(&changes, CompatibilityMode::None).is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+use super::super::{constants::*, util::rename_key};
+use super::legacy::{upgrade, DRAFT_2020_12_URI};
+use serde_json::{Map, Value};
+
+/// The draft-07 meta-schema URI
+pub const DRAFT_07_URI: &str = "http://json-schema.org/draft-07/schema#";
+
+/// A draft that schemas can be converted to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Draft {
+    Draft07,
+    Draft2020_12,
+}
+
+/// A construct that couldn't be converted automatically and was left unchanged
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unconverted {
+    pub path: Vec<String>,
+    pub keyword: String,
+    pub reason: &'static str,
+}
+
+/// Keywords that can appear alongside `$ref` in any draft without affecting validation
+const REF_SIBLING_KEYS: [&str; 9] = [
+    COMMENT_KEY,
+    DEFAULT_KEY,
+    DEFS_KEY,
+    DESCRIPTION_KEY,
+    EXAMPLES_KEY,
+    ID_KEY,
+    DEFINITIONS_KEY,
+    SCHEMA_KEY,
+    TITLE_KEY,
+];
+
+/// 2020-12 keywords that have no draft-07 equivalent
+const UNSUPPORTED_DRAFT_07_KEYS: [(&str, &str); 8] = [
+    (ANCHOR_KEY, "not supported before 2019-09"),
+    (DYNAMIC_ANCHOR_KEY, "not supported before 2020-12"),
+    (DYNAMIC_REF_KEY, "not supported before 2020-12"),
+    (MAX_CONTAINS_KEY, "not supported before 2019-09"),
+    (MIN_CONTAINS_KEY, "not supported before 2019-09"),
+    (UNEVALUATED_ITEMS_KEY, "not supported before 2019-09"),
+    (UNEVALUATED_PROPERTIES_KEY, "not supported before 2019-09"),
+    (VOCABULARY_KEY, "not supported before 2019-09"),
+];
+
+/// Convert a schema to the given draft in place
+///
+/// Returns the constructs that were left unchanged because they have no equivalent in the target
+/// draft, or because their intended meaning is ambiguous.
+pub fn convert(value: &mut Value, target: Draft) -> Vec<Unconverted> {
+    let mut result = vec![];
+
+    // Older constructs are always upgraded first, so that draft-04 and -06 schemas can also be
+    // converted to draft-07.
+    upgrade(value);
+
+    match target {
+        Draft::Draft07 => visit(value, &mut vec![], &mut |fields, path| {
+            downgrade_fields(fields, path, &mut result)
+        }),
+        Draft::Draft2020_12 => visit(value, &mut vec![], &mut |fields, path| {
+            check_upgraded_fields(fields, path, &mut result)
+        }),
+    }
+
+    result
+}
+
+fn downgrade_fields(fields: &mut Map<String, Value>, path: &[String], acc: &mut Vec<Unconverted>) {
+    if let Some(Value::String(uri)) = fields.get_mut(SCHEMA_KEY) {
+        if uri == DRAFT_2020_12_URI {
+            *uri = DRAFT_07_URI.to_string();
+        }
+    }
+
+    if fields.contains_key(DEFS_KEY) && !fields.contains_key(DEFINITIONS_KEY) {
+        rename_key(fields, DEFS_KEY, DEFINITIONS_KEY);
+    }
+
+    if let Some(Value::String(reference)) = fields.get_mut(REF_KEY) {
+        *reference = reference.replace("#/$defs/", "#/definitions/");
+    }
+
+    // Keywords alongside `$ref` are ignored in draft-07, so they need to be moved out of the way.
+    if has_ref_siblings(fields) {
+        if let Some(reference) = fields.shift_remove(REF_KEY) {
+            let branch = Value::Object(Map::from_iter([(REF_KEY.to_string(), reference)]));
+
+            match fields.get_mut(ALL_OF_KEY) {
+                Some(Value::Array(branches)) => branches.insert(0, branch),
+                _ => {
+                    fields.insert(ALL_OF_KEY.to_string(), Value::Array(vec![branch]));
+                }
+            }
+        }
+    }
+
+    if fields.contains_key(PREFIX_ITEMS_KEY) {
+        if fields.contains_key(ITEMS_KEY) {
+            rename_key(fields, ITEMS_KEY, ADDITIONAL_ITEMS_KEY);
+        }
+
+        rename_key(fields, PREFIX_ITEMS_KEY, ITEMS_KEY);
+    }
+
+    if !fields.contains_key(DEPENDENCIES_KEY) {
+        let mut dependencies = Map::new();
+
+        for key in [DEPENDENT_REQUIRED_KEY, DEPENDENT_SCHEMAS_KEY] {
+            if let Some(Value::Object(dependents)) = fields.shift_remove(key) {
+                dependencies.extend(dependents);
+            }
+        }
+
+        if !dependencies.is_empty() {
+            fields.insert(DEPENDENCIES_KEY.to_string(), Value::Object(dependencies));
+        }
+    }
+
+    for (keyword, reason) in UNSUPPORTED_DRAFT_07_KEYS {
+        if fields.contains_key(keyword) {
+            acc.push(Unconverted {
+                path: path.to_vec(),
+                keyword: keyword.to_string(),
+                reason,
+            });
+        }
+    }
+}
+
+fn check_upgraded_fields(
+    fields: &mut Map<String, Value>,
+    path: &[String],
+    acc: &mut Vec<Unconverted>,
+) {
+    let mut report = |keyword: &str, reason| {
+        acc.push(Unconverted {
+            path: path.to_vec(),
+            keyword: keyword.to_string(),
+            reason,
+        })
+    };
+
+    // We can't tell whether these were meant to apply, so we don't drop them or move them.
+    if has_ref_siblings(fields) {
+        report(
+            REF_KEY,
+            "keywords alongside $ref were ignored before 2019-09 but now apply",
+        );
+    }
+
+    if fields
+        .get(ID_KEY)
+        .and_then(Value::as_str)
+        .is_some_and(|id| id.starts_with('#'))
+    {
+        report(
+            ID_KEY,
+            "plain-name fragments should be replaced with $anchor",
+        );
+    }
+}
+
+fn has_ref_siblings(fields: &Map<String, Value>) -> bool {
+    fields.contains_key(REF_KEY)
+        && fields
+            .keys()
+            .any(|key| key != REF_KEY && !REF_SIBLING_KEYS.contains(&key.as_str()))
+}
+
+/// Apply a function to every schema object, parents before children
+fn visit<F: FnMut(&mut Map<String, Value>, &[String])>(
+    value: &mut Value,
+    path: &mut Vec<String>,
+    f: &mut F,
+) {
+    let fields = match value.as_object_mut() {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    f(fields, path);
+
+    for (key, child) in fields.iter_mut() {
+        path.push(key.clone());
+
+        if SCHEMA_MAP_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_object_mut() {
+                for (name, schema) in schemas.iter_mut() {
+                    path.push(name.clone());
+                    visit(schema, path, f);
+                    path.pop();
+                }
+            }
+        } else if !DATA_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_array_mut() {
+                for (index, schema) in schemas.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    visit(schema, path, f);
+                    path.pop();
+                }
+            } else {
+                visit(child, path, f);
+            }
+        }
+
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_draft_07() {
+        let original = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "pair": {
+                    "prefixItems": [{ "type": "string" }, { "$ref": "#/$defs/count" }],
+                    "items": false
+                },
+                "count": { "$ref": "#/$defs/count", "maximum": 5, "description": "Count" },
+                "tags": { "type": "array", "contains": { "type": "string" }, "minContains": 2 }
+            },
+            "dependentRequired": { "pair": ["count"] },
+            "$defs": {
+                "count": { "type": "integer", "exclusiveMinimum": 0 }
+            }
+        });
+
+        let mut value = original.clone();
+        let unconverted = convert(&mut value, Draft::Draft07);
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "properties": {
+                    "pair": {
+                        "items": [{ "type": "string" }, { "$ref": "#/definitions/count" }],
+                        "additionalItems": false
+                    },
+                    "count": {
+                        "maximum": 5,
+                        "description": "Count",
+                        "allOf": [{ "$ref": "#/definitions/count" }]
+                    },
+                    "tags": { "type": "array", "contains": { "type": "string" }, "minContains": 2 }
+                },
+                "definitions": {
+                    "count": { "type": "integer", "exclusiveMinimum": 0 }
+                },
+                "dependencies": { "pair": ["count"] }
+            })
+        );
+        assert_eq!(
+            unconverted,
+            vec![Unconverted {
+                path: vec!["properties".to_string(), "tags".to_string()],
+                keyword: MIN_CONTAINS_KEY.to_string(),
+                reason: "not supported before 2019-09",
+            }]
+        );
+
+        let unconverted = convert(&mut value, Draft::Draft2020_12);
+
+        assert_eq!(value["properties"]["pair"], original["properties"]["pair"]);
+        assert_eq!(value["$defs"], original["$defs"]);
+        assert_eq!(value["dependentRequired"], original["dependentRequired"]);
+        assert!(unconverted.is_empty());
+    }
+}
@@ -0,0 +1,818 @@
+use super::super::constants::DEFS_KEY;
+use super::{AdditionalProperties, Schema, SchemaDef, SchemaFile, SchemaType};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// A single structural difference between two schema files
+///
+/// Paths inside definitions start with `$defs` and the definition name.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Change {
+    pub old_path: Vec<String>,
+    pub new_path: Vec<String>,
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChangeKind {
+    DefAdded {
+        name: String,
+    },
+    DefRemoved {
+        name: String,
+    },
+    DefRenamed {
+        old_name: String,
+        new_name: String,
+    },
+    PropertyAdded {
+        name: String,
+        required: bool,
+    },
+    PropertyRemoved {
+        name: String,
+        required: bool,
+    },
+    /// An existing property became required
+    RequiredAdded {
+        name: String,
+    },
+    /// An existing property became optional
+    RequiredRemoved {
+        name: String,
+    },
+    TypeChanged {
+        old: String,
+        new: String,
+    },
+    ConstraintTightened {
+        keyword: String,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+    ConstraintLoosened {
+        keyword: String,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+    /// A constraint changed in a way that is neither strictly tighter nor looser
+    ConstraintChanged {
+        keyword: String,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+    EnumMemberAdded {
+        value: String,
+    },
+    EnumMemberRemoved {
+        value: String,
+    },
+    ConstChanged {
+        old: Value,
+        new: Value,
+    },
+    RefChanged {
+        old: String,
+        new: String,
+    },
+    BranchAdded {
+        keyword: String,
+        index: usize,
+    },
+    BranchRemoved {
+        keyword: String,
+        index: usize,
+    },
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bound = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .map_or_else(|| "none".to_string(), Value::to_string)
+        };
+
+        match self {
+            Self::DefAdded { name } => write!(f, "definition {} added", name),
+            Self::DefRemoved { name } => write!(f, "definition {} removed", name),
+            Self::DefRenamed { old_name, new_name } => {
+                write!(f, "definition {} renamed to {}", old_name, new_name)
+            }
+            Self::PropertyAdded { name, required } => write!(
+                f,
+                "{}property {} added",
+                if *required { "required " } else { "" },
+                name
+            ),
+            Self::PropertyRemoved { name, required } => write!(
+                f,
+                "{}property {} removed",
+                if *required { "required " } else { "" },
+                name
+            ),
+            Self::RequiredAdded { name } => write!(f, "property {} is now required", name),
+            Self::RequiredRemoved { name } => {
+                write!(f, "property {} is no longer required", name)
+            }
+            Self::TypeChanged { old, new } => write!(f, "type changed from {} to {}", old, new),
+            Self::ConstraintTightened { keyword, old, new } => write!(
+                f,
+                "{} tightened from {} to {}",
+                keyword,
+                bound(old),
+                bound(new)
+            ),
+            Self::ConstraintLoosened { keyword, old, new } => write!(
+                f,
+                "{} loosened from {} to {}",
+                keyword,
+                bound(old),
+                bound(new)
+            ),
+            Self::ConstraintChanged { keyword, old, new } => write!(
+                f,
+                "{} changed from {} to {}",
+                keyword,
+                bound(old),
+                bound(new)
+            ),
+            Self::EnumMemberAdded { value } => write!(f, "enum value {} added", value),
+            Self::EnumMemberRemoved { value } => write!(f, "enum value {} removed", value),
+            Self::ConstChanged { old, new } => write!(f, "const changed from {} to {}", old, new),
+            Self::RefChanged { old, new } => {
+                write!(f, "reference changed from {} to {}", old, new)
+            }
+            Self::BranchAdded { keyword, index } => {
+                write!(f, "{} branch {} added", keyword, index)
+            }
+            Self::BranchRemoved { keyword, index } => {
+                write!(f, "{} branch {} removed", keyword, index)
+            }
+        }
+    }
+}
+
+/// Compute the structural changes between two schema files
+///
+/// Annotations are ignored. Definitions that were removed and added with identical contents are
+/// reported as renames, and references are compared modulo these renames.
+pub fn diff(old: &SchemaFile, new: &SchemaFile) -> Vec<Change> {
+    let empty = indexmap::IndexMap::new();
+    let old_defs = old.definitions.as_ref().unwrap_or(&empty);
+    let new_defs = new.definitions.as_ref().unwrap_or(&empty);
+
+    let mut differ = Differ::default();
+
+    let removed = old_defs
+        .iter()
+        .filter(|(name, _)| !new_defs.contains_key(*name))
+        .collect::<Vec<_>>();
+    let mut added = new_defs
+        .iter()
+        .filter(|(name, _)| !old_defs.contains_key(*name))
+        .collect::<Vec<_>>();
+
+    for (name, schema) in removed {
+        let fingerprint = def_fingerprint(schema);
+
+        match added
+            .iter()
+            .position(|(_, new_schema)| def_fingerprint(new_schema) == fingerprint)
+        {
+            Some(index) => {
+                let (new_name, _) = added.remove(index);
+                differ
+                    .renames
+                    .insert(name.to_string(), new_name.to_string());
+                differ.changes.push(Change {
+                    old_path: vec![DEFS_KEY.to_string(), name.to_string()],
+                    new_path: vec![DEFS_KEY.to_string(), new_name.to_string()],
+                    kind: ChangeKind::DefRenamed {
+                        old_name: name.to_string(),
+                        new_name: new_name.to_string(),
+                    },
+                });
+            }
+            None => differ.report(
+                &[],
+                ChangeKind::DefRemoved {
+                    name: name.to_string(),
+                },
+            ),
+        }
+    }
+
+    for (name, _) in added {
+        differ.report(
+            &[],
+            ChangeKind::DefAdded {
+                name: name.to_string(),
+            },
+        );
+    }
+
+    let empty_schema = SchemaDef::Empty {};
+    differ.diff_def(
+        &[],
+        old.schema.as_ref().unwrap_or(&empty_schema),
+        new.schema.as_ref().unwrap_or(&empty_schema),
+    );
+
+    for (name, old_schema) in old_defs {
+        if let Some(new_schema) = new_defs.get(name) {
+            differ.diff_def(
+                &[DEFS_KEY.to_string(), name.to_string()],
+                &old_schema.schema,
+                &new_schema.schema,
+            );
+        }
+    }
+
+    differ.changes
+}
+
+/// A short description of the kind of schema, used in type change records
+pub fn kind_name(schema: &SchemaDef) -> &'static str {
+    match schema {
+        SchemaDef::Type(SchemaType::Null {}) => "null",
+        SchemaDef::Type(SchemaType::Boolean {}) => "boolean",
+        SchemaDef::Type(SchemaType::String { .. }) => "string",
+        SchemaDef::Type(SchemaType::Integer { .. }) => "integer",
+        SchemaDef::Type(SchemaType::Number { .. }) => "number",
+        SchemaDef::Type(SchemaType::Array { .. }) => "array",
+        SchemaDef::Type(SchemaType::Object(_)) => "object",
+        SchemaDef::Ref { .. } => "$ref",
+        SchemaDef::Enum { .. } => "enum",
+        SchemaDef::Const { .. } => "const",
+        SchemaDef::OneOf { .. } => "oneOf",
+        SchemaDef::AnyOf { .. } => "anyOf",
+        SchemaDef::AllOf { .. } => "allOf",
+        SchemaDef::Empty {} => "any",
+    }
+}
+
+fn def_fingerprint(schema: &Schema) -> Option<[u8; 32]> {
+    serde_json::to_value(schema).ok().map(|value| {
+        super::fingerprint_with_options(
+            &value,
+            super::FingerprintOptions {
+                ignore_annotations: true,
+            },
+        )
+    })
+}
+
+#[derive(Default)]
+struct Differ {
+    renames: HashMap<String, String>,
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn report(&mut self, path: &[String], kind: ChangeKind) {
+        self.changes.push(Change {
+            old_path: path.to_vec(),
+            new_path: path.to_vec(),
+            kind,
+        });
+    }
+
+    fn diff_def(&mut self, path: &[String], old: &SchemaDef, new: &SchemaDef) {
+        let with_segment = |segment: String| {
+            let mut new_path = path.to_vec();
+            new_path.push(segment);
+            new_path
+        };
+
+        match (old, new) {
+            (
+                SchemaDef::Type(SchemaType::String { pattern: old }),
+                SchemaDef::Type(SchemaType::String { pattern: new }),
+            ) => match (old, new) {
+                (None, Some(_)) => {
+                    self.constraint(path, "pattern", true, None, new.clone().map(Into::into))
+                }
+                (Some(_), None) => {
+                    self.constraint(path, "pattern", false, old.clone().map(Into::into), None)
+                }
+                (Some(old_pattern), Some(new_pattern)) if old_pattern != new_pattern => self
+                    .report(
+                        path,
+                        ChangeKind::ConstraintChanged {
+                            keyword: "pattern".to_string(),
+                            old: Some(old_pattern.clone().into()),
+                            new: Some(new_pattern.clone().into()),
+                        },
+                    ),
+                _ => {}
+            },
+            (
+                SchemaDef::Type(SchemaType::Integer {
+                    minimum: old_minimum,
+                    maximum: old_maximum,
+                    exclusive_minimum: old_exclusive_minimum,
+                    exclusive_maximum: old_exclusive_maximum,
+                }),
+                SchemaDef::Type(SchemaType::Integer {
+                    minimum: new_minimum,
+                    maximum: new_maximum,
+                    exclusive_minimum: new_exclusive_minimum,
+                    exclusive_maximum: new_exclusive_maximum,
+                }),
+            ) => {
+                self.lower_bound(path, "minimum", *old_minimum, *new_minimum);
+                self.upper_bound(path, "maximum", *old_maximum, *new_maximum);
+                self.lower_bound(
+                    path,
+                    "exclusiveMinimum",
+                    *old_exclusive_minimum,
+                    *new_exclusive_minimum,
+                );
+                self.upper_bound(
+                    path,
+                    "exclusiveMaximum",
+                    *old_exclusive_maximum,
+                    *new_exclusive_maximum,
+                );
+            }
+            (
+                SchemaDef::Type(SchemaType::Number {
+                    minimum: old_minimum,
+                    maximum: old_maximum,
+                    exclusive_minimum: old_exclusive_minimum,
+                    exclusive_maximum: old_exclusive_maximum,
+                }),
+                SchemaDef::Type(SchemaType::Number {
+                    minimum: new_minimum,
+                    maximum: new_maximum,
+                    exclusive_minimum: new_exclusive_minimum,
+                    exclusive_maximum: new_exclusive_maximum,
+                }),
+            ) => {
+                self.lower_bound(path, "minimum", *old_minimum, *new_minimum);
+                self.upper_bound(path, "maximum", *old_maximum, *new_maximum);
+                self.lower_bound(
+                    path,
+                    "exclusiveMinimum",
+                    *old_exclusive_minimum,
+                    *new_exclusive_minimum,
+                );
+                self.upper_bound(
+                    path,
+                    "exclusiveMaximum",
+                    *old_exclusive_maximum,
+                    *new_exclusive_maximum,
+                );
+            }
+            (
+                SchemaDef::Type(SchemaType::Array {
+                    prefix_items: old_prefix_items,
+                    items: old_items,
+                    min_items: old_min_items,
+                    max_items: old_max_items,
+                }),
+                SchemaDef::Type(SchemaType::Array {
+                    prefix_items: new_prefix_items,
+                    items: new_items,
+                    min_items: new_min_items,
+                    max_items: new_max_items,
+                }),
+            ) => {
+                self.lower_bound(path, "minItems", *old_min_items, *new_min_items);
+                self.upper_bound(path, "maxItems", *old_max_items, *new_max_items);
+                self.diff_branches(path, "prefixItems", old_prefix_items, new_prefix_items);
+                self.diff_def(
+                    &with_segment("array".to_string()),
+                    &old_items.schema,
+                    &new_items.schema,
+                );
+            }
+            (
+                SchemaDef::Type(SchemaType::Object(old)),
+                SchemaDef::Type(SchemaType::Object(new)),
+            ) => {
+                match (&old.additional_properties, &new.additional_properties) {
+                    (AdditionalProperties::Boolean(old), AdditionalProperties::Boolean(new))
+                        if old != new =>
+                    {
+                        self.constraint(
+                            path,
+                            "additionalProperties",
+                            !new,
+                            Some((*old).into()),
+                            Some((*new).into()),
+                        );
+                    }
+                    (AdditionalProperties::Schema(old), AdditionalProperties::Schema(new)) => {
+                        self.diff_def(
+                            &with_segment("additionalProperties".to_string()),
+                            &old.schema,
+                            &new.schema,
+                        );
+                    }
+                    (AdditionalProperties::Boolean(true), AdditionalProperties::Schema(new)) => {
+                        self.constraint(
+                            path,
+                            "additionalProperties",
+                            true,
+                            Some(true.into()),
+                            serde_json::to_value(new).ok(),
+                        );
+                    }
+                    (AdditionalProperties::Schema(old), AdditionalProperties::Boolean(true)) => {
+                        self.constraint(
+                            path,
+                            "additionalProperties",
+                            false,
+                            serde_json::to_value(old).ok(),
+                            Some(true.into()),
+                        );
+                    }
+                    (AdditionalProperties::Boolean(false), AdditionalProperties::Schema(new)) => {
+                        self.constraint(
+                            path,
+                            "additionalProperties",
+                            false,
+                            Some(false.into()),
+                            serde_json::to_value(new).ok(),
+                        );
+                    }
+                    (AdditionalProperties::Schema(old), AdditionalProperties::Boolean(false)) => {
+                        self.constraint(
+                            path,
+                            "additionalProperties",
+                            true,
+                            serde_json::to_value(old).ok(),
+                            Some(false.into()),
+                        );
+                    }
+                    _ => {}
+                }
+
+                for (keyword, old_value, new_value) in [
+                    (
+                        "dependentRequired",
+                        serde_json::to_value(&old.dependent_required).ok(),
+                        serde_json::to_value(&new.dependent_required).ok(),
+                    ),
+                    (
+                        "dependentSchemas",
+                        serde_json::to_value(&old.dependent_schemas).ok(),
+                        serde_json::to_value(&new.dependent_schemas).ok(),
+                    ),
+                ] {
+                    if old_value != new_value {
+                        self.report(
+                            path,
+                            ChangeKind::ConstraintChanged {
+                                keyword: keyword.to_string(),
+                                old: old_value,
+                                new: new_value,
+                            },
+                        );
+                    }
+                }
+
+                for (name, _) in &old.properties {
+                    if !new.properties.contains_key(name) {
+                        self.report(
+                            path,
+                            ChangeKind::PropertyRemoved {
+                                name: name.to_string(),
+                                required: old.required.contains(name),
+                            },
+                        );
+                    }
+                }
+
+                for (name, _) in &new.properties {
+                    if !old.properties.contains_key(name) {
+                        self.report(
+                            path,
+                            ChangeKind::PropertyAdded {
+                                name: name.to_string(),
+                                required: new.required.contains(name),
+                            },
+                        );
+                    }
+                }
+
+                for (name, old_schema) in &old.properties {
+                    if let Some(new_schema) = new.properties.get(name) {
+                        match (old.required.contains(name), new.required.contains(name)) {
+                            (false, true) => self.report(
+                                path,
+                                ChangeKind::RequiredAdded {
+                                    name: name.to_string(),
+                                },
+                            ),
+                            (true, false) => self.report(
+                                path,
+                                ChangeKind::RequiredRemoved {
+                                    name: name.to_string(),
+                                },
+                            ),
+                            _ => {}
+                        }
+
+                        self.diff_def(
+                            &with_segment(name.to_string()),
+                            &old_schema.schema,
+                            &new_schema.schema,
+                        );
+                    }
+                }
+            }
+            (SchemaDef::Ref { value: old }, SchemaDef::Ref { value: new }) => {
+                if !self.same_ref(old, new) {
+                    self.report(
+                        path,
+                        ChangeKind::RefChanged {
+                            old: old.clone(),
+                            new: new.clone(),
+                        },
+                    );
+                }
+            }
+            (SchemaDef::Enum { value: old }, SchemaDef::Enum { value: new }) => {
+                for value in old {
+                    if !new.contains(value) {
+                        self.report(
+                            path,
+                            ChangeKind::EnumMemberRemoved {
+                                value: value.clone(),
+                            },
+                        );
+                    }
+                }
+
+                for value in new {
+                    if !old.contains(value) {
+                        self.report(
+                            path,
+                            ChangeKind::EnumMemberAdded {
+                                value: value.clone(),
+                            },
+                        );
+                    }
+                }
+            }
+            (SchemaDef::Const { value: old }, SchemaDef::Const { value: new }) => {
+                if old != new {
+                    self.report(
+                        path,
+                        ChangeKind::ConstChanged {
+                            old: old.clone(),
+                            new: new.clone(),
+                        },
+                    );
+                }
+            }
+            (SchemaDef::OneOf { value: old }, SchemaDef::OneOf { value: new }) => {
+                self.diff_branches(path, "oneOf", old, new)
+            }
+            (SchemaDef::AnyOf { value: old }, SchemaDef::AnyOf { value: new }) => {
+                self.diff_branches(path, "anyOf", old, new)
+            }
+            (SchemaDef::AllOf { value: old }, SchemaDef::AllOf { value: new }) => {
+                self.diff_branches(path, "allOf", old, new)
+            }
+            (SchemaDef::Type(SchemaType::Null {}), SchemaDef::Type(SchemaType::Null {}))
+            | (SchemaDef::Type(SchemaType::Boolean {}), SchemaDef::Type(SchemaType::Boolean {}))
+            | (SchemaDef::Empty {}, SchemaDef::Empty {}) => {}
+            (old, new) => self.report(
+                path,
+                ChangeKind::TypeChanged {
+                    old: kind_name(old).to_string(),
+                    new: kind_name(new).to_string(),
+                },
+            ),
+        }
+    }
+
+    fn diff_branches(&mut self, path: &[String], keyword: &str, old: &[Schema], new: &[Schema]) {
+        for (index, (old, new)) in old.iter().zip(new).enumerate() {
+            let mut new_path = path.to_vec();
+            new_path.push(format!("{}[{}]", keyword, index));
+
+            self.diff_def(&new_path, &old.schema, &new.schema);
+        }
+
+        for index in new.len()..old.len() {
+            self.report(
+                path,
+                ChangeKind::BranchRemoved {
+                    keyword: keyword.to_string(),
+                    index,
+                },
+            );
+        }
+
+        for index in old.len()..new.len() {
+            self.report(
+                path,
+                ChangeKind::BranchAdded {
+                    keyword: keyword.to_string(),
+                    index,
+                },
+            );
+        }
+    }
+
+    fn same_ref(&self, old: &str, new: &str) -> bool {
+        old == new
+            || match (old.strip_prefix("#/$defs/"), new.strip_prefix("#/$defs/")) {
+                (Some(old_name), Some(new_name)) => {
+                    self.renames.get(old_name).map(String::as_str) == Some(new_name)
+                }
+                _ => false,
+            }
+    }
+
+    fn constraint(
+        &mut self,
+        path: &[String],
+        keyword: &str,
+        tightened: bool,
+        old: Option<Value>,
+        new: Option<Value>,
+    ) {
+        let keyword = keyword.to_string();
+
+        self.report(
+            path,
+            if tightened {
+                ChangeKind::ConstraintTightened { keyword, old, new }
+            } else {
+                ChangeKind::ConstraintLoosened { keyword, old, new }
+            },
+        );
+    }
+
+    fn lower_bound<T: Copy + PartialOrd + Into<Value>>(
+        &mut self,
+        path: &[String],
+        keyword: &str,
+        old: Option<T>,
+        new: Option<T>,
+    ) {
+        match (old, new) {
+            (None, Some(_)) => self.constraint(path, keyword, true, None, new.map(Into::into)),
+            (Some(_), None) => self.constraint(path, keyword, false, old.map(Into::into), None),
+            (Some(old_value), Some(new_value)) if old_value != new_value => self.constraint(
+                path,
+                keyword,
+                new_value > old_value,
+                Some(old_value.into()),
+                Some(new_value.into()),
+            ),
+            _ => {}
+        }
+    }
+
+    fn upper_bound<T: Copy + PartialOrd + Into<Value>>(
+        &mut self,
+        path: &[String],
+        keyword: &str,
+        old: Option<T>,
+        new: Option<T>,
+    ) {
+        match (old, new) {
+            (None, Some(_)) => self.constraint(path, keyword, true, None, new.map(Into::into)),
+            (Some(_), None) => self.constraint(path, keyword, false, old.map(Into::into), None),
+            (Some(old_value), Some(new_value)) if old_value != new_value => self.constraint(
+                path,
+                keyword,
+                new_value < old_value,
+                Some(old_value.into()),
+                Some(new_value.into()),
+            ),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema_file(value: Value) -> SchemaFile {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn diff_changes() {
+        let old = schema_file(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "minimum": 0 },
+                "name": { "type": "string" },
+                "kind": { "$ref": "#/$defs/kind" },
+                "flag": { "type": "boolean" }
+            },
+            "required": ["id"],
+            "$defs": {
+                "kind": { "enum": ["A", "B"] },
+                "unused": { "type": "null" }
+            }
+        }));
+        let new = schema_file(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "minimum": 1, "maximum": 100 },
+                "name": { "type": "integer" },
+                "kind": { "$ref": "#/$defs/kind" },
+                "extra": { "type": "string" }
+            },
+            "required": ["id", "extra"],
+            "$defs": {
+                "kind": { "enum": ["A", "C"] },
+                "still_unused": { "type": "null" }
+            }
+        }));
+
+        let changes = diff(&old, &new)
+            .into_iter()
+            .map(|change| (change.old_path, change.new_path, change.kind))
+            .collect::<Vec<_>>();
+
+        let path = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    path(&["$defs", "unused"]),
+                    path(&["$defs", "still_unused"]),
+                    ChangeKind::DefRenamed {
+                        old_name: "unused".to_string(),
+                        new_name: "still_unused".to_string()
+                    }
+                ),
+                (
+                    path(&[]),
+                    path(&[]),
+                    ChangeKind::PropertyRemoved {
+                        name: "flag".to_string(),
+                        required: false
+                    }
+                ),
+                (
+                    path(&[]),
+                    path(&[]),
+                    ChangeKind::PropertyAdded {
+                        name: "extra".to_string(),
+                        required: true
+                    }
+                ),
+                (
+                    path(&["id"]),
+                    path(&["id"]),
+                    ChangeKind::ConstraintTightened {
+                        keyword: "minimum".to_string(),
+                        old: Some(0.into()),
+                        new: Some(1.into())
+                    }
+                ),
+                (
+                    path(&["id"]),
+                    path(&["id"]),
+                    ChangeKind::ConstraintTightened {
+                        keyword: "maximum".to_string(),
+                        old: None,
+                        new: Some(100.into())
+                    }
+                ),
+                (
+                    path(&["name"]),
+                    path(&["name"]),
+                    ChangeKind::TypeChanged {
+                        old: "string".to_string(),
+                        new: "integer".to_string()
+                    }
+                ),
+                (
+                    path(&["$defs", "kind"]),
+                    path(&["$defs", "kind"]),
+                    ChangeKind::EnumMemberRemoved {
+                        value: "B".to_string()
+                    }
+                ),
+                (
+                    path(&["$defs", "kind"]),
+                    path(&["$defs", "kind"]),
+                    ChangeKind::EnumMemberAdded {
+                        value: "C".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use super::Compatibility;
+use crate::example::seed_corpus;
+use crate::resolve::Error;
+use crate::validate::{ValidationError, Validator};
+use serde_json::Value;
+
+/// How many instances of each version are generated
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DifferentialOptions {
+    /// The seed for random instances
+    pub seed: u64,
+    /// The number of random instances (in addition to minimal, full, and boundary instances)
+    pub count: usize,
+}
+
+impl Default for DifferentialOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            count: 100,
+        }
+    }
+}
+
+/// An instance that one version of a schema accepts and the other rejects
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counterexample {
+    /// At best `Forward` for an instance of the old version that the new version rejects, and at
+    /// best `Backward` for an instance of the new version that the old version rejects
+    pub compatibility: Compatibility,
+    pub instance: Value,
+    /// Why the other version rejects the instance
+    pub errors: Vec<ValidationError>,
+}
+
+/// Generate instances of each version of a schema (the seed corpus for a fuzzer) and return the
+/// ones that the other version rejects
+///
+/// Only the first instance that fails in each way (with the same set of keyword locations) is
+/// returned. Generated instances that their own version rejects are skipped, since generation
+/// doesn't handle every keyword. Finding no counterexamples doesn't show that the versions are
+/// compatible.
+pub fn counterexamples(
+    old: &Validator,
+    new: &Validator,
+    options: DifferentialOptions,
+) -> Result<Vec<Counterexample>, Error> {
+    let mut result = vec![];
+
+    for (from, to, compatibility) in [
+        (old, new, Compatibility::Forward),
+        (new, old, Compatibility::Backward),
+    ] {
+        let schemas = std::slice::from_ref(from.schema());
+        let mut causes = vec![];
+
+        for instance in seed_corpus(schemas, 0, options.seed, options.count)? {
+            if !from.is_valid(&instance) {
+                continue;
+            }
+
+            let errors = to.validate(&instance);
+            let mut cause = errors
+                .iter()
+                .map(ValidationError::keyword_location)
+                .collect::<Vec<_>>();
+            cause.sort();
+            cause.dedup();
+
+            if !errors.is_empty() && !causes.contains(&cause) {
+                causes.push(cause);
+                result.push(Counterexample {
+                    compatibility,
+                    instance,
+                    errors,
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn differential_counterexamples() {
+        let old = Validator::new(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "maxLength": 5 },
+                "size": { "enum": ["small", "large"] }
+            }
+        }))
+        .unwrap();
+        let new = Validator::new(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "maxLength": 3 },
+                "size": { "enum": ["small", "medium", "large"] },
+                "id": { "type": "integer" }
+            },
+            "required": ["id"]
+        }))
+        .unwrap();
+
+        let found = counterexamples(&old, &new, DifferentialOptions::default()).unwrap();
+        let summary = found
+            .iter()
+            .map(|counterexample| {
+                (
+                    counterexample.compatibility,
+                    counterexample
+                        .errors
+                        .iter()
+                        .map(ValidationError::keyword_location)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert!(summary.contains(&(Compatibility::Forward, vec!["/required".to_string()])));
+        assert!(summary.iter().any(|(compatibility, locations)| {
+            *compatibility == Compatibility::Forward
+                && locations.contains(&"/properties/name/maxLength".to_string())
+        }));
+        assert!(summary.contains(&(
+            Compatibility::Backward,
+            vec!["/properties/size/enum".to_string()]
+        )));
+    }
+}
@@ -0,0 +1,100 @@
+use super::super::strip::{strip, StripOptions};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FingerprintOptions {
+    /// Ignore `title`, `description`, `$comment`, and `examples`
+    pub ignore_annotations: bool,
+}
+
+/// Compute a SHA-256 fingerprint of a schema that does not depend on formatting or key order
+pub fn fingerprint(value: &Value) -> [u8; 32] {
+    fingerprint_with_options(value, FingerprintOptions::default())
+}
+
+pub fn fingerprint_with_options(value: &Value, options: FingerprintOptions) -> [u8; 32] {
+    let mut canonical = value.clone();
+
+    if options.ignore_annotations {
+        strip(
+            &mut canonical,
+            StripOptions {
+                title: true,
+                default: false,
+            },
+        );
+    }
+
+    canonical.sort_all_objects();
+
+    let mut hasher = Sha256::new();
+    // Writing a value to a hasher cannot fail.
+    serde_json::to_writer(&mut hasher, &canonical).unwrap();
+
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_ignores_formatting() {
+        let first = serde_json::from_str::<Value>(
+            r#"{"type": "object", "properties": {"a": {"type": "string"}, "b": {"type": "integer"}}}"#,
+        )
+        .unwrap();
+        let second = serde_json::from_str::<Value>(
+            r#"{
+                "properties": {
+                    "b": { "type": "integer" },
+                    "a": { "type": "string" }
+                },
+                "type": "object"
+            }"#,
+        )
+        .unwrap();
+        let third = serde_json::json!({"type": "object", "properties": {"a": {"type": "number"}}});
+
+        assert_eq!(fingerprint(&first), fingerprint(&second));
+        assert_ne!(fingerprint(&first), fingerprint(&third));
+    }
+
+    #[test]
+    fn fingerprint_ignoring_annotations() {
+        let first = serde_json::json!({
+            "title": "Foo",
+            "type": "object",
+            "properties": {
+                "title": { "type": "string", "description": "The title" }
+            },
+            "examples": [{ "title": "abc" }]
+        });
+        let second = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" }
+            }
+        });
+        let third = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" }
+            }
+        });
+        let options = FingerprintOptions {
+            ignore_annotations: true,
+        };
+
+        assert_ne!(fingerprint(&first), fingerprint(&second));
+        assert_eq!(
+            fingerprint_with_options(&first, options),
+            fingerprint_with_options(&second, options)
+        );
+        assert_ne!(
+            fingerprint_with_options(&second, options),
+            fingerprint_with_options(&third, options)
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use super::super::{constants::*, util::rename_key};
+use super::SchemaFile;
+use serde_json::{Map, Value};
+
+/// The 2020-12 meta-schema URI
+pub const DRAFT_2020_12_URI: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const LEGACY_DIALECT_URIS: [&str; 6] = [
+    "http://json-schema.org/draft-04/schema#",
+    "http://json-schema.org/draft-04/schema",
+    "http://json-schema.org/draft-06/schema#",
+    "http://json-schema.org/draft-06/schema",
+    "http://json-schema.org/draft-07/schema#",
+    "http://json-schema.org/draft-07/schema",
+];
+
+/// A draft-04, -06, or -07 construct that was rewritten during an upgrade
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyConstruct {
+    pub path: Vec<String>,
+    pub kind: LegacyKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LegacyKind {
+    /// A `$schema` URI for an older draft
+    Dialect(String),
+    /// `definitions` instead of `$defs`
+    Definitions,
+    /// A reference into `#/definitions/`
+    DefinitionsRef(String),
+    /// `id` instead of `$id`
+    Id,
+    /// Boolean `exclusiveMinimum` modifying `minimum`
+    BooleanExclusiveMinimum,
+    /// Boolean `exclusiveMaximum` modifying `maximum`
+    BooleanExclusiveMaximum,
+    /// Array-form `items` (with optional `additionalItems`)
+    ArrayItems,
+    /// `additionalItems` without array-form `items`, which has no effect
+    IgnoredAdditionalItems,
+    /// `dependencies` instead of `dependentRequired` and `dependentSchemas`
+    Dependencies,
+}
+
+impl SchemaFile {
+    /// Deserialize a schema file that may use draft-04, -06, or -07 constructs
+    ///
+    /// Also returns the legacy constructs that were encountered, which will be empty for a
+    /// 2020-12 schema.
+    pub fn from_legacy_value(
+        mut value: Value,
+    ) -> Result<(Self, Vec<LegacyConstruct>), serde_json::Error> {
+        let constructs = upgrade(&mut value);
+
+        Ok((serde_json::from_value(value)?, constructs))
+    }
+}
+
+/// The schema declares an older draft with `$schema`
+pub fn is_legacy(value: &Value) -> bool {
+    value
+        .get(SCHEMA_KEY)
+        .and_then(Value::as_str)
+        .is_some_and(|uri| LEGACY_DIALECT_URIS.contains(&uri))
+}
+
+/// Rewrite draft-04, -06, and -07 constructs to their 2020-12 equivalents in place
+pub fn upgrade(value: &mut Value) -> Vec<LegacyConstruct> {
+    let mut result = vec![];
+    upgrade_rec(value, &mut vec![], &mut result);
+    result
+}
+
+fn upgrade_rec(value: &mut Value, path: &mut Vec<String>, acc: &mut Vec<LegacyConstruct>) {
+    let fields = match value.as_object_mut() {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    let mut report = |kind| {
+        acc.push(LegacyConstruct {
+            path: path.clone(),
+            kind,
+        })
+    };
+
+    if let Some(Value::String(uri)) = fields.get_mut(SCHEMA_KEY) {
+        if LEGACY_DIALECT_URIS.contains(&uri.as_str()) {
+            report(LegacyKind::Dialect(uri.clone()));
+            *uri = DRAFT_2020_12_URI.to_string();
+        }
+    }
+
+    if fields.contains_key(DEFINITIONS_KEY) && !fields.contains_key(DEFS_KEY) {
+        rename_key(fields, DEFINITIONS_KEY, DEFS_KEY);
+        report(LegacyKind::Definitions);
+    }
+
+    if fields.get(LEGACY_ID_KEY).is_some_and(Value::is_string) && !fields.contains_key(ID_KEY) {
+        rename_key(fields, LEGACY_ID_KEY, ID_KEY);
+        report(LegacyKind::Id);
+    }
+
+    if let Some(Value::String(reference)) = fields.get_mut(REF_KEY) {
+        if reference.contains(LEGACY_DEFINITIONS_FRAGMENT) {
+            report(LegacyKind::DefinitionsRef(reference.clone()));
+            *reference = reference.replace(LEGACY_DEFINITIONS_FRAGMENT, "#/$defs/");
+        }
+    }
+
+    for (exclusive_key, inclusive_key, kind) in [
+        (
+            EXCLUSIVE_MINIMUM_KEY,
+            MINIMUM_KEY,
+            LegacyKind::BooleanExclusiveMinimum,
+        ),
+        (
+            EXCLUSIVE_MAXIMUM_KEY,
+            MAXIMUM_KEY,
+            LegacyKind::BooleanExclusiveMaximum,
+        ),
+    ] {
+        if let Some(exclusive) = fields.get(exclusive_key).and_then(Value::as_bool) {
+            if exclusive {
+                match fields.shift_remove(inclusive_key) {
+                    Some(bound) => {
+                        fields.insert(exclusive_key.to_string(), bound);
+                    }
+                    None => {
+                        fields.shift_remove(exclusive_key);
+                    }
+                }
+            } else {
+                fields.shift_remove(exclusive_key);
+            }
+
+            report(kind);
+        }
+    }
+
+    if fields.get(ITEMS_KEY).is_some_and(Value::is_array) {
+        rename_key(fields, ITEMS_KEY, PREFIX_ITEMS_KEY);
+
+        if fields.contains_key(ADDITIONAL_ITEMS_KEY) {
+            rename_key(fields, ADDITIONAL_ITEMS_KEY, ITEMS_KEY);
+        }
+
+        report(LegacyKind::ArrayItems);
+    } else if fields.shift_remove(ADDITIONAL_ITEMS_KEY).is_some() {
+        report(LegacyKind::IgnoredAdditionalItems);
+    }
+
+    if let Some(Value::Object(dependencies)) = fields.shift_remove(DEPENDENCIES_KEY) {
+        let mut dependent_required = Map::new();
+        let mut dependent_schemas = Map::new();
+
+        for (name, dependency) in dependencies {
+            if dependency.is_array() {
+                dependent_required.insert(name, dependency);
+            } else {
+                dependent_schemas.insert(name, dependency);
+            }
+        }
+
+        if !dependent_required.is_empty() {
+            fields.insert(
+                DEPENDENT_REQUIRED_KEY.to_string(),
+                Value::Object(dependent_required),
+            );
+        }
+
+        if !dependent_schemas.is_empty() {
+            fields.insert(
+                DEPENDENT_SCHEMAS_KEY.to_string(),
+                Value::Object(dependent_schemas),
+            );
+        }
+
+        report(LegacyKind::Dependencies);
+    }
+
+    for (key, child) in fields.iter_mut() {
+        path.push(key.clone());
+
+        if SCHEMA_MAP_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_object_mut() {
+                for (name, schema) in schemas.iter_mut() {
+                    path.push(name.clone());
+                    upgrade_rec(schema, path, acc);
+                    path.pop();
+                }
+            }
+        } else if !DATA_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_array_mut() {
+                for (index, schema) in schemas.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    upgrade_rec(schema, path, acc);
+                    path.pop();
+                }
+            } else {
+                upgrade_rec(child, path, acc);
+            }
+        }
+
+        path.pop();
+    }
+}
+
+const LEGACY_DEFINITIONS_FRAGMENT: &str = "#/definitions/";
+const LEGACY_ID_KEY: &str = "id";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrade_draft_07() {
+        let value = serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "id": "/schemas/legacy",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "id": { "type": "integer", "minimum": 0, "exclusiveMinimum": true },
+                "pair": {
+                    "type": "array",
+                    "items": [{ "type": "string" }, { "$ref": "#/definitions/count" }],
+                    "additionalItems": { "type": "null" }
+                }
+            },
+            "required": ["id"],
+            "dependencies": {
+                "id": ["pair"]
+            },
+            "definitions": {
+                "count": { "type": "number", "maximum": 10, "exclusiveMaximum": false }
+            }
+        });
+
+        let (schema_file, constructs) = SchemaFile::from_legacy_value(value).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&schema_file).unwrap(),
+            serde_json::json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "$id": "/schemas/legacy",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "id": { "type": "integer", "exclusiveMinimum": 0 },
+                    "pair": {
+                        "type": "array",
+                        "prefixItems": [{ "type": "string" }, { "$ref": "#/$defs/count" }],
+                        "items": { "type": "null" }
+                    }
+                },
+                "required": ["id"],
+                "dependentRequired": {
+                    "id": ["pair"]
+                },
+                "$defs": {
+                    "count": { "type": "number", "maximum": 10.0 }
+                }
+            })
+        );
+
+        assert_eq!(
+            constructs
+                .into_iter()
+                .map(|construct| (construct.path.join("/"), construct.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "".to_string(),
+                    LegacyKind::Dialect("http://json-schema.org/draft-07/schema#".to_string())
+                ),
+                ("".to_string(), LegacyKind::Definitions),
+                ("".to_string(), LegacyKind::Id),
+                ("".to_string(), LegacyKind::Dependencies),
+                (
+                    "properties/id".to_string(),
+                    LegacyKind::BooleanExclusiveMinimum
+                ),
+                ("properties/pair".to_string(), LegacyKind::ArrayItems),
+                (
+                    "properties/pair/prefixItems/1".to_string(),
+                    LegacyKind::DefinitionsRef("#/definitions/count".to_string())
+                ),
+                (
+                    "$defs/count".to_string(),
+                    LegacyKind::BooleanExclusiveMaximum
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use super::convert::Draft;
+use super::legacy::is_legacy;
+use crate::validate::{ValidationError, Validator};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+
+const DRAFT_07_META_SCHEMA: &str = include_str!("meta/draft-07.json");
+const DRAFT_2020_12_META_SCHEMA: &str = include_str!("meta/draft-2020-12.json");
+
+// The bundled meta-schemas are valid JSON with resolvable references, so these cannot fail.
+static DRAFT_07_VALIDATOR: Lazy<Validator> =
+    Lazy::new(|| Validator::new(serde_json::from_str(DRAFT_07_META_SCHEMA).unwrap()).unwrap());
+static DRAFT_2020_12_VALIDATOR: Lazy<Validator> =
+    Lazy::new(|| Validator::new(serde_json::from_str(DRAFT_2020_12_META_SCHEMA).unwrap()).unwrap());
+
+/// The draft a schema declares with `$schema`, where anything older than 2020-12 is draft-07
+pub fn declared_draft(value: &Value) -> Draft {
+    if is_legacy(value) {
+        Draft::Draft07
+    } else {
+        Draft::Draft2020_12
+    }
+}
+
+/// Check a schema against a bundled meta-schema (by default the one for its declared draft)
+///
+/// The `format` keyword isn't checked, so invalid URIs and patterns aren't reported.
+pub fn meta_validate(value: &Value, draft: Option<Draft>) -> Vec<ValidationError> {
+    meta_validator(draft.unwrap_or_else(|| declared_draft(value))).validate(value)
+}
+
+fn meta_validator(draft: Draft) -> &'static Validator {
+    match draft {
+        Draft::Draft07 => &DRAFT_07_VALIDATOR,
+        Draft::Draft2020_12 => &DRAFT_2020_12_VALIDATOR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::Failure;
+
+    #[test]
+    fn meta_validate_schemas() {
+        for meta_schema in [DRAFT_07_META_SCHEMA, DRAFT_2020_12_META_SCHEMA] {
+            let meta_schema = serde_json::from_str(meta_schema).unwrap();
+            assert_eq!(meta_validate(&meta_schema, None), vec![]);
+        }
+
+        let value = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "text", "minLength": -1 },
+                "tags": { "items": [{ "type": "string" }] }
+            },
+            "required": "name"
+        });
+
+        let errors = meta_validate(&value, None)
+            .into_iter()
+            .map(|error| error.instance_pointer())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                "/properties/name/type",
+                "/properties/name/minLength",
+                "/properties/tags/items",
+                "/required"
+            ]
+        );
+
+        // Draft-07 allows arrays for `items`.
+        let errors = meta_validate(&value, Some(Draft::Draft07));
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[0].failure,
+            Failure::Type {
+                expected: vec!["array".to_string()],
+                found: "string"
+            }
+        );
+    }
+}
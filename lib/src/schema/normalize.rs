@@ -0,0 +1,359 @@
+use super::super::constants::*;
+use serde_json::{Map, Value};
+
+/// A simplification applied by `normalize`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Simplification {
+    pub path: Vec<String>,
+    pub kind: SimplificationKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SimplificationKind {
+    /// A single-branch combinator was replaced by its branch
+    CollapsedSingleBranch { keyword: String },
+    /// A branch that is itself only a combinator of the same kind was inlined
+    FlattenedNested { keyword: String, index: usize },
+    /// A branch identical to an earlier one was removed
+    RemovedDuplicateBranch { keyword: String, index: usize },
+    /// A keyword with no effect was removed
+    RemovedNoOp { keyword: String },
+}
+
+const COMBINATOR_KEYS: [&str; 3] = [ALL_OF_KEY, ANY_OF_KEY, ONE_OF_KEY];
+
+/// Keys whose meaning changes if a branch's keywords are merged into the schema
+const COVERAGE_KEYS: [&str; 3] = [
+    ADDITIONAL_PROPERTIES_KEY,
+    UNEVALUATED_PROPERTIES_KEY,
+    UNEVALUATED_ITEMS_KEY,
+];
+
+/// Simplify a schema in place, returning the simplifications that were applied
+///
+/// Flattening nested `oneOf`s only preserves the meaning of the schema when its branches are
+/// disjoint, which is the case for most generated schemas. Duplicate branches are only removed from
+/// `anyOf`, since a value matching two identical `oneOf` branches is rejected.
+pub fn normalize(value: &mut Value) -> Vec<Simplification> {
+    let mut result = vec![];
+    normalize_rec(value, &mut vec![], &mut result);
+    result
+}
+
+fn normalize_rec(value: &mut Value, path: &mut Vec<String>, acc: &mut Vec<Simplification>) {
+    let fields = match value.as_object_mut() {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    for (key, child) in fields.iter_mut() {
+        path.push(key.clone());
+
+        if SCHEMA_MAP_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_object_mut() {
+                for (name, schema) in schemas.iter_mut() {
+                    path.push(name.clone());
+                    normalize_rec(schema, path, acc);
+                    path.pop();
+                }
+            }
+        } else if !DATA_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_array_mut() {
+                for (index, schema) in schemas.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    normalize_rec(schema, path, acc);
+                    path.pop();
+                }
+            } else {
+                normalize_rec(child, path, acc);
+            }
+        }
+
+        path.pop();
+    }
+
+    let mut report = |kind| {
+        acc.push(Simplification {
+            path: path.clone(),
+            kind,
+        })
+    };
+
+    if fields
+        .get(REQUIRED_KEY)
+        .and_then(Value::as_array)
+        .is_some_and(Vec::is_empty)
+    {
+        fields.shift_remove(REQUIRED_KEY);
+        report(SimplificationKind::RemovedNoOp {
+            keyword: REQUIRED_KEY.to_string(),
+        });
+    }
+
+    if fields
+        .get(PROPERTIES_KEY)
+        .and_then(Value::as_object)
+        .is_some_and(Map::is_empty)
+    {
+        fields.shift_remove(PROPERTIES_KEY);
+        report(SimplificationKind::RemovedNoOp {
+            keyword: PROPERTIES_KEY.to_string(),
+        });
+    }
+
+    for keyword in COMBINATOR_KEYS {
+        if let Some(Value::Array(branches)) = fields.get_mut(keyword) {
+            let mut flattened = Vec::with_capacity(branches.len());
+
+            for (index, branch) in std::mem::take(branches).into_iter().enumerate() {
+                match only_combinator(&branch, keyword) {
+                    Some(nested) => {
+                        report(SimplificationKind::FlattenedNested {
+                            keyword: keyword.to_string(),
+                            index,
+                        });
+                        flattened.extend(nested.iter().cloned());
+                    }
+                    None => flattened.push(branch),
+                }
+            }
+
+            for (index, branch) in flattened.into_iter().enumerate() {
+                if keyword == ANY_OF_KEY && branches.contains(&branch) {
+                    report(SimplificationKind::RemovedDuplicateBranch {
+                        keyword: keyword.to_string(),
+                        index,
+                    });
+                } else {
+                    branches.push(branch);
+                }
+            }
+        }
+
+        let single = match fields.get(keyword) {
+            Some(Value::Array(branches)) if branches.len() == 1 => branches[0].as_object(),
+            _ => None,
+        };
+
+        // The branch can only be merged into its parent if their keys don't overlap, and if the
+        // parent has no keywords that depend on which properties or items its own keywords cover.
+        if let Some(branch) = single.filter(|branch| {
+            !COVERAGE_KEYS.iter().any(|key| fields.contains_key(*key))
+                && branch
+                    .keys()
+                    .all(|key| key != keyword && !fields.contains_key(key))
+        }) {
+            let branch = branch.clone();
+            fields.shift_remove(keyword);
+            fields.extend(branch);
+            report(SimplificationKind::CollapsedSingleBranch {
+                keyword: keyword.to_string(),
+            });
+        }
+    }
+}
+
+/// The branches if this schema is nothing but the given combinator
+fn only_combinator<'a>(value: &'a Value, keyword: &str) -> Option<&'a Vec<Value>> {
+    value
+        .as_object()
+        .filter(|fields| fields.len() == 1)
+        .and_then(|fields| fields.get(keyword))
+        .and_then(Value::as_array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_generated() {
+        let mut value = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "a": { "allOf": [{ "$ref": "#/$defs/a" }], "description": "A" },
+                "b": {
+                    "anyOf": [
+                        { "type": "null" },
+                        { "anyOf": [{ "type": "string" }, { "type": "null" }] }
+                    ]
+                },
+                "allOf": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            "$defs": {
+                "a": { "anyOf": [{ "type": "string" }], "type": "integer" }
+            }
+        });
+
+        let simplifications = normalize(&mut value);
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "a": { "description": "A", "$ref": "#/$defs/a" },
+                    "b": { "anyOf": [{ "type": "null" }, { "type": "string" }] },
+                    "allOf": { "type": "object" }
+                },
+                "$defs": {
+                    "a": { "anyOf": [{ "type": "string" }], "type": "integer" }
+                }
+            })
+        );
+
+        let path = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+
+        assert_eq!(
+            simplifications,
+            vec![
+                Simplification {
+                    path: path(&["properties", "a"]),
+                    kind: SimplificationKind::CollapsedSingleBranch {
+                        keyword: "allOf".to_string()
+                    }
+                },
+                Simplification {
+                    path: path(&["properties", "b"]),
+                    kind: SimplificationKind::FlattenedNested {
+                        keyword: "anyOf".to_string(),
+                        index: 1
+                    }
+                },
+                Simplification {
+                    path: path(&["properties", "b"]),
+                    kind: SimplificationKind::RemovedDuplicateBranch {
+                        keyword: "anyOf".to_string(),
+                        index: 2
+                    }
+                },
+                Simplification {
+                    path: path(&["properties", "allOf"]),
+                    kind: SimplificationKind::RemovedNoOp {
+                        keyword: "required".to_string()
+                    }
+                },
+                Simplification {
+                    path: path(&["properties", "allOf"]),
+                    kind: SimplificationKind::RemovedNoOp {
+                        keyword: "properties".to_string()
+                    }
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_one_of_branches() {
+        let mut value = serde_json::json!({
+            "oneOf": [{ "type": "string" }, { "type": "string" }]
+        });
+        let original = value.clone();
+
+        assert!(normalize(&mut value).is_empty());
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn single_branch_with_coverage_keywords() {
+        for keyword in COVERAGE_KEYS {
+            let mut value = serde_json::json!({
+                "type": "object",
+                "allOf": [{ "properties": { "a": { "type": "string" } } }],
+                keyword: false
+            });
+            let original = value.clone();
+
+            assert!(normalize(&mut value).is_empty());
+            assert_eq!(value, original);
+        }
+
+        let mut value = serde_json::json!({
+            "type": "object",
+            "allOf": [{ "properties": { "a": { "type": "string" } } }]
+        });
+
+        normalize(&mut value);
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "type": "object",
+                "properties": { "a": { "type": "string" } }
+            })
+        );
+    }
+
+    #[test]
+    fn normalize_edge_cases() {
+        let mut value = serde_json::json!({
+            "const": { "allOf": [{ "type": "string" }], "required": [] },
+            "properties": {
+                "nested": { "allOf": [{ "allOf": [{ "type": "string" }] }] },
+                "boolean": { "anyOf": [true] },
+                "annotated": {
+                    "anyOf": [
+                        { "anyOf": [{ "type": "string" }, { "type": "null" }], "description": "A" },
+                        { "type": "integer" }
+                    ]
+                },
+                "other": { "anyOf": [{ "oneOf": [{ "type": "string" }, { "type": "null" }] }] }
+            }
+        });
+
+        let simplifications = normalize(&mut value);
+
+        // Subschemas are simplified first, data isn't simplified, and a branch with other keywords
+        // isn't flattened.
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "const": { "allOf": [{ "type": "string" }], "required": [] },
+                "properties": {
+                    "nested": { "type": "string" },
+                    "boolean": { "anyOf": [true] },
+                    "annotated": {
+                        "anyOf": [
+                            {
+                                "anyOf": [{ "type": "string" }, { "type": "null" }],
+                                "description": "A"
+                            },
+                            { "type": "integer" }
+                        ]
+                    },
+                    "other": { "oneOf": [{ "type": "string" }, { "type": "null" }] }
+                }
+            })
+        );
+
+        let path = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+
+        assert_eq!(
+            simplifications,
+            vec![
+                Simplification {
+                    path: path(&["properties", "nested", "allOf", "0"]),
+                    kind: SimplificationKind::CollapsedSingleBranch {
+                        keyword: "allOf".to_string()
+                    }
+                },
+                Simplification {
+                    path: path(&["properties", "nested"]),
+                    kind: SimplificationKind::CollapsedSingleBranch {
+                        keyword: "allOf".to_string()
+                    }
+                },
+                Simplification {
+                    path: path(&["properties", "other"]),
+                    kind: SimplificationKind::CollapsedSingleBranch {
+                        keyword: "anyOf".to_string()
+                    }
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use super::super::{constants::*, util::rename_key};
+use super::legacy::upgrade;
+use super::SchemaFile;
+use serde_json::{json, Map, Value};
+
+/// A construct in the output of the `schemars` crate that was rewritten by `adapt_schemars`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Adaptation {
+    pub path: Vec<String>,
+    pub kind: AdaptationKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdaptationKind {
+    /// A Rust numeric `format` (like `int32` or `uint8`), which isn't a JSON Schema format
+    NumericFormat(String),
+    /// A whole-number float bound (like `0.0`) on an integer
+    FloatBound(String),
+    /// A `type` array with `null`, which became `anyOf` with a null branch
+    NullableType,
+    /// A reference wrapped in a single-branch `allOf` so that it could have annotations
+    WrappedRef,
+    /// `type` next to an `enum` of strings or a string `const`
+    EnumType,
+    /// A keyword the model doesn't support, which was removed
+    Removed(String),
+}
+
+/// Formats that `schemars` uses for Rust numeric types
+const NUMERIC_FORMATS: [&str; 14] = [
+    "double", "float", "int", "int128", "int16", "int32", "int64", "int8", "uint", "uint128",
+    "uint16", "uint32", "uint64", "uint8",
+];
+
+/// Keywords that `schemars` adds (from `#[serde(default)]`, `#[deprecated]`, sets, etc.)
+const REMOVED_KEYS: [&str; 5] = [
+    DEFAULT_KEY,
+    "deprecated",
+    "readOnly",
+    UNIQUE_ITEMS_KEY,
+    "writeOnly",
+];
+
+/// Annotations that stay outside `anyOf` when a nullable type is rewritten
+const ANNOTATION_KEYS: [&str; 5] = [
+    COMMENT_KEY,
+    DESCRIPTION_KEY,
+    EXAMPLES_KEY,
+    SCHEMA_KEY,
+    TITLE_KEY,
+];
+
+const INTEGER_BOUND_KEYS: [&str; 4] = [
+    EXCLUSIVE_MAXIMUM_KEY,
+    EXCLUSIVE_MINIMUM_KEY,
+    MAXIMUM_KEY,
+    MINIMUM_KEY,
+];
+
+impl SchemaFile {
+    /// Deserialize a schema generated by `schemars`, in either its draft-07 or 2020-12 layout
+    ///
+    /// Also returns the constructs that were rewritten.
+    pub fn from_schemars_value(
+        mut value: Value,
+    ) -> Result<(Self, Vec<Adaptation>), serde_json::Error> {
+        let adaptations = adapt_schemars(&mut value);
+
+        Ok((serde_json::from_value(value)?, adaptations))
+    }
+}
+
+/// Rewrite a schema generated by `schemars` in place to fit the schema model
+///
+/// Draft-07 constructs are upgraded first. Removed keywords (mostly annotations, but also
+/// `uniqueItems`) are reported, since they can change what the schema means.
+pub fn adapt_schemars(value: &mut Value) -> Vec<Adaptation> {
+    upgrade(value);
+
+    let mut result = vec![];
+    adapt_rec(value, &mut vec![], &mut result);
+    result
+}
+
+fn adapt_rec(value: &mut Value, path: &mut Vec<String>, acc: &mut Vec<Adaptation>) {
+    let fields = match value.as_object_mut() {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    let mut report = |kind| {
+        acc.push(Adaptation {
+            path: path.clone(),
+            kind,
+        })
+    };
+
+    for key in REMOVED_KEYS {
+        if fields.shift_remove(key).is_some() {
+            report(AdaptationKind::Removed(key.to_string()));
+        }
+    }
+
+    if let Some(Value::String(format)) = fields.get(FORMAT_KEY) {
+        if NUMERIC_FORMATS.contains(&format.as_str()) {
+            report(AdaptationKind::NumericFormat(format.clone()));
+            fields.shift_remove(FORMAT_KEY);
+        }
+    }
+
+    let nullable_type = match fields.get(TYPE_KEY) {
+        Some(Value::Array(types)) if types.len() == 2 && types.contains(&json!("null")) => {
+            types.iter().find(|value| *value != "null").cloned()
+        }
+        _ => None,
+    };
+
+    if let Some(single) = nullable_type {
+        let mut outer = Map::new();
+        let mut inner = Map::new();
+
+        for (key, value) in std::mem::take(fields) {
+            if ANNOTATION_KEYS.contains(&key.as_str()) {
+                outer.insert(key, value);
+            } else if key == TYPE_KEY {
+                inner.insert(key, single.clone());
+            } else {
+                inner.insert(key, value);
+            }
+        }
+
+        outer.insert(ANY_OF_KEY.to_string(), json!([inner, { TYPE_KEY: "null" }]));
+        *fields = outer;
+        report(AdaptationKind::NullableType);
+    }
+
+    if fields.get(TYPE_KEY).and_then(Value::as_str) == Some("integer") {
+        for key in INTEGER_BOUND_KEYS {
+            if let Some(bound) = fields.get_mut(key) {
+                if let Some(float) = bound.as_f64().filter(|_| bound.is_f64()) {
+                    if float.fract() == 0.0 && float.abs() < i64::MAX as f64 {
+                        *bound = json!(float as i64);
+                        report(AdaptationKind::FloatBound(key.to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    let is_string_enum = match (fields.get(ENUM_KEY), fields.get(CONST_KEY)) {
+        (Some(Value::Array(values)), _) => values.iter().all(Value::is_string),
+        (_, Some(value)) => value.is_string(),
+        _ => false,
+    };
+
+    if is_string_enum && fields.get(TYPE_KEY).and_then(Value::as_str) == Some("string") {
+        fields.shift_remove(TYPE_KEY);
+        report(AdaptationKind::EnumType);
+    }
+
+    if !fields.contains_key(REF_KEY) {
+        if let Some(Value::Array(schemas)) = fields.get(ALL_OF_KEY) {
+            if let [Value::Object(schema)] = schemas.as_slice() {
+                if let (1, Some(reference)) = (schema.len(), schema.get(REF_KEY)) {
+                    let reference = reference.clone();
+                    rename_key(fields, ALL_OF_KEY, REF_KEY);
+                    fields.insert(REF_KEY.to_string(), reference);
+                    report(AdaptationKind::WrappedRef);
+                }
+            }
+        }
+    }
+
+    for (key, child) in fields.iter_mut() {
+        path.push(key.clone());
+
+        if SCHEMA_MAP_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_object_mut() {
+                for (name, schema) in schemas.iter_mut() {
+                    path.push(name.clone());
+                    adapt_rec(schema, path, acc);
+                    path.pop();
+                }
+            }
+        } else if !DATA_KEYS.contains(&key.as_str()) {
+            if let Some(schemas) = child.as_array_mut() {
+                for (index, schema) in schemas.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    adapt_rec(schema, path, acc);
+                    path.pop();
+                }
+            } else {
+                adapt_rec(child, path, acc);
+            }
+        }
+
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adapt_schemars_draft_07() {
+        let value = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Order",
+            "type": "object",
+            "required": ["id", "status"],
+            "properties": {
+                "id": { "type": "integer", "format": "uint64", "minimum": 0.0 },
+                "note": { "description": "Free text", "default": null, "type": ["null", "string"] },
+                "status": {
+                    "description": "Where it is",
+                    "allOf": [{ "$ref": "#/definitions/Status" }]
+                },
+                "tags": { "type": "array", "items": { "type": "string" }, "uniqueItems": true }
+            },
+            "definitions": {
+                "Status": {
+                    "oneOf": [
+                        { "type": "string", "enum": ["Placed", "Shipped"] },
+                        {
+                            "type": "object",
+                            "required": ["Cancelled"],
+                            "properties": { "Cancelled": { "type": "string" } },
+                            "additionalProperties": false
+                        }
+                    ]
+                }
+            }
+        });
+
+        let (schema_file, adaptations) = SchemaFile::from_schemars_value(value).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&schema_file).unwrap(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "Order",
+                "type": "object",
+                "additionalProperties": true,
+                "properties": {
+                    "id": { "type": "integer", "minimum": 0 },
+                    "note": {
+                        "description": "Free text",
+                        "anyOf": [{ "type": "string" }, { "type": "null" }]
+                    },
+                    "status": { "description": "Where it is", "$ref": "#/$defs/Status" },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["id", "status"],
+                "$defs": {
+                    "Status": {
+                        "oneOf": [
+                            { "enum": ["Placed", "Shipped"] },
+                            {
+                                "type": "object",
+                                "additionalProperties": false,
+                                "properties": { "Cancelled": { "type": "string" } },
+                                "required": ["Cancelled"]
+                            }
+                        ]
+                    }
+                }
+            })
+        );
+
+        assert_eq!(
+            adaptations
+                .into_iter()
+                .map(|adaptation| (adaptation.path.join("/"), adaptation.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "properties/id".to_string(),
+                    AdaptationKind::NumericFormat("uint64".to_string())
+                ),
+                (
+                    "properties/id".to_string(),
+                    AdaptationKind::FloatBound("minimum".to_string())
+                ),
+                (
+                    "properties/note".to_string(),
+                    AdaptationKind::Removed("default".to_string())
+                ),
+                ("properties/note".to_string(), AdaptationKind::NullableType),
+                ("properties/status".to_string(), AdaptationKind::WrappedRef),
+                (
+                    "properties/tags".to_string(),
+                    AdaptationKind::Removed("uniqueItems".to_string())
+                ),
+                ("$defs/Status/oneOf/0".to_string(), AdaptationKind::EnumType),
+            ]
+        );
+    }
+}
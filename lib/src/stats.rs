@@ -0,0 +1,167 @@
+use super::constants::*;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Metrics for one or more schema documents
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Stats {
+    pub definitions: usize,
+    pub documented_definitions: usize,
+    pub properties: usize,
+    pub documented_properties: usize,
+    pub internal_refs: usize,
+    pub external_refs: usize,
+    /// The deepest nesting of sub-schemas, where the root schema is at depth zero
+    pub max_depth: usize,
+    pub enums: usize,
+    pub enum_values: usize,
+    pub max_enum_size: usize,
+}
+
+impl Stats {
+    /// The proportion of definitions and properties with a title or description
+    pub fn documentation_coverage(&self) -> f64 {
+        let total = self.definitions + self.properties;
+
+        if total == 0 {
+            1.0
+        } else {
+            (self.documented_definitions + self.documented_properties) as f64 / total as f64
+        }
+    }
+
+    /// Combine the metrics for two sets of documents
+    pub fn merge(&mut self, other: &Self) {
+        self.definitions += other.definitions;
+        self.documented_definitions += other.documented_definitions;
+        self.properties += other.properties;
+        self.documented_properties += other.documented_properties;
+        self.internal_refs += other.internal_refs;
+        self.external_refs += other.external_refs;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        self.enums += other.enums;
+        self.enum_values += other.enum_values;
+        self.max_enum_size = self.max_enum_size.max(other.max_enum_size);
+    }
+}
+
+pub fn stats(value: &Value) -> Stats {
+    let mut result = Stats::default();
+    stats_rec(value, 0, &mut result);
+    result
+}
+
+fn stats_rec(value: &Value, depth: usize, acc: &mut Stats) {
+    let fields = match value.as_object() {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    acc.max_depth = acc.max_depth.max(depth);
+
+    if let Some(Value::String(reference)) = fields.get(REF_KEY) {
+        if reference.starts_with('#') {
+            acc.internal_refs += 1;
+        } else {
+            acc.external_refs += 1;
+        }
+    }
+
+    if let Some(Value::Array(values)) = fields.get(ENUM_KEY) {
+        acc.enums += 1;
+        acc.enum_values += values.len();
+        acc.max_enum_size = acc.max_enum_size.max(values.len());
+    }
+
+    for (key, child) in fields {
+        match key.as_str() {
+            PROPERTIES_KEY | DEFS_KEY => {
+                if let Some(schemas) = child.as_object() {
+                    let documented = schemas.values().filter(|schema| is_documented(schema));
+
+                    if key == PROPERTIES_KEY {
+                        acc.properties += schemas.len();
+                        acc.documented_properties += documented.count();
+                    } else {
+                        acc.definitions += schemas.len();
+                        acc.documented_definitions += documented.count();
+                    }
+
+                    for schema in schemas.values() {
+                        stats_rec(schema, depth + 1, acc);
+                    }
+                }
+            }
+            PATTERN_PROPERTIES_KEY | DEPENDENT_SCHEMAS_KEY => {
+                if let Some(schemas) = child.as_object() {
+                    for schema in schemas.values() {
+                        stats_rec(schema, depth + 1, acc);
+                    }
+                }
+            }
+            CONST_KEY | DEFAULT_KEY | ENUM_KEY | EXAMPLES_KEY => {}
+            _ => {
+                if let Some(schemas) = child.as_array() {
+                    for schema in schemas {
+                        stats_rec(schema, depth + 1, acc);
+                    }
+                } else {
+                    stats_rec(child, depth + 1, acc);
+                }
+            }
+        }
+    }
+}
+
+fn is_documented(value: &Value) -> bool {
+    value.as_object().is_some_and(|fields| {
+        fields.contains_key(TITLE_KEY) || fields.contains_key(DESCRIPTION_KEY)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_schema_metrics() {
+        let value = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "a": { "type": "string", "description": "A" },
+                "b": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/c" }
+                },
+                "d": { "$ref": "/schemas/d" }
+            },
+            "$defs": {
+                "c": {
+                    "title": "C",
+                    "enum": ["x", "y", "z"],
+                    "default": { "properties": { "e": {} } }
+                },
+                "f": { "enum": [1, 2] }
+            }
+        });
+
+        let stats = stats(&value);
+
+        assert_eq!(
+            stats,
+            Stats {
+                definitions: 2,
+                documented_definitions: 1,
+                properties: 3,
+                documented_properties: 1,
+                internal_refs: 1,
+                external_refs: 1,
+                max_depth: 2,
+                enums: 2,
+                enum_values: 5,
+                max_enum_size: 3,
+            }
+        );
+        assert_eq!(stats.documentation_coverage(), 0.4);
+    }
+}
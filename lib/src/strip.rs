@@ -0,0 +1,113 @@
+use super::constants::*;
+use super::util::{walk_mut, Children};
+use serde_json::Value;
+
+/// Annotations to remove in addition to `description`, `$comment`, and `examples`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StripOptions {
+    pub title: bool,
+    pub default: bool,
+}
+
+/// Remove annotations that don't affect validation
+pub fn strip(value: &mut Value, options: StripOptions) {
+    walk_mut(value, |path, value| {
+        if path.is_data() {
+            return Children::Skip;
+        }
+
+        if let Some(fields) = value.as_object_mut().filter(|_| !path.is_schema_map()) {
+            for key in [DESCRIPTION_KEY, COMMENT_KEY, EXAMPLES_KEY] {
+                fields.shift_remove(key);
+            }
+
+            if options.title {
+                fields.shift_remove(TITLE_KEY);
+            }
+
+            if options.default {
+                fields.shift_remove(DEFAULT_KEY);
+            }
+        }
+
+        Children::Visit
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_annotations() {
+        let mut value = serde_json::json!({
+            "$comment": "Internal",
+            "title": "User",
+            "description": "A user",
+            "properties": {
+                "description": { "type": "string", "description": "Text", "default": "" },
+                "role": { "enum": [{ "title": "data" }], "examples": ["admin"] }
+            }
+        });
+
+        strip(&mut value, StripOptions::default());
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "title": "User",
+                "properties": {
+                    "description": { "type": "string", "default": "" },
+                    "role": { "enum": [{ "title": "data" }] }
+                }
+            })
+        );
+
+        strip(
+            &mut value,
+            StripOptions {
+                title: true,
+                default: true,
+            },
+        );
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "properties": {
+                    "description": { "type": "string" },
+                    "role": { "enum": [{ "title": "data" }] }
+                }
+            })
+        );
+    }
+    #[test]
+    fn strip_draft_07_definitions() {
+        let mut value = serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "definitions": {
+                "title": { "type": "string", "description": "A title" },
+                "description": { "type": "string" }
+            }
+        });
+
+        strip(
+            &mut value,
+            StripOptions {
+                title: true,
+                default: false,
+            },
+        );
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "definitions": {
+                    "title": { "type": "string" },
+                    "description": { "type": "string" }
+                }
+            })
+        );
+    }
+}
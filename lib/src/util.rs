@@ -1,31 +1,137 @@
-use super::path::Path;
-use serde_json::Value;
+use super::path::{Entry, Path, PathFrame, PathRef};
+use serde_json::{Map, Value};
+use std::convert::Infallible;
+use std::ops::ControlFlow;
+
+/// Whether a traversal should go on to a value's children
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Children {
+    Visit,
+    Skip,
+}
 
 /// Collect all JSON values with their path (depth-first)
-pub fn nodes_with_path(value: &Value) -> Vec<(Path, &Value)> {
-    nodes_with_path_rec(value, Path::default(), vec![])
+#[deprecated(note = "use `walk`, which doesn't allocate a path for every value")]
+#[allow(dead_code)]
+pub fn nodes_with_path(value: &Value) -> Vec<(Path<'_>, &Value)> {
+    let mut acc = vec![];
+
+    walk(value, |path, value| {
+        acc.push((path.to_path(), value));
+        Children::Visit
+    });
+
+    acc
+}
+
+/// Call the function with every JSON value and its path (depth-first, parents before children),
+/// skipping the children of values for which it returns `Skip`
+///
+/// A single stack of entries is updated as the traversal goes, so nothing is allocated per node.
+pub fn walk<'a>(value: &'a Value, mut f: impl FnMut(&PathRef<'_, 'a>, &'a Value) -> Children) {
+    walk_rec(value, &mut vec![], &mut f);
 }
 
-fn nodes_with_path_rec<'a>(
+fn walk_rec<'a>(
     value: &'a Value,
-    path: Path<'a>,
-    mut acc: Vec<(Path<'a>, &'a Value)>,
-) -> Vec<(Path<'a>, &'a Value)> {
-    acc.push((path.clone(), value));
+    entries: &mut Vec<Entry<'a>>,
+    f: &mut impl FnMut(&PathRef<'_, 'a>, &'a Value) -> Children,
+) {
+    if f(&PathRef::new(entries), value) == Children::Skip {
+        return;
+    }
 
     if let Some(values) = value.as_array() {
         for (i, child) in values.iter().enumerate() {
-            let mut child_path = path.clone();
-            child_path.push(i);
-            acc = nodes_with_path_rec(child, child_path, acc);
+            entries.push(Entry::Index(i));
+            walk_rec(child, entries, f);
+            entries.pop();
         }
     } else if let Some(fields) = value.as_object() {
         for (key, child) in fields {
-            let mut child_path = path.clone();
-            child_path.push(key.as_ref());
-            acc = nodes_with_path_rec(child, child_path, acc);
+            entries.push(Entry::Key(key));
+            walk_rec(child, entries, f);
+            entries.pop();
         }
     }
+}
 
-    acc
+/// Call the function with every JSON value and its path, allowing it to modify the value before
+/// its children are visited (depth-first, parents before children), and skipping the children of
+/// values for which it returns `Skip`
+///
+/// The path is made of frames on the call stack, so nothing is allocated per node.
+pub fn walk_mut(value: &mut Value, mut f: impl FnMut(&PathFrame<'_>, &mut Value) -> Children) {
+    let ControlFlow::Continue(()) =
+        try_walk_mut::<Infallible>(value, |path, value| ControlFlow::Continue(f(path, value)));
+}
+
+/// Like `walk_mut`, but stopping at the first `Break`, which is returned
+pub fn try_walk_mut<B>(
+    value: &mut Value,
+    mut f: impl FnMut(&PathFrame<'_>, &mut Value) -> ControlFlow<B, Children>,
+) -> ControlFlow<B> {
+    try_walk_mut_rec(value, &PathFrame::default(), &mut f)
+}
+
+fn try_walk_mut_rec<B>(
+    value: &mut Value,
+    path: &PathFrame<'_>,
+    f: &mut impl FnMut(&PathFrame<'_>, &mut Value) -> ControlFlow<B, Children>,
+) -> ControlFlow<B> {
+    if f(path, value)? == Children::Skip {
+        return ControlFlow::Continue(());
+    }
+
+    if let Some(values) = value.as_array_mut() {
+        for (i, child) in values.iter_mut().enumerate() {
+            try_walk_mut_rec(child, &path.child(i), f)?;
+        }
+    } else if let Some(fields) = value.as_object_mut() {
+        for (key, child) in fields.iter_mut() {
+            try_walk_mut_rec(child, &path.child(key.as_str()), f)?;
+        }
+    }
+
+    ControlFlow::Continue(())
+}
+
+/// Rename a key in place, keeping its position
+pub fn rename_key(fields: &mut Map<String, Value>, old: &str, new: &str) {
+    *fields = std::mem::take(fields)
+        .into_iter()
+        .map(|(key, value)| {
+            if key == old {
+                (new.to_string(), value)
+            } else {
+                (key, value)
+            }
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn nodes_with_path_order() {
+        let value = serde_json::json!({ "a": [1, { "b": 2 }], "c": 3 });
+
+        assert_eq!(
+            nodes_with_path(&value)
+                .into_iter()
+                .map(|(path, value)| (path.to_string(), value.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("".to_string(), value.clone()),
+                (".a".to_string(), value["a"].clone()),
+                (".a[0]".to_string(), serde_json::json!(1)),
+                (".a[1]".to_string(), serde_json::json!({ "b": 2 })),
+                (".a[1].b".to_string(), serde_json::json!(2)),
+                (".c".to_string(), serde_json::json!(3)),
+            ]
+        );
+    }
 }
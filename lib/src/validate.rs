@@ -0,0 +1,1355 @@
+use super::constants::*;
+use super::keyword::{Keyword, Keywords};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+mod annotations;
+mod coverage;
+mod dialect;
+pub(crate) mod formats;
+mod output;
+mod redact;
+mod stream;
+mod tighten;
+
+pub use annotations::{Annotation, ANNOTATION_KEYS};
+pub use coverage::{Coverage, Target, TargetKind};
+pub use dialect::Dialect;
+use formats::Format;
+pub use formats::{FormatCheck, BUILT_IN_FORMATS};
+pub use output::Output;
+pub use redact::Redaction;
+pub use tighten::{Observations, Suggestion, Tightening, TighteningOptions};
+
+/// Limit on nested schema evaluations, which guards against reference cycles
+const MAX_DEPTH: usize = 512;
+
+/// Keywords that are recognized but not checked
+const IGNORED_KEYS: [&str; 3] = [
+    UNEVALUATED_ITEMS_KEY,
+    UNEVALUATED_PROPERTIES_KEY,
+    DYNAMIC_REF_KEY,
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid pattern")]
+    InvalidPattern {
+        path: Vec<String>,
+        source: regex::Error,
+    },
+    #[error("Unresolvable reference")]
+    UnresolvableRef { path: Vec<String>, value: String },
+}
+
+/// A location in an instance that does not satisfy the schema
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    pub instance_path: Vec<String>,
+    /// The path to the keyword through the schema, including any references that were followed
+    pub schema_path: Vec<String>,
+    /// The path to the keyword in the schema document, after following references
+    pub absolute_schema_path: Vec<String>,
+    pub failure: Failure,
+}
+
+impl ValidationError {
+    /// The instance location as a JSON pointer
+    pub fn instance_pointer(&self) -> String {
+        pointer(&self.instance_path)
+    }
+
+    /// The schema keyword location as a JSON pointer fragment
+    pub fn schema_pointer(&self) -> String {
+        format!("#{}", self.keyword_location())
+    }
+
+    /// The `keywordLocation` of the error in the standard output format
+    pub fn keyword_location(&self) -> String {
+        pointer(&self.schema_path)
+    }
+
+    /// The `absoluteKeywordLocation` of the error in the standard output format, for a schema
+    /// with the given `$id` (which must be an absolute URI)
+    pub fn absolute_keyword_location(&self, id: Option<&str>) -> Option<String> {
+        id.filter(|id| id.contains(':')).map(|id| {
+            format!(
+                "{}#{}",
+                id.split('#').next().unwrap_or(id),
+                pointer(&self.absolute_schema_path)
+            )
+        })
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} (at {})",
+            self.instance_pointer(),
+            self.failure,
+            self.schema_pointer()
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure {
+    /// The schema is `false`
+    False,
+    Type {
+        expected: Vec<String>,
+        found: &'static str,
+    },
+    Const {
+        expected: Value,
+    },
+    Enum,
+    /// A numeric bound (`minimum`, `maximum`, `exclusiveMinimum`, or `exclusiveMaximum`)
+    Bound {
+        keyword: &'static str,
+        limit: Value,
+    },
+    MultipleOf {
+        divisor: Value,
+    },
+    /// A size bound on a string, array, or object (for example `minLength` or `maxItems`)
+    Size {
+        keyword: &'static str,
+        limit: u64,
+        actual: usize,
+    },
+    Pattern {
+        pattern: String,
+    },
+    /// A string that isn't valid for a format that's asserted
+    Format {
+        format: String,
+    },
+    UniqueItems {
+        first: usize,
+        second: usize,
+    },
+    Required {
+        name: String,
+    },
+    DependentRequired {
+        name: String,
+        dependency: String,
+    },
+    AdditionalProperty {
+        name: String,
+    },
+    /// Too few or too many items match `contains`
+    Contains {
+        matched: usize,
+    },
+    AnyOf,
+    /// Either no branch or more than one branch matched
+    OneOf {
+        matched: Vec<usize>,
+    },
+    Not,
+    RecursionLimit,
+    /// A reference led back to a schema that's already being applied to the same value, so
+    /// evaluation would never end
+    ReferenceCycle,
+    /// A user-defined keyword's handler rejected the value
+    Keyword {
+        keyword: String,
+        message: String,
+    },
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::False => write!(f, "no value is allowed"),
+            Self::Type { expected, found } => {
+                write!(f, "expected {}, found {}", expected.join(" or "), found)
+            }
+            Self::Const { expected } => write!(f, "expected {}", expected),
+            Self::Enum => write!(f, "value is not one of the allowed values"),
+            Self::Bound { keyword, limit } => write!(f, "value violates {} {}", keyword, limit),
+            Self::MultipleOf { divisor } => write!(f, "value is not a multiple of {}", divisor),
+            Self::Size {
+                keyword,
+                limit,
+                actual,
+            } => write!(f, "size {} violates {} {}", actual, keyword, limit),
+            Self::Pattern { pattern } => write!(f, "value does not match pattern {}", pattern),
+            Self::Format { format } => write!(f, "value is not a valid {}", format),
+            Self::UniqueItems { first, second } => {
+                write!(f, "items {} and {} are equal", first, second)
+            }
+            Self::Required { name } => write!(f, "missing required property {}", name),
+            Self::DependentRequired { name, dependency } => write!(
+                f,
+                "missing property {}, which is required when {} is present",
+                dependency, name
+            ),
+            Self::AdditionalProperty { name } => {
+                write!(f, "additional property {} is not allowed", name)
+            }
+            Self::Contains { matched } => {
+                write!(f, "{} items match contains", matched)
+            }
+            Self::AnyOf => write!(f, "value does not match any branch of anyOf"),
+            Self::OneOf { matched } if matched.is_empty() => {
+                write!(f, "value does not match any branch of oneOf")
+            }
+            Self::OneOf { matched } => write!(
+                f,
+                "value matches more than one branch of oneOf ({})",
+                matched
+                    .iter()
+                    .map(|index| index.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Not => write!(f, "value matches the schema in not"),
+            Self::RecursionLimit => write!(f, "schema evaluation is nested too deeply"),
+            Self::ReferenceCycle => {
+                write!(f, "references loop back to a schema for the same value")
+            }
+            Self::Keyword { keyword, message } => write!(f, "{} failed: {}", keyword, message),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Options {
+    /// Apply the semantics of older drafts to subschemas that declare them with `$schema` (by
+    /// upgrading them)
+    pub upgrade: bool,
+    /// The draft for subschemas that don't declare one (or are in a subschema that does)
+    pub dialect: Dialect,
+    /// Limit on nested schema evaluations, which guards against reference cycles
+    pub max_depth: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            upgrade: true,
+            dialect: Dialect::default(),
+            max_depth: MAX_DEPTH,
+        }
+    }
+}
+
+/// Check a schema and prepare it for validating instances
+///
+/// This is the entry point for applications that embed the library. Validation is native, and
+/// covers the same keywords as [`Validator`].
+pub fn compile(schema: &Value, options: Options) -> Result<CompiledSchema, Error> {
+    Validator::with_options(schema.clone(), options).map(|validator| CompiledSchema { validator })
+}
+
+/// A compiled schema
+#[derive(Clone, Debug)]
+pub struct CompiledSchema {
+    validator: Validator,
+}
+
+impl CompiledSchema {
+    pub fn schema(&self) -> &Value {
+        self.validator.schema()
+    }
+
+    pub fn validator(&self) -> &Validator {
+        &self.validator
+    }
+
+    /// Register a check for a format, which is asserted
+    pub fn register_format(
+        &mut self,
+        name: impl Into<String>,
+        check: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.validator.register_format(name, check);
+        self
+    }
+
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validator.is_valid(instance)
+    }
+
+    /// Validate an instance, returning all of its errors in schema order if there are any
+    pub fn validate(&self, instance: &Value) -> Result<(), Vec<ValidationError>> {
+        let errors = self.validator.validate(instance);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// A schema that has been checked and prepared for validating instances
+///
+/// References must point into the schema itself, either as fragments or by the schema's `$id`
+/// (composing the schema first will ensure this). Subschemas in older drafts (declared with
+/// `$schema`, or by default in [`Options`]) are upgraded so that they keep their own semantics.
+/// The `unevaluated*` keywords and `$dynamicRef` are ignored (see
+/// [`Validator::ignored_keywords`]), and `format` is only checked for formats that are asserted
+/// (see [`Validator::register_format`]).
+#[derive(Clone, Debug)]
+pub struct Validator {
+    schema: Value,
+    id: Option<String>,
+    patterns: HashMap<String, Regex>,
+    formats: HashMap<String, Format>,
+    keywords: Keywords,
+    max_depth: usize,
+    ignored: Vec<Vec<String>>,
+}
+
+impl Validator {
+    pub fn new(schema: Value) -> Result<Self, Error> {
+        Self::with_options(schema, Options::default())
+    }
+
+    pub fn with_options(mut schema: Value, options: Options) -> Result<Self, Error> {
+        if options.upgrade {
+            dialect::normalize(&mut schema, options.dialect);
+        }
+
+        let mut validator = Self {
+            id: schema
+                .get(ID_KEY)
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            schema: Value::Null,
+            patterns: HashMap::new(),
+            formats: BUILT_IN_FORMATS
+                .iter()
+                .filter_map(|name| {
+                    let check = formats::built_in(name)?;
+
+                    Some((
+                        name.to_string(),
+                        Format {
+                            check: Arc::new(check),
+                            assertion: false,
+                        },
+                    ))
+                })
+                .collect(),
+            keywords: Keywords::default(),
+            max_depth: options.max_depth,
+            ignored: vec![],
+        };
+
+        validator.prepare(&schema, &schema, &mut vec![])?;
+        validator.schema = schema;
+
+        Ok(validator)
+    }
+
+    pub fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// The paths to keywords in the schema that can restrict instances but aren't checked
+    /// (`unevaluatedItems`, `unevaluatedProperties`, and `$dynamicRef`)
+    pub fn ignored_keywords(&self) -> &[Vec<String>] {
+        &self.ignored
+    }
+
+    /// Register a check for a format, which is asserted (replacing any existing check)
+    pub fn register_format(
+        &mut self,
+        name: impl Into<String>,
+        check: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.formats.insert(
+            name.into(),
+            Format {
+                check: Arc::new(check),
+                assertion: true,
+            },
+        );
+        self
+    }
+
+    /// Register a format that's asserted by matching a regular expression
+    pub fn register_format_pattern(
+        &mut self,
+        name: impl Into<String>,
+        pattern: &str,
+    ) -> Result<&mut Self, Error> {
+        let name = name.into();
+        let regex = Regex::new(pattern).map_err(|source| Error::InvalidPattern {
+            path: vec![FORMAT_KEY.to_string(), name.clone()],
+            source,
+        })?;
+
+        Ok(self.register_format(name, move |value| regex.is_match(value)))
+    }
+
+    /// Register a handler for a user-defined keyword, which is checked wherever it appears
+    pub fn register_keyword(&mut self, keyword: impl Keyword + 'static) -> &mut Self {
+        self.keywords.register(keyword);
+        self
+    }
+
+    /// Use the handlers in a registry for user-defined keywords
+    pub fn with_keywords(mut self, keywords: Keywords) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Make a known format an assertion (which fails validation) or only an annotation, returning
+    /// `false` if the format isn't known
+    pub fn set_format_assertion(&mut self, name: &str, assertion: bool) -> bool {
+        match self.formats.get_mut(name) {
+            Some(format) => {
+                format.assertion = assertion;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        self.validate(instance).is_empty()
+    }
+
+    /// All validation errors for the instance, in schema order
+    pub fn validate(&self, instance: &Value) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        self.check(
+            &self.schema,
+            instance,
+            &mut Paths {
+                references: vec![(&self.schema, instance)],
+                ..Paths::default()
+            },
+            0,
+            &mut errors,
+        );
+        errors
+    }
+
+    /// Compile patterns, check that references resolve, and find ignored keywords
+    fn prepare(
+        &mut self,
+        root: &Value,
+        schema: &Value,
+        path: &mut Vec<String>,
+    ) -> Result<(), Error> {
+        let fields = match schema.as_object() {
+            Some(fields) => fields,
+            None => return Ok(()),
+        };
+
+        if let Some(Value::String(reference)) = fields.get(REF_KEY) {
+            if resolve(root, self.id.as_deref(), reference).is_none() {
+                let mut path = path.clone();
+                path.push(REF_KEY.to_string());
+
+                return Err(Error::UnresolvableRef {
+                    path,
+                    value: reference.clone(),
+                });
+            }
+        }
+
+        if let Some(Value::String(pattern)) = fields.get(PATTERN_KEY) {
+            path.push(PATTERN_KEY.to_string());
+            self.compile_pattern(pattern, path)?;
+            path.pop();
+        }
+
+        if let Some(Value::Object(patterns)) = fields.get(PATTERN_PROPERTIES_KEY) {
+            path.push(PATTERN_PROPERTIES_KEY.to_string());
+            for pattern in patterns.keys() {
+                path.push(pattern.clone());
+                self.compile_pattern(pattern, path)?;
+                path.pop();
+            }
+            path.pop();
+        }
+
+        for (key, child) in fields {
+            path.push(key.clone());
+
+            // These don't restrict anything if they allow everything.
+            if IGNORED_KEYS.contains(&key.as_str())
+                && *child != Value::Bool(true)
+                && *child != Value::Object(Map::new())
+            {
+                self.ignored.push(path.clone());
+            }
+
+            if SCHEMA_MAP_KEYS.contains(&key.as_str()) {
+                if let Some(schemas) = child.as_object() {
+                    for (name, schema) in schemas {
+                        path.push(name.clone());
+                        self.prepare(root, schema, path)?;
+                        path.pop();
+                    }
+                }
+            } else if !DATA_KEYS.contains(&key.as_str()) {
+                if let Some(schemas) = child.as_array() {
+                    for (index, schema) in schemas.iter().enumerate() {
+                        path.push(index.to_string());
+                        self.prepare(root, schema, path)?;
+                        path.pop();
+                    }
+                } else {
+                    self.prepare(root, child, path)?;
+                }
+            }
+
+            path.pop();
+        }
+
+        Ok(())
+    }
+
+    fn compile_pattern(&mut self, pattern: &str, path: &[String]) -> Result<(), Error> {
+        if !self.patterns.contains_key(pattern) {
+            let regex = Regex::new(pattern).map_err(|source| Error::InvalidPattern {
+                path: path.to_vec(),
+                source,
+            })?;
+            self.patterns.insert(pattern.to_string(), regex);
+        }
+
+        Ok(())
+    }
+
+    fn check(
+        &self,
+        schema: &Value,
+        instance: &Value,
+        paths: &mut Paths,
+        depth: usize,
+        acc: &mut Vec<ValidationError>,
+    ) {
+        if depth > self.max_depth {
+            paths.fail(Failure::RecursionLimit, acc);
+            return;
+        }
+
+        let fields = match schema {
+            Value::Bool(false) => {
+                paths.fail(Failure::False, acc);
+                return;
+            }
+            Value::Object(fields) => fields,
+            _ => return,
+        };
+
+        for (keyword, value) in fields {
+            paths.push_schema(keyword.clone());
+
+            match keyword.as_str() {
+                REF_KEY => {
+                    if let Some(fragment) = value
+                        .as_str()
+                        .and_then(|reference| fragment(self.id.as_deref(), reference))
+                    {
+                        if let Some(target) = self.schema.pointer(fragment) {
+                            let pair = (target as *const Value, instance as *const Value);
+
+                            if paths.references.contains(&pair) {
+                                paths.fail(Failure::ReferenceCycle, acc);
+                            } else {
+                                let absolute =
+                                    std::mem::replace(&mut paths.absolute, segments(fragment));
+                                paths.references.push(pair);
+                                self.check(target, instance, paths, depth + 1, acc);
+                                paths.references.pop();
+                                paths.absolute = absolute;
+                            }
+                        }
+                    }
+                }
+                TYPE_KEY => {
+                    let expected = match value {
+                        Value::String(name) => vec![name.clone()],
+                        Value::Array(names) => names
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .map(str::to_string)
+                            .collect(),
+                        _ => vec![],
+                    };
+
+                    if !expected.iter().any(|name| has_type(instance, name)) {
+                        paths.fail(
+                            Failure::Type {
+                                expected,
+                                found: type_name(instance),
+                            },
+                            acc,
+                        );
+                    }
+                }
+                CONST_KEY if !json_eq(value, instance) => {
+                    paths.fail(
+                        Failure::Const {
+                            expected: value.clone(),
+                        },
+                        acc,
+                    );
+                }
+                ENUM_KEY => {
+                    if let Some(values) = value.as_array() {
+                        if !values.iter().any(|value| json_eq(value, instance)) {
+                            paths.fail(Failure::Enum, acc);
+                        }
+                    }
+                }
+                MULTIPLE_OF_KEY if !is_multiple_of(instance, value) => {
+                    paths.fail(
+                        Failure::MultipleOf {
+                            divisor: value.clone(),
+                        },
+                        acc,
+                    );
+                }
+                MINIMUM_KEY | MAXIMUM_KEY | EXCLUSIVE_MINIMUM_KEY | EXCLUSIVE_MAXIMUM_KEY => {
+                    if let (Some(actual), Some(limit)) = (instance.as_f64(), value.as_f64()) {
+                        let (keyword, ok) = match keyword.as_str() {
+                            MINIMUM_KEY => (MINIMUM_KEY, actual >= limit),
+                            MAXIMUM_KEY => (MAXIMUM_KEY, actual <= limit),
+                            EXCLUSIVE_MINIMUM_KEY => (EXCLUSIVE_MINIMUM_KEY, actual > limit),
+                            _ => (EXCLUSIVE_MAXIMUM_KEY, actual < limit),
+                        };
+
+                        if !ok {
+                            paths.fail(
+                                Failure::Bound {
+                                    keyword,
+                                    limit: value.clone(),
+                                },
+                                acc,
+                            );
+                        }
+                    }
+                }
+                MIN_LENGTH_KEY | MAX_LENGTH_KEY => {
+                    if let Some(string) = instance.as_str() {
+                        check_size(keyword, value, string.chars().count(), paths, acc);
+                    }
+                }
+                FORMAT_KEY => {
+                    if let (Some(string), Some(name)) = (instance.as_str(), value.as_str()) {
+                        if let Some(format) =
+                            self.formats.get(name).filter(|format| format.assertion)
+                        {
+                            if !(format.check)(string) {
+                                paths.fail(
+                                    Failure::Format {
+                                        format: name.to_string(),
+                                    },
+                                    acc,
+                                );
+                            }
+                        }
+                    }
+                }
+                PATTERN_KEY => {
+                    if let (Some(string), Some(regex)) = (
+                        instance.as_str(),
+                        value
+                            .as_str()
+                            .and_then(|pattern| self.patterns.get(pattern)),
+                    ) {
+                        if !regex.is_match(string) {
+                            paths.fail(
+                                Failure::Pattern {
+                                    pattern: regex.as_str().to_string(),
+                                },
+                                acc,
+                            );
+                        }
+                    }
+                }
+                MIN_ITEMS_KEY | MAX_ITEMS_KEY => {
+                    if let Some(items) = instance.as_array() {
+                        check_size(keyword, value, items.len(), paths, acc);
+                    }
+                }
+                UNIQUE_ITEMS_KEY => {
+                    if let (Some(items), Some(true)) = (instance.as_array(), value.as_bool()) {
+                        if let Some((first, second)) = first_duplicate(items) {
+                            paths.fail(Failure::UniqueItems { first, second }, acc);
+                        }
+                    }
+                }
+                PREFIX_ITEMS_KEY => {
+                    if let (Some(items), Some(schemas)) = (instance.as_array(), value.as_array()) {
+                        for (index, (item, schema)) in items.iter().zip(schemas).enumerate() {
+                            paths.push_both(index.to_string());
+                            self.check(schema, item, paths, depth + 1, acc);
+                            paths.pop_both();
+                        }
+                    }
+                }
+                ITEMS_KEY => {
+                    if let Some(items) = instance.as_array() {
+                        let skip = fields
+                            .get(PREFIX_ITEMS_KEY)
+                            .and_then(Value::as_array)
+                            .map_or(0, Vec::len);
+
+                        for (index, item) in items.iter().enumerate().skip(skip) {
+                            paths.instance.push(index.to_string());
+                            self.check(value, item, paths, depth + 1, acc);
+                            paths.instance.pop();
+                        }
+                    }
+                }
+                CONTAINS_KEY => {
+                    if let Some(items) = instance.as_array() {
+                        let matched = items
+                            .iter()
+                            .filter(|item| self.matches_with(value, item, paths, depth))
+                            .count();
+                        let minimum = fields
+                            .get(MIN_CONTAINS_KEY)
+                            .and_then(Value::as_u64)
+                            .unwrap_or(1);
+                        let maximum = fields.get(MAX_CONTAINS_KEY).and_then(Value::as_u64);
+
+                        if (matched as u64) < minimum
+                            || maximum.is_some_and(|maximum| matched as u64 > maximum)
+                        {
+                            paths.fail(Failure::Contains { matched }, acc);
+                        }
+                    }
+                }
+                MIN_PROPERTIES_KEY | MAX_PROPERTIES_KEY => {
+                    if let Some(properties) = instance.as_object() {
+                        check_size(keyword, value, properties.len(), paths, acc);
+                    }
+                }
+                REQUIRED_KEY => {
+                    if let (Some(properties), Some(names)) =
+                        (instance.as_object(), value.as_array())
+                    {
+                        for name in names.iter().filter_map(Value::as_str) {
+                            if !properties.contains_key(name) {
+                                paths.fail(
+                                    Failure::Required {
+                                        name: name.to_string(),
+                                    },
+                                    acc,
+                                );
+                            }
+                        }
+                    }
+                }
+                DEPENDENT_REQUIRED_KEY => {
+                    if let (Some(properties), Some(dependencies)) =
+                        (instance.as_object(), value.as_object())
+                    {
+                        for (name, dependencies) in dependencies {
+                            if properties.contains_key(name) {
+                                for dependency in dependencies
+                                    .as_array()
+                                    .into_iter()
+                                    .flatten()
+                                    .filter_map(Value::as_str)
+                                {
+                                    if !properties.contains_key(dependency) {
+                                        paths.fail(
+                                            Failure::DependentRequired {
+                                                name: name.clone(),
+                                                dependency: dependency.to_string(),
+                                            },
+                                            acc,
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                DEPENDENT_SCHEMAS_KEY => {
+                    if let (Some(properties), Some(schemas)) =
+                        (instance.as_object(), value.as_object())
+                    {
+                        for (name, schema) in schemas {
+                            if properties.contains_key(name) {
+                                paths.push_schema(name.clone());
+                                self.check(schema, instance, paths, depth + 1, acc);
+                                paths.pop_schema();
+                            }
+                        }
+                    }
+                }
+                PROPERTIES_KEY => {
+                    if let (Some(properties), Some(schemas)) =
+                        (instance.as_object(), value.as_object())
+                    {
+                        for (name, schema) in schemas {
+                            if let Some(property) = properties.get(name) {
+                                paths.push_both(name.clone());
+                                self.check(schema, property, paths, depth + 1, acc);
+                                paths.pop_both();
+                            }
+                        }
+                    }
+                }
+                PATTERN_PROPERTIES_KEY => {
+                    if let (Some(properties), Some(schemas)) =
+                        (instance.as_object(), value.as_object())
+                    {
+                        for (pattern, schema) in schemas {
+                            if let Some(regex) = self.patterns.get(pattern) {
+                                for (name, property) in properties {
+                                    if regex.is_match(name) {
+                                        paths.push_schema(pattern.clone());
+                                        paths.instance.push(name.clone());
+                                        self.check(schema, property, paths, depth + 1, acc);
+                                        paths.pop_both();
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                ADDITIONAL_PROPERTIES_KEY => {
+                    if let Some(properties) = instance.as_object() {
+                        for (name, property) in properties {
+                            if !self.is_declared(fields, name) {
+                                if value == &Value::Bool(false) {
+                                    paths.fail(
+                                        Failure::AdditionalProperty { name: name.clone() },
+                                        acc,
+                                    );
+                                } else {
+                                    paths.instance.push(name.clone());
+                                    self.check(value, property, paths, depth + 1, acc);
+                                    paths.instance.pop();
+                                }
+                            }
+                        }
+                    }
+                }
+                PROPERTY_NAMES_KEY => {
+                    if let Some(properties) = instance.as_object() {
+                        for name in properties.keys() {
+                            paths.instance.push(name.clone());
+                            self.check(value, &Value::String(name.clone()), paths, depth + 1, acc);
+                            paths.instance.pop();
+                        }
+                    }
+                }
+                ALL_OF_KEY => {
+                    for (index, schema) in value.as_array().into_iter().flatten().enumerate() {
+                        paths.push_schema(index.to_string());
+                        self.check(schema, instance, paths, depth + 1, acc);
+                        paths.pop_schema();
+                    }
+                }
+                ANY_OF_KEY => {
+                    if let Some(schemas) = value.as_array() {
+                        if !schemas
+                            .iter()
+                            .any(|schema| self.matches_with(schema, instance, paths, depth))
+                        {
+                            paths.fail(Failure::AnyOf, acc);
+                        }
+                    }
+                }
+                ONE_OF_KEY => {
+                    if let Some(schemas) = value.as_array() {
+                        let matched = schemas
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, schema)| self.matches_with(schema, instance, paths, depth))
+                            .map(|(index, _)| index)
+                            .collect::<Vec<_>>();
+
+                        if matched.len() != 1 {
+                            paths.fail(Failure::OneOf { matched }, acc);
+                        }
+                    }
+                }
+                NOT_KEY if self.matches_with(value, instance, paths, depth) => {
+                    paths.fail(Failure::Not, acc);
+                }
+                IF_KEY => {
+                    let branch = if self.matches_with(value, instance, paths, depth) {
+                        THEN_KEY
+                    } else {
+                        ELSE_KEY
+                    };
+
+                    if let Some(schema) = fields.get(branch) {
+                        paths.pop_schema();
+                        paths.push_schema(branch.to_string());
+                        self.check(schema, instance, paths, depth + 1, acc);
+                    }
+                }
+                name => {
+                    if let Some(message) = self
+                        .keywords
+                        .get(name)
+                        .and_then(|handler| handler.validate(value, instance))
+                    {
+                        paths.fail(
+                            Failure::Keyword {
+                                keyword: name.to_string(),
+                                message,
+                            },
+                            acc,
+                        );
+                    }
+                }
+            }
+
+            paths.pop_schema();
+        }
+    }
+
+    fn matches(&self, schema: &Value, instance: &Value, depth: usize) -> bool {
+        self.matches_with(schema, instance, &Paths::default(), depth)
+    }
+
+    /// Whether the value matches, while following the references on the given paths
+    fn matches_with(&self, schema: &Value, instance: &Value, paths: &Paths, depth: usize) -> bool {
+        let mut errors = vec![];
+        self.check(
+            schema,
+            instance,
+            &mut Paths {
+                references: paths.references.clone(),
+                ..Paths::default()
+            },
+            depth + 1,
+            &mut errors,
+        );
+        errors.is_empty()
+    }
+
+    /// The property is covered by `properties` or `patternProperties`
+    fn is_declared(&self, fields: &Map<String, Value>, name: &str) -> bool {
+        fields
+            .get(PROPERTIES_KEY)
+            .and_then(Value::as_object)
+            .is_some_and(|properties| properties.contains_key(name))
+            || fields
+                .get(PATTERN_PROPERTIES_KEY)
+                .and_then(Value::as_object)
+                .is_some_and(|patterns| {
+                    patterns.keys().any(|pattern| {
+                        self.patterns
+                            .get(pattern)
+                            .is_some_and(|regex| regex.is_match(name))
+                    })
+                })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Paths {
+    schema: Vec<String>,
+    /// The schema path after following references
+    absolute: Vec<String>,
+    instance: Vec<String>,
+    /// The targets of the references being followed, with the values they're applied to
+    references: Vec<(*const Value, *const Value)>,
+}
+
+impl Paths {
+    fn fail(&self, failure: Failure, acc: &mut Vec<ValidationError>) {
+        acc.push(ValidationError {
+            instance_path: self.instance.clone(),
+            schema_path: self.schema.clone(),
+            absolute_schema_path: self.absolute.clone(),
+            failure,
+        });
+    }
+
+    fn push_schema(&mut self, entry: String) {
+        self.absolute.push(entry.clone());
+        self.schema.push(entry);
+    }
+
+    fn pop_schema(&mut self) {
+        self.schema.pop();
+        self.absolute.pop();
+    }
+
+    fn push_both(&mut self, entry: String) {
+        self.push_schema(entry.clone());
+        self.instance.push(entry);
+    }
+
+    fn pop_both(&mut self) {
+        self.pop_schema();
+        self.instance.pop();
+    }
+}
+
+fn check_size(
+    keyword: &str,
+    limit: &Value,
+    actual: usize,
+    paths: &Paths,
+    acc: &mut Vec<ValidationError>,
+) {
+    let (keyword, ok): (&'static str, fn(usize, u64) -> bool) = match keyword {
+        MIN_LENGTH_KEY => (MIN_LENGTH_KEY, |actual, limit| actual as u64 >= limit),
+        MAX_LENGTH_KEY => (MAX_LENGTH_KEY, |actual, limit| actual as u64 <= limit),
+        MIN_ITEMS_KEY => (MIN_ITEMS_KEY, |actual, limit| actual as u64 >= limit),
+        MAX_ITEMS_KEY => (MAX_ITEMS_KEY, |actual, limit| actual as u64 <= limit),
+        MIN_PROPERTIES_KEY => (MIN_PROPERTIES_KEY, |actual, limit| actual as u64 >= limit),
+        _ => (MAX_PROPERTIES_KEY, |actual, limit| actual as u64 <= limit),
+    };
+
+    if let Some(limit) = limit.as_u64() {
+        if !ok(actual, limit) {
+            paths.fail(
+                Failure::Size {
+                    keyword,
+                    limit,
+                    actual,
+                },
+                acc,
+            );
+        }
+    }
+}
+
+/// Resolve a fragment reference or a reference to the schema's own `$id`
+fn resolve<'a>(root: &'a Value, id: Option<&str>, reference: &str) -> Option<&'a Value> {
+    root.pointer(fragment(id, reference)?)
+}
+
+/// The JSON Pointer a reference resolves to within the schema with the given ID
+fn fragment<'a>(id: Option<&str>, reference: &'a str) -> Option<&'a str> {
+    match reference.strip_prefix('#') {
+        Some(fragment) => Some(fragment),
+        None => {
+            let rest = reference.strip_prefix(id?)?;
+
+            if rest.is_empty() {
+                Some(rest)
+            } else {
+                rest.strip_prefix('#')
+            }
+        }
+    }
+}
+
+/// The unescaped segments of a JSON Pointer
+fn segments(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => value.as_f64().is_some_and(|value| value.fract() == 0.0),
+        "number" => value.is_number(),
+        _ => type_name(value) == name,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_multiple_of(value: &Value, divisor: &Value) -> bool {
+    match (value.as_i64(), divisor.as_i64()) {
+        (Some(value), Some(divisor)) if divisor != 0 => value % divisor == 0,
+        _ => match (value.as_f64(), divisor.as_f64()) {
+            (Some(value), Some(divisor)) => {
+                let quotient = value / divisor;
+                (quotient - quotient.round()).abs() <= 1e-9 * quotient.abs().max(1.0)
+            }
+            _ => true,
+        },
+    }
+}
+
+/// JSON equality, where numbers are compared by value
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.as_f64() == b.as_f64(),
+        },
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| json_eq(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn first_duplicate(items: &[Value]) -> Option<(usize, usize)> {
+    items.iter().enumerate().find_map(|(second, item)| {
+        items[..second]
+            .iter()
+            .position(|other| json_eq(other, item))
+            .map(|first| (first, second))
+    })
+}
+
+fn pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|entry| format!("/{}", entry.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_instances() {
+        let validator = Validator::new(serde_json::json!({
+            "$id": "/schemas/user",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "age": { "type": "integer", "minimum": 0 },
+                "tags": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/tag" },
+                    "uniqueItems": true
+                },
+                "manager": { "$ref": "/schemas/user" }
+            },
+            "required": ["name"],
+            "additionalProperties": false,
+            "$defs": {
+                "tag": { "type": "string", "pattern": "^[a-z]+$" }
+            }
+        }))
+        .unwrap();
+
+        assert!(validator.is_valid(&serde_json::json!({
+            "name": "Alice",
+            "age": 30.0,
+            "tags": ["admin", "dev"],
+            "manager": { "name": "Bob" }
+        })));
+
+        let errors = validator.validate(&serde_json::json!({
+            "age": -1,
+            "tags": ["ok", "Not OK", "ok"],
+            "manager": { "name": "" },
+            "extra": null
+        }));
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "/age: value violates minimum 0 (at #/properties/age/minimum)",
+                "/tags/1: value does not match pattern ^[a-z]+$ (at #/properties/tags/items/$ref/pattern)",
+                "/tags: items 0 and 2 are equal (at #/properties/tags/uniqueItems)",
+                "/manager/name: size 0 violates minLength 1 (at #/properties/manager/$ref/properties/name/minLength)",
+                ": missing required property name (at #/required)",
+                ": additional property extra is not allowed (at #/additionalProperties)",
+            ]
+        );
+    }
+
+    #[test]
+    fn compile_with_options() {
+        let schema = serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "array",
+            "items": [{ "type": "string" }],
+            "additionalItems": false
+        });
+
+        let compiled = compile(&schema, Options::default()).unwrap();
+
+        assert_eq!(compiled.validate(&serde_json::json!(["a"])), Ok(()));
+        assert_eq!(
+            compiled
+                .validate(&serde_json::json!([1, 2]))
+                .unwrap_err()
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "/0: expected string, found integer (at #/prefixItems/0/type)",
+                "/1: no value is allowed (at #/items)",
+            ]
+        );
+
+        let recursive = serde_json::json!({ "$ref": "#" });
+        let options = Options {
+            upgrade: false,
+            max_depth: 8,
+            ..Options::default()
+        };
+
+        let errors = compile(&recursive, options)
+            .unwrap()
+            .validate(&serde_json::json!(null))
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].failure, Failure::ReferenceCycle);
+        assert_eq!(errors[0].keyword_location(), "/$ref");
+
+        let cycle = serde_json::json!({
+            "anyOf": [{ "$ref": "#/$defs/a" }],
+            "$defs": { "a": { "allOf": [{ "$ref": "#" }] } }
+        });
+        let errors = Validator::new(cycle)
+            .unwrap()
+            .validate(&serde_json::json!(1));
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.keyword_location())
+                .collect::<Vec<_>>(),
+            vec!["/anyOf"]
+        );
+
+        let nested = serde_json::json!({ "properties": { "a": { "$ref": "#" } } });
+
+        assert_eq!(
+            compile(&nested, options)
+                .unwrap()
+                .validate(&serde_json::json!({ "a": { "a": { "a": { "a": { "a": 1 } } } } }))
+                .unwrap_err()[0]
+                .failure,
+            Failure::RecursionLimit
+        );
+    }
+
+    #[test]
+    fn ignored_keywords() {
+        let validator = Validator::new(serde_json::json!({
+            "unevaluatedProperties": false,
+            "properties": {
+                "a": { "unevaluatedItems": { "type": "string" } },
+                "b": { "$dynamicRef": "#node", "unevaluatedProperties": true }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            validator.ignored_keywords(),
+            [
+                vec!["unevaluatedProperties"],
+                vec!["properties", "a", "unevaluatedItems"],
+                vec!["properties", "b", "$dynamicRef"],
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_formats() {
+        let schema = serde_json::json!({
+            "properties": {
+                "id": { "type": "string", "format": "ulid" },
+                "created": { "type": "string", "format": "date" }
+            }
+        });
+        let instance = serde_json::json!({ "id": "not-a-ulid", "created": "2024-13-01" });
+        let mut validator = Validator::new(schema).unwrap();
+
+        assert!(validator.is_valid(&instance));
+
+        validator
+            .register_format_pattern("ulid", "^[0-9A-HJKMNP-TV-Z]{26}$")
+            .unwrap();
+
+        assert_eq!(
+            validator
+                .validate(&instance)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["/id: value is not a valid ulid (at #/properties/id/format)"]
+        );
+
+        assert!(validator.set_format_assertion("date", true));
+        assert!(!validator.set_format_assertion("unknown", true));
+        assert_eq!(validator.validate(&instance).len(), 2);
+
+        validator.set_format_assertion("ulid", false);
+        validator.register_format("date", |_| true);
+
+        assert!(validator.is_valid(&instance));
+    }
+
+    #[test]
+    fn custom_keywords() {
+        struct Pii;
+
+        impl Keyword for Pii {
+            fn name(&self) -> &str {
+                "x-pii"
+            }
+
+            fn validate(&self, value: &Value, instance: &Value) -> Option<String> {
+                (value == &Value::Bool(true) && instance.as_str().is_some_and(|s| s.contains('@')))
+                    .then(|| "value looks like an email address".to_string())
+            }
+        }
+
+        let schema = serde_json::json!({
+            "properties": { "note": { "type": "string", "x-pii": true } }
+        });
+        let instance = serde_json::json!({ "note": "contact a@example.com" });
+        let mut validator = Validator::new(schema).unwrap();
+
+        assert!(validator.is_valid(&instance));
+
+        validator.register_keyword(Pii);
+
+        assert_eq!(
+            validator
+                .validate(&instance)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["/note: x-pii failed: value looks like an email address (at #/properties/note/x-pii)"]
+        );
+    }
+
+    #[test]
+    fn unresolvable_ref() {
+        let result = Validator::new(serde_json::json!({
+            "properties": { "a": { "$ref": "#/$defs/missing" } }
+        }));
+
+        assert!(matches!(
+            result,
+            Err(Error::UnresolvableRef { path, value })
+                if path == ["properties", "a", "$ref"] && value == "#/$defs/missing"
+        ));
+    }
+}
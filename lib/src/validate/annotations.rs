@@ -0,0 +1,314 @@
+use super::{fragment, pointer, segments, Validator};
+use crate::constants::*;
+use serde_json::Value;
+
+/// The annotation keywords that are collected
+pub const ANNOTATION_KEYS: [&str; 5] = [
+    DEFAULT_KEY,
+    DEPRECATED_KEY,
+    READ_ONLY_KEY,
+    FORMAT_KEY,
+    TITLE_KEY,
+];
+
+/// A callback for keywords that apply to locations in an instance
+pub(super) type Visitor<'a> = dyn FnMut(&[String], &[String], &str, &Value) + 'a;
+
+/// An annotation keyword that applies to a value in an instance
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub instance_path: Vec<String>,
+    /// The path to the keyword in the schema document (after following references)
+    pub schema_path: Vec<String>,
+    pub keyword: &'static str,
+    pub value: Value,
+}
+
+impl Annotation {
+    pub fn instance_pointer(&self) -> String {
+        pointer(&self.instance_path)
+    }
+
+    pub fn schema_pointer(&self) -> String {
+        format!("#{}", pointer(&self.schema_path))
+    }
+}
+
+impl Validator {
+    /// The annotations that apply to each location in the instance, in schema order
+    ///
+    /// Annotations come from every subschema that applies to a value: `anyOf` and `oneOf`
+    /// branches, `if` (with `then`), and `contains` only contribute when the value matches them,
+    /// and `not` never does. The rest of the instance doesn't have to be valid, so partial data
+    /// can still be annotated.
+    pub fn annotations(&self, instance: &Value) -> Vec<Annotation> {
+        let mut acc = vec![];
+
+        self.visit_keywords(
+            instance,
+            &mut |instance_path, schema_path, keyword, value| {
+                if let Some(keyword) = ANNOTATION_KEYS.iter().find(|key| **key == keyword) {
+                    acc.push(Annotation {
+                        instance_path: instance_path.to_vec(),
+                        schema_path: schema_path.to_vec(),
+                        keyword,
+                        value: value.clone(),
+                    });
+                }
+            },
+        );
+
+        acc
+    }
+
+    /// Call the visitor with the instance path, schema path, name, and value of every keyword in
+    /// every subschema that applies to a location in the instance (as for annotations)
+    pub(super) fn visit_keywords(&self, instance: &Value, visitor: &mut Visitor<'_>) {
+        self.annotate(&self.schema, instance, &mut vec![], &mut vec![], 0, visitor);
+    }
+
+    fn annotate(
+        &self,
+        schema: &Value,
+        instance: &Value,
+        path: &mut Vec<String>,
+        instance_path: &mut Vec<String>,
+        depth: usize,
+        visitor: &mut Visitor<'_>,
+    ) {
+        let fields = match schema.as_object() {
+            Some(fields) if depth <= self.max_depth => fields,
+            _ => return,
+        };
+        let properties = instance.as_object();
+
+        for (keyword, value) in fields {
+            path.push(keyword.clone());
+
+            visitor(instance_path, path, keyword, value);
+
+            match keyword.as_str() {
+                REF_KEY => {
+                    if let Some(fragment) = value
+                        .as_str()
+                        .and_then(|reference| fragment(self.id.as_deref(), reference))
+                    {
+                        if let Some(target) = self.schema.pointer(fragment) {
+                            let mut location = segments(fragment);
+
+                            self.annotate(
+                                target,
+                                instance,
+                                &mut location,
+                                instance_path,
+                                depth + 1,
+                                visitor,
+                            );
+                        }
+                    }
+                }
+                PROPERTIES_KEY => {
+                    for (name, schema) in value.as_object().into_iter().flatten() {
+                        if let Some(property) =
+                            properties.and_then(|properties| properties.get(name))
+                        {
+                            path.push(name.clone());
+                            instance_path.push(name.clone());
+                            self.annotate(
+                                schema,
+                                property,
+                                path,
+                                instance_path,
+                                depth + 1,
+                                visitor,
+                            );
+                            instance_path.pop();
+                            path.pop();
+                        }
+                    }
+                }
+                PATTERN_PROPERTIES_KEY => {
+                    for (pattern, schema) in value.as_object().into_iter().flatten() {
+                        if let Some(regex) = self.patterns.get(pattern) {
+                            for (name, property) in properties.into_iter().flatten() {
+                                if regex.is_match(name) {
+                                    path.push(pattern.clone());
+                                    instance_path.push(name.clone());
+                                    self.annotate(
+                                        schema,
+                                        property,
+                                        path,
+                                        instance_path,
+                                        depth + 1,
+                                        visitor,
+                                    );
+                                    instance_path.pop();
+                                    path.pop();
+                                }
+                            }
+                        }
+                    }
+                }
+                ADDITIONAL_PROPERTIES_KEY => {
+                    for (name, property) in properties.into_iter().flatten() {
+                        if !self.is_declared(fields, name) {
+                            instance_path.push(name.clone());
+                            self.annotate(value, property, path, instance_path, depth + 1, visitor);
+                            instance_path.pop();
+                        }
+                    }
+                }
+                DEPENDENT_SCHEMAS_KEY => {
+                    for (name, schema) in value.as_object().into_iter().flatten() {
+                        if properties.is_some_and(|properties| properties.contains_key(name)) {
+                            path.push(name.clone());
+                            self.annotate(
+                                schema,
+                                instance,
+                                path,
+                                instance_path,
+                                depth + 1,
+                                visitor,
+                            );
+                            path.pop();
+                        }
+                    }
+                }
+                PREFIX_ITEMS_KEY => {
+                    if let (Some(items), Some(schemas)) = (instance.as_array(), value.as_array()) {
+                        for (index, (item, schema)) in items.iter().zip(schemas).enumerate() {
+                            path.push(index.to_string());
+                            instance_path.push(index.to_string());
+                            self.annotate(schema, item, path, instance_path, depth + 1, visitor);
+                            instance_path.pop();
+                            path.pop();
+                        }
+                    }
+                }
+                ITEMS_KEY => {
+                    let skip = fields
+                        .get(PREFIX_ITEMS_KEY)
+                        .and_then(Value::as_array)
+                        .map_or(0, Vec::len);
+
+                    for (index, item) in instance.as_array().into_iter().flatten().enumerate() {
+                        if index >= skip {
+                            instance_path.push(index.to_string());
+                            self.annotate(value, item, path, instance_path, depth + 1, visitor);
+                            instance_path.pop();
+                        }
+                    }
+                }
+                CONTAINS_KEY => {
+                    for (index, item) in instance.as_array().into_iter().flatten().enumerate() {
+                        if self.matches(value, item, depth) {
+                            instance_path.push(index.to_string());
+                            self.annotate(value, item, path, instance_path, depth + 1, visitor);
+                            instance_path.pop();
+                        }
+                    }
+                }
+                ALL_OF_KEY | ANY_OF_KEY | ONE_OF_KEY => {
+                    for (index, schema) in value.as_array().into_iter().flatten().enumerate() {
+                        if keyword == ALL_OF_KEY || self.matches(schema, instance, depth) {
+                            path.push(index.to_string());
+                            self.annotate(
+                                schema,
+                                instance,
+                                path,
+                                instance_path,
+                                depth + 1,
+                                visitor,
+                            );
+                            path.pop();
+                        }
+                    }
+                }
+                IF_KEY => {
+                    let branch = if self.matches(value, instance, depth) {
+                        self.annotate(value, instance, path, instance_path, depth + 1, visitor);
+                        THEN_KEY
+                    } else {
+                        ELSE_KEY
+                    };
+
+                    if let Some(schema) = fields.get(branch) {
+                        path.pop();
+                        path.push(branch.to_string());
+                        self.annotate(schema, instance, path, instance_path, depth + 1, visitor);
+                    }
+                }
+                _ => {}
+            }
+
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn annotations() {
+        let validator = Validator::new(json!({
+            "title": "Account",
+            "type": "object",
+            "properties": {
+                "id": { "type": "string", "format": "uuid", "readOnly": true },
+                "contact": {
+                    "anyOf": [
+                        { "type": "string", "format": "email" },
+                        { "type": "integer", "title": "Phone", "deprecated": true }
+                    ]
+                },
+                "role": { "$ref": "#/$defs/role" }
+            },
+            "$defs": {
+                "role": { "enum": ["admin", "user"], "default": "user" }
+            }
+        }))
+        .unwrap();
+
+        let annotations = validator
+            .annotations(&json!({ "id": "x", "contact": "a@example.com", "role": "admin" }))
+            .into_iter()
+            .map(|annotation| {
+                (
+                    annotation.instance_pointer(),
+                    annotation.schema_pointer(),
+                    annotation.value,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            annotations,
+            vec![
+                ("".to_string(), "#/title".to_string(), json!("Account")),
+                (
+                    "/id".to_string(),
+                    "#/properties/id/format".to_string(),
+                    json!("uuid")
+                ),
+                (
+                    "/id".to_string(),
+                    "#/properties/id/readOnly".to_string(),
+                    json!(true)
+                ),
+                (
+                    "/contact".to_string(),
+                    "#/properties/contact/anyOf/0/format".to_string(),
+                    json!("email")
+                ),
+                (
+                    "/role".to_string(),
+                    "#/$defs/role/default".to_string(),
+                    json!("user")
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use super::{fragment, json_eq, pointer, segments, Validator};
+use crate::constants::*;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TargetKind {
+    Property,
+    EnumMember,
+    /// An `anyOf` or `oneOf` branch
+    Branch,
+}
+
+/// A part of a schema that instances can exercise
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Target {
+    /// JSON Pointer to the property schema, enum member, or branch
+    pub pointer: String,
+    pub kind: TargetKind,
+    /// The number of times an instance (or a value in one) exercised it
+    pub count: usize,
+}
+
+/// Which properties, enum members, and branches of a schema have been exercised by instances
+#[derive(Clone, Debug, Default)]
+pub struct Coverage {
+    targets: Vec<Target>,
+    indices: HashMap<String, usize>,
+}
+
+impl Coverage {
+    /// All targets, in schema order
+    pub fn targets(&self) -> &[Target] {
+        &self.targets
+    }
+
+    pub fn unexercised(&self) -> impl Iterator<Item = &Target> {
+        self.targets.iter().filter(|target| target.count == 0)
+    }
+
+    pub fn exercised_count(&self) -> usize {
+        self.targets
+            .iter()
+            .filter(|target| target.count > 0)
+            .count()
+    }
+
+    fn add(&mut self, path: &[String], kind: TargetKind) {
+        let pointer = pointer(path);
+
+        if !self.indices.contains_key(&pointer) {
+            self.indices.insert(pointer.clone(), self.targets.len());
+            self.targets.push(Target {
+                pointer,
+                kind,
+                count: 0,
+            });
+        }
+    }
+
+    fn hit(&mut self, path: &[String]) {
+        if let Some(index) = self.indices.get(&pointer(path)) {
+            self.targets[*index].count += 1;
+        }
+    }
+}
+
+impl Validator {
+    /// The targets in the schema, none of which have been exercised yet
+    pub fn coverage(&self) -> Coverage {
+        let mut coverage = Coverage::default();
+        collect_targets(&self.schema, &mut vec![], &mut coverage);
+        coverage
+    }
+
+    /// Record the targets exercised by an instance
+    ///
+    /// Branches are exercised by values that match them, and the subschemas of properties and
+    /// items are followed for any values that are present, whether or not the instance is valid.
+    pub fn cover(&self, instance: &Value, coverage: &mut Coverage) {
+        self.cover_rec(&self.schema, instance, &mut vec![], 0, coverage);
+    }
+
+    fn cover_rec(
+        &self,
+        schema: &Value,
+        instance: &Value,
+        path: &mut Vec<String>,
+        depth: usize,
+        coverage: &mut Coverage,
+    ) {
+        let fields = match schema.as_object() {
+            Some(fields) if depth <= self.max_depth => fields,
+            _ => return,
+        };
+        let properties = instance.as_object();
+
+        for (keyword, value) in fields {
+            path.push(keyword.clone());
+
+            match keyword.as_str() {
+                REF_KEY => {
+                    if let Some(fragment) = value
+                        .as_str()
+                        .and_then(|reference| fragment(self.id.as_deref(), reference))
+                    {
+                        if let Some(target) = self.schema.pointer(fragment) {
+                            let mut location = segments(fragment);
+
+                            self.cover_rec(target, instance, &mut location, depth + 1, coverage);
+                        }
+                    }
+                }
+                PROPERTIES_KEY => {
+                    for (name, schema) in value.as_object().into_iter().flatten() {
+                        if let Some(property) =
+                            properties.and_then(|properties| properties.get(name))
+                        {
+                            path.push(name.clone());
+                            coverage.hit(path);
+                            self.cover_rec(schema, property, path, depth + 1, coverage);
+                            path.pop();
+                        }
+                    }
+                }
+                PATTERN_PROPERTIES_KEY => {
+                    for (pattern, schema) in value.as_object().into_iter().flatten() {
+                        if let Some(regex) = self.patterns.get(pattern) {
+                            for (name, property) in properties.into_iter().flatten() {
+                                if regex.is_match(name) {
+                                    path.push(pattern.clone());
+                                    self.cover_rec(schema, property, path, depth + 1, coverage);
+                                    path.pop();
+                                }
+                            }
+                        }
+                    }
+                }
+                ADDITIONAL_PROPERTIES_KEY => {
+                    for (name, property) in properties.into_iter().flatten() {
+                        if !self.is_declared(fields, name) {
+                            self.cover_rec(value, property, path, depth + 1, coverage);
+                        }
+                    }
+                }
+                DEPENDENT_SCHEMAS_KEY => {
+                    for (name, schema) in value.as_object().into_iter().flatten() {
+                        if properties.is_some_and(|properties| properties.contains_key(name)) {
+                            path.push(name.clone());
+                            self.cover_rec(schema, instance, path, depth + 1, coverage);
+                            path.pop();
+                        }
+                    }
+                }
+                PREFIX_ITEMS_KEY => {
+                    if let (Some(items), Some(schemas)) = (instance.as_array(), value.as_array()) {
+                        for (index, (item, schema)) in items.iter().zip(schemas).enumerate() {
+                            path.push(index.to_string());
+                            self.cover_rec(schema, item, path, depth + 1, coverage);
+                            path.pop();
+                        }
+                    }
+                }
+                ITEMS_KEY => {
+                    let skip = fields
+                        .get(PREFIX_ITEMS_KEY)
+                        .and_then(Value::as_array)
+                        .map_or(0, Vec::len);
+
+                    for item in instance.as_array().into_iter().flatten().skip(skip) {
+                        self.cover_rec(value, item, path, depth + 1, coverage);
+                    }
+                }
+                ALL_OF_KEY | ANY_OF_KEY | ONE_OF_KEY => {
+                    for (index, schema) in value.as_array().into_iter().flatten().enumerate() {
+                        if keyword == ALL_OF_KEY || self.matches(schema, instance, depth) {
+                            path.push(index.to_string());
+                            coverage.hit(path);
+                            self.cover_rec(schema, instance, path, depth + 1, coverage);
+                            path.pop();
+                        }
+                    }
+                }
+                IF_KEY => {
+                    let branch = if self.matches(value, instance, depth) {
+                        THEN_KEY
+                    } else {
+                        ELSE_KEY
+                    };
+
+                    if let Some(schema) = fields.get(branch) {
+                        path.pop();
+                        path.push(branch.to_string());
+                        self.cover_rec(schema, instance, path, depth + 1, coverage);
+                    }
+                }
+                ENUM_KEY => {
+                    for (index, member) in value.as_array().into_iter().flatten().enumerate() {
+                        if json_eq(member, instance) {
+                            path.push(index.to_string());
+                            coverage.hit(path);
+                            path.pop();
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            path.pop();
+        }
+    }
+}
+
+fn collect_targets(schema: &Value, path: &mut Vec<String>, coverage: &mut Coverage) {
+    for (key, child) in schema.as_object().into_iter().flatten() {
+        path.push(key.clone());
+
+        match key.as_str() {
+            ENUM_KEY => {
+                for index in 0..child.as_array().map_or(0, Vec::len) {
+                    path.push(index.to_string());
+                    coverage.add(path, TargetKind::EnumMember);
+                    path.pop();
+                }
+            }
+            key if SCHEMA_MAP_KEYS.contains(&key) => {
+                for (name, schema) in child.as_object().into_iter().flatten() {
+                    path.push(name.clone());
+
+                    if key == PROPERTIES_KEY {
+                        coverage.add(path, TargetKind::Property);
+                    }
+
+                    collect_targets(schema, path, coverage);
+                    path.pop();
+                }
+            }
+            key if DATA_KEYS.contains(&key) => {}
+            _ => match child.as_array() {
+                Some(schemas) => {
+                    for (index, schema) in schemas.iter().enumerate() {
+                        path.push(index.to_string());
+
+                        if key == ANY_OF_KEY || key == ONE_OF_KEY {
+                            coverage.add(path, TargetKind::Branch);
+                        }
+
+                        collect_targets(schema, path, coverage);
+                        path.pop();
+                    }
+                }
+                None => collect_targets(child, path, coverage),
+            },
+        }
+
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_from_instances() {
+        let validator = Validator::new(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "role": { "$ref": "#/$defs/role" },
+                "contact": {
+                    "oneOf": [
+                        { "type": "string" },
+                        { "type": "object", "properties": { "phone": { "type": "string" } } }
+                    ]
+                },
+                "tags": { "items": { "type": "string" } }
+            },
+            "$defs": { "role": { "enum": ["admin", "user"] } }
+        }))
+        .unwrap();
+
+        let mut coverage = validator.coverage();
+        validator.cover(
+            &serde_json::json!({ "role": "admin", "contact": "x" }),
+            &mut coverage,
+        );
+        validator.cover(&serde_json::json!({ "role": "admin" }), &mut coverage);
+
+        assert_eq!(coverage.exercised_count(), 4);
+        assert_eq!(
+            coverage
+                .unexercised()
+                .map(|target| (target.pointer.as_str(), target.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/properties/contact/oneOf/1", TargetKind::Branch),
+                (
+                    "/properties/contact/oneOf/1/properties/phone",
+                    TargetKind::Property
+                ),
+                ("/properties/tags", TargetKind::Property),
+                ("/$defs/role/enum/1", TargetKind::EnumMember),
+            ]
+        );
+        assert_eq!(coverage.targets()[0].count, 2);
+    }
+}
@@ -0,0 +1,171 @@
+use super::pointer;
+use crate::constants::*;
+use crate::schema::upgrade;
+use serde_json::Value;
+
+/// A JSON Schema draft, whose keyword semantics apply to the subschemas that declare it with
+/// `$schema` (or to every subschema, if it's the default)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Dialect {
+    Draft04,
+    Draft06,
+    Draft07,
+    Draft2019_09,
+    #[default]
+    Draft2020_12,
+}
+
+impl Dialect {
+    /// The dialect for a `$schema` URI (with or without an empty fragment), if it's known
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let uri = uri.strip_suffix('#').unwrap_or(uri);
+
+        match uri.split_once("://").map_or(uri, |(_, rest)| rest) {
+            "json-schema.org/draft-04/schema" => Some(Self::Draft04),
+            "json-schema.org/draft-06/schema" => Some(Self::Draft06),
+            "json-schema.org/draft-07/schema" => Some(Self::Draft07),
+            "json-schema.org/draft/2019-09/schema" => Some(Self::Draft2019_09),
+            "json-schema.org/draft/2020-12/schema" => Some(Self::Draft2020_12),
+            _ => None,
+        }
+    }
+
+    /// The dialect that a schema declares with `$schema`, if it's known
+    pub fn declared(schema: &Value) -> Option<Self> {
+        schema
+            .get(SCHEMA_KEY)
+            .and_then(Value::as_str)
+            .and_then(Self::from_uri)
+    }
+
+    /// Whether keywords next to `$ref` are ignored (as they are before 2019-09)
+    fn ignores_ref_siblings(self) -> bool {
+        matches!(self, Self::Draft04 | Self::Draft06 | Self::Draft07)
+    }
+}
+
+/// Rewrite a schema so that 2020-12 semantics give the results of each subschema's dialect
+///
+/// Keywords next to `$ref` are removed where they'd be ignored, and then each subschema in an
+/// older dialect is upgraded (which rewrites `definitions`, boolean exclusive bounds, array-form
+/// `items`, and `dependencies`).
+pub(super) fn normalize(schema: &mut Value, default: Dialect) {
+    let mut older = vec![];
+
+    normalize_rec(schema, default, None, &mut vec![], &mut older);
+
+    for path in older {
+        if let Some(subschema) = schema.pointer_mut(&pointer(&path)) {
+            upgrade(subschema);
+        }
+    }
+}
+
+/// Remove ignored `$ref` siblings, and collect the outermost subschemas that are in an older
+/// dialect than 2020-12
+fn normalize_rec(
+    schema: &mut Value,
+    inherited: Dialect,
+    parent: Option<Dialect>,
+    path: &mut Vec<String>,
+    older: &mut Vec<Vec<String>>,
+) {
+    let dialect = Dialect::declared(schema).unwrap_or(inherited);
+    let fields = match schema.as_object_mut() {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    if dialect.ignores_ref_siblings() && fields.contains_key(REF_KEY) {
+        fields.retain(|key, _| {
+            [REF_KEY, SCHEMA_KEY, DEFS_KEY, DEFINITIONS_KEY].contains(&key.as_str())
+        });
+    }
+
+    if dialect != Dialect::Draft2020_12
+        && parent.is_none_or(|parent| parent == Dialect::Draft2020_12)
+    {
+        older.push(path.clone());
+    }
+
+    for (key, child) in fields.iter_mut() {
+        path.push(key.clone());
+
+        if SCHEMA_MAP_KEYS.contains(&key.as_str()) {
+            for (name, schema) in child.as_object_mut().into_iter().flatten() {
+                path.push(name.clone());
+                normalize_rec(schema, dialect, Some(dialect), path, older);
+                path.pop();
+            }
+        } else if !DATA_KEYS.contains(&key.as_str()) {
+            match child.as_array_mut() {
+                Some(schemas) => {
+                    for (index, schema) in schemas.iter_mut().enumerate() {
+                        path.push(index.to_string());
+                        normalize_rec(schema, dialect, Some(dialect), path, older);
+                        path.pop();
+                    }
+                }
+                None => normalize_rec(child, dialect, Some(dialect), path, older),
+            }
+        }
+
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Options, Validator};
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dialect_semantics() {
+        let with_dialect = |schema: Value, dialect: Dialect| {
+            Validator::with_options(
+                schema,
+                Options {
+                    dialect,
+                    ..Options::default()
+                },
+            )
+            .unwrap()
+        };
+
+        // Draft-07 ignores keywords next to `$ref`, but 2020-12 doesn't.
+        let schema = json!({
+            "$defs": { "name": { "type": "string" } },
+            "properties": { "name": { "$ref": "#/$defs/name", "maxLength": 3 } }
+        });
+        let instance = json!({ "name": "abcdef" });
+
+        assert!(!with_dialect(schema.clone(), Dialect::Draft2020_12).is_valid(&instance));
+        assert!(with_dialect(schema.clone(), Dialect::Draft07).is_valid(&instance));
+
+        // A declared `$schema` overrides the default, for the subschema that declares it.
+        let mut declared = schema;
+        declared["properties"]["name"]["$schema"] =
+            json!("http://json-schema.org/draft-07/schema#");
+
+        assert!(with_dialect(declared, Dialect::Draft2020_12).is_valid(&instance));
+
+        // 2019-09 uses array-form `items` and `additionalItems`.
+        let tuple = json!({
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "items": [{ "type": "integer" }],
+            "additionalItems": false
+        });
+        let validator = with_dialect(tuple, Dialect::Draft2020_12);
+
+        assert!(validator.is_valid(&json!([1])));
+        assert!(!validator.is_valid(&json!(["a"])));
+        assert!(!validator.is_valid(&json!([1, 2])));
+
+        assert_eq!(
+            Dialect::from_uri("https://json-schema.org/draft-07/schema"),
+            Some(Dialect::Draft07)
+        );
+        assert_eq!(Dialect::from_uri("https://example.com/schema"), None);
+    }
+}
@@ -0,0 +1,233 @@
+use std::sync::Arc;
+
+/// A check for strings with a `format`
+pub type FormatCheck = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A registered format, which is only checked if it's an assertion
+#[derive(Clone)]
+pub(super) struct Format {
+    pub(super) check: FormatCheck,
+    pub(super) assertion: bool,
+}
+
+impl std::fmt::Debug for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Format")
+            .field("assertion", &self.assertion)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The formats that are known without registration (all annotations by default)
+pub const BUILT_IN_FORMATS: [&str; 9] = [
+    "date",
+    "date-time",
+    "duration",
+    "email",
+    "ipv4",
+    "ipv6",
+    "time",
+    "uri",
+    "uuid",
+];
+
+pub(super) fn built_in(name: &str) -> Option<fn(&str) -> bool> {
+    match name {
+        "date" => Some(is_date),
+        "date-time" => Some(is_date_time),
+        "duration" => Some(is_duration),
+        "email" => Some(is_email),
+        "ipv4" => Some(|value| value.parse::<std::net::Ipv4Addr>().is_ok()),
+        "ipv6" => Some(|value| value.parse::<std::net::Ipv6Addr>().is_ok()),
+        "time" => Some(is_time),
+        "uri" => Some(is_uri),
+        "uuid" => Some(is_uuid),
+        _ => None,
+    }
+}
+
+/// The digits as a number, if they're all ASCII digits
+fn number(digits: &str) -> Option<u32> {
+    if !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// An RFC 3339 `full-date`
+pub(crate) fn is_date(value: &str) -> bool {
+    let mut parts = value.split('-');
+
+    match (
+        parts.next().filter(|year| year.len() == 4).and_then(number),
+        parts
+            .next()
+            .filter(|month| month.len() == 2)
+            .and_then(number),
+        parts.next().filter(|day| day.len() == 2).and_then(number),
+        parts.next(),
+    ) {
+        (Some(year), Some(month), Some(day), None) => {
+            let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+            let days = match month {
+                2 if leap => 29,
+                2 => 28,
+                4 | 6 | 9 | 11 => 30,
+                1..=12 => 31,
+                _ => 0,
+            };
+
+            (1..=days).contains(&day)
+        }
+        _ => false,
+    }
+}
+
+/// An RFC 3339 `full-time` (a leap second is allowed in any minute)
+fn is_time(value: &str) -> bool {
+    let (time, offset) = match value.find(['Z', 'z', '+', '-']) {
+        Some(index) => value.split_at(index),
+        None => return false,
+    };
+    let time = time.split_once('.').map_or(Some(time), |(time, fraction)| {
+        number(fraction).map(|_| time)
+    });
+    let valid_offset = match offset {
+        "Z" | "z" => true,
+        _ => offset[1..]
+            .split_once(':')
+            .and_then(|(hours, minutes)| {
+                Some(
+                    hours.len() == 2
+                        && minutes.len() == 2
+                        && number(hours)? < 24
+                        && number(minutes)? < 60,
+                )
+            })
+            .unwrap_or(false),
+    };
+    let valid_time = time
+        .map(|time| {
+            let parts = time.split(':').collect::<Vec<_>>();
+
+            parts.len() == 3
+                && parts.iter().all(|part| part.len() == 2)
+                && number(parts[0]).is_some_and(|hours| hours < 24)
+                && number(parts[1]).is_some_and(|minutes| minutes < 60)
+                && number(parts[2]).is_some_and(|seconds| seconds <= 60)
+        })
+        .unwrap_or(false);
+
+    valid_time && valid_offset
+}
+
+/// An RFC 3339 `date-time`
+pub(crate) fn is_date_time(value: &str) -> bool {
+    value
+        .split_once(['T', 't'])
+        .is_some_and(|(date, time)| is_date(date) && is_time(time))
+}
+
+/// An ISO 8601 duration (for example `P1Y2M` or `PT1.5S`)
+fn is_duration(value: &str) -> bool {
+    let rest = match value.strip_prefix('P') {
+        Some(rest) if !rest.is_empty() => rest,
+        _ => return false,
+    };
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return false,
+        Some((date, time)) => (date, time),
+        None => (rest, ""),
+    };
+
+    let valid = |part: &str, designators: &str| {
+        let mut remaining = designators;
+        let mut digits = String::new();
+
+        for c in part.chars() {
+            if c.is_ascii_digit() || (c == '.' && !digits.is_empty()) {
+                digits.push(c);
+            } else {
+                match remaining.find(c) {
+                    Some(index) if !digits.is_empty() => {
+                        remaining = &remaining[index + 1..];
+                        digits.clear();
+                    }
+                    _ => return false,
+                }
+            }
+        }
+
+        digits.is_empty()
+    };
+
+    if date.ends_with('W') && date.len() > 1 {
+        time.is_empty() && number(&date[..date.len() - 1]).is_some()
+    } else {
+        valid(date, "YMD") && valid(time, "HMS")
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    value.rsplit_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty()
+            && !domain.is_empty()
+            && !domain.starts_with('.')
+            && !domain.ends_with('.')
+            && !value.chars().any(char::is_whitespace)
+    })
+}
+
+/// An absolute URI (with a scheme)
+fn is_uri(value: &str) -> bool {
+    value.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    }) && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(index, c)| match index {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_formats() {
+        let cases = [
+            ("date", "2024-02-29", true),
+            ("date", "2023-02-29", false),
+            ("date-time", "2024-01-01T12:30:00.5+01:00", true),
+            ("date-time", "2024-01-01 12:30:00Z", false),
+            ("duration", "P1Y2MT3.5S", true),
+            ("duration", "PT", false),
+            ("email", "user@example.com", true),
+            ("email", "user example.com", false),
+            ("ipv4", "192.168.0.1", true),
+            ("ipv6", "::1", true),
+            ("time", "23:59:60Z", true),
+            ("time", "24:00:00Z", false),
+            ("uri", "https://example.com/a", true),
+            ("uri", "/relative", false),
+            ("uuid", "123e4567-e89b-12d3-a456-426614174000", true),
+            ("uuid", "123e4567e89b12d3a456426614174000", false),
+        ];
+
+        for (format, value, expected) in cases {
+            assert_eq!(
+                built_in(format).unwrap()(value),
+                expected,
+                "{format} {value}"
+            );
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use super::{pointer, ValidationError, Validator};
+use crate::constants::*;
+use serde_json::{Map, Value};
+
+/// The standard output formats for validation results (from the 2020-12 specification)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Output {
+    /// Only whether the instance is valid
+    Flag,
+    /// A flat list of errors
+    Basic,
+    /// Errors nested by the subschemas they were found in
+    Detailed,
+}
+
+impl Validator {
+    /// The validation result for the instance as an output unit in the given format
+    ///
+    /// `absoluteKeywordLocation` is only included when the schema has an absolute `$id`, and only
+    /// for errors (not for the subschemas that group them in the detailed format).
+    pub fn output(&self, instance: &Value, output: Output) -> Value {
+        let errors = self.validate(instance);
+
+        if errors.is_empty() || output == Output::Flag {
+            return serde_json::json!({ "valid": errors.is_empty() });
+        }
+
+        let id = self.id.as_deref();
+
+        let units = match output {
+            Output::Basic => errors.iter().map(|error| error_unit(error, id)).collect(),
+            _ => {
+                let mut root = Node::default();
+
+                for error in &errors {
+                    root.insert(error);
+                }
+
+                root.children
+                    .into_iter()
+                    .map(|child| child.collapse().into_unit(id))
+                    .collect()
+            }
+        };
+
+        serde_json::json!({
+            "valid": false,
+            "keywordLocation": "",
+            "instanceLocation": "",
+            "errors": Value::Array(units),
+        })
+    }
+}
+
+fn error_unit(error: &ValidationError, id: Option<&str>) -> Value {
+    let mut fields = Map::new();
+    fields.insert("valid".to_string(), Value::Bool(false));
+    fields.insert(
+        "keywordLocation".to_string(),
+        Value::String(error.keyword_location()),
+    );
+
+    if let Some(location) = error.absolute_keyword_location(id) {
+        fields.insert(
+            "absoluteKeywordLocation".to_string(),
+            Value::String(location),
+        );
+    }
+
+    fields.insert(
+        "instanceLocation".to_string(),
+        Value::String(error.instance_pointer()),
+    );
+    fields.insert(
+        "error".to_string(),
+        Value::String(error.failure.to_string()),
+    );
+
+    Value::Object(fields)
+}
+
+/// A subschema location in the detailed output, with the errors found under it
+#[derive(Default)]
+struct Node<'a> {
+    schema_path: &'a [String],
+    instance_path: &'a [String],
+    children: Vec<Node<'a>>,
+    error: Option<&'a ValidationError>,
+}
+
+impl<'a> Node<'a> {
+    fn insert(&mut self, error: &'a ValidationError) {
+        let mut node = self;
+
+        for (schema_length, instance_length) in subschemas(error) {
+            let schema_path = &error.schema_path[..schema_length];
+            let instance_path = &error.instance_path[..instance_length];
+
+            let index = match node.children.iter().position(|child| {
+                child.error.is_none()
+                    && child.schema_path == schema_path
+                    && child.instance_path == instance_path
+            }) {
+                Some(index) => index,
+                None => {
+                    node.children.push(Node {
+                        schema_path,
+                        instance_path,
+                        ..Node::default()
+                    });
+                    node.children.len() - 1
+                }
+            };
+
+            node = &mut node.children[index];
+        }
+
+        node.children.push(Node {
+            schema_path: &error.schema_path,
+            instance_path: &error.instance_path,
+            children: vec![],
+            error: Some(error),
+        });
+    }
+
+    /// Replace subschemas with only one result by that result
+    fn collapse(mut self) -> Self {
+        self.children = self.children.into_iter().map(Self::collapse).collect();
+
+        if self.error.is_none() && self.children.len() == 1 {
+            self.children.remove(0)
+        } else {
+            self
+        }
+    }
+
+    fn into_unit(self, id: Option<&str>) -> Value {
+        match self.error {
+            Some(error) => error_unit(error, id),
+            None => serde_json::json!({
+                "valid": false,
+                "keywordLocation": pointer(self.schema_path),
+                "instanceLocation": pointer(self.instance_path),
+                "errors": self
+                    .children
+                    .into_iter()
+                    .map(|child| child.into_unit(id))
+                    .collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+/// The lengths of the schema and instance paths at each subschema that the error was found under
+/// (not including the root schema or the subschema that failed itself)
+fn subschemas(error: &ValidationError) -> Vec<(usize, usize)> {
+    let path = &error.schema_path;
+    let mut result = vec![];
+    let mut index = 0;
+    let mut instance_length = 0;
+
+    while index < path.len() {
+        match path[index].as_str() {
+            PROPERTIES_KEY | PATTERN_PROPERTIES_KEY | PREFIX_ITEMS_KEY => {
+                index += 2;
+                instance_length += 1;
+            }
+            ITEMS_KEY | ADDITIONAL_PROPERTIES_KEY | PROPERTY_NAMES_KEY => {
+                index += 1;
+                instance_length += 1;
+            }
+            DEFS_KEY | DEPENDENT_SCHEMAS_KEY | ALL_OF_KEY | ANY_OF_KEY | ONE_OF_KEY => {
+                index += 2;
+            }
+            REF_KEY | NOT_KEY | IF_KEY | THEN_KEY | ELSE_KEY | CONTAINS_KEY => {
+                index += 1;
+            }
+            _ => break,
+        }
+
+        if index >= path.len() {
+            break;
+        }
+
+        result.push((index, instance_length.min(error.instance_path.len())));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn output_formats() {
+        let validator = Validator::new(json!({
+            "$id": "https://example.com/person",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "maxLength": 4, "pattern": "^[a-z]*$" },
+                "address": { "$ref": "#/$defs/address" }
+            },
+            "$defs": {
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } }
+                }
+            }
+        }))
+        .unwrap();
+        let instance = json!({ "name": "", "address": { "city": 1 } });
+
+        assert_eq!(
+            validator.output(&json!({}), Output::Detailed),
+            json!({ "valid": true })
+        );
+        assert_eq!(
+            validator.output(&instance, Output::Flag),
+            json!({ "valid": false })
+        );
+
+        let basic = validator.output(&instance, Output::Basic);
+        assert_eq!(basic["errors"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            basic["errors"][1]["keywordLocation"],
+            "/properties/address/$ref/properties/city/type"
+        );
+        assert_eq!(
+            basic["errors"][1]["absoluteKeywordLocation"],
+            "https://example.com/person#/$defs/address/properties/city/type"
+        );
+        assert_eq!(basic["errors"][1]["instanceLocation"], "/address/city");
+
+        let detailed = validator.output(&instance, Output::Detailed);
+        assert_eq!(
+            detailed["errors"][0]["keywordLocation"],
+            "/properties/name/minLength"
+        );
+        assert_eq!(
+            detailed["errors"][1]["keywordLocation"],
+            "/properties/address/$ref/properties/city/type"
+        );
+
+        let instance = json!({ "name": "ABCDEF", "address": { "city": 1 } });
+        let detailed = validator.output(&instance, Output::Detailed);
+        assert_eq!(detailed["errors"][0]["keywordLocation"], "/properties/name");
+        assert_eq!(detailed["errors"][0]["instanceLocation"], "/name");
+        assert_eq!(
+            detailed["errors"][0]["errors"][0]["keywordLocation"],
+            "/properties/name/maxLength"
+        );
+    }
+}
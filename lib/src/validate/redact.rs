@@ -0,0 +1,156 @@
+use super::{pointer, Validator};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// The number of hexadecimal digits of the digest that replace a hashed value
+const HASH_LENGTH: usize = 16;
+
+/// How marked values are replaced
+#[derive(Clone, Debug, PartialEq)]
+pub enum Redaction {
+    /// Replace each value with the given value
+    Replace(Value),
+    /// Replace each value with a string digest of it, so that equal values are still equal (and
+    /// joins between documents still work) after redaction
+    Hash,
+}
+
+impl Redaction {
+    fn apply(&self, value: &Value) -> Value {
+        match self {
+            Self::Replace(replacement) => replacement.clone(),
+            Self::Hash => {
+                let mut hasher = Sha256::new();
+
+                match value {
+                    Value::String(value) => hasher.update(value.as_bytes()),
+                    // Writing a value to a hasher cannot fail.
+                    other => serde_json::to_writer(&mut hasher, other).unwrap(),
+                }
+
+                let digest = hasher.finalize();
+
+                Value::String(
+                    digest
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect::<String>()[..HASH_LENGTH]
+                        .to_string(),
+                )
+            }
+        }
+    }
+}
+
+impl Validator {
+    /// Redact every value in the instance that's described by a subschema with one of the marker
+    /// keywords (such as `x-pii` or `writeOnly`), unless the keyword's value is `false`, and return
+    /// JSON pointers to the redacted values
+    ///
+    /// Subschemas apply to values as they do for [`Validator::annotations`]. Values inside a
+    /// redacted value aren't listed separately, and the redacted instance may no longer be valid.
+    pub fn redact(
+        &self,
+        instance: &mut Value,
+        markers: &[&str],
+        redaction: &Redaction,
+    ) -> Vec<String> {
+        let mut marked = vec![];
+
+        self.visit_keywords(instance, &mut |instance_path, _, keyword, value| {
+            if markers.contains(&keyword) && value != &Value::Bool(false) {
+                marked.push(instance_path.to_vec());
+            }
+        });
+
+        // Every location sorts before the locations inside it.
+        marked.sort();
+        marked.dedup();
+
+        let mut redacted: Vec<Vec<String>> = vec![];
+
+        for path in marked {
+            if redacted.iter().any(|outer| path.starts_with(outer)) {
+                continue;
+            }
+
+            if let Some(target) = instance.pointer_mut(&pointer(&path)) {
+                *target = redaction.apply(target);
+                redacted.push(path);
+            }
+        }
+
+        redacted.iter().map(|path| pointer(path)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redaction() {
+        let validator = Validator::new(json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "contacts": { "type": "array", "items": { "$ref": "#/$defs/contact" } },
+                "password": { "type": "string", "writeOnly": true },
+                "address": {
+                    "x-pii": true,
+                    "properties": { "street": { "x-pii": true } }
+                },
+                "notes": { "type": "string", "x-pii": false }
+            },
+            "$defs": {
+                "contact": {
+                    "properties": { "email": { "type": "string", "x-pii": true } }
+                }
+            }
+        }))
+        .unwrap();
+
+        let original = json!({
+            "name": "Ada",
+            "contacts": [{ "email": "ada@example.com" }, { "email": "ada@example.com" }],
+            "password": "secret",
+            "address": { "street": "1 Main St" },
+            "notes": "none"
+        });
+
+        let mut instance = original.clone();
+        let redacted = validator.redact(
+            &mut instance,
+            &["x-pii", "writeOnly"],
+            &Redaction::Replace(json!("REDACTED")),
+        );
+
+        assert_eq!(
+            redacted,
+            vec![
+                "/address",
+                "/contacts/0/email",
+                "/contacts/1/email",
+                "/password"
+            ]
+        );
+        assert_eq!(
+            instance,
+            json!({
+                "name": "Ada",
+                "contacts": [{ "email": "REDACTED" }, { "email": "REDACTED" }],
+                "password": "REDACTED",
+                "address": "REDACTED",
+                "notes": "none"
+            })
+        );
+
+        let mut instance = original;
+        validator.redact(&mut instance, &["x-pii"], &Redaction::Hash);
+
+        assert_eq!(instance["contacts"][0], instance["contacts"][1]);
+        assert_eq!(instance["password"], "secret");
+        assert_eq!(instance["contacts"][0]["email"].as_str().unwrap().len(), 16);
+    }
+}
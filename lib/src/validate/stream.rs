@@ -0,0 +1,513 @@
+use super::{check_size, fragment, segments, Failure, Paths, ValidationError, Validator};
+use crate::constants::*;
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Read;
+
+impl Validator {
+    /// Validate a JSON document as it's read, without building it in memory
+    ///
+    /// Strings, numbers, booleans, and nulls are checked against the whole schema, but objects
+    /// and arrays are only checked against the structural keywords (`type`, `properties`,
+    /// `patternProperties`, `additionalProperties`, `propertyNames`, `required`,
+    /// `dependentRequired`, `prefixItems`, `items`, and the size bounds), through `$ref` and
+    /// `allOf`. Keywords like `enum` or `anyOf` that would need a whole object or array are
+    /// ignored for them. Errors are in document order rather than schema order.
+    pub fn validate_reader(
+        &self,
+        reader: impl Read,
+    ) -> Result<Vec<ValidationError>, serde_json::Error> {
+        let mut state = State {
+            validator: self,
+            instance: vec![],
+            errors: vec![],
+        };
+        let mut deserializer =
+            serde_json::Deserializer::from_reader(std::io::BufReader::new(reader));
+
+        ValueSeed {
+            state: &mut state,
+            applied: vec![Applied {
+                schema: &self.schema,
+                schema_path: vec![],
+                absolute: vec![],
+                depth: 0,
+            }],
+        }
+        .deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok(state.errors)
+    }
+}
+
+/// A subschema that applies to the current value
+#[derive(Clone)]
+struct Applied<'a> {
+    schema: &'a Value,
+    schema_path: Vec<String>,
+    absolute: Vec<String>,
+    depth: usize,
+}
+
+impl<'a> Applied<'a> {
+    fn child(&self, entries: &[&str], schema: &'a Value) -> Self {
+        let entries = entries.iter().map(|entry| entry.to_string());
+
+        Self {
+            schema,
+            schema_path: self
+                .schema_path
+                .iter()
+                .cloned()
+                .chain(entries.clone())
+                .collect(),
+            absolute: self.absolute.iter().cloned().chain(entries).collect(),
+            depth: self.depth + 1,
+        }
+    }
+}
+
+struct State<'a> {
+    validator: &'a Validator,
+    instance: Vec<String>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'a> State<'a> {
+    fn paths(&self, applied: &Applied, keyword: Option<&str>) -> Paths {
+        let mut paths = Paths {
+            schema: applied.schema_path.clone(),
+            absolute: applied.absolute.clone(),
+            instance: self.instance.clone(),
+            ..Paths::default()
+        };
+
+        if let Some(keyword) = keyword {
+            paths.push_schema(keyword.to_string());
+        }
+
+        paths
+    }
+
+    fn fail(&mut self, applied: &Applied, keyword: Option<&str>, failure: Failure) {
+        self.paths(applied, keyword).fail(failure, &mut self.errors);
+    }
+
+    /// The schemas that apply to an object or array, after following references and `allOf`
+    fn expand(&mut self, applied: Applied<'a>, acc: &mut Vec<Applied<'a>>) {
+        if applied.depth > self.validator.max_depth {
+            self.fail(&applied, None, Failure::RecursionLimit);
+            return;
+        }
+
+        let fields = match applied.schema {
+            Value::Bool(false) => {
+                self.fail(&applied, None, Failure::False);
+                return;
+            }
+            Value::Object(fields) => fields,
+            _ => return,
+        };
+
+        acc.push(applied.clone());
+
+        if let Some(fragment) = fields
+            .get(REF_KEY)
+            .and_then(Value::as_str)
+            .and_then(|reference| fragment(self.validator.id.as_deref(), reference))
+        {
+            if let Some(target) = self.validator.schema.pointer(fragment) {
+                let mut child = applied.child(&[REF_KEY], target);
+                child.absolute = segments(fragment);
+                self.expand(child, acc);
+            }
+        }
+
+        for (index, schema) in fields
+            .get(ALL_OF_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            self.expand(
+                applied.child(&[ALL_OF_KEY, &index.to_string()], schema),
+                acc,
+            );
+        }
+    }
+
+    /// Expand the schemas for an object or array and check their `type`
+    fn container(&mut self, applied: Vec<Applied<'a>>, found: &'static str) -> Vec<Applied<'a>> {
+        let mut expanded = vec![];
+
+        for applied in applied {
+            self.expand(applied, &mut expanded);
+        }
+
+        for applied in &expanded {
+            let expected = match applied.schema.get(TYPE_KEY) {
+                Some(Value::String(name)) => vec![name.clone()],
+                Some(Value::Array(names)) => names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+                _ => continue,
+            };
+
+            if !expected.iter().any(|name| name == found) {
+                self.fail(applied, Some(TYPE_KEY), Failure::Type { expected, found });
+            }
+        }
+
+        expanded
+    }
+
+    fn scalar(&mut self, applied: Vec<Applied<'a>>, value: Value) {
+        for applied in applied {
+            let mut paths = self.paths(&applied, None);
+            self.validator.check(
+                applied.schema,
+                &value,
+                &mut paths,
+                applied.depth,
+                &mut self.errors,
+            );
+        }
+    }
+}
+
+struct ValueSeed<'s, 'a> {
+    state: &'s mut State<'a>,
+    applied: Vec<Applied<'a>>,
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.applied.is_empty() {
+            IgnoredAny::deserialize(deserializer).map(|_| ())
+        } else {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for ValueSeed<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        self.state.scalar(self.applied, Value::Null);
+        Ok(())
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<(), E> {
+        self.state.scalar(self.applied, Value::Bool(value));
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<(), E> {
+        self.state.scalar(self.applied, Value::from(value));
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<(), E> {
+        self.state.scalar(self.applied, Value::from(value));
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<(), E> {
+        self.state.scalar(self.applied, Value::from(value));
+        Ok(())
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<(), E> {
+        self.state
+            .scalar(self.applied, Value::String(value.to_string()));
+        Ok(())
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<(), E> {
+        self.state.scalar(self.applied, Value::String(value));
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let state = self.state;
+        let expanded = state.container(self.applied, "array");
+        let mut index = 0;
+
+        loop {
+            let mut children = vec![];
+
+            for applied in &expanded {
+                let prefix = applied
+                    .schema
+                    .get(PREFIX_ITEMS_KEY)
+                    .and_then(Value::as_array)
+                    .and_then(|schemas| schemas.get(index));
+
+                if let Some(schema) = prefix {
+                    children.push(applied.child(&[PREFIX_ITEMS_KEY, &index.to_string()], schema));
+                } else if let Some(schema) = applied.schema.get(ITEMS_KEY) {
+                    children.push(applied.child(&[ITEMS_KEY], schema));
+                }
+            }
+
+            state.instance.push(index.to_string());
+            let item = seq.next_element_seed(ValueSeed {
+                state: &mut *state,
+                applied: children,
+            })?;
+            state.instance.pop();
+
+            if item.is_none() {
+                break;
+            }
+
+            index += 1;
+        }
+
+        for applied in &expanded {
+            for keyword in [MIN_ITEMS_KEY, MAX_ITEMS_KEY] {
+                if let Some(limit) = applied.schema.get(keyword) {
+                    let paths = state.paths(applied, Some(keyword));
+                    check_size(keyword, limit, index, &paths, &mut state.errors);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let state = self.state;
+        let expanded = state.container(self.applied, "object");
+
+        // Only the names that `required` and `dependentRequired` ask about are remembered.
+        let mut relevant = HashSet::new();
+
+        for applied in &expanded {
+            relevant.extend(
+                applied
+                    .schema
+                    .get(REQUIRED_KEY)
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str),
+            );
+
+            for (name, dependencies) in applied
+                .schema
+                .get(DEPENDENT_REQUIRED_KEY)
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+            {
+                relevant.insert(name.as_str());
+                relevant.extend(
+                    dependencies
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str),
+                );
+            }
+        }
+
+        let mut present = HashSet::new();
+        let mut count = 0;
+
+        while let Some(name) = map.next_key::<String>()? {
+            let mut children = vec![];
+            count += 1;
+
+            for applied in &expanded {
+                let mut declared = false;
+
+                if let Some(schema) = applied
+                    .schema
+                    .get(PROPERTIES_KEY)
+                    .and_then(|schemas| schemas.get(&name))
+                {
+                    declared = true;
+                    children.push(applied.child(&[PROPERTIES_KEY, &name], schema));
+                }
+
+                for (pattern, schema) in applied
+                    .schema
+                    .get(PATTERN_PROPERTIES_KEY)
+                    .and_then(Value::as_object)
+                    .into_iter()
+                    .flatten()
+                {
+                    if state
+                        .validator
+                        .patterns
+                        .get(pattern)
+                        .is_some_and(|regex| regex.is_match(&name))
+                    {
+                        declared = true;
+                        children.push(applied.child(&[PATTERN_PROPERTIES_KEY, pattern], schema));
+                    }
+                }
+
+                match applied.schema.get(ADDITIONAL_PROPERTIES_KEY) {
+                    Some(Value::Bool(false)) if !declared => state.fail(
+                        applied,
+                        Some(ADDITIONAL_PROPERTIES_KEY),
+                        Failure::AdditionalProperty { name: name.clone() },
+                    ),
+                    Some(schema) if !declared => {
+                        children.push(applied.child(&[ADDITIONAL_PROPERTIES_KEY], schema));
+                    }
+                    _ => {}
+                }
+
+                if let Some(schema) = applied.schema.get(PROPERTY_NAMES_KEY) {
+                    state.instance.push(name.clone());
+                    state.scalar(
+                        vec![applied.child(&[PROPERTY_NAMES_KEY], schema)],
+                        Value::String(name.clone()),
+                    );
+                    state.instance.pop();
+                }
+            }
+
+            state.instance.push(name);
+            map.next_value_seed(ValueSeed {
+                state: &mut *state,
+                applied: children,
+            })?;
+
+            if let Some(name) = state.instance.pop() {
+                if relevant.contains(name.as_str()) {
+                    present.insert(name);
+                }
+            }
+        }
+
+        for applied in &expanded {
+            for keyword in [MIN_PROPERTIES_KEY, MAX_PROPERTIES_KEY] {
+                if let Some(limit) = applied.schema.get(keyword) {
+                    let paths = state.paths(applied, Some(keyword));
+                    check_size(keyword, limit, count, &paths, &mut state.errors);
+                }
+            }
+
+            for name in applied
+                .schema
+                .get(REQUIRED_KEY)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !present.contains(name) {
+                    state.fail(
+                        applied,
+                        Some(REQUIRED_KEY),
+                        Failure::Required {
+                            name: name.to_string(),
+                        },
+                    );
+                }
+            }
+
+            for (name, dependencies) in applied
+                .schema
+                .get(DEPENDENT_REQUIRED_KEY)
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+                .filter(|(name, _)| present.contains(name.as_str()))
+            {
+                for dependency in dependencies
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                {
+                    if !present.contains(dependency) {
+                        state.fail(
+                            applied,
+                            Some(DEPENDENT_REQUIRED_KEY),
+                            Failure::DependentRequired {
+                                name: name.clone(),
+                                dependency: dependency.to_string(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn streaming_matches_validate() {
+        let validator = Validator::new(json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 1 },
+                "tags": { "type": "array", "items": { "type": "string", "maxLength": 3 }, "maxItems": 2 },
+                "owner": { "$ref": "#/$defs/user" }
+            },
+            "patternProperties": { "^x-": { "type": "string" } },
+            "additionalProperties": false,
+            "required": ["id", "owner"],
+            "$defs": {
+                "user": {
+                    "allOf": [{ "required": ["name"] }],
+                    "properties": { "name": { "enum": ["a", "b"] } },
+                    "dependentRequired": { "name": ["email"] }
+                }
+            }
+        }))
+        .unwrap();
+
+        for instance in [
+            json!({ "id": 0, "tags": ["abcd", 1, "ok"], "owner": { "name": "c" }, "x-a": 1, "extra": [] }),
+            json!({ "tags": "none", "owner": {} }),
+            json!({ "id": 1, "owner": { "name": "a", "email": "a@example.com" }, "x-b": "ok" }),
+            json!([1, 2]),
+        ] {
+            let mut expected = validator
+                .validate(&instance)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            let mut actual = validator
+                .validate_reader(instance.to_string().as_bytes())
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+
+            expected.sort();
+            actual.sort();
+
+            assert_eq!(actual, expected);
+        }
+
+        assert!(validator.validate_reader("{\"id\": ".as_bytes()).is_err());
+    }
+}
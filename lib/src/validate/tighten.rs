@@ -0,0 +1,425 @@
+use super::{json_eq, pointer, Validator};
+use crate::constants::*;
+use indexmap::IndexMap;
+use serde_json::{Number, Value};
+use std::collections::HashSet;
+use std::fmt::Display;
+
+/// Thresholds for suggesting tighter constraints
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TighteningOptions {
+    /// The most distinct values to suggest as an enum
+    pub max_enum_values: usize,
+    /// The fewest values a subschema must describe before anything is suggested for it
+    pub min_count: usize,
+}
+
+impl Default for TighteningOptions {
+    fn default() -> Self {
+        Self {
+            max_enum_values: 10,
+            min_count: 10,
+        }
+    }
+}
+
+/// A constraint that every observed value satisfies
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tightening {
+    Enum(Vec<Value>),
+    Minimum(Number),
+    Maximum(Number),
+    /// Replace `number` with `integer`
+    Integer,
+    MaxLength(usize),
+    /// Require properties that were present in every object
+    Required {
+        added: Vec<String>,
+        required: Vec<String>,
+    },
+}
+
+impl Tightening {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Self::Enum(_) => ENUM_KEY,
+            Self::Minimum(_) => MINIMUM_KEY,
+            Self::Maximum(_) => MAXIMUM_KEY,
+            Self::Integer => TYPE_KEY,
+            Self::MaxLength(_) => MAX_LENGTH_KEY,
+            Self::Required { .. } => REQUIRED_KEY,
+        }
+    }
+
+    /// The new value of the keyword
+    pub fn value(&self) -> Value {
+        match self {
+            Self::Enum(values) => Value::Array(values.clone()),
+            Self::Minimum(number) | Self::Maximum(number) => Value::Number(number.clone()),
+            Self::Integer => Value::String("integer".to_string()),
+            Self::MaxLength(length) => (*length).into(),
+            Self::Required { required, .. } => required.clone().into(),
+        }
+    }
+}
+
+/// A tighter constraint for a subschema that the observed values would support
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    /// JSON Pointer to the subschema
+    pub pointer: String,
+    pub tightening: Tightening,
+    /// The number of values the subschema described
+    pub count: usize,
+}
+
+impl Suggestion {
+    /// A JSON Patch operation that applies the suggestion to the schema
+    pub fn patch(&self) -> Value {
+        serde_json::json!({
+            "op": "add",
+            "path": format!("{}/{}", self.pointer, self.tightening.keyword()),
+            "value": self.tightening.value(),
+        })
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(", ");
+
+        write!(f, "#{}: ", self.pointer)?;
+
+        match &self.tightening {
+            Tightening::Enum(values) => write!(
+                f,
+                "all {} values are in {{{}}}, consider an enum",
+                self.count,
+                list(&mut values.iter().map(ToString::to_string))
+            ),
+            Tightening::Minimum(number) => write!(
+                f,
+                "all {} values are at least {}, consider a minimum",
+                self.count, number
+            ),
+            Tightening::Maximum(number) => write!(
+                f,
+                "all {} values are at most {}, consider a maximum",
+                self.count, number
+            ),
+            Tightening::Integer => write!(
+                f,
+                "all {} values are integers, consider the integer type",
+                self.count
+            ),
+            Tightening::MaxLength(length) => write!(
+                f,
+                "all {} values have at most {} {}, consider a maxLength",
+                self.count,
+                length,
+                if *length == 1 {
+                    "character"
+                } else {
+                    "characters"
+                }
+            ),
+            Tightening::Required { added, .. } => write!(
+                f,
+                "{} present in all {} objects, consider requiring {}",
+                list(&mut added.iter().map(|name| format!("\"{}\"", name))),
+                self.count,
+                if added.len() == 1 { "it" } else { "them" }
+            ),
+        }
+    }
+}
+
+/// What's been seen of the values that a subschema describes
+#[derive(Clone, Debug, Default)]
+struct Observed {
+    count: usize,
+    /// Distinct scalar values, until there are more than could be suggested as an enum
+    values: Vec<Value>,
+    too_many_values: bool,
+    numbers: usize,
+    integers: usize,
+    minimum: Option<Number>,
+    maximum: Option<Number>,
+    strings: usize,
+    max_length: usize,
+    objects: usize,
+    /// The number of objects that contain each property
+    properties: IndexMap<String, usize>,
+}
+
+impl Observed {
+    fn add(&mut self, value: &Value, options: &TighteningOptions) {
+        self.count += 1;
+
+        match value {
+            Value::Array(_) | Value::Object(_) => self.too_many_values = true,
+            _ if self.too_many_values => {}
+            _ => {
+                if !self.values.iter().any(|other| json_eq(other, value)) {
+                    if self.values.len() < options.max_enum_values {
+                        self.values.push(value.clone());
+                    } else {
+                        self.too_many_values = true;
+                        self.values.clear();
+                    }
+                }
+            }
+        }
+
+        match value {
+            Value::Number(number) => {
+                let float = number.as_f64().unwrap_or(f64::NAN);
+
+                self.numbers += 1;
+
+                if float.fract() == 0.0 {
+                    self.integers += 1;
+                }
+
+                if self
+                    .minimum
+                    .as_ref()
+                    .is_none_or(|minimum| float < minimum.as_f64().unwrap_or(f64::NAN))
+                {
+                    self.minimum = Some(number.clone());
+                }
+
+                if self
+                    .maximum
+                    .as_ref()
+                    .is_none_or(|maximum| float > maximum.as_f64().unwrap_or(f64::NAN))
+                {
+                    self.maximum = Some(number.clone());
+                }
+            }
+            Value::String(value) => {
+                self.strings += 1;
+                self.max_length = self.max_length.max(value.chars().count());
+            }
+            Value::Object(fields) => {
+                self.objects += 1;
+
+                for key in fields.keys() {
+                    *self.properties.entry(key.clone()).or_default() += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn suggestions(&self, schema: &Value, options: &TighteningOptions) -> Vec<Tightening> {
+        let mut result = vec![];
+        let fields = match schema.as_object() {
+            Some(fields) if self.count >= options.min_count => fields,
+            _ => return result,
+        };
+        let has = |keys: &[&str]| keys.iter().any(|key| fields.contains_key(*key));
+
+        if has(&[ENUM_KEY, CONST_KEY]) {
+            return result;
+        }
+
+        if !self.too_many_values && self.values.len() < self.count && self.objects == 0 {
+            result.push(Tightening::Enum(self.values.clone()));
+        }
+
+        if self.numbers == self.count {
+            if fields.get(TYPE_KEY).and_then(Value::as_str) == Some("number")
+                && self.integers == self.count
+            {
+                result.push(Tightening::Integer);
+            }
+
+            if let Some(minimum) = self
+                .minimum
+                .clone()
+                .filter(|_| !has(&[MINIMUM_KEY, EXCLUSIVE_MINIMUM_KEY]))
+            {
+                result.push(Tightening::Minimum(minimum));
+            }
+
+            if let Some(maximum) = self
+                .maximum
+                .clone()
+                .filter(|_| !has(&[MAXIMUM_KEY, EXCLUSIVE_MAXIMUM_KEY]))
+            {
+                result.push(Tightening::Maximum(maximum));
+            }
+        }
+
+        if self.strings == self.count && !has(&[MAX_LENGTH_KEY]) {
+            result.push(Tightening::MaxLength(self.max_length));
+        }
+
+        if self.objects == self.count {
+            let mut required = fields
+                .get(REQUIRED_KEY)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|name| name.as_str().map(str::to_string))
+                .collect::<Vec<_>>();
+            let added = fields
+                .get(PROPERTIES_KEY)
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+                .map(|(name, _)| name)
+                .filter(|name| {
+                    self.properties.get(*name) == Some(&self.count) && !required.contains(name)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if !added.is_empty() {
+                required.extend(added.iter().cloned());
+                result.push(Tightening::Required { added, required });
+            }
+        }
+
+        result
+    }
+}
+
+/// Observations of the values that each subschema describes, from which tighter constraints
+/// can be suggested
+///
+/// Only counts, bounds, and a few distinct values are kept (not the instances).
+#[derive(Clone, Debug, Default)]
+pub struct Observations {
+    options: TighteningOptions,
+    subschemas: IndexMap<Vec<String>, Observed>,
+}
+
+impl Validator {
+    /// Observations of none of the schema's values yet
+    pub fn observations(&self, options: TighteningOptions) -> Observations {
+        Observations {
+            options,
+            subschemas: IndexMap::new(),
+        }
+    }
+
+    /// Record the values in an instance that each subschema describes
+    ///
+    /// Subschemas apply to values as they do for [`Validator::annotations`].
+    pub fn observe(&self, instance: &Value, observations: &mut Observations) {
+        let mut seen = HashSet::new();
+
+        self.visit_keywords(instance, &mut |instance_path, schema_path, _, _| {
+            let subschema = &schema_path[..schema_path.len() - 1];
+
+            // A tighter `if` could make a schema looser.
+            if subschema.last().map(String::as_str) != Some(IF_KEY)
+                && seen.insert((instance_path.to_vec(), subschema.to_vec()))
+            {
+                if let Some(value) = instance.pointer(&pointer(instance_path)) {
+                    observations
+                        .subschemas
+                        .entry(subschema.to_vec())
+                        .or_default()
+                        .add(value, &observations.options);
+                }
+            }
+        });
+    }
+
+    /// The tighter constraints that every observed value would satisfy, in schema order
+    ///
+    /// Subschemas with `enum`, `const`, or `$ref` get no suggestions, and existing bounds are
+    /// never replaced.
+    pub fn suggestions(&self, observations: &Observations) -> Vec<Suggestion> {
+        let mut result = vec![];
+
+        for (path, observed) in &observations.subschemas {
+            let pointer = pointer(path);
+
+            if let Some(schema) = self
+                .schema
+                .pointer(&pointer)
+                .filter(|schema| schema.get(REF_KEY).is_none())
+            {
+                for tightening in observed.suggestions(schema, &observations.options) {
+                    result.push(Suggestion {
+                        pointer: pointer.clone(),
+                        tightening,
+                        count: observed.count,
+                    });
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tightening_suggestions() {
+        let validator = Validator::new(json!({
+            "type": "object",
+            "properties": {
+                "status": { "type": "string" },
+                "count": { "type": "number", "minimum": 0 },
+                "note": { "type": "string" },
+                "tags": { "type": "array", "items": { "$ref": "#/$defs/tag" } }
+            },
+            "required": ["status"],
+            "$defs": {
+                "tag": { "enum": ["a", "b"] }
+            }
+        }))
+        .unwrap();
+        let mut observations = validator.observations(TighteningOptions {
+            max_enum_values: 3,
+            min_count: 4,
+        });
+
+        for index in 0..6 {
+            let mut instance = json!({
+                "status": (["A", "B", "C"][index % 3]),
+                "count": index * 20,
+                "tags": ["a"]
+            });
+
+            if index % 2 == 0 {
+                instance["note"] = json!(format!("note {}", index));
+            }
+
+            validator.observe(&instance, &mut observations);
+        }
+
+        let suggestions = validator.suggestions(&observations);
+
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "#: \"count\", \"tags\" present in all 6 objects, consider requiring them",
+                "#/properties/status: all 6 values are in {\"A\", \"B\", \"C\"}, consider an enum",
+                "#/properties/status: all 6 values have at most 1 character, consider a maxLength",
+                "#/properties/count: all 6 values are integers, consider the integer type",
+                "#/properties/count: all 6 values are at most 100, consider a maximum",
+            ]
+        );
+        assert_eq!(
+            suggestions[0].patch(),
+            json!({
+                "op": "add",
+                "path": "/required",
+                "value": ["status", "count", "tags"]
+            })
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use super::constants::*;
+use super::schema::{Change, ChangeKind};
+use serde_json::Value;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+pub enum Error {
+    #[error("No version found")]
+    MissingVersion,
+    #[error("Invalid version")]
+    InvalidVersion(String),
+}
+
+/// A semantic version, which may be written with fewer components (for example `v2` in an `$id`)
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Level {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Version {
+    pub fn bump(self, level: Level) -> Self {
+        match level {
+            Level::Major => Self {
+                major: self.major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            Level::Minor => Self {
+                minor: self.minor + 1,
+                patch: 0,
+                ..self
+            },
+            Level::Patch => Self {
+                patch: self.patch + 1,
+                ..self
+            },
+        }
+    }
+
+    /// Format with the given number of components (the rest are dropped)
+    fn format_components(&self, count: usize) -> String {
+        [self.major, self.minor, self.patch][..count.clamp(1, 3)]
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let components = s
+            .strip_prefix('v')
+            .unwrap_or(s)
+            .split('.')
+            .map(|component| {
+                if !component.is_empty() && component.bytes().all(|byte| byte.is_ascii_digit()) {
+                    component.parse::<u64>().ok()
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|components| components.len() <= 3)
+            .ok_or_else(|| Error::InvalidVersion(s.to_string()))?;
+
+        Ok(Self {
+            major: components[0],
+            minor: components.get(1).copied().unwrap_or(0),
+            patch: components.get(2).copied().unwrap_or(0),
+        })
+    }
+}
+
+impl Level {
+    /// The smallest bump that reflects the changes, where changes that old data might not satisfy
+    /// are major, and any other structural change is minor
+    pub fn required(changes: &[Change]) -> Self {
+        if changes
+            .iter()
+            .any(|change| !change.kind.compatibility().is_backward())
+        {
+            Self::Major
+        } else if !changes.is_empty() {
+            Self::Minor
+        } else {
+            Self::Patch
+        }
+    }
+}
+
+/// The version of a schema, from its `version` annotation or else a path segment of its `$id`
+pub fn version(value: &Value) -> Option<Version> {
+    value
+        .get(VERSION_KEY)
+        .and_then(Value::as_str)
+        .and_then(|version| version.parse().ok())
+        .or_else(|| {
+            let id = value.get(ID_KEY).and_then(Value::as_str)?;
+            let (_, segment) = id_version_segment(id)?;
+            segment.parse().ok()
+        })
+}
+
+/// Bump the version in the schema's `version` annotation and `$id`, returning the old and new
+/// versions
+///
+/// An `$id` segment keeps its number of components, so `v1` only changes for a major bump.
+pub fn bump(value: &mut Value, level: Level) -> Result<(Version, Version), Error> {
+    let old = version(value).ok_or(Error::MissingVersion)?;
+    let new = old.bump(level);
+
+    if let Some(fields) = value.as_object_mut() {
+        if let Some(Value::String(version)) = fields.get_mut(VERSION_KEY) {
+            let components = version.split('.').count();
+            let prefix = if version.starts_with('v') { "v" } else { "" };
+            *version = format!("{}{}", prefix, new.format_components(components));
+        }
+
+        if let Some(Value::String(id)) = fields.get_mut(ID_KEY) {
+            if let Some((start, segment)) = id_version_segment(id) {
+                let components = segment.split('.').count();
+                let prefix = if segment.starts_with('v') { "v" } else { "" };
+                let replacement = format!("{}{}", prefix, new.format_components(components));
+
+                id.replace_range(start..start + segment.len(), &replacement);
+            }
+        }
+    }
+
+    Ok((old, new))
+}
+
+/// The last path segment of an ID that is a version, with its byte offset
+fn id_version_segment(id: &str) -> Option<(usize, &str)> {
+    let path = id.split(['#', '?']).next().unwrap_or(id);
+    let mut start = 0;
+    let mut result = None;
+
+    for segment in path.split('/') {
+        if segment.parse::<Version>().is_ok() {
+            result = Some((start, segment));
+        }
+
+        start += segment.len() + 1;
+    }
+
+    result
+}
+
+/// A Markdown changelog section listing added, removed, and changed definitions and fields
+pub fn changelog_entry(version: &Version, changes: &[Change]) -> String {
+    let mut added = vec![];
+    let mut removed = vec![];
+    let mut changed = vec![];
+
+    for change in changes {
+        let (list, path) = match change.kind {
+            ChangeKind::DefAdded { .. }
+            | ChangeKind::PropertyAdded { .. }
+            | ChangeKind::EnumMemberAdded { .. }
+            | ChangeKind::BranchAdded { .. } => (&mut added, &change.new_path),
+            ChangeKind::DefRemoved { .. }
+            | ChangeKind::PropertyRemoved { .. }
+            | ChangeKind::EnumMemberRemoved { .. }
+            | ChangeKind::BranchRemoved { .. } => (&mut removed, &change.old_path),
+            _ => (&mut changed, &change.new_path),
+        };
+
+        list.push(if path.is_empty() {
+            format!("- {}\n", change.kind)
+        } else {
+            format!("- {} (in `{}`)\n", change.kind, path.join("/"))
+        });
+    }
+
+    let mut result = format!("## {}\n", version);
+
+    for (heading, lines) in [("Added", added), ("Removed", removed), ("Changed", changed)] {
+        if !lines.is_empty() {
+            result.push_str(&format!("\n### {}\n\n", heading));
+            result.extend(lines);
+        }
+    }
+
+    result
+}
+
+/// Add an entry to a changelog, above the previous entries and below any title
+pub fn insert_changelog_entry(changelog: &str, entry: &str) -> String {
+    let position = changelog
+        .match_indices("## ")
+        .find(|(index, _)| *index == 0 || changelog[..*index].ends_with('\n'))
+        .map(|(index, _)| index);
+
+    match position {
+        Some(index) => format!("{}{}\n{}", &changelog[..index], entry, &changelog[index..]),
+        None if changelog.trim().is_empty() => format!("# Changelog\n\n{}", entry),
+        None => format!("{}\n\n{}", changelog.trim_end(), entry),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{diff, SchemaFile};
+
+    #[test]
+    fn bump_versions() {
+        let old = serde_json::json!({
+            "$id": "https://example.com/schemas/v1/user",
+            "version": "1.2.0",
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "$defs": {}
+        });
+        let mut new = serde_json::json!({
+            "$id": "https://example.com/schemas/v1/user",
+            "version": "1.2.0",
+            "type": "object",
+            "properties": { "name": { "type": "integer" } },
+            "$defs": {}
+        });
+
+        let changes = diff(
+            &serde_json::from_value::<SchemaFile>(old).unwrap(),
+            &serde_json::from_value::<SchemaFile>(new.clone()).unwrap(),
+        );
+        let level = Level::required(&changes);
+        assert_eq!(level, Level::Major);
+
+        let (_, version) = bump(&mut new, level).unwrap();
+        assert_eq!(version.to_string(), "2.0.0");
+        assert_eq!(new[ID_KEY], "https://example.com/schemas/v2/user");
+        assert_eq!(new[VERSION_KEY], "2.0.0");
+
+        let changelog = insert_changelog_entry(
+            "# Changelog\n\n## 1.2.0\n\n- Initial\n",
+            &changelog_entry(&version, &changes),
+        );
+        assert!(changelog.starts_with("# Changelog\n\n## 2.0.0\n\n### Changed\n\n- "));
+        assert!(changelog.ends_with("\n\n## 1.2.0\n\n- Initial\n"));
+
+        assert_eq!(
+            bump(
+                &mut serde_json::json!({ "$id": "/schemas/user" }),
+                Level::Patch
+            ),
+            Err(Error::MissingVersion)
+        );
+    }
+}
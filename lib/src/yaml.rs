@@ -0,0 +1,1531 @@
+//! Reading and writing the subset of YAML that corresponds to JSON
+//!
+//! Schemas and instances only need YAML as another syntax for JSON values, which this covers in
+//! far less code than a full YAML implementation (and without another dependency to track). Block
+//! and flow collections, all scalar styles, and anchors and aliases are supported. Scalar keys are
+//! used as strings, and tags, merge keys, complex keys, infinite numbers, and multiple documents
+//! are errors.
+
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// The most nodes that aliases can expand to in a document, so that a small document with nested
+/// aliases can't expand to an enormous value
+const MAX_ALIAS_NODES: usize = 100_000;
+
+#[derive(thiserror::Error, Debug, Eq, PartialEq)]
+#[error("Invalid YAML at line {line}, column {column}: {message}")]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub message: &'static str,
+}
+
+/// Parse a YAML document, which is null if it's empty
+pub fn from_str(input: &str) -> Result<Value, Error> {
+    let mut parser = Parser {
+        chars: input
+            .strip_prefix('\u{feff}')
+            .unwrap_or(input)
+            .chars()
+            .collect(),
+        pos: 0,
+        anchors: HashMap::new(),
+        alias_nodes: 0,
+    };
+
+    parser.document()
+}
+
+/// Write a value in block style with two spaces of indentation and a trailing newline
+pub fn to_string(value: &Value) -> String {
+    to_string_with_indent(value, 2)
+}
+
+/// Write a value in block style with the given number of spaces of indentation and a trailing
+/// newline
+///
+/// Strings are only quoted when they would otherwise be read as something else, and strings with
+/// line breaks are written as literal block scalars where possible.
+pub fn to_string_with_indent(value: &Value, indent: usize) -> String {
+    let mut emitter = Emitter {
+        indent: indent.max(1),
+        output: String::new(),
+    };
+
+    match value {
+        Value::Object(fields) if !fields.is_empty() => emitter.mapping(fields, 0, false),
+        Value::Array(values) if !values.is_empty() => emitter.sequence(values, 0, false),
+        Value::String(value) if is_block(value) => emitter.block(value, emitter.indent),
+        value => {
+            emitter.scalar(value);
+            emitter.output.push('\n');
+        }
+    }
+
+    emitter.output
+}
+
+/// A scalar before resolution, where plain scalars can be read as other types
+enum Scalar {
+    Plain(String),
+    Quoted(String),
+}
+
+impl Scalar {
+    fn into_text(self) -> String {
+        match self {
+            Self::Plain(text) | Self::Quoted(text) => text,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Chomping {
+    Clip,
+    Keep,
+    Strip,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Anchored values with their numbers of nodes
+    anchors: HashMap<String, (Value, usize)>,
+    /// The number of nodes that aliases have expanded to so far
+    alias_nodes: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn error(&self, message: &'static str) -> Error {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: &'static str) -> Error {
+        let before = &self.chars[..pos.min(self.chars.len())];
+        let line_start = before
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map_or(0, |index| index + 1);
+
+        Error {
+            line: before.iter().filter(|c| **c == '\n').count() + 1,
+            column: pos - line_start + 1,
+            message,
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.pos
+            - self.chars[..self.pos]
+                .iter()
+                .rposition(|c| *c == '\n')
+                .map_or(0, |index| index + 1)
+    }
+
+    /// Whether the character at the offset ends a token (in block context)
+    fn is_separator_at(&self, offset: usize) -> bool {
+        matches!(self.peek_at(offset), None | Some(' ' | '\t' | '\n' | '\r'))
+    }
+
+    fn is_flow_separator_at(&self, offset: usize) -> bool {
+        self.is_separator_at(offset)
+            || matches!(self.peek_at(offset), Some(',' | '[' | ']' | '{' | '}'))
+    }
+
+    fn is_sequence_indicator(&self) -> bool {
+        self.peek() == Some('-') && self.is_separator_at(1)
+    }
+
+    fn at_document_marker(&self) -> bool {
+        self.column() == 0
+            && (self.chars[self.pos..].starts_with(&['-', '-', '-'])
+                || self.chars[self.pos..].starts_with(&['.', '.', '.']))
+            && self.is_separator_at(3)
+    }
+
+    fn at_end(&self) -> bool {
+        self.peek().is_none() || self.at_document_marker()
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_to_line_end(&mut self) {
+        while !matches!(self.peek(), None | Some('\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip whitespace, comments, and line breaks
+    fn skip_blank(&mut self) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\n' | '\r') => self.pos += 1,
+                Some('#')
+                    if self.pos == 0
+                        || matches!(self.chars[self.pos - 1], ' ' | '\t' | '\n' | '\r') =>
+                {
+                    self.skip_to_line_end()
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Skip a line break (if there is one), returning whether there was one
+    fn line_break(&mut self) -> bool {
+        match self.peek() {
+            Some('\r') if self.peek_at(1) == Some('\n') => {
+                self.pos += 2;
+                true
+            }
+            Some('\n' | '\r') => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn document(&mut self) -> Result<Value, Error> {
+        self.skip_blank();
+
+        while self.column() == 0 && self.peek() == Some('%') {
+            self.skip_to_line_end();
+            self.skip_blank();
+        }
+
+        if self.at_document_marker() && self.peek() == Some('-') {
+            self.pos += 3;
+        }
+
+        let value = self.node(-1, false, true)?;
+        self.skip_blank();
+
+        if self.at_document_marker() && self.peek() == Some('.') {
+            self.pos += 3;
+            self.skip_blank();
+        }
+
+        if self.at_document_marker() {
+            Err(self.error("multiple documents aren't supported"))
+        } else if self.peek().is_some() {
+            Err(self.error("unexpected content"))
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Parse a node in block context, which has to be indented more than its parent (or as much
+    /// for a sequence that's a mapping value, when `indentless` is set)
+    ///
+    /// Unless `compact` is set, a block collection has to start on a new line (so `a: b: c` is an
+    /// error, although `- b: c` isn't).
+    fn node(&mut self, parent: isize, indentless: bool, compact: bool) -> Result<Value, Error> {
+        let entry = self.pos;
+        self.skip_blank();
+
+        if self.at_end() || !self.is_indented(parent, indentless) {
+            return Ok(Value::Null);
+        }
+
+        self.check_indentation()?;
+
+        let anchor = self.anchor()?;
+
+        if anchor.is_some() && matches!(self.peek(), None | Some('#' | '\n' | '\r')) {
+            self.skip_blank();
+
+            if self.at_end() || !self.is_indented(parent, indentless) {
+                return Ok(self.finish_node(anchor, Value::Null));
+            }
+        }
+
+        let inline = !compact && !self.chars[entry..self.pos].contains(&'\n');
+        let column = self.column();
+        let value = match self.peek() {
+            Some('-') if self.is_sequence_indicator() => {
+                if inline {
+                    return Err(
+                        self.error("block sequences aren't allowed on the same line as a key")
+                    );
+                }
+
+                self.block_sequence(column)?
+            }
+            Some('?') if self.is_separator_at(1) => {
+                if inline {
+                    return Err(
+                        self.error("mapping values aren't allowed on the same line as a key")
+                    );
+                }
+
+                self.block_mapping(column)?
+            }
+            Some('[' | '{') => {
+                let value = self.flow_collection()?;
+                self.skip_spaces();
+
+                if self.peek() == Some(':') {
+                    return Err(self.error("complex mapping keys aren't supported"));
+                }
+
+                value
+            }
+            Some('|' | '>') => Value::String(self.block_scalar(parent)?),
+            Some('*') => {
+                let alias = self.alias()?;
+                self.skip_spaces();
+
+                if self.peek() == Some(':') {
+                    return Err(self.error("aliases can't be used as keys"));
+                }
+
+                alias
+            }
+            _ => {
+                let start = self.pos;
+                let scalar = self.scalar(false)?;
+
+                if self.at_mapping_indicator(matches!(scalar, Scalar::Quoted(_))) {
+                    if inline {
+                        return Err(
+                            self.error("mapping values aren't allowed on the same line as a key")
+                        );
+                    }
+
+                    self.pos = start;
+                    self.block_mapping(column)?
+                } else {
+                    let scalar = match scalar {
+                        Scalar::Plain(text) => {
+                            Scalar::Plain(self.plain_continuation(text, parent, false)?)
+                        }
+                        quoted => quoted,
+                    };
+
+                    self.resolve(scalar, start)?
+                }
+            }
+        };
+
+        Ok(self.finish_node(anchor, value))
+    }
+
+    /// Reject tabs before the first thing on a line, since they don't have a width as indentation
+    fn check_indentation(&self) -> Result<(), Error> {
+        let line_start = self.pos - self.column();
+        let indentation = &self.chars[line_start..self.pos];
+
+        if indentation.contains(&'\t') && indentation.iter().all(|c| matches!(c, ' ' | '\t')) {
+            Err(self.error("tabs aren't allowed in indentation"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_indented(&self, parent: isize, indentless: bool) -> bool {
+        let column = self.column() as isize;
+
+        column > parent || (indentless && column == parent && self.is_sequence_indicator())
+    }
+
+    fn finish_node(&mut self, anchor: Option<String>, value: Value) -> Value {
+        if let Some(anchor) = anchor {
+            self.anchors
+                .insert(anchor, (value.clone(), node_count(&value)));
+        }
+
+        value
+    }
+
+    /// Parse an anchor, if there is one (and reject a tag, which isn't supported)
+    fn anchor(&mut self) -> Result<Option<String>, Error> {
+        let mut anchor = None;
+
+        if self.peek() == Some('&') {
+            self.pos += 1;
+            anchor = Some(self.name()?);
+            self.skip_spaces();
+        }
+
+        if self.peek() == Some('!') {
+            Err(self.error("tags aren't supported"))
+        } else {
+            Ok(anchor)
+        }
+    }
+
+    fn name(&mut self) -> Result<String, Error> {
+        let start = self.pos;
+
+        while !self.is_flow_separator_at(0) {
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            Err(self.error("expected a name"))
+        } else {
+            Ok(self.chars[start..self.pos].iter().collect())
+        }
+    }
+
+    fn alias(&mut self) -> Result<Value, Error> {
+        let start = self.pos;
+        self.pos += 1;
+        let name = self.name()?;
+
+        let nodes = match self.anchors.get(&name) {
+            Some((_, nodes)) => *nodes,
+            None => return Err(self.error_at(start, "unknown alias")),
+        };
+
+        self.alias_nodes += nodes;
+
+        if self.alias_nodes > MAX_ALIAS_NODES {
+            return Err(self.error_at(start, "aliases expand to too many nodes"));
+        }
+
+        Ok(self.anchors[&name].0.clone())
+    }
+
+    /// Whether the next thing on the line (after any spaces) is the `:` after a key
+    ///
+    /// JSON-style keys (quoted keys) don't need a space after the colon.
+    fn at_mapping_indicator(&self, quoted: bool) -> bool {
+        let mut offset = 0;
+
+        while matches!(self.peek_at(offset), Some(' ' | '\t')) {
+            offset += 1;
+        }
+
+        self.peek_at(offset) == Some(':') && (quoted || self.is_separator_at(offset + 1))
+    }
+
+    fn block_mapping(&mut self, column: usize) -> Result<Value, Error> {
+        let mut fields = Map::new();
+
+        loop {
+            let start = self.pos;
+            let explicit = self.peek() == Some('?') && self.is_separator_at(1);
+            let key = match self.peek() {
+                _ if explicit => {
+                    self.pos += 1;
+                    let key = self.node(column as isize, false, true)?;
+                    self.explicit_key(key, start)?
+                }
+                Some('-') if self.is_sequence_indicator() => {
+                    return Err(self.error("expected a mapping key"))
+                }
+                Some('*') => return Err(self.error("aliases can't be used as keys")),
+                Some('[' | '{') => return Err(self.error("complex mapping keys aren't supported")),
+                _ => self.scalar(false)?,
+            };
+
+            let value = if explicit {
+                // The value is optional, and on the next line at the same indentation.
+                self.skip_blank();
+
+                if !self.at_end()
+                    && self.column() == column
+                    && self.peek() == Some(':')
+                    && self.is_separator_at(1)
+                {
+                    self.pos += 1;
+                    self.node(column as isize, true, true)?
+                } else {
+                    Value::Null
+                }
+            } else {
+                self.skip_spaces();
+
+                if self.peek() != Some(':') {
+                    return Err(self.error("expected ':' after a mapping key"));
+                }
+
+                self.pos += 1;
+                self.node(column as isize, true, false)?
+            };
+            self.insert(&mut fields, key, value, start)?;
+            self.skip_blank();
+            self.check_indentation()?;
+
+            if self.at_end() || self.column() < column {
+                break;
+            } else if self.column() > column {
+                return Err(self.error("unexpected indentation"));
+            }
+        }
+
+        Ok(Value::Object(fields))
+    }
+
+    fn block_sequence(&mut self, column: usize) -> Result<Value, Error> {
+        let mut values = vec![];
+
+        loop {
+            self.pos += 1;
+            values.push(self.node(column as isize, false, true)?);
+            self.skip_blank();
+            self.check_indentation()?;
+
+            if self.at_end() || self.column() < column {
+                break;
+            } else if self.column() > column {
+                return Err(self.error("unexpected indentation"));
+            } else if !self.is_sequence_indicator() {
+                // A mapping key after an indentless sequence.
+                break;
+            }
+        }
+
+        Ok(Value::Array(values))
+    }
+
+    /// Use the value of an explicit key (after `?`) as a key, which only works for scalars
+    fn explicit_key(&self, key: Value, start: usize) -> Result<Scalar, Error> {
+        match key {
+            Value::String(key) => Ok(Scalar::Quoted(key)),
+            Value::Array(_) | Value::Object(_) => {
+                Err(self.error_at(start, "complex mapping keys aren't supported"))
+            }
+            key => Ok(Scalar::Quoted(key.to_string())),
+        }
+    }
+
+    fn insert(
+        &self,
+        fields: &mut Map<String, Value>,
+        key: Scalar,
+        value: Value,
+        start: usize,
+    ) -> Result<(), Error> {
+        if matches!(&key, Scalar::Plain(key) if key == "<<") {
+            return Err(self.error_at(start, "merge keys aren't supported"));
+        }
+
+        let key = key.into_text();
+
+        if fields.contains_key(&key) {
+            return Err(self.error_at(start, "duplicate key"));
+        }
+
+        fields.insert(key, value);
+
+        Ok(())
+    }
+
+    fn flow_collection(&mut self) -> Result<Value, Error> {
+        if self.peek() == Some('[') {
+            self.flow_sequence()
+        } else {
+            self.flow_mapping()
+        }
+    }
+
+    fn flow_node(&mut self) -> Result<Value, Error> {
+        self.skip_blank();
+        let anchor = self.anchor()?;
+        self.skip_blank();
+        let start = self.pos;
+
+        let value = match self.peek() {
+            Some('[' | '{') => self.flow_collection()?,
+            Some('*') => self.alias()?,
+            Some(',' | ']' | '}') => Value::Null,
+            _ => {
+                let scalar = match self.scalar(true)? {
+                    Scalar::Plain(text) => Scalar::Plain(self.plain_continuation(text, -1, true)?),
+                    quoted => quoted,
+                };
+
+                self.resolve(scalar, start)?
+            }
+        };
+
+        Ok(self.finish_node(anchor, value))
+    }
+
+    fn flow_sequence(&mut self) -> Result<Value, Error> {
+        let mut values = vec![];
+        self.pos += 1;
+
+        loop {
+            self.skip_blank();
+
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                break;
+            }
+
+            values.push(self.flow_node()?);
+            self.skip_blank();
+
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(':') => return Err(self.error("mappings in flow sequences aren't supported")),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+
+        Ok(Value::Array(values))
+    }
+
+    fn flow_mapping(&mut self) -> Result<Value, Error> {
+        let mut fields = Map::new();
+        self.pos += 1;
+
+        loop {
+            self.skip_blank();
+
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                break;
+            }
+
+            let start = self.pos;
+            let key = match self.peek() {
+                Some('?') if self.is_flow_separator_at(1) => {
+                    self.pos += 1;
+                    let key = self.flow_node()?;
+                    self.explicit_key(key, start)?
+                }
+                Some('[' | '{') => return Err(self.error("complex mapping keys aren't supported")),
+                Some('*') => return Err(self.error("aliases can't be used as keys")),
+                Some(',') => return Err(self.error("expected a mapping key")),
+                _ => self.scalar(true)?,
+            };
+
+            self.skip_blank();
+
+            let value = if self.peek() == Some(':') {
+                self.pos += 1;
+                self.flow_node()?
+            } else {
+                Value::Null
+            };
+
+            self.insert(&mut fields, key, value, start)?;
+            self.skip_blank();
+            self.check_indentation()?;
+
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+
+        Ok(Value::Object(fields))
+    }
+
+    fn scalar(&mut self, flow: bool) -> Result<Scalar, Error> {
+        match self.peek() {
+            Some('"') => self.double_quoted().map(Scalar::Quoted),
+            Some('\'') => self.single_quoted().map(Scalar::Quoted),
+            Some(
+                ',' | '[' | ']' | '{' | '}' | '#' | '&' | '*' | '!' | '|' | '>' | '%' | '@' | '`',
+            ) => Err(self.error("unexpected character")),
+            Some('-' | '?' | ':') if !flow && self.is_separator_at(1) => {
+                Err(self.error("unexpected character"))
+            }
+            Some('-' | '?' | ':') if flow && self.is_flow_separator_at(1) => {
+                Err(self.error("unexpected character"))
+            }
+            _ => Ok(Scalar::Plain(self.plain_line(flow)?)),
+        }
+    }
+
+    /// Read the rest of the line of a plain scalar, stopping before a comment or a `:` indicator
+    fn plain_line(&mut self, flow: bool) -> Result<String, Error> {
+        let start = self.pos;
+
+        loop {
+            match self.peek() {
+                None | Some('\n' | '\r') => break,
+                Some(':') if self.is_separator_at(1) => break,
+                Some(':') if flow && self.is_flow_separator_at(1) => break,
+                Some(',' | '[' | ']' | '{' | '}') if flow => break,
+                Some(' ' | '\t') if self.peek_at(1) == Some('#') => break,
+                _ => self.pos += 1,
+            }
+        }
+
+        let mut end = self.pos;
+
+        while end > start && matches!(self.chars[end - 1], ' ' | '\t') {
+            end -= 1;
+        }
+
+        Ok(self.chars[start..end].iter().collect())
+    }
+
+    /// Read any more lines of a plain scalar, which have to be indented more than its parent in
+    /// block context
+    fn plain_continuation(
+        &mut self,
+        mut text: String,
+        parent: isize,
+        flow: bool,
+    ) -> Result<String, Error> {
+        loop {
+            let before = self.pos;
+            self.skip_spaces();
+
+            if !self.line_break() {
+                self.pos = before;
+                break;
+            }
+
+            let mut breaks = 1;
+            self.skip_spaces();
+
+            while self.line_break() {
+                breaks += 1;
+                self.skip_spaces();
+            }
+
+            let ends = match self.peek() {
+                None | Some('#') => true,
+                Some(',' | ']' | '}') if flow => true,
+                Some(':') if self.is_separator_at(1) || (flow && self.is_flow_separator_at(1)) => {
+                    true
+                }
+                _ => self.at_document_marker() || (!flow && self.column() as isize <= parent),
+            };
+
+            if ends {
+                self.pos = before;
+                break;
+            }
+
+            let line = self.plain_line(flow)?;
+
+            if !flow && self.at_mapping_indicator(false) {
+                return Err(self.error("mapping values aren't allowed in a multi-line scalar"));
+            }
+
+            if breaks == 1 {
+                text.push(' ');
+            } else {
+                text.extend(std::iter::repeat_n('\n', breaks - 1));
+            }
+
+            text.push_str(&line);
+        }
+
+        Ok(text)
+    }
+
+    /// Fold a line break in a quoted scalar, which is a space unless it's followed by empty lines
+    fn fold_quoted_line(&mut self, text: &mut String) {
+        let mut breaks = 0;
+
+        while self.line_break() {
+            breaks += 1;
+            self.skip_spaces();
+        }
+
+        if breaks == 1 {
+            text.push(' ');
+        } else {
+            text.extend(std::iter::repeat_n('\n', breaks - 1));
+        }
+    }
+
+    fn single_quoted(&mut self) -> Result<String, Error> {
+        let start = self.pos;
+        let mut text = String::new();
+        self.pos += 1;
+
+        loop {
+            match self.peek() {
+                None => return Err(self.error_at(start, "unterminated string")),
+                Some('\'') if self.peek_at(1) == Some('\'') => {
+                    text.push('\'');
+                    self.pos += 2;
+                }
+                Some('\'') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('\n' | '\r') => {
+                    text.truncate(text.trim_end_matches([' ', '\t']).len());
+                    self.fold_quoted_line(&mut text);
+                }
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
+    fn double_quoted(&mut self) -> Result<String, Error> {
+        let start = self.pos;
+        let mut text = String::new();
+        // Whitespace before a line break is dropped, unless it was escaped.
+        let mut escaped_len = 0;
+        self.pos += 1;
+
+        loop {
+            match self.peek() {
+                None => return Err(self.error_at(start, "unterminated string")),
+                Some('"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('\\') => {
+                    self.pos += 1;
+
+                    if self.line_break() {
+                        self.skip_spaces();
+                    } else {
+                        let c = self.escape()?;
+                        text.push(c);
+                    }
+
+                    escaped_len = text.len();
+                }
+                Some('\n' | '\r') => {
+                    let trimmed = text.trim_end_matches([' ', '\t']).len();
+                    text.truncate(trimmed.max(escaped_len));
+                    self.fold_quoted_line(&mut text);
+                }
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// Read the escape sequence after a backslash
+    fn escape(&mut self) -> Result<char, Error> {
+        let start = self.pos;
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error("unterminated string"))?;
+        self.pos += 1;
+
+        let digits = match c {
+            '0' => return Ok('\0'),
+            'a' => return Ok('\u{7}'),
+            'b' => return Ok('\u{8}'),
+            't' | '\t' => return Ok('\t'),
+            'n' => return Ok('\n'),
+            'v' => return Ok('\u{b}'),
+            'f' => return Ok('\u{c}'),
+            'r' => return Ok('\r'),
+            'e' => return Ok('\u{1b}'),
+            ' ' | '"' | '/' | '\\' => return Ok(c),
+            'N' => return Ok('\u{85}'),
+            '_' => return Ok('\u{a0}'),
+            'L' => return Ok('\u{2028}'),
+            'P' => return Ok('\u{2029}'),
+            'x' => 2,
+            'u' => 4,
+            'U' => 8,
+            _ => return Err(self.error_at(start, "unknown escape sequence")),
+        };
+
+        let mut code = self.hex(digits)?;
+
+        // Surrogate pairs aren't valid YAML, but JSON strings use them.
+        if (0xd800..0xdc00).contains(&code)
+            && self.peek() == Some('\\')
+            && self.peek_at(1) == Some('u')
+        {
+            let before = self.pos;
+            self.pos += 2;
+            let low = self.hex(4)?;
+
+            if (0xdc00..0xe000).contains(&low) {
+                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+            } else {
+                self.pos = before;
+            }
+        }
+
+        char::from_u32(code).ok_or_else(|| self.error_at(start, "invalid escaped character"))
+    }
+
+    fn hex(&mut self, digits: usize) -> Result<u32, Error> {
+        let start = self.pos;
+
+        if self.pos + digits > self.chars.len() {
+            return Err(self.error("invalid escape sequence"));
+        }
+
+        let text = self.chars[start..start + digits].iter().collect::<String>();
+        self.pos += digits;
+
+        u32::from_str_radix(&text, 16).map_err(|_| self.error_at(start, "invalid escape sequence"))
+    }
+
+    /// Read a literal (`|`) or folded (`>`) block scalar
+    fn block_scalar(&mut self, parent: isize) -> Result<String, Error> {
+        let literal = self.peek() == Some('|');
+        let mut chomping = Chomping::Clip;
+        let mut explicit = None;
+        self.pos += 1;
+
+        loop {
+            match self.peek() {
+                Some('-') if chomping == Chomping::Clip => chomping = Chomping::Strip,
+                Some('+') if chomping == Chomping::Clip => chomping = Chomping::Keep,
+                Some(c @ '1'..='9') if explicit.is_none() => explicit = c.to_digit(10),
+                _ => break,
+            }
+
+            self.pos += 1;
+        }
+
+        self.skip_spaces();
+
+        if self.peek() == Some('#') && matches!(self.chars[self.pos - 1], ' ' | '\t') {
+            self.skip_to_line_end();
+        }
+
+        if !matches!(self.peek(), None | Some('\n' | '\r')) {
+            return Err(self.error("expected a line break after a block scalar header"));
+        }
+
+        let base = parent.max(0) as usize;
+        let mut indent = explicit.map(|digit| base + digit as usize);
+        let mut lines = vec![];
+
+        while self.line_break() {
+            if self.peek().is_none() {
+                break;
+            }
+
+            let line_start = self.pos;
+            let mut spaces = 0;
+
+            while self.peek_at(spaces) == Some(' ') {
+                spaces += 1;
+            }
+
+            let empty = matches!(self.peek_at(spaces), None | Some('\n' | '\r'));
+
+            if !empty {
+                let content_indent = *indent.get_or_insert(if spaces as isize > parent {
+                    spaces
+                } else {
+                    usize::MAX
+                });
+
+                if spaces < content_indent || self.at_document_marker() {
+                    break;
+                }
+            }
+
+            match indent {
+                Some(content_indent) if spaces > content_indent || !empty => {
+                    self.pos = line_start + content_indent;
+                    let start = self.pos;
+                    self.skip_to_line_end();
+                    lines.push(self.chars[start..self.pos].iter().collect::<String>());
+                }
+                _ => {
+                    self.pos = line_start + spaces;
+                    lines.push(String::new());
+                }
+            }
+        }
+
+        let content = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |index| index + 1);
+        let trailing = lines.len() - content;
+        let lines = &lines[..content];
+
+        let mut text = if literal {
+            lines.join("\n")
+        } else {
+            fold_lines(lines)
+        };
+
+        if !lines.is_empty() && chomping != Chomping::Strip {
+            text.push('\n');
+        }
+
+        if chomping == Chomping::Keep {
+            text.extend(std::iter::repeat_n('\n', trailing));
+        }
+
+        Ok(text)
+    }
+
+    fn resolve(&self, scalar: Scalar, start: usize) -> Result<Value, Error> {
+        match scalar {
+            Scalar::Plain(text) => {
+                resolve_plain(&text).map_err(|message| self.error_at(start, message))
+            }
+            Scalar::Quoted(text) => Ok(Value::String(text)),
+        }
+    }
+}
+
+/// The number of nodes in a value
+fn node_count(value: &Value) -> usize {
+    match value {
+        Value::Array(values) => 1 + values.iter().map(node_count).sum::<usize>(),
+        Value::Object(fields) => 1 + fields.values().map(node_count).sum::<usize>(),
+        _ => 1,
+    }
+}
+
+/// Fold the lines of a folded block scalar, where line breaks between lines of text become spaces
+fn fold_lines(lines: &[String]) -> String {
+    let mut text = String::new();
+    let mut empty = 0;
+    let mut previous = None;
+
+    for line in lines {
+        if line.is_empty() {
+            empty += 1;
+            continue;
+        }
+
+        let more_indented = line.starts_with([' ', '\t']);
+
+        match previous {
+            None => text.extend(std::iter::repeat_n('\n', empty)),
+            Some(false) if !more_indented && empty == 0 => text.push(' '),
+            Some(false) if !more_indented => text.extend(std::iter::repeat_n('\n', empty)),
+            Some(_) => text.extend(std::iter::repeat_n('\n', empty + 1)),
+        }
+
+        text.push_str(line);
+        previous = Some(more_indented);
+        empty = 0;
+    }
+
+    text
+}
+
+/// Resolve a plain scalar with the core schema
+fn resolve_plain(text: &str) -> Result<Value, &'static str> {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Ok(Value::Null),
+        "true" | "True" | "TRUE" => return Ok(Value::Bool(true)),
+        "false" | "False" | "FALSE" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+
+    if matches!(
+        unsigned,
+        ".inf" | ".Inf" | ".INF" | ".nan" | ".NaN" | ".NAN"
+    ) {
+        return Err("infinite and NaN numbers can't be represented in JSON");
+    }
+
+    let radix = |prefix: &str, radix: u32| {
+        text.strip_prefix(prefix)
+            .filter(|digits| !digits.is_empty())
+            .and_then(|digits| u64::from_str_radix(digits, radix).ok())
+    };
+
+    if let Some(value) = radix("0o", 8).or_else(|| radix("0x", 16)) {
+        return Ok(Value::from(value));
+    }
+
+    if !unsigned.is_empty() && unsigned.chars().all(|c| c.is_ascii_digit()) {
+        if let Ok(value) = text.parse::<i64>() {
+            return Ok(Value::from(value));
+        } else if let Ok(value) = text.parse::<u64>() {
+            return Ok(Value::from(value));
+        }
+    }
+
+    if is_float(unsigned) {
+        if let Some(number) = text.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Ok(Value::Number(number));
+        }
+    }
+
+    Ok(Value::String(text.to_string()))
+}
+
+/// Whether an unsigned plain scalar matches the core schema's pattern for floats
+fn is_float(text: &str) -> bool {
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+
+    let digits = |value: &str| value.chars().all(|c| c.is_ascii_digit());
+    let mantissa_valid = match fraction {
+        Some(fraction) => {
+            digits(whole) && digits(fraction) && (!whole.is_empty() || !fraction.is_empty())
+        }
+        None => !whole.is_empty() && digits(whole),
+    };
+    let exponent_valid = exponent.is_none_or(|exponent| {
+        let exponent = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+
+        !exponent.is_empty() && digits(exponent)
+    });
+
+    mantissa_valid && exponent_valid
+}
+
+/// Whether a string can be written as a block scalar, which is the case for multi-line strings
+/// that don't need an indentation indicator
+fn is_block(value: &str) -> bool {
+    value.contains('\n')
+        && !value.starts_with([' ', '\t', '\n'])
+        && value
+            .chars()
+            .all(|c| c == '\n' || c == '\t' || !needs_escape(c))
+}
+
+fn needs_escape(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{2028}' | '\u{2029}' | '\u{feff}')
+}
+
+/// Whether a string can be written without quotes, which means it won't be read as another type
+/// (including by YAML 1.1 parsers) or as anything other than a scalar
+fn is_plain(value: &str) -> bool {
+    const YAML_1_1_BOOLEANS: [&str; 6] = ["n", "no", "off", "on", "y", "yes"];
+
+    if value.is_empty() || !matches!(resolve_plain(value), Ok(Value::String(_))) {
+        return false;
+    }
+
+    if YAML_1_1_BOOLEANS.contains(&value.to_lowercase().as_str()) {
+        return false;
+    }
+
+    // YAML 1.1 also has sexagesimal numbers, timestamps, and underscores in numbers.
+    if value.starts_with(|c: char| c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '.' | '+' | '-'))
+    {
+        return false;
+    }
+
+    !value.starts_with([
+        ' ', '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+        '@', '`',
+    ]) && !value.starts_with("...")
+        && !value.ends_with([' ', ':'])
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && value != "<<"
+        && !value
+            .chars()
+            .any(|c| c == '\t' || c == '\n' || needs_escape(c))
+}
+
+fn quote(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\u{2028}' => result.push_str("\\L"),
+            '\u{2029}' => result.push_str("\\P"),
+            c if needs_escape(c) && (c as u32) < 0x100 => {
+                result.push_str(&format!("\\x{:02x}", c as u32))
+            }
+            c if needs_escape(c) => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+struct Emitter {
+    indent: usize,
+    output: String,
+}
+
+impl Emitter {
+    fn pad(&mut self, column: usize) {
+        self.output.extend(std::iter::repeat_n(' ', column));
+    }
+
+    fn scalar(&mut self, value: &Value) {
+        match value {
+            Value::String(value) if is_plain(value) => self.output.push_str(value),
+            Value::String(value) => self.output.push_str(&quote(value)),
+            Value::Object(_) => self.output.push_str("{}"),
+            Value::Array(_) => self.output.push_str("[]"),
+            other => self.output.push_str(&other.to_string()),
+        }
+    }
+
+    fn key(&mut self, key: &str) {
+        if is_plain(key) {
+            self.output.push_str(key);
+        } else {
+            self.output.push_str(&quote(key));
+        }
+    }
+
+    /// Write a mapping whose keys are at the given column, where the first key has already been
+    /// indented if `inline` is set
+    fn mapping(&mut self, fields: &Map<String, Value>, column: usize, inline: bool) {
+        for (index, (key, value)) in fields.iter().enumerate() {
+            if index > 0 || !inline {
+                self.pad(column);
+            }
+
+            self.key(key);
+            self.output.push(':');
+
+            match value {
+                Value::Object(fields) if !fields.is_empty() => {
+                    self.output.push('\n');
+                    self.mapping(fields, column + self.indent, false);
+                }
+                Value::Array(values) if !values.is_empty() => {
+                    self.output.push('\n');
+                    self.sequence(values, column + self.indent, false);
+                }
+                Value::String(value) if is_block(value) => {
+                    self.output.push(' ');
+                    self.block(value, column + self.indent);
+                }
+                value => {
+                    self.output.push(' ');
+                    self.scalar(value);
+                    self.output.push('\n');
+                }
+            }
+        }
+    }
+
+    fn sequence(&mut self, values: &[Value], column: usize, inline: bool) {
+        let content = column + self.indent.max(2);
+
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 || !inline {
+                self.pad(column);
+            }
+
+            self.output.push('-');
+
+            match value {
+                Value::Object(fields) if !fields.is_empty() => {
+                    self.pad(content - column - 1);
+                    self.mapping(fields, content, true);
+                }
+                Value::Array(values) if !values.is_empty() => {
+                    self.pad(content - column - 1);
+                    self.sequence(values, content, true);
+                }
+                Value::String(value) if is_block(value) => {
+                    self.output.push(' ');
+                    self.block(value, content);
+                }
+                value => {
+                    self.output.push(' ');
+                    self.scalar(value);
+                    self.output.push('\n');
+                }
+            }
+        }
+    }
+
+    /// Write a literal block scalar with its lines at the given column
+    fn block(&mut self, value: &str, column: usize) {
+        let body = value.trim_end_matches('\n');
+        let trailing = value.len() - body.len();
+
+        self.output.push_str(match trailing {
+            0 => "|-",
+            1 => "|",
+            _ => "|+",
+        });
+        self.output.push('\n');
+
+        for line in body.split('\n') {
+            if !line.is_empty() {
+                self.pad(column);
+                self.output.push_str(line);
+            }
+
+            self.output.push('\n');
+        }
+
+        self.output
+            .extend(std::iter::repeat_n('\n', trailing.saturating_sub(1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn yaml_parsing() {
+        let input = r#"
+%YAML 1.2
+---
+# A schema
+$schema: https://json-schema.org/draft/2020-12/schema
+title: Order   # trailing comment
+description: >
+  An order placed
+  by a customer.
+
+  Folded.
+type: object
+properties:
+  id: &id
+    type: integer
+    minimum: 0
+  parent: *id
+  tags:
+  - type: string
+    pattern: '^[a-z]+''s$'
+  - {type: "null", "x": [1, 2.5, -3e2, 0x1f, ~]}
+  note:
+    type: string
+    examples: ["a\tb\u00e9", 'single', plain text
+      continued]
+    $comment: |-
+      literal
+        indented
+  200: "12"
+  empty:
+required: [id]
+additionalProperties: false
+...
+"#;
+
+        assert_eq!(
+            from_str(input).unwrap(),
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "Order",
+                "description": "An order placed by a customer.\nFolded.\n",
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer", "minimum": 0 },
+                    "parent": { "type": "integer", "minimum": 0 },
+                    "tags": [
+                        { "type": "string", "pattern": "^[a-z]+'s$" },
+                        { "type": "null", "x": [1, 2.5, -300.0, 31, null] }
+                    ],
+                    "note": {
+                        "type": "string",
+                        "examples": ["a\tbé", "single", "plain text continued"],
+                        "$comment": "literal\n  indented"
+                    },
+                    "200": "12",
+                    "empty": null
+                },
+                "required": ["id"],
+                "additionalProperties": false
+            })
+        );
+
+        assert_eq!(from_str("").unwrap(), Value::Null);
+        assert_eq!(
+            from_str("a: 1\na: 2").unwrap_err(),
+            Error {
+                line: 2,
+                column: 1,
+                message: "duplicate key"
+            }
+        );
+        assert_eq!(
+            from_str("a:\n  b: 1\n   c: 2").unwrap_err().message,
+            "mapping values aren't allowed in a multi-line scalar"
+        );
+        assert_eq!(
+            from_str("a: [1]\n  b: 2").unwrap_err().message,
+            "unexpected indentation"
+        );
+        assert!(from_str("a: .inf").is_err());
+        assert!(from_str("a: 1\n---\nb: 2").is_err());
+    }
+
+    #[test]
+    fn yaml_invalid_nesting() {
+        for (input, line, column, message) in [
+            (
+                "a: b: c",
+                1,
+                5,
+                "mapping values aren't allowed on the same line as a key",
+            ),
+            (
+                "x: 1\nkey: value: more",
+                2,
+                11,
+                "mapping values aren't allowed on the same line as a key",
+            ),
+            (
+                "a: &x b: c",
+                1,
+                8,
+                "mapping values aren't allowed on the same line as a key",
+            ),
+            (
+                "a: - b",
+                1,
+                4,
+                "block sequences aren't allowed on the same line as a key",
+            ),
+            ("a:\n\tb: 1", 2, 2, "tabs aren't allowed in indentation"),
+            (
+                "a:\n  b: 1\n\tc: 2",
+                3,
+                2,
+                "tabs aren't allowed in indentation",
+            ),
+        ] {
+            assert_eq!(
+                from_str(input).unwrap_err(),
+                Error {
+                    line,
+                    column,
+                    message
+                },
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(
+            from_str("- a: b\n  c: d\n- k:\t1\n  # \tcomment\n").unwrap(),
+            json!([{ "a": "b", "c": "d" }, { "k": 1 }])
+        );
+    }
+
+    #[test]
+    fn yaml_unsupported() {
+        assert_eq!(
+            from_str("a: !!str 1").unwrap_err().message,
+            "tags aren't supported"
+        );
+        assert_eq!(
+            from_str("a: &a !!str 1").unwrap_err().message,
+            "tags aren't supported"
+        );
+        assert_eq!(
+            from_str("a: &a {b: 1}\nc:\n  <<: *a").unwrap_err().message,
+            "merge keys aren't supported"
+        );
+        assert_eq!(from_str("a: *b").unwrap_err().message, "unknown alias");
+    }
+
+    #[test]
+    fn yaml_alias_expansion() {
+        let mut input = "a0: &a0 [x, x, x, x, x, x, x, x, x, x]\n".to_string();
+
+        for i in 1..9 {
+            let previous = format!("*a{}", i - 1);
+            input.push_str(&format!(
+                "a{}: &a{} [{}]\n",
+                i,
+                i,
+                vec![previous; 10].join(", ")
+            ));
+        }
+
+        assert_eq!(
+            from_str(&input).unwrap_err(),
+            Error {
+                line: 5,
+                column: 45,
+                message: "aliases expand to too many nodes"
+            }
+        );
+
+        let value = from_str("a: &a [1, 2]\nb: [*a, *a]").unwrap();
+
+        assert_eq!(value, json!({ "a": [1, 2], "b": [[1, 2], [1, 2]] }));
+    }
+
+    #[test]
+    fn yaml_round_trip() {
+        let value = json!({
+            "title": "Order",
+            "description": "First line\nsecond line\n",
+            "enum": ["yes", "1.5", "", "null", "- item", "a: b", "plain words", "line\n\nbreaks"],
+            "nested": [[1, 2], { "a": [], "b": {} }, "tab\there"],
+            "key with: colon": true,
+            "kept": "a\n\n"
+        });
+        let yaml = to_string(&value);
+
+        assert_eq!(
+            yaml,
+            r#"title: Order
+description: |
+  First line
+  second line
+enum:
+  - "yes"
+  - "1.5"
+  - ""
+  - "null"
+  - "- item"
+  - "a: b"
+  - plain words
+  - |-
+    line
+
+    breaks
+nested:
+  - - 1
+    - 2
+  - a: []
+    b: {}
+  - "tab\there"
+"key with: colon": true
+kept: |+
+  a
+
+"#
+        );
+        assert_eq!(from_str(&yaml).unwrap(), value);
+        assert_eq!(from_str(&to_string_with_indent(&value, 4)).unwrap(), value);
+    }
+}